@@ -20,6 +20,13 @@ fn create_realm(c: &mut Criterion) {
     c.bench_function("Create Realm", move |b| b.iter(Realm::create));
 }
 
+fn restore_realm(c: &mut Criterion) {
+    let image = Realm::create().snapshot().unwrap();
+    c.bench_function("Restore Realm", move |b| {
+        b.iter(|| Realm::from_snapshot(black_box(&image)))
+    });
+}
+
 fn symbol_creation(c: &mut Criterion) {
     c.bench_function("Symbols (Execution)", move |b| {
         b.iter(|| exec(black_box(SYMBOL_CREATION)))
@@ -68,6 +75,7 @@ fn fibonacci(c: &mut Criterion) {
 criterion_group!(
     execution,
     create_realm,
+    restore_realm,
     symbol_creation,
     for_loop_execution,
     fibonacci
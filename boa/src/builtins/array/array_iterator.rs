@@ -0,0 +1,126 @@
+//! This module implements the `ArrayIterator` objects, returned by `Array.prototype.keys()`,
+//! `Array.prototype.values()` and `Array.prototype.entries()`.
+//!
+//! More information:
+//!  - [ECMAScript reference][spec]
+//!
+//! [spec]: https://tc39.es/ecma262/#sec-array-iterator-objects
+
+use crate::{
+    builtins::{
+        array::{create_array_from_list, length_of_array_like},
+        iterator::{create_iter_result_object, create_iterator_prototype},
+        object::{InternalState, Object},
+        value::{ResultValue, Value},
+    },
+    exec::Interpreter,
+};
+
+/// The internal slot of the global object holding `%ArrayIteratorPrototype%`.
+const ARRAY_ITERATOR_PROTOTYPE: &str = "ArrayIteratorPrototype";
+
+/// What an array iterator produces for each element.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ArrayIterationKind {
+    /// The index of the element.
+    Key,
+    /// The element itself.
+    Value,
+    /// An `[index, element]` pair.
+    KeyAndValue,
+}
+
+/// The internal state of an array iterator.
+///
+/// The iterated object lives in the `IteratedObject` internal slot, so that it is traced.
+#[derive(Debug, Copy, Clone)]
+struct ArrayIterator {
+    /// `[[ArrayIteratorNextIndex]]`
+    next_index: usize,
+    /// `[[ArrayIterationKind]]`
+    kind: ArrayIterationKind,
+}
+
+impl InternalState for ArrayIterator {}
+
+/// Creates a new iterator over the array-like `array`.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-createarrayiterator
+pub(crate) fn create_array_iterator(
+    array: Value,
+    kind: ArrayIterationKind,
+    ctx: &Interpreter,
+) -> Value {
+    let prototype = ctx
+        .realm
+        .intrinsics
+        .get_internal_slot(ARRAY_ITERATOR_PROTOTYPE);
+    let iterator = Value::object(Object::create(prototype));
+    iterator.set_internal_slot("IteratedObject", array);
+    iterator.set_internal_state(ArrayIterator {
+        next_index: 0,
+        kind,
+    });
+    iterator
+}
+
+/// `%ArrayIteratorPrototype%.next( )`
+///
+/// Produces the next element of the iteration, until the index reaches the length the iterated
+/// object has at that point.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-%arrayiteratorprototype%.next
+pub fn next(this: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let state = this
+        .get_internal_state()
+        .and_then(|state| state.downcast_ref::<ArrayIterator>().copied());
+    let state = match state {
+        Some(state) => state,
+        None => return ctx.throw_type_error("next method called on an incompatible receiver"),
+    };
+
+    let array = this.get_internal_slot("IteratedObject");
+    if array.is_undefined() {
+        return Ok(create_iter_result_object(Value::undefined(), true, ctx));
+    }
+
+    let length = length_of_array_like(&array, ctx)?;
+    let index = state.next_index;
+    if index >= length {
+        this.set_internal_slot("IteratedObject", Value::undefined());
+        return Ok(create_iter_result_object(Value::undefined(), true, ctx));
+    }
+
+    this.set_internal_state(ArrayIterator {
+        next_index: index + 1,
+        ..state
+    });
+    let result = match state.kind {
+        ArrayIterationKind::Key => Value::from(index),
+        ArrayIterationKind::Value => array.get_field_slice(&index.to_string()),
+        ArrayIterationKind::KeyAndValue => {
+            let element = array.get_field_slice(&index.to_string());
+            create_array_from_list(&[Value::from(index), element], ctx)?
+        }
+    };
+    Ok(create_iter_result_object(result, false, ctx))
+}
+
+/// Create `%ArrayIteratorPrototype%`.
+pub fn create(global: &Value) -> Value {
+    let prototype = create_iterator_prototype(global, "Array Iterator");
+    make_builtin_fn!(next, named "next", of prototype);
+    prototype
+}
+
+/// Initialise `%ArrayIteratorPrototype%` in an internal slot of the global object.
+#[inline]
+pub fn init(global: &Value) {
+    global.set_internal_slot(ARRAY_ITERATOR_PROTOTYPE, create(global));
+}
@@ -9,41 +9,47 @@
 //! [spec]: https://tc39.es/ecma262/#sec-array-objects
 //! [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array
 
+pub mod array_iterator;
 #[cfg(test)]
 mod tests;
 
+use self::array_iterator::{create_array_iterator, ArrayIterationKind};
 use crate::{
     builtins::{
+        array_buffer::relative_index,
         object::{Object, ObjectInternalMethods, ObjectKind, INSTANCE_PROTOTYPE, PROTOTYPE},
         property::Property,
-        value::{same_value_zero, ResultValue, Value, ValueData},
+        string,
+        symbol::WellKnownSymbol,
+        value::{same_value_zero, JsString, ResultValue, Value, ValueData},
     },
     exec::Interpreter,
 };
 use std::borrow::Borrow;
-use std::cmp::{max, min};
+use std::cmp::{min, Ordering};
 use std::ops::Deref;
 
+/// The largest length of an array-like object, `2^53 - 1`.
+const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_991.0;
+
 /// Creates a new `Array` instance.
 pub(crate) fn new_array(interpreter: &Interpreter) -> ResultValue {
-    let array = Value::new_object(Some(
-        &interpreter
-            .get_realm()
-            .environment
-            .get_global_object()
-            .expect("Could not get global object"),
-    ));
+    let array = Value::new_object(Some(&interpreter.get_realm().intrinsics));
     array.set_kind(ObjectKind::Array);
     array.borrow().set_internal_slot(
         INSTANCE_PROTOTYPE,
         interpreter
             .get_realm()
-            .environment
-            .get_binding_value("Array")
-            .borrow()
+            .intrinsics
+            .get_field_slice("Array")
             .get_field_slice(PROTOTYPE),
     );
-    array.borrow().set_field_slice("length", Value::from(0));
+    let length = Property::new()
+        .value(Value::from(0))
+        .writable(true)
+        .configurable(false)
+        .enumerable(false);
+    array.borrow().set_property_slice("length", length);
     Ok(array)
 }
 
@@ -93,6 +99,128 @@ pub(crate) fn add_to_array_object(array_ptr: &Value, add_values: &[Value]) -> Re
     Ok(array_ptr.clone())
 }
 
+/// Creates a new array holding `elements`.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-createarrayfromlist
+pub(crate) fn create_array_from_list(elements: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    // The elements and the `length` of the array
+    ctx.reserve_properties(elements.len() + 1)?;
+    let array = new_array(ctx)?;
+    add_to_array_object(&array, elements)
+}
+
+/// Creates a new array with the given `length`.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-arraycreate
+fn array_create(length: usize, ctx: &Interpreter) -> ResultValue {
+    let array = new_array(ctx)?;
+    array.set_field_slice("length", Value::from(length));
+    Ok(array)
+}
+
+/// Creates the array returned by methods like `map` and `filter`, using the
+/// `constructor[Symbol.species]` of the original array if it has one.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-arrayspeciescreate
+fn array_species_create(original: &Value, length: usize, ctx: &mut Interpreter) -> ResultValue {
+    if !is_array_object(original) {
+        return array_create(length, ctx);
+    }
+
+    let mut constructor = original.get_field_slice("constructor");
+    if constructor.is_object() {
+        constructor = constructor.get_field(Value::symbol(WellKnownSymbol::Species.into()));
+        if constructor.is_null() {
+            constructor = Value::undefined();
+        }
+    }
+    if constructor.is_undefined() {
+        return array_create(length, ctx);
+    }
+    if !is_constructor(&constructor) {
+        return ctx.throw_type_error("object.constructor[Symbol.species] is not a constructor");
+    }
+    ctx.construct(&constructor, &[Value::from(length)])
+}
+
+/// Checks if the value is an `Array` object.
+pub(crate) fn is_array_object(value: &Value) -> bool {
+    value
+        .as_object()
+        .map_or(false, |obj| obj.kind == ObjectKind::Array)
+}
+
+/// Checks if the value is an object with a `[[Construct]]` internal method.
+fn is_constructor(value: &Value) -> bool {
+    value.as_object().map_or(false, |obj| obj.is_constructor())
+}
+
+/// The abstract operation `LengthOfArrayLike`, which reads the `length` of an array-like object.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-lengthofarraylike
+pub(crate) fn length_of_array_like(object: &Value, ctx: &mut Interpreter) -> Result<usize, Value> {
+    let length = ctx.get_field(object, Value::from("length"))?;
+    let length = ctx.to_number(&length)?;
+    Ok(if length.is_nan() || length <= 0.0 {
+        0
+    } else {
+        length.min(MAX_SAFE_INTEGER).trunc() as usize
+    })
+}
+
+/// Fetches the callback argument of methods like `forEach`, throwing a `TypeError` if it is not
+/// callable.
+fn callback_arg(args: &[Value], ctx: &Interpreter) -> Result<Value, Value> {
+    let callback = args.get(0).cloned().unwrap_or_else(Value::undefined);
+    if callback.is_function() {
+        Ok(callback)
+    } else {
+        Err(ctx.construct_type_error(format!("{} is not a function", callback)))
+    }
+}
+
+/// Sets a property of the object, throwing a `TypeError` if the assignment is rejected.
+fn set_or_throw(
+    object: &Value,
+    key: &str,
+    value: Value,
+    ctx: &mut Interpreter,
+) -> Result<(), Value> {
+    // Builtins fill arrays in loops which don't run any script, the heap limit is checked here
+    ctx.check_heap()?;
+    ctx.set_field(object, Value::from(key), value, true)
+}
+
+/// Deletes a property of the object, throwing a `TypeError` if it can't be deleted.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-deletepropertyorthrow
+fn delete_or_throw(object: &Value, key: &str, ctx: &Interpreter) -> Result<(), Value> {
+    let deleted = match object.as_object_mut() {
+        Some(mut obj) => obj.delete(&Value::from(key)),
+        None => true,
+    };
+    if deleted {
+        Ok(())
+    } else {
+        Err(ctx.construct_type_error(format!("Cannot delete property {}", key)))
+    }
+}
+
 /// Create a new array
 pub fn make_array(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
     // Make a new Object which will internally represent the Array (mapping
@@ -101,7 +229,7 @@ pub fn make_array(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> Re
     // Set Prototype
     let prototype = ctx
         .realm
-        .global_obj
+        .intrinsics
         .get_field_slice("Array")
         .get_field_slice(PROTOTYPE);
 
@@ -113,17 +241,19 @@ pub fn make_array(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> Re
     // add our arguments in
     let mut length = args.len() as i32;
     match args.len() {
-        1 if args[0].is_integer() => {
-            length = i32::from(&args[0]);
+        1 if args[0].is_number() => {
+            let number = args[0].to_number();
+            if number.trunc() != number || number < 0.0 || number > f64::from(u32::MAX) {
+                return ctx.throw_range_error("Invalid array length");
+            }
+            length = number as i32;
             // TODO: It should not create an array of undefineds, but an empty array ("holy" array in V8) with length `n`.
             for n in 0..length {
+                ctx.check_limits(n as usize)?;
+                ctx.check_heap()?;
                 this.set_field_slice(&n.to_string(), Value::undefined());
             }
         }
-        1 if args[0].is_double() => {
-            // TODO: throw `RangeError`.
-            panic!("RangeError: invalid array length");
-        }
         _ => {
             for (n, value) in args.iter().enumerate() {
                 this.set_field_slice(&n.to_string(), value.clone());
@@ -143,6 +273,11 @@ pub fn make_array(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> Re
     Ok(this.clone())
 }
 
+/// `Array( ...items )` called as a function, which creates an array just like `new Array()`.
+pub fn call_array(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    make_array(&mut Value::new_object(None), args, ctx)
+}
+
 /// `Array.isArray( arg )`
 ///
 /// The isArray function takes one argument arg, and returns the Boolean value true
@@ -177,6 +312,105 @@ pub fn is_array(_this: &mut Value, args: &[Value], _interpreter: &mut Interprete
     }
 }
 
+/// `Array.from( items [ , mapfn [ , thisArg ] ] )`
+///
+/// This method creates a new array from an iterable or an array-like object, optionally
+/// mapping each element.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-array.from
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/from
+pub fn from(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let items = args.get(0).cloned().unwrap_or_else(Value::undefined);
+    let map_fn = match args.get(1) {
+        Some(map_fn) if !map_fn.is_undefined() => {
+            if !map_fn.is_function() {
+                return ctx.throw_type_error(format!("{} is not a function", map_fn));
+            }
+            Some(map_fn.clone())
+        }
+        _ => None,
+    };
+    let mut this_arg = args.get(2).cloned().unwrap_or_else(Value::undefined);
+
+    let iterator = if items.is_null_or_undefined() {
+        Value::undefined()
+    } else {
+        ctx.to_object(&items)?
+            .get_field(Value::symbol(WellKnownSymbol::Iterator.into()))
+    };
+    let values = if iterator.is_null_or_undefined() {
+        // An array-like object
+        let array_like = ctx.to_object(&items)?;
+        let len = length_of_array_like(&array_like, ctx)?;
+        (0..len)
+            .map(|k| array_like.get_field_slice(&k.to_string()))
+            .collect()
+    } else {
+        ctx.extract_array_properties(&items)?
+    };
+
+    let array = if is_constructor(this) {
+        ctx.construct(this, &[])?
+    } else {
+        array_create(0, ctx)?
+    };
+    let len = values.len();
+    for (k, value) in values.into_iter().enumerate() {
+        let value = match map_fn {
+            Some(ref map_fn) => ctx.call(map_fn, &mut this_arg, &[value, Value::from(k)])?,
+            None => value,
+        };
+        set_or_throw(&array, &k.to_string(), value, ctx)?;
+    }
+    set_or_throw(&array, "length", Value::from(len), ctx)?;
+    Ok(array)
+}
+
+/// `Array.of( ...items )`
+///
+/// This method creates a new array from its arguments.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-array.of
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/of
+pub fn of(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let array = if is_constructor(this) {
+        ctx.construct(this, &[Value::from(args.len())])?
+    } else {
+        array_create(args.len(), ctx)?
+    };
+    for (k, value) in args.iter().enumerate() {
+        set_or_throw(&array, &k.to_string(), value.clone(), ctx)?;
+    }
+    set_or_throw(&array, "length", Value::from(args.len()), ctx)?;
+    Ok(array)
+}
+
+/// Checks if `concat` should add the elements of the value rather than the value itself.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-isconcatspreadable
+fn is_concat_spreadable(value: &Value) -> bool {
+    if !value.is_object() {
+        return false;
+    }
+    let spreadable = value.get_field(Value::symbol(WellKnownSymbol::IsConcatSpreadable.into()));
+    if spreadable.is_undefined() {
+        is_array_object(value)
+    } else {
+        spreadable.is_true()
+    }
+}
+
 /// `Array.prototype.concat(...arguments)`
 ///
 /// When the concat method is called with zero or more arguments, it returns an
@@ -189,29 +423,30 @@ pub fn is_array(_this: &mut Value, args: &[Value], _interpreter: &mut Interprete
 ///
 /// [spec]: https://tc39.es/ecma262/#sec-array.prototype.concat
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/concat
-pub fn concat(this: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValue {
-    if args.is_empty() {
-        // If concat is called with no arguments, it returns the original array
-        return Ok(this.clone());
-    }
-
-    // Make a new array (using this object as the prototype basis for the new
-    // one)
-    let mut new_values: Vec<Value> = Vec::new();
-
-    let this_length = i32::from(&this.get_field_slice("length"));
-    for n in 0..this_length {
-        new_values.push(this.get_field_slice(&n.to_string()));
-    }
-
-    for concat_array in args {
-        let concat_length = i32::from(&concat_array.get_field_slice("length"));
-        for n in 0..concat_length {
-            new_values.push(concat_array.get_field_slice(&n.to_string()));
+pub fn concat(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let object = ctx.to_object(this)?;
+    let array = array_species_create(&object, 0, ctx)?;
+
+    let mut n = 0;
+    for item in std::iter::once(&object).chain(args.iter()) {
+        if is_concat_spreadable(item) {
+            let len = length_of_array_like(item, ctx)?;
+            for k in 0..len {
+                ctx.check_limits(k)?;
+                let key = k.to_string();
+                if item.has_field(&key) {
+                    set_or_throw(&array, &n.to_string(), item.get_field_slice(&key), ctx)?;
+                }
+                n += 1;
+            }
+        } else {
+            set_or_throw(&array, &n.to_string(), item.clone(), ctx)?;
+            n += 1;
         }
     }
+    set_or_throw(&array, "length", Value::from(n), ctx)?;
 
-    construct_array(this, &new_values)
+    Ok(array)
 }
 
 /// `Array.prototype.push( ...items )`
@@ -226,9 +461,16 @@ pub fn concat(this: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultVa
 ///
 /// [spec]: https://tc39.es/ecma262/#sec-array.prototype.push
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/push
-pub fn push(this: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValue {
-    let new_array = add_to_array_object(this, args)?;
-    Ok(new_array.get_field_slice("length"))
+pub fn push(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let object = ctx.to_object(this)?;
+    let len = length_of_array_like(&object, ctx)?;
+    for (n, value) in args.iter().enumerate() {
+        set_or_throw(&object, &(len + n).to_string(), value.clone(), ctx)?;
+    }
+
+    let new_length = Value::from(len + args.len());
+    set_or_throw(&object, "length", new_length.clone(), ctx)?;
+    Ok(new_length)
 }
 
 /// `Array.prototype.pop()`
@@ -241,15 +483,18 @@ pub fn push(this: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValu
 ///
 /// [spec]: https://tc39.es/ecma262/#sec-array.prototype.pop
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/pop
-pub fn pop(this: &mut Value, _: &[Value], _: &mut Interpreter) -> ResultValue {
-    let curr_length = i32::from(&this.get_field_slice("length"));
-    if curr_length < 1 {
+pub fn pop(this: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let object = ctx.to_object(this)?;
+    let len = length_of_array_like(&object, ctx)?;
+    if len == 0 {
+        set_or_throw(&object, "length", Value::from(0), ctx)?;
         return Ok(Value::undefined());
     }
-    let pop_index = curr_length.wrapping_sub(1);
-    let pop_value: Value = this.get_field_slice(&pop_index.to_string());
-    this.remove_property(&pop_index.to_string());
-    this.set_field_slice("length", Value::from(pop_index));
+
+    let pop_index = (len - 1).to_string();
+    let pop_value = object.get_field_slice(&pop_index);
+    delete_or_throw(&object, &pop_index, ctx)?;
+    set_or_throw(&object, "length", Value::from(len - 1), ctx)?;
     Ok(pop_value)
 }
 
@@ -264,20 +509,21 @@ pub fn pop(this: &mut Value, _: &[Value], _: &mut Interpreter) -> ResultValue {
 /// [spec]: https://tc39.es/ecma262/#sec-array.prototype.foreach
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/forEach
 pub fn for_each(this: &mut Value, args: &[Value], interpreter: &mut Interpreter) -> ResultValue {
-    if args.is_empty() {
-        return Err(Value::from("Missing argument for Array.prototype.forEach"));
-    }
-
-    let callback_arg = args.get(0).expect("Could not get `callbackFn` argument.");
+    let object = interpreter.to_object(this)?;
+    let length = length_of_array_like(&object, interpreter)?;
+    let callback_arg = callback_arg(args, interpreter)?;
     let mut this_arg = args.get(1).cloned().unwrap_or_else(Value::undefined);
 
-    let length = i32::from(&this.get_field_slice("length"));
-
     for i in 0..length {
-        let element = this.get_field_slice(&i.to_string());
-        let arguments = [element, Value::from(i), this.clone()];
+        interpreter.check_limits(i)?;
+        let key = i.to_string();
+        if !object.has_field(&key) {
+            continue;
+        }
+        let element = object.get_field_slice(&key);
+        let arguments = [element, Value::from(i), object.clone()];
 
-        interpreter.call(callback_arg, &mut this_arg, &arguments)?;
+        interpreter.call(&callback_arg, &mut this_arg, &arguments)?;
     }
 
     Ok(Value::undefined())
@@ -295,21 +541,28 @@ pub fn for_each(this: &mut Value, args: &[Value], interpreter: &mut Interpreter)
 ///
 /// [spec]: https://tc39.es/ecma262/#sec-array.prototype.join
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/join
-pub fn join(this: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValue {
-    let separator = if args.is_empty() {
-        String::from(",")
-    } else {
-        args.get(0).expect("Could not get argument").to_string()
+pub fn join(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let object = ctx.to_object(this)?;
+    let length = length_of_array_like(&object, ctx)?;
+    let separator = match args.get(0) {
+        Some(separator) if !separator.is_undefined() => string::to_js_string(separator, ctx)?,
+        _ => JsString::from(","),
     };
 
-    let mut elem_strs: Vec<String> = Vec::new();
-    let length = i32::from(&this.get_field_slice("length"));
+    let mut joined: Vec<u16> = Vec::new();
     for n in 0..length {
-        let elem_str: String = this.get_field_slice(&n.to_string()).to_string();
-        elem_strs.push(elem_str);
+        ctx.check_limits(n)?;
+        if n > 0 {
+            joined.extend_from_slice(&separator);
+        }
+        let element = object.get_field_slice(&n.to_string());
+        if !element.is_null_or_undefined() {
+            joined.extend_from_slice(&string::to_js_string(&element, ctx)?);
+        }
+        ctx.reserve_string(joined.len())?;
     }
 
-    Ok(Value::from(elem_strs.join(&separator)))
+    Ok(Value::from(JsString::from(joined)))
 }
 
 /// `Array.prototype.toString( separator )`
@@ -333,7 +586,7 @@ pub fn to_string(this: &mut Value, _args: &[Value], _ctx: &mut Interpreter) -> R
     if !method.is_function() {
         method = _ctx
             .realm
-            .global_obj
+            .intrinsics
             .get_field_slice("Object")
             .get_field_slice(PROTOTYPE)
             .get_field_slice("toString");
@@ -344,7 +597,7 @@ pub fn to_string(this: &mut Value, _args: &[Value], _ctx: &mut Interpreter) -> R
     let join_result = _ctx.call(&method, this, &arguments);
     let match_string = match join_result {
         Ok(v) => match *v {
-            ValueData::String(ref s) => (*s).clone(),
+            ValueData::String(ref s) => s.to_string(),
             _ => "".to_string(),
         },
         Err(v) => format!("error: {}", v),
@@ -364,32 +617,35 @@ pub fn to_string(this: &mut Value, _args: &[Value], _ctx: &mut Interpreter) -> R
 /// [spec]: https://tc39.es/ecma262/#sec-array.prototype.reverse
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/reverse
 #[allow(clippy::else_if_without_else)]
-pub fn reverse(this: &mut Value, _: &[Value], _: &mut Interpreter) -> ResultValue {
-    let len = i32::from(&this.get_field_slice("length"));
-    let middle: i32 = len.wrapping_div(2);
+pub fn reverse(this: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let object = ctx.to_object(this)?;
+    let len = length_of_array_like(&object, ctx)?;
+    let middle = len / 2;
 
     for lower in 0..middle {
-        let upper = len.wrapping_sub(lower).wrapping_sub(1);
+        ctx.check_limits(lower)?;
+        let upper = (len - lower - 1).to_string();
+        let lower = lower.to_string();
 
-        let upper_exists = this.has_field(&upper.to_string());
-        let lower_exists = this.has_field(&lower.to_string());
+        let upper_exists = object.has_field(&upper);
+        let lower_exists = object.has_field(&lower);
 
-        let upper_value = this.get_field_slice(&upper.to_string());
-        let lower_value = this.get_field_slice(&lower.to_string());
+        let upper_value = object.get_field_slice(&upper);
+        let lower_value = object.get_field_slice(&lower);
 
         if upper_exists && lower_exists {
-            this.set_field_slice(&upper.to_string(), lower_value);
-            this.set_field_slice(&lower.to_string(), upper_value);
+            set_or_throw(&object, &upper, lower_value, ctx)?;
+            set_or_throw(&object, &lower, upper_value, ctx)?;
         } else if upper_exists {
-            this.set_field_slice(&lower.to_string(), upper_value);
-            this.remove_property(&upper.to_string());
+            set_or_throw(&object, &lower, upper_value, ctx)?;
+            delete_or_throw(&object, &upper, ctx)?;
         } else if lower_exists {
-            this.set_field_slice(&upper.to_string(), lower_value);
-            this.remove_property(&lower.to_string());
+            set_or_throw(&object, &upper, lower_value, ctx)?;
+            delete_or_throw(&object, &lower, ctx)?;
         }
     }
 
-    Ok(this.clone())
+    Ok(object)
 }
 
 /// `Array.prototype.shift()`
@@ -402,32 +658,32 @@ pub fn reverse(this: &mut Value, _: &[Value], _: &mut Interpreter) -> ResultValu
 ///
 /// [spec]: https://tc39.es/ecma262/#sec-array.prototype.shift
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/shift
-pub fn shift(this: &mut Value, _: &[Value], _: &mut Interpreter) -> ResultValue {
-    let len = i32::from(&this.get_field_slice("length"));
+pub fn shift(this: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let object = ctx.to_object(this)?;
+    let len = length_of_array_like(&object, ctx)?;
 
     if len == 0 {
-        this.set_field_slice("length", Value::from(0));
-        // Since length is 0, this will be an Undefined value
-        return Ok(this.get_field_slice(&0.to_string()));
+        set_or_throw(&object, "length", Value::from(0), ctx)?;
+        return Ok(Value::undefined());
     }
 
-    let first: Value = this.get_field_slice(&0.to_string());
+    let first: Value = object.get_field_slice("0");
 
     for k in 1..len {
+        ctx.check_limits(k)?;
         let from = k.to_string();
-        let to = (k.wrapping_sub(1)).to_string();
+        let to = (k - 1).to_string();
 
-        let from_value = this.get_field_slice(&from);
-        if from_value.is_undefined() {
-            this.remove_property(&to);
+        if object.has_field(&from) {
+            set_or_throw(&object, &to, object.get_field_slice(&from), ctx)?;
         } else {
-            this.set_field_slice(&to, from_value);
+            delete_or_throw(&object, &to, ctx)?;
         }
     }
 
-    let final_index = len.wrapping_sub(1);
-    this.remove_property(&(final_index).to_string());
-    this.set_field_slice("length", Value::from(final_index));
+    let final_index = len - 1;
+    delete_or_throw(&object, &final_index.to_string(), ctx)?;
+    set_or_throw(&object, "length", Value::from(final_index), ctx)?;
 
     Ok(first)
 }
@@ -444,35 +700,31 @@ pub fn shift(this: &mut Value, _: &[Value], _: &mut Interpreter) -> ResultValue
 ///
 /// [spec]: https://tc39.es/ecma262/#sec-array.prototype.unshift
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/unshift
-pub fn unshift(this: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValue {
-    let len = i32::from(&this.get_field_slice("length"));
-    let arg_c: i32 = args.len() as i32;
+pub fn unshift(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let object = ctx.to_object(this)?;
+    let len = length_of_array_like(&object, ctx)?;
+    let arg_c = args.len();
 
     if arg_c > 0 {
-        for k in (1..=len).rev() {
-            let from = (k.wrapping_sub(1)).to_string();
-            let to = (k.wrapping_add(arg_c).wrapping_sub(1)).to_string();
+        for k in (0..len).rev() {
+            ctx.check_limits(k)?;
+            let from = k.to_string();
+            let to = (k + arg_c).to_string();
 
-            let from_value = this.get_field_slice(&from);
-            if from_value.is_undefined() {
-                this.remove_property(&to);
+            if object.has_field(&from) {
+                set_or_throw(&object, &to, object.get_field_slice(&from), ctx)?;
             } else {
-                this.set_field_slice(&to, from_value);
+                delete_or_throw(&object, &to, ctx)?;
             }
         }
-        for j in 0..arg_c {
-            this.set_field_slice(
-                &j.to_string(),
-                args.get(j as usize)
-                    .expect("Could not get argument")
-                    .clone(),
-            );
+        for (j, value) in args.iter().enumerate() {
+            set_or_throw(&object, &j.to_string(), value.clone(), ctx)?;
         }
     }
 
-    let temp = len.wrapping_add(arg_c);
-    this.set_field_slice("length", Value::from(temp));
-    Ok(Value::from(temp))
+    let temp = Value::from(len + arg_c);
+    set_or_throw(&object, "length", temp.clone(), ctx)?;
+    Ok(temp)
 }
 
 /// `Array.prototype.every( callback, [ thisArg ] )`
@@ -489,31 +741,26 @@ pub fn unshift(this: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultV
 /// [spec]: https://tc39.es/ecma262/#sec-array.prototype.every
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/every
 pub fn every(this: &mut Value, args: &[Value], interpreter: &mut Interpreter) -> ResultValue {
-    if args.is_empty() {
-        return Err(Value::from(
-            "missing callback when calling function Array.prototype.every",
-        ));
-    }
-    let callback = &args[0];
-    let mut this_arg = if args.len() > 1 {
-        args[1].clone()
-    } else {
-        Value::undefined()
-    };
-    let mut i = 0;
-    let max_len = i32::from(&this.get_field_slice("length"));
-    let mut len = max_len;
-    while i < len {
-        let element = this.get_field_slice(&i.to_string());
-        let arguments = [element, Value::from(i), this.clone()];
+    let object = interpreter.to_object(this)?;
+    let len = length_of_array_like(&object, interpreter)?;
+    let callback = callback_arg(args, interpreter)?;
+    let mut this_arg = args.get(1).cloned().unwrap_or_else(Value::undefined);
+
+    for i in 0..len {
+        interpreter.check_limits(i)?;
+        // Elements deleted by the callback are skipped
+        let key = i.to_string();
+        if !object.has_field(&key) {
+            continue;
+        }
+        let element = object.get_field_slice(&key);
+        let arguments = [element, Value::from(i), object.clone()];
         let result = interpreter
-            .call(callback, &mut this_arg, &arguments)?
+            .call(&callback, &mut this_arg, &arguments)?
             .is_true();
         if !result {
             return Ok(Value::from(false));
         }
-        len = min(max_len, i32::from(&this.get_field_slice("length")));
-        i += 1;
     }
     Ok(Value::from(true))
 }
@@ -530,31 +777,27 @@ pub fn every(this: &mut Value, args: &[Value], interpreter: &mut Interpreter) ->
 /// [spec]: https://tc39.es/ecma262/#sec-array.prototype.map
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/map
 pub fn map(this: &mut Value, args: &[Value], interpreter: &mut Interpreter) -> ResultValue {
-    if args.is_empty() {
-        return Err(Value::from(
-            "missing argument 0 when calling function Array.prototype.map",
-        ));
-    }
-
-    let callback = args.get(0).cloned().unwrap_or_else(Value::undefined);
+    let object = interpreter.to_object(this)?;
+    let length = length_of_array_like(&object, interpreter)?;
+    let callback = callback_arg(args, interpreter)?;
     let mut this_val = args.get(1).cloned().unwrap_or_else(Value::undefined);
 
-    let length = i32::from(&this.get_field_slice("length"));
-
-    let new = new_array(&interpreter)?;
+    let new = array_species_create(&object, length, interpreter)?;
 
-    let values: Vec<Value> = (0..length)
-        .map(|idx| {
-            let element = this.get_field_slice(&idx.to_string());
-            let args = [element, Value::from(idx), new.clone()];
+    for idx in 0..length {
+        interpreter.check_limits(idx)?;
+        let key = idx.to_string();
+        if !object.has_field(&key) {
+            continue;
+        }
+        let element = object.get_field_slice(&key);
+        let args = [element, Value::from(idx), object.clone()];
 
-            interpreter
-                .call(&callback, &mut this_val, &args)
-                .unwrap_or_else(|_| Value::undefined())
-        })
-        .collect();
+        let mapped = interpreter.call(&callback, &mut this_val, &args)?;
+        set_or_throw(&new, &key, mapped, interpreter)?;
+    }
 
-    construct_array(&new, &values)
+    Ok(new)
 }
 
 /// `Array.prototype.indexOf( searchElement[, fromIndex ] )`
@@ -576,36 +819,23 @@ pub fn map(this: &mut Value, args: &[Value], interpreter: &mut Interpreter) -> R
 ///
 /// [spec]: https://tc39.es/ecma262/#sec-array.prototype.indexof
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/indexOf
-pub fn index_of(this: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValue {
+pub fn index_of(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let object = ctx.to_object(this)?;
+    let len = length_of_array_like(&object, ctx)?;
     // If no arguments, return -1. Not described in spec, but is what chrome does.
-    if args.is_empty() {
+    if args.is_empty() || len == 0 {
         return Ok(Value::from(-1));
     }
 
     let search_element = args[0].clone();
-    let len = i32::from(&this.get_field_slice("length"));
-
-    let mut idx = match args.get(1) {
-        Some(from_idx_ptr) => {
-            let from_idx = i32::from(from_idx_ptr);
-
-            if from_idx < 0 {
-                len + from_idx
-            } else {
-                from_idx
-            }
-        }
-        None => 0,
-    };
+    let start = relative_index(args.get(1), len, 0);
 
-    while idx < len {
-        let check_element = this.get_field_slice(&idx.to_string()).clone();
-
-        if check_element.strict_equals(&search_element) {
+    for idx in start..len {
+        ctx.check_limits(idx)?;
+        let key = idx.to_string();
+        if object.has_field(&key) && object.get_field_slice(&key).strict_equals(&search_element) {
             return Ok(Value::from(idx));
         }
-
-        idx += 1;
     }
 
     Ok(Value::from(-1))
@@ -629,41 +859,73 @@ pub fn index_of(this: &mut Value, args: &[Value], _: &mut Interpreter) -> Result
 ///
 /// [spec]: https://tc39.es/ecma262/#sec-array.prototype.lastindexof
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/lastIndexOf
-pub fn last_index_of(this: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValue {
+pub fn last_index_of(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let object = ctx.to_object(this)?;
+    let len = length_of_array_like(&object, ctx)?;
     // If no arguments, return -1. Not described in spec, but is what chrome does.
-    if args.is_empty() {
+    if args.is_empty() || len == 0 {
         return Ok(Value::from(-1));
     }
 
     let search_element = args[0].clone();
-    let len = i32::from(&this.get_field_slice("length"));
-
-    let mut idx = match args.get(1) {
-        Some(from_idx_ptr) => {
-            let from_idx = i32::from(from_idx_ptr);
-
-            if from_idx >= 0 {
-                min(from_idx, len - 1)
-            } else {
-                len + from_idx
-            }
-        }
-        None => len - 1,
+    let from_index = match args.get(1) {
+        Some(from_index) => from_index.to_integer_or_infinity(),
+        None => len as f64 - 1.0,
     };
+    let start = if from_index >= 0.0 {
+        from_index.min(len as f64 - 1.0)
+    } else {
+        len as f64 + from_index
+    };
+    if start < 0.0 {
+        return Ok(Value::from(-1));
+    }
 
-    while idx >= 0 {
-        let check_element = this.get_field_slice(&idx.to_string()).clone();
-
-        if check_element.strict_equals(&search_element) {
+    for idx in (0..=start as usize).rev() {
+        ctx.check_limits(idx)?;
+        let key = idx.to_string();
+        if object.has_field(&key) && object.get_field_slice(&key).strict_equals(&search_element) {
             return Ok(Value::from(idx));
         }
-
-        idx -= 1;
     }
 
     Ok(Value::from(-1))
 }
 
+/// Searches the elements in ascending or descending order for one that satisfies `predicate`,
+/// as `find`, `findIndex`, `findLast` and `findLastIndex` do.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-findviapredicate
+fn find_via_predicate(
+    this: &Value,
+    args: &[Value],
+    reverse: bool,
+    ctx: &mut Interpreter,
+) -> Result<Option<(usize, Value)>, Value> {
+    let object = ctx.to_object(this)?;
+    let len = length_of_array_like(&object, ctx)?;
+    let predicate = callback_arg(args, ctx)?;
+    let mut this_arg = args.get(1).cloned().unwrap_or_else(Value::undefined);
+
+    let indices: Box<dyn Iterator<Item = usize>> = if reverse {
+        Box::new((0..len).rev())
+    } else {
+        Box::new(0..len)
+    };
+    for i in indices {
+        ctx.check_limits(i)?;
+        let element = object.get_field_slice(&i.to_string());
+        let arguments = [element.clone(), Value::from(i), object.clone()];
+        if ctx.call(&predicate, &mut this_arg, &arguments)?.is_true() {
+            return Ok(Some((i, element)));
+        }
+    }
+    Ok(None)
+}
+
 /// `Array.prototype.find( callback, [thisArg] )`
 ///
 /// The find method executes the callback function once for each index of the array
@@ -677,27 +939,8 @@ pub fn last_index_of(this: &mut Value, args: &[Value], _: &mut Interpreter) -> R
 /// [spec]: https://tc39.es/ecma262/#sec-array.prototype.find
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/find
 pub fn find(this: &mut Value, args: &[Value], interpreter: &mut Interpreter) -> ResultValue {
-    if args.is_empty() {
-        return Err(Value::from(
-            "missing callback when calling function Array.prototype.find",
-        ));
-    }
-    let callback = &args[0];
-    let mut this_arg = if args.len() > 1 {
-        args[1].clone()
-    } else {
-        Value::undefined()
-    };
-    let len = i32::from(&this.get_field_slice("length"));
-    for i in 0..len {
-        let element = this.get_field_slice(&i.to_string());
-        let arguments = [element.clone(), Value::from(i), this.clone()];
-        let result = interpreter.call(callback, &mut this_arg, &arguments)?;
-        if result.is_true() {
-            return Ok(element);
-        }
-    }
-    Ok(Value::undefined())
+    let found = find_via_predicate(this, args, false, interpreter)?;
+    Ok(found.map_or_else(Value::undefined, |(_, element)| element))
 }
 
 /// `Array.prototype.findIndex( predicate [ , thisArg ] )`
@@ -713,30 +956,40 @@ pub fn find(this: &mut Value, args: &[Value], interpreter: &mut Interpreter) ->
 /// [spec]: https://tc39.es/ecma262/#sec-array.prototype.findindex
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/findIndex
 pub fn find_index(this: &mut Value, args: &[Value], interpreter: &mut Interpreter) -> ResultValue {
-    if args.is_empty() {
-        return Err(Value::from(
-            "Missing argument for Array.prototype.findIndex",
-        ));
-    }
-
-    let predicate_arg = args.get(0).expect("Could not get `predicate` argument.");
-
-    let mut this_arg = args.get(1).cloned().unwrap_or_else(Value::undefined);
-
-    let length = i32::from(&this.get_field_slice("length"));
-
-    for i in 0..length {
-        let element = this.get_field_slice(&i.to_string());
-        let arguments = [element, Value::from(i), this.clone()];
-
-        let result = interpreter.call(predicate_arg, &mut this_arg, &arguments)?;
+    let found = find_via_predicate(this, args, false, interpreter)?;
+    Ok(found.map_or_else(|| Value::from(-1), |(index, _)| Value::from(index)))
+}
 
-        if result.is_true() {
-            return Ok(Value::rational(f64::from(i)));
-        }
-    }
+/// `Array.prototype.findLast( predicate [ , thisArg ] )`
+///
+/// This method returns the value of the last element that satisfies the predicate, or
+/// `undefined` if there is none.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-array.prototype.findlast
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/findLast
+pub fn find_last(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let found = find_via_predicate(this, args, true, ctx)?;
+    Ok(found.map_or_else(Value::undefined, |(_, element)| element))
+}
 
-    Ok(Value::rational(-1_f64))
+/// `Array.prototype.findLastIndex( predicate [ , thisArg ] )`
+///
+/// This method returns the index of the last element that satisfies the predicate, or `-1` if
+/// there is none.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-array.prototype.findlastindex
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/findLastIndex
+pub fn find_last_index(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let found = find_via_predicate(this, args, true, ctx)?;
+    Ok(found.map_or_else(|| Value::from(-1), |(index, _)| Value::from(index)))
 }
 
 /// `Array.prototype.fill( value[, start[, end]] )`
@@ -750,33 +1003,19 @@ pub fn find_index(this: &mut Value, args: &[Value], interpreter: &mut Interprete
 ///
 /// [spec]: https://tc39.es/ecma262/#sec-array.prototype.fill
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/fill
-pub fn fill(this: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValue {
-    let len: i32 = i32::from(&this.get_field_slice("length"));
-    let default_value = Value::undefined();
-    let value = args.get(0).unwrap_or(&default_value);
-    let relative_start = args.get(1).unwrap_or(&default_value).to_number() as i32;
-    let relative_end_val = args.get(2).unwrap_or(&default_value);
-    let relative_end = if relative_end_val.is_undefined() {
-        len
-    } else {
-        relative_end_val.to_number() as i32
-    };
-    let start = if relative_start < 0 {
-        max(len + relative_start, 0)
-    } else {
-        min(relative_start, len)
-    };
-    let fin = if relative_end < 0 {
-        max(len + relative_end, 0)
-    } else {
-        min(relative_end, len)
-    };
+pub fn fill(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let object = ctx.to_object(this)?;
+    let len = length_of_array_like(&object, ctx)?;
+    let value = args.get(0).cloned().unwrap_or_else(Value::undefined);
+    let start = relative_index(args.get(1), len, 0);
+    let fin = relative_index(args.get(2), len, len);
 
     for i in start..fin {
-        this.set_field_slice(&i.to_string(), value.clone());
+        ctx.check_limits(i)?;
+        set_or_throw(&object, &i.to_string(), value.clone(), ctx)?;
     }
 
-    Ok(this.clone())
+    Ok(object)
 }
 
 /// `Array.prototype.includes( valueToFind [, fromIndex] )`
@@ -789,13 +1028,15 @@ pub fn fill(this: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValu
 ///
 /// [spec]: https://tc39.es/ecma262/#sec-array.prototype.includes
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/includes
-pub fn includes_value(this: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValue {
+pub fn includes_value(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let object = ctx.to_object(this)?;
+    let length = length_of_array_like(&object, ctx)?;
     let search_element = args.get(0).cloned().unwrap_or_else(Value::undefined);
+    let start = relative_index(args.get(1), length, 0);
 
-    let length = i32::from(&this.get_field_slice("length"));
-
-    for idx in 0..length {
-        let check_element = this.get_field_slice(&idx.to_string()).clone();
+    for idx in start..length {
+        ctx.check_limits(idx)?;
+        let check_element = object.get_field_slice(&idx.to_string());
 
         if same_value_zero(&check_element, &search_element) {
             return Ok(Value::from(true));
@@ -820,40 +1061,103 @@ pub fn includes_value(this: &mut Value, args: &[Value], _: &mut Interpreter) ->
 /// [spec]: https://tc39.es/ecma262/#sec-array.prototype.slice
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/slice
 pub fn slice(this: &mut Value, args: &[Value], interpreter: &mut Interpreter) -> ResultValue {
-    let new_array = new_array(interpreter)?;
-    let len = i32::from(&this.get_field_slice("length"));
+    let object = interpreter.to_object(this)?;
+    let len = length_of_array_like(&object, interpreter)?;
+    let from = relative_index(args.get(0), len, 0);
+    let to = relative_index(args.get(1), len, len);
+
+    let span = to.saturating_sub(from);
+    let new_array = array_species_create(&object, span, interpreter)?;
+    for (n, i) in (from..from + span).enumerate() {
+        interpreter.check_limits(i)?;
+        let key = i.to_string();
+        if object.has_field(&key) {
+            set_or_throw(
+                &new_array,
+                &n.to_string(),
+                object.get_field_slice(&key),
+                interpreter,
+            )?;
+        }
+    }
+    set_or_throw(&new_array, "length", Value::from(span), interpreter)?;
+    Ok(new_array)
+}
 
-    let start = match args.get(0) {
-        Some(v) => i32::from(v),
-        None => 0,
-    };
-    let end = match args.get(1) {
-        Some(v) => i32::from(v),
-        None => len,
+/// `Array.prototype.splice( start [ , deleteCount [ , ...items ] ] )`
+///
+/// This method changes the contents of an array by removing or replacing existing elements
+/// and/or adding new elements in place. The removed elements are returned in a new array.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-array.prototype.splice
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/splice
+pub fn splice(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let object = ctx.to_object(this)?;
+    let len = length_of_array_like(&object, ctx)?;
+    let start = relative_index(args.get(0), len, 0);
+    let items = args.get(2..).unwrap_or(&[]);
+    let delete_count = match args.len() {
+        0 => 0,
+        1 => len - start,
+        _ => {
+            let count = args[1].to_integer_or_infinity().max(0.0);
+            count.min((len - start) as f64) as usize
+        }
     };
+    if (len + items.len() - delete_count) as f64 > MAX_SAFE_INTEGER {
+        return ctx.throw_type_error("Invalid array length");
+    }
 
-    let from = if start < 0 {
-        max(len.wrapping_add(start), 0)
-    } else {
-        min(start, len)
-    };
-    let to = if end < 0 {
-        max(len.wrapping_add(end), 0)
-    } else {
-        min(end, len)
+    let removed = array_species_create(&object, delete_count, ctx)?;
+    for k in 0..delete_count {
+        ctx.check_limits(k)?;
+        let from = (start + k).to_string();
+        if object.has_field(&from) {
+            set_or_throw(&removed, &k.to_string(), object.get_field_slice(&from), ctx)?;
+        }
+    }
+    set_or_throw(&removed, "length", Value::from(delete_count), ctx)?;
+
+    // Move the elements after the removed ones to their new place
+    let item_count = items.len();
+    let move_element = |from: usize, to: usize, ctx: &mut Interpreter| -> Result<(), Value> {
+        let (from, to) = (from.to_string(), to.to_string());
+        if object.has_field(&from) {
+            set_or_throw(&object, &to, object.get_field_slice(&from), ctx)
+        } else {
+            delete_or_throw(&object, &to, ctx)
+        }
     };
+    if item_count < delete_count {
+        for k in start..len - delete_count {
+            ctx.check_limits(k)?;
+            move_element(k + delete_count, k + item_count, ctx)?;
+        }
+        for k in (len - delete_count + item_count..len).rev() {
+            ctx.check_limits(k)?;
+            delete_or_throw(&object, &k.to_string(), ctx)?;
+        }
+    } else if item_count > delete_count {
+        for k in (start..len - delete_count).rev() {
+            ctx.check_limits(k)?;
+            move_element(k + delete_count, k + item_count, ctx)?;
+        }
+    }
 
-    let span = max(to.wrapping_sub(from), 0);
-    let mut new_array_len: i32 = 0;
-    for i in from..from.wrapping_add(span) {
-        new_array.set_field_slice(
-            &new_array_len.to_string(),
-            this.get_field_slice(&i.to_string()),
-        );
-        new_array_len = new_array_len.wrapping_add(1);
+    for (k, item) in items.iter().enumerate() {
+        set_or_throw(&object, &(start + k).to_string(), item.clone(), ctx)?;
     }
-    new_array.set_field_slice("length", Value::from(new_array_len));
-    Ok(new_array)
+    set_or_throw(
+        &object,
+        "length",
+        Value::from(len - delete_count + item_count),
+        ctx,
+    )?;
+    Ok(removed)
 }
 
 /// `Array.prototype.filter( callback, [ thisArg ] )`
@@ -868,38 +1172,30 @@ pub fn slice(this: &mut Value, args: &[Value], interpreter: &mut Interpreter) ->
 /// [spec]: https://tc39.es/ecma262/#sec-array.prototype.filter
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/filter
 pub fn filter(this: &mut Value, args: &[Value], interpreter: &mut Interpreter) -> ResultValue {
-    if args.is_empty() {
-        return Err(Value::from(
-            "missing argument 0 when calling function Array.prototype.filter",
-        ));
-    }
-
-    let callback = args.get(0).cloned().unwrap_or_else(Value::undefined);
+    let object = interpreter.to_object(this)?;
+    let length = length_of_array_like(&object, interpreter)?;
+    let callback = callback_arg(args, interpreter)?;
     let mut this_val = args.get(1).cloned().unwrap_or_else(Value::undefined);
 
-    let length = i32::from(&this.get_field_slice("length"));
-
-    let new = new_array(&interpreter)?;
-
-    let values = (0..length)
-        .filter_map(|idx| {
-            let element = this.get_field_slice(&idx.to_string());
+    let new = array_species_create(&object, 0, interpreter)?;
 
-            let args = [element.clone(), Value::from(idx), new.clone()];
-
-            let callback_result = interpreter
-                .call(&callback, &mut this_val, &args)
-                .unwrap_or_else(|_| Value::undefined());
+    let mut to = 0;
+    for idx in 0..length {
+        interpreter.check_limits(idx)?;
+        let key = idx.to_string();
+        if !object.has_field(&key) {
+            continue;
+        }
+        let element = object.get_field_slice(&key);
+        let args = [element.clone(), Value::from(idx), object.clone()];
 
-            if callback_result.is_true() {
-                Some(element)
-            } else {
-                None
-            }
-        })
-        .collect::<Vec<Value>>();
+        if interpreter.call(&callback, &mut this_val, &args)?.is_true() {
+            set_or_throw(&new, &to.to_string(), element, interpreter)?;
+            to += 1;
+        }
+    }
 
-    construct_array(&new, &values)
+    Ok(new)
 }
 
 /// Array.prototype.some ( callbackfn [ , thisArg ] )
@@ -918,40 +1214,439 @@ pub fn filter(this: &mut Value, args: &[Value], interpreter: &mut Interpreter) -
 /// [spec]: https://tc39.es/ecma262/#sec-array.prototype.some
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/some
 pub fn some(this: &mut Value, args: &[Value], interpreter: &mut Interpreter) -> ResultValue {
-    if args.is_empty() {
-        return Err(Value::from(
-            "missing callback when calling function Array.prototype.some",
-        ));
-    }
-    let callback = &args[0];
-    let mut this_arg = if args.len() > 1 {
-        args[1].clone()
-    } else {
-        Value::undefined()
-    };
-    let mut i = 0;
-    let max_len = i32::from(&this.get_field_slice("length"));
-    let mut len = max_len;
-    while i < len {
-        let element = this.get_field_slice(&i.to_string());
-        let arguments = [element, Value::from(i), this.clone()];
+    let object = interpreter.to_object(this)?;
+    let len = length_of_array_like(&object, interpreter)?;
+    let callback = callback_arg(args, interpreter)?;
+    let mut this_arg = args.get(1).cloned().unwrap_or_else(Value::undefined);
+
+    for i in 0..len {
+        interpreter.check_limits(i)?;
+        // the callback can mutate the array, elements it deleted are skipped.
+        let key = i.to_string();
+        if !object.has_field(&key) {
+            continue;
+        }
+        let element = object.get_field_slice(&key);
+        let arguments = [element, Value::from(i), object.clone()];
         let result = interpreter
-            .call(callback, &mut this_arg, &arguments)?
+            .call(&callback, &mut this_arg, &arguments)?
             .is_true();
         if result {
             return Ok(Value::from(true));
         }
-        // the length of the array must be updated because the callback can mutate it.
-        len = min(max_len, i32::from(&this.get_field_slice("length")));
-        i += 1;
     }
     Ok(Value::from(false))
 }
 
+/// Shared implementation of `reduce` and `reduceRight`.
+fn reduce_elements(
+    this: &Value,
+    args: &[Value],
+    reverse: bool,
+    ctx: &mut Interpreter,
+) -> ResultValue {
+    let object = ctx.to_object(this)?;
+    let len = length_of_array_like(&object, ctx)?;
+    let callback = callback_arg(args, ctx)?;
+
+    let mut indices: Box<dyn Iterator<Item = usize>> = if reverse {
+        Box::new((0..len).rev())
+    } else {
+        Box::new(0..len)
+    };
+    let mut accumulator = match args.get(1) {
+        Some(initial_value) => initial_value.clone(),
+        // Without an initial value the first present element is used
+        None => loop {
+            match indices.next() {
+                Some(k) if object.has_field(&k.to_string()) => {
+                    break object.get_field_slice(&k.to_string())
+                }
+                Some(k) => ctx.check_limits(k)?,
+                None => return ctx.throw_type_error("Reduce of empty array with no initial value"),
+            }
+        },
+    };
+
+    for k in indices {
+        ctx.check_limits(k)?;
+        let key = k.to_string();
+        if !object.has_field(&key) {
+            continue;
+        }
+        let arguments = [
+            accumulator,
+            object.get_field_slice(&key),
+            Value::from(k),
+            object.clone(),
+        ];
+        accumulator = ctx.call(&callback, &mut Value::undefined(), &arguments)?;
+    }
+    Ok(accumulator)
+}
+
+/// `Array.prototype.reduce( callbackfn [ , initialValue ] )`
+///
+/// This method executes a reducer callback on each element of the array, in ascending order,
+/// passing in the return value from the calculation on the preceding element.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-array.prototype.reduce
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/reduce
+pub fn reduce(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    reduce_elements(this, args, false, ctx)
+}
+
+/// `Array.prototype.reduceRight( callbackfn [ , initialValue ] )`
+///
+/// This method is like `reduce`, but it goes through the elements from the last to the first.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-array.prototype.reduceright
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/reduceRight
+pub fn reduce_right(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    reduce_elements(this, args, true, ctx)
+}
+
+/// `Array.prototype.sort( [ comparefn ] )`
+///
+/// This method sorts the elements of an array in place, and returns the array. The sort is
+/// stable. Without a comparison function the elements are compared as strings, `undefined`
+/// always sorts last.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-array.prototype.sort
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/sort
+pub fn sort(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let compare_fn = match args.get(0) {
+        Some(f) if !f.is_undefined() => {
+            if !f.is_function() {
+                return ctx.throw_type_error("The comparison function must be a function");
+            }
+            Some(f.clone())
+        }
+        _ => None,
+    };
+    let object = ctx.to_object(this)?;
+    let len = length_of_array_like(&object, ctx)?;
+
+    // Holes are moved to the end, after the `undefined`s
+    let mut items = Vec::new();
+    let mut undefined_count = 0;
+    for k in 0..len {
+        ctx.check_limits(k)?;
+        let key = k.to_string();
+        if object.has_field(&key) {
+            let element = object.get_field_slice(&key);
+            if element.is_undefined() {
+                undefined_count += 1;
+            } else {
+                items.push(element);
+            }
+        }
+    }
+
+    let sorted = merge_sort(items, |x, y| match compare_fn {
+        Some(ref f) => {
+            let v = ctx.call(f, &mut Value::undefined(), &[x.clone(), y.clone()])?;
+            let v = ctx.to_number(&v)?;
+            Ok(if v < 0.0 {
+                Ordering::Less
+            } else if v > 0.0 {
+                Ordering::Greater
+            } else {
+                Ordering::Equal
+            })
+        }
+        None => {
+            let x = ctx.to_string(x)?;
+            let y = ctx.to_string(y)?;
+            Ok(string::to_js_string(&x, ctx)?.cmp(&string::to_js_string(&y, ctx)?))
+        }
+    })?;
+
+    let count = sorted.len();
+    for (k, element) in sorted.into_iter().enumerate() {
+        set_or_throw(&object, &k.to_string(), element, ctx)?;
+    }
+    for k in count..count + undefined_count {
+        ctx.check_limits(k)?;
+        set_or_throw(&object, &k.to_string(), Value::undefined(), ctx)?;
+    }
+    for k in count + undefined_count..len {
+        ctx.check_limits(k)?;
+        delete_or_throw(&object, &k.to_string(), ctx)?;
+    }
+    Ok(object)
+}
+
+/// Copies the elements of `source` into `target` starting at `start`, flattening nested arrays
+/// up to `depth` levels and mapping the top level elements with `mapper` if there is one.
+///
+/// Returns the index after the last element written.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-flattenintoarray
+fn flatten_into_array(
+    target: &Value,
+    source: &Value,
+    start: usize,
+    depth: f64,
+    mapper: Option<(&Value, &mut Value)>,
+    ctx: &mut Interpreter,
+) -> Result<usize, Value> {
+    let source_len = length_of_array_like(source, ctx)?;
+    let mut target_index = start;
+    let mut mapper = mapper;
+    for source_index in 0..source_len {
+        ctx.check_limits(source_index)?;
+        let key = source_index.to_string();
+        if !source.has_field(&key) {
+            continue;
+        }
+        let mut element = source.get_field_slice(&key);
+        if let Some((ref mapper_fn, ref mut this_arg)) = mapper {
+            element = ctx.call(
+                mapper_fn,
+                this_arg,
+                &[element, Value::from(source_index), source.clone()],
+            )?;
+        }
+
+        if depth > 0.0 && is_array_object(&element) {
+            target_index =
+                flatten_into_array(target, &element, target_index, depth - 1.0, None, ctx)?;
+        } else {
+            if target_index as f64 >= MAX_SAFE_INTEGER {
+                return Err(ctx.construct_type_error("Invalid array length"));
+            }
+            set_or_throw(target, &target_index.to_string(), element, ctx)?;
+            target_index += 1;
+        }
+    }
+    Ok(target_index)
+}
+
+/// `Array.prototype.flat( [ depth ] )`
+///
+/// This method creates a new array with all sub-array elements concatenated into it
+/// recursively up to the specified depth, which defaults to `1`.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-array.prototype.flat
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/flat
+pub fn flat(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let object = ctx.to_object(this)?;
+    let depth = match args.get(0) {
+        Some(depth) if !depth.is_undefined() => depth.to_integer_or_infinity().max(0.0),
+        _ => 1.0,
+    };
+    let array = array_species_create(&object, 0, ctx)?;
+    let len = flatten_into_array(&array, &object, 0, depth, None, ctx)?;
+    set_or_throw(&array, "length", Value::from(len), ctx)?;
+    Ok(array)
+}
+
+/// `Array.prototype.flatMap( mapperFunction [ , thisArg ] )`
+///
+/// This method maps each element using a mapping function, then flattens the result into a new
+/// array, one level deep.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-array.prototype.flatmap
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/flatMap
+pub fn flat_map(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let object = ctx.to_object(this)?;
+    let mapper = callback_arg(args, ctx)?;
+    let mut this_arg = args.get(1).cloned().unwrap_or_else(Value::undefined);
+    let array = array_species_create(&object, 0, ctx)?;
+    let len = flatten_into_array(&array, &object, 0, 1.0, Some((&mapper, &mut this_arg)), ctx)?;
+    set_or_throw(&array, "length", Value::from(len), ctx)?;
+    Ok(array)
+}
+
+/// `Array.prototype.copyWithin( target, start [ , end ] )`
+///
+/// This method shallow copies part of an array to another location in the same array and
+/// returns it without modifying its length.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-array.prototype.copywithin
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/copyWithin
+pub fn copy_within(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let object = ctx.to_object(this)?;
+    let len = length_of_array_like(&object, ctx)?;
+    let to = relative_index(args.get(0), len, 0);
+    let from = relative_index(args.get(1), len, 0);
+    let fin = relative_index(args.get(2), len, len);
+    let count = fin.saturating_sub(from).min(len - to);
+
+    // Copy backwards when the ranges overlap and the target is after the source
+    let offsets: Box<dyn Iterator<Item = usize>> = if from < to && to < from + count {
+        Box::new((0..count).rev())
+    } else {
+        Box::new(0..count)
+    };
+    for offset in offsets {
+        ctx.check_limits(offset)?;
+        let from_key = (from + offset).to_string();
+        let to_key = (to + offset).to_string();
+        if object.has_field(&from_key) {
+            set_or_throw(&object, &to_key, object.get_field_slice(&from_key), ctx)?;
+        } else {
+            delete_or_throw(&object, &to_key, ctx)?;
+        }
+    }
+    Ok(object)
+}
+
+/// `Array.prototype.at( index )`
+///
+/// This method returns the element at the given index, negative integers count back from the
+/// last element.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-array.prototype.at
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/at
+pub fn at(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let object = ctx.to_object(this)?;
+    let len = length_of_array_like(&object, ctx)? as f64;
+    let relative = args
+        .get(0)
+        .unwrap_or(&Value::undefined())
+        .to_integer_or_infinity();
+    let index = if relative >= 0.0 {
+        relative
+    } else {
+        len + relative
+    };
+    if index < 0.0 || index >= len {
+        return Ok(Value::undefined());
+    }
+    Ok(object.get_field_slice(&(index as usize).to_string()))
+}
+
+/// `Array.prototype.keys( )`
+///
+/// This method returns a new array iterator that contains the keys for each index in the array.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-array.prototype.keys
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/keys
+pub fn keys(this: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let object = ctx.to_object(this)?;
+    Ok(create_array_iterator(object, ArrayIterationKind::Key, ctx))
+}
+
+/// `Array.prototype.values( )`
+///
+/// This method returns a new array iterator that contains the values for each index in the
+/// array. It is also the `Array.prototype[Symbol.iterator]` method.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-array.prototype.values
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/values
+pub fn values(this: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let object = ctx.to_object(this)?;
+    Ok(create_array_iterator(
+        object,
+        ArrayIterationKind::Value,
+        ctx,
+    ))
+}
+
+/// `Array.prototype.entries( )`
+///
+/// This method returns a new array iterator that contains the `[index, value]` pairs for each
+/// index in the array.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-array.prototype.entries
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array/entries
+pub fn entries(this: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let object = ctx.to_object(this)?;
+    Ok(create_array_iterator(
+        object,
+        ArrayIterationKind::KeyAndValue,
+        ctx,
+    ))
+}
+
+/// Stable merge sort driven by a fallible comparator.
+///
+/// User supplied comparators can be inconsistent or throw, so unlike `slice::sort_by` this never
+/// panics on a bogus ordering and stops at the first error.
+pub(crate) fn merge_sort<T, F>(mut items: Vec<T>, mut compare: F) -> Result<Vec<T>, Value>
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Result<Ordering, Value>,
+{
+    let len = items.len();
+    let mut buffer = items.clone();
+    let mut width = 1;
+    while width < len {
+        let mut start = 0;
+        while start < len {
+            let mid = min(start + width, len);
+            let end = min(start + 2 * width, len);
+            let (mut left, mut right, mut out) = (start, mid, start);
+            while left < mid && right < end {
+                if compare(&items[right], &items[left])? == Ordering::Less {
+                    buffer[out] = items[right].clone();
+                    right += 1;
+                } else {
+                    buffer[out] = items[left].clone();
+                    left += 1;
+                }
+                out += 1;
+            }
+            buffer[out..out + mid - left].clone_from_slice(&items[left..mid]);
+            out += mid - left;
+            buffer[out..out + end - right].clone_from_slice(&items[right..end]);
+            start = end;
+        }
+        std::mem::swap(&mut items, &mut buffer);
+        width *= 2;
+    }
+    Ok(items)
+}
+
 /// Create a new `Array` object.
 pub fn create(global: &Value) -> Value {
     // Create prototype
-    let prototype = Value::new_object(None);
+    let prototype = Value::new_object(Some(global));
     let length = Property::default().value(Value::from(0));
 
     prototype.set_property_slice("length", length);
@@ -976,11 +1671,33 @@ pub fn create(global: &Value) -> Value {
     make_builtin_fn!(find_index, named "findIndex", with length 1, of prototype);
     make_builtin_fn!(slice, named "slice", with length 2, of prototype);
     make_builtin_fn!(some, named "some", with length 2, of prototype);
-
-    let array = make_constructor_fn!(make_array, make_array, global, prototype);
+    make_builtin_fn!(reduce, named "reduce", with length 1, of prototype);
+    make_builtin_fn!(reduce_right, named "reduceRight", with length 1, of prototype);
+    make_builtin_fn!(sort, named "sort", with length 1, of prototype);
+    make_builtin_fn!(splice, named "splice", with length 2, of prototype);
+    make_builtin_fn!(flat, named "flat", of prototype);
+    make_builtin_fn!(flat_map, named "flatMap", with length 1, of prototype);
+    make_builtin_fn!(copy_within, named "copyWithin", with length 2, of prototype);
+    make_builtin_fn!(at, named "at", with length 1, of prototype);
+    make_builtin_fn!(find_last, named "findLast", with length 1, of prototype);
+    make_builtin_fn!(find_last_index, named "findLastIndex", with length 1, of prototype);
+    make_builtin_fn!(keys, named "keys", of prototype);
+    make_builtin_fn!(entries, named "entries", of prototype);
+    let values = make_builtin_fn!(values, named "values", with length 0);
+    prototype.set_field_slice("values", values.clone());
+    prototype.set_field(Value::symbol(WellKnownSymbol::Iterator.into()), values);
+
+    let array = make_constructor_fn!(make_array, call_array, global, prototype);
 
     // Static Methods
     make_builtin_fn!(is_array, named "isArray", with length 1, of array);
+    make_builtin_fn!(from, named "from", with length 1, of array);
+    make_builtin_fn!(of, named "of", of array);
+    // Property getters aren't invoked yet, so `Array[Symbol.species]` holds `Array` itself
+    array.set_field(
+        Value::symbol(WellKnownSymbol::Species.into()),
+        array.clone(),
+    );
 
     array
 }
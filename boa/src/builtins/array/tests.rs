@@ -85,6 +85,38 @@ fn join() {
     // Many
     let many = forward(&mut engine, "many.join('.')");
     assert_eq!(many, String::from("a.b.c"));
+    // Null and undefined are joined as empty strings
+    assert_eq!(
+        forward(&mut engine, "[null, undefined, 1].join('-')"),
+        "--1"
+    );
+    assert_eq!(forward(&mut engine, "[1, , 2].join()"), "1,,2");
+    // Elements and the separator are converted with ToString
+    assert_eq!(forward(&mut engine, "[[1, 2]].join()"), "1,2");
+    assert_eq!(
+        forward(&mut engine, "[[1, [2, 3]], 4].join(';')"),
+        "1,2,3;4"
+    );
+    assert_eq!(forward(&mut engine, "[1, 2].join(0)"), "102");
+    assert_eq!(
+        forward(
+            &mut engine,
+            "[{ toString: function () { return 'x'; } }, true].join()"
+        ),
+        "x,true"
+    );
+    // Lone surrogates are kept
+    assert_eq!(
+        forward(
+            &mut engine,
+            "['\\uD800', '\\uDC00'].join('') === '\\uD800\\uDC00'"
+        ),
+        "true"
+    );
+    assert_eq!(
+        forward(&mut engine, "['a', 'b'].join('\\uDC00').charCodeAt(1)"),
+        "56320"
+    );
 }
 
 #[test]
@@ -497,10 +529,7 @@ fn fill() {
         String::from("4,4,4")
     );
 
-    assert_eq!(
-        forward(&mut engine, "a.fill().join()"),
-        String::from("undefined,undefined,undefined")
-    );
+    assert_eq!(forward(&mut engine, "a.fill().join()"), String::from(",,"));
 
     // test object reference
     forward(&mut engine, "a = (new Array(3)).fill({});");
@@ -813,3 +842,276 @@ fn call_array_constructor_with_one_argument() {
     let result = forward(&mut engine, "one.length");
     assert_eq!(result, "1");
 }
+
+#[test]
+fn reduce() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var arr = [1, 2, 3, 4];
+        function add(acc, x) {
+            return acc + x;
+        }
+        function concat(acc, x) {
+            return acc + '' + x;
+        }
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "arr.reduce(add)"), "10");
+    assert_eq!(forward(&mut engine, "arr.reduce(add, 5)"), "15");
+    assert_eq!(forward(&mut engine, "arr.reduce(concat)"), "1234");
+    assert_eq!(forward(&mut engine, "arr.reduceRight(concat)"), "4321");
+    assert_eq!(forward(&mut engine, "[].reduce(add, 1)"), "1");
+    assert_eq!(
+        forward(&mut engine, "[].reduce(add)"),
+        "Error: TypeError: Reduce of empty array with no initial value"
+    );
+    assert_eq!(
+        forward(&mut engine, "[].reduceRight(add)"),
+        "Error: TypeError: Reduce of empty array with no initial value"
+    );
+}
+
+#[test]
+fn sort() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var numbers = [10, 9, 1, undefined, 2];
+        numbers.sort();
+        var people = [
+            { name: 'a', age: 30 },
+            { name: 'b', age: 20 },
+            { name: 'c', age: 30 },
+            { name: 'd', age: 20 }
+        ];
+        people.sort(function(x, y) { return x.age - y.age; });
+        "#;
+    forward(&mut engine, init);
+    // Elements are compared as strings by default, undefined goes last
+    assert_eq!(forward(&mut engine, "numbers.join()"), "1,10,2,9,");
+    // The sort is stable
+    assert_eq!(
+        forward(
+            &mut engine,
+            "people.map(function(p) { return p.name; }).join()"
+        ),
+        "b,d,a,c"
+    );
+    assert_eq!(
+        forward(
+            &mut engine,
+            "[3, 1, 2].sort(function(a, b) { return b - a; }).join()"
+        ),
+        "3,2,1"
+    );
+    assert_eq!(
+        forward(&mut engine, "[1].sort(1)"),
+        "Error: TypeError: The comparison function must be a function"
+    );
+}
+
+#[test]
+fn splice() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var arr = [1, 2, 3, 4, 5];
+        var removed = arr.splice(1, 2, 'a', 'b', 'c');
+        var tail = [1, 2, 3];
+        var removedTail = tail.splice(-1);
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "removed.join()"), "2,3");
+    assert_eq!(forward(&mut engine, "arr.join()"), "1,a,b,c,4,5");
+    assert_eq!(forward(&mut engine, "arr.length"), "6");
+    assert_eq!(forward(&mut engine, "removedTail.join()"), "3");
+    assert_eq!(forward(&mut engine, "tail.length"), "2");
+    assert_eq!(forward(&mut engine, "arr.splice(0, 5).length"), "5");
+    assert_eq!(forward(&mut engine, "arr.join()"), "5");
+}
+
+#[test]
+fn flat_and_flat_map() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var nested = [1, [2, [3, [4]]]];
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "nested.flat().length"), "3");
+    assert_eq!(forward(&mut engine, "nested.flat(2).length"), "4");
+    assert_eq!(forward(&mut engine, "nested.flat(1000).join()"), "1,2,3,4");
+    assert_eq!(
+        forward(
+            &mut engine,
+            "[1, 2].flatMap(function(x) { return [x, [x * 2]]; }).length"
+        ),
+        "4"
+    );
+    assert_eq!(
+        forward(
+            &mut engine,
+            "[1, 2].flatMap(function(x) { return [x, x * 2]; }).join()"
+        ),
+        "1,2,2,4"
+    );
+}
+
+#[test]
+fn copy_within() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    assert_eq!(
+        forward(&mut engine, "[1, 2, 3, 4, 5].copyWithin(0, 3).join()"),
+        "4,5,3,4,5"
+    );
+    assert_eq!(
+        forward(&mut engine, "[1, 2, 3, 4, 5].copyWithin(1, 0, 3).join()"),
+        "1,1,2,3,5"
+    );
+    assert_eq!(
+        forward(&mut engine, "[1, 2, 3, 4, 5].copyWithin(-2, -3, -1).join()"),
+        "1,2,3,3,4"
+    );
+}
+
+#[test]
+fn iterators() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var arr = ['a', 'b'];
+        var it = arr.values();
+        var first = it.next();
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "first.value"), "a");
+    assert_eq!(forward(&mut engine, "first.done"), "false");
+    assert_eq!(forward(&mut engine, "it.next().value"), "b");
+    assert_eq!(forward(&mut engine, "it.next().done"), "true");
+    assert_eq!(forward(&mut engine, "[...arr.keys()].join()"), "0,1");
+    assert_eq!(forward(&mut engine, "[...arr.entries()][1].join()"), "1,b");
+    assert_eq!(forward(&mut engine, "[...arr].join()"), "a,b");
+    assert_eq!(
+        forward(&mut engine, "arr[Symbol.iterator] === arr.values"),
+        "true"
+    );
+    assert_eq!(forward(&mut engine, "it[Symbol.iterator]() === it"), "true");
+}
+
+#[test]
+fn at_and_find_last() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var arr = [1, 2, 3, 4];
+        function isEven(x) {
+            return x % 2 === 0;
+        }
+        function isBig(x) {
+            return x > 10;
+        }
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "arr.at(0)"), "1");
+    assert_eq!(forward(&mut engine, "arr.at(-1)"), "4");
+    assert_eq!(forward(&mut engine, "arr.at(4)"), "undefined");
+    assert_eq!(forward(&mut engine, "arr.findLast(isEven)"), "4");
+    assert_eq!(forward(&mut engine, "arr.findLastIndex(isEven)"), "3");
+    assert_eq!(forward(&mut engine, "arr.findLast(isBig)"), "undefined");
+    assert_eq!(forward(&mut engine, "arr.findLastIndex(isBig)"), "-1");
+}
+
+#[test]
+fn array_from_and_of() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var arrayLike = { length: 2, 0: 'a', 1: 'b' };
+        function double(x) {
+            return x * 2;
+        }
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "Array.from(arrayLike).join()"), "a,b");
+    assert_eq!(
+        forward(&mut engine, "Array.from([1, 2], double).join()"),
+        "2,4"
+    );
+    assert_eq!(forward(&mut engine, "Array.from('ab').join()"), "a,b");
+    assert_eq!(
+        forward(&mut engine, "Array.from([1, 2].keys()).join()"),
+        "0,1"
+    );
+    assert_eq!(
+        forward(&mut engine, "Array.isArray(Array.from({}))"),
+        "true"
+    );
+    assert_eq!(
+        forward(&mut engine, "Array.from([], 1)"),
+        "Error: TypeError: 1 is not a function"
+    );
+    assert_eq!(forward(&mut engine, "Array.of(7).length"), "1");
+    assert_eq!(forward(&mut engine, "Array.of(1, 2, 3).join()"), "1,2,3");
+    assert_eq!(
+        forward(&mut engine, "new Array(-1)"),
+        "Error: RangeError: Invalid array length"
+    );
+}
+
+#[test]
+fn generic_over_array_likes() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var obj = {
+            length: 0,
+            push: Array.prototype.push,
+            join: Array.prototype.join,
+            map: Array.prototype.map,
+            indexOf: Array.prototype.indexOf
+        };
+        obj.push('a', 'b');
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "obj.length"), "2");
+    assert_eq!(forward(&mut engine, "obj[1]"), "b");
+    assert_eq!(forward(&mut engine, "obj.join('-')"), "a-b");
+    assert_eq!(forward(&mut engine, "obj.indexOf('b')"), "1");
+    assert_eq!(
+        forward(
+            &mut engine,
+            "Array.isArray(obj.map(function(x) { return x; }))"
+        ),
+        "true"
+    );
+}
+
+#[test]
+fn species() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var arr = [1, 2, 3];
+        var made = 0;
+        function Custom(length) {
+            made = made + 1;
+        }
+        arr.constructor = {};
+        arr.constructor[Symbol.species] = Custom;
+        var result = arr.map(function(x) { return x * 2; });
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(
+        forward(&mut engine, "Array[Symbol.species] === Array"),
+        "true"
+    );
+    assert_eq!(forward(&mut engine, "made"), "1");
+    assert_eq!(forward(&mut engine, "Array.isArray(result)"), "false");
+    assert_eq!(forward(&mut engine, "result[2]"), "6");
+    assert_eq!(
+        forward(&mut engine, "[1, 2].concat([3], 4).join()"),
+        "1,2,3,4"
+    );
+}
@@ -0,0 +1,431 @@
+//! This module implements the global `ArrayBuffer` object.
+//!
+//! The `ArrayBuffer` object is used to represent a generic, fixed-length (or resizable) raw binary
+//! data buffer. Its contents cannot be manipulated directly, instead one of the typed array
+//! objects or a `DataView` has to be created to read and write the bytes.
+//!
+//! The bytes are held in a [`DataBlock`](struct.DataBlock.html), which is shared between the buffer
+//! and every view over it, so Rust code can read and write them without copying.
+//!
+//! More information:
+//!  - [ECMAScript reference][spec]
+//!  - [MDN documentation][mdn]
+//!
+//! [spec]: https://tc39.es/ecma262/#sec-arraybuffer-objects
+//! [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/ArrayBuffer
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    builtins::{
+        data_view,
+        object::{InternalState, Object, ObjectInternalMethods, ObjectKind, PROTOTYPE},
+        symbol::WellKnownSymbol,
+        typed_array,
+        value::{ResultValue, Value, ValueData},
+    },
+    exec::Interpreter,
+    heap::Charge,
+};
+use std::{
+    cell::{Ref, RefCell, RefMut},
+    rc::Rc,
+};
+
+/// The largest index `ToIndex` accepts, `2^53 - 1`.
+const MAX_INDEX: f64 = 9_007_199_254_740_991.0;
+
+/// The largest data block the engine allocates, 4 GiB, larger ones fail with a `RangeError`.
+const MAX_BYTE_LENGTH: usize = 1 << 32;
+
+/// The bytes of a data block, charged to the interpreter which created them.
+#[derive(Debug, Default)]
+struct Bytes {
+    bytes: Vec<u8>,
+    charge: Charge,
+}
+
+/// A shared, growable block of bytes backing an `ArrayBuffer`.
+///
+/// Cloning a `DataBlock` does not copy the bytes, it creates another handle to the same block.
+#[derive(Debug, Clone, Default)]
+pub struct DataBlock(Rc<RefCell<Bytes>>);
+
+impl DataBlock {
+    /// Creates a new zero filled data block of `len` bytes.
+    pub fn new(len: usize) -> Self {
+        Self::from(vec![0; len])
+    }
+
+    /// Returns the current length of the data block in bytes.
+    pub fn len(&self) -> usize {
+        self.0.borrow().bytes.len()
+    }
+
+    /// Returns `true` if the data block contains no bytes.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Immutably borrows the bytes of the data block.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the block is currently mutably borrowed.
+    pub fn borrow(&self) -> Ref<'_, [u8]> {
+        Ref::map(self.0.borrow(), |block| block.bytes.as_slice())
+    }
+
+    /// Mutably borrows the bytes of the data block.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the block is currently borrowed.
+    pub fn borrow_mut(&self) -> RefMut<'_, [u8]> {
+        RefMut::map(self.0.borrow_mut(), |block| block.bytes.as_mut_slice())
+    }
+
+    /// Returns the address of the shared block, which identifies it among the handles to it.
+    pub(crate) fn as_ptr(&self) -> *const () {
+        Rc::as_ptr(&self.0).cast()
+    }
+
+    /// Resizes the data block in place, zero filling any new bytes, the memory to grow it has to
+    /// be reserved on the heap first.
+    fn resize(&self, new_len: usize, ctx: &mut Interpreter) -> Result<(), Value> {
+        let old_len = self.len();
+        if new_len > MAX_BYTE_LENGTH {
+            return Err(ctx.construct_range_error("Array buffer allocation failed"));
+        }
+        if new_len > old_len {
+            ctx.reserve_heap(new_len - old_len)?;
+        }
+        let mut block = self.0.borrow_mut();
+        if block
+            .bytes
+            .try_reserve_exact(new_len - old_len.min(new_len))
+            .is_err()
+        {
+            return Err(ctx.construct_range_error("Array buffer allocation failed"));
+        }
+        block.bytes.resize(new_len, 0);
+        block.charge.resize(new_len);
+        Ok(())
+    }
+}
+
+impl From<Vec<u8>> for DataBlock {
+    /// Creates a data block holding `bytes`, which are charged to the running interpreter.
+    fn from(bytes: Vec<u8>) -> Self {
+        let charge = Charge::new(bytes.len());
+        Self(Rc::new(RefCell::new(Bytes { bytes, charge })))
+    }
+}
+
+/// The abstract operation `CreateByteDataBlock`, which allocates `len` zeroed bytes after
+/// reserving them on the heap of the interpreter.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-createbytedatablock
+pub(crate) fn create_byte_data_block(len: usize, ctx: &mut Interpreter) -> Result<Vec<u8>, Value> {
+    if len > MAX_BYTE_LENGTH {
+        return Err(ctx.construct_range_error("Array buffer allocation failed"));
+    }
+    ctx.reserve_heap(len)?;
+    // Zeroed memory is allocated at once, without writing every byte
+    Ok(vec![0; len])
+}
+
+/// The internal representation of an `ArrayBuffer` object.
+#[derive(Debug)]
+pub(crate) struct ArrayBuffer {
+    /// The `[[ArrayBufferData]]` internal slot.
+    pub(crate) data: DataBlock,
+
+    /// The `[[ArrayBufferMaxByteLength]]` internal slot, only set for resizable buffers.
+    pub(crate) max_byte_length: Option<usize>,
+}
+
+impl InternalState for ArrayBuffer {}
+
+/// Runs `f` on the `ArrayBuffer` internal state of `value`, if it has one.
+fn with_array_buffer<R, F>(value: &Value, f: F) -> Option<R>
+where
+    F: FnOnce(&ArrayBuffer) -> R,
+{
+    match value.data() {
+        ValueData::Object(ref obj) => obj
+            .borrow()
+            .state
+            .as_ref()
+            .and_then(|state| state.downcast_ref::<ArrayBuffer>())
+            .map(f),
+        _ => None,
+    }
+}
+
+/// Returns the data block of an `ArrayBuffer` value.
+///
+/// Returns `None` if the value is not an `ArrayBuffer`.
+pub fn data_block(value: &Value) -> Option<DataBlock> {
+    with_array_buffer(value, |buffer| buffer.data.clone())
+}
+
+/// Gives `f` read access to the bytes of an `ArrayBuffer`, typed array or `DataView`, without
+/// copying them.
+///
+/// For views only the bytes that are visible through the view are passed. Returns `None` if the
+/// value is none of those objects.
+pub fn with_bytes<R, F>(value: &Value, f: F) -> Option<R>
+where
+    F: FnOnce(&[u8]) -> R,
+{
+    let (data, offset, len) = viewed_bytes(value)?;
+    let bytes = data.borrow();
+    Some(f(&bytes[offset..offset + len]))
+}
+
+/// Gives `f` write access to the bytes of an `ArrayBuffer`, typed array or `DataView`, without
+/// copying them.
+///
+/// For views only the bytes that are visible through the view are passed. Returns `None` if the
+/// value is none of those objects.
+pub fn with_bytes_mut<R, F>(value: &Value, f: F) -> Option<R>
+where
+    F: FnOnce(&mut [u8]) -> R,
+{
+    let (data, offset, len) = viewed_bytes(value)?;
+    let mut bytes = data.borrow_mut();
+    Some(f(&mut bytes[offset..offset + len]))
+}
+
+/// Returns the data block, byte offset and byte length visible through `value`.
+fn viewed_bytes(value: &Value) -> Option<(DataBlock, usize, usize)> {
+    let kind = match value.data() {
+        ValueData::Object(ref obj) => obj.borrow().kind,
+        _ => return None,
+    };
+    match kind {
+        ObjectKind::ArrayBuffer => data_block(value).map(|data| {
+            let len = data.len();
+            (data, 0, len)
+        }),
+        ObjectKind::TypedArray => typed_array::viewed_bytes(value),
+        ObjectKind::DataView => data_view::viewed_bytes(value),
+        _ => None,
+    }
+}
+
+/// Creates a new `ArrayBuffer` object that takes ownership of `bytes`.
+///
+/// The prototype of the buffer is taken from the `ArrayBuffer` constructor of `global`.
+pub fn new_array_buffer(global: &Value, bytes: Vec<u8>) -> Value {
+    let proto = global
+        .get_field_slice("ArrayBuffer")
+        .get_field_slice(PROTOTYPE);
+    let buffer = Value::new_object_from_prototype(proto, ObjectKind::ArrayBuffer);
+    buffer.set_internal_state(ArrayBuffer {
+        data: DataBlock::from(bytes),
+        max_byte_length: None,
+    });
+    buffer
+}
+
+/// The abstract operation `ToIndex`.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-toindex
+pub(crate) fn to_index(value: &Value, ctx: &mut Interpreter) -> Result<usize, Value> {
+    if value.is_undefined() {
+        return Ok(0);
+    }
+    let integer = value.to_integer_or_infinity();
+    if integer < 0.0 || integer > MAX_INDEX {
+        return Err(ctx.construct_range_error("Invalid index"));
+    }
+    Ok(integer as usize)
+}
+
+/// Resolves a relative index argument, as used by `slice` and friends, against `len`.
+///
+/// Negative values count from the end, and `undefined` resolves to `default`.
+pub(crate) fn relative_index(value: Option<&Value>, len: usize, default: usize) -> usize {
+    match value {
+        None => default,
+        Some(value) if value.is_undefined() => default,
+        Some(value) => {
+            let relative = value.to_integer_or_infinity();
+            if relative < 0.0 {
+                (len as f64 + relative).max(0.0) as usize
+            } else {
+                relative.min(len as f64) as usize
+            }
+        }
+    }
+}
+
+/// Create a new `ArrayBuffer` object.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-arraybuffer-length
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/ArrayBuffer/ArrayBuffer
+pub fn make_array_buffer(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let byte_length = to_index(args.get(0).unwrap_or(&Value::undefined()), ctx)?;
+
+    let max_byte_length = match args.get(1) {
+        Some(options) if options.is_object() => {
+            let max = options.get_field_slice("maxByteLength");
+            if max.is_undefined() {
+                None
+            } else {
+                Some(to_index(&max, ctx)?)
+            }
+        }
+        _ => None,
+    };
+    if let Some(max) = max_byte_length {
+        if byte_length > max {
+            return ctx.throw_range_error("byteLength exceeds maxByteLength");
+        }
+    }
+
+    this.set_kind(ObjectKind::ArrayBuffer);
+    this.set_internal_state(ArrayBuffer {
+        data: DataBlock::from(create_byte_data_block(byte_length, ctx)?),
+        max_byte_length,
+    });
+    Ok(this.clone())
+}
+
+/// `ArrayBuffer()` called as a function.
+pub fn call_array_buffer(_: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    ctx.throw_type_error("Constructor ArrayBuffer requires 'new'")
+}
+
+/// `ArrayBuffer.isView( arg )`
+///
+/// The `isView()` method determines whether the passed value is one of the `ArrayBuffer` views,
+/// such as typed array objects or a `DataView`.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-arraybuffer.isview
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/ArrayBuffer/isView
+pub fn is_view(_: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValue {
+    let is_view = match args.get(0).map(Value::data) {
+        Some(ValueData::Object(ref obj)) => match obj.borrow().kind {
+            ObjectKind::TypedArray | ObjectKind::DataView => true,
+            _ => false,
+        },
+        _ => false,
+    };
+    Ok(Value::from(is_view))
+}
+
+/// `ArrayBuffer.prototype.slice( begin[, end] )`
+///
+/// The `slice()` method returns a new `ArrayBuffer` whose contents are a copy of this
+/// `ArrayBuffer`'s bytes from `begin`, inclusive, up to `end`, exclusive.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-arraybuffer.prototype.slice
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/ArrayBuffer/slice
+pub fn slice(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let data = match data_block(this) {
+        Some(data) => data,
+        None => {
+            return ctx
+                .throw_type_error("ArrayBuffer.prototype.slice called on incompatible receiver")
+        }
+    };
+    let len = data.len();
+    let first = relative_index(args.get(0), len, 0);
+    let fin = relative_index(args.get(1), len, len);
+    let mut bytes = create_byte_data_block(fin.saturating_sub(first), ctx)?;
+    if first < fin {
+        bytes.copy_from_slice(&data.borrow()[first..fin]);
+    }
+    Ok(new_array_buffer(&ctx.realm.intrinsics, bytes))
+}
+
+/// `ArrayBuffer.prototype.resize( newLength )`
+///
+/// The `resize()` method resizes the `ArrayBuffer` to the specified size, in bytes. Only buffers
+/// created with a `maxByteLength` option can be resized.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-arraybuffer.prototype.resize
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/ArrayBuffer/resize
+pub fn resize(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let (data, max_byte_length) = match with_array_buffer(this, |buffer| {
+        (buffer.data.clone(), buffer.max_byte_length)
+    }) {
+        Some((data, Some(max))) => (data, max),
+        _ => {
+            return ctx
+                .throw_type_error("ArrayBuffer.prototype.resize called on a non-resizable buffer")
+        }
+    };
+    let new_byte_length = to_index(args.get(0).unwrap_or(&Value::undefined()), ctx)?;
+    if new_byte_length > max_byte_length {
+        return ctx.throw_range_error("new byteLength exceeds maxByteLength");
+    }
+    data.resize(new_byte_length, ctx)?;
+    Ok(Value::undefined())
+}
+
+/// Returns the value of the accessor properties of an `ArrayBuffer`, which are computed from its
+/// internal state.
+pub(crate) fn get_own_property(object: &Object, field: &str) -> Option<Value> {
+    let buffer = object.state.as_ref()?.downcast_ref::<ArrayBuffer>()?;
+    match field {
+        "byteLength" => Some(Value::from(buffer.data.len())),
+        "maxByteLength" => Some(Value::from(
+            buffer.max_byte_length.unwrap_or_else(|| buffer.data.len()),
+        )),
+        "resizable" => Some(Value::from(buffer.max_byte_length.is_some())),
+        _ => None,
+    }
+}
+
+/// Create a new `ArrayBuffer` object.
+pub fn create(global: &Value) -> Value {
+    let prototype = Value::new_object(Some(global));
+
+    make_builtin_fn!(slice, named "slice", with length 2, of prototype);
+    make_builtin_fn!(resize, named "resize", with length 1, of prototype);
+    prototype.set_field(
+        Value::symbol(WellKnownSymbol::ToStringTag.into()),
+        Value::from("ArrayBuffer"),
+    );
+
+    let array_buffer =
+        make_constructor_fn!(make_array_buffer, call_array_buffer, global, prototype);
+    array_buffer.set_field_slice("length", Value::from(1));
+
+    make_builtin_fn!(is_view, named "isView", with length 1, of array_buffer);
+
+    array_buffer
+}
+
+/// Initialise the `ArrayBuffer` object on the global object.
+#[inline]
+pub fn init(global: &Value) {
+    global.set_field_slice("ArrayBuffer", create(global));
+}
@@ -0,0 +1,157 @@
+use crate::{
+    builtins::array_buffer::{with_bytes, with_bytes_mut},
+    exec::{Executor, Interpreter},
+    forward, forward_val,
+    realm::Realm,
+};
+
+#[test]
+fn construct() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    forward(&mut engine, "var buffer = new ArrayBuffer(8);");
+    assert_eq!(forward(&mut engine, "buffer.byteLength"), "8");
+    assert_eq!(forward(&mut engine, "buffer.resizable"), "false");
+    assert_eq!(
+        forward(&mut engine, "new ArrayBuffer(-1)"),
+        "Error: RangeError: Invalid index"
+    );
+}
+
+#[test]
+fn slice() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var buffer = new ArrayBuffer(8);
+        var bytes = new Uint8Array(buffer);
+        bytes.set([1, 2, 3, 4, 5, 6, 7, 8]);
+        var sliced = new Uint8Array(buffer.slice(2, -2));
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "sliced.length"), "4");
+    assert_eq!(forward(&mut engine, "sliced.join()"), "3,4,5,6");
+    forward(&mut engine, "sliced[0] = 42;");
+    assert_eq!(forward(&mut engine, "bytes[2]"), "3");
+}
+
+#[test]
+fn resize() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var buffer = new ArrayBuffer(4, { maxByteLength: 16 });
+        var tracking = new Uint8Array(buffer);
+        var fixed = new Uint8Array(buffer, 0, 4);
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "buffer.resizable"), "true");
+    assert_eq!(forward(&mut engine, "buffer.maxByteLength"), "16");
+    forward(&mut engine, "buffer.resize(12);");
+    assert_eq!(forward(&mut engine, "buffer.byteLength"), "12");
+    assert_eq!(forward(&mut engine, "tracking.length"), "12");
+    forward(&mut engine, "buffer.resize(2);");
+    assert_eq!(forward(&mut engine, "tracking.length"), "2");
+    assert_eq!(forward(&mut engine, "fixed.length"), "0");
+    assert_eq!(
+        forward(&mut engine, "buffer.resize(17)"),
+        "Error: RangeError: new byteLength exceeds maxByteLength"
+    );
+    assert_eq!(
+        forward(&mut engine, "new ArrayBuffer(4).resize(2)"),
+        "Error: TypeError: ArrayBuffer.prototype.resize called on a non-resizable buffer"
+    );
+}
+
+#[test]
+fn is_view() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    forward(&mut engine, "var buffer = new ArrayBuffer(8);");
+    assert_eq!(forward(&mut engine, "ArrayBuffer.isView(buffer)"), "false");
+    assert_eq!(
+        forward(&mut engine, "ArrayBuffer.isView(new Int16Array(buffer))"),
+        "true"
+    );
+    assert_eq!(
+        forward(&mut engine, "ArrayBuffer.isView(new DataView(buffer))"),
+        "true"
+    );
+    assert_eq!(forward(&mut engine, "ArrayBuffer.isView([])"), "false");
+}
+
+#[test]
+fn zero_copy_access() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let view = forward_val(
+        &mut engine,
+        "var buffer = new ArrayBuffer(4); new Uint8Array(buffer, 1, 2)",
+    )
+    .unwrap();
+    with_bytes_mut(&view, |bytes| bytes.copy_from_slice(&[7, 9])).unwrap();
+    let buffer = forward_val(&mut engine, "buffer").unwrap();
+    assert_eq!(
+        with_bytes(&buffer, |bytes| bytes.to_vec()),
+        Some(vec![0, 7, 9, 0])
+    );
+    assert_eq!(
+        forward(&mut engine, "new Uint8Array(buffer).join()"),
+        "0,7,9,0"
+    );
+}
+
+#[test]
+fn heap_limit() {
+    let realm = Realm::create();
+    let mut engine: Interpreter = Executor::new(realm);
+    engine.set_heap_limit(Some(10 * 1024 * 1024));
+    for scenario in &[
+        "new ArrayBuffer(1e9)",
+        "new Float64Array(2e8)",
+        "new ArrayBuffer(8, { maxByteLength: 1e9 }).resize(1e9)",
+        "new Uint8Array(8e6).slice()",
+    ] {
+        assert_eq!(
+            forward(&mut engine, scenario),
+            "Error: RangeError: Out of memory",
+            "{}",
+            scenario
+        );
+    }
+
+    forward(&mut engine, "var buffer = new ArrayBuffer(1048576);");
+    assert!(engine.heap_stats().used_bytes >= 1_048_576);
+    forward(&mut engine, "buffer = undefined;");
+    gc::force_collect();
+    assert!(engine.heap_stats().used_bytes < 1_048_576);
+}
+
+#[test]
+fn allocation_failure() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    assert_eq!(
+        forward(&mut engine, "new ArrayBuffer(9007199254740991)"),
+        "Error: RangeError: Array buffer allocation failed"
+    );
+    assert_eq!(
+        forward(&mut engine, "new ArrayBuffer(2147483648).byteLength"),
+        "2147483648"
+    );
+    assert_eq!(
+        forward(&mut engine, "new ArrayBuffer(1e10)"),
+        "Error: RangeError: Array buffer allocation failed"
+    );
+    assert_eq!(
+        forward(
+            &mut engine,
+            "new ArrayBuffer(0, { maxByteLength: 1e10 }).resize(1e10)"
+        ),
+        "Error: RangeError: Array buffer allocation failed"
+    );
+    assert_eq!(
+        forward(&mut engine, "new ArrayBuffer(9007199254740992)"),
+        "Error: RangeError: Invalid index"
+    );
+}
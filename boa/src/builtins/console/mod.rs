@@ -29,9 +29,9 @@ use std::time::SystemTime;
 /// This is the internal console object state.
 #[derive(Debug, Default)]
 pub struct ConsoleState {
-    count_map: FxHashMap<String, u32>,
-    timer_map: FxHashMap<String, u128>,
-    groups: Vec<String>,
+    pub(crate) count_map: FxHashMap<String, u32>,
+    pub(crate) timer_map: FxHashMap<String, u128>,
+    pub(crate) groups: Vec<String>,
 }
 
 impl InternalState for ConsoleState {}
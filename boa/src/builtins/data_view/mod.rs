@@ -0,0 +1,263 @@
+//! This module implements the global `DataView` object.
+//!
+//! A `DataView` provides a low-level interface for reading and writing multiple number types in
+//! an `ArrayBuffer`, with explicit control over the byte order.
+//!
+//! More information:
+//!  - [ECMAScript reference][spec]
+//!  - [MDN documentation][mdn]
+//!
+//! [spec]: https://tc39.es/ecma262/#sec-dataview-objects
+//! [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/DataView
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    builtins::{
+        array_buffer::{self, to_index, DataBlock},
+        object::{InternalState, Object, ObjectInternalMethods, ObjectKind, PROTOTYPE},
+        symbol::WellKnownSymbol,
+        typed_array::TypedArrayKind,
+        value::{ResultValue, Value, ValueData},
+    },
+    exec::Interpreter,
+};
+
+/// The internal representation of a `DataView` object.
+#[derive(Debug)]
+pub(crate) struct DataView {
+    /// The data block of the `[[ViewedArrayBuffer]]`.
+    pub(crate) data: DataBlock,
+
+    /// The `[[ByteOffset]]` internal slot.
+    pub(crate) byte_offset: usize,
+
+    /// The `[[ByteLength]]` internal slot, `None` for views that track the length of a
+    /// resizable buffer.
+    pub(crate) byte_length: Option<usize>,
+}
+
+impl InternalState for DataView {}
+
+impl DataView {
+    /// Returns the number of bytes visible through the view, or `None` if the view is out of
+    /// bounds of its (resized) buffer.
+    fn view_byte_length(&self) -> Option<usize> {
+        let buffer_len = self.data.len();
+        match self.byte_length {
+            Some(len) if self.byte_offset + len <= buffer_len => Some(len),
+            None if self.byte_offset <= buffer_len => Some(buffer_len - self.byte_offset),
+            _ => None,
+        }
+    }
+}
+
+/// Runs `f` on the `DataView` internal state of `value`, if it has one.
+fn with_data_view<R, F>(value: &Value, f: F) -> Option<R>
+where
+    F: FnOnce(&DataView) -> R,
+{
+    match value.data() {
+        ValueData::Object(ref obj) => obj
+            .borrow()
+            .state
+            .as_ref()
+            .and_then(|state| state.downcast_ref::<DataView>())
+            .map(f),
+        _ => None,
+    }
+}
+
+/// Returns the data block, byte offset and byte length visible through a `DataView`.
+pub(crate) fn viewed_bytes(value: &Value) -> Option<(DataBlock, usize, usize)> {
+    with_data_view(value, |view| {
+        (
+            view.data.clone(),
+            view.byte_offset,
+            view.view_byte_length().unwrap_or(0),
+        )
+    })
+}
+
+/// Returns the value of the accessor properties of a `DataView`, which are computed from its
+/// internal state.
+pub(crate) fn get_own_property(object: &Object, field: &str) -> Option<Value> {
+    let view = object.state.as_ref()?.downcast_ref::<DataView>()?;
+    match field {
+        "byteLength" => Some(Value::from(view.view_byte_length().unwrap_or(0))),
+        "byteOffset" => Some(Value::from(view.byte_offset)),
+        "buffer" => object.internal_slots.get("ViewedArrayBuffer").cloned(),
+        _ => None,
+    }
+}
+
+/// Create a new `DataView` object.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-dataview-buffer-byteoffset-bytelength
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/DataView/DataView
+pub fn make_data_view(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let buffer = args.get(0).cloned().unwrap_or_else(Value::undefined);
+    let data = match array_buffer::data_block(&buffer) {
+        Some(data) => data,
+        None => {
+            return ctx
+                .throw_type_error("First argument to DataView constructor must be an ArrayBuffer")
+        }
+    };
+    let byte_offset = to_index(args.get(1).unwrap_or(&Value::undefined()), ctx)?;
+    let buffer_len = data.len();
+    if byte_offset > buffer_len {
+        return ctx.throw_range_error(format!(
+            "Start offset {} is outside the bounds of the buffer",
+            byte_offset
+        ));
+    }
+    let byte_length = match args.get(2) {
+        Some(length) if !length.is_undefined() => {
+            let length = to_index(length, ctx)?;
+            if byte_offset + length > buffer_len {
+                return ctx.throw_range_error(format!("Invalid DataView length {}", length));
+            }
+            Some(length)
+        }
+        _ if buffer.get_field_slice("resizable").is_true() => None,
+        _ => Some(buffer_len - byte_offset),
+    };
+
+    this.set_kind(ObjectKind::DataView);
+    this.set_internal_slot("ViewedArrayBuffer", buffer);
+    this.set_internal_state(DataView {
+        data,
+        byte_offset,
+        byte_length,
+    });
+    Ok(this.clone())
+}
+
+/// `DataView()` called as a function.
+pub fn call_data_view(_: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    ctx.throw_type_error("Constructor DataView requires 'new'")
+}
+
+/// Resolves the byte index of an element of `kind` at `request_index` in the view, checking
+/// that it is in bounds.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-getviewvalue
+fn view_index(
+    view: &Value,
+    request_index: &Value,
+    kind: TypedArrayKind,
+    ctx: &mut Interpreter,
+) -> Result<(DataBlock, usize), Value> {
+    let index = to_index(request_index, ctx)?;
+    let (data, offset, len) = match with_data_view(view, |v| {
+        (v.data.clone(), v.byte_offset, v.view_byte_length())
+    }) {
+        Some((data, offset, Some(len))) => (data, offset, len),
+        Some(_) => return Err(ctx.construct_type_error("DataView is out of bounds")),
+        None => return Err(ctx.construct_type_error("this is not a DataView")),
+    };
+    if index + kind.element_size() > len {
+        return Err(ctx.construct_range_error("Offset is outside the bounds of the DataView"));
+    }
+    Ok((data, offset + index))
+}
+
+/// The abstract operation `GetViewValue`.
+fn get_view_value(
+    this: &Value,
+    args: &[Value],
+    kind: TypedArrayKind,
+    ctx: &mut Interpreter,
+) -> ResultValue {
+    let (data, index) = view_index(this, args.get(0).unwrap_or(&Value::undefined()), kind, ctx)?;
+    let little_endian = args.get(1).map_or(false, |v| v.is_true());
+    let value = kind.read(&data.borrow()[index..], little_endian);
+    Ok(Value::from(value))
+}
+
+/// The abstract operation `SetViewValue`.
+fn set_view_value(
+    this: &Value,
+    args: &[Value],
+    kind: TypedArrayKind,
+    ctx: &mut Interpreter,
+) -> ResultValue {
+    let (data, index) = view_index(this, args.get(0).unwrap_or(&Value::undefined()), kind, ctx)?;
+    let value = args.get(1).unwrap_or(&Value::undefined()).to_number();
+    let little_endian = args.get(2).map_or(false, |v| v.is_true());
+    kind.write(&mut data.borrow_mut()[index..], value, little_endian);
+    Ok(Value::undefined())
+}
+
+/// Generates the `DataView.prototype` getter and setter methods for one element type.
+macro_rules! view_accessors {
+    ($get:ident, $set:ident, $kind:expr) => {
+        fn $get(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+            get_view_value(this, args, $kind, ctx)
+        }
+
+        fn $set(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+            set_view_value(this, args, $kind, ctx)
+        }
+    };
+}
+
+view_accessors!(get_int8, set_int8, TypedArrayKind::Int8);
+view_accessors!(get_uint8, set_uint8, TypedArrayKind::Uint8);
+view_accessors!(get_int16, set_int16, TypedArrayKind::Int16);
+view_accessors!(get_uint16, set_uint16, TypedArrayKind::Uint16);
+view_accessors!(get_int32, set_int32, TypedArrayKind::Int32);
+view_accessors!(get_uint32, set_uint32, TypedArrayKind::Uint32);
+view_accessors!(get_float32, set_float32, TypedArrayKind::Float32);
+view_accessors!(get_float64, set_float64, TypedArrayKind::Float64);
+view_accessors!(get_big_int64, set_big_int64, TypedArrayKind::BigInt64);
+view_accessors!(get_big_uint64, set_big_uint64, TypedArrayKind::BigUint64);
+
+/// Create a new `DataView` object.
+pub fn create(global: &Value) -> Value {
+    let prototype = Value::new_object(Some(global));
+
+    make_builtin_fn!(get_int8, named "getInt8", with length 1, of prototype);
+    make_builtin_fn!(set_int8, named "setInt8", with length 2, of prototype);
+    make_builtin_fn!(get_uint8, named "getUint8", with length 1, of prototype);
+    make_builtin_fn!(set_uint8, named "setUint8", with length 2, of prototype);
+    make_builtin_fn!(get_int16, named "getInt16", with length 1, of prototype);
+    make_builtin_fn!(set_int16, named "setInt16", with length 2, of prototype);
+    make_builtin_fn!(get_uint16, named "getUint16", with length 1, of prototype);
+    make_builtin_fn!(set_uint16, named "setUint16", with length 2, of prototype);
+    make_builtin_fn!(get_int32, named "getInt32", with length 1, of prototype);
+    make_builtin_fn!(set_int32, named "setInt32", with length 2, of prototype);
+    make_builtin_fn!(get_uint32, named "getUint32", with length 1, of prototype);
+    make_builtin_fn!(set_uint32, named "setUint32", with length 2, of prototype);
+    make_builtin_fn!(get_float32, named "getFloat32", with length 1, of prototype);
+    make_builtin_fn!(set_float32, named "setFloat32", with length 2, of prototype);
+    make_builtin_fn!(get_float64, named "getFloat64", with length 1, of prototype);
+    make_builtin_fn!(set_float64, named "setFloat64", with length 2, of prototype);
+    make_builtin_fn!(get_big_int64, named "getBigInt64", with length 1, of prototype);
+    make_builtin_fn!(set_big_int64, named "setBigInt64", with length 2, of prototype);
+    make_builtin_fn!(get_big_uint64, named "getBigUint64", with length 1, of prototype);
+    make_builtin_fn!(set_big_uint64, named "setBigUint64", with length 2, of prototype);
+    prototype.set_field(
+        Value::symbol(WellKnownSymbol::ToStringTag.into()),
+        Value::from("DataView"),
+    );
+
+    let data_view = make_constructor_fn!(make_data_view, call_data_view, global, prototype);
+    data_view.set_field_slice("length", Value::from(1));
+    data_view
+}
+
+/// Initialise the `DataView` object on the global object.
+#[inline]
+pub fn init(global: &Value) {
+    global.set_field_slice("DataView", create(global));
+}
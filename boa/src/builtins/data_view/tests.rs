@@ -0,0 +1,65 @@
+use crate::{exec::Executor, forward, realm::Realm};
+
+#[test]
+fn construct() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var buffer = new ArrayBuffer(16);
+        var view = new DataView(buffer, 4, 8);
+        var rest = new DataView(buffer, 12);
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "view.byteOffset"), "4");
+    assert_eq!(forward(&mut engine, "view.byteLength"), "8");
+    assert_eq!(forward(&mut engine, "rest.byteLength"), "4");
+    assert_eq!(forward(&mut engine, "view.buffer === buffer"), "true");
+    assert_eq!(
+        forward(&mut engine, "new DataView(buffer, 17)"),
+        "Error: RangeError: Start offset 17 is outside the bounds of the buffer"
+    );
+    assert_eq!(
+        forward(&mut engine, "new DataView({})"),
+        "Error: TypeError: First argument to DataView constructor must be an ArrayBuffer"
+    );
+}
+
+#[test]
+fn endianness() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var view = new DataView(new ArrayBuffer(8));
+        view.setUint16(0, 0x0102);
+        view.setUint16(2, 0x0102, true);
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "view.getUint8(0)"), "1");
+    assert_eq!(forward(&mut engine, "view.getUint8(1)"), "2");
+    assert_eq!(forward(&mut engine, "view.getUint8(2)"), "2");
+    assert_eq!(forward(&mut engine, "view.getUint8(3)"), "1");
+    assert_eq!(forward(&mut engine, "view.getUint16(0, true)"), "513");
+    forward(&mut engine, "view.setFloat64(0, 1.5);");
+    assert_eq!(forward(&mut engine, "view.getFloat64(0)"), "1.5");
+    forward(&mut engine, "view.setInt32(4, -2, true);");
+    assert_eq!(forward(&mut engine, "view.getInt32(4, true)"), "-2");
+    assert_eq!(
+        forward(&mut engine, "view.getUint32(4, true)"),
+        "4294967294"
+    );
+}
+
+#[test]
+fn out_of_bounds() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    forward(
+        &mut engine,
+        "var view = new DataView(new ArrayBuffer(4), 2);",
+    );
+    assert_eq!(
+        forward(&mut engine, "view.getInt32(0)"),
+        "Error: RangeError: Offset is outside the bounds of the DataView"
+    );
+    assert_eq!(forward(&mut engine, "view.getInt16(0)"), "0");
+}
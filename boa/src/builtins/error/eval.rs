@@ -0,0 +1,50 @@
+//! This module implements the global `EvalError` object.
+//!
+//! Indicates an error regarding the global `eval` function. It is not thrown by the specification
+//! anymore, the engine uses it when the host refuses to compile source text at runtime.
+//!
+//! More information:
+//!  - [MDN documentation][mdn]
+//!  - [ECMAScript reference][spec]
+//!
+//! [spec]: https://tc39.es/ecma262/#sec-native-error-types-used-in-this-standard-evalerror
+//! [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/EvalError
+
+use crate::{
+    builtins::{
+        object::{internal_methods_trait::ObjectInternalMethods, Object, ObjectKind, PROTOTYPE},
+        value::{ResultValue, Value},
+    },
+    exec::Interpreter,
+};
+
+/// Create a new `EvalError` object.
+pub fn make_error(this: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValue {
+    if let Some(message) = args.get(0) {
+        if !message.is_undefined() {
+            this.set_field_slice("message", Value::from(message.to_string()));
+        }
+    }
+    // This value is used by console.log and other routines to match Object type
+    // to its Javascript Identifier (global constructor method name)
+    this.set_kind(ObjectKind::Error);
+    Ok(this.clone())
+}
+
+/// Create a new `EvalError` object.
+pub fn create(global: &Value) -> Value {
+    let prototype = Value::new_object(Some(global));
+    prototype.set_internal_slot(
+        "__proto__",
+        global.get_field_slice("Error").get_field_slice(PROTOTYPE),
+    );
+    prototype.set_field_slice("message", Value::from(""));
+    prototype.set_field_slice("name", Value::from("EvalError"));
+    make_constructor_fn!(make_error, make_error, global, prototype)
+}
+
+/// Initialise the global object with the `EvalError` object.
+#[inline]
+pub fn init(global: &Value) {
+    global.set_field_slice("EvalError", create(global));
+}
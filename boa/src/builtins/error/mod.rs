@@ -18,6 +18,16 @@ use crate::{
     exec::Interpreter,
 };
 
+pub mod eval;
+pub mod range;
+pub mod reference;
+pub mod syntax;
+pub mod r#type;
+pub mod uri;
+
+#[cfg(test)]
+mod tests;
+
 /// Create a new error object.
 pub fn make_error(this: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValue {
     if !args.is_empty() {
@@ -33,7 +43,7 @@ pub fn make_error(this: &mut Value, args: &[Value], _: &mut Interpreter) -> Resu
     // This value is used by console.log and other routines to match Object type
     // to its Javascript Identifier (global constructor method name)
     this.set_kind(ObjectKind::Error);
-    Ok(Value::undefined())
+    Ok(this.clone())
 }
 
 /// `Error.prototype.toString()`
@@ -62,6 +72,7 @@ pub fn create(global: &Value) -> Value {
 }
 
 /// Initialise the global object with the `Error` object.
+#[inline]
 pub fn init(global: &Value) {
     global.set_field_slice("Error", create(global));
 }
@@ -0,0 +1,49 @@
+//! This module implements the global `RangeError` object.
+//!
+//! Indicates a value that is not in the set or range of allowable values.
+//!
+//! More information:
+//!  - [MDN documentation][mdn]
+//!  - [ECMAScript reference][spec]
+//!
+//! [spec]: https://tc39.es/ecma262/#sec-native-error-types-used-in-this-standard-rangeerror
+//! [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/RangeError
+
+use crate::{
+    builtins::{
+        object::{internal_methods_trait::ObjectInternalMethods, Object, ObjectKind, PROTOTYPE},
+        value::{ResultValue, Value},
+    },
+    exec::Interpreter,
+};
+
+/// Create a new `RangeError` object.
+pub fn make_error(this: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValue {
+    if let Some(message) = args.get(0) {
+        if !message.is_undefined() {
+            this.set_field_slice("message", Value::from(message.to_string()));
+        }
+    }
+    // This value is used by console.log and other routines to match Object type
+    // to its Javascript Identifier (global constructor method name)
+    this.set_kind(ObjectKind::Error);
+    Ok(this.clone())
+}
+
+/// Create a new `RangeError` object.
+pub fn create(global: &Value) -> Value {
+    let prototype = Value::new_object(Some(global));
+    prototype.set_internal_slot(
+        "__proto__",
+        global.get_field_slice("Error").get_field_slice(PROTOTYPE),
+    );
+    prototype.set_field_slice("message", Value::from(""));
+    prototype.set_field_slice("name", Value::from("RangeError"));
+    make_constructor_fn!(make_error, make_error, global, prototype)
+}
+
+/// Initialise the global object with the `RangeError` object.
+#[inline]
+pub fn init(global: &Value) {
+    global.set_field_slice("RangeError", create(global));
+}
@@ -0,0 +1,32 @@
+use crate::{exec::Executor, forward, realm::Realm};
+
+#[test]
+fn error_constructors() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var e = new Error('oops');
+        var r = new RangeError('out of range');
+        var t = new TypeError('bad type');
+        var s = new SyntaxError('bad syntax');
+        var u = new URIError('bad uri');
+        var v = new EvalError('bad eval');
+        var f = new ReferenceError('bad reference');
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "e.message"), "oops");
+    assert_eq!(forward(&mut engine, "r.name"), "RangeError");
+    assert_eq!(forward(&mut engine, "r.message"), "out of range");
+    assert_eq!(forward(&mut engine, "t.name"), "TypeError");
+    assert_eq!(forward(&mut engine, "t.toString()"), "TypeError: bad type");
+    assert_eq!(
+        forward(&mut engine, "s.toString()"),
+        "SyntaxError: bad syntax"
+    );
+    assert_eq!(forward(&mut engine, "u.toString()"), "URIError: bad uri");
+    assert_eq!(forward(&mut engine, "v.toString()"), "EvalError: bad eval");
+    assert_eq!(
+        forward(&mut engine, "f.toString()"),
+        "ReferenceError: bad reference"
+    );
+}
@@ -0,0 +1,94 @@
+//! This module implements the global `eval` function.
+//!
+//! The `eval()` function evaluates JavaScript code represented as a string. A call through the
+//! name `eval` is a direct eval, the code sees the bindings of the caller. Any other call is an
+//! indirect eval, the code runs in the global scope.
+//!
+//! More information:
+//!  - [ECMAScript reference][spec]
+//!  - [MDN documentation][mdn]
+//!
+//! [spec]: https://tc39.es/ecma262/#sec-eval-x
+//! [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/eval
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    builtins::value::{ResultValue, Value},
+    exec::Interpreter,
+    syntax::{ast::node::Node, lexer::Lexer, parser::Parser},
+};
+use std::mem;
+
+/// Parses source text given at runtime, once the host allowed its compilation.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-hostensurecancompilestrings
+pub(crate) fn compile(source: &str, ctx: &Interpreter) -> Result<Node, Value> {
+    if !ctx.realm.can_compile_strings(source) {
+        return Err(ctx.construct_eval_error("Code generation from strings is disallowed"));
+    }
+    let mut lexer = Lexer::new(source);
+    lexer
+        .lex()
+        .map_err(|e| ctx.construct_syntax_error(e.to_string()))?;
+    Parser::with_source(&lexer.tokens, source)
+        .parse_all()
+        .map_err(|e| ctx.construct_syntax_error(e.to_string()))
+}
+
+/// Runs `f` in the global scope instead of the scope of the caller, as non-strict code unless
+/// the code has its own `"use strict"` directive.
+pub(crate) fn run_in_global_scope<F>(ctx: &mut Interpreter, f: F) -> ResultValue
+where
+    F: FnOnce(&mut Interpreter) -> ResultValue,
+{
+    let global_scope = ctx.realm.environment.global_scope();
+    let caller_environment = mem::replace(&mut ctx.realm.environment, global_scope);
+    let result = ctx.run_in_mode(false, f);
+    ctx.realm.environment = caller_environment;
+    result
+}
+
+/// The abstract operation `PerformEval`, values other than strings are returned as they are.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-performeval
+pub(crate) fn perform_eval(x: &Value, direct: bool, ctx: &mut Interpreter) -> ResultValue {
+    if !x.is_string() {
+        return Ok(x.clone());
+    }
+    let script = compile(&x.to_string(), ctx)?;
+    match script {
+        Node::StatementList(ref list) if list.is_empty() => Ok(Value::undefined()),
+        Node::StatementList(ref list) if direct => ctx.run_eval_code(list),
+        Node::StatementList(ref list) => run_in_global_scope(ctx, |ctx| ctx.run_eval_code(list)),
+        _ => unreachable!("scripts are parsed into a statement list"),
+    }
+}
+
+/// `eval( x )`
+///
+/// Called as a function value, `eval` is an indirect eval. Direct calls are recognised by the
+/// interpreter, which performs them in its current environment.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-eval-x
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/eval
+pub fn eval(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    perform_eval(args.get(0).unwrap_or(&Value::undefined()), false, ctx)
+}
+
+/// Initialise the `eval` function on the global object.
+#[inline]
+pub fn init(global: &Value) {
+    make_builtin_fn!(eval, named "eval", with length 1, of global);
+}
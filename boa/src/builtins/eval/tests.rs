@@ -0,0 +1,92 @@
+use crate::{exec::Executor, forward, realm::Realm};
+
+#[test]
+fn direct_and_indirect_eval() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var x = 'global';
+        function direct() {
+            var x = 'local';
+            return eval('x');
+        }
+        function indirect() {
+            var x = 'local';
+            var alias = eval;
+            return alias('x');
+        }
+        function declares() {
+            eval('var y = 5');
+            return y;
+        }
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "direct()"), "local");
+    assert_eq!(forward(&mut engine, "indirect()"), "global");
+    assert_eq!(forward(&mut engine, "[eval][0]('x')"), "global");
+    assert_eq!(forward(&mut engine, "declares()"), "5");
+    assert_eq!(forward(&mut engine, "eval('1 + 2')"), "3");
+    assert_eq!(forward(&mut engine, "eval(42)"), "42");
+    assert_eq!(forward(&mut engine, "eval()"), "undefined");
+    assert_eq!(forward(&mut engine, "eval('')"), "undefined");
+    assert_eq!(
+        forward(&mut engine, "eval('1 +')"),
+        "Error: SyntaxError: Abrupt End"
+    );
+}
+
+#[test]
+fn function_constructor() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var x = 'global';
+        var add = new Function('a', 'b', 'return a + b');
+        var multiply = Function('a, b', 'return a * b');
+        function scoped() {
+            var x = 'local';
+            return new Function('return x')();
+        }
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "add(2, 3)"), "5");
+    assert_eq!(forward(&mut engine, "add.name"), "anonymous");
+    assert_eq!(forward(&mut engine, "add.length"), "2");
+    assert_eq!(forward(&mut engine, "multiply(3, 4)"), "12");
+    assert_eq!(forward(&mut engine, "scoped()"), "global");
+    assert_eq!(
+        forward(&mut engine, "new Function('}); (function() {')"),
+        "Error: SyntaxError: Invalid function source"
+    );
+}
+
+#[test]
+fn disabled_dynamic_code() {
+    let mut realm = Realm::create();
+    realm.disable_dynamic_code();
+    let mut engine = Executor::new(realm);
+
+    assert_eq!(
+        forward(&mut engine, "eval('1 + 2')"),
+        "Error: EvalError: Code generation from strings is disallowed"
+    );
+    assert_eq!(
+        forward(&mut engine, "new Function('return 1')"),
+        "Error: EvalError: Code generation from strings is disallowed"
+    );
+    // Only source text is compiled, other values are still returned as they are
+    assert_eq!(forward(&mut engine, "eval(42)"), "42");
+}
+
+#[test]
+fn compile_strings_hook() {
+    let mut realm = Realm::create();
+    realm.set_compile_strings_hook(|source| !source.contains("forbidden"));
+    let mut engine = Executor::new(realm);
+
+    assert_eq!(forward(&mut engine, "eval('1 + 2')"), "3");
+    assert_eq!(
+        forward(&mut engine, "eval('var forbidden = 1')"),
+        "Error: EvalError: Code generation from strings is disallowed"
+    );
+}
@@ -13,19 +13,25 @@
 
 use crate::{
     builtins::{
-        array,
-        object::{Object, ObjectInternalMethods, ObjectKind, PROTOTYPE},
+        array, eval,
+        object::{Object, ObjectInternalMethods, ObjectKind, INSTANCE_PROTOTYPE, PROTOTYPE},
         property::Property,
-        value::{ResultValue, Value},
+        shadow_realm,
+        symbol::WellKnownSymbol,
+        value::{ResultValue, Value, ValueData},
     },
     environment::lexical_environment::{new_function_environment, Environment},
-    exec::Executor,
     syntax::ast::node::{FormalParameter, Node},
     Interpreter,
 };
-use gc::{unsafe_empty_trace, Finalize, Trace};
+use gc::{unsafe_empty_trace, Finalize, GcCell, Trace};
+use rustc_hash::FxHashSet;
 use std::fmt::{self, Debug};
 
+/// The internal slot of a function holding the intrinsics of the realm it was created in, the
+/// function runs in that realm whichever realm calls it.
+pub(crate) static REALM: &str = "Realm";
+
 /// _fn(this, arguments, ctx) -> ResultValue_ - The signature of a built-in function
 pub type NativeFunctionData = fn(&mut Value, &[Value], &mut Interpreter) -> ResultValue;
 
@@ -46,10 +52,15 @@ pub enum ThisMode {
 }
 
 /// FunctionBody is specific to this interpreter, it will either be Rust code or JavaScript code (AST Node)
+///
+/// Bound and wrapped functions have no code, they call the target function kept in their
+/// internal slots.
 #[derive(Clone, Finalize)]
 pub enum FunctionBody {
     BuiltIn(NativeFunctionData),
     Ordinary(Node),
+    Bound,
+    Wrapped,
 }
 
 impl Debug for FunctionBody {
@@ -57,6 +68,8 @@ impl Debug for FunctionBody {
         match self {
             Self::BuiltIn(_) => write!(f, "native code"),
             Self::Ordinary(node) => write!(f, "{}", node),
+            Self::Bound => write!(f, "bound function"),
+            Self::Wrapped => write!(f, "wrapped function"),
         }
     }
 }
@@ -77,6 +90,8 @@ unsafe impl Trace for FunctionBody {
 pub enum FunctionKind {
     BuiltIn,
     Ordinary,
+    Bound,
+    Wrapped,
 }
 
 /// Waiting on <https://github.com/Manishearth/rust-gc/issues/87> until we can derive Copy
@@ -99,6 +114,8 @@ pub struct Function {
     pub kind: FunctionKind,
     // Environment, built-in functions don't need Environments
     pub environment: Option<Environment>,
+    /// Whether the function is strict mode code
+    pub strict: bool,
 }
 
 impl Function {
@@ -120,6 +137,7 @@ impl Function {
             params: parameter_list.into(),
             kind: FunctionKind::Ordinary,
             this_mode,
+            strict: false,
         }
     }
 
@@ -136,6 +154,37 @@ impl Function {
             this_mode: ThisMode::NonLexical,
             kind: FunctionKind::BuiltIn,
             environment: None,
+            strict: false,
+        }
+    }
+
+    /// This will create the call and construct behaviour of a bound function exotic object, the
+    /// target function, the `this` value and the arguments are internal slots of the object
+    ///
+    /// <https://tc39.es/ecma262/#sec-bound-function-exotic-objects>
+    pub fn create_bound() -> Self {
+        Self {
+            body: FunctionBody::Bound,
+            params: Box::new([]),
+            this_mode: ThisMode::NonLexical,
+            kind: FunctionKind::Bound,
+            environment: None,
+            strict: false,
+        }
+    }
+
+    /// This will create the call behaviour of a wrapped function exotic object, which calls a
+    /// function of another realm through a `ShadowRealm` boundary
+    ///
+    /// <https://tc39.es/proposal-shadowrealm/#sec-wrapped-function-exotic-objects>
+    pub fn create_wrapped() -> Self {
+        Self {
+            body: FunctionBody::Wrapped,
+            params: Box::new([]),
+            this_mode: ThisMode::NonLexical,
+            kind: FunctionKind::Wrapped,
+            environment: None,
+            strict: false,
         }
     }
 
@@ -153,16 +202,35 @@ impl Function {
         match self.kind {
             FunctionKind::BuiltIn => match &self.body {
                 FunctionBody::BuiltIn(func) => func(this_obj, args_list, interpreter),
-                FunctionBody::Ordinary(_) => {
-                    panic!("Builtin function should not have Ordinary Function body")
-                }
+                _ => panic!("Builtin function should not have Ordinary Function body"),
             },
+            // <https://tc39.es/ecma262/#sec-bound-function-exotic-objects-call-thisargument-argumentslist>
+            FunctionKind::Bound => {
+                let target = this.get_internal_slot("BoundTargetFunction");
+                let mut bound_this = this.get_internal_slot("BoundThis");
+                let args = bound_arguments_list(this, args_list, interpreter)?;
+                interpreter.call(&target, &mut bound_this, &args)
+            }
+            FunctionKind::Wrapped => {
+                shadow_realm::call_wrapped_function(this, args_list, interpreter)
+            }
             FunctionKind::Ordinary => {
+                // <https://tc39.es/ecma262/#sec-ordinarycallbindthis>
+                let this_value = match self.this_mode {
+                    ThisMode::Lexical => None,
+                    ThisMode::NonLexical if self.strict => Some(this_obj.clone()),
+                    ThisMode::NonLexical if this_obj.is_null_or_undefined() => {
+                        Some(interpreter.realm.environment.get_global_this())
+                    }
+                    ThisMode::NonLexical => Some(interpreter.to_object(this_obj)?),
+                };
+
                 // Create a new Function environment who's parent is set to the scope of the function declaration (self.environment)
                 // <https://tc39.es/ecma262/#sec-prepareforordinarycall>
                 let local_env = new_function_environment(
                     this.clone(),
-                    this_obj.clone(),
+                    this_value,
+                    Value::undefined(),
                     Some(self.environment.as_ref().unwrap().clone()),
                 );
 
@@ -175,8 +243,8 @@ impl Function {
                         break;
                     }
 
-                    let value = args_list.get(i).expect("Could not get value");
-                    self.add_arguments_to_environment(param, value.clone(), &local_env);
+                    let value = args_list.get(i).cloned().unwrap_or_else(Value::undefined);
+                    self.add_arguments_to_environment(param, value, &local_env);
                 }
 
                 // Add arguments object
@@ -192,7 +260,10 @@ impl Function {
 
                 // Call body should be set before reaching here
                 let result = match &self.body {
-                    FunctionBody::Ordinary(ref body) => interpreter.run(body),
+                    FunctionBody::Ordinary(ref body) => interpreter
+                        .run_in_mode(self.strict, |interpreter| {
+                            interpreter.run_function_body(&self.params, body)
+                        }),
                     _ => panic!("Ordinary function should not have BuiltIn Function body"),
                 };
 
@@ -213,16 +284,22 @@ impl Function {
         match self.kind {
             FunctionKind::BuiltIn => match &self.body {
                 FunctionBody::BuiltIn(func) => func(this_obj, args_list, interpreter),
-                FunctionBody::Ordinary(_) => {
-                    panic!("Builtin function should not have Ordinary Function body")
-                }
+                _ => panic!("Builtin function should not have Ordinary Function body"),
             },
+            // <https://tc39.es/ecma262/#sec-bound-function-exotic-objects-construct-argumentslist-newtarget>
+            FunctionKind::Bound => {
+                let target = this.get_internal_slot("BoundTargetFunction");
+                let args = bound_arguments_list(this, args_list, interpreter)?;
+                interpreter.construct(&target, &args)
+            }
+            FunctionKind::Wrapped => unreachable!("wrapped functions aren't constructors"),
             FunctionKind::Ordinary => {
                 // Create a new Function environment who's parent is set to the scope of the function declaration (self.environment)
                 // <https://tc39.es/ecma262/#sec-prepareforordinarycall>
                 let local_env = new_function_environment(
                     this.clone(),
-                    this_obj.clone(),
+                    Some(this_obj.clone()),
+                    this.clone(),
                     Some(self.environment.as_ref().unwrap().clone()),
                 );
 
@@ -234,8 +311,8 @@ impl Function {
                         break;
                     }
 
-                    let value = args_list.get(i).expect("Could not get value");
-                    self.add_arguments_to_environment(param, value.clone(), &local_env);
+                    let value = args_list.get(i).cloned().unwrap_or_else(Value::undefined);
+                    self.add_arguments_to_environment(param, value, &local_env);
                 }
 
                 // Add arguments object
@@ -251,7 +328,10 @@ impl Function {
 
                 // Call body should be set before reaching here
                 let result = match &self.body {
-                    FunctionBody::Ordinary(ref body) => interpreter.run(body),
+                    FunctionBody::Ordinary(ref body) => interpreter
+                        .run_in_mode(self.strict, |interpreter| {
+                            interpreter.run_function_body(&self.params, body)
+                        }),
                     _ => panic!("Ordinary function should not have BuiltIn Function body"),
                 };
 
@@ -334,7 +414,7 @@ pub fn create_unmapped_arguments_object(arguments_list: &[Value]) -> Value {
     let mut length = Property::default();
     length = length.writable(true).value(Value::from(len));
     // Define length as a property
-    obj.define_own_property("length".to_string(), length);
+    obj.define_own_property(Value::from("length"), length);
     let mut index: usize = 0;
     while index < len {
         let val = arguments_list.get(index).expect("Could not get argument");
@@ -345,7 +425,7 @@ pub fn create_unmapped_arguments_object(arguments_list: &[Value]) -> Value {
             .writable(true)
             .configurable(true);
 
-        obj.properties.insert(index.to_string(), prop);
+        obj.properties.insert(index.to_string().into(), prop);
         index += 1;
     }
 
@@ -354,18 +434,343 @@ pub fn create_unmapped_arguments_object(arguments_list: &[Value]) -> Value {
 
 /// Create new function `[[Construct]]`
 ///
-// This gets called when a new Function() is created.
-pub fn make_function(this: &mut Value, _: &[Value], _: &mut Interpreter) -> ResultValue {
-    this.set_kind(ObjectKind::Function);
-    Ok(this.clone())
+/// `Function( p1, p2, … , pn, body )` builds a function from the source text of its parameters
+/// and body, its scope is the global one whatever the caller is.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-createdynamicfunction
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Function/Function
+pub fn make_function(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let mut texts = Vec::with_capacity(args.len());
+    for arg in args {
+        texts.push(ctx.to_string(arg)?.to_string());
+    }
+    let body = texts.pop().unwrap_or_default();
+    let source = format!(
+        "(function anonymous({}\n) {{\n{}\n}})",
+        texts.join(","),
+        body
+    );
+
+    // The parameters and the body must not close the function early and add other code around it
+    let script = eval::compile(&source, ctx)?;
+    match script {
+        Node::StatementList(ref list) => match list.as_ref() {
+            [function @ Node::FunctionExpr(..)] => {
+                eval::run_in_global_scope(ctx, |ctx| ctx.run_node(function))
+            }
+            _ => ctx.throw_syntax_error("Invalid function source"),
+        },
+        _ => ctx.throw_syntax_error("Invalid function source"),
+    }
+}
+
+/// Checks that the `this` value of a `Function.prototype` method is callable.
+fn this_function(this: &Value, method: &str, ctx: &Interpreter) -> Result<Value, Value> {
+    if this.is_function() {
+        Ok(this.clone())
+    } else {
+        Err(ctx.construct_type_error(format!(
+            "Function.prototype.{} called on incompatible {}",
+            method, this
+        )))
+    }
+}
+
+/// Prepends the bound arguments of a bound function to the arguments of the call.
+fn bound_arguments_list(
+    function: &Value,
+    args: &[Value],
+    ctx: &mut Interpreter,
+) -> Result<Vec<Value>, Value> {
+    let bound_args = function.get_internal_slot("BoundArguments");
+    let length = array::length_of_array_like(&bound_args, ctx)?;
+    let mut list: Vec<Value> = (0..length)
+        .map(|index| bound_args.get_field_slice(&index.to_string()))
+        .collect();
+    list.extend_from_slice(args);
+    Ok(list)
+}
+
+/// `Function.prototype.apply( thisArg, argArray )`
+///
+/// The `apply()` method calls a function with a given `this` value, and the arguments provided
+/// as an array-like object.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-function.prototype.apply
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Function/apply
+pub fn apply(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let function = this_function(this, "apply", ctx)?;
+    let mut this_arg = args.get(0).cloned().unwrap_or_else(Value::undefined);
+    let arg_array = args.get(1).cloned().unwrap_or_else(Value::undefined);
+    if arg_array.is_null_or_undefined() {
+        return ctx.call(&function, &mut this_arg, &[]);
+    }
+
+    // <https://tc39.es/ecma262/#sec-createlistfromarraylike>
+    if !arg_array.is_object() {
+        return ctx.throw_type_error("CreateListFromArrayLike called on non-object");
+    }
+    let length = array::length_of_array_like(&arg_array, ctx)?;
+    let list: Vec<Value> = (0..length)
+        .map(|index| arg_array.get_field_slice(&index.to_string()))
+        .collect();
+    ctx.call(&function, &mut this_arg, &list)
+}
+
+/// `Function.prototype.bind( thisArg, ...args )`
+///
+/// The `bind()` method creates a bound function, which calls the original function with the
+/// given `this` value, and the given arguments before the ones of the call.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-function.prototype.bind
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Function/bind
+pub fn bind(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let target = this_function(this, "bind", ctx)?;
+    let bound_this = args.get(0).cloned().unwrap_or_else(Value::undefined);
+    let bound_args = args.get(1..).unwrap_or(&[]);
+
+    // <https://tc39.es/ecma262/#sec-boundfunctioncreate>
+    let mut bound = Object::function();
+    bound.set_call(Function::create_bound());
+    if target
+        .as_object()
+        .map_or(false, |object| object.is_constructor())
+    {
+        bound.set_construct(Function::create_bound());
+    }
+    bound.set_internal_slot(
+        INSTANCE_PROTOTYPE,
+        target.get_internal_slot(INSTANCE_PROTOTYPE),
+    );
+    bound.set_internal_slot("BoundTargetFunction", target.clone());
+    bound.set_internal_slot("BoundThis", bound_this);
+    bound.set_internal_slot(
+        "BoundArguments",
+        array::create_array_from_list(bound_args, ctx)?,
+    );
+    let bound = Value::from(bound);
+
+    copy_name_and_length(&bound, &target, "bound ", bound_args.len());
+    Ok(bound)
+}
+
+/// The abstract operation `CopyNameAndLength`, which gives a bound or wrapped function the
+/// `length` of its target minus the arguments it supplies, and the name of the target behind a
+/// prefix.
+///
+/// More information:
+///  - [Proposal][proposal]
+///
+/// [proposal]: https://tc39.es/proposal-shadowrealm/#sec-copynameandlength
+pub(crate) fn copy_name_and_length(
+    function: &Value,
+    target: &Value,
+    prefix: &str,
+    arg_count: usize,
+) {
+    let target_length = target.get_field_slice("length");
+    let length = if target_length.is_number() {
+        (target_length.to_number().trunc() - arg_count as f64).max(0.0)
+    } else {
+        0.0
+    };
+    let target_name = target.get_field_slice("name");
+    let name = if target_name.is_string() {
+        target_name.to_string()
+    } else {
+        String::new()
+    };
+    define_function_property(function, "length", Value::from(length));
+    define_function_property(function, "name", Value::from(format!("{}{}", prefix, name)));
+}
+
+/// The intrinsics of the realm recorded on a function, undefined for the functions which run in
+/// the realm of their caller.
+pub(crate) fn own_realm(function: &Value) -> Value {
+    function
+        .as_object()
+        .and_then(|object| object.internal_slots.get(REALM).cloned())
+        .unwrap_or_else(Value::undefined)
+}
+
+/// The abstract operation `GetFunctionRealm`, bound functions belong to the realm of their target
+/// and the functions without a realm of their own to the current realm.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-getfunctionrealm
+pub(crate) fn function_realm(function: &Value, ctx: &Interpreter) -> Value {
+    let realm = own_realm(function);
+    if realm.is_object() {
+        return realm;
+    }
+    let target = function.get_internal_slot("BoundTargetFunction");
+    if target.is_object() {
+        function_realm(&target, ctx)
+    } else {
+        ctx.realm.intrinsics.clone()
+    }
+}
+
+/// Defines the `length` or `name` property of a function, which is only configurable.
+fn define_function_property(function: &Value, key: &str, value: Value) {
+    let property = Property::new()
+        .value(value)
+        .writable(false)
+        .enumerable(false)
+        .configurable(true);
+    function
+        .as_object_mut()
+        .expect("functions are objects")
+        .define_own_property(Value::from(key), property);
+}
+
+/// `Function.prototype.call( thisArg, ...args )`
+///
+/// The `call()` method calls a function with a given `this` value and the arguments provided
+/// individually.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-function.prototype.call
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Function/call
+pub fn call(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let function = this_function(this, "call", ctx)?;
+    let mut this_arg = args.get(0).cloned().unwrap_or_else(Value::undefined);
+    ctx.call(&function, &mut this_arg, args.get(1..).unwrap_or(&[]))
+}
+
+/// `Function.prototype.toString()`
+///
+/// The `toString()` method returns the source text of an ordinary function. Built-in and bound
+/// functions, and functions parsed without their source, give a `NativeFunction` text instead.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-function.prototype.tostring
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Function/toString
+pub fn to_string(this: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let function = this_function(this, "toString", ctx)?;
+    let source_text = function.get_internal_slot("SourceText");
+    if source_text.is_string() {
+        return Ok(source_text);
+    }
+
+    let name = function.get_field_slice("name");
+    let name = if name.is_string() {
+        name.to_string()
+    } else {
+        String::new()
+    };
+    Ok(Value::from(format!(
+        "function {}() {{ [native code] }}",
+        name
+    )))
+}
+
+/// `Function.prototype[ @@hasInstance ]( V )`
+///
+/// The default `instanceof` check of functions, it looks for the `prototype` of the function in
+/// the prototype chain of the value.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-function.prototype-@@hasinstance
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Function/@@hasInstance
+pub fn has_instance(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let value = args.get(0).cloned().unwrap_or_else(Value::undefined);
+    Ok(Value::from(ctx.ordinary_has_instance(this, &value)?))
 }
 
 pub fn create(global: &Value) -> Value {
     let prototype = Value::new_object(Some(global));
 
+    make_builtin_fn!(apply, named "apply", with length 2, of prototype);
+    make_builtin_fn!(bind, named "bind", with length 1, of prototype);
+    make_builtin_fn!(call, named "call", with length 1, of prototype);
+    make_builtin_fn!(to_string, named "toString", of prototype);
+
+    // Unlike other methods it can't be overwritten, so `instanceof` can't be changed for all functions
+    prototype
+        .as_object_mut()
+        .expect("Function.prototype is an object")
+        .define_own_property(
+            Value::symbol(WellKnownSymbol::HasInstance.into()),
+            Property::new()
+                .value(make_builtin_fn!(has_instance, named "[Symbol.hasInstance]", with length 1))
+                .writable(false)
+                .enumerable(false)
+                .configurable(false),
+        );
+
     make_constructor_fn!(make_function, make_function, global, prototype)
 }
 
+/// Makes `Function.prototype` the prototype of the built-in functions, and records the realm
+/// they belong to.
+///
+/// Most builtins are created before `Function.prototype` can be reached from them, so their
+/// function objects, found from the global object, are linked once all of them exist.
+pub(crate) fn link_builtin_functions(global: &Value) {
+    let prototype = global
+        .get_field_slice("Function")
+        .get_field_slice(PROTOTYPE);
+    let mut visited = FxHashSet::default();
+    let mut pending = vec![global.clone()];
+    while let Some(value) = pending.pop() {
+        let object = match value.data() {
+            ValueData::Object(ref object) => object,
+            _ => continue,
+        };
+        if !visited.insert(&**object as *const GcCell<Object>) {
+            continue;
+        }
+
+        let mut object = object.borrow_mut();
+        let is_function = object.is_callable() || object.is_constructor();
+        let has_prototype = object
+            .internal_slots
+            .get(INSTANCE_PROTOTYPE)
+            .map_or(false, |prototype| prototype.is_object());
+        if is_function && !has_prototype {
+            object.set_internal_slot(INSTANCE_PROTOTYPE, prototype.clone());
+        }
+        if is_function && !object.internal_slots.contains_key(REALM) {
+            object.set_internal_slot(REALM, global.clone());
+        }
+
+        for property in object
+            .properties
+            .values()
+            .chain(object.sym_properties.values())
+        {
+            pending.extend(property.value.iter().cloned());
+            pending.extend(property.get.iter().cloned());
+            pending.extend(property.set.iter().cloned());
+        }
+        pending.extend(object.internal_slots.values().cloned());
+    }
+}
+
 /// Initialise the `Function` object on the global object.
 #[inline]
 pub fn init(global: &Value) {
@@ -23,3 +23,129 @@ fn check_arguments_object() {
         expected_return_val
     );
 }
+
+#[test]
+fn call_and_apply() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        function greet(greeting, punctuation) {
+            return greeting + ', ' + this.name + punctuation;
+        }
+        function self() {
+            return this;
+        }
+        var person = { name: 'Ann' };
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(
+        forward(&mut engine, "greet.call(person, 'Hi', '!')"),
+        "Hi, Ann!"
+    );
+    assert_eq!(
+        forward(&mut engine, "greet.apply(person, ['Hello', '?'])"),
+        "Hello, Ann?"
+    );
+    assert_eq!(forward(&mut engine, "Math.max.apply(null, [1, 5, 3])"), "5");
+    assert_eq!(
+        forward(&mut engine, "Math.max.apply(null, { length: 2, 0: 4, 1: 7 })"),
+        "7"
+    );
+    assert_eq!(forward(&mut engine, "self.call(undefined) === globalThis"), "true");
+    assert_eq!(forward(&mut engine, "self.apply(null) === globalThis"), "true");
+    assert_eq!(
+        forward(&mut engine, "Array.prototype.slice.call([1, 2, 3], 1).length"),
+        "2"
+    );
+    assert_eq!(
+        forward(&mut engine, "greet.apply(person, 1)"),
+        "Error: TypeError: CreateListFromArrayLike called on non-object"
+    );
+    assert_eq!(
+        forward(&mut engine, "Function.prototype.call.call({})"),
+        "Error: TypeError: Function.prototype.call called on incompatible [object Object]"
+    );
+}
+
+#[test]
+fn bind() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        function greet(greeting, punctuation) {
+            return greeting + ', ' + this.name + punctuation;
+        }
+        function Point(x, y) {
+            this.x = x;
+            this.y = y;
+        }
+        var hey = greet.bind({ name: 'Bob' }, 'Hey');
+        var Origin = Point.bind(null, 0);
+        var point = new Origin(5);
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "hey('.')"), "Hey, Bob.");
+    assert_eq!(forward(&mut engine, "hey.call({ name: 'Eve' }, '.')"), "Hey, Bob.");
+    assert_eq!(forward(&mut engine, "hey.name"), "bound greet");
+    assert_eq!(forward(&mut engine, "hey.length"), "1");
+    assert_eq!(forward(&mut engine, "greet.bind(null, 1, 2, 3).length"), "0");
+    assert_eq!(forward(&mut engine, "hey.bind(null, '!').name"), "bound bound greet");
+    assert_eq!(forward(&mut engine, "point.x"), "0");
+    assert_eq!(forward(&mut engine, "point.y"), "5");
+    assert_eq!(
+        forward(&mut engine, "Object.getPrototypeOf(hey) === Function.prototype"),
+        "true"
+    );
+}
+
+#[test]
+fn to_string() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        function add(a, b) { return a + b; }
+        var arrow = (a, b) => a * b;
+        var expression = function named() { return 1; };
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(
+        forward(&mut engine, "add.toString()"),
+        "function add(a, b) { return a + b; }"
+    );
+    assert_eq!(forward(&mut engine, "arrow.toString()"), "(a, b) => a * b");
+    assert_eq!(
+        forward(&mut engine, "expression.toString()"),
+        "function named() { return 1; }"
+    );
+    assert_eq!(
+        forward(&mut engine, "Math.max.toString()"),
+        "function max() { [native code] }"
+    );
+    assert_eq!(
+        forward(&mut engine, "add.bind(null).toString()"),
+        "function bound add() { [native code] }"
+    );
+    assert_eq!(
+        forward(&mut engine, "new Function('a', 'return a').toString()"),
+        "function anonymous(a\n) {\nreturn a\n}"
+    );
+}
+
+#[test]
+fn builtins_inherit_function_prototype() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+
+    assert_eq!(
+        forward(&mut engine, "Object.getPrototypeOf(Math.max) === Function.prototype"),
+        "true"
+    );
+    assert_eq!(
+        forward(&mut engine, "Object.getPrototypeOf(Array) === Function.prototype"),
+        "true"
+    );
+    assert_eq!(
+        forward(&mut engine, "Object.getPrototypeOf(Function) === Function.prototype"),
+        "true"
+    );
+}
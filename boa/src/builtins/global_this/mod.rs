@@ -0,0 +1,32 @@
+//! This module implements the global `globalThis` property.
+//!
+//! The global `globalThis` property holds the global object itself, so that it can be reached
+//! from any code.
+//!
+//! More information:
+//!  - [ECMAScript reference][spec]
+//!  - [MDN documentation][mdn]
+//!
+//! [spec]: https://tc39.es/ecma262/#sec-globalthis
+//! [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/globalThis
+
+#[cfg(test)]
+mod tests;
+
+use crate::builtins::{
+    object::internal_methods_trait::ObjectInternalMethods, property::Property, value::Value,
+};
+
+/// Initialise the `globalThis` property on the global object.
+#[inline]
+pub fn init(global: &Value) {
+    let property = Property::new()
+        .value(global.clone())
+        .writable(true)
+        .enumerable(false)
+        .configurable(true);
+    global
+        .as_object_mut()
+        .expect("the global object is an object")
+        .define_own_property(Value::from("globalThis"), property);
+}
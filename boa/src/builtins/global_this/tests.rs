@@ -0,0 +1,21 @@
+use crate::{exec::Executor, forward, realm::Realm};
+
+#[test]
+fn global_value_properties() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+
+    assert_eq!(forward(&mut engine, "globalThis.Math === Math"), "true");
+    forward(&mut engine, "globalThis.answer = 42");
+    assert_eq!(forward(&mut engine, "answer"), "42");
+    assert_eq!(forward(&mut engine, "Infinity"), "Infinity");
+    assert_eq!(forward(&mut engine, "-Infinity"), "-Infinity");
+    assert_eq!(forward(&mut engine, "globalThis['NaN']"), "NaN");
+    assert_eq!(forward(&mut engine, "globalThis.undefined"), "undefined");
+    forward(&mut engine, "Infinity = 1");
+    assert_eq!(forward(&mut engine, "Infinity"), "Infinity");
+    assert_eq!(
+        forward(&mut engine, "Object.keys(globalThis).indexOf('NaN')"),
+        "-1"
+    );
+}
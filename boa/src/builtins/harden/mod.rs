@@ -0,0 +1,86 @@
+//! This module implements the global `harden` function, in the style of
+//! [SES](https://github.com/endojs/endo/tree/master/packages/ses).
+//!
+//! `harden` freezes an object together with everything reachable from it: the values and the
+//! accessors of its properties and its prototype. Unlike `Object.freeze`, a hardened object can be
+//! handed to untrusted code, which can't tamper with anything it reaches through it.
+//!
+//! `Realm::lockdown` applies the same walk to the intrinsics of a realm.
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    builtins::{
+        object::{
+            internal_methods_trait::{IntegrityLevel, ObjectInternalMethods},
+            Object, INSTANCE_PROTOTYPE,
+        },
+        value::{ResultValue, Value, ValueData},
+    },
+    exec::Interpreter,
+};
+use gc::GcCell;
+use rustc_hash::FxHashSet;
+
+/// Freezes the objects reachable from `roots` through their properties, accessors, prototypes
+/// and the buffers viewed by typed arrays and data views.
+///
+/// The other internal slots aren't followed, the realm of a function isn't reachable from it.
+///
+/// Returns the first object that can't be frozen, such as a typed array with elements. The
+/// objects visited before it stay frozen.
+pub(crate) fn deep_freeze<I>(roots: I) -> Result<(), Value>
+where
+    I: IntoIterator<Item = Value>,
+{
+    let mut visited = FxHashSet::default();
+    let mut pending: Vec<Value> = roots.into_iter().collect();
+    while let Some(value) = pending.pop() {
+        let object = match value.data() {
+            ValueData::Object(ref object) => object,
+            _ => continue,
+        };
+        if !visited.insert(&**object as *const GcCell<Object>) {
+            continue;
+        }
+
+        let mut object = object.borrow_mut();
+        for property in object
+            .properties
+            .values()
+            .chain(object.sym_properties.values())
+        {
+            pending.extend(property.value.iter().cloned());
+            pending.extend(property.get.iter().cloned());
+            pending.extend(property.set.iter().cloned());
+        }
+        pending.extend(object.internal_slots.get(INSTANCE_PROTOTYPE).cloned());
+        pending.extend(object.internal_slots.get("ViewedArrayBuffer").cloned());
+        if !object.set_integrity_level(IntegrityLevel::Frozen) {
+            return Err(value.clone());
+        }
+    }
+    Ok(())
+}
+
+/// `harden( object )`
+///
+/// Freezes the object and everything reachable from it, then returns it. Primitives are returned
+/// as they are.
+///
+/// Like SES, it throws a `TypeError` if it reaches an object that can't be frozen, such as a
+/// typed array with elements, rather than leaving part of it mutable.
+pub fn harden(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let value = args.get(0).cloned().unwrap_or_else(Value::undefined);
+    match deep_freeze(Some(value.clone())) {
+        Ok(()) => Ok(value),
+        Err(_) => ctx.throw_type_error("Cannot harden an object that can't be frozen"),
+    }
+}
+
+/// Initialise the `harden` function on the global object.
+#[inline]
+pub fn init(global: &Value) {
+    make_builtin_fn!(harden, named "harden", with length 1, of global);
+}
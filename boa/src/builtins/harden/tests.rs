@@ -0,0 +1,162 @@
+use crate::{
+    exec::{Executor, Interpreter},
+    forward,
+    realm::Realm,
+};
+
+fn locked_down() -> Interpreter {
+    let realm = Realm::create();
+    realm.lockdown();
+    Executor::new(realm)
+}
+
+#[test]
+fn harden_freezes_reachable_objects() {
+    let realm = Realm::create();
+    let mut engine: Interpreter = Executor::new(realm);
+    let init = r#"
+        var proto = { greet: function() { return 'hi'; } };
+        var inner = { b: 1 };
+        var obj = Object.create(proto);
+        obj.a = inner;
+        var hardened = harden(obj);
+        obj.a = 2;
+        inner.b = 2;
+        inner.c = 3;
+        proto.greet = 4;
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "hardened === obj"), "true");
+    assert_eq!(forward(&mut engine, "obj.a === inner"), "true");
+    assert_eq!(forward(&mut engine, "inner.b"), "1");
+    assert_eq!(forward(&mut engine, "inner.c"), "undefined");
+    assert_eq!(forward(&mut engine, "obj.greet()"), "hi");
+    assert_eq!(forward(&mut engine, "Object.isFrozen(proto)"), "true");
+    assert_eq!(forward(&mut engine, "harden(1)"), "1");
+    assert_eq!(forward(&mut engine, "harden.length"), "1");
+}
+
+#[test]
+fn lockdown_freezes_intrinsics() {
+    let mut engine = locked_down();
+    let init = r#"
+        Array.prototype.push = function() { return 'pwned'; };
+        Array.prototype.extra = 1;
+        Object.prototype.polluted = true;
+        Math.max = Math.min;
+        JSON.stringify = undefined;
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "[].push(1)"), "1");
+    assert_eq!(forward(&mut engine, "[].extra"), "undefined");
+    assert_eq!(forward(&mut engine, "({}).polluted"), "undefined");
+    assert_eq!(forward(&mut engine, "Math.max(1, 2)"), "2");
+    assert_eq!(
+        forward(&mut engine, "Object.isFrozen(Object.prototype)"),
+        "true"
+    );
+    assert_eq!(
+        forward(&mut engine, "Object.isFrozen(Function.prototype)"),
+        "true"
+    );
+    assert_eq!(
+        forward(&mut engine, "Object.isFrozen(String.prototype.trim)"),
+        "true"
+    );
+}
+
+#[test]
+fn lockdown_protects_globals() {
+    let mut engine = locked_down();
+    forward(&mut engine, "Array = 1; delete Object; var mine = 2;");
+    assert_eq!(forward(&mut engine, "Array.isArray([])"), "true");
+    assert_eq!(forward(&mut engine, "Object.keys({ a: 1 }).length"), "1");
+    // Scripts can still add their own globals
+    assert_eq!(forward(&mut engine, "mine"), "2");
+    assert_eq!(
+        forward(
+            &mut engine,
+            "Object.getOwnPropertyDescriptor(globalThis, 'Math').writable"
+        ),
+        "false"
+    );
+}
+
+#[test]
+fn builtins_work_after_lockdown() {
+    let mut engine = locked_down();
+    assert_eq!(forward(&mut engine, "[3, 1, 2].sort().join()"), "1,2,3");
+    assert_eq!(
+        forward(&mut engine, "[1, 2].map(function (x) { return x * 2; })[1]"),
+        "4"
+    );
+    assert_eq!(forward(&mut engine, "'abc'.toUpperCase()"), "ABC");
+    assert_eq!(forward(&mut engine, "/b(c)/.exec('abc')[1]"), "c");
+    assert_eq!(
+        forward(&mut engine, "JSON.stringify({ a: [1] })"),
+        r#"{"a":[1]}"#
+    );
+    assert_eq!(
+        forward(
+            &mut engine,
+            "var it = [1, 2][Symbol.iterator](); it.next(); it.next().value"
+        ),
+        "2"
+    );
+    assert_eq!(
+        forward(&mut engine, "Symbol.for('a') === Symbol.for('a')"),
+        "true"
+    );
+    assert_eq!(forward(&mut engine, "var o = { a: 1 }; o.a = 2; o.a"), "2");
+}
+
+#[test]
+fn lockdown_protects_prototypes() {
+    let mut engine = locked_down();
+    assert_eq!(
+        forward(&mut engine, "Object.setPrototypeOf(Array.prototype, null)"),
+        "Error: TypeError: Cannot set the prototype of a non-extensible object"
+    );
+    assert_eq!(
+        forward(
+            &mut engine,
+            "Object.getPrototypeOf(Array.prototype) === Object.prototype"
+        ),
+        "true"
+    );
+    assert_eq!(forward(&mut engine, "[1, 2].join()"), "1,2");
+    // Setting the prototype it already has doesn't change anything
+    assert_eq!(
+        forward(
+            &mut engine,
+            "Object.setPrototypeOf(Array.prototype, Object.prototype) === Array.prototype"
+        ),
+        "true"
+    );
+}
+
+#[test]
+fn harden_views_and_buffers() {
+    let realm = Realm::create();
+    let mut engine: Interpreter = Executor::new(realm);
+    let init = r#"
+        var empty = harden(new Uint8Array(0));
+        var buffer = new ArrayBuffer(4);
+        var view = harden(new DataView(buffer));
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "Object.isFrozen(empty)"), "true");
+    assert_eq!(
+        forward(&mut engine, "Object.isFrozen(empty.buffer)"),
+        "true"
+    );
+    assert_eq!(forward(&mut engine, "Object.isFrozen(buffer)"), "true");
+    assert_eq!(
+        forward(&mut engine, "harden(new Uint8Array([1]))"),
+        "Error: TypeError: Cannot harden an object that can't be frozen"
+    );
+    assert_eq!(
+        forward(&mut engine, "harden({ bytes: new Int16Array(2) })"),
+        "Error: TypeError: Cannot harden an object that can't be frozen"
+    );
+}
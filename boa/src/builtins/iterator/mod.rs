@@ -0,0 +1,70 @@
+//! This module implements `%IteratorPrototype%`, the prototype of the iterators created by the
+//! builtins, and the helpers they share.
+//!
+//! More information:
+//!  - [ECMAScript reference][spec]
+//!
+//! [spec]: https://tc39.es/ecma262/#sec-%iteratorprototype%-object
+
+use crate::{
+    builtins::{
+        object::Object,
+        symbol::WellKnownSymbol,
+        value::{ResultValue, Value},
+    },
+    exec::Interpreter,
+};
+
+/// The internal slot of the global object holding `%IteratorPrototype%`.
+const ITERATOR_PROTOTYPE: &str = "IteratorPrototype";
+
+/// `%IteratorPrototype% [ @@iterator ] ( )`
+///
+/// Iterators are iterable themselves, this returns the iterator it is called on.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-%iteratorprototype%-@@iterator
+pub fn iterator(this: &mut Value, _: &[Value], _: &mut Interpreter) -> ResultValue {
+    Ok(this.clone())
+}
+
+/// Creates an iterator result object, `{ value, done }`.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-createiterresultobject
+pub(crate) fn create_iter_result_object(value: Value, done: bool, ctx: &Interpreter) -> Value {
+    let result = Value::new_object(Some(&ctx.realm.intrinsics));
+    result.set_field_slice("value", value);
+    result.set_field_slice("done", Value::from(done));
+    result
+}
+
+/// Creates the prototype of a kind of builtin iterator, like `%ArrayIteratorPrototype%`, which
+/// inherits from `%IteratorPrototype%`.
+pub(crate) fn create_iterator_prototype(global: &Value, to_string_tag: &str) -> Value {
+    let prototype = Value::object(Object::create(global.get_internal_slot(ITERATOR_PROTOTYPE)));
+    prototype.set_field(
+        Value::symbol(WellKnownSymbol::ToStringTag.into()),
+        Value::from(to_string_tag),
+    );
+    prototype
+}
+
+/// Create `%IteratorPrototype%`.
+pub fn create(global: &Value) -> Value {
+    let prototype = Value::new_object(Some(global));
+    let iterator = make_builtin_fn!(iterator, named "[Symbol.iterator]", with length 0);
+    prototype.set_field(Value::symbol(WellKnownSymbol::Iterator.into()), iterator);
+    prototype
+}
+
+/// Initialise `%IteratorPrototype%`, it is not reachable from the global object but kept in one of
+/// its internal slots.
+#[inline]
+pub fn init(global: &Value) {
+    global.set_internal_slot(ITERATOR_PROTOTYPE, create(global));
+}
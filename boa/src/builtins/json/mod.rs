@@ -13,13 +13,250 @@
 //! [json]: https://www.json.org/json-en.html
 //! [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/JSON
 
-use crate::builtins::value::{ResultValue, Value};
+use crate::builtins::{
+    array::{create_array_from_list, is_array_object, length_of_array_like},
+    number::number_to_string,
+    object::{enumerable_own_keys, internal_methods_trait::ObjectInternalMethods},
+    string::to_js_string,
+    symbol::WellKnownSymbol,
+    value::{JsString, ResultValue, Value, ValueData},
+};
 use crate::exec::Interpreter;
-use serde_json::{self, Value as JSONValue};
 
 #[cfg(test)]
 mod tests;
 
+/// A parser of the JSON text given to `JSON.parse`, working on UTF-16 code units.
+struct Parser<'a> {
+    text: &'a [u16],
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a [u16]) -> Self {
+        Self { text, position: 0 }
+    }
+
+    /// Builds the `SyntaxError` for the code unit at the current position.
+    fn unexpected(&self, ctx: &Interpreter) -> Value {
+        match self.text.get(self.position) {
+            Some(&unit) => ctx.construct_syntax_error(format!(
+                "Unexpected token {} in JSON at position {}",
+                String::from_utf16_lossy(&[unit]),
+                self.position
+            )),
+            None => ctx.construct_syntax_error("Unexpected end of JSON input"),
+        }
+    }
+
+    fn peek(&self) -> Option<u16> {
+        self.text.get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(0x09) | Some(0x0A) | Some(0x0D) | Some(0x20) = self.peek() {
+            self.position += 1;
+        }
+    }
+
+    /// Consumes `unit`, or fails if the next code unit is something else.
+    fn expect(&mut self, unit: u8, ctx: &Interpreter) -> Result<(), Value> {
+        if self.peek() == Some(u16::from(unit)) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(self.unexpected(ctx))
+        }
+    }
+
+    /// Consumes the keyword `word`, like `true`.
+    fn keyword(&mut self, word: &str, ctx: &Interpreter) -> Result<(), Value> {
+        for unit in word.bytes() {
+            self.expect(unit, ctx)?;
+        }
+        Ok(())
+    }
+
+    /// Parses the whole text, which must hold a single value.
+    fn parse_text(&mut self, ctx: &mut Interpreter) -> ResultValue {
+        let value = self.parse_value(ctx)?;
+        self.skip_whitespace();
+        if self.position < self.text.len() {
+            return Err(self.unexpected(ctx));
+        }
+        Ok(value)
+    }
+
+    fn parse_value(&mut self, ctx: &mut Interpreter) -> ResultValue {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(0x7B) => self.parse_object(ctx),
+            Some(0x5B) => self.parse_array(ctx),
+            Some(0x22) => Ok(Value::from(self.parse_string(ctx)?)),
+            Some(0x74) => {
+                self.keyword("true", ctx)?;
+                Ok(Value::boolean(true))
+            }
+            Some(0x66) => {
+                self.keyword("false", ctx)?;
+                Ok(Value::boolean(false))
+            }
+            Some(0x6E) => {
+                self.keyword("null", ctx)?;
+                Ok(Value::null())
+            }
+            Some(0x2D) | Some(0x30..=0x39) => self.parse_number(ctx),
+            _ => Err(self.unexpected(ctx)),
+        }
+    }
+
+    fn parse_object(&mut self, ctx: &mut Interpreter) -> ResultValue {
+        self.expect(b'{', ctx)?;
+        let object = Value::new_object(Some(&ctx.realm.intrinsics));
+        self.skip_whitespace();
+        if self.peek() == Some(u16::from(b'}')) {
+            self.position += 1;
+            return Ok(object);
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some(u16::from(b'"')) {
+                return Err(self.unexpected(ctx));
+            }
+            let key = self.parse_string(ctx)?;
+            self.skip_whitespace();
+            self.expect(b':', ctx)?;
+            let value = self.parse_value(ctx)?;
+            object.set_field(Value::from(key), value);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(0x2C) => self.position += 1,
+                Some(0x7D) => {
+                    self.position += 1;
+                    return Ok(object);
+                }
+                _ => return Err(self.unexpected(ctx)),
+            }
+        }
+    }
+
+    fn parse_array(&mut self, ctx: &mut Interpreter) -> ResultValue {
+        self.expect(b'[', ctx)?;
+        let mut elements = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(u16::from(b']')) {
+            self.position += 1;
+            return create_array_from_list(&elements, ctx);
+        }
+        loop {
+            elements.push(self.parse_value(ctx)?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(0x2C) => self.position += 1,
+                Some(0x5D) => {
+                    self.position += 1;
+                    return create_array_from_list(&elements, ctx);
+                }
+                _ => return Err(self.unexpected(ctx)),
+            }
+        }
+    }
+
+    fn parse_string(&mut self, ctx: &Interpreter) -> Result<JsString, Value> {
+        self.expect(b'"', ctx)?;
+        let mut units = Vec::new();
+        loop {
+            let unit = match self.peek() {
+                Some(unit) if unit >= 0x20 => unit,
+                _ => return Err(self.unexpected(ctx)),
+            };
+            self.position += 1;
+            match unit {
+                0x22 => return Ok(JsString::from(units)),
+                0x5C => {
+                    let escaped = match self.peek() {
+                        Some(0x22) => 0x22,
+                        Some(0x5C) => 0x5C,
+                        Some(0x2F) => 0x2F,
+                        Some(0x62) => 0x08,
+                        Some(0x66) => 0x0C,
+                        Some(0x6E) => 0x0A,
+                        Some(0x72) => 0x0D,
+                        Some(0x74) => 0x09,
+                        Some(0x75) => {
+                            self.position += 1;
+                            let mut code_unit = 0;
+                            for _ in 0..4 {
+                                let digit = self
+                                    .peek()
+                                    .and_then(|unit| std::char::from_u32(u32::from(unit)))
+                                    .and_then(|c| c.to_digit(16));
+                                match digit {
+                                    Some(digit) => code_unit = code_unit * 16 + digit as u16,
+                                    None => return Err(self.unexpected(ctx)),
+                                }
+                                self.position += 1;
+                            }
+                            units.push(code_unit);
+                            continue;
+                        }
+                        _ => return Err(self.unexpected(ctx)),
+                    };
+                    self.position += 1;
+                    units.push(escaped);
+                }
+                _ => units.push(unit),
+            }
+        }
+    }
+
+    fn parse_number(&mut self, ctx: &Interpreter) -> ResultValue {
+        let start = self.position;
+        let is_digit = |unit: Option<u16>| unit.map_or(false, |u| u >= 0x30 && u <= 0x39);
+
+        if self.peek() == Some(u16::from(b'-')) {
+            self.position += 1;
+        }
+        // A leading zero can't be followed by more digits
+        if self.peek() == Some(u16::from(b'0')) {
+            self.position += 1;
+        } else if is_digit(self.peek()) {
+            while is_digit(self.peek()) {
+                self.position += 1;
+            }
+        } else {
+            return Err(self.unexpected(ctx));
+        }
+        if self.peek() == Some(u16::from(b'.')) {
+            self.position += 1;
+            if !is_digit(self.peek()) {
+                return Err(self.unexpected(ctx));
+            }
+            while is_digit(self.peek()) {
+                self.position += 1;
+            }
+        }
+        if let Some(0x45) | Some(0x65) = self.peek() {
+            self.position += 1;
+            if let Some(0x2B) | Some(0x2D) = self.peek() {
+                self.position += 1;
+            }
+            if !is_digit(self.peek()) {
+                return Err(self.unexpected(ctx));
+            }
+            while is_digit(self.peek()) {
+                self.position += 1;
+            }
+        }
+
+        let literal = String::from_utf16_lossy(&self.text[start..self.position]);
+        let number = literal
+            .parse::<f64>()
+            .expect("a JSON number literal is a valid float");
+        Ok(Value::rational(number))
+    }
+}
+
 /// `JSON.parse( text[, reviver] )`
 ///
 /// This `JSON` method parses a JSON string, constructing the JavaScript value or object described by the string.
@@ -32,18 +269,269 @@ mod tests;
 ///
 /// [spec]: https://tc39.es/ecma262/#sec-json.parse
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/JSON/parse
-// TODO: implement optional revever argument.
-pub fn parse(_: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValue {
-    match serde_json::from_str::<JSONValue>(
-        &args
-            .get(0)
-            .expect("cannot get argument for JSON.parse")
-            .clone()
-            .to_string(),
-    ) {
-        Ok(json) => Ok(Value::from(json)),
-        Err(err) => Err(Value::from(err.to_string())),
+pub fn parse(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let text = to_js_string(args.get(0).unwrap_or(&Value::undefined()), ctx)?;
+    let unfiltered = Parser::new(&text).parse_text(ctx)?;
+
+    let reviver = args.get(1).cloned().unwrap_or_else(Value::undefined);
+    if !reviver.is_function() {
+        return Ok(unfiltered);
+    }
+    let root = Value::new_object(Some(&ctx.realm.intrinsics));
+    root.set_field_slice("", unfiltered);
+    internalize_json_property(&root, Value::from(""), &reviver, ctx)
+}
+
+/// The abstract operation `InternalizeJSONProperty`, which walks the parsed value bottom up and
+/// gives every property to the reviver.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-internalizejsonproperty
+fn internalize_json_property(
+    holder: &Value,
+    name: Value,
+    reviver: &Value,
+    ctx: &mut Interpreter,
+) -> ResultValue {
+    let value = holder.get_field(name.clone());
+    if value.is_object() {
+        let keys = if is_array_object(&value) {
+            (0..length_of_array_like(&value, ctx)?)
+                .map(Value::from)
+                .map(|index| ctx.to_string(&index))
+                .collect::<Result<_, _>>()?
+        } else {
+            enumerable_own_keys(&value)
+        };
+        for key in keys {
+            let element = internalize_json_property(&value, key.clone(), reviver, ctx)?;
+            if element.is_undefined() {
+                if let Some(mut obj) = value.as_object_mut() {
+                    obj.delete(&key);
+                }
+            } else {
+                value.set_field(key, element);
+            }
+        }
     }
+    ctx.call(reviver, &mut holder.clone(), &[name, value])
+}
+
+/// The state of a `JSON.stringify` call, the `state` record of the specification.
+struct Stringifier {
+    /// `[[ReplacerFunction]]`
+    replacer_function: Option<Value>,
+    /// `[[PropertyList]]`, the keys to serialize when the replacer is an array.
+    property_list: Option<Vec<JsString>>,
+    /// `[[Stack]]`, the objects being serialized, to detect cycles.
+    stack: Vec<Value>,
+    /// `[[Indent]]`
+    indent: Vec<u16>,
+    /// `[[Gap]]`
+    gap: Vec<u16>,
+}
+
+impl Stringifier {
+    /// The abstract operation `SerializeJSONProperty`, which returns `None` for values that have
+    /// no representation in JSON, like `undefined` or functions.
+    ///
+    /// More information:
+    ///  - [ECMAScript reference][spec]
+    ///
+    /// [spec]: https://tc39.es/ecma262/#sec-serializejsonproperty
+    fn serialize_property(
+        &mut self,
+        key: Value,
+        holder: &Value,
+        ctx: &mut Interpreter,
+    ) -> Result<Option<Vec<u16>>, Value> {
+        let mut value = holder.get_field(key.clone());
+
+        if value.is_object() {
+            let to_json = value.get_field_slice("toJSON");
+            if to_json.is_function() {
+                value = ctx.call(&to_json, &mut value.clone(), &[key.clone()])?;
+            }
+        }
+        if let Some(ref replacer) = self.replacer_function {
+            value = ctx.call(replacer, &mut holder.clone(), &[key, value])?;
+        }
+
+        // Unwrap the primitive wrapper objects
+        if value.is_object() {
+            for slot in &["NumberData", "StringData", "BooleanData"] {
+                let primitive = value.get_internal_slot(slot);
+                if !primitive.is_undefined() {
+                    value = match *slot {
+                        "NumberData" => Value::rational(ctx.to_number(&value)?),
+                        "StringData" => ctx.to_string(&value)?,
+                        _ => primitive,
+                    };
+                    break;
+                }
+            }
+        }
+
+        Ok(match *value {
+            ValueData::Null => Some("null".encode_utf16().collect()),
+            ValueData::Boolean(true) => Some("true".encode_utf16().collect()),
+            ValueData::Boolean(false) => Some("false".encode_utf16().collect()),
+            ValueData::String(ref string) => Some(quote_json_string(string)),
+            ValueData::Integer(integer) => Some(integer.to_string().encode_utf16().collect()),
+            ValueData::Rational(number) if number.is_finite() => {
+                Some(number_to_string(number).encode_utf16().collect())
+            }
+            ValueData::Rational(_) => Some("null".encode_utf16().collect()),
+            ValueData::Object(_) if !value.is_function() => {
+                if is_array_object(&value) {
+                    Some(self.serialize_array(&value, ctx)?)
+                } else {
+                    Some(self.serialize_object(&value, ctx)?)
+                }
+            }
+            _ => None,
+        })
+    }
+
+    /// Pushes `value` on the stack, throwing a `TypeError` if it is already being serialized.
+    fn enter(&mut self, value: &Value, ctx: &Interpreter) -> Result<(), Value> {
+        if self.stack.iter().any(|seen| seen.strict_equals(value)) {
+            return Err(ctx.construct_type_error("Converting circular structure to JSON"));
+        }
+        self.stack.push(value.clone());
+        Ok(())
+    }
+
+    /// Joins the serialized members between `open` and `close`, on separate lines if there is a
+    /// gap.
+    fn join(&self, open: u8, close: u8, members: Vec<Vec<u16>>, stepback: &[u16]) -> Vec<u16> {
+        let mut result = vec![u16::from(open)];
+        if !members.is_empty() {
+            if self.gap.is_empty() {
+                for (i, member) in members.into_iter().enumerate() {
+                    if i > 0 {
+                        result.push(u16::from(b','));
+                    }
+                    result.extend(member);
+                }
+            } else {
+                for (i, member) in members.into_iter().enumerate() {
+                    if i > 0 {
+                        result.push(u16::from(b','));
+                    }
+                    result.push(u16::from(b'\n'));
+                    result.extend_from_slice(&self.indent);
+                    result.extend(member);
+                }
+                result.push(u16::from(b'\n'));
+                result.extend_from_slice(stepback);
+            }
+        }
+        result.push(u16::from(close));
+        result
+    }
+
+    /// The abstract operation `SerializeJSONObject`.
+    ///
+    /// More information:
+    ///  - [ECMAScript reference][spec]
+    ///
+    /// [spec]: https://tc39.es/ecma262/#sec-serializejsonobject
+    fn serialize_object(
+        &mut self,
+        value: &Value,
+        ctx: &mut Interpreter,
+    ) -> Result<Vec<u16>, Value> {
+        self.enter(value, ctx)?;
+        let stepback = self.indent.clone();
+        self.indent.extend_from_slice(&self.gap.clone());
+
+        let keys: Vec<Value> = match self.property_list {
+            Some(ref list) => list.iter().cloned().map(Value::from).collect(),
+            None => enumerable_own_keys(value),
+        };
+        let mut members = Vec::new();
+        for key in keys {
+            if let Some(serialized) = self.serialize_property(key.clone(), value, ctx)? {
+                let mut member = quote_json_string(&to_js_string(&key, ctx)?);
+                member.push(u16::from(b':'));
+                if !self.gap.is_empty() {
+                    member.push(u16::from(b' '));
+                }
+                member.extend(serialized);
+                members.push(member);
+            }
+        }
+
+        let result = self.join(b'{', b'}', members, &stepback);
+        self.stack.pop();
+        self.indent = stepback;
+        Ok(result)
+    }
+
+    /// The abstract operation `SerializeJSONArray`.
+    ///
+    /// More information:
+    ///  - [ECMAScript reference][spec]
+    ///
+    /// [spec]: https://tc39.es/ecma262/#sec-serializejsonarray
+    fn serialize_array(&mut self, value: &Value, ctx: &mut Interpreter) -> Result<Vec<u16>, Value> {
+        self.enter(value, ctx)?;
+        let stepback = self.indent.clone();
+        self.indent.extend_from_slice(&self.gap.clone());
+
+        let length = length_of_array_like(value, ctx)?;
+        let mut members = Vec::with_capacity(length);
+        for index in 0..length {
+            let key = ctx.to_string(&Value::from(index))?;
+            match self.serialize_property(key, value, ctx)? {
+                Some(serialized) => members.push(serialized),
+                None => members.push("null".encode_utf16().collect()),
+            }
+        }
+
+        let result = self.join(b'[', b']', members, &stepback);
+        self.stack.pop();
+        self.indent = stepback;
+        Ok(result)
+    }
+}
+
+/// The abstract operation `QuoteJSONString`, which wraps a string in double quotes and escapes
+/// the characters JSON can't hold.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-quotejsonstring
+fn quote_json_string(string: &JsString) -> Vec<u16> {
+    let mut result = Vec::with_capacity(string.len() + 2);
+    result.push(u16::from(b'"'));
+    for code_point in string.code_points() {
+        let escape = match code_point.code_point {
+            0x08 => Some("\\b".to_owned()),
+            0x09 => Some("\\t".to_owned()),
+            0x0A => Some("\\n".to_owned()),
+            0x0C => Some("\\f".to_owned()),
+            0x0D => Some("\\r".to_owned()),
+            0x22 => Some("\\\"".to_owned()),
+            0x5C => Some("\\\\".to_owned()),
+            // Control characters and lone surrogates
+            c if c < 0x20 || (c >= 0xD800 && c <= 0xDFFF) => Some(format!("\\u{:04x}", c)),
+            _ => None,
+        };
+        match escape {
+            Some(escape) => result.extend(escape.encode_utf16()),
+            None => match code_point.as_char() {
+                Some(c) => result.extend_from_slice(c.encode_utf16(&mut [0; 2])),
+                None => result.push(code_point.code_point as u16),
+            },
+        }
+    }
+    result.push(u16::from(b'"'));
+    result
 }
 
 /// `JSON.stringify( value[, replacer[, space]] )`
@@ -62,10 +550,71 @@ pub fn parse(_: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValue
 ///
 /// [spec]: https://tc39.es/ecma262/#sec-json.stringify
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/JSON/stringify
-pub fn stringify(_: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValue {
-    let obj = args.get(0).expect("cannot get argument for JSON.stringify");
-    let json = obj.to_json().to_string();
-    Ok(Value::from(json))
+pub fn stringify(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let value = args.get(0).cloned().unwrap_or_else(Value::undefined);
+    let replacer = args.get(1).cloned().unwrap_or_else(Value::undefined);
+    let mut space = args.get(2).cloned().unwrap_or_else(Value::undefined);
+
+    let mut replacer_function = None;
+    let mut property_list = None;
+    if replacer.is_function() {
+        replacer_function = Some(replacer);
+    } else if is_array_object(&replacer) {
+        let mut list: Vec<JsString> = Vec::new();
+        for index in 0..length_of_array_like(&replacer, ctx)? {
+            let element = replacer.get_field_slice(&index.to_string());
+            let item = if element.is_string()
+                || element.is_number()
+                || !element.get_internal_slot("StringData").is_undefined()
+                || !element.get_internal_slot("NumberData").is_undefined()
+            {
+                Some(to_js_string(&element, ctx)?)
+            } else {
+                None
+            };
+            if let Some(item) = item {
+                if !list.contains(&item) {
+                    list.push(item);
+                }
+            }
+        }
+        property_list = Some(list);
+    }
+
+    if !space.get_internal_slot("NumberData").is_undefined() {
+        space = Value::rational(ctx.to_number(&space)?);
+    } else if !space.get_internal_slot("StringData").is_undefined() {
+        space = ctx.to_string(&space)?;
+    }
+    let gap = if space.is_number() {
+        let count = space.to_number().min(10.0);
+        if count >= 1.0 {
+            vec![u16::from(b' '); count as usize]
+        } else {
+            Vec::new()
+        }
+    } else if let ValueData::String(ref string) = *space {
+        string[..string.len().min(10)].to_vec()
+    } else {
+        Vec::new()
+    };
+
+    let mut stringifier = Stringifier {
+        replacer_function,
+        property_list,
+        stack: Vec::new(),
+        indent: Vec::new(),
+        gap,
+    };
+    let wrapper = Value::new_object(Some(&ctx.realm.intrinsics));
+    wrapper.set_field_slice("", value);
+    match stringifier.serialize_property(Value::from(""), &wrapper, ctx)? {
+        Some(result) => {
+            ctx.reserve_string(result.len())?;
+            Ok(Value::from(JsString::from(result)))
+        }
+        None => Ok(Value::undefined()),
+    }
 }
 
 /// Create a new `JSON` object.
@@ -74,6 +623,10 @@ pub fn create(global: &Value) -> Value {
 
     make_builtin_fn!(parse, named "parse", with length 2, of json);
     make_builtin_fn!(stringify, named "stringify", with length 3, of json);
+    json.set_field(
+        Value::symbol(WellKnownSymbol::ToStringTag.into()),
+        Value::from("JSON"),
+    );
 
     json
 }
@@ -16,3 +16,210 @@ fn json_sanity() {
         "true"
     );
 }
+
+#[test]
+fn parse_values() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    assert_eq!(
+        forward(&mut engine, "JSON.parse(' [1, -2.5e1, true, null] ')[1]"),
+        "-25"
+    );
+    assert_eq!(forward(&mut engine, "JSON.parse('[1, 2, 3]').length"), "3");
+    assert_eq!(
+        forward(&mut engine, r#"JSON.parse('"a\\u0041\\n"')"#),
+        "aA\n"
+    );
+    assert_eq!(
+        forward(&mut engine, r#"JSON.parse('{"a":{"b":[false]}}').a.b[0]"#),
+        "false"
+    );
+    assert_eq!(
+        forward(
+            &mut engine,
+            r#"Object.keys(JSON.parse('{"b":1,"a":2}')).join()"#
+        ),
+        "b,a"
+    );
+}
+
+#[test]
+fn parse_syntax_error() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    assert_eq!(
+        forward(&mut engine, "JSON.parse('{\"a\":1,}')"),
+        "Error: SyntaxError: Unexpected token } in JSON at position 7"
+    );
+    assert_eq!(
+        forward(&mut engine, "JSON.parse('[1, 2')"),
+        "Error: SyntaxError: Unexpected end of JSON input"
+    );
+    assert_eq!(
+        forward(&mut engine, "JSON.parse('01')"),
+        "Error: SyntaxError: Unexpected token 1 in JSON at position 1"
+    );
+}
+
+#[test]
+fn parse_reviver() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+    var keys = [];
+    var revived = JSON.parse('{"a":1,"b":{"c":2},"d":3}', function(key, value) {
+        keys.push(key);
+        if (key === 'a') {
+            return value * 10;
+        }
+        if (key === 'd') {
+            return undefined;
+        }
+        return value;
+    });
+    "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "keys.join()"), "a,c,b,d,");
+    assert_eq!(forward(&mut engine, "revived.a"), "10");
+    assert_eq!(forward(&mut engine, "revived.b.c"), "2");
+    assert_eq!(forward(&mut engine, "Object.keys(revived).join()"), "a,b");
+}
+
+#[test]
+fn stringify_values() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    assert_eq!(
+        forward(
+            &mut engine,
+            "JSON.stringify([1, 'a', true, null, undefined])"
+        ),
+        r#"[1,"a",true,null,null]"#
+    );
+    assert_eq!(
+        forward(
+            &mut engine,
+            "JSON.stringify({a: undefined, b: function() {}, c: 1})"
+        ),
+        r#"{"c":1}"#
+    );
+    assert_eq!(
+        forward(&mut engine, "JSON.stringify(undefined)"),
+        "undefined"
+    );
+    assert_eq!(
+        forward(
+            &mut engine,
+            "JSON.stringify([new Number(3), new String('s'), new Boolean(false)])"
+        ),
+        r#"[3,"s",false]"#
+    );
+    assert_eq!(
+        forward(&mut engine, r#"JSON.stringify('"\\\n\u0001\ud800')"#),
+        r#""\"\\\n\u0001\ud800""#
+    );
+}
+
+#[test]
+fn stringify_numbers() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    assert_eq!(
+        forward(
+            &mut engine,
+            "JSON.stringify([1e21, 1e-7, 0.1, -0, NaN, 123.456])"
+        ),
+        "[1e+21,1e-7,0.1,0,null,123.456]"
+    );
+}
+
+#[test]
+fn stringify_property_order() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    assert_eq!(
+        forward(&mut engine, "JSON.stringify({b: 1, a: 2, 1: 3, 0: 4})"),
+        r#"{"0":4,"1":3,"b":1,"a":2}"#
+    );
+}
+
+#[test]
+fn stringify_to_json() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    assert_eq!(
+        forward(
+            &mut engine,
+            "JSON.stringify({a: {toJSON: function(key) { return key + '!'; }}})"
+        ),
+        r#"{"a":"a!"}"#
+    );
+}
+
+#[test]
+fn stringify_replacer() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+    function replacer(key, value) {
+        if (key === 'b') {
+            return undefined;
+        }
+        if (key === 'a') {
+            return value + 1;
+        }
+        return value;
+    }
+    "#;
+    forward(&mut engine, init);
+    assert_eq!(
+        forward(&mut engine, "JSON.stringify({a: 1, b: 2, c: 3}, replacer)"),
+        r#"{"a":2,"c":3}"#
+    );
+    assert_eq!(
+        forward(
+            &mut engine,
+            "JSON.stringify({a: 1, b: {a: 2, c: 3}, c: 4}, ['c', 'b', 'c'])"
+        ),
+        r#"{"c":4,"b":{"c":3}}"#
+    );
+}
+
+#[test]
+fn stringify_space() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    assert_eq!(
+        forward(
+            &mut engine,
+            "JSON.stringify({a: [1, 2], b: {}}, undefined, 2)"
+        ),
+        "{\n  \"a\": [\n    1,\n    2\n  ],\n  \"b\": {}\n}"
+    );
+    assert_eq!(
+        forward(&mut engine, "JSON.stringify([1], undefined, '--')"),
+        "[\n--1\n]"
+    );
+    assert_eq!(
+        forward(&mut engine, "JSON.stringify([1], undefined, 20).length"),
+        "15"
+    );
+}
+
+#[test]
+fn stringify_cycle() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    forward(&mut engine, "var a = {}; a.b = [a];");
+    assert_eq!(
+        forward(&mut engine, "JSON.stringify(a)"),
+        "Error: TypeError: Converting circular structure to JSON"
+    );
+    assert_eq!(
+        forward(
+            &mut engine,
+            "var shared = {}; JSON.stringify([shared, shared])"
+        ),
+        "[{},{}]"
+    );
+}
@@ -12,7 +12,10 @@
 //! [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Math
 
 use crate::{
-    builtins::value::{ResultValue, Value},
+    builtins::{
+        symbol::WellKnownSymbol,
+        value::{ResultValue, Value},
+    },
     exec::Interpreter,
 };
 use rand::random;
@@ -543,6 +546,10 @@ pub fn create(global: &Value) -> Value {
     make_builtin_fn!(tan, named "tan", with length 1, of math);
     make_builtin_fn!(tanh, named "tanh", with length 1, of math);
     make_builtin_fn!(trunc, named "trunc", with length 1, of math);
+    math.set_field(
+        Value::symbol(WellKnownSymbol::ToStringTag.into()),
+        Value::from("Math"),
+    );
 
     math
 }
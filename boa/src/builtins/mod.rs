@@ -2,9 +2,10 @@
 
 /// Macro to create a new member function of a prototype.
 ///
-/// If no length is provided, the length will be set to 0.
+/// If no length is provided, the length will be set to 0. Without a target object the function
+/// is returned instead, e.g. to be stored under a symbol key.
 macro_rules! make_builtin_fn {
-    ($fn:ident, named $name:expr, with length $l:tt, of $p:ident) => {
+    ($fn:ident, named $name:expr, with length $l:tt) => {{
         let func = crate::builtins::function::Function::create_builtin(
             vec![],
             crate::builtins::function::FunctionBody::BuiltIn($fn),
@@ -14,7 +15,11 @@ macro_rules! make_builtin_fn {
         new_func.set_call(func);
         let new_func_obj = Value::from(new_func);
         new_func_obj.set_field_slice("length", Value::from($l));
-        $p.set_field_slice($name, new_func_obj);
+        new_func_obj.set_field_slice("name", Value::from($name));
+        new_func_obj
+    }};
+    ($fn:ident, named $name:expr, with length $l:tt, of $p:ident) => {
+        $p.set_field_slice($name, make_builtin_fn!($fn, named $name, with length $l));
     };
     ($fn:ident, named $name:expr, of $p:ident) => {
         make_builtin_fn!($fn, named $name, with length 0, of $p);
@@ -82,18 +87,30 @@ macro_rules! make_constructor_fn {
 }
 
 pub mod array;
+pub mod array_buffer;
 pub mod boolean;
 pub mod console;
+pub mod data_view;
 pub mod error;
+pub mod eval;
 pub mod function;
+pub mod global_this;
+pub mod harden;
+pub mod infinity;
+pub mod iterator;
 pub mod json;
 pub mod math;
+pub mod nan;
 pub mod number;
 pub mod object;
 pub mod property;
 pub mod regexp;
+pub mod shadow_realm;
 pub mod string;
 pub mod symbol;
+pub mod typed_array;
+pub mod undefined;
+pub mod uri;
 pub mod value;
 
 use value::Value;
@@ -101,15 +118,39 @@ use value::Value;
 /// Initializes builtin objects and functions
 #[inline]
 pub fn init(global: &Value) {
+    // The other builtins inherit from `Object.prototype` and `Function.prototype`
+    object::init(global);
+    function::init(global);
+    iterator::init(global);
     array::init(global);
+    array::array_iterator::init(global);
     boolean::init(global);
     json::init(global);
     math::init(global);
     number::init(global);
-    object::init(global);
-    function::init(global);
+    error::init(global);
+    error::eval::init(global);
+    error::range::init(global);
+    error::reference::init(global);
+    error::syntax::init(global);
+    error::r#type::init(global);
+    error::uri::init(global);
+    array_buffer::init(global);
+    typed_array::init(global);
+    data_view::init(global);
     regexp::init(global);
+    regexp::regexp_string_iterator::init(global);
+    shadow_realm::init(global);
     string::init(global);
+    string::string_iterator::init(global);
     symbol::init(global);
     console::init(global);
+    uri::init(global);
+    eval::init(global);
+    harden::init(global);
+    global_this::init(global);
+    infinity::init(global);
+    nan::init(global);
+    undefined::init(global);
+    function::link_builtin_functions(global);
 }
@@ -0,0 +1,28 @@
+//! This module implements the global `NaN` property.
+//!
+//! The global `NaN` property is a read-only value representing Not-A-Number.
+//!
+//! More information:
+//!  - [ECMAScript reference][spec]
+//!  - [MDN documentation][mdn]
+//!
+//! [spec]: https://tc39.es/ecma262/#sec-value-properties-of-the-global-object-nan
+//! [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/NaN
+
+use crate::builtins::{
+    object::internal_methods_trait::ObjectInternalMethods, property::Property, value::Value,
+};
+
+/// Initialise the `NaN` property on the global object.
+#[inline]
+pub fn init(global: &Value) {
+    let property = Property::new()
+        .value(Value::from(f64::NAN))
+        .writable(false)
+        .enumerable(false)
+        .configurable(false);
+    global
+        .as_object_mut()
+        .expect("the global object is an object")
+        .define_own_property(Value::from("NaN"), property);
+}
@@ -19,13 +19,19 @@ mod tests;
 use crate::{
     builtins::{
         object::{internal_methods_trait::ObjectInternalMethods, Object, PROTOTYPE},
-        value::{ResultValue, Value, ValueData},
+        property::Property,
+        string::{to_js_string, trim_string},
+        typed_array::modulo,
+        value::{JsString, ResultValue, Value, ValueData},
     },
     exec::Interpreter,
 };
 use num_traits::float::FloatCore;
 use std::{borrow::Borrow, f64, ops::Deref};
 
+/// The largest integer `n` such that `n` and `n + 1` are both exactly representable.
+const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_991.0;
+
 /// Helper function that converts a Value to a Number.
 fn to_number(value: &Value) -> Value {
     match *value.deref().borrow() {
@@ -41,10 +47,7 @@ fn to_number(value: &Value) -> Value {
         ValueData::Object(ref o) => (o).deref().borrow().get_internal_slot("NumberData"),
         ValueData::Null => Value::from(0),
         ValueData::Rational(n) => Value::from(n),
-        ValueData::String(ref s) => match s.parse::<f64>() {
-            Ok(n) => Value::from(n),
-            Err(_) => Value::from(f64::NAN),
-        },
+        ValueData::String(ref s) => Value::from(string_to_number(s)),
     }
 }
 
@@ -58,10 +61,10 @@ fn num_to_exponential(n: f64) -> String {
 }
 
 /// Create a new number `[[Construct]]`
-pub fn make_number(this: &mut Value, args: &[Value], _ctx: &mut Interpreter) -> ResultValue {
+pub fn make_number(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
     let data = match args.get(0) {
-        Some(ref value) => to_number(value),
-        None => to_number(&Value::from(0)),
+        Some(ref value) => Value::from(ctx.to_number(value)?),
+        None => Value::from(0.0),
     };
     this.set_internal_slot("NumberData", data);
     Ok(this.clone())
@@ -70,10 +73,10 @@ pub fn make_number(this: &mut Value, args: &[Value], _ctx: &mut Interpreter) ->
 /// `Number()` function.
 ///
 /// More Information https://tc39.es/ecma262/#sec-number-constructor-number-value
-pub fn call_number(_this: &mut Value, args: &[Value], _ctx: &mut Interpreter) -> ResultValue {
+pub fn call_number(_this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
     let data = match args.get(0) {
-        Some(ref value) => to_number(value),
-        None => to_number(&Value::from(0)),
+        Some(ref value) => Value::from(ctx.to_number(value)?),
+        None => Value::from(0.0),
     };
     Ok(data)
 }
@@ -283,6 +286,53 @@ pub fn num_to_string(mut value: f64, radix: u8) -> String {
     String::from_utf8_lossy(&buffer[integer_cursor..fraction_cursor]).into()
 }
 
+/// The abstract operation `Number::toString`, which formats a number in base 10.
+///
+/// The shortest digits that round-trip are used, with an exponent for numbers below `1e-6` or
+/// of `1e21` and more.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-numeric-types-number-tostring
+pub(crate) fn number_to_string(x: f64) -> String {
+    if x.is_nan() {
+        return String::from("NaN");
+    }
+    if x == 0.0 {
+        return String::from("0");
+    }
+    if x.is_infinite() {
+        return String::from(if x < 0.0 { "-Infinity" } else { "Infinity" });
+    }
+    if x < 0.0 {
+        return format!("-{}", number_to_string(-x));
+    }
+
+    // The shortest round-trip digits `s` and the exponent `n` such that x = 0.s * 10^n
+    let scientific = format!("{:e}", x);
+    let (mantissa, exponent) = scientific.split_at(scientific.find('e').expect("no exponent"));
+    let digits: String = mantissa.chars().filter(|&c| c != '.').collect();
+    let k = digits.len() as i32;
+    let n = exponent[1..].parse::<i32>().expect("invalid exponent") + 1;
+
+    if k <= n && n <= 21 {
+        format!("{}{}", digits, "0".repeat((n - k) as usize))
+    } else if 0 < n && n <= 21 {
+        format!("{}.{}", &digits[..n as usize], &digits[n as usize..])
+    } else if -6 < n && n <= 0 {
+        format!("0.{}{}", "0".repeat(-n as usize), digits)
+    } else {
+        let sign = if n - 1 < 0 { '-' } else { '+' };
+        let (first, rest) = digits.split_at(1);
+        if rest.is_empty() {
+            format!("{}e{}{}", first, sign, (n - 1).abs())
+        } else {
+            format!("{}.{}e{}{}", first, rest, sign, (n - 1).abs())
+        }
+    }
+}
+
 /// `Number.prototype.toString( [radix] )`
 ///
 /// The `toString()` method returns a string representing the specified Number object.
@@ -316,10 +366,8 @@ pub fn to_string(this: &mut Value, args: &[Value], _ctx: &mut Interpreter) -> Re
     }
 
     // 5. If radixNumber = 10, return ! ToString(x).
-    // This part should use exponential notations for long integer numbers commented tests
     if radix_number == 10 {
-        // return Ok(to_value(format!("{}", to_number(this).to_num())));
-        return Ok(Value::from(format!("{}", x)));
+        return Ok(Value::from(number_to_string(x)));
     }
 
     // This is a Optimization from the v8 source code to print values that can fit in a single character
@@ -348,6 +396,304 @@ pub fn value_of(this: &mut Value, _args: &[Value], _ctx: &mut Interpreter) -> Re
     Ok(to_number(this))
 }
 
+/// Parses the digits of an integer in the given radix, or returns `None` if `digits` is empty or
+/// has a character which isn't a digit of the radix.
+fn parse_digits(digits: &str, radix: u32) -> Option<f64> {
+    if digits.is_empty() {
+        return None;
+    }
+    if radix == 10 {
+        // The standard library rounds long decimal integers correctly
+        return digits.parse::<f64>().ok();
+    }
+    let mut value = 0.0;
+    for c in digits.chars() {
+        value = value * f64::from(radix) + f64::from(c.to_digit(radix)?);
+    }
+    Some(value)
+}
+
+/// Returns the length of the longest prefix of `string` which is a `StrUnsignedDecimalLiteral`,
+/// `Infinity` excluded, or `0` if there is none.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#prod-StrUnsignedDecimalLiteral
+fn decimal_literal_prefix(string: &str) -> usize {
+    let bytes = string.as_bytes();
+    let digits_from = |start: usize| {
+        bytes[start..]
+            .iter()
+            .take_while(|byte| byte.is_ascii_digit())
+            .count()
+    };
+
+    let integer_digits = digits_from(0);
+    let mut end = integer_digits;
+    let mut fraction_digits = 0;
+    if bytes.get(end) == Some(&b'.') {
+        fraction_digits = digits_from(end + 1);
+        end += 1 + fraction_digits;
+    }
+    if integer_digits == 0 && fraction_digits == 0 {
+        return 0;
+    }
+
+    // The exponent is only part of the literal if it has digits
+    if let Some(b'e') | Some(b'E') = bytes.get(end) {
+        let mut exponent = end + 1;
+        if let Some(b'+') | Some(b'-') = bytes.get(exponent) {
+            exponent += 1;
+        }
+        let exponent_digits = digits_from(exponent);
+        if exponent_digits > 0 {
+            end = exponent + exponent_digits;
+        }
+    }
+    end
+}
+
+/// Parses a decimal literal found by `decimal_literal_prefix`.
+fn parse_decimal_literal(literal: &str) -> f64 {
+    // A literal like `1.` or `.5` is completed, so that it has digits around the point
+    let mut literal = literal.replace(".e", ".0e").replace(".E", ".0E");
+    if literal.starts_with('.') {
+        literal.insert(0, '0');
+    }
+    if literal.ends_with('.') {
+        literal.push('0');
+    }
+    literal.parse::<f64>().unwrap_or(f64::NAN)
+}
+
+/// The abstract operation `StringToNumber`, which converts a string to a number following the
+/// grammar of `StringNumericLiteral`, or returns `NaN` if the string doesn't match it.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-stringtonumber
+pub(crate) fn string_to_number(string: &JsString) -> f64 {
+    // Numeric literals are ASCII, other characters are replaced and make the string invalid
+    let trimmed = trim_string(string, true, true).to_std_string_lossy();
+    if trimmed.is_empty() {
+        return 0.0;
+    }
+
+    let bytes = trimmed.as_bytes();
+    if bytes.len() > 2 && bytes[0] == b'0' {
+        let radix = match bytes[1] {
+            b'x' | b'X' => 16,
+            b'o' | b'O' => 8,
+            b'b' | b'B' => 2,
+            _ => 0,
+        };
+        if radix != 0 {
+            return parse_digits(&trimmed[2..], radix).unwrap_or(f64::NAN);
+        }
+    }
+
+    let (sign, unsigned) = match bytes[0] {
+        b'+' => (1.0, &trimmed[1..]),
+        b'-' => (-1.0, &trimmed[1..]),
+        _ => (1.0, &trimmed[..]),
+    };
+    if unsigned == "Infinity" {
+        return sign * f64::INFINITY;
+    }
+    if decimal_literal_prefix(unsigned) != unsigned.len() {
+        return f64::NAN;
+    }
+    sign * parse_decimal_literal(unsigned)
+}
+
+/// `parseInt( string, radix )`
+///
+/// The `parseInt()` function parses a string argument and returns an integer of the specified
+/// radix. It is also available as `Number.parseInt`.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-parseint-string-radix
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/parseInt
+pub fn parse_int(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let input = to_js_string(args.get(0).unwrap_or(&Value::undefined()), ctx)?;
+    let trimmed = trim_string(&input, true, false).to_std_string_lossy();
+
+    let (sign, mut digits) = match trimmed.as_bytes().first() {
+        Some(b'-') => (-1.0, &trimmed[1..]),
+        Some(b'+') => (1.0, &trimmed[1..]),
+        _ => (1.0, &trimmed[..]),
+    };
+
+    let radix = modulo(
+        ctx.to_number(args.get(1).unwrap_or(&Value::undefined()))?,
+        32,
+    ) as u32 as i32;
+    let mut strip_prefix = true;
+    let mut radix = match radix {
+        0 => 10,
+        2..=36 => {
+            strip_prefix = radix == 16;
+            radix as u32
+        }
+        _ => return Ok(Value::from(f64::NAN)),
+    };
+    if strip_prefix && (digits.starts_with("0x") || digits.starts_with("0X")) {
+        digits = &digits[2..];
+        radix = 16;
+    }
+
+    let end = digits
+        .char_indices()
+        .find(|(_, c)| !c.is_digit(radix))
+        .map_or(digits.len(), |(index, _)| index);
+    match parse_digits(&digits[..end], radix) {
+        Some(value) => Ok(Value::from(sign * value)),
+        None => Ok(Value::from(f64::NAN)),
+    }
+}
+
+/// `parseFloat( string )`
+///
+/// The `parseFloat()` function parses the longest prefix of a string which is a decimal literal.
+/// It is also available as `Number.parseFloat`.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-parsefloat-string
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/parseFloat
+pub fn parse_float(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let input = to_js_string(args.get(0).unwrap_or(&Value::undefined()), ctx)?;
+    let trimmed = trim_string(&input, true, false).to_std_string_lossy();
+
+    let (sign, unsigned) = match trimmed.as_bytes().first() {
+        Some(b'-') => (-1.0, &trimmed[1..]),
+        Some(b'+') => (1.0, &trimmed[1..]),
+        _ => (1.0, &trimmed[..]),
+    };
+    if unsigned.starts_with("Infinity") {
+        return Ok(Value::from(sign * f64::INFINITY));
+    }
+    match decimal_literal_prefix(unsigned) {
+        0 => Ok(Value::from(f64::NAN)),
+        end => Ok(Value::from(sign * parse_decimal_literal(&unsigned[..end]))),
+    }
+}
+
+/// `isFinite( number )`
+///
+/// The global `isFinite()` function determines whether the passed value, converted to a number,
+/// is a finite number.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-isfinite-number
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/isFinite
+pub fn global_is_finite(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let number = ctx.to_number(args.get(0).unwrap_or(&Value::undefined()))?;
+    Ok(Value::from(number.is_finite()))
+}
+
+/// `isNaN( number )`
+///
+/// The global `isNaN()` function determines whether the passed value, converted to a number, is
+/// `NaN`.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-isnan-number
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/isNaN
+pub fn global_is_nan(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let number = ctx.to_number(args.get(0).unwrap_or(&Value::undefined()))?;
+    Ok(Value::from(number.is_nan()))
+}
+
+/// Returns the argument if it is a number, without converting other values.
+fn number_arg(args: &[Value]) -> Option<f64> {
+    match args.get(0).map(|value| value.data()) {
+        Some(ValueData::Rational(number)) => Some(*number),
+        Some(ValueData::Integer(number)) => Some(f64::from(*number)),
+        _ => None,
+    }
+}
+
+/// `Number.isFinite( number )`
+///
+/// The `Number.isFinite()` method determines whether the passed value is a finite number, other
+/// values aren't converted.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-number.isfinite
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Number/isFinite
+pub fn is_finite(_: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValue {
+    Ok(Value::from(number_arg(args).map_or(false, f64::is_finite)))
+}
+
+/// `Number.isInteger( number )`
+///
+/// The `Number.isInteger()` method determines whether the passed value is an integral number.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-number.isinteger
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Number/isInteger
+pub fn is_integer(_: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValue {
+    Ok(Value::from(number_arg(args).map_or(false, is_integral)))
+}
+
+/// `Number.isNaN( number )`
+///
+/// The `Number.isNaN()` method determines whether the passed value is `NaN`, other values aren't
+/// converted.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-number.isnan
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Number/isNaN
+pub fn is_nan(_: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValue {
+    Ok(Value::from(number_arg(args).map_or(false, f64::is_nan)))
+}
+
+/// `Number.isSafeInteger( number )`
+///
+/// The `Number.isSafeInteger()` method determines whether the passed value is an integral number
+/// which can be represented exactly, between `-(2^53 - 1)` and `2^53 - 1`.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-number.issafeinteger
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Number/isSafeInteger
+pub fn is_safe_integer(_: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValue {
+    Ok(Value::from(number_arg(args).map_or(false, |number| {
+        is_integral(number) && number.abs() <= MAX_SAFE_INTEGER
+    })))
+}
+
+/// Checks if a number is finite and has no fractional part.
+#[allow(clippy::float_cmp)]
+fn is_integral(number: f64) -> bool {
+    number.is_finite() && number.trunc() == number
+}
+
 /// Create a new `Number` object
 pub fn create(global: &Value) -> Value {
     let prototype = Value::new_object(Some(global));
@@ -360,13 +706,51 @@ pub fn create(global: &Value) -> Value {
     make_builtin_fn!(to_string, named "toString", with length 1, of prototype);
     make_builtin_fn!(value_of, named "valueOf", of prototype);
 
-    make_constructor_fn!(make_number, call_number, global, prototype)
+    let number = make_constructor_fn!(make_number, call_number, global, prototype);
+
+    make_builtin_fn!(is_finite, named "isFinite", with length 1, of number);
+    make_builtin_fn!(is_integer, named "isInteger", with length 1, of number);
+    make_builtin_fn!(is_nan, named "isNaN", with length 1, of number);
+    make_builtin_fn!(is_safe_integer, named "isSafeInteger", with length 1, of number);
+    make_builtin_fn!(parse_float, named "parseFloat", with length 1, of number);
+    make_builtin_fn!(parse_int, named "parseInt", with length 2, of number);
+
+    let constants = [
+        ("EPSILON", f64::EPSILON),
+        ("MAX_SAFE_INTEGER", MAX_SAFE_INTEGER),
+        ("MIN_SAFE_INTEGER", -MAX_SAFE_INTEGER),
+        ("MAX_VALUE", f64::MAX),
+        ("MIN_VALUE", f64::from_bits(1)),
+        ("NaN", f64::NAN),
+        ("NEGATIVE_INFINITY", f64::NEG_INFINITY),
+        ("POSITIVE_INFINITY", f64::INFINITY),
+    ];
+    for &(name, constant) in constants.iter() {
+        let property = Property::new()
+            .value(Value::from(constant))
+            .writable(false)
+            .enumerable(false)
+            .configurable(false);
+        number
+            .as_object_mut()
+            .expect("Number is an object")
+            .define_own_property(Value::from(name), property);
+    }
+
+    number
 }
 
-/// Initialise the `Number` object on the global object.
+/// Initialise the `Number` object, and the global functions dealing with numbers, on the global
+/// object.
 #[inline]
 pub fn init(global: &Value) {
-    global.set_field_slice("Number", create(global));
+    let number = create(global);
+    // The global `parseFloat` and `parseInt` are the same function objects as the static methods
+    global.set_field_slice("parseFloat", number.get_field_slice("parseFloat"));
+    global.set_field_slice("parseInt", number.get_field_slice("parseInt"));
+    make_builtin_fn!(global_is_finite, named "isFinite", with length 1, of global);
+    make_builtin_fn!(global_is_nan, named "isNaN", with length 1, of global);
+    global.set_field_slice("Number", number);
 }
 
 /// The abstract operation Number::equal takes arguments
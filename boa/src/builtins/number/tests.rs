@@ -184,6 +184,17 @@ fn to_string() {
         "0.000001",
         &forward(&mut engine, "Number(0.000001).toString()")
     );
+    assert_eq!(
+        "1e-7",
+        &forward(&mut engine, "Number(0.0000001).toString()")
+    );
+    assert_eq!("1.5e-7", &forward(&mut engine, "Number(1.5e-7).toString()"));
+    assert_eq!(
+        "100000000000000000000",
+        &forward(&mut engine, "Number(1e20).toString()")
+    );
+    assert_eq!("1e+21", &forward(&mut engine, "Number(1e21).toString()"));
+    assert_eq!("0", &forward(&mut engine, "Number(-0).toString()"));
     assert_eq!("NaN", &forward(&mut engine, "Number(NaN).toString(16)"));
     assert_eq!(
         "Infinity",
@@ -426,3 +437,143 @@ fn same_value_zero() {
     assert_eq!(super::same_value_zero(0.0, f64::NAN), false);
     assert_eq!(super::equals(1.0, 1.0), true);
 }
+
+#[test]
+fn string_to_number() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+
+    assert_eq!(forward(&mut engine, "Number('  42\\n')"), "42");
+    assert_eq!(forward(&mut engine, "Number('')"), "0");
+    assert_eq!(forward(&mut engine, "Number(' \\t ')"), "0");
+    assert_eq!(forward(&mut engine, "Number('0x1F')"), "31");
+    assert_eq!(forward(&mut engine, "Number('0b101')"), "5");
+    assert_eq!(forward(&mut engine, "Number('0o17')"), "15");
+    assert_eq!(forward(&mut engine, "Number('-0x1F')"), "NaN");
+    assert_eq!(forward(&mut engine, "Number('.5')"), "0.5");
+    assert_eq!(forward(&mut engine, "Number('5.')"), "5");
+    assert_eq!(forward(&mut engine, "Number('1e3')"), "1000");
+    assert_eq!(forward(&mut engine, "Number('-Infinity')"), "-Infinity");
+    assert_eq!(forward(&mut engine, "Number('infinity')"), "NaN");
+    assert_eq!(forward(&mut engine, "Number('inf')"), "NaN");
+    assert_eq!(forward(&mut engine, "Number('1_000')"), "NaN");
+    assert_eq!(forward(&mut engine, "Number('12px')"), "NaN");
+    assert_eq!(forward(&mut engine, "1 / Number('-0')"), "-Infinity");
+    assert_eq!(forward(&mut engine, "'3' * '4'"), "12");
+}
+
+#[test]
+fn parse_int() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+
+    assert_eq!(forward(&mut engine, "parseInt('  123abc')"), "123");
+    assert_eq!(forward(&mut engine, "parseInt('-0x1A')"), "-26");
+    assert_eq!(forward(&mut engine, "parseInt('0x1A', 16)"), "26");
+    assert_eq!(forward(&mut engine, "parseInt('0x1A', 10)"), "0");
+    assert_eq!(forward(&mut engine, "parseInt('z', 36)"), "35");
+    assert_eq!(forward(&mut engine, "parseInt('101', 2)"), "5");
+    assert_eq!(forward(&mut engine, "parseInt('12', 1)"), "NaN");
+    assert_eq!(forward(&mut engine, "parseInt('12', 37)"), "NaN");
+    assert_eq!(forward(&mut engine, "parseInt('')"), "NaN");
+    assert_eq!(forward(&mut engine, "parseInt('1e3')"), "1");
+    assert_eq!(forward(&mut engine, "parseInt(15.99)"), "15");
+    assert_eq!(forward(&mut engine, "1 / parseInt('-0')"), "-Infinity");
+    assert_eq!(forward(&mut engine, "Number.parseInt === parseInt"), "true");
+}
+
+#[test]
+fn parse_float() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+
+    assert_eq!(forward(&mut engine, "parseFloat('  3.14more')"), "3.14");
+    assert_eq!(forward(&mut engine, "parseFloat('-.5e2x')"), "-50");
+    assert_eq!(forward(&mut engine, "parseFloat('1e')"), "1");
+    assert_eq!(forward(&mut engine, "parseFloat('Infinityx')"), "Infinity");
+    assert_eq!(forward(&mut engine, "parseFloat('0x10')"), "0");
+    assert_eq!(forward(&mut engine, "parseFloat('.')"), "NaN");
+    assert_eq!(forward(&mut engine, "parseFloat('abc')"), "NaN");
+    assert_eq!(
+        forward(&mut engine, "Number.parseFloat === parseFloat"),
+        "true"
+    );
+}
+
+#[test]
+fn number_statics() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+
+    assert_eq!(forward(&mut engine, "isNaN('abc')"), "true");
+    assert_eq!(forward(&mut engine, "Number.isNaN('abc')"), "false");
+    assert_eq!(forward(&mut engine, "Number.isNaN(NaN)"), "true");
+    assert_eq!(forward(&mut engine, "isFinite('12')"), "true");
+    assert_eq!(forward(&mut engine, "Number.isFinite('12')"), "false");
+    assert_eq!(forward(&mut engine, "Number.isFinite(Infinity)"), "false");
+    assert_eq!(forward(&mut engine, "Number.isInteger(5)"), "true");
+    assert_eq!(forward(&mut engine, "Number.isInteger(5.5)"), "false");
+    assert_eq!(
+        forward(&mut engine, "Number.isSafeInteger(Number.MAX_SAFE_INTEGER)"),
+        "true"
+    );
+    assert_eq!(
+        forward(
+            &mut engine,
+            "Number.isSafeInteger(Number.MAX_SAFE_INTEGER + 1)"
+        ),
+        "false"
+    );
+    assert_eq!(
+        forward(&mut engine, "Number.MIN_SAFE_INTEGER"),
+        "-9007199254740991"
+    );
+    assert_eq!(forward(&mut engine, "Number.EPSILON > 0"), "true");
+    assert_eq!(forward(&mut engine, "Number.MIN_VALUE"), "5e-324");
+    assert_eq!(
+        forward(&mut engine, "Number.MAX_VALUE"),
+        "1.7976931348623157e+308"
+    );
+    assert_eq!(
+        forward(&mut engine, "Number.NEGATIVE_INFINITY"),
+        "-Infinity"
+    );
+}
+
+#[test]
+fn number_constants() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        Number.NaN = 1;
+        Number.MAX_SAFE_INTEGER = 1;
+        var deleted = delete Number.EPSILON;
+        var descriptor = Object.getOwnPropertyDescriptor(Number, 'POSITIVE_INFINITY');
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "Number.NaN"), "NaN");
+    assert_eq!(
+        forward(&mut engine, "Number.MAX_SAFE_INTEGER"),
+        "9007199254740991"
+    );
+    assert_eq!(forward(&mut engine, "deleted"), "false");
+    assert_eq!(forward(&mut engine, "Number.EPSILON > 0"), "true");
+    assert_eq!(forward(&mut engine, "descriptor.writable"), "false");
+    assert_eq!(forward(&mut engine, "descriptor.enumerable"), "false");
+    assert_eq!(forward(&mut engine, "descriptor.configurable"), "false");
+    assert_eq!(
+        forward(&mut engine, "Object.keys(Number).indexOf('NaN')"),
+        "-1"
+    );
+}
+
+#[test]
+fn nan_is_a_global_property() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    assert_eq!(forward(&mut engine, "delete NaN"), "false");
+    assert_eq!(forward(&mut engine, "NaN"), "NaN");
+    assert_eq!(forward(&mut engine, "globalThis.NaN"), "NaN");
+    assert_eq!(forward(&mut engine, "var o = {}; o.NaN = 1; o.NaN"), "1");
+    assert_eq!(forward(&mut engine, "({ NaN: 2 }).NaN"), "2");
+}
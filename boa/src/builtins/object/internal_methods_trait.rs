@@ -13,6 +13,20 @@ use crate::builtins::{
 use std::borrow::Borrow;
 use std::ops::Deref;
 
+/// The integrity levels an object can be locked down to.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-setintegritylevel
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum IntegrityLevel {
+    /// No properties can be added or removed.
+    Sealed,
+    /// Like sealed, and additionally no data property can be written to.
+    Frozen,
+}
+
 /// Here lies the internal methods for ordinary objects.
 ///
 /// Most objects make use of these methods, including exotic objects like functions.
@@ -30,9 +44,19 @@ pub trait ObjectInternalMethods {
     ///
     /// [spec]: https://tc39.es/ecma262/#sec-ordinary-object-internal-methods-and-internal-slots-hasproperty-p
     fn has_property(&self, val: &Value) -> bool {
+        self.ordinary_has_property(val)
+    }
+
+    /// The `[[HasProperty]]` of ordinary objects, which exotic objects fall back to.
+    ///
+    /// More information:
+    ///  - [ECMAScript reference][spec]
+    ///
+    /// [spec]: https://tc39.es/ecma262/#sec-ordinaryhasproperty
+    fn ordinary_has_property(&self, val: &Value) -> bool {
         debug_assert!(Property::is_property_key(val));
         let prop = self.get_own_property(val);
-        if prop.value.is_none() {
+        if prop.is_none() {
             let parent: Value = self.get_prototype_of();
             if !parent.is_null() {
                 // the parent value variant should be an object
@@ -75,18 +99,23 @@ pub trait ObjectInternalMethods {
 
     /// Delete property.
     fn delete(&mut self, prop_key: &Value) -> bool {
+        self.ordinary_delete(prop_key)
+    }
+
+    /// The `[[Delete]]` of ordinary objects, which exotic objects fall back to.
+    ///
+    /// More information:
+    ///  - [ECMAScript reference][spec]
+    ///
+    /// [spec]: https://tc39.es/ecma262/#sec-ordinarydelete
+    fn ordinary_delete(&mut self, prop_key: &Value) -> bool {
         debug_assert!(Property::is_property_key(prop_key));
         let desc = self.get_own_property(prop_key);
-        if desc
-            .value
-            .clone()
-            .expect("unable to get value")
-            .is_undefined()
-        {
+        if desc.is_none() {
             return true;
         }
-        if desc.configurable.expect("unable to get value") {
-            self.remove_property(&prop_key.to_string());
+        if desc.configurable.unwrap_or(false) {
+            self.remove_property(prop_key);
             return true;
         }
 
@@ -119,12 +148,7 @@ pub trait ObjectInternalMethods {
             return desc.value.clone().expect("failed to extract value");
         }
 
-        let getter = desc.get.clone();
-        if getter.is_none() || getter.expect("Failed to get object").is_undefined() {
-            return Value::undefined();
-        }
-
-        // TODO!!!!! Call getter from here
+        // Running a getter needs an interpreter, `Interpreter::get_field` calls it instead
         Value::undefined()
     }
 
@@ -149,36 +173,61 @@ pub trait ObjectInternalMethods {
         }
         // [3]
         if own_desc.is_data_descriptor() {
-            if !own_desc.writable.unwrap() {
+            if !own_desc.writable.unwrap_or(false) {
                 return false;
             }
 
             // Change value on the current descriptor
             own_desc = own_desc.value(val);
-            return self.define_own_property(field.to_string(), own_desc);
+            return self.define_own_property(field, own_desc);
         }
         // [4]
+        // Running a setter needs an interpreter, `Interpreter::set_field` calls it instead
         debug_assert!(own_desc.is_accessor_descriptor());
-        match own_desc.set {
-            None => false,
-            Some(_) => {
-                unimplemented!();
-            }
-        }
+        false
     }
 
-    fn define_own_property(&mut self, property_key: String, desc: Property) -> bool {
-        let mut current = self.get_own_property(&Value::from(property_key.to_string()));
+    /// Define or update an own property.
+    ///
+    /// More information:
+    ///  - [ECMAScript reference][spec]
+    ///
+    /// [spec]: https://tc39.es/ecma262/#sec-ordinary-object-internal-methods-and-internal-slots-defineownproperty-p-desc
+    fn define_own_property(&mut self, property_key: Value, desc: Property) -> bool {
+        self.ordinary_define_own_property(property_key, desc)
+    }
+
+    /// The `[[DefineOwnProperty]]` of ordinary objects, which exotic objects fall back to.
+    ///
+    /// More information:
+    ///  - [ECMAScript reference][spec]
+    ///
+    /// [spec]: https://tc39.es/ecma262/#sec-ordinarydefineownproperty
+    fn ordinary_define_own_property(&mut self, property_key: Value, desc: Property) -> bool {
+        debug_assert!(Property::is_property_key(&property_key));
+        let mut current = self.get_own_property(&property_key);
         let extensible = self.is_extensible();
 
         // https://tc39.es/ecma262/#sec-validateandapplypropertydescriptor
-        // There currently isn't a property, lets create a new one
-        if current.value.is_none() || current.value.as_ref().expect("failed").is_undefined() {
+        // There currently isn't a property, lets create a new one with the absent fields set to
+        // their default values
+        if current.is_none() {
             if !extensible {
                 return false;
             }
 
-            self.insert_property(property_key, desc);
+            let mut new_desc = desc;
+            if new_desc.is_accessor_descriptor() {
+                new_desc.get.get_or_insert_with(Value::undefined);
+                new_desc.set.get_or_insert_with(Value::undefined);
+            } else {
+                new_desc.value.get_or_insert_with(Value::undefined);
+                new_desc.writable = new_desc.writable.or(Some(false));
+            }
+            new_desc.enumerable = new_desc.enumerable.or(Some(false));
+            new_desc.configurable = new_desc.configurable.or(Some(false));
+
+            self.insert_property(&property_key, new_desc);
             return true;
         }
         // If every field is absent we don't need to set anything
@@ -193,11 +242,7 @@ pub trait ObjectInternalMethods {
             }
 
             if desc.enumerable.is_some()
-                && (desc.enumerable.as_ref().expect("unable to get prop desc")
-                    != current
-                        .enumerable
-                        .as_ref()
-                        .expect("unable to get prop desc"))
+                && desc.enumerable != Some(current.enumerable.unwrap_or(false))
             {
                 return false;
             }
@@ -208,7 +253,7 @@ pub trait ObjectInternalMethods {
             // 6
         } else if current.is_data_descriptor() != desc.is_data_descriptor() {
             // a
-            if !current.configurable.expect("unable to get prop desc") {
+            if !current.configurable.unwrap_or(false) {
                 return false;
             }
             // b
@@ -216,56 +261,42 @@ pub trait ObjectInternalMethods {
                 // Convert to accessor
                 current.value = None;
                 current.writable = None;
+                current.get = Some(Value::undefined());
+                current.set = Some(Value::undefined());
             } else {
                 // c
                 // convert to data
                 current.get = None;
                 current.set = None;
+                current.value = Some(Value::undefined());
+                current.writable = Some(false);
             }
-
-            self.insert_property(property_key.clone(), current);
         // 7
         } else if current.is_data_descriptor() && desc.is_data_descriptor() {
             // a
-            if !current.configurable.expect("unable to get prop desc")
-                && !current.writable.expect("unable to get prop desc")
-            {
-                if desc.writable.is_some() && desc.writable.expect("unable to get prop desc") {
+            if !current.configurable.unwrap_or(false) && !current.writable.unwrap_or(false) {
+                if desc.writable == Some(true) {
                     return false;
                 }
 
-                if desc.value.is_some()
-                    && !same_value(
-                        &desc.value.clone().unwrap(),
-                        &current.value.clone().unwrap(),
-                        false,
-                    )
-                {
-                    return false;
+                if let (Some(ref new), Some(ref old)) = (&desc.value, &current.value) {
+                    if !same_value(new, old, false) {
+                        return false;
+                    }
                 }
 
                 return true;
             }
         // 8
-        } else {
-            if !current.configurable.unwrap() {
-                if desc.set.is_some()
-                    && !same_value(
-                        &desc.set.clone().unwrap(),
-                        &current.set.clone().unwrap(),
-                        false,
-                    )
-                {
+        } else if !current.configurable.unwrap_or(false) {
+            if let (Some(ref new), Some(ref old)) = (&desc.set, &current.set) {
+                if !same_value(new, old, false) {
                     return false;
                 }
+            }
 
-                if desc.get.is_some()
-                    && !same_value(
-                        &desc.get.clone().unwrap(),
-                        &current.get.clone().unwrap(),
-                        false,
-                    )
-                {
+            if let (Some(ref new), Some(ref old)) = (&desc.get, &current.get) {
+                if !same_value(new, old, false) {
                     return false;
                 }
             }
@@ -273,10 +304,83 @@ pub trait ObjectInternalMethods {
             return true;
         }
         // 9
-        self.insert_property(property_key, desc);
+        // Only the fields present in the descriptor overwrite the current ones
+        if desc.value.is_some() {
+            current.value = desc.value.clone();
+        }
+        if desc.writable.is_some() {
+            current.writable = desc.writable;
+        }
+        if desc.get.is_some() {
+            current.get = desc.get.clone();
+        }
+        if desc.set.is_some() {
+            current.set = desc.set.clone();
+        }
+        if desc.enumerable.is_some() {
+            current.enumerable = desc.enumerable;
+        }
+        if desc.configurable.is_some() {
+            current.configurable = desc.configurable;
+        }
+        self.insert_property(&property_key, current);
         true
     }
 
+    /// Collects the keys of the own properties: integer indices in ascending order, then the
+    /// other strings and finally the symbols.
+    ///
+    /// More information:
+    ///  - [ECMAScript reference][spec]
+    ///
+    /// [spec]: https://tc39.es/ecma262/#sec-ordinary-object-internal-methods-and-internal-slots-ownpropertykeys
+    fn own_property_keys(&self) -> Vec<Value>;
+
+    /// Seal or freeze the object.
+    ///
+    /// More information:
+    ///  - [ECMAScript reference][spec]
+    ///
+    /// [spec]: https://tc39.es/ecma262/#sec-setintegritylevel
+    fn set_integrity_level(&mut self, level: IntegrityLevel) -> bool {
+        if !self.prevent_extensions() {
+            return false;
+        }
+
+        for key in self.own_property_keys() {
+            let mut desc = Property::new().configurable(false);
+            if level == IntegrityLevel::Frozen && self.get_own_property(&key).is_data_descriptor() {
+                desc = desc.writable(false);
+            }
+            if !self.define_own_property(key, desc) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Check whether the object is sealed or frozen.
+    ///
+    /// More information:
+    ///  - [ECMAScript reference][spec]
+    ///
+    /// [spec]: https://tc39.es/ecma262/#sec-testintegritylevel
+    fn test_integrity_level(&self, level: IntegrityLevel) -> bool {
+        if self.is_extensible() {
+            return false;
+        }
+
+        self.own_property_keys().iter().all(|key| {
+            let desc = self.get_own_property(key);
+            if desc.configurable.unwrap_or(false) {
+                return false;
+            }
+            level == IntegrityLevel::Sealed
+                || !desc.is_data_descriptor()
+                || !desc.writable.unwrap_or(false)
+        })
+    }
+
     /// https://tc39.es/ecma262/#sec-ordinary-object-internal-methods-and-internal-slots-getownproperty-p
     /// The specification returns a Property Descriptor or Undefined. These are 2 separate types and we can't do that here.
     fn get_own_property(&self, prop: &Value) -> Property;
@@ -295,7 +399,7 @@ pub trait ObjectInternalMethods {
 
     fn set_internal_slot(&mut self, name: &str, val: Value);
 
-    fn insert_property(&mut self, name: String, p: Property);
+    fn insert_property(&mut self, name: &Value, p: Property);
 
-    fn remove_property(&mut self, name: &str);
+    fn remove_property(&mut self, name: &Value);
 }
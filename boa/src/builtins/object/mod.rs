@@ -15,11 +15,16 @@
 
 use crate::{
     builtins::{
+        array,
         function::Function,
         property::Property,
-        value::{same_value, ResultValue, Value, ValueData},
+        string,
+        symbol::{Symbol, WellKnownSymbol},
+        typed_array,
+        value::{same_value, JsString, ResultValue, Value, ValueData},
     },
     exec::Interpreter,
+    realm::RealmHandle,
 };
 use gc::{unsafe_empty_trace, Finalize, Trace};
 use rustc_hash::FxHashMap;
@@ -29,11 +34,16 @@ use std::{
     ops::Deref,
 };
 
-pub use internal_methods_trait::ObjectInternalMethods;
+pub use internal_methods_trait::{IntegrityLevel, ObjectInternalMethods};
 pub use internal_state::{InternalState, InternalStateCell};
+pub use property_map::PropertyMap;
 
 pub mod internal_methods_trait;
 mod internal_state;
+mod property_map;
+
+#[cfg(test)]
+mod tests;
 
 /// Static `prototype`, usually set on constructors as a key to point to their respective prototype object.
 pub static PROTOTYPE: &str = "prototype";
@@ -49,15 +59,17 @@ pub struct Object {
     /// Intfiernal Slots
     pub internal_slots: FxHashMap<String, Value>,
     /// Properties
-    pub properties: FxHashMap<String, Property>,
+    pub properties: PropertyMap<JsString, Property>,
     /// Symbol Properties
-    pub sym_properties: FxHashMap<i32, Property>,
+    pub sym_properties: PropertyMap<Symbol, Property>,
     /// Some rust object that stores internal state
     pub state: Option<InternalStateCell>,
     /// [[Call]]
     pub call: Option<Function>,
     /// [[Construct]]
     pub construct: Option<Function>,
+    /// The realm of which this object is the intrinsics or the global object
+    pub(crate) realm: Option<RealmHandle>,
 }
 
 impl Debug for Object {
@@ -90,37 +102,83 @@ impl ObjectInternalMethods for Object {
     /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Object/setPrototypeOf
     fn set_prototype_of(&mut self, val: Value) -> bool {
         debug_assert!(val.is_object() || val.is_null());
-        let current = self.get_internal_slot(PROTOTYPE);
+        let current = self.get_internal_slot(INSTANCE_PROTOTYPE);
         if same_value(&current, &val, false) {
             return true;
         }
-        let extensible = self.get_internal_slot("extensible");
-        if extensible.is_null() {
+        if !self.is_extensible() {
             return false;
         }
-        let mut p = val.clone();
-        let mut done = false;
-        while !done {
-            if p.is_null() {
-                done = true
-            } else if same_value(&Value::from(self.clone()), &p, false) {
-                return false;
-            } else {
-                p = p.get_internal_slot(PROTOTYPE);
-            }
-        }
-        self.set_internal_slot(PROTOTYPE, val);
+        // The object can't see its own identity, so `Object.setPrototypeOf` rejects cycles
+        // before calling this
+        self.set_internal_slot(INSTANCE_PROTOTYPE, val);
         true
     }
 
+    /// Integer indices of typed arrays exist exactly when they are in bounds, whatever the
+    /// prototype chain holds.
+    ///
+    /// More information:
+    ///  - [ECMAScript reference][spec]
+    ///
+    /// [spec]: https://tc39.es/ecma262/#sec-integer-indexed-exotic-objects-hasproperty-p
+    fn has_property(&self, val: &Value) -> bool {
+        typed_array::has_element(self, val).unwrap_or_else(|| self.ordinary_has_property(val))
+    }
+
+    /// Typed arrays only accept writable, enumerable and configurable data descriptors for
+    /// their integer indexed elements.
+    ///
+    /// More information:
+    ///  - [ECMAScript reference][spec]
+    ///
+    /// [spec]: https://tc39.es/ecma262/#sec-integer-indexed-exotic-objects-defineownproperty-p-desc
+    fn define_own_property(&mut self, property_key: Value, desc: Property) -> bool {
+        match typed_array::define_element(self, &property_key, &desc) {
+            Some(defined) => defined,
+            None => self.ordinary_define_own_property(property_key, desc),
+        }
+    }
+
+    /// The integer indexed elements of typed arrays can't be deleted.
+    ///
+    /// More information:
+    ///  - [ECMAScript reference][spec]
+    ///
+    /// [spec]: https://tc39.es/ecma262/#sec-integer-indexed-exotic-objects-delete-p
+    fn delete(&mut self, prop_key: &Value) -> bool {
+        typed_array::delete_element(self, prop_key)
+            .unwrap_or_else(|| self.ordinary_delete(prop_key))
+    }
+
     /// Helper function for property insertion.
-    fn insert_property(&mut self, name: String, p: Property) {
-        self.properties.insert(name, p);
+    fn insert_property(&mut self, name: &Value, p: Property) {
+        match name.data() {
+            ValueData::Symbol(ref sym) => {
+                self.sym_properties.insert(sym.clone(), p);
+            }
+            ValueData::String(ref name) => {
+                self.properties.insert(name.clone(), p);
+            }
+            _ => {
+                self.properties.insert(name.to_string().into(), p);
+            }
+        }
     }
 
     /// Helper function for property removal.
-    fn remove_property(&mut self, name: &str) {
-        self.properties.remove(name);
+    fn remove_property(&mut self, name: &Value) {
+        match name.data() {
+            ValueData::Symbol(ref sym) => {
+                self.sym_properties.remove(sym);
+            }
+            ValueData::String(ref name) => {
+                self.properties.remove(name);
+            }
+            _ => {
+                self.properties.remove(&JsString::from(name.to_string()));
+            }
+        }
     }
 
     /// Helper function to set an internal slot
@@ -146,190 +204,85 @@ impl ObjectInternalMethods for Object {
     /// [spec]: https://tc39.es/ecma262/#sec-ordinary-object-internal-methods-and-internal-slots-getownproperty-p
     fn get_own_property(&self, prop: &Value) -> Property {
         debug_assert!(Property::is_property_key(prop));
+        // The integer indexed elements of typed arrays are read from their buffer
+        if let Some(element) = typed_array::get_own_element(self, prop) {
+            return element;
+        }
         // Prop could either be a String or Symbol
-        match *(*prop) {
+        // If O does not have an own property with key P, return undefined.
+        // In this case we return a new empty Property
+        let property = match *(*prop) {
             ValueData::String(ref st) => {
                 match self.properties.get(st) {
-                    // If O does not have an own property with key P, return undefined.
-                    // In this case we return a new empty Property
-                    None => Property::default(),
-                    Some(ref v) => {
-                        let mut d = Property::default();
-                        if v.is_data_descriptor() {
-                            d.value = v.value.clone();
-                            d.writable = v.writable;
-                        } else {
-                            debug_assert!(v.is_accessor_descriptor());
-                            d.get = v.get.clone();
-                            d.set = v.set.clone();
-                        }
-                        d.enumerable = v.enumerable;
-                        d.configurable = v.configurable;
-                        d
-                    }
-                }
-            }
-            ValueData::Symbol(ref sym) => {
-                let sym_id = (**sym)
-                    .borrow()
-                    .get_internal_slot("SymbolData")
-                    .to_string()
-                    .parse::<i32>()
-                    .expect("Could not get Symbol ID");
-                match self.sym_properties.get(&sym_id) {
-                    // If O does not have an own property with key P, return undefined.
-                    // In this case we return a new empty Property
-                    None => Property::default(),
-                    Some(ref v) => {
-                        let mut d = Property::default();
-                        if v.is_data_descriptor() {
-                            d.value = v.value.clone();
-                            d.writable = v.writable;
-                        } else {
-                            debug_assert!(v.is_accessor_descriptor());
-                            d.get = v.get.clone();
-                            d.set = v.set.clone();
-                        }
-                        d.enumerable = v.enumerable;
-                        d.configurable = v.configurable;
-                        d
+                    Some(v) => v,
+                    // String objects expose their length and code units as read-only properties
+                    None => {
+                        let key = st.to_std_string_lossy();
+                        return self
+                            .string_data()
+                            .and_then(|string| string::get_own_property(&string, &key))
+                            .map(|value| {
+                                Property::new()
+                                    .value(value)
+                                    .writable(false)
+                                    .enumerable(key != "length")
+                                    .configurable(false)
+                            })
+                            .unwrap_or_default();
                     }
                 }
             }
-            _ => Property::default(),
-        }
-    }
+            ValueData::Symbol(ref sym) => match self.sym_properties.get(sym) {
+                Some(v) => v,
+                None => return Property::default(),
+            },
+            _ => return Property::default(),
+        };
 
-    /// Define an own property.
-    ///
-    /// More information:
-    ///  - [ECMAScript reference][spec]
-    ///
-    /// [spec]: https://tc39.es/ecma262/#sec-ordinary-object-internal-methods-and-internal-slots-defineownproperty-p-desc
-    #[allow(clippy::option_unwrap_used)]
-    fn define_own_property(&mut self, property_key: String, desc: Property) -> bool {
-        let mut current = self.get_own_property(&Value::from(property_key.to_string()));
-        let extensible = self.is_extensible();
-
-        // https://tc39.es/ecma262/#sec-validateandapplypropertydescriptor
-        // There currently isn't a property, lets create a new one
-        if current.value.is_none() || current.value.as_ref().expect("failed").is_undefined() {
-            if !extensible {
-                return false;
-            }
-            if desc.value.is_some() && desc.value.clone().unwrap().is_symbol() {
-                let sym_id = desc
-                    .value
-                    .clone()
-                    .unwrap()
-                    .to_string()
-                    .parse::<i32>()
-                    .expect("parsing failed");
-                self.sym_properties.insert(sym_id, desc);
-            } else {
-                self.properties.insert(property_key, desc);
-            }
-            return true;
-        }
-        // If every field is absent we don't need to set anything
-        if desc.is_none() {
-            return true;
+        let mut d = Property::default();
+        if property.is_data_descriptor() {
+            d.value = property.value.clone();
+            d.writable = property.writable;
+        } else {
+            debug_assert!(property.is_accessor_descriptor());
+            d.get = property.get.clone();
+            d.set = property.set.clone();
         }
+        d.enumerable = property.enumerable;
+        d.configurable = property.configurable;
+        d
+    }
 
-        // 4
-        if !current.configurable.unwrap_or(false) {
-            if desc.configurable.is_some() && desc.configurable.unwrap() {
-                return false;
-            }
+    fn own_property_keys(&self) -> Vec<Value> {
+        let mut indices: Vec<u32> = Vec::new();
+        let mut strings = Vec::new();
 
-            if desc.enumerable.is_some()
-                && (desc.enumerable.as_ref().unwrap() != current.enumerable.as_ref().unwrap())
-            {
-                return false;
-            }
+        if let Some(ref string) = self.string_data() {
+            indices.extend(0..string.len() as u32);
+            strings.push(Value::from("length"));
+        }
+        if let Some(length) = typed_array::element_count(self) {
+            indices.extend(0..length as u32);
         }
 
-        // 5
-        if desc.is_generic_descriptor() {
-            // 6
-        } else if current.is_data_descriptor() != desc.is_data_descriptor() {
-            // a
-            if !current.configurable.unwrap() {
-                return false;
-            }
-            // b
-            if current.is_data_descriptor() {
-                // Convert to accessor
-                current.value = None;
-                current.writable = None;
-            } else {
-                // c
-                // convert to data
-                current.get = None;
-                current.set = None;
-            }
-
-            if current.value.is_some() && current.value.clone().unwrap().is_symbol() {
-                let sym_id = current
-                    .value
-                    .clone()
-                    .unwrap()
-                    .to_string()
-                    .parse::<i32>()
-                    .expect("parsing failed");
-                self.sym_properties.insert(sym_id, current);
-            } else {
-                self.properties.insert(property_key.clone(), current);
-            }
-        // 7
-        } else if current.is_data_descriptor() && desc.is_data_descriptor() {
-            // a
-            if !current.configurable.unwrap() && !current.writable.unwrap() {
-                if desc.writable.is_some() && desc.writable.unwrap() {
-                    return false;
-                }
-
-                if desc.value.is_some()
-                    && !same_value(
-                        &desc.value.clone().unwrap(),
-                        &current.value.clone().unwrap(),
-                        false,
-                    )
-                {
-                    return false;
-                }
-
-                return true;
-            }
-        // 8
-        } else {
-            if !current.configurable.unwrap() {
-                if desc.set.is_some()
-                    && !same_value(
-                        &desc.set.clone().unwrap(),
-                        &current.set.clone().unwrap(),
-                        false,
-                    )
-                {
-                    return false;
-                }
-
-                if desc.get.is_some()
-                    && !same_value(
-                        &desc.get.clone().unwrap(),
-                        &current.get.clone().unwrap(),
-                        false,
-                    )
-                {
-                    return false;
+        for key in self.properties.keys() {
+            match key.to_std_string().and_then(|key| key.parse::<u32>().ok()) {
+                // An array index is the canonical form of an integer below 2^32 - 1
+                Some(index) if index != u32::max_value() && *key == *index.to_string() => {
+                    indices.push(index)
                 }
+                _ => strings.push(Value::from(key.clone())),
             }
-
-            return true;
         }
-        // 9
-        self.properties.insert(property_key, desc);
-        true
+        indices.sort_unstable();
+        indices.dedup();
+
+        indices
+            .into_iter()
+            .map(|index| Value::from(index.to_string()))
+            .chain(strings)
+            .chain(self.sym_properties.keys().cloned().map(Value::symbol))
+            .collect()
     }
 }
 
@@ -339,11 +292,12 @@ impl Object {
         let mut object = Self {
             kind: ObjectKind::Ordinary,
             internal_slots: FxHashMap::default(),
-            properties: FxHashMap::default(),
-            sym_properties: FxHashMap::default(),
+            properties: PropertyMap::default(),
+            sym_properties: PropertyMap::default(),
             state: None,
             call: None,
             construct: None,
+            realm: None,
         };
 
         object.set_internal_slot("extensible", Value::from(true));
@@ -355,11 +309,12 @@ impl Object {
         let mut object = Self {
             kind: ObjectKind::Function,
             internal_slots: FxHashMap::default(),
-            properties: FxHashMap::default(),
-            sym_properties: FxHashMap::default(),
+            properties: PropertyMap::default(),
+            sym_properties: PropertyMap::default(),
             state: None,
             call: None,
             construct: None,
+            realm: None,
         };
 
         object.set_internal_slot("extensible", Value::from(true));
@@ -397,11 +352,12 @@ impl Object {
         let mut obj = Self {
             kind: ObjectKind::Boolean,
             internal_slots: FxHashMap::default(),
-            properties: FxHashMap::default(),
-            sym_properties: FxHashMap::default(),
+            properties: PropertyMap::default(),
+            sym_properties: PropertyMap::default(),
             state: None,
             call: None,
             construct: None,
+            realm: None,
         };
 
         obj.internal_slots
@@ -414,11 +370,12 @@ impl Object {
         let mut obj = Self {
             kind: ObjectKind::Number,
             internal_slots: FxHashMap::default(),
-            properties: FxHashMap::default(),
-            sym_properties: FxHashMap::default(),
+            properties: PropertyMap::default(),
+            sym_properties: PropertyMap::default(),
             state: None,
             call: None,
             construct: None,
+            realm: None,
         };
 
         obj.internal_slots
@@ -431,11 +388,12 @@ impl Object {
         let mut obj = Self {
             kind: ObjectKind::String,
             internal_slots: FxHashMap::default(),
-            properties: FxHashMap::default(),
-            sym_properties: FxHashMap::default(),
+            properties: PropertyMap::default(),
+            sym_properties: PropertyMap::default(),
             state: None,
             call: None,
             construct: None,
+            realm: None,
         };
 
         obj.internal_slots
@@ -459,6 +417,17 @@ impl Object {
         }
     }
 
+    /// The `[[StringData]]` of a `String` object.
+    fn string_data(&self) -> Option<JsString> {
+        if self.kind != ObjectKind::String {
+            return None;
+        }
+        match self.internal_slots.get("StringData").map(Value::data) {
+            Some(ValueData::String(ref string)) => Some(string.clone()),
+            _ => None,
+        }
+    }
+
     /// It determines if Object is a callable function with a [[Call]] internal method.
     ///
     /// More information:
@@ -491,6 +460,9 @@ pub enum ObjectKind {
     Ordinary,
     Boolean,
     Number,
+    ArrayBuffer,
+    TypedArray,
+    DataView,
 }
 
 impl Display for ObjectKind {
@@ -507,6 +479,9 @@ impl Display for ObjectKind {
                 Self::Ordinary => "Ordinary",
                 Self::Boolean => "Boolean",
                 Self::Number => "Number",
+                Self::ArrayBuffer => "ArrayBuffer",
+                Self::TypedArray => "TypedArray",
+                Self::DataView => "DataView",
             }
         )
     }
@@ -531,7 +506,7 @@ pub fn make_object(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> Resu
             return Ok(Value::object(Object::from(arg).unwrap()));
         }
     }
-    let global = &ctx.realm.global_obj;
+    let global = &ctx.realm.intrinsics;
 
     let object = Value::new_object(Some(global));
 
@@ -539,26 +514,616 @@ pub fn make_object(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> Resu
 }
 
 /// Get the `prototype` of an object.
-pub fn get_prototype_of(_: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValue {
-    let obj = args.get(0).expect("Cannot get object");
-    Ok(obj.get_field_slice(INSTANCE_PROTOTYPE))
+pub fn get_prototype_of(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let obj = ctx.to_object(args.get(0).unwrap_or(&Value::undefined()))?;
+    Ok(obj
+        .as_object()
+        .map_or_else(Value::null, |obj| obj.get_prototype_of()))
 }
 
 /// Set the `prototype` of an object.
-pub fn set_prototype_of(_: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValue {
-    let obj = args.get(0).expect("Cannot get object").clone();
-    let proto = args.get(1).expect("Cannot get object").clone();
-    obj.set_internal_slot(INSTANCE_PROTOTYPE, proto);
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-object.setprototypeof
+pub fn set_prototype_of(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let obj = args.get(0).cloned().unwrap_or_else(Value::undefined);
+    let proto = args.get(1).cloned().unwrap_or_else(Value::undefined);
+    if obj.is_null_or_undefined() {
+        return ctx.throw_type_error("Object.setPrototypeOf called on null or undefined");
+    }
+    if !proto.is_object() && !proto.is_null() {
+        return ctx.throw_type_error(format!(
+            "Object prototype may only be an Object or null: {}",
+            proto
+        ));
+    }
+    if !obj.is_object() {
+        return Ok(obj);
+    }
+
+    // Walk the new prototype chain first, the object is borrowed while its prototype changes
+    let mut ancestor = proto.clone();
+    while ancestor.is_object() {
+        if same_value(&ancestor, &obj, false) {
+            return ctx.throw_type_error("Cyclic __proto__ value");
+        }
+        ancestor = ancestor.get_internal_slot(INSTANCE_PROTOTYPE);
+    }
+
+    let changed = obj
+        .as_object_mut()
+        .map_or(false, |mut object| object.set_prototype_of(proto));
+    if changed {
+        Ok(obj)
+    } else {
+        ctx.throw_type_error("Cannot set the prototype of a non-extensible object")
+    }
+}
+
+/// Converts a descriptor object, like the one passed to `Object.defineProperty`, into a `Property`.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-topropertydescriptor
+fn to_property_descriptor(value: &Value, ctx: &Interpreter) -> Result<Property, Value> {
+    if !value.is_object() {
+        return Err(
+            ctx.construct_type_error(format!("Property description must be an object: {}", value))
+        );
+    }
+
+    let desc = Property::from(value);
+    for accessor in [&desc.get, &desc.set].iter() {
+        if let Some(ref accessor) = accessor {
+            if !accessor.is_undefined() && !accessor.is_function() {
+                return Err(
+                    ctx.construct_type_error(format!("Accessor must be a function: {}", accessor))
+                );
+            }
+        }
+    }
+    if desc.is_accessor_descriptor() && desc.is_data_descriptor() {
+        return Err(ctx.construct_type_error(
+            "Invalid property descriptor. Cannot both specify accessors and a value or writable attribute",
+        ));
+    }
+    Ok(desc)
+}
+
+/// Defines a property on an object, throwing a `TypeError` if that's not allowed.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-definepropertyorthrow
+fn define_property_or_throw(
+    object: &Value,
+    key: Value,
+    desc: Property,
+    ctx: &Interpreter,
+) -> Result<(), Value> {
+    let defined = match object.as_object_mut() {
+        Some(mut obj) => obj.define_own_property(key.clone(), desc),
+        None => false,
+    };
+    if defined {
+        Ok(())
+    } else {
+        Err(ctx.construct_type_error(format!("Cannot define property {}", key)))
+    }
+}
+
+/// The own property keys of an object.
+fn own_property_keys(object: &Value) -> Vec<Value> {
+    object
+        .as_object()
+        .map(|obj| obj.own_property_keys())
+        .unwrap_or_default()
+}
+
+/// The own string keyed properties of an object which are enumerable, as `Object.keys` lists them.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-enumerableownpropertynames
+pub(crate) fn enumerable_own_keys(object: &Value) -> Vec<Value> {
+    match object.as_object() {
+        Some(obj) => obj
+            .own_property_keys()
+            .into_iter()
+            .filter(|key| !key.is_symbol() && obj.get_own_property(key).enumerable == Some(true))
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+/// `Object.defineProperty( obj, prop, descriptor )`
+///
+/// This method defines a new property directly on an object, or modifies an existing property
+/// on an object, and returns the object.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-object.defineproperty
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Object/defineProperty
+pub fn define_property(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let obj = args.get(0).cloned().unwrap_or_else(Value::undefined);
+    if !obj.is_object() {
+        return ctx.throw_type_error("Object.defineProperty called on non-object");
+    }
+    let key = ctx.to_property_key(&mut args.get(1).cloned().unwrap_or_else(Value::undefined))?;
+    let desc = to_property_descriptor(args.get(2).unwrap_or(&Value::undefined()), ctx)?;
+    define_property_or_throw(&obj, key, desc, ctx)?;
+    Ok(obj)
+}
+
+/// `Object.defineProperties( obj, props )`
+///
+/// This method defines new or modifies existing properties directly on an object, returning
+/// the object.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-object.defineproperties
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Object/defineProperties
+pub fn define_properties(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let obj = args.get(0).cloned().unwrap_or_else(Value::undefined);
+    if !obj.is_object() {
+        return ctx.throw_type_error("Object.defineProperties called on non-object");
+    }
+    object_define_properties(&obj, args.get(1).unwrap_or(&Value::undefined()), ctx)?;
+    Ok(obj)
+}
+
+/// Defines every enumerable own property of `properties` as a property of `object`.
+///
+/// All the descriptors are validated before the first property is defined.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-objectdefineproperties
+fn object_define_properties(
+    object: &Value,
+    properties: &Value,
+    ctx: &mut Interpreter,
+) -> Result<(), Value> {
+    let props = ctx.to_object(properties)?;
+    let mut descriptors = Vec::new();
+    for key in own_property_keys(&props) {
+        let enumerable = props.as_object().map_or(false, |obj| {
+            obj.get_own_property(&key).enumerable == Some(true)
+        });
+        if enumerable {
+            let desc = to_property_descriptor(&props.get_field(key.clone()), ctx)?;
+            descriptors.push((key, desc));
+        }
+    }
+
+    for (key, desc) in descriptors {
+        define_property_or_throw(object, key, desc, ctx)?;
+    }
+    Ok(())
+}
+
+/// `Object.create( proto, [ propertiesObject ] )`
+///
+/// This method creates a new object, using an existing object as the prototype of the newly
+/// created object.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-object.create
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Object/create
+pub fn create_object(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let proto = args.get(0).cloned().unwrap_or_else(Value::undefined);
+    if !proto.is_object() && !proto.is_null() {
+        return ctx.throw_type_error(format!(
+            "Object prototype may only be an Object or null: {}",
+            proto
+        ));
+    }
+
+    let obj = Value::object(Object::create(proto));
+    match args.get(1) {
+        Some(properties) if !properties.is_undefined() => {
+            object_define_properties(&obj, properties, ctx)?;
+        }
+        _ => {}
+    }
+    Ok(obj)
+}
+
+/// `Object.keys( obj )`
+///
+/// This method returns an array of a given object's own enumerable property names.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-object.keys
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Object/keys
+pub fn keys(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let obj = ctx.to_object(args.get(0).unwrap_or(&Value::undefined()))?;
+    array::create_array_from_list(&enumerable_own_keys(&obj), ctx)
+}
+
+/// `Object.values( obj )`
+///
+/// This method returns an array of a given object's own enumerable property values.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-object.values
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Object/values
+pub fn values(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let obj = ctx.to_object(args.get(0).unwrap_or(&Value::undefined()))?;
+    let values: Vec<Value> = enumerable_own_keys(&obj)
+        .into_iter()
+        .map(|key| obj.get_field(key))
+        .collect();
+    array::create_array_from_list(&values, ctx)
+}
+
+/// `Object.entries( obj )`
+///
+/// This method returns an array of a given object's own enumerable `[key, value]` pairs.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-object.entries
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Object/entries
+pub fn entries(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let obj = ctx.to_object(args.get(0).unwrap_or(&Value::undefined()))?;
+    let mut entries = Vec::new();
+    for key in enumerable_own_keys(&obj) {
+        let value = obj.get_field(key.clone());
+        entries.push(array::create_array_from_list(&[key, value], ctx)?);
+    }
+    array::create_array_from_list(&entries, ctx)
+}
+
+/// `Object.fromEntries( iterable )`
+///
+/// This method transforms a list of key-value pairs into an object.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-object.fromentries
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Object/fromEntries
+pub fn from_entries(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let iterable = args.get(0).cloned().unwrap_or_else(Value::undefined);
+    if iterable.is_null_or_undefined() {
+        return ctx.throw_type_error(format!("{} is not iterable", iterable));
+    }
+
+    let obj = Value::new_object(Some(&ctx.realm.intrinsics));
+    for entry in ctx.extract_array_properties(&iterable)? {
+        if !entry.is_object() {
+            return ctx
+                .throw_type_error(format!("Iterator value {} is not an entry object", entry));
+        }
+        let key = ctx.to_property_key(&mut entry.get_field_slice("0"))?;
+        let value = entry.get_field_slice("1");
+        define_property_or_throw(
+            &obj,
+            key,
+            Property::new()
+                .value(value)
+                .writable(true)
+                .enumerable(true)
+                .configurable(true),
+            ctx,
+        )?;
+    }
     Ok(obj)
 }
 
-/// Define a property in an object
-pub fn define_property(_: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValue {
-    let obj = args.get(0).expect("Cannot get object");
-    let prop = String::from(args.get(1).expect("Cannot get object"));
-    let desc = Property::from(args.get(2).expect("Cannot get object"));
-    obj.set_property(prop, desc);
-    Ok(Value::undefined())
+/// `Object.assign( target, ...sources )`
+///
+/// This method copies all enumerable own properties from one or more source objects to a
+/// target object. It returns the target object.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-object.assign
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Object/assign
+pub fn assign(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let target = ctx.to_object(args.get(0).unwrap_or(&Value::undefined()))?;
+    for source in args.iter().skip(1) {
+        if source.is_null_or_undefined() {
+            continue;
+        }
+        let from = ctx.to_object(source)?;
+        for key in own_property_keys(&from) {
+            let enumerable = from.as_object().map_or(false, |obj| {
+                obj.get_own_property(&key).enumerable == Some(true)
+            });
+            if enumerable {
+                let value = ctx.get_field(&from, key.clone())?;
+                ctx.set_field(&target, key, value, true)?;
+            }
+        }
+    }
+    Ok(target)
+}
+
+/// `Object.getOwnPropertyNames( obj )`
+///
+/// This method returns an array of all properties (including non-enumerable ones, but not
+/// symbols) found directly in a given object.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-object.getownpropertynames
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Object/getOwnPropertyNames
+pub fn get_own_property_names(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let obj = ctx.to_object(args.get(0).unwrap_or(&Value::undefined()))?;
+    let names: Vec<Value> = own_property_keys(&obj)
+        .into_iter()
+        .filter(|key| !key.is_symbol())
+        .collect();
+    array::create_array_from_list(&names, ctx)
+}
+
+/// `Object.getOwnPropertySymbols( obj )`
+///
+/// This method returns an array of all symbol properties found directly upon a given object.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-object.getownpropertysymbols
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Object/getOwnPropertySymbols
+pub fn get_own_property_symbols(
+    _: &mut Value,
+    args: &[Value],
+    ctx: &mut Interpreter,
+) -> ResultValue {
+    let obj = ctx.to_object(args.get(0).unwrap_or(&Value::undefined()))?;
+    let symbols: Vec<Value> = own_property_keys(&obj)
+        .into_iter()
+        .filter(|key| key.is_symbol())
+        .collect();
+    array::create_array_from_list(&symbols, ctx)
+}
+
+/// Creates the descriptor object of an own property, or `undefined` if there is none.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-frompropertydescriptor
+fn from_property_descriptor(object: &Value, key: &Value, ctx: &Interpreter) -> Value {
+    let desc = match object.as_object() {
+        Some(obj) => obj.get_own_property(key),
+        None => return Value::undefined(),
+    };
+    if desc.is_none() {
+        return Value::undefined();
+    }
+
+    let descriptor = Value::from(&desc);
+    descriptor.set_internal_slot(
+        INSTANCE_PROTOTYPE,
+        ctx.realm
+            .intrinsics
+            .get_field_slice("Object")
+            .get_field_slice(PROTOTYPE),
+    );
+    descriptor
+}
+
+/// `Object.getOwnPropertyDescriptor( obj, prop )`
+///
+/// This method returns an object describing the configuration of a specific own property.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-object.getownpropertydescriptor
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Object/getOwnPropertyDescriptor
+pub fn get_own_property_descriptor(
+    _: &mut Value,
+    args: &[Value],
+    ctx: &mut Interpreter,
+) -> ResultValue {
+    let obj = ctx.to_object(args.get(0).unwrap_or(&Value::undefined()))?;
+    let key = ctx.to_property_key(&mut args.get(1).cloned().unwrap_or_else(Value::undefined))?;
+    Ok(from_property_descriptor(&obj, &key, ctx))
+}
+
+/// `Object.getOwnPropertyDescriptors( obj )`
+///
+/// This method returns all own property descriptors of a given object.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-object.getownpropertydescriptors
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Object/getOwnPropertyDescriptors
+pub fn get_own_property_descriptors(
+    _: &mut Value,
+    args: &[Value],
+    ctx: &mut Interpreter,
+) -> ResultValue {
+    let obj = ctx.to_object(args.get(0).unwrap_or(&Value::undefined()))?;
+    let descriptors = Value::new_object(Some(&ctx.realm.intrinsics));
+    for key in own_property_keys(&obj) {
+        let descriptor = from_property_descriptor(&obj, &key, ctx);
+        if !descriptor.is_undefined() {
+            descriptors.set_field(key, descriptor);
+        }
+    }
+    Ok(descriptors)
+}
+
+/// Seals or freezes an object, as `Object.seal` and `Object.freeze` do.
+fn set_integrity_level(args: &[Value], level: IntegrityLevel, ctx: &Interpreter) -> ResultValue {
+    let obj = args.get(0).cloned().unwrap_or_else(Value::undefined);
+    // Primitives are already immutable
+    if !obj.is_object() {
+        return Ok(obj);
+    }
+    let locked = obj
+        .as_object_mut()
+        .map_or(false, |mut object| object.set_integrity_level(level));
+    if locked {
+        Ok(obj)
+    } else {
+        ctx.throw_type_error("Cannot change the integrity level of the object")
+    }
+}
+
+/// Checks the integrity level of an object, as `Object.isSealed` and `Object.isFrozen` do.
+fn test_integrity_level(args: &[Value], level: IntegrityLevel) -> ResultValue {
+    let obj = args.get(0).cloned().unwrap_or_else(Value::undefined);
+    let result = match obj.as_object() {
+        Some(object) => object.test_integrity_level(level),
+        None => true,
+    };
+    Ok(Value::from(result))
+}
+
+/// `Object.freeze( obj )`
+///
+/// This method freezes an object: new properties can no longer be added, existing ones can't be
+/// removed or reconfigured and their values can't be changed.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-object.freeze
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Object/freeze
+pub fn freeze(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    set_integrity_level(args, IntegrityLevel::Frozen, ctx)
+}
+
+/// `Object.isFrozen( obj )`
+///
+/// This method determines if an object is frozen.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-object.isfrozen
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Object/isFrozen
+pub fn is_frozen(_: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValue {
+    test_integrity_level(args, IntegrityLevel::Frozen)
+}
+
+/// `Object.seal( obj )`
+///
+/// This method seals an object: new properties can no longer be added and existing ones can't
+/// be removed or reconfigured, but their values can still be changed.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-object.seal
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Object/seal
+pub fn seal(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    set_integrity_level(args, IntegrityLevel::Sealed, ctx)
+}
+
+/// `Object.isSealed( obj )`
+///
+/// This method determines if an object is sealed.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-object.issealed
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Object/isSealed
+pub fn is_sealed(_: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValue {
+    test_integrity_level(args, IntegrityLevel::Sealed)
+}
+
+/// `Object.preventExtensions( obj )`
+///
+/// This method prevents new properties from ever being added to an object.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-object.preventextensions
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Object/preventExtensions
+pub fn prevent_extensions(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let obj = args.get(0).cloned().unwrap_or_else(Value::undefined);
+    if !obj.is_object() {
+        return Ok(obj);
+    }
+    let prevented = obj
+        .as_object_mut()
+        .map_or(false, |mut object| object.prevent_extensions());
+    if prevented {
+        Ok(obj)
+    } else {
+        ctx.throw_type_error("Cannot prevent extensions of the object")
+    }
+}
+
+/// `Object.isExtensible( obj )`
+///
+/// This method determines if new properties can be added to an object.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-object.isextensible
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Object/isExtensible
+pub fn is_extensible(_: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValue {
+    let result = match args.get(0).and_then(|obj| obj.as_object()) {
+        Some(object) => object.is_extensible(),
+        None => false,
+    };
+    Ok(Value::from(result))
+}
+
+/// `Object.is( value1, value2 )`
+///
+/// This method determines whether two values are the same value.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-object.is
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Object/is
+pub fn is(_: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValue {
+    let x = args.get(0).cloned().unwrap_or_else(Value::undefined);
+    let y = args.get(1).cloned().unwrap_or_else(Value::undefined);
+    Ok(Value::from(same_value(&x, &y, false)))
 }
 
 /// `Object.prototype.toString()`
@@ -571,8 +1136,37 @@ pub fn define_property(_: &mut Value, args: &[Value], _: &mut Interpreter) -> Re
 ///
 /// [spec]: https://tc39.es/ecma262/#sec-object.prototype.tostring
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Object/toString
-pub fn to_string(this: &mut Value, _: &[Value], _: &mut Interpreter) -> ResultValue {
-    Ok(Value::from(this.to_string()))
+pub fn to_string(this: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let object = match this.data() {
+        ValueData::Undefined => return Ok(Value::from("[object Undefined]")),
+        ValueData::Null => return Ok(Value::from("[object Null]")),
+        _ => ctx.to_object(this)?,
+    };
+
+    let builtin_tag = match object.data() {
+        ValueData::Object(ref obj) => {
+            let obj = (*obj).deref().borrow();
+            match obj.kind {
+                ObjectKind::Array => "Array",
+                ObjectKind::Error => "Error",
+                ObjectKind::Boolean => "Boolean",
+                ObjectKind::Number => "Number",
+                ObjectKind::String => "String",
+                _ if obj.is_callable() => "Function",
+                _ => "Object",
+            }
+        }
+        _ => "Object",
+    };
+
+    let tag = match object
+        .get_field(Value::symbol(WellKnownSymbol::ToStringTag.into()))
+        .data()
+    {
+        ValueData::String(ref tag) => tag.to_string(),
+        _ => builtin_tag.to_string(),
+    };
+    Ok(Value::from(format!("[object {}]", tag)))
 }
 
 /// `Object.prototype.hasOwnPrototype( property )`
@@ -586,18 +1180,14 @@ pub fn to_string(this: &mut Value, _: &[Value], _: &mut Interpreter) -> ResultVa
 ///
 /// [spec]: https://tc39.es/ecma262/#sec-object.prototype.hasownproperty
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Object/hasOwnProperty
-pub fn has_own_property(this: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValue {
-    let prop = if args.is_empty() {
-        None
-    } else {
-        Some(String::from(args.get(0).expect("Cannot get object")))
+pub fn has_own_property(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let key = ctx.to_property_key(&mut args.get(0).cloned().unwrap_or_else(Value::undefined))?;
+    let obj = ctx.to_object(this)?;
+    let result = match obj.as_object() {
+        Some(object) => !object.get_own_property(&key).is_none(),
+        None => false,
     };
-    Ok(Value::from(
-        prop.is_some()
-            && this
-                .get_property(&prop.expect("Cannot get object"))
-                .is_some(),
-    ))
+    Ok(Value::from(result))
 }
 
 /// Create a new `Object` object.
@@ -613,6 +1203,24 @@ pub fn create(global: &Value) -> Value {
     make_builtin_fn!(set_prototype_of, named "setPrototypeOf", with length 2, of object);
     make_builtin_fn!(get_prototype_of, named "getPrototypeOf", with length 1, of object);
     make_builtin_fn!(define_property, named "defineProperty", with length 3, of object);
+    make_builtin_fn!(define_properties, named "defineProperties", with length 2, of object);
+    make_builtin_fn!(create_object, named "create", with length 2, of object);
+    make_builtin_fn!(keys, named "keys", with length 1, of object);
+    make_builtin_fn!(values, named "values", with length 1, of object);
+    make_builtin_fn!(entries, named "entries", with length 1, of object);
+    make_builtin_fn!(from_entries, named "fromEntries", with length 1, of object);
+    make_builtin_fn!(assign, named "assign", with length 2, of object);
+    make_builtin_fn!(get_own_property_names, named "getOwnPropertyNames", with length 1, of object);
+    make_builtin_fn!(get_own_property_symbols, named "getOwnPropertySymbols", with length 1, of object);
+    make_builtin_fn!(get_own_property_descriptor, named "getOwnPropertyDescriptor", with length 2, of object);
+    make_builtin_fn!(get_own_property_descriptors, named "getOwnPropertyDescriptors", with length 1, of object);
+    make_builtin_fn!(freeze, named "freeze", with length 1, of object);
+    make_builtin_fn!(is_frozen, named "isFrozen", with length 1, of object);
+    make_builtin_fn!(seal, named "seal", with length 1, of object);
+    make_builtin_fn!(is_sealed, named "isSealed", with length 1, of object);
+    make_builtin_fn!(prevent_extensions, named "preventExtensions", with length 1, of object);
+    make_builtin_fn!(is_extensible, named "isExtensible", with length 1, of object);
+    make_builtin_fn!(is, named "is", with length 2, of object);
 
     object
 }
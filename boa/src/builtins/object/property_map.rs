@@ -0,0 +1,130 @@
+//! This module implements `PropertyMap`, the storage of the properties of objects.
+
+use crate::{builtins::value::ValueData, heap::Charge};
+use gc::{Finalize, Trace};
+use rustc_hash::FxHashMap;
+use std::{borrow::Borrow, hash::Hash, mem};
+
+/// A map which keeps its entries in the order their keys were first inserted.
+///
+/// `[[OwnPropertyKeys]]` lists the string and symbol keys of an object in the order the properties
+/// were created, which is visible through `Object.keys`, `for..in` or `JSON.stringify`. Replacing
+/// the value of a key keeps its position, removing it and inserting it again moves it to the end.
+#[derive(Debug, Clone, Trace, Finalize)]
+pub struct PropertyMap<K: Trace + Eq + Hash + Clone + 'static, V: Trace + 'static> {
+    /// The entries, in insertion order.
+    entries: Vec<(K, V)>,
+    /// The position of each key in `entries`.
+    #[unsafe_ignore_trace]
+    indices: FxHashMap<K, usize>,
+    /// The memory used by the entries, charged to the interpreter which created the map.
+    #[unsafe_ignore_trace]
+    charge: Charge,
+}
+
+impl<K: Trace + Eq + Hash + Clone, V: Trace> Default for PropertyMap<K, V> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+            indices: FxHashMap::default(),
+            charge: Charge::default(),
+        }
+    }
+}
+
+impl<K: Trace + Eq + Hash + Clone, V: Trace> PropertyMap<K, V> {
+    /// The estimated number of bytes used by an entry: the key is stored twice, and the value
+    /// usually keeps a garbage collected value alive.
+    pub(crate) fn entry_size() -> usize {
+        2 * mem::size_of::<K>()
+            + mem::size_of::<V>()
+            + 2 * mem::size_of::<usize>()
+            + mem::size_of::<ValueData>()
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns `true` if the map has an entry for `key`.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.indices.contains_key(key)
+    }
+
+    /// Returns a reference to the value of `key`.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let index = *self.indices.get(key)?;
+        Some(&self.entries[index].1)
+    }
+
+    /// Returns a mutable reference to the value of `key`.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let index = *self.indices.get(key)?;
+        Some(&mut self.entries[index].1)
+    }
+
+    /// Sets the value of `key`, returning the previous one.
+    ///
+    /// A new key is added after all the others.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(&index) = self.indices.get(&key) {
+            return Some(std::mem::replace(&mut self.entries[index].1, value));
+        }
+        self.indices.insert(key.clone(), self.entries.len());
+        self.entries.push((key, value));
+        self.charge.resize(self.entries.len() * Self::entry_size());
+        None
+    }
+
+    /// Removes the entry of `key`, returning its value.
+    ///
+    /// The entries after it move up, so removing the last entry is the cheapest.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let index = self.indices.remove(key)?;
+        let (_, value) = self.entries.remove(index);
+        self.charge.resize(self.entries.len() * Self::entry_size());
+        for (moved, _) in &self.entries[index..] {
+            if let Some(position) = self.indices.get_mut::<K>(moved) {
+                *position -= 1;
+            }
+        }
+        Some(value)
+    }
+
+    /// Returns an iterator over the entries, in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.entries.iter().map(|(key, value)| (key, value))
+    }
+
+    /// Returns an iterator over the keys, in insertion order.
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.entries.iter().map(|(key, _)| key)
+    }
+
+    /// Returns an iterator over the values, in insertion order.
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.entries.iter().map(|(_, value)| value)
+    }
+}
@@ -0,0 +1,463 @@
+use crate::exec::Executor;
+use crate::realm::Realm;
+use crate::{forward, forward_val};
+
+#[test]
+fn object_keys_values_entries() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var obj = { b: 2 };
+        obj[1] = 'one';
+        obj[0] = 'zero';
+        obj[Symbol('hidden')] = 3;
+        Object.defineProperty(obj, 'secret', { value: 4 });
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "Object.keys(obj).length"), "3");
+    // Integer keys come first, in ascending order
+    assert_eq!(forward(&mut engine, "Object.keys(obj)[0]"), "0");
+    assert_eq!(forward(&mut engine, "Object.keys(obj)[1]"), "1");
+    assert_eq!(forward(&mut engine, "Object.keys(obj)[2]"), "b");
+    assert_eq!(forward(&mut engine, "Object.values(obj)[2]"), "2");
+    assert_eq!(forward(&mut engine, "Object.entries(obj)[0][1]"), "zero");
+    assert_eq!(forward(&mut engine, "Object.keys('ab').length"), "2");
+    assert_eq!(forward(&mut engine, "Object.keys([4, 5])[1]"), "1");
+}
+
+#[test]
+fn object_from_entries() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var obj = Object.fromEntries([['a', 1], ['b', 2]]);
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "obj.a"), "1");
+    assert_eq!(forward(&mut engine, "obj.b"), "2");
+    assert_eq!(
+        forward(&mut engine, "Object.fromEntries([1])"),
+        "Error: TypeError: Iterator value 1 is not an entry object"
+    );
+}
+
+#[test]
+fn object_assign() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var target = { a: 1 };
+        var result = Object.assign(target, { b: 2 }, null, { a: 3 });
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "result === target"), "true");
+    assert_eq!(forward(&mut engine, "target.a"), "3");
+    assert_eq!(forward(&mut engine, "target.b"), "2");
+    assert_eq!(
+        forward(
+            &mut engine,
+            "Object.assign(Object.freeze({ a: 1 }), { a: 2 })"
+        ),
+        "Error: TypeError: Cannot assign to read only property a"
+    );
+}
+
+#[test]
+fn object_create() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var proto = { greet: 'hello' };
+        var obj = Object.create(proto, {
+            own: { value: 1, enumerable: true },
+            hidden: { value: 2 }
+        });
+        var bare = Object.create(null);
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "obj.greet"), "hello");
+    assert_eq!(
+        forward(&mut engine, "Object.getPrototypeOf(obj) === proto"),
+        "true"
+    );
+    assert_eq!(forward(&mut engine, "obj.own + obj.hidden"), "3");
+    assert_eq!(forward(&mut engine, "Object.keys(obj).length"), "1");
+    assert_eq!(forward(&mut engine, "bare.toString"), "undefined");
+    assert_eq!(
+        forward(&mut engine, "Object.create(1)"),
+        "Error: TypeError: Object prototype may only be an Object or null: 1"
+    );
+}
+
+#[test]
+fn object_set_prototype_of() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var proto = { greet: 'hello' };
+        var obj = {};
+        var result = Object.setPrototypeOf(obj, proto);
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "result === obj"), "true");
+    assert_eq!(forward(&mut engine, "obj.greet"), "hello");
+    assert_eq!(
+        forward(&mut engine, "Object.getPrototypeOf(obj) === proto"),
+        "true"
+    );
+    assert_eq!(forward(&mut engine, "Object.setPrototypeOf(1, null)"), "1");
+    assert_eq!(
+        forward(&mut engine, "Object.setPrototypeOf(proto, obj)"),
+        "Error: TypeError: Cyclic __proto__ value"
+    );
+    assert_eq!(
+        forward(&mut engine, "Object.setPrototypeOf(obj, 1)"),
+        "Error: TypeError: Object prototype may only be an Object or null: 1"
+    );
+    assert_eq!(
+        forward(&mut engine, "Object.setPrototypeOf(null, proto)"),
+        "Error: TypeError: Object.setPrototypeOf called on null or undefined"
+    );
+}
+
+#[test]
+fn object_define_properties() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var obj = {};
+        Object.defineProperties(obj, {
+            a: { value: 1, writable: true },
+            b: { value: 2 }
+        });
+        obj.a = 10;
+        obj.b = 20;
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "obj.a"), "10");
+    assert_eq!(forward(&mut engine, "obj.b"), "2");
+    assert_eq!(
+        forward(&mut engine, "Object.defineProperty(obj, 'b', { value: 3 })"),
+        "Error: TypeError: Cannot define property b"
+    );
+    assert_eq!(
+        forward(&mut engine, "Object.defineProperty(obj, 'c', 1)"),
+        "Error: TypeError: Property description must be an object: 1"
+    );
+}
+
+#[test]
+fn object_own_property_names_and_symbols() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var sym = Symbol('s');
+        var obj = { a: 1 };
+        obj[sym] = 2;
+        Object.defineProperty(obj, 'hidden', { value: 3 });
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(
+        forward(&mut engine, "Object.getOwnPropertyNames(obj).length"),
+        "2"
+    );
+    assert_eq!(
+        forward(
+            &mut engine,
+            "Object.getOwnPropertyNames(obj).indexOf('hidden') !== -1"
+        ),
+        "true"
+    );
+    assert_eq!(
+        forward(&mut engine, "Object.getOwnPropertySymbols(obj).length"),
+        "1"
+    );
+    assert_eq!(
+        forward(&mut engine, "Object.getOwnPropertySymbols(obj)[0] === sym"),
+        "true"
+    );
+    assert_eq!(
+        forward(&mut engine, "Object.getOwnPropertyNames('ab')[2]"),
+        "length"
+    );
+}
+
+#[test]
+fn object_get_own_property_descriptor() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var obj = { a: 1 };
+        Object.defineProperty(obj, 'b', { value: 2 });
+        var a = Object.getOwnPropertyDescriptor(obj, 'a');
+        var b = Object.getOwnPropertyDescriptors(obj).b;
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "a.value"), "1");
+    assert_eq!(forward(&mut engine, "a.writable"), "true");
+    assert_eq!(forward(&mut engine, "a.enumerable"), "true");
+    assert_eq!(forward(&mut engine, "a.configurable"), "true");
+    assert_eq!(forward(&mut engine, "a.get"), "undefined");
+    assert_eq!(forward(&mut engine, "b.value"), "2");
+    assert_eq!(forward(&mut engine, "b.writable"), "false");
+    assert_eq!(forward(&mut engine, "b.enumerable"), "false");
+    assert_eq!(forward(&mut engine, "b.configurable"), "false");
+    assert_eq!(
+        forward(&mut engine, "Object.getOwnPropertyDescriptor(obj, 'c')"),
+        "undefined"
+    );
+    assert_eq!(
+        forward(
+            &mut engine,
+            "Object.getOwnPropertyDescriptor('ab', 1).value"
+        ),
+        "b"
+    );
+}
+
+#[test]
+fn object_freeze() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var obj = { a: 1 };
+        var frozen = Object.freeze(obj);
+        obj.a = 2;
+        obj.b = 3;
+        var arr = Object.freeze([1, 2]);
+        arr[0] = 5;
+        arr[2] = 6;
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "frozen === obj"), "true");
+    assert_eq!(forward(&mut engine, "obj.a"), "1");
+    assert_eq!(forward(&mut engine, "obj.b"), "undefined");
+    assert_eq!(forward(&mut engine, "arr[0]"), "1");
+    assert_eq!(forward(&mut engine, "arr.length"), "2");
+    assert_eq!(forward(&mut engine, "Object.isFrozen(obj)"), "true");
+    assert_eq!(forward(&mut engine, "Object.isSealed(obj)"), "true");
+    assert_eq!(forward(&mut engine, "Object.isFrozen({})"), "false");
+    assert_eq!(forward(&mut engine, "Object.isFrozen(1)"), "true");
+    assert_eq!(
+        forward(&mut engine, "Object.defineProperty(obj, 'a', { value: 2 })"),
+        "Error: TypeError: Cannot define property a"
+    );
+}
+
+#[test]
+fn object_seal() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var obj = Object.seal({ a: 1 });
+        obj.a = 2;
+        obj.b = 3;
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "obj.a"), "2");
+    assert_eq!(forward(&mut engine, "obj.b"), "undefined");
+    assert_eq!(forward(&mut engine, "Object.isSealed(obj)"), "true");
+    assert_eq!(forward(&mut engine, "Object.isFrozen(obj)"), "false");
+    assert_eq!(
+        forward(
+            &mut engine,
+            "Object.defineProperty(obj, 'a', { enumerable: false })"
+        ),
+        "Error: TypeError: Cannot define property a"
+    );
+}
+
+#[test]
+fn object_prevent_extensions() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var obj = { a: 1 };
+        var before = Object.isExtensible(obj);
+        Object.preventExtensions(obj);
+        obj.a = 2;
+        obj.b = 3;
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "before"), "true");
+    assert_eq!(forward(&mut engine, "Object.isExtensible(obj)"), "false");
+    assert_eq!(forward(&mut engine, "Object.isExtensible(1)"), "false");
+    assert_eq!(forward(&mut engine, "obj.a"), "2");
+    assert_eq!(forward(&mut engine, "obj.b"), "undefined");
+    // An empty object which can't be extended is frozen
+    assert_eq!(
+        forward(&mut engine, "Object.isFrozen(Object.preventExtensions({}))"),
+        "true"
+    );
+}
+
+#[test]
+fn accessor_properties() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var obj = { stored: 1 };
+        Object.defineProperty(obj, 'doubled', {
+            get: function() { return this.stored * 2; },
+            set: function(value) { this.stored = value / 2; }
+        });
+        Object.defineProperties(obj, {
+            tripled: { get: function() { return this.stored * 3; } }
+        });
+        var child = Object.create(obj, {
+            own: { get: function() { return 'own'; } }
+        });
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "obj.doubled"), "2");
+    assert_eq!(forward(&mut engine, "obj['tripled']"), "3");
+    assert_eq!(forward(&mut engine, "child.own"), "own");
+    assert_eq!(forward(&mut engine, "obj.doubled = 10; obj.stored"), "5");
+    assert_eq!(forward(&mut engine, "obj.doubled += 2; obj.stored"), "6");
+    // Inherited accessors run with the object they're read or written through as `this`
+    assert_eq!(forward(&mut engine, "child.doubled = 8; child.stored"), "4");
+    assert_eq!(forward(&mut engine, "obj.stored"), "6");
+    assert_eq!(forward(&mut engine, "child.tripled"), "12");
+    // Writing an accessor without a setter is ignored, or throws in strict mode
+    assert_eq!(forward(&mut engine, "obj.tripled = 1; obj.tripled"), "18");
+    assert_eq!(
+        forward(
+            &mut engine,
+            "(function() { 'use strict'; obj.tripled = 1; })()"
+        ),
+        "Error: TypeError: Cannot assign to read only property tripled"
+    );
+    assert_eq!(
+        forward(
+            &mut engine,
+            "Object.defineProperty(obj, 'fails', { get: function() { throw 1; } }); obj.fails"
+        ),
+        "Error: 1"
+    );
+}
+
+#[test]
+fn strict_mode_writes() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var frozen = Object.freeze({ a: 1 });
+        var closed = Object.preventExtensions({ a: 1 });
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(
+        forward(
+            &mut engine,
+            "(function() { 'use strict'; frozen.a = 2; })()"
+        ),
+        "Error: TypeError: Cannot assign to read only property a"
+    );
+    assert_eq!(
+        forward(
+            &mut engine,
+            "(function() { 'use strict'; frozen['a'] = 2; })()"
+        ),
+        "Error: TypeError: Cannot assign to read only property a"
+    );
+    assert_eq!(
+        forward(
+            &mut engine,
+            "(function() { 'use strict'; closed.b = 2; })()"
+        ),
+        "Error: TypeError: Cannot add property b, object is not extensible"
+    );
+    assert_eq!(
+        forward(
+            &mut engine,
+            "(function() { 'use strict'; closed.a = 2; return closed.a; })()"
+        ),
+        "2"
+    );
+    // Sloppy mode code ignores the failed writes
+    assert_eq!(forward(&mut engine, "frozen.a = 2; frozen.a"), "1");
+    assert_eq!(forward(&mut engine, "closed.b = 2; closed.b"), "undefined");
+}
+
+#[test]
+fn set_prototype_of_non_extensible_objects() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let error = "Error: TypeError: Cannot set the prototype of a non-extensible object";
+    assert_eq!(
+        forward(
+            &mut engine,
+            "Object.setPrototypeOf(Object.freeze({}), null)"
+        ),
+        error
+    );
+    assert_eq!(
+        forward(&mut engine, "Object.setPrototypeOf(Object.seal({}), null)"),
+        error
+    );
+    assert_eq!(
+        forward(
+            &mut engine,
+            "Object.setPrototypeOf(Object.preventExtensions({}), null)"
+        ),
+        error
+    );
+}
+
+#[test]
+fn object_is() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var obj = {};
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "Object.is(obj, obj)"), "true");
+    assert_eq!(forward(&mut engine, "Object.is(obj, {})"), "false");
+    assert_eq!(forward(&mut engine, "Object.is('a', 'a')"), "true");
+    assert_eq!(forward(&mut engine, "Object.is(NaN, NaN)"), "true");
+    assert_eq!(forward(&mut engine, "Object.is(0, -0)"), "false");
+    assert_eq!(forward(&mut engine, "Object.is(undefined, null)"), "false");
+    let result = forward_val(&mut engine, "Object.is()").unwrap();
+    assert_eq!(result.is_true(), true);
+}
+
+#[test]
+fn lone_surrogate_keys() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var obj = {};
+        obj["\uD800"] = 1;
+        obj["\uDC00"] = 2;
+        var lit = { "\uD800": 3 };
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "obj['\\uFFFD']"), "undefined");
+    assert_eq!(forward(&mut engine, "obj['\\uD800']"), "1");
+    assert_eq!(forward(&mut engine, "obj['\\uDC00']"), "2");
+    assert_eq!(forward(&mut engine, "Object.keys(obj).length"), "2");
+    assert_eq!(
+        forward(&mut engine, "Object.keys(obj)[0] === '\\uD800'"),
+        "true"
+    );
+    assert_eq!(
+        forward(&mut engine, "Object.keys(obj)[1] === '\\uDC00'"),
+        "true"
+    );
+    assert_eq!(forward(&mut engine, "lit['\\uD800']"), "3");
+    assert_eq!(forward(&mut engine, "lit['\\uFFFD']"), "undefined");
+}
+
+#[test]
+fn has_own_property() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    forward(&mut engine, "var o = { a: 1 };");
+    assert_eq!(forward(&mut engine, "o.hasOwnProperty('a')"), "true");
+    assert_eq!(
+        forward(&mut engine, "o.hasOwnProperty('toString')"),
+        "false"
+    );
+    assert_eq!(forward(&mut engine, "[1].hasOwnProperty(0)"), "true");
+    assert_eq!(forward(&mut engine, "'ab'.hasOwnProperty('1')"), "true");
+}
@@ -113,7 +113,8 @@ impl Property {
     ///
     /// `true` if all fields are set to none
     pub fn is_none(&self) -> bool {
-        self.get.is_none()
+        self.value.is_none()
+            && self.get.is_none()
             && self.set.is_none()
             && self.writable.is_none()
             && self.configurable.is_none()
@@ -171,29 +172,54 @@ impl Default for Property {
 }
 
 impl From<&Property> for Value {
+    /// Creates an object with a property for each field present in the Property.
+    ///
+    /// More information:
+    /// - [ECMAScript reference][spec]
+    ///
+    /// [spec]: https://tc39.es/ecma262/#sec-frompropertydescriptor
     fn from(value: &Property) -> Value {
         let property = Value::new_object(None);
-        property.set_field_slice("configurable", Value::from(value.configurable));
-        property.set_field_slice("enumerable", Value::from(value.enumerable));
-        property.set_field_slice("writable", Value::from(value.writable));
-        property.set_field_slice("value", value.value.clone().unwrap_or_else(Value::null));
-        property.set_field_slice("get", value.get.clone().unwrap_or_else(Value::null));
-        property.set_field_slice("set", value.set.clone().unwrap_or_else(Value::null));
+        let fields = [
+            ("value", value.value.clone()),
+            ("writable", value.writable.map(Value::from)),
+            ("get", value.get.clone()),
+            ("set", value.set.clone()),
+            ("enumerable", value.enumerable.map(Value::from)),
+            ("configurable", value.configurable.map(Value::from)),
+        ];
+        for (name, field) in fields.iter() {
+            if let Some(field) = field {
+                property.set_field_slice(name, field.clone());
+            }
+        }
         property
     }
 }
 
 impl<'a> From<&'a Value> for Property {
-    /// Attempt to fetch values "configurable", "enumerable", "writable" from the value,
-    /// if they're not there default to false
+    /// Fetch the fields "configurable", "enumerable", "writable", "value", "get" and "set" from
+    /// the value, the fields which aren't there stay absent.
+    ///
+    /// More information:
+    /// - [ECMAScript reference][spec]
+    ///
+    /// [spec]: https://tc39.es/ecma262/#sec-topropertydescriptor
     fn from(value: &Value) -> Self {
+        let field = |name: &str| {
+            if value.has_field(name) {
+                Some(value.get_field_slice(name))
+            } else {
+                None
+            }
+        };
         Self {
-            configurable: { Some(bool::from(&value.get_field_slice("configurable"))) },
-            enumerable: { Some(bool::from(&value.get_field_slice("enumerable"))) },
-            writable: { Some(bool::from(&value.get_field_slice("writable"))) },
-            value: Some(value.get_field_slice("value")),
-            get: Some(value.get_field_slice("get")),
-            set: Some(value.get_field_slice("set")),
+            configurable: field("configurable").map(|v| v.is_true()),
+            enumerable: field("enumerable").map(|v| v.is_true()),
+            writable: field("writable").map(|v| v.is_true()),
+            value: field("value"),
+            get: field("get"),
+            set: field("set"),
         }
     }
 }
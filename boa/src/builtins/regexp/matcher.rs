@@ -0,0 +1,548 @@
+//! The backtracking matcher of regular expressions.
+//!
+//! Every node is matched with a continuation, the rest of the pattern, and backtracks into the
+//! node when the continuation fails, as the semantics of the specification are described. Input
+//! strings are UTF-16 code units, in Unicode mode surrogate pairs are matched as a single
+//! character.
+//!
+//! More information:
+//!  - [ECMAScript reference][spec]
+//!
+//! [spec]: https://tc39.es/ecma262/#sec-pattern-semantics
+
+use super::parser::{self, CharClass, Node};
+use crate::builtins::value::js_string::{is_leading_surrogate, is_trailing_surrogate};
+use std::cell::{Cell, RefCell};
+
+/// The captured ranges of a match, the first one being the whole match.
+pub(crate) type Captures = Vec<Option<(usize, usize)>>;
+
+/// The continuation of a match, called with the position reached so far.
+type Continuation<'k> = &'k mut dyn FnMut(usize, &mut Captures) -> bool;
+
+/// The number of matched nodes between two calls of the callback checking if a match can go on.
+const CHECK_INTERVAL: usize = 4096;
+
+/// The error of a match stopped by its callback, the execution limits of the interpreter having
+/// been hit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Aborted;
+
+/// A compiled regular expression.
+#[derive(Debug)]
+pub(crate) struct Matcher {
+    pattern: parser::Pattern,
+    ignore_case: bool,
+    multiline: bool,
+    dot_all: bool,
+    unicode: bool,
+}
+
+/// Checks if a character is a line terminator.
+fn is_line_terminator(c: u32) -> bool {
+    c == 0x0A || c == 0x0D || c == 0x2028 || c == 0x2029
+}
+
+/// Checks if a character is matched by `\w`.
+fn is_word_char(c: u32) -> bool {
+    (c < 0x80 && (c as u8 as char).is_ascii_alphanumeric()) || c == u32::from(b'_')
+}
+
+/// Returns the character of a case mapping which produces a single one.
+fn single_char(mut mapping: impl Iterator<Item = char>) -> Option<u32> {
+    match (mapping.next(), mapping.next()) {
+        (Some(c), None) => Some(c as u32),
+        _ => None,
+    }
+}
+
+impl Matcher {
+    /// Compiles the pattern `source` with the given flags, returning the message of the
+    /// `SyntaxError` to throw if it isn't valid.
+    pub(crate) fn new(
+        source: &[u16],
+        ignore_case: bool,
+        multiline: bool,
+        dot_all: bool,
+        unicode: bool,
+    ) -> Result<Self, String> {
+        Ok(Self {
+            pattern: parser::parse(source, unicode)?,
+            ignore_case,
+            multiline,
+            dot_all,
+            unicode,
+        })
+    }
+
+    /// The names of the named capture groups with their indices.
+    pub(crate) fn group_names(&self) -> &[(String, usize)] {
+        &self.pattern.group_names
+    }
+
+    /// Tries to match the pattern at exactly the code unit `start` of `input`.
+    ///
+    /// A backtracking match can take exponential time, so `go_on` is called every few thousand
+    /// steps and the match is aborted once it returns `false`.
+    pub(crate) fn match_at(
+        &self,
+        input: &[u16],
+        start: usize,
+        go_on: &mut dyn FnMut() -> bool,
+    ) -> Result<Option<Captures>, Aborted> {
+        if start > input.len() {
+            return Ok(None);
+        }
+        let state = State {
+            matcher: self,
+            input,
+            steps: Cell::new(0),
+            go_on: RefCell::new(go_on),
+            aborted: Cell::new(false),
+        };
+        let mut captures = vec![None; self.pattern.capture_count + 1];
+        let mut end = None;
+        let found = state.match_node(
+            &self.pattern.node,
+            start,
+            &mut captures,
+            false,
+            &mut |position, _| {
+                end = Some(position);
+                true
+            },
+        );
+        if state.aborted.get() {
+            return Err(Aborted);
+        }
+        if !found {
+            return Ok(None);
+        }
+        captures[0] = Some((start, end.expect("a successful match has an end")));
+        Ok(Some(captures))
+    }
+
+    /// The abstract operation `Canonicalize`, which folds the case of a character when the
+    /// "`i`" flag is set.
+    ///
+    /// More information:
+    ///  - [ECMAScript reference][spec]
+    ///
+    /// [spec]: https://tc39.es/ecma262/#sec-runtime-semantics-canonicalize-ch
+    fn canonicalize(&self, c: u32) -> u32 {
+        if !self.ignore_case {
+            return c;
+        }
+        let ch = match std::char::from_u32(c) {
+            Some(ch) => ch,
+            None => return c,
+        };
+        if self.unicode {
+            // Simple case folding, which is the lowercase mapping for nearly every character
+            single_char(ch.to_lowercase()).unwrap_or(c)
+        } else {
+            // A character is not mapped to ASCII from outside of it, nor out of a code unit
+            let mut upper = ch.to_uppercase();
+            match (upper.next(), upper.next()) {
+                (Some(upper), None)
+                    if !(c >= 0x80 && (upper as u32) < 0x80) && (upper as u32) <= 0xFFFF =>
+                {
+                    upper as u32
+                }
+                _ => c,
+            }
+        }
+    }
+
+    /// Checks if a character is in a class, taking the case into account when the "`i`" flag
+    /// is set.
+    fn class_matches(&self, class: &CharClass, c: u32) -> bool {
+        let found = if self.ignore_case {
+            let canonical = self.canonicalize(c);
+            let mut candidates = vec![c, canonical];
+            for &base in &[c, canonical] {
+                if let Some(ch) = std::char::from_u32(base) {
+                    candidates.extend(single_char(ch.to_lowercase()));
+                    candidates.extend(single_char(ch.to_uppercase()));
+                }
+            }
+            candidates.into_iter().any(|candidate| {
+                class.contains(candidate) && self.canonicalize(candidate) == canonical
+            })
+        } else {
+            class.contains(c)
+        };
+        found != class.negated
+    }
+
+    /// Checks if a single character node matches `c`.
+    fn char_matches(&self, node: &Node, c: u32) -> bool {
+        match node {
+            Node::Char(expected) => self.canonicalize(*expected) == self.canonicalize(c),
+            Node::Dot => self.dot_all || !is_line_terminator(c),
+            Node::Class(class) => self.class_matches(class, c),
+            _ => unreachable!("not a single character node"),
+        }
+    }
+}
+
+/// A match in progress.
+struct State<'a> {
+    matcher: &'a Matcher,
+    input: &'a [u16],
+    /// The number of nodes matched so far
+    steps: Cell<usize>,
+    go_on: RefCell<&'a mut dyn FnMut() -> bool>,
+    /// Whether `go_on` stopped the match, every node fails from then on
+    aborted: Cell<bool>,
+}
+
+impl State<'_> {
+    /// Counts the match of a node, returning `false` if the match was aborted.
+    fn step(&self) -> bool {
+        if self.aborted.get() {
+            return false;
+        }
+        let steps = self.steps.get() + 1;
+        self.steps.set(steps);
+        if steps % CHECK_INTERVAL == 0 && !(self.go_on.borrow_mut())() {
+            self.aborted.set(true);
+            return false;
+        }
+        true
+    }
+
+    /// Reads the character after `position`, or before it when going `back`, returning it with
+    /// the position on its other side.
+    fn read_char(&self, position: usize, back: bool) -> Option<(u32, usize)> {
+        let input = self.input;
+        if back {
+            if position == 0 {
+                return None;
+            }
+            let unit = input[position - 1];
+            if self.matcher.unicode
+                && is_trailing_surrogate(unit)
+                && position >= 2
+                && is_leading_surrogate(input[position - 2])
+            {
+                let lead = u32::from(input[position - 2]);
+                let c = 0x10000 + ((lead - 0xD800) << 10) + (u32::from(unit) - 0xDC00);
+                return Some((c, position - 2));
+            }
+            Some((u32::from(unit), position - 1))
+        } else {
+            let unit = *input.get(position)?;
+            if self.matcher.unicode && is_leading_surrogate(unit) {
+                if let Some(&trail) = input.get(position + 1) {
+                    if is_trailing_surrogate(trail) {
+                        let c = 0x10000
+                            + ((u32::from(unit) - 0xD800) << 10)
+                            + (u32::from(trail) - 0xDC00);
+                        return Some((c, position + 2));
+                    }
+                }
+            }
+            Some((u32::from(unit), position + 1))
+        }
+    }
+
+    /// Matches `node` at `position` and then the continuation `k`, backtracking into the node
+    /// until the continuation succeeds. When going `back`, inside of lookbehinds, the input is
+    /// read from right to left.
+    ///
+    /// The captures are left as they were when the match fails.
+    fn match_node(
+        &self,
+        node: &Node,
+        position: usize,
+        captures: &mut Captures,
+        back: bool,
+        k: Continuation<'_>,
+    ) -> bool {
+        // Every step of the match nests a call, so long inputs can outgrow the stack of the
+        // thread, which is then extended on the heap
+        stacker::maybe_grow(STACK_RED_ZONE, STACK_GROWTH, || {
+            self.match_node_here(node, position, captures, back, k)
+        })
+    }
+
+    /// Implements `match_node` once enough stack is available.
+    fn match_node_here(
+        &self,
+        node: &Node,
+        position: usize,
+        captures: &mut Captures,
+        back: bool,
+        k: Continuation<'_>,
+    ) -> bool {
+        if !self.step() {
+            return false;
+        }
+        match node {
+            Node::Empty => k(position, captures),
+            Node::Char(_) | Node::Dot | Node::Class(_) => match self.read_char(position, back) {
+                Some((c, next)) if self.matcher.char_matches(node, c) => k(next, captures),
+                _ => false,
+            },
+            Node::LineStart => {
+                let at_start = position == 0
+                    || (self.matcher.multiline
+                        && is_line_terminator(u32::from(self.input[position - 1])));
+                at_start && k(position, captures)
+            }
+            Node::LineEnd => {
+                let at_end = position == self.input.len()
+                    || (self.matcher.multiline
+                        && is_line_terminator(u32::from(self.input[position])));
+                at_end && k(position, captures)
+            }
+            Node::WordBoundary { negated } => {
+                let before = position > 0 && is_word_char(u32::from(self.input[position - 1]));
+                let after =
+                    position < self.input.len() && is_word_char(u32::from(self.input[position]));
+                ((before != after) != *negated) && k(position, captures)
+            }
+            Node::Capture(node, index) => {
+                let index = *index;
+                self.match_node(node, position, captures, back, &mut |end, captures| {
+                    let previous = captures[index];
+                    captures[index] = Some(if back {
+                        (end, position)
+                    } else {
+                        (position, end)
+                    });
+                    if k(end, captures) {
+                        true
+                    } else {
+                        captures[index] = previous;
+                        false
+                    }
+                })
+            }
+            Node::Look {
+                behind,
+                negated,
+                node,
+            } => {
+                // Once a lookaround has matched, the matcher doesn't backtrack into it
+                let mut inner = captures.clone();
+                let matched =
+                    self.match_node(node, position, &mut inner, *behind, &mut |_, _| true);
+                if *negated {
+                    !matched && k(position, captures)
+                } else if matched {
+                    let previous = std::mem::replace(captures, inner);
+                    if k(position, captures) {
+                        true
+                    } else {
+                        *captures = previous;
+                        false
+                    }
+                } else {
+                    false
+                }
+            }
+            Node::BackReference(index) => {
+                self.match_back_reference(*index, position, captures, back, k)
+            }
+            Node::Repeat {
+                node,
+                min,
+                max,
+                greedy,
+                groups,
+            } => {
+                let repeat = Repeat {
+                    node,
+                    min: *min,
+                    max: *max,
+                    greedy: *greedy,
+                    groups: groups.clone(),
+                };
+                if node.is_single_char() {
+                    self.match_simple_repeat(&repeat, position, captures, back, k)
+                } else {
+                    self.match_repeat(&repeat, 0, position, captures, back, k)
+                }
+            }
+            Node::Sequence(nodes) => self.match_sequence(nodes, position, captures, back, k),
+            Node::Alternation(alternatives) => alternatives
+                .iter()
+                .any(|alternative| self.match_node(alternative, position, captures, back, &mut *k)),
+        }
+    }
+
+    /// Matches the nodes one after another, from the last one when going `back`.
+    fn match_sequence(
+        &self,
+        nodes: &[Node],
+        position: usize,
+        captures: &mut Captures,
+        back: bool,
+        k: Continuation<'_>,
+    ) -> bool {
+        let (node, rest) = if back {
+            match nodes.split_last() {
+                Some(split) => split,
+                None => return k(position, captures),
+            }
+        } else {
+            match nodes.split_first() {
+                Some(split) => split,
+                None => return k(position, captures),
+            }
+        };
+        self.match_node(node, position, captures, back, &mut |next, captures| {
+            self.match_sequence(rest, next, captures, back, &mut *k)
+        })
+    }
+
+    fn match_back_reference(
+        &self,
+        index: usize,
+        position: usize,
+        captures: &mut Captures,
+        back: bool,
+        k: Continuation<'_>,
+    ) -> bool {
+        // A group which didn't participate in the match matches the empty string
+        let (start, end) = match captures[index] {
+            Some(range) => range,
+            None => return k(position, captures),
+        };
+        let length = end - start;
+        let range = if back {
+            if position < length {
+                return false;
+            }
+            position - length..position
+        } else {
+            if position + length > self.input.len() {
+                return false;
+            }
+            position..position + length
+        };
+        let next = if back { range.start } else { range.end };
+        let equal = self.input[range]
+            .iter()
+            .zip(&self.input[start..end])
+            .all(|(&a, &b)| {
+                self.matcher.canonicalize(u32::from(a)) == self.matcher.canonicalize(u32::from(b))
+            });
+        equal && k(next, captures)
+    }
+
+    /// Repeats a single character node, which can't contain captures nor match the empty
+    /// string, so the positions it can stop at are found without recursion.
+    fn match_simple_repeat(
+        &self,
+        repeat: &Repeat<'_>,
+        position: usize,
+        captures: &mut Captures,
+        back: bool,
+        k: Continuation<'_>,
+    ) -> bool {
+        let mut positions = vec![position];
+        let mut current = position;
+        while repeat.max.map_or(true, |max| positions.len() <= max) {
+            match self.read_char(current, back) {
+                Some((c, next)) if self.matcher.char_matches(repeat.node, c) => {
+                    current = next;
+                    positions.push(current);
+                }
+                _ => break,
+            }
+        }
+        if positions.len() <= repeat.min {
+            return false;
+        }
+        let candidates = &positions[repeat.min..];
+        if repeat.greedy {
+            candidates.iter().rev().any(|&end| k(end, captures))
+        } else {
+            candidates.iter().any(|&end| k(end, captures))
+        }
+    }
+
+    /// The `RepeatMatcher` of the specification, `count` being the number of iterations done
+    /// so far.
+    ///
+    /// More information:
+    ///  - [ECMAScript reference][spec]
+    ///
+    /// [spec]: https://tc39.es/ecma262/#sec-runtime-semantics-repeatmatcher-abstract-operation
+    fn match_repeat(
+        &self,
+        repeat: &Repeat<'_>,
+        count: usize,
+        position: usize,
+        captures: &mut Captures,
+        back: bool,
+        k: Continuation<'_>,
+    ) -> bool {
+        if repeat.max.map_or(false, |max| count >= max) {
+            return k(position, captures);
+        }
+        if count < repeat.min {
+            return self.match_iteration(repeat, count, position, captures, back, k);
+        }
+        // A greedy repetition tries one more iteration before the rest of the pattern, a lazy one
+        // after it
+        if repeat.greedy && self.match_iteration(repeat, count, position, captures, back, &mut *k) {
+            return true;
+        }
+        k(position, captures)
+            || (!repeat.greedy
+                && self.match_iteration(repeat, count, position, captures, back, &mut *k))
+    }
+
+    /// Matches one more iteration of a repetition, and then the rest of it.
+    fn match_iteration(
+        &self,
+        repeat: &Repeat<'_>,
+        count: usize,
+        position: usize,
+        captures: &mut Captures,
+        back: bool,
+        k: Continuation<'_>,
+    ) -> bool {
+        // The captures of the previous iteration are cleared
+        let saved: Vec<_> = captures[repeat.groups.clone()].to_vec();
+        for capture in &mut captures[repeat.groups.clone()] {
+            *capture = None;
+        }
+        let matched = self.match_node(
+            repeat.node,
+            position,
+            captures,
+            back,
+            &mut |next, captures| {
+                // Once the minimum is reached, an empty iteration would repeat forever
+                if next == position && count >= repeat.min {
+                    return false;
+                }
+                self.match_repeat(repeat, count + 1, next, captures, back, &mut *k)
+            },
+        );
+        if !matched {
+            captures[repeat.groups.clone()].copy_from_slice(&saved);
+        }
+        matched
+    }
+}
+
+/// The stack space left below which `match_node` switches to a new stack segment.
+const STACK_RED_ZONE: usize = 64 * 1024;
+
+/// The size of the stack segments allocated by `match_node`.
+const STACK_GROWTH: usize = 1024 * 1024;
+
+/// The parameters of a quantified node.
+struct Repeat<'a> {
+    node: &'a Node,
+    min: usize,
+    max: Option<usize>,
+    greedy: bool,
+    groups: std::ops::Range<usize>,
+}
@@ -9,33 +9,40 @@
 //! [spec]: https://tc39.es/ecma262/#sec-regexp-constructor
 //! [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/RegExp
 
-use std::ops::Deref;
-
-use regex::Regex;
-
+use self::{
+    matcher::{Aborted, Matcher},
+    regexp_string_iterator::create_regexp_string_iterator,
+};
 use crate::{
     builtins::{
-        object::{InternalState, Object, ObjectInternalMethods, ObjectKind, PROTOTYPE},
-        property::Property,
-        value::{ResultValue, Value, ValueData},
+        array::create_array_from_list,
+        object::{
+            enumerable_own_keys, internal_methods_trait::ObjectInternalMethods, InternalState,
+            Object, ObjectKind, PROTOTYPE,
+        },
+        string::{get_substitution, to_js_string},
+        symbol::WellKnownSymbol,
+        typed_array::modulo,
+        value::{same_value, JsString, ResultValue, Value},
     },
     exec::Interpreter,
 };
 
+mod matcher;
+mod parser;
+pub mod regexp_string_iterator;
+
 #[cfg(test)]
 mod tests;
 
 /// The internal representation on a `RegExp` object.
 #[derive(Debug)]
-struct RegExp {
+pub(crate) struct RegExp {
     /// Regex matcher.
-    matcher: Regex,
-
-    /// Update last_index, set if global or sticky flags are set.
-    use_last_index: bool,
+    matcher: Matcher,
 
     /// String of parsed flags.
-    flags: String,
+    pub(crate) flags: String,
 
     /// Flag 's' - dot matches newline characters.
     dot_all: bool,
@@ -43,6 +50,9 @@ struct RegExp {
     /// Flag 'g'
     global: bool,
 
+    /// Flag 'd' - the match results have the indices of the captures.
+    has_indices: bool,
+
     /// Flag 'i' - ignore case.
     ignore_case: bool,
 
@@ -58,110 +68,148 @@ struct RegExp {
 
 impl InternalState for RegExp {}
 
-/// Create a new `RegExp`
-pub fn make_regexp(this: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValue {
-    if args.is_empty() {
-        return Err(Value::undefined());
-    }
-    let mut regex_body = String::new();
-    let mut regex_flags = String::new();
-    #[allow(clippy::indexing_slicing)] // length has been checked
-    match args[0].deref() {
-        ValueData::String(ref body) => {
-            // first argument is a string -> use it as regex pattern
-            regex_body = body.into();
-        }
-        ValueData::Object(ref obj) => {
-            let slots = &obj.borrow().internal_slots;
-            if slots.get("RegExpMatcher").is_some() {
-                // first argument is another `RegExp` object, so copy its pattern and flags
-                if let Some(body) = slots.get("OriginalSource") {
-                    regex_body = String::from(body);
-                }
-                if let Some(flags) = slots.get("OriginalFlags") {
-                    regex_flags = String::from(flags);
-                }
-            }
+impl RegExp {
+    /// Compiles the pattern `source` with the given flags, which must be valid flags in the order
+    /// of the `flags` getter. Returns the message of the `SyntaxError` to throw if the pattern
+    /// isn't valid.
+    pub(crate) fn new(source: &[u16], flags: &str) -> Result<Self, String> {
+        let has_flag = |flag| flags.contains(flag);
+        Ok(Self {
+            matcher: Matcher::new(
+                source,
+                has_flag('i'),
+                has_flag('m'),
+                has_flag('s'),
+                has_flag('u'),
+            )?,
+            flags: flags.to_string(),
+            dot_all: has_flag('s'),
+            global: has_flag('g'),
+            has_indices: has_flag('d'),
+            ignore_case: has_flag('i'),
+            multiline: has_flag('m'),
+            sticky: has_flag('y'),
+            unicode: has_flag('u'),
+        })
+    }
+}
+
+/// The abstract operation `RegExpInitialize`, which compiles the pattern of a `RegExp` object.
+///
+/// Throws a `SyntaxError` if the flags or the pattern are invalid.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-regexpinitialize
+fn regexp_initialize(
+    this: &mut Value,
+    source: JsString,
+    flags: JsString,
+    ctx: &mut Interpreter,
+) -> ResultValue {
+    let invalid_flags = || {
+        ctx.construct_syntax_error(format!(
+            "Invalid flags supplied to RegExp constructor '{}'",
+            flags
+        ))
+    };
+
+    // The flags are kept in the order of the `flags` getter, whatever the given order is
+    let mut sorted_flags = String::new();
+    for flag in "dgimsuy".chars() {
+        let count = flags.iter().filter(|&&unit| unit == flag as u16).count();
+        if count > 1 {
+            return Err(invalid_flags());
         }
-        _ => return Err(Value::undefined()),
-    }
-    // if a second argument is given and it's a string, use it as flags
-    match args.get(1) {
-        None => {}
-        Some(flags) => {
-            if let ValueData::String(flags) = flags.deref() {
-                regex_flags = flags.into();
-            }
+        if count == 1 {
+            sorted_flags.push(flag);
         }
     }
+    if sorted_flags.len() != flags.len() {
+        return Err(invalid_flags());
+    }
 
-    // parse flags
-    let mut sorted_flags = String::new();
-    let mut pattern = String::new();
-    let mut dot_all = false;
-    let mut global = false;
-    let mut ignore_case = false;
-    let mut multiline = false;
-    let mut sticky = false;
-    let mut unicode = false;
-    if regex_flags.contains('g') {
-        global = true;
-        sorted_flags.push('g');
-    }
-    if regex_flags.contains('i') {
-        ignore_case = true;
-        sorted_flags.push('i');
-        pattern.push('i');
-    }
-    if regex_flags.contains('m') {
-        multiline = true;
-        sorted_flags.push('m');
-        pattern.push('m');
-    }
-    if regex_flags.contains('s') {
-        dot_all = true;
-        sorted_flags.push('s');
-        pattern.push('s');
-    }
-    if regex_flags.contains('u') {
-        unicode = true;
-        sorted_flags.push('u');
-        //pattern.push('s'); // rust uses utf-8 anyway
-    }
-    if regex_flags.contains('y') {
-        sticky = true;
-        sorted_flags.push('y');
-    }
-    // the `regex` crate uses '(?{flags})` inside the pattern to enable flags
-    if !pattern.is_empty() {
-        pattern = format!("(?{})", pattern);
-    }
-    pattern.push_str(regex_body.as_str());
-
-    let matcher = Regex::new(pattern.as_str()).expect("failed to create matcher");
-    let regexp = RegExp {
-        matcher,
-        use_last_index: global || sticky,
-        flags: sorted_flags,
-        dot_all,
-        global,
-        ignore_case,
-        multiline,
-        sticky,
-        unicode,
+    let regexp = match RegExp::new(&source, &sorted_flags) {
+        Ok(regexp) => regexp,
+        Err(message) => {
+            return ctx.throw_syntax_error(format!(
+                "Invalid regular expression: /{}/: {}",
+                source, message
+            ))
+        }
     };
 
     // This value is used by console.log and other routines to match Object type
     // to its Javascript Identifier (global constructor method name)
     this.set_kind(ObjectKind::Ordinary);
     this.set_internal_slot("RegExpMatcher", Value::undefined());
-    this.set_internal_slot("OriginalSource", Value::from(regex_body));
-    this.set_internal_slot("OriginalFlags", Value::from(regex_flags));
-
+    this.set_internal_slot("OriginalSource", Value::from(source));
+    this.set_internal_slot("OriginalFlags", Value::from(sorted_flags));
     this.set_internal_state(regexp);
+    this.set_field_slice("lastIndex", Value::from(0));
     Ok(this.clone())
 }
 
+/// Create a new `RegExp`
+pub fn make_regexp(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let pattern = args.get(0).cloned().unwrap_or_else(Value::undefined);
+    let flags = args.get(1).cloned().unwrap_or_else(Value::undefined);
+
+    // A `RegExp` pattern is copied, with its flags unless others are given
+    let (source, flags) = if is_regexp(&pattern) {
+        let flags = if flags.is_undefined() {
+            pattern.get_internal_slot("OriginalFlags")
+        } else {
+            flags
+        };
+        (pattern.get_internal_slot("OriginalSource"), flags)
+    } else {
+        (pattern, flags)
+    };
+    let source = if source.is_undefined() {
+        JsString::new()
+    } else {
+        to_js_string(&source, ctx)?
+    };
+    let flags = if flags.is_undefined() {
+        JsString::new()
+    } else {
+        to_js_string(&flags, ctx)?
+    };
+    regexp_initialize(this, source, flags, ctx)
+}
+
+/// Calling `RegExp` as a function returns the pattern itself when it is a `RegExp` object and no
+/// flags are given, otherwise it creates a new `RegExp` object.
+pub fn call_regexp(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let constructor = ctx.realm.intrinsics.get_field_slice("RegExp");
+    if let Some(pattern) = args.get(0) {
+        let flags = args.get(1).cloned().unwrap_or_else(Value::undefined);
+        if is_regexp(pattern)
+            && flags.is_undefined()
+            && pattern
+                .get_field_slice("constructor")
+                .strict_equals(&constructor)
+        {
+            return Ok(pattern.clone());
+        }
+    }
+    ctx.construct(&constructor, args)
+}
+
+/// The abstract operation `RegExpCreate`, which creates a `RegExp` object from a pattern and
+/// flags.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-regexpcreate
+pub(crate) fn regexp_create(pattern: Value, flags: &str, ctx: &mut Interpreter) -> ResultValue {
+    let constructor = ctx.realm.intrinsics.get_field_slice("RegExp");
+    ctx.construct(&constructor, &[pattern, Value::from(flags)])
+}
+
 /// `RegExp.prototype.dotAll`
 ///
 /// The `dotAll` property indicates whether or not the "`s`" flag is used with the regular expression.
@@ -244,8 +292,9 @@ fn get_multiline(this: &mut Value, _: &[Value], _: &mut Interpreter) -> ResultVa
 ///
 /// [spec]: https://tc39.es/ecma262/#sec-get-regexp.prototype.source
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/RegExp/source
-fn get_source(this: &mut Value, _: &[Value], _: &mut Interpreter) -> ResultValue {
-    Ok(this.get_internal_slot("OriginalSource"))
+fn get_source(this: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let source = to_js_string(&this.get_internal_slot("OriginalSource"), ctx)?;
+    Ok(Value::from(escape_pattern(&source)))
 }
 
 /// `RegExp.prototype.sticky`
@@ -277,6 +326,227 @@ fn get_unicode(this: &mut Value, _: &[Value], _: &mut Interpreter) -> ResultValu
     this.with_internal_state_ref(|regex: &RegExp| Ok(Value::from(regex.unicode)))
 }
 
+/// `RegExp.prototype.hasIndices`
+///
+/// The `hasIndices` property indicates whether or not the "`d`" flag is used with the regular expression.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-get-regexp.prototype.hasIndices
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/RegExp/hasIndices
+fn get_has_indices(this: &mut Value, _: &[Value], _: &mut Interpreter) -> ResultValue {
+    this.with_internal_state_ref(|regex: &RegExp| Ok(Value::from(regex.has_indices)))
+}
+
+/// Runs `f` with the internal state of a `RegExp` object, or returns `None` if the value isn't
+/// one.
+fn with_regexp<R, F: FnOnce(&RegExp) -> R>(value: &Value, f: F) -> Option<R> {
+    let state = value.get_internal_state()?;
+    state.downcast_ref::<RegExp>().map(f)
+}
+
+/// The abstract operation `ToLength`, which clamps a value to a valid index.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-tolength
+fn to_length(value: &Value, ctx: &mut Interpreter) -> Result<usize, Value> {
+    let length = ctx.to_number(value)?;
+    if length.is_nan() || length <= 0.0 {
+        Ok(0)
+    } else {
+        Ok(length.min(9_007_199_254_740_991.0).trunc() as usize)
+    }
+}
+
+/// The abstract operation `AdvanceStringIndex`, which moves past a whole code point in Unicode
+/// mode.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-advancestringindex
+fn advance_string_index(string: &JsString, index: usize, unicode: bool) -> usize {
+    if !unicode || index + 1 >= string.len() {
+        return index + 1;
+    }
+    index
+        + string
+            .code_point_at(index)
+            .map_or(1, |code_point| code_point.code_unit_count)
+}
+
+/// Reads the flags of a `RegExp` object, or its `flags` property for other objects.
+pub(crate) fn regexp_flags(regexp: &Value, ctx: &mut Interpreter) -> Result<String, Value> {
+    // Property getters aren't invoked yet, so the flags of `RegExp` objects are read directly
+    match with_regexp(regexp, |regex| regex.flags.clone()) {
+        Some(flags) => Ok(flags),
+        None => Ok(to_js_string(&regexp.get_field_slice("flags"), ctx)?.to_std_string_lossy()),
+    }
+}
+
+/// The abstract operation `SpeciesConstructor`, with `%RegExp%` as the default constructor.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-speciesconstructor
+fn species_constructor(object: &Value, ctx: &mut Interpreter) -> ResultValue {
+    let default = ctx.realm.intrinsics.get_field_slice("RegExp");
+    let constructor = object.get_field_slice("constructor");
+    if constructor.is_undefined() {
+        return Ok(default);
+    }
+    if !constructor.is_object() {
+        return ctx.throw_type_error("object.constructor is not an object");
+    }
+    let species = constructor.get_field(Value::symbol(WellKnownSymbol::Species.into()));
+    if species.is_null_or_undefined() {
+        return Ok(default);
+    }
+    if species
+        .as_object()
+        .map_or(false, |obj| obj.is_constructor())
+    {
+        Ok(species)
+    } else {
+        ctx.throw_type_error("object.constructor[Symbol.species] is not a constructor")
+    }
+}
+
+/// The abstract operation `RegExpBuiltinExec`, which matches a `RegExp` object against a string
+/// starting at its `lastIndex`.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-regexpbuiltinexec
+fn regexp_builtin_exec(this: &Value, input: &JsString, ctx: &mut Interpreter) -> ResultValue {
+    let state = this
+        .get_internal_state()
+        .expect("RegExpBuiltinExec called on a RegExp object");
+    let regexp = state
+        .downcast_ref::<RegExp>()
+        .expect("RegExpBuiltinExec called on a RegExp object");
+
+    let use_last_index = regexp.global || regexp.sticky;
+    let mut last_index = if use_last_index {
+        to_length(&this.get_field_slice("lastIndex"), ctx)?
+    } else {
+        0
+    };
+    let mut attempts = 0;
+    let captures = loop {
+        if last_index > input.len() {
+            if use_last_index {
+                this.set_field_slice("lastIndex", Value::from(0));
+            }
+            return Ok(Value::null());
+        }
+        ctx.check_limits(attempts)?;
+        attempts += 1;
+        let mut go_on = || ctx.check_limits_now().is_ok();
+        match regexp.matcher.match_at(input, last_index, &mut go_on) {
+            Ok(Some(captures)) => break captures,
+            // The termination is kept by the interpreter, so checking again returns its error
+            Err(Aborted) => return ctx.check_limits_now().map(|_| Value::undefined()),
+            Ok(None) if regexp.sticky => {
+                this.set_field_slice("lastIndex", Value::from(0));
+                return Ok(Value::null());
+            }
+            Ok(None) => last_index = advance_string_index(input, last_index, regexp.unicode),
+        }
+    };
+
+    let (start, end) = captures[0].expect("a match has a range");
+    if use_last_index {
+        this.set_field_slice("lastIndex", Value::from(end));
+    }
+    let substring = |range: Option<(usize, usize)>| {
+        range.map_or_else(Value::undefined, |(start, end)| {
+            Value::from(JsString::from(&input[start..end]))
+        })
+    };
+    let values: Vec<Value> = captures.iter().map(|&range| substring(range)).collect();
+    let result = create_array_from_list(&values, ctx)?;
+    result.set_field_slice("index", Value::from(start));
+    result.set_field_slice("input", Value::from(input.clone()));
+
+    let names = regexp.matcher.group_names();
+    let groups = if names.is_empty() {
+        Value::undefined()
+    } else {
+        let groups = Value::new_object(None);
+        for (name, index) in names {
+            groups.set_field_slice(name, substring(captures[*index]));
+        }
+        groups
+    };
+    result.set_field_slice("groups", groups);
+
+    if regexp.has_indices {
+        let mut pairs = Vec::with_capacity(captures.len());
+        for range in &captures {
+            pairs.push(match range {
+                Some((start, end)) => {
+                    create_array_from_list(&[Value::from(*start), Value::from(*end)], ctx)?
+                }
+                None => Value::undefined(),
+            });
+        }
+        let indices = create_array_from_list(&pairs, ctx)?;
+        let index_groups = if names.is_empty() {
+            Value::undefined()
+        } else {
+            let index_groups = Value::new_object(None);
+            for (name, index) in names {
+                index_groups.set_field_slice(name, pairs[*index].clone());
+            }
+            index_groups
+        };
+        indices.set_field_slice("groups", index_groups);
+        result.set_field_slice("indices", indices);
+    }
+    Ok(result)
+}
+
+/// The abstract operation `RegExpExec`, which calls the `exec` method of an object, so that
+/// subclasses and look-alikes of `RegExp` can take part in the matching protocol.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-regexpexec
+pub(crate) fn regexp_exec(this: &Value, input: &JsString, ctx: &mut Interpreter) -> ResultValue {
+    let exec = this.get_field_slice("exec");
+    if exec.is_function() {
+        let result = ctx.call(&exec, &mut this.clone(), &[Value::from(input.clone())])?;
+        if !result.is_object() && !result.is_null() {
+            return ctx.throw_type_error("exec result must be an object or null");
+        }
+        return Ok(result);
+    }
+    if !is_regexp(this) {
+        return ctx.throw_type_error("exec called on an incompatible receiver");
+    }
+    regexp_builtin_exec(this, input, ctx)
+}
+
+/// Throws a `TypeError` unless `this` is an object, as the methods of `RegExp.prototype` require.
+fn require_object(this: &Value, method: &str, ctx: &Interpreter) -> Result<(), Value> {
+    if this.is_object() {
+        Ok(())
+    } else {
+        Err(ctx.construct_type_error(format!(
+            "RegExp.prototype.{} called on a non-object",
+            method
+        )))
+    }
+}
+
 /// `RegExp.prototype.test( string )`
 ///
 /// The `test()` method executes a search for a match between a regular expression and a specified string.
@@ -289,25 +559,10 @@ fn get_unicode(this: &mut Value, _: &[Value], _: &mut Interpreter) -> ResultValu
 ///
 /// [spec]: https://tc39.es/ecma262/#sec-regexp.prototype.test
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/RegExp/test
-pub fn test(this: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValue {
-    let arg_str = String::from(args.get(0).expect("could not get argument"));
-    let mut last_index = usize::from(&this.get_field_slice("lastIndex"));
-    let result = this.with_internal_state_ref(|regex: &RegExp| {
-        let result = if let Some(m) = regex.matcher.find_at(arg_str.as_str(), last_index) {
-            if regex.use_last_index {
-                last_index = m.end();
-            }
-            true
-        } else {
-            if regex.use_last_index {
-                last_index = 0;
-            }
-            false
-        };
-        Ok(Value::boolean(result))
-    });
-    this.set_field_slice("lastIndex", Value::from(last_index));
-    result
+pub fn test(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    require_object(this, "test", ctx)?;
+    let input = to_js_string(args.get(0).unwrap_or(&Value::undefined()), ctx)?;
+    Ok(Value::from(!regexp_exec(this, &input, ctx)?.is_null()))
 }
 
 /// `RegExp.prototype.exec( string )`
@@ -322,44 +577,12 @@ pub fn test(this: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValu
 ///
 /// [spec]: https://tc39.es/ecma262/#sec-regexp.prototype.exec
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/RegExp/exec
-pub fn exec(this: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValue {
-    let arg_str = String::from(args.get(0).expect("could not get argument"));
-    let mut last_index = usize::from(&this.get_field_slice("lastIndex"));
-    let result = this.with_internal_state_ref(|regex: &RegExp| {
-        let mut locations = regex.matcher.capture_locations();
-        let result = if let Some(m) =
-            regex
-                .matcher
-                .captures_read_at(&mut locations, arg_str.as_str(), last_index)
-        {
-            if regex.use_last_index {
-                last_index = m.end();
-            }
-            let mut result = Vec::with_capacity(locations.len());
-            for i in 0..locations.len() {
-                if let Some((start, end)) = locations.get(i) {
-                    result.push(Value::from(
-                        arg_str.get(start..end).expect("Could not get slice"),
-                    ));
-                } else {
-                    result.push(Value::undefined());
-                }
-            }
-
-            let result = Value::from(result);
-            result.set_property_slice("index", Property::default().value(Value::from(m.start())));
-            result.set_property_slice("input", Property::default().value(Value::from(arg_str)));
-            result
-        } else {
-            if regex.use_last_index {
-                last_index = 0;
-            }
-            Value::null()
-        };
-        Ok(result)
-    });
-    this.set_field_slice("lastIndex", Value::from(last_index));
-    result
+pub fn exec(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    if !is_regexp(this) {
+        return ctx.throw_type_error("RegExp.prototype.exec called on an incompatible receiver");
+    }
+    let input = to_js_string(args.get(0).unwrap_or(&Value::undefined()), ctx)?;
+    regexp_builtin_exec(this, &input, ctx)
 }
 
 /// `RegExp.prototype[ @@match ]( string )`
@@ -372,42 +595,267 @@ pub fn exec(this: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValu
 ///
 /// [spec]: https://tc39.es/ecma262/#sec-regexp.prototype-@@match
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/RegExp/@@match
-pub fn r#match(this: &mut Value, arg: String, ctx: &mut Interpreter) -> ResultValue {
-    let (matcher, flags) =
-        this.with_internal_state_ref(|regex: &RegExp| (regex.matcher.clone(), regex.flags.clone()));
-    if flags.contains('g') {
-        let mut matches = Vec::new();
-        for mat in matcher.find_iter(&arg) {
-            matches.push(Value::from(mat.as_str()));
+pub fn r#match(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    require_object(this, "[Symbol.match]", ctx)?;
+    let input = to_js_string(args.get(0).unwrap_or(&Value::undefined()), ctx)?;
+    let flags = regexp_flags(this, ctx)?;
+    if !flags.contains('g') {
+        return regexp_exec(this, &input, ctx);
+    }
+
+    let full_unicode = flags.contains('u');
+    this.set_field_slice("lastIndex", Value::from(0));
+    let mut matches = Vec::new();
+    loop {
+        let result = regexp_exec(this, &input, ctx)?;
+        if result.is_null() {
+            if matches.is_empty() {
+                return Ok(Value::null());
+            }
+            return create_array_from_list(&matches, ctx);
         }
-        if matches.is_empty() {
-            return Ok(Value::null());
+        let matched = to_js_string(&result.get_field_slice("0"), ctx)?;
+        // An empty match would be found again at the same index
+        if matched.is_empty() {
+            let this_index = to_length(&this.get_field_slice("lastIndex"), ctx)?;
+            let next_index = advance_string_index(&input, this_index, full_unicode);
+            this.set_field_slice("lastIndex", Value::from(next_index));
         }
-        Ok(Value::from(matches))
+        matches.push(Value::from(matched));
+    }
+}
+
+/// `RegExp.prototype[ @@replace ]( string, replaceValue )`
+///
+/// This method replaces some or all matches of the regular expression in a string, with a
+/// replacement string or the result of a function called for each match.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-regexp.prototype-@@replace
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/RegExp/@@replace
+pub fn replace(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    require_object(this, "[Symbol.replace]", ctx)?;
+    let input = to_js_string(args.get(0).unwrap_or(&Value::undefined()), ctx)?;
+    let replace_value = args.get(1).cloned().unwrap_or_else(Value::undefined);
+    let replacement = if replace_value.is_function() {
+        None
     } else {
-        exec(this, &[Value::from(arg)], ctx)
+        Some(to_js_string(&replace_value, ctx)?)
+    };
+    let flags = regexp_flags(this, ctx)?;
+    let global = flags.contains('g');
+    let full_unicode = flags.contains('u');
+
+    if global {
+        this.set_field_slice("lastIndex", Value::from(0));
+    }
+    let mut results = Vec::new();
+    loop {
+        let result = regexp_exec(this, &input, ctx)?;
+        if result.is_null() {
+            break;
+        }
+        results.push(result.clone());
+        if !global {
+            break;
+        }
+        let matched = to_js_string(&result.get_field_slice("0"), ctx)?;
+        if matched.is_empty() {
+            let this_index = to_length(&this.get_field_slice("lastIndex"), ctx)?;
+            let next_index = advance_string_index(&input, this_index, full_unicode);
+            this.set_field_slice("lastIndex", Value::from(next_index));
+        }
     }
+
+    let mut accumulated = Vec::with_capacity(input.len());
+    let mut next_source_position = 0;
+    for result in results {
+        let capture_count = to_length(&result.get_field_slice("length"), ctx)?.max(1) - 1;
+        let matched = to_js_string(&result.get_field_slice("0"), ctx)?;
+        let position = ctx
+            .to_number(&result.get_field_slice("index"))?
+            .max(0.0)
+            .min(input.len() as f64) as usize;
+        let mut captures = vec![Some(matched.clone())];
+        for index in 1..=capture_count {
+            let capture = result.get_field_slice(&index.to_string());
+            captures.push(if capture.is_undefined() {
+                None
+            } else {
+                Some(to_js_string(&capture, ctx)?)
+            });
+        }
+        let named_captures = result.get_field_slice("groups");
+
+        let replaced = match replacement {
+            Some(ref replacement) => {
+                let mut named_groups = Vec::new();
+                if !named_captures.is_undefined() {
+                    let groups = ctx.to_object(&named_captures)?;
+                    for key in enumerable_own_keys(&groups) {
+                        let group = groups.get_field(key.clone());
+                        let group = if group.is_undefined() {
+                            None
+                        } else {
+                            Some(to_js_string(&group, ctx)?)
+                        };
+                        named_groups.push((key.to_string(), group));
+                    }
+                }
+                let m = RegExpMatch {
+                    start: position,
+                    end: position + matched.len(),
+                    captures,
+                    named_groups,
+                };
+                JsString::from(get_substitution(&input, &m, replacement))
+            }
+            None => {
+                // The function receives the match, the captures, the position, the whole string
+                // and the named groups if there are some
+                let mut arguments: Vec<Value> = captures
+                    .into_iter()
+                    .map(|capture| capture.map_or_else(Value::undefined, Value::from))
+                    .collect();
+                arguments.push(Value::from(position));
+                arguments.push(Value::from(input.clone()));
+                if !named_captures.is_undefined() {
+                    arguments.push(named_captures);
+                }
+                let replaced = ctx.call(&replace_value, &mut Value::undefined(), &arguments)?;
+                to_js_string(&replaced, ctx)?
+            }
+        };
+
+        // Matches which overlap with a previous one are skipped
+        if position >= next_source_position {
+            accumulated.extend_from_slice(&input[next_source_position..position]);
+            accumulated.extend_from_slice(&replaced);
+            next_source_position = position + matched.len();
+        }
+    }
+    if next_source_position < input.len() {
+        accumulated.extend_from_slice(&input[next_source_position..]);
+    }
+    ctx.reserve_string(accumulated.len())?;
+    Ok(Value::from(JsString::from(accumulated)))
 }
 
-/// `RegExp.prototype.toString()`
+/// `RegExp.prototype[ @@search ]( string )`
 ///
-/// Return a string representing the regular expression.
+/// This method returns the index of the first match of the regular expression in a string, or
+/// `-1`. The `lastIndex` of the regular expression is left unchanged.
 ///
 /// More information:
 ///  - [ECMAScript reference][spec]
 ///  - [MDN documentation][mdn]
 ///
-/// [spec]: https://tc39.es/ecma262/#sec-regexp.prototype.tostring
-/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/RegExp/toString
-pub fn to_string(this: &mut Value, _: &[Value], _: &mut Interpreter) -> ResultValue {
-    let body = String::from(&this.get_internal_slot("OriginalSource"));
-    let flags = this.with_internal_state_ref(|regex: &RegExp| regex.flags.clone());
-    Ok(Value::from(format!("/{}/{}", body, flags)))
+/// [spec]: https://tc39.es/ecma262/#sec-regexp.prototype-@@search
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/RegExp/@@search
+pub fn search(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    require_object(this, "[Symbol.search]", ctx)?;
+    let input = to_js_string(args.get(0).unwrap_or(&Value::undefined()), ctx)?;
+
+    let previous_last_index = this.get_field_slice("lastIndex");
+    if !same_value(&previous_last_index, &Value::from(0), false) {
+        this.set_field_slice("lastIndex", Value::from(0));
+    }
+    let result = regexp_exec(this, &input, ctx)?;
+    let current_last_index = this.get_field_slice("lastIndex");
+    if !same_value(&current_last_index, &previous_last_index, false) {
+        this.set_field_slice("lastIndex", previous_last_index);
+    }
+
+    if result.is_null() {
+        Ok(Value::from(-1))
+    } else {
+        Ok(result.get_field_slice("index"))
+    }
+}
+
+/// `RegExp.prototype[ @@split ]( string, limit )`
+///
+/// This method splits a string into an array of substrings at the matches of the regular
+/// expression, the captures of each match are included in the array.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-regexp.prototype-@@split
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/RegExp/@@split
+pub fn split(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    require_object(this, "[Symbol.split]", ctx)?;
+    let input = to_js_string(args.get(0).unwrap_or(&Value::undefined()), ctx)?;
+    let constructor = species_constructor(this, ctx)?;
+    let flags = regexp_flags(this, ctx)?;
+    let unicode_matching = flags.contains('u');
+
+    // The splitter is sticky, so that it can be tried at every index
+    let new_flags = if flags.contains('y') {
+        flags
+    } else {
+        format!("{}y", flags)
+    };
+    let splitter = ctx.construct(&constructor, &[this.clone(), Value::from(new_flags)])?;
+
+    let limit = match args.get(1) {
+        Some(limit) if !limit.is_undefined() => modulo(ctx.to_number(limit)?, 32) as usize,
+        _ => u32::MAX as usize,
+    };
+    let mut parts = Vec::new();
+    if limit == 0 {
+        return create_array_from_list(&parts, ctx);
+    }
+
+    let size = input.len();
+    if size == 0 {
+        if regexp_exec(&splitter, &input, ctx)?.is_null() {
+            parts.push(Value::from(input));
+        }
+        return create_array_from_list(&parts, ctx);
+    }
+
+    let mut p = 0;
+    let mut q = 0;
+    while q < size {
+        splitter.set_field_slice("lastIndex", Value::from(q));
+        let z = regexp_exec(&splitter, &input, ctx)?;
+        if z.is_null() {
+            q = advance_string_index(&input, q, unicode_matching);
+            continue;
+        }
+        let e = to_length(&splitter.get_field_slice("lastIndex"), ctx)?.min(size);
+        if e == p {
+            q = advance_string_index(&input, q, unicode_matching);
+            continue;
+        }
+
+        parts.push(Value::from(JsString::from(&input[p..q])));
+        if parts.len() == limit {
+            return create_array_from_list(&parts, ctx);
+        }
+        p = e;
+        let capture_count = to_length(&z.get_field_slice("length"), ctx)?.max(1) - 1;
+        for index in 1..=capture_count {
+            parts.push(z.get_field_slice(&index.to_string()));
+            if parts.len() == limit {
+                return create_array_from_list(&parts, ctx);
+            }
+        }
+        q = p;
+    }
+    parts.push(Value::from(JsString::from(&input[p..])));
+    create_array_from_list(&parts, ctx)
 }
 
 /// `RegExp.prototype[ @@matchAll ]( string )`
 ///
-/// The `[@@matchAll]` method returns all matches of the regular expression against a string.
+/// The `[@@matchAll]` method returns an iterator over all the matches of the regular expression
+/// in a string.
 ///
 /// More information:
 ///  - [ECMAScript reference][spec]
@@ -415,53 +863,111 @@ pub fn to_string(this: &mut Value, _: &[Value], _: &mut Interpreter) -> ResultVa
 ///
 /// [spec]: https://tc39.es/ecma262/#sec-regexp-prototype-matchall
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/RegExp/@@matchAll
-// TODO: it's returning an array, it should return an iterator
-pub fn match_all(this: &mut Value, arg_str: String) -> ResultValue {
-    let matches: Vec<Value> = this.with_internal_state_ref(|regex: &RegExp| {
-        let mut matches = Vec::new();
-
-        for m in regex.matcher.find_iter(&arg_str) {
-            if let Some(caps) = regex.matcher.captures(&m.as_str()) {
-                let match_vec = caps
-                    .iter()
-                    .map(|group| match group {
-                        Some(g) => Value::from(g.as_str()),
-                        None => Value::undefined(),
-                    })
-                    .collect::<Vec<Value>>();
-
-                let match_val = Value::from(match_vec);
-
-                match_val
-                    .set_property_slice("index", Property::default().value(Value::from(m.start())));
-                match_val.set_property_slice(
-                    "input",
-                    Property::default().value(Value::from(arg_str.clone())),
-                );
-                matches.push(match_val);
-
-                if !regex.flags.contains('g') {
-                    break;
-                }
+pub fn match_all(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    require_object(this, "[Symbol.matchAll]", ctx)?;
+    let input = to_js_string(args.get(0).unwrap_or(&Value::undefined()), ctx)?;
+    let constructor = species_constructor(this, ctx)?;
+    let flags = regexp_flags(this, ctx)?;
+    let matcher = ctx.construct(&constructor, &[this.clone(), Value::from(flags.clone())])?;
+    let last_index = to_length(&this.get_field_slice("lastIndex"), ctx)?;
+    matcher.set_field_slice("lastIndex", Value::from(last_index));
+
+    Ok(create_regexp_string_iterator(
+        matcher,
+        input,
+        flags.contains('g'),
+        flags.contains('u'),
+        ctx,
+    ))
+}
+
+/// The abstract operation `EscapeRegExpPattern`, which escapes the source of a pattern so that
+/// it can be written as a literal, `/source/flags`.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-escaperegexppattern
+fn escape_pattern(source: &JsString) -> JsString {
+    if source.is_empty() {
+        return JsString::from("(?:)");
+    }
+    let mut result = Vec::with_capacity(source.len());
+    let mut escaped = false;
+    let mut in_class = false;
+    for &unit in source.iter() {
+        match unit {
+            0x0A => result.extend("\\n".encode_utf16()),
+            0x0D => result.extend("\\r".encode_utf16()),
+            0x2028 => result.extend("\\u2028".encode_utf16()),
+            0x2029 => result.extend("\\u2029".encode_utf16()),
+            0x2F if !escaped && !in_class => result.extend("\\/".encode_utf16()),
+            _ => result.push(unit),
+        }
+        if !escaped {
+            if unit == u16::from(b'[') {
+                in_class = true;
+            } else if unit == u16::from(b']') {
+                in_class = false;
             }
         }
+        escaped = !escaped && unit == u16::from(b'\\');
+    }
+    JsString::from(result)
+}
+
+/// `RegExp.prototype.toString()`
+///
+/// Return a string representing the regular expression.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-regexp.prototype.tostring
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/RegExp/toString
+pub fn to_string(this: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    require_object(this, "toString", ctx)?;
+    let source = if is_regexp(this) {
+        escape_pattern(&to_js_string(
+            &this.get_internal_slot("OriginalSource"),
+            ctx,
+        )?)
+    } else {
+        to_js_string(&this.get_field_slice("source"), ctx)?
+    };
+    let flags = regexp_flags(this, ctx)?;
+    Ok(Value::from(format!("/{}/{}", source, flags)))
+}
 
-        matches
-    });
+/// A match of a regular expression, with positions counted in UTF-16 code units.
+#[derive(Debug, Clone)]
+pub(crate) struct RegExpMatch {
+    /// The index of the first code unit of the match.
+    pub(crate) start: usize,
 
-    let length = matches.len();
-    let result = Value::from(matches);
-    result.set_field_slice("length", Value::from(length));
-    result.set_kind(ObjectKind::Array);
+    /// The index after the last code unit of the match.
+    pub(crate) end: usize,
 
-    Ok(result)
+    /// The matched substring followed by the capture groups, `None` for groups that did not
+    /// participate in the match.
+    pub(crate) captures: Vec<Option<JsString>>,
+
+    /// The named capture groups, in the order they appear in the pattern.
+    pub(crate) named_groups: Vec<(String, Option<JsString>)>,
+}
+
+/// Checks if the value is a `RegExp` object.
+pub(crate) fn is_regexp(value: &Value) -> bool {
+    value.as_object().map_or(false, |obj| {
+        obj.internal_slots.contains_key("RegExpMatcher")
+    })
 }
 
 /// Create a new `RegExp` object.
 pub fn create(global: &Value) -> Value {
     // Create prototype
     let prototype = Value::new_object(Some(global));
-    prototype.set_field_slice("lastIndex", Value::from(0));
 
     make_builtin_fn!(test, named "test", with length 1, of prototype);
     make_builtin_fn!(exec, named "exec", with length 1, of prototype);
@@ -469,13 +975,47 @@ pub fn create(global: &Value) -> Value {
     make_builtin_fn!(get_dot_all, named "dotAll", of prototype);
     make_builtin_fn!(get_flags, named "flags", of prototype);
     make_builtin_fn!(get_global, named "global", of prototype);
+    make_builtin_fn!(get_has_indices, named "hasIndices", of prototype);
     make_builtin_fn!(get_ignore_case, named "ignoreCase", of prototype);
     make_builtin_fn!(get_multiline, named "multiline", of prototype);
     make_builtin_fn!(get_source, named "source", of prototype);
     make_builtin_fn!(get_sticky, named "sticky", of prototype);
     make_builtin_fn!(get_unicode, named "unicode", of prototype);
 
-    make_constructor_fn!(make_regexp, make_regexp, global, prototype)
+    let symbol_methods = [
+        (
+            WellKnownSymbol::Match,
+            make_builtin_fn!(r#match, named "[Symbol.match]", with length 1),
+        ),
+        (
+            WellKnownSymbol::MatchAll,
+            make_builtin_fn!(match_all, named "[Symbol.matchAll]", with length 1),
+        ),
+        (
+            WellKnownSymbol::Replace,
+            make_builtin_fn!(replace, named "[Symbol.replace]", with length 2),
+        ),
+        (
+            WellKnownSymbol::Search,
+            make_builtin_fn!(search, named "[Symbol.search]", with length 1),
+        ),
+        (
+            WellKnownSymbol::Split,
+            make_builtin_fn!(split, named "[Symbol.split]", with length 2),
+        ),
+    ];
+    for (symbol, method) in symbol_methods.iter().cloned() {
+        prototype.set_field(Value::symbol(symbol.into()), method);
+    }
+
+    let regexp = make_constructor_fn!(make_regexp, call_regexp, global, prototype);
+    // Property getters aren't invoked yet, so `RegExp[Symbol.species]` holds `RegExp` itself
+    regexp.set_field(
+        Value::symbol(WellKnownSymbol::Species.into()),
+        regexp.clone(),
+    );
+
+    regexp
 }
 
 /// Initialise the `RegExp` object on the global object.
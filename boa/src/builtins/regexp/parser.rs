@@ -0,0 +1,773 @@
+//! The parser of regular expression patterns.
+//!
+//! Patterns are parsed into a tree of [`Node`]s, which the matcher walks with backtracking. Without
+//! the "`u`" flag a pattern is a sequence of UTF-16 code units and the legacy syntax of Annex B
+//! is accepted, with it a pattern is a sequence of code points and the syntax is strict.
+//!
+//! More information:
+//!  - [ECMAScript reference][spec]
+//!  - [ECMAScript reference, Annex B][annex-b]
+//!
+//! [spec]: https://tc39.es/ecma262/#sec-patterns
+//! [annex-b]: https://tc39.es/ecma262/#sec-regular-expressions-patterns
+
+use std::ops::Range;
+
+/// The largest code point.
+const MAX_CODE_POINT: u32 = 0x10_FFFF;
+
+/// `\d`
+const DIGIT: &[(u32, u32)] = &[(0x30, 0x39)];
+
+/// `\w`
+const WORD: &[(u32, u32)] = &[(0x30, 0x39), (0x41, 0x5A), (0x5F, 0x5F), (0x61, 0x7A)];
+
+/// `\s`, the white space and line terminator characters.
+const SPACE: &[(u32, u32)] = &[
+    (0x09, 0x0D),
+    (0x20, 0x20),
+    (0xA0, 0xA0),
+    (0x1680, 0x1680),
+    (0x2000, 0x200A),
+    (0x2028, 0x2029),
+    (0x202F, 0x202F),
+    (0x205F, 0x205F),
+    (0x3000, 0x3000),
+    (0xFEFF, 0xFEFF),
+];
+
+/// A node of a parsed pattern.
+#[derive(Debug, Clone)]
+pub(crate) enum Node {
+    /// Matches the empty string.
+    Empty,
+    /// A single character, a code unit or a code point in Unicode mode.
+    Char(u32),
+    /// `.`
+    Dot,
+    /// A character class, like `[a-z]` or `\d`.
+    Class(CharClass),
+    /// `^`
+    LineStart,
+    /// `$`
+    LineEnd,
+    /// `\b`, or `\B` when negated.
+    WordBoundary { negated: bool },
+    /// A capturing group, with its index.
+    Capture(Box<Node>, usize),
+    /// A lookahead or lookbehind assertion.
+    Look {
+        behind: bool,
+        negated: bool,
+        node: Box<Node>,
+    },
+    /// A backreference to the capture group with the index.
+    BackReference(usize),
+    /// A quantified atom. The capture groups inside of it, `groups`, are cleared on every
+    /// iteration.
+    Repeat {
+        node: Box<Node>,
+        min: usize,
+        max: Option<usize>,
+        greedy: bool,
+        groups: Range<usize>,
+    },
+    /// Terms matched one after the other.
+    Sequence(Vec<Node>),
+    /// Alternatives, tried from left to right.
+    Alternation(Vec<Node>),
+}
+
+impl Node {
+    /// Checks if the node always matches exactly one character, which allows repeating it
+    /// without backtracking into each iteration.
+    pub(crate) fn is_single_char(&self) -> bool {
+        match self {
+            Self::Char(_) | Self::Dot | Self::Class(_) => true,
+            _ => false,
+        }
+    }
+}
+
+/// A set of characters, stored as sorted and disjoint ranges.
+#[derive(Debug, Clone)]
+pub(crate) struct CharClass {
+    ranges: Vec<(u32, u32)>,
+    /// Set for `[^...]`, the class then matches the characters which are not in the ranges.
+    pub(crate) negated: bool,
+}
+
+impl CharClass {
+    fn new(mut ranges: Vec<(u32, u32)>, negated: bool) -> Self {
+        ranges.sort_unstable();
+        let mut merged: Vec<(u32, u32)> = Vec::with_capacity(ranges.len());
+        for (start, end) in ranges {
+            match merged.last_mut() {
+                Some(last) if start <= last.1.saturating_add(1) => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+        Self {
+            ranges: merged,
+            negated,
+        }
+    }
+
+    /// Checks if `c` is in the ranges of the class, ignoring the negation.
+    pub(crate) fn contains(&self, c: u32) -> bool {
+        self.ranges
+            .binary_search_by(|&(start, end)| {
+                if end < c {
+                    std::cmp::Ordering::Less
+                } else if start > c {
+                    std::cmp::Ordering::Greater
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            })
+            .is_ok()
+    }
+}
+
+/// Returns the characters which are not in the sorted `ranges`.
+fn complement(ranges: &[(u32, u32)]) -> Vec<(u32, u32)> {
+    let mut result = Vec::with_capacity(ranges.len() + 1);
+    let mut next = 0;
+    for &(start, end) in ranges {
+        if start > next {
+            result.push((next, start - 1));
+        }
+        next = end + 1;
+    }
+    if next <= MAX_CODE_POINT {
+        result.push((next, MAX_CODE_POINT));
+    }
+    result
+}
+
+/// A parsed pattern.
+#[derive(Debug)]
+pub(crate) struct Pattern {
+    /// The root of the tree.
+    pub(crate) node: Node,
+    /// The number of capture groups, not counting the whole match.
+    pub(crate) capture_count: usize,
+    /// The names of the named capture groups with their indices, in the order they appear.
+    pub(crate) group_names: Vec<(String, usize)>,
+}
+
+/// Parses a pattern, returning the message of the `SyntaxError` to throw if it isn't valid.
+pub(crate) fn parse(source: &[u16], unicode: bool) -> Result<Pattern, String> {
+    let chars = if unicode {
+        std::char::decode_utf16(source.iter().copied())
+            .map(|c| match c {
+                Ok(c) => c as u32,
+                Err(error) => u32::from(error.unpaired_surrogate()),
+            })
+            .collect()
+    } else {
+        source.iter().copied().map(u32::from).collect()
+    };
+    let mut parser = Parser {
+        chars,
+        position: 0,
+        unicode,
+        capture_count: 0,
+        group_names: Vec::new(),
+        total_captures: 0,
+    };
+    parser.scan_groups()?;
+
+    let node = parser.parse_disjunction()?;
+    if parser.position < parser.chars.len() {
+        // Only a `)` can end a disjunction early
+        return Err(String::from("Unmatched ')'"));
+    }
+    Ok(Pattern {
+        node,
+        capture_count: parser.capture_count,
+        group_names: parser.group_names,
+    })
+}
+
+/// The state of the parser.
+struct Parser {
+    chars: Vec<u32>,
+    position: usize,
+    unicode: bool,
+    /// The number of capture groups opened so far.
+    capture_count: usize,
+    /// The names of all the capture groups of the pattern, known before parsing it.
+    group_names: Vec<(String, usize)>,
+    /// The number of capture groups of the pattern.
+    total_captures: usize,
+}
+
+/// Checks if a character can start the name of a capture group.
+fn is_name_start(c: u32) -> bool {
+    std::char::from_u32(c).map_or(false, |c| c == '$' || c == '_' || c.is_alphabetic())
+}
+
+/// Checks if a character can continue the name of a capture group.
+fn is_name_continue(c: u32) -> bool {
+    is_name_start(c)
+        || std::char::from_u32(c).map_or(false, |c| {
+            c.is_alphanumeric() || c == '\u{200C}' || c == '\u{200D}'
+        })
+}
+
+fn is_syntax_char(c: u32) -> bool {
+    match std::char::from_u32(c) {
+        Some(c) => "^$\\.*+?()[]{}|/".contains(c),
+        None => false,
+    }
+}
+
+fn digit_value(c: u32, radix: u32) -> Option<u32> {
+    std::char::from_u32(c).and_then(|c| c.to_digit(radix))
+}
+
+impl Parser {
+    fn peek(&self) -> Option<u32> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<u32> {
+        self.chars.get(self.position + offset).copied()
+    }
+
+    /// Consumes `c` if it is the next character.
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c as u32) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Counts the capture groups and collects their names, so that backreferences can refer to
+    /// groups which come after them.
+    fn scan_groups(&mut self) -> Result<(), String> {
+        let mut in_class = false;
+        let mut i = 0;
+        let mut count = 0;
+        while i < self.chars.len() {
+            let c = self.chars[i];
+            if c == '\\' as u32 {
+                i += 2;
+                continue;
+            }
+            if in_class {
+                in_class = c != ']' as u32;
+            } else if c == '[' as u32 {
+                in_class = true;
+            } else if c == '(' as u32 {
+                if self.chars.get(i + 1) != Some(&('?' as u32)) {
+                    count += 1;
+                } else if self.chars.get(i + 2) == Some(&('<' as u32))
+                    && self.chars.get(i + 3) != Some(&('=' as u32))
+                    && self.chars.get(i + 3) != Some(&('!' as u32))
+                {
+                    count += 1;
+                    let start = i + 3;
+                    let end = self.chars[start..]
+                        .iter()
+                        .position(|&c| c == '>' as u32)
+                        .map(|offset| start + offset);
+                    let name = match end {
+                        Some(end)
+                            if end > start
+                                && is_name_start(self.chars[start])
+                                && self.chars[start + 1..end]
+                                    .iter()
+                                    .all(|&c| is_name_continue(c)) =>
+                        {
+                            self.chars[start..end]
+                                .iter()
+                                .filter_map(|&c| std::char::from_u32(c))
+                                .collect::<String>()
+                        }
+                        _ => return Err(String::from("Invalid capture group name")),
+                    };
+                    if self.group_names.iter().any(|(other, _)| *other == name) {
+                        return Err(String::from("Duplicate capture group name"));
+                    }
+                    self.group_names.push((name, count));
+                }
+            }
+            i += 1;
+        }
+        self.total_captures = count;
+        Ok(())
+    }
+
+    /// Parses alternatives separated by `|`, up to a `)` or the end of the pattern.
+    fn parse_disjunction(&mut self) -> Result<Node, String> {
+        let mut alternatives = vec![self.parse_alternative()?];
+        while self.eat('|') {
+            alternatives.push(self.parse_alternative()?);
+        }
+        Ok(if alternatives.len() == 1 {
+            alternatives.pop().expect("one alternative")
+        } else {
+            Node::Alternation(alternatives)
+        })
+    }
+
+    /// Parses a sequence of terms, up to a `|`, a `)` or the end of the pattern.
+    fn parse_alternative(&mut self) -> Result<Node, String> {
+        let mut terms = Vec::new();
+        while let Some(c) = self.peek() {
+            if c == '|' as u32 || c == ')' as u32 {
+                break;
+            }
+            terms.push(self.parse_term()?);
+        }
+        Ok(match terms.len() {
+            0 => Node::Empty,
+            1 => terms.pop().expect("one term"),
+            _ => Node::Sequence(terms),
+        })
+    }
+
+    fn parse_term(&mut self) -> Result<Node, String> {
+        let first_group = self.capture_count + 1;
+        let c = self.peek().expect("parse_term called at the end");
+
+        // Assertions, only lookaheads can be quantified and only without the "u" flag
+        let atom = match std::char::from_u32(c) {
+            Some('^') => {
+                self.position += 1;
+                return Ok(Node::LineStart);
+            }
+            Some('$') => {
+                self.position += 1;
+                return Ok(Node::LineEnd);
+            }
+            Some('\\') if self.peek_at(1) == Some('b' as u32) => {
+                self.position += 2;
+                return Ok(Node::WordBoundary { negated: false });
+            }
+            Some('\\') if self.peek_at(1) == Some('B' as u32) => {
+                self.position += 2;
+                return Ok(Node::WordBoundary { negated: true });
+            }
+            Some('(') if self.peek_at(1) == Some('?' as u32) => {
+                let behind = self.peek_at(2) == Some('<' as u32);
+                let kind = self.peek_at(if behind { 3 } else { 2 });
+                if kind == Some('=' as u32) || kind == Some('!' as u32) {
+                    self.position += if behind { 4 } else { 3 };
+                    let node = self.parse_disjunction()?;
+                    if !self.eat(')') {
+                        return Err(String::from("Unterminated group"));
+                    }
+                    let look = Node::Look {
+                        behind,
+                        negated: kind == Some('!' as u32),
+                        node: Box::new(node),
+                    };
+                    if behind || self.unicode {
+                        return Ok(look);
+                    }
+                    look
+                } else {
+                    self.parse_atom()?
+                }
+            }
+            _ => self.parse_atom()?,
+        };
+
+        let (min, max) = match self.parse_quantifier()? {
+            Some(bounds) => bounds,
+            None => return Ok(atom),
+        };
+        let greedy = !self.eat('?');
+        Ok(Node::Repeat {
+            node: Box::new(atom),
+            min,
+            max,
+            greedy,
+            groups: first_group..self.capture_count + 1,
+        })
+    }
+
+    /// Parses a quantifier, returning its minimum and maximum.
+    fn parse_quantifier(&mut self) -> Result<Option<(usize, Option<usize>)>, String> {
+        let bounds = match self.peek().and_then(std::char::from_u32) {
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('?') => (0, Some(1)),
+            Some('{') => match self.parse_braced_quantifier()? {
+                Some(bounds) => return Ok(Some(bounds)),
+                None => return Ok(None),
+            },
+            _ => return Ok(None),
+        };
+        self.position += 1;
+        Ok(Some(bounds))
+    }
+
+    /// Parses `{n}`, `{n,}` or `{n,m}`. Without the "`u`" flag a brace which doesn't start a
+    /// quantifier is a literal character, so nothing is consumed then.
+    fn parse_braced_quantifier(&mut self) -> Result<Option<(usize, Option<usize>)>, String> {
+        let start = self.position;
+        self.position += 1;
+        let min = self.parse_decimal();
+        let bounds = match min {
+            Some(min) if self.eat('}') => Some((min, Some(min))),
+            Some(min) if self.eat(',') => {
+                if self.eat('}') {
+                    Some((min, None))
+                } else {
+                    match self.parse_decimal() {
+                        Some(max) if self.eat('}') => Some((min, Some(max))),
+                        _ => None,
+                    }
+                }
+            }
+            _ => None,
+        };
+        match bounds {
+            Some((min, Some(max))) if min > max => {
+                Err(String::from("numbers out of order in {} quantifier"))
+            }
+            Some(bounds) => Ok(Some(bounds)),
+            None if self.unicode => Err(String::from("Incomplete quantifier")),
+            None => {
+                self.position = start;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Parses a decimal number, saturating instead of overflowing.
+    fn parse_decimal(&mut self) -> Option<usize> {
+        let mut value: Option<usize> = None;
+        while let Some(digit) = self.peek().and_then(|c| digit_value(c, 10)) {
+            value = Some(
+                value
+                    .unwrap_or(0)
+                    .saturating_mul(10)
+                    .saturating_add(digit as usize),
+            );
+            self.position += 1;
+        }
+        value
+    }
+
+    fn parse_atom(&mut self) -> Result<Node, String> {
+        let c = self.peek().expect("parse_atom called at the end");
+        self.position += 1;
+        match std::char::from_u32(c) {
+            Some('.') => Ok(Node::Dot),
+            Some('(') => self.parse_group(),
+            Some('[') => self.parse_class(),
+            Some('\\') => self.parse_atom_escape(),
+            Some('*') | Some('+') | Some('?') => Err(String::from("Nothing to repeat")),
+            Some('{') => {
+                if self.unicode {
+                    return Err(String::from("Nothing to repeat"));
+                }
+                self.position -= 1;
+                if self.parse_braced_quantifier()?.is_some() {
+                    return Err(String::from("Nothing to repeat"));
+                }
+                self.position += 1;
+                Ok(Node::Char(c))
+            }
+            Some(']') | Some('}') if self.unicode => Err(String::from("Lone quantifier brackets")),
+            _ => Ok(Node::Char(c)),
+        }
+    }
+
+    /// Parses a group after its `(`.
+    fn parse_group(&mut self) -> Result<Node, String> {
+        let capture = if self.eat('?') {
+            if self.eat(':') {
+                None
+            } else if self.eat('<') {
+                // The name has been validated when scanning the groups
+                while !self.eat('>') {
+                    self.position += 1;
+                }
+                Some(self.open_capture())
+            } else {
+                return Err(String::from("Invalid group"));
+            }
+        } else {
+            Some(self.open_capture())
+        };
+
+        let node = self.parse_disjunction()?;
+        if !self.eat(')') {
+            return Err(String::from("Unterminated group"));
+        }
+        Ok(match capture {
+            Some(index) => Node::Capture(Box::new(node), index),
+            None => node,
+        })
+    }
+
+    fn open_capture(&mut self) -> usize {
+        self.capture_count += 1;
+        self.capture_count
+    }
+
+    /// Parses an escape outside of a character class, after its `\`.
+    fn parse_atom_escape(&mut self) -> Result<Node, String> {
+        let c = match self.peek() {
+            Some(c) => c,
+            None => return Err(String::from("\\ at end of pattern")),
+        };
+
+        // Backreferences
+        if c != '0' as u32 && digit_value(c, 10).is_some() {
+            let start = self.position;
+            let index = self.parse_decimal().expect("a digit");
+            if index <= self.total_captures {
+                return Ok(Node::BackReference(index));
+            }
+            if self.unicode {
+                return Err(String::from("Invalid escape"));
+            }
+            // Otherwise it's a legacy octal escape or an identity escape, like `\8`
+            self.position = start;
+        }
+        if c == 'k' as u32 && (self.unicode || !self.group_names.is_empty()) {
+            self.position += 1;
+            if !self.eat('<') {
+                return Err(String::from("Invalid named reference"));
+            }
+            let start = self.position;
+            while self.peek().map_or(false, |c| c != '>' as u32) {
+                self.position += 1;
+            }
+            let name: String = self.chars[start..self.position]
+                .iter()
+                .filter_map(|&c| std::char::from_u32(c))
+                .collect();
+            if !self.eat('>') {
+                return Err(String::from("Invalid named reference"));
+            }
+            return match self.group_names.iter().find(|(other, _)| *other == name) {
+                Some(&(_, index)) => Ok(Node::BackReference(index)),
+                None => Err(String::from("Invalid named capture referenced")),
+            };
+        }
+
+        if let Some(class) = self.parse_class_escape()? {
+            return Ok(Node::Class(CharClass::new(class, false)));
+        }
+        Ok(Node::Char(self.parse_character_escape(false)?))
+    }
+
+    /// Parses the escapes of sets of characters, `\d`, `\D`, `\s`, `\S`, `\w` and `\W`.
+    fn parse_class_escape(&mut self) -> Result<Option<Vec<(u32, u32)>>, String> {
+        let set = match self.peek().and_then(std::char::from_u32) {
+            Some('d') => DIGIT.to_vec(),
+            Some('D') => complement(DIGIT),
+            Some('s') => SPACE.to_vec(),
+            Some('S') => complement(SPACE),
+            Some('w') => WORD.to_vec(),
+            Some('W') => complement(WORD),
+            Some('p') | Some('P') if self.unicode => {
+                return Err(String::from("Unicode property escapes are not supported"))
+            }
+            _ => return Ok(None),
+        };
+        self.position += 1;
+        Ok(Some(set))
+    }
+
+    /// Parses an escape which stands for a single character, after its `\`.
+    fn parse_character_escape(&mut self, in_class: bool) -> Result<u32, String> {
+        let c = match self.peek() {
+            Some(c) => c,
+            None => return Err(String::from("\\ at end of pattern")),
+        };
+        self.position += 1;
+        let value = match std::char::from_u32(c) {
+            Some('t') => 0x09,
+            Some('n') => 0x0A,
+            Some('v') => 0x0B,
+            Some('f') => 0x0C,
+            Some('r') => 0x0D,
+            Some('c') => {
+                let letter = self.peek().and_then(std::char::from_u32);
+                match letter {
+                    Some(letter) if letter.is_ascii_alphabetic() => {
+                        self.position += 1;
+                        letter as u32 % 32
+                    }
+                    Some(letter)
+                        if in_class
+                            && !self.unicode
+                            && (letter.is_ascii_digit() || letter == '_') =>
+                    {
+                        self.position += 1;
+                        letter as u32 % 32
+                    }
+                    _ if self.unicode => return Err(String::from("Invalid unicode escape")),
+                    // The backslash is a literal character, and `c` is parsed again
+                    _ => {
+                        self.position -= 1;
+                        '\\' as u32
+                    }
+                }
+            }
+            Some('0') if self.peek().and_then(|c| digit_value(c, 10)).is_none() => 0,
+            Some('0'..='7') if !self.unicode => {
+                // Legacy octal escapes, up to `\377`
+                let mut value = digit_value(c, 8).expect("an octal digit");
+                for _ in 0..2 {
+                    match self.peek().and_then(|c| digit_value(c, 8)) {
+                        Some(digit) if value * 8 + digit <= 0o377 => {
+                            value = value * 8 + digit;
+                            self.position += 1;
+                        }
+                        _ => break,
+                    }
+                }
+                value
+            }
+            Some('x') => match self.parse_hex_digits(2) {
+                Some(value) => value,
+                None if self.unicode => return Err(String::from("Invalid escape")),
+                None => 'x' as u32,
+            },
+            Some('u') => match self.parse_unicode_escape() {
+                Some(value) => value,
+                None if self.unicode => return Err(String::from("Invalid Unicode escape")),
+                None => 'u' as u32,
+            },
+            Some('-') if self.unicode && in_class => c,
+            Some('b') if in_class => 0x08,
+            _ if self.unicode && !is_syntax_char(c) => return Err(String::from("Invalid escape")),
+            _ => c,
+        };
+        Ok(value)
+    }
+
+    /// Parses exactly `count` hexadecimal digits, consuming nothing if they are not there.
+    fn parse_hex_digits(&mut self, count: usize) -> Option<u32> {
+        let mut value = 0;
+        for offset in 0..count {
+            value = value * 16 + self.peek_at(offset).and_then(|c| digit_value(c, 16))?;
+        }
+        self.position += count;
+        Some(value)
+    }
+
+    /// Parses the rest of a `\u` escape: four hexadecimal digits, a surrogate pair of such
+    /// escapes or `{...}` in Unicode mode.
+    fn parse_unicode_escape(&mut self) -> Option<u32> {
+        if self.unicode && self.peek() == Some('{' as u32) {
+            let start = self.position;
+            self.position += 1;
+            let mut value: u32 = 0;
+            let mut digits = 0;
+            while let Some(digit) = self.peek().and_then(|c| digit_value(c, 16)) {
+                value = value.saturating_mul(16).saturating_add(digit);
+                digits += 1;
+                self.position += 1;
+            }
+            if digits == 0 || value > MAX_CODE_POINT || !self.eat('}') {
+                self.position = start;
+                return None;
+            }
+            return Some(value);
+        }
+
+        let lead = self.parse_hex_digits(4)?;
+        if self.unicode
+            && lead >= 0xD800
+            && lead <= 0xDBFF
+            && self.peek() == Some('\\' as u32)
+            && self.peek_at(1) == Some('u' as u32)
+        {
+            let start = self.position;
+            self.position += 2;
+            match self.parse_hex_digits(4) {
+                Some(trail) if trail >= 0xDC00 && trail <= 0xDFFF => {
+                    return Some(0x10000 + ((lead - 0xD800) << 10) + (trail - 0xDC00));
+                }
+                _ => self.position = start,
+            }
+        }
+        Some(lead)
+    }
+
+    /// Parses a character class after its `[`.
+    fn parse_class(&mut self) -> Result<Node, String> {
+        let negated = self.eat('^');
+        let mut ranges = Vec::new();
+        loop {
+            match self.peek() {
+                None => return Err(String::from("Unterminated character class")),
+                Some(c) if c == ']' as u32 => {
+                    self.position += 1;
+                    break;
+                }
+                _ => {}
+            }
+
+            let first = self.parse_class_atom()?;
+            if self.peek() != Some('-' as u32) || self.peek_at(1).map_or(true, |c| c == ']' as u32)
+            {
+                match first {
+                    ClassAtom::Char(c) => ranges.push((c, c)),
+                    ClassAtom::Set(set) => ranges.extend(set),
+                }
+                continue;
+            }
+            self.position += 1;
+            let second = self.parse_class_atom()?;
+            match (first, second) {
+                (ClassAtom::Char(start), ClassAtom::Char(end)) => {
+                    if start > end {
+                        return Err(String::from("Range out of order in character class"));
+                    }
+                    ranges.push((start, end));
+                }
+                _ if self.unicode => return Err(String::from("Invalid character class")),
+                // A set can't bound a range, then the `-` is a literal character
+                (first, second) => {
+                    for atom in vec![first, ClassAtom::Char('-' as u32), second] {
+                        match atom {
+                            ClassAtom::Char(c) => ranges.push((c, c)),
+                            ClassAtom::Set(set) => ranges.extend(set),
+                        }
+                    }
+                }
+            }
+        }
+        Ok(Node::Class(CharClass::new(ranges, negated)))
+    }
+
+    fn parse_class_atom(&mut self) -> Result<ClassAtom, String> {
+        let c = self.peek().expect("parse_class_atom called at the end");
+        self.position += 1;
+        if c != '\\' as u32 {
+            return Ok(ClassAtom::Char(c));
+        }
+        if let Some(set) = self.parse_class_escape()? {
+            return Ok(ClassAtom::Set(set));
+        }
+        match self.peek().and_then(std::char::from_u32) {
+            Some('8') | Some('9') if !self.unicode => {
+                self.position += 1;
+                Ok(ClassAtom::Char(self.chars[self.position - 1]))
+            }
+            Some('B') if self.unicode => Err(String::from("Invalid class escape")),
+            Some('1'..='9') if self.unicode => Err(String::from("Invalid class escape")),
+            _ => Ok(ClassAtom::Char(self.parse_character_escape(true)?)),
+        }
+    }
+}
+
+/// An element of a character class.
+enum ClassAtom {
+    Char(u32),
+    Set(Vec<(u32, u32)>),
+}
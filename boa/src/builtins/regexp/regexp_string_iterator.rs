@@ -0,0 +1,122 @@
+//! This module implements the `RegExpStringIterator` objects, returned by
+//! `RegExp.prototype[Symbol.matchAll]()`.
+//!
+//! More information:
+//!  - [ECMAScript reference][spec]
+//!
+//! [spec]: https://tc39.es/ecma262/#sec-regexp-string-iterator-objects
+
+use super::{advance_string_index, regexp_exec, to_length};
+use crate::{
+    builtins::{
+        iterator::{create_iter_result_object, create_iterator_prototype},
+        object::{InternalState, Object},
+        string::to_js_string,
+        value::{JsString, ResultValue, Value},
+    },
+    exec::Interpreter,
+};
+
+/// The internal slot of the global object holding `%RegExpStringIteratorPrototype%`.
+const REGEXP_STRING_ITERATOR_PROTOTYPE: &str = "RegExpStringIteratorPrototype";
+
+/// The internal state of a regexp string iterator, the regular expression itself is kept in
+/// the `IteratingRegExp` internal slot.
+#[derive(Debug, Clone)]
+struct RegExpStringIterator {
+    /// `[[IteratedString]]`
+    string: JsString,
+    /// `[[Global]]`
+    global: bool,
+    /// `[[Unicode]]`
+    unicode: bool,
+    /// `[[Done]]`
+    done: bool,
+}
+
+impl InternalState for RegExpStringIterator {}
+
+/// Creates a new iterator over the matches of `matcher` in `string`.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-createregexpstringiterator
+pub(crate) fn create_regexp_string_iterator(
+    matcher: Value,
+    string: JsString,
+    global: bool,
+    unicode: bool,
+    ctx: &Interpreter,
+) -> Value {
+    let prototype = ctx
+        .realm
+        .intrinsics
+        .get_internal_slot(REGEXP_STRING_ITERATOR_PROTOTYPE);
+    let iterator = Value::object(Object::create(prototype));
+    iterator.set_internal_slot("IteratingRegExp", matcher);
+    iterator.set_internal_state(RegExpStringIterator {
+        string,
+        global,
+        unicode,
+        done: false,
+    });
+    iterator
+}
+
+/// `%RegExpStringIteratorPrototype%.next( )`
+///
+/// Produces the next match of the regular expression, a non-global regular expression only
+/// produces its first match.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-%regexpstringiteratorprototype%.next
+pub fn next(this: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let state = this
+        .get_internal_state()
+        .and_then(|state| state.downcast_ref::<RegExpStringIterator>().cloned());
+    let mut state = match state {
+        Some(state) => state,
+        None => return ctx.throw_type_error("next method called on an incompatible receiver"),
+    };
+    if state.done {
+        return Ok(create_iter_result_object(Value::undefined(), true, ctx));
+    }
+
+    let matcher = this.get_internal_slot("IteratingRegExp");
+    let result = regexp_exec(&matcher, &state.string, ctx)?;
+    if result.is_null() {
+        state.done = true;
+        this.set_internal_state(state);
+        return Ok(create_iter_result_object(Value::undefined(), true, ctx));
+    }
+
+    if state.global {
+        // An empty match would be found again at the same index
+        let matched = to_js_string(&result.get_field_slice("0"), ctx)?;
+        if matched.is_empty() {
+            let this_index = to_length(&matcher.get_field_slice("lastIndex"), ctx)?;
+            let next_index = advance_string_index(&state.string, this_index, state.unicode);
+            matcher.set_field_slice("lastIndex", Value::from(next_index));
+        }
+    } else {
+        state.done = true;
+        this.set_internal_state(state);
+    }
+    Ok(create_iter_result_object(result, false, ctx))
+}
+
+/// Create `%RegExpStringIteratorPrototype%`.
+pub fn create(global: &Value) -> Value {
+    let prototype = create_iterator_prototype(global, "RegExp String Iterator");
+    make_builtin_fn!(next, named "next", of prototype);
+    prototype
+}
+
+/// Initialise `%RegExpStringIteratorPrototype%` in an internal slot of the global object.
+#[inline]
+pub fn init(global: &Value) {
+    global.set_internal_slot(REGEXP_STRING_ITERATOR_PROTOTYPE, create(global));
+}
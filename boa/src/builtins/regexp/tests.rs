@@ -109,3 +109,200 @@ fn to_string() {
     );
     assert_eq!(forward(&mut engine, "/\\n/g.toString()"), "/\\n/g");
 }
+
+#[test]
+fn backreferences_and_lookaround() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+
+    assert_eq!(
+        forward(&mut engine, r#"/(a+)b\1/.exec('aabaa')[0]"#),
+        "aabaa"
+    );
+    assert_eq!(forward(&mut engine, r#"/(a)\1/.test('ab')"#), "false");
+    assert_eq!(forward(&mut engine, r#"/\1(a)/.exec('a')[0]"#), "a");
+    assert_eq!(
+        forward(&mut engine, r#"/foo(?=bar)/.exec('foobar')[0]"#),
+        "foo"
+    );
+    assert_eq!(
+        forward(&mut engine, r#"/foo(?!bar)/.test('foobar')"#),
+        "false"
+    );
+    assert_eq!(
+        forward(&mut engine, r#"/(?<=\$)\d+/.exec('cost: $42')[0]"#),
+        "42"
+    );
+    assert_eq!(forward(&mut engine, r#"/(?<!x)y/.exec('xyay').index"#), "3");
+    assert_eq!(
+        forward(&mut engine, r#"/(a|ab)(c|bcd)(d*)/.exec('abcd')[0]"#),
+        "abcd"
+    );
+    assert_eq!(
+        forward(
+            &mut engine,
+            r#"/(z)((a+)?(b+)?(c))*/.exec('zaacbbbcac')[4]"#
+        ),
+        "undefined"
+    );
+}
+
+#[test]
+fn named_groups() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var result = /(?<year>\d{4})-(?<month>\d{2})/.exec('on 2020-05');
+        "#;
+
+    eprintln!("{}", forward(&mut engine, init));
+    assert_eq!(forward(&mut engine, "result.groups.year"), "2020");
+    assert_eq!(forward(&mut engine, "result.groups.month"), "05");
+    assert_eq!(forward(&mut engine, "result[1]"), "2020");
+    assert_eq!(
+        forward(&mut engine, r#"/(?<q>[ab]).*\k<q>/.exec('xabba')[0]"#),
+        "abba"
+    );
+    assert_eq!(forward(&mut engine, "/a/.exec('a').groups"), "undefined");
+    assert_eq!(
+        forward(
+            &mut engine,
+            "'2020-05'.replace(/(?<y>\\d+)-(?<m>\\d+)/, '$<m>/$<y>')"
+        ),
+        "05/2020"
+    );
+}
+
+#[test]
+fn indices() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var result = /b(?<c>c)?(d)/d.exec('abd');
+        "#;
+
+    eprintln!("{}", forward(&mut engine, init));
+    assert_eq!(forward(&mut engine, "result.indices[0].join()"), "1,3");
+    assert_eq!(forward(&mut engine, "result.indices[1]"), "undefined");
+    assert_eq!(forward(&mut engine, "result.indices[2].join()"), "2,3");
+    assert_eq!(forward(&mut engine, "result.indices.groups.c"), "undefined");
+    assert_eq!(forward(&mut engine, "/a/.exec('a').indices"), "undefined");
+    assert_eq!(forward(&mut engine, "/a/gd.hasIndices()"), "true");
+    assert_eq!(forward(&mut engine, "/a/yigd.flags()"), "dgiy");
+}
+
+#[test]
+fn sticky() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var re = /foo/y;
+        "#;
+
+    eprintln!("{}", forward(&mut engine, init));
+    assert_eq!(forward(&mut engine, "re.test('xfoo')"), "false");
+    assert_eq!(forward(&mut engine, "re.lastIndex = 1"), "1");
+    assert_eq!(forward(&mut engine, "re.test('xfoo')"), "true");
+    assert_eq!(forward(&mut engine, "re.lastIndex"), "4");
+    assert_eq!(forward(&mut engine, "re.test('xfoo')"), "false");
+    assert_eq!(forward(&mut engine, "re.lastIndex"), "0");
+    assert_eq!(forward(&mut engine, "'a,b'.split(/,/y).join('|')"), "a|b");
+}
+
+#[test]
+fn flags_semantics() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+
+    assert_eq!(forward(&mut engine, "/ABC/i.test('abc')"), "true");
+    assert_eq!(forward(&mut engine, "/[a-z]+/i.exec('HeLLo')[0]"), "HeLLo");
+    assert_eq!(forward(&mut engine, "/^b/m.test('a\\nb')"), "true");
+    assert_eq!(forward(&mut engine, "/^b/.test('a\\nb')"), "false");
+    assert_eq!(forward(&mut engine, "/a.b/.test('a\\nb')"), "false");
+    assert_eq!(forward(&mut engine, "/a.b/s.test('a\\nb')"), "true");
+    assert_eq!(forward(&mut engine, "/^.$/u.test('😀')"), "true");
+    assert_eq!(forward(&mut engine, "/^.$/.test('😀')"), "false");
+    assert_eq!(forward(&mut engine, "/\\u{1F600}/u.test('😀')"), "true");
+    assert_eq!(forward(&mut engine, "/\\bfoo\\b/.test('a foo b')"), "true");
+}
+
+#[test]
+fn syntax_errors() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+
+    assert_eq!(
+        forward(&mut engine, "new RegExp('a', 'gg')"),
+        "Error: SyntaxError: Invalid flags supplied to RegExp constructor 'gg'"
+    );
+    assert_eq!(
+        forward(&mut engine, "new RegExp('(a')"),
+        "Error: SyntaxError: Invalid regular expression: /(a/: Unterminated group"
+    );
+    assert_eq!(
+        forward(&mut engine, "new RegExp('*')"),
+        "Error: SyntaxError: Invalid regular expression: /*/: Nothing to repeat"
+    );
+    assert_eq!(
+        forward(&mut engine, "new RegExp('(?<a>x)(?<a>y)')"),
+        "Error: SyntaxError: Invalid regular expression: /(?<a>x)(?<a>y)/: Duplicate capture group name"
+    );
+}
+
+#[test]
+fn symbol_methods() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+
+    assert_eq!(
+        forward(&mut engine, "'a1b2c3'.split(/(\\d)/).join()"),
+        "a,1,b,2,c,3,"
+    );
+    assert_eq!(
+        forward(&mut engine, "'a1b2c3'.split(/\\d/, 2).join()"),
+        "a,b"
+    );
+    assert_eq!(forward(&mut engine, "''.split(/x/).length"), "1");
+    assert_eq!(forward(&mut engine, "'abc'.split(/(?:)/).join()"), "a,b,c");
+    assert_eq!(
+        forward(
+            &mut engine,
+            "'x1y22'.replace(/\\d+/g, function (m, p) { return '[' + m + p + ']'; })"
+        ),
+        "x[11]y[223]"
+    );
+    assert_eq!(
+        forward(&mut engine, "'aaa'.replace(/a*?/g, '-')"),
+        "-a-a-a-"
+    );
+    assert_eq!(forward(&mut engine, "'abc'.match(/x/)"), "null");
+    assert_eq!(
+        forward(&mut engine, "'a1b22'.match(/\\d+/g).join()"),
+        "1,22"
+    );
+
+    let init = r#"
+        var re = /b/g;
+        re.lastIndex = 2;
+        var position = 'abc'.search(re);
+        "#;
+    eprintln!("{}", forward(&mut engine, init));
+    assert_eq!(forward(&mut engine, "position"), "1");
+    assert_eq!(forward(&mut engine, "re.lastIndex"), "2");
+
+    let init = r#"
+        var iterator = /a(\d)/g[Symbol.matchAll]('a1a2');
+        var first = iterator.next();
+        "#;
+    eprintln!("{}", forward(&mut engine, init));
+    assert_eq!(forward(&mut engine, "first.value[1]"), "1");
+    assert_eq!(forward(&mut engine, "iterator.next().value.index"), "2");
+    assert_eq!(forward(&mut engine, "iterator.next().done"), "true");
+
+    let init = r#"
+        var custom = {};
+        custom[Symbol.split] = function (string, limit) { return 'split ' + string; };
+        "#;
+    eprintln!("{}", forward(&mut engine, init));
+    assert_eq!(forward(&mut engine, "'abc'.split(custom)"), "split abc");
+}
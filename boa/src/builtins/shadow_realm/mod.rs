@@ -0,0 +1,171 @@
+//! This module implements the global `ShadowRealm` object.
+//!
+//! A `ShadowRealm` is a new realm, with intrinsics and a global object of its own, in which code
+//! can be evaluated without reaching the objects of the realm which created it. Only primitives
+//! and functions cross the boundary: a function is wrapped into a function of the other realm,
+//! which wraps its arguments and its result in turn.
+//!
+//! More information:
+//!  - [Proposal][proposal]
+//!
+//! [proposal]: https://tc39.es/proposal-shadowrealm/
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    builtins::{
+        eval,
+        function::{self, Function, REALM},
+        object::{Object, ObjectInternalMethods, INSTANCE_PROTOTYPE, PROTOTYPE},
+        symbol::WellKnownSymbol,
+        value::{ResultValue, Value},
+    },
+    exec::Interpreter,
+    syntax::ast::node::Node,
+};
+
+/// The internal slot of a `ShadowRealm` object holding the intrinsics of its realm.
+const SHADOW_REALM: &str = "ShadowRealm";
+
+/// Create a new `ShadowRealm` object.
+///
+/// More information:
+///  - [Proposal][proposal]
+///
+/// [proposal]: https://tc39.es/proposal-shadowrealm/#sec-shadowrealm
+pub fn make_shadow_realm(this: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let realm = ctx.realm.create_child();
+    this.set_internal_slot(SHADOW_REALM, realm.intrinsics.clone());
+    ctx.add_realm(realm);
+    Ok(this.clone())
+}
+
+/// `ShadowRealm()` called as a function.
+pub fn call_shadow_realm(_: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    ctx.throw_type_error("Constructor ShadowRealm requires 'new'")
+}
+
+/// `ShadowRealm.prototype.evaluate( sourceText )`
+///
+/// The `evaluate()` method runs a script in the global scope of the realm. Its result must be a
+/// primitive or a function, which is wrapped into a function of the calling realm.
+///
+/// More information:
+///  - [Proposal][proposal]
+///
+/// [proposal]: https://tc39.es/proposal-shadowrealm/#sec-shadowrealm.prototype.evaluate
+pub fn evaluate(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let eval_realm = this.get_internal_slot(SHADOW_REALM);
+    if !eval_realm.is_object() {
+        return ctx.throw_type_error(format!(
+            "ShadowRealm.prototype.evaluate called on incompatible {}",
+            this
+        ));
+    }
+    let source = match args.get(0) {
+        Some(source) if source.is_string() => source.to_string(),
+        _ => return ctx.throw_type_error("ShadowRealm.prototype.evaluate expects a string"),
+    };
+
+    // The source text is checked and parsed in the calling realm, which gets the errors
+    let script = eval::compile(&source, ctx)?;
+    let caller_realm = ctx.realm.intrinsics.clone();
+    let result = ctx.run_in_realm(&eval_realm, |ctx| match script {
+        Node::StatementList(ref list) if list.is_empty() => Ok(Value::undefined()),
+        Node::StatementList(ref list) => {
+            eval::run_in_global_scope(ctx, |ctx| ctx.run_eval_code(list))
+        }
+        _ => unreachable!("scripts are parsed into a statement list"),
+    });
+    let result = result.map_err(|error| wrapped_error(&error, ctx))?;
+    get_wrapped_value(&result, &caller_realm, ctx)
+}
+
+/// Replaces an error thrown in another realm with a `TypeError` of the current realm, the
+/// termination of the script goes through as it is.
+fn wrapped_error(error: &Value, ctx: &Interpreter) -> Value {
+    if ctx.termination().is_some() {
+        return error.clone();
+    }
+    let message = error.get_field_slice("message");
+    if message.is_string() {
+        ctx.construct_type_error(format!("Error thrown in another realm: {}", message))
+    } else {
+        ctx.construct_type_error("Error thrown in another realm")
+    }
+}
+
+/// The abstract operation `GetWrappedValue`, which lets a value cross into the realm of the given
+/// intrinsics.
+///
+/// More information:
+///  - [Proposal][proposal]
+///
+/// [proposal]: https://tc39.es/proposal-shadowrealm/#sec-getwrappedvalue
+fn get_wrapped_value(value: &Value, realm: &Value, ctx: &mut Interpreter) -> ResultValue {
+    if !value.is_object() {
+        return Ok(value.clone());
+    }
+    if !value.is_function() {
+        return ctx.throw_type_error("Only primitives and functions can cross a ShadowRealm");
+    }
+
+    // <https://tc39.es/proposal-shadowrealm/#sec-wrappedfunctioncreate>
+    let mut wrapped = Object::function();
+    wrapped.set_call(Function::create_wrapped());
+    wrapped.set_internal_slot(
+        INSTANCE_PROTOTYPE,
+        realm.get_field_slice("Function").get_field_slice(PROTOTYPE),
+    );
+    wrapped.set_internal_slot(REALM, realm.clone());
+    wrapped.set_internal_slot("WrappedTargetFunction", value.clone());
+    let wrapped = Value::from(wrapped);
+    function::copy_name_and_length(&wrapped, value, "", 0);
+    Ok(wrapped)
+}
+
+/// The `[[Call]]` internal method of a wrapped function, it runs in the realm of the wrapped
+/// function.
+///
+/// More information:
+///  - [Proposal][proposal]
+///
+/// [proposal]: https://tc39.es/proposal-shadowrealm/#sec-wrapped-function-exotic-objects-call-thisargument-argumentslist
+pub(crate) fn call_wrapped_function(
+    function: &Value,
+    args: &[Value],
+    ctx: &mut Interpreter,
+) -> ResultValue {
+    let target = function.get_internal_slot("WrappedTargetFunction");
+    let caller_realm = ctx.realm.intrinsics.clone();
+    let target_realm = function::function_realm(&target, ctx);
+
+    let mut wrapped_args = Vec::with_capacity(args.len());
+    for arg in args {
+        wrapped_args.push(get_wrapped_value(arg, &target_realm, ctx)?);
+    }
+    let result = ctx
+        .call(&target, &mut Value::undefined(), &wrapped_args)
+        .map_err(|error| wrapped_error(&error, ctx))?;
+    get_wrapped_value(&result, &caller_realm, ctx)
+}
+
+/// Create a new `ShadowRealm` constructor.
+pub fn create(global: &Value) -> Value {
+    let prototype = Value::new_object(Some(global));
+
+    make_builtin_fn!(evaluate, named "evaluate", with length 1, of prototype);
+    prototype.set_field(
+        Value::symbol(WellKnownSymbol::ToStringTag.into()),
+        Value::from("ShadowRealm"),
+    );
+
+    make_constructor_fn!(make_shadow_realm, call_shadow_realm, global, prototype)
+}
+
+/// Initialise the `ShadowRealm` object on the global object.
+#[inline]
+pub fn init(global: &Value) {
+    global.set_field_slice("ShadowRealm", create(global));
+}
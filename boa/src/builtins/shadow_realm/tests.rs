@@ -0,0 +1,202 @@
+use crate::{
+    exec::{Executor, Interpreter},
+    forward, forward_val,
+    realm::Realm,
+};
+
+fn engine() -> Interpreter {
+    Executor::new(Realm::create())
+}
+
+/// Runs a script which throws, and stores the error in the global `caught`.
+fn catch(engine: &mut Interpreter, src: &str) {
+    let error = forward_val(engine, src).expect_err("the script should throw");
+    engine.realm.global_obj.set_field_slice("caught", error);
+}
+
+#[test]
+fn evaluate_primitives() {
+    let mut engine = engine();
+    forward(
+        &mut engine,
+        "var realm = new ShadowRealm(); var x = 'outer';",
+    );
+    assert_eq!(forward(&mut engine, "realm.evaluate('1 + 2')"), "3");
+    assert_eq!(
+        forward(&mut engine, "realm.evaluate('var x = \"inner\"; x')"),
+        "inner"
+    );
+    // The realm has a global object of its own
+    assert_eq!(forward(&mut engine, "x"), "outer");
+    assert_eq!(forward(&mut engine, "realm.evaluate('x')"), "inner");
+    assert_eq!(
+        forward(&mut engine, "realm.evaluate('Array') === Array"),
+        "false"
+    );
+    assert_eq!(
+        forward(&mut engine, "ShadowRealm()"),
+        "Error: TypeError: Constructor ShadowRealm requires 'new'"
+    );
+}
+
+#[test]
+fn evaluate_errors() {
+    let mut engine = engine();
+    forward(&mut engine, "var realm = new ShadowRealm();");
+    assert_eq!(
+        forward(&mut engine, "realm.evaluate('({})')"),
+        "Error: TypeError: Only primitives and functions can cross a ShadowRealm"
+    );
+    assert_eq!(
+        forward(&mut engine, "realm.evaluate(1)"),
+        "Error: TypeError: ShadowRealm.prototype.evaluate expects a string"
+    );
+    // Errors of the other realm are replaced with errors of the calling realm
+    catch(&mut engine, "realm.evaluate('[].reduce(Math.max)')");
+    assert_eq!(forward(&mut engine, "caught instanceof TypeError"), "true");
+    catch(&mut engine, "realm.evaluate('(')");
+    assert_eq!(
+        forward(&mut engine, "caught instanceof SyntaxError"),
+        "true"
+    );
+}
+
+#[test]
+fn wrapped_functions() {
+    let mut engine = engine();
+    let init = r#"
+        var realm = new ShadowRealm();
+        var add = realm.evaluate('(function add(a, b) { return a + b; })');
+        var apply = realm.evaluate('(function (f, x) { return f(x) * 2; })');
+        var leak = realm.evaluate('(function () { return []; })');
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "add(1, 2)"), "3");
+    assert_eq!(forward(&mut engine, "add.name"), "add");
+    assert_eq!(forward(&mut engine, "add.length"), "2");
+    assert_eq!(forward(&mut engine, "add instanceof Function"), "true");
+    // Functions passed in are wrapped as well
+    assert_eq!(
+        forward(&mut engine, "apply(function (x) { return x + 1; }, 1)"),
+        "4"
+    );
+    catch(&mut engine, "leak()");
+    assert_eq!(forward(&mut engine, "caught instanceof TypeError"), "true");
+}
+
+#[test]
+fn realms_of_the_interpreter() {
+    let mut engine = engine();
+    let other = engine.add_realm(Realm::create());
+    let value = engine
+        .enter_realm(&other, |engine| {
+            forward_val(engine, "var f = function () { return [] }; f")
+        })
+        .unwrap();
+    engine.realm.global_obj.set_field_slice("f", value);
+
+    // The function creates its objects and throws its errors in its own realm
+    assert_eq!(forward(&mut engine, "f() instanceof Array"), "false");
+    assert_eq!(forward(&mut engine, "Array.isArray(f())"), "true");
+    catch(&mut engine, "f.constructor('return [].reduce(Math.max)')()");
+    assert_eq!(forward(&mut engine, "caught instanceof TypeError"), "false");
+    assert_eq!(forward(&mut engine, "caught.name"), "TypeError");
+    // The prototype of a constructed object defaults to the one of the realm of the constructor
+    forward(&mut engine, "f.prototype = null");
+    assert_eq!(
+        forward(
+            &mut engine,
+            "Object.getPrototypeOf(new f()) === Object.prototype"
+        ),
+        "false"
+    );
+}
+
+#[test]
+fn compile_strings_hook() {
+    let mut realm = Realm::create();
+    realm.disable_dynamic_code();
+    let mut engine: Interpreter = Executor::new(realm);
+    assert_eq!(
+        forward(&mut engine, "new ShadowRealm().evaluate('1')"),
+        "Error: EvalError: Code generation from strings is disallowed"
+    );
+}
+
+#[test]
+fn child_realms_follow_the_parent() {
+    let realm = Realm::builder().without_global("console").build();
+    let mut engine: Interpreter = Executor::new(realm);
+    forward(&mut engine, "var realm = new ShadowRealm();");
+    assert_eq!(
+        forward(&mut engine, "realm.evaluate('this.console === undefined')"),
+        "true"
+    );
+    assert_eq!(
+        forward(&mut engine, "realm.evaluate('this.Math !== undefined')"),
+        "true"
+    );
+
+    let realm = Realm::builder()
+        .only_globals(vec!["ShadowRealm", "Object"])
+        .build();
+    realm.lockdown();
+    let mut engine: Interpreter = Executor::new(realm);
+    forward(&mut engine, "var realm = new ShadowRealm();");
+    assert_eq!(
+        forward(&mut engine, "realm.evaluate('this.Math === undefined')"),
+        "true"
+    );
+    assert_eq!(
+        forward(
+            &mut engine,
+            "realm.evaluate('Object.isFrozen(Object.prototype)')"
+        ),
+        "true"
+    );
+    // The realms it creates in turn are built the same way
+    assert_eq!(
+        forward(
+            &mut engine,
+            "realm.evaluate('new ShadowRealm().evaluate(\"this.Math === undefined\")')"
+        ),
+        "true"
+    );
+}
+
+#[test]
+fn without_shadow_realm() {
+    let realm = Realm::builder().without_global("ShadowRealm").build();
+    let mut engine: Interpreter = Executor::new(realm);
+    assert_eq!(
+        forward(&mut engine, "this.ShadowRealm === undefined"),
+        "true"
+    );
+}
+
+#[test]
+fn realms_are_collected() {
+    let mut engine = engine();
+    let create = "(function () { new ShadowRealm().evaluate('1'); })()";
+    forward(&mut engine, create);
+    gc::force_collect();
+    let used = engine.heap_stats().used_bytes;
+    for _ in 0..20 {
+        forward(&mut engine, create);
+    }
+    gc::force_collect();
+    // The realms of the dropped `ShadowRealm` objects are freed with them
+    assert!(
+        engine.heap_stats().used_bytes <= used,
+        "{} > {}",
+        engine.heap_stats().used_bytes,
+        used
+    );
+    // A realm which can still be reached keeps running
+    forward(
+        &mut engine,
+        "var f = new ShadowRealm().evaluate('var x = 1; () => ++x');",
+    );
+    gc::force_collect();
+    assert_eq!(forward(&mut engine, "f() + f()"), "5");
+}
@@ -9,49 +9,87 @@
 //! [spec]: https://tc39.es/ecma262/#sec-string-object
 //! [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/String
 
+mod normalization;
+mod normalization_tables;
+pub mod string_iterator;
 #[cfg(test)]
 mod tests;
 
+use self::{normalization::NormalizationForm, string_iterator::create_string_iterator};
 use crate::{
     builtins::{
+        array::{create_array_from_list, length_of_array_like},
         object::{internal_methods_trait::ObjectInternalMethods, Object, ObjectKind, PROTOTYPE},
         property::Property,
-        regexp::{make_regexp, match_all as regexp_match_all, r#match as regexp_match},
-        value::{ResultValue, Value, ValueData},
+        regexp::{is_regexp, regexp_create, regexp_flags, RegExpMatch},
+        symbol::WellKnownSymbol,
+        typed_array::modulo,
+        value::{JsString, ResultValue, Value, ValueData},
     },
     exec::Interpreter,
 };
-use regex::Regex;
-use std::{
-    cmp::{max, min},
-    f64::NAN,
-    ops::Deref,
-};
+use std::{char::from_u32, f64::NAN, mem};
+
+/// Converts a value to a string and returns its code units, as done by `ToString`.
+pub(crate) fn to_js_string(value: &Value, ctx: &mut Interpreter) -> Result<JsString, Value> {
+    Ok(match *ctx.to_string(value)? {
+        ValueData::String(ref string) => string.clone(),
+        ref other => JsString::from(other.to_string()),
+    })
+}
+
+/// Returns the own properties of a string that are computed from its contents: its `length`
+/// and the code units at integer indices.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-string-exotic-objects-getownproperty-p
+pub(crate) fn get_own_property(string: &JsString, field: &str) -> Option<Value> {
+    if field == "length" {
+        return Some(Value::from(string.len()));
+    }
+    let index = field.parse::<usize>().ok()?;
+    if index.to_string() != field {
+        return None;
+    }
+    string
+        .get(index..=index)
+        .map(|unit| Value::from(JsString::from(unit)))
+}
+
+/// Resolves an optional integer argument with `ToIntegerOrInfinity`, using `default` if it is
+/// missing or `undefined`.
+fn integer_arg(args: &[Value], index: usize, default: f64) -> f64 {
+    match args.get(index) {
+        Some(arg) if !arg.is_undefined() => arg.to_integer_or_infinity(),
+        _ => default,
+    }
+}
+
+/// Clamps an integer to the range `0..=len`.
+fn clamp_index(value: f64, len: usize) -> usize {
+    value.max(0.0).min(len as f64) as usize
+}
 
 /// Create new string [[Construct]]
 // This gets called when a new String() is created, it's called by exec:346
-pub fn make_string(this: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValue {
-    // If we're constructing a string, we should set the initial length
-    // To do this we need to convert the string back to a Rust String, then get the .len()
-    // let a: String = from_value(args.get(0).expect("failed to get argument for String method").clone()).unwrap();
-    // this.set_field_slice("length", to_value(a.len() as i32));
-
-    // This value is used by console.log and other routines to match Obexpecty"failed to parse argument for String method"pe
+pub fn make_string(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    // This value is used by console.log and other routines to match Object type
     // to its Javascript Identifier (global constructor method name)
+    let string = match args.get(0) {
+        Some(value) => to_js_string(value, ctx)?,
+        None => JsString::new(),
+    };
     this.set_kind(ObjectKind::String);
-    this.set_internal_slot(
-        "StringData",
-        args.get(0)
-            .expect("failed to get StringData for make_string()")
-            .clone(),
-    );
+    this.set_internal_slot("StringData", Value::from(string));
     Ok(this.clone())
 }
 
 /// Call new string [[Call]]
 ///
 /// More information: [ECMAScript reference](https://tc39.es/ecma262/#sec-string-constructor-string-value)
-pub fn call_string(_: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValue {
+pub fn call_string(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
     let arg = match args.get(0) {
         Some(v) => v.clone(),
         None => Value::undefined(),
@@ -61,14 +99,16 @@ pub fn call_string(_: &mut Value, args: &[Value], _: &mut Interpreter) -> Result
         return Ok(Value::from(String::new()));
     }
 
-    Ok(Value::from(arg.to_string()))
+    Ok(Value::from(to_js_string(&arg, ctx)?))
 }
 
 /// Get the string value to a primitive string
 pub fn to_string(this: &mut Value, _: &[Value], _: &mut Interpreter) -> ResultValue {
     // Get String from String Object and send it back as a new value
-    let primitive_val = this.get_internal_slot("StringData");
-    Ok(Value::from(format!("{}", primitive_val)))
+    if this.is_string() {
+        return Ok(this.clone());
+    }
+    Ok(this.get_internal_slot("StringData"))
 }
 
 /// `String.prototype.charAt( index )`
@@ -88,31 +128,16 @@ pub fn to_string(this: &mut Value, _: &[Value], _: &mut Interpreter) -> ResultVa
 /// [spec]: https://tc39.es/ecma262/#sec-string.prototype.charat
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/String/charAt
 pub fn char_at(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
-    // First we get it the actual string a private field stored on the object only the engine has access to.
-    // Then we convert it into a Rust String by wrapping it in from_value
-    let primitive_val = ctx.value_to_rust_string(this);
-    let pos = i32::from(
-        args.get(0)
-            .expect("failed to get argument for String method"),
-    );
-
-    // Calling .len() on a string would give the wrong result, as they are bytes not the number of
-    // unicode code points
-    // Note that this is an O(N) operation (because UTF-8 is complex) while getting the number of
-    // bytes is an O(1) operation.
-    let length = primitive_val.chars().count();
+    let primitive_val = to_js_string(this, ctx)?;
+    let pos = integer_arg(args, 0, 0.0);
 
     // We should return an empty string is pos is out of range
-    if pos >= length as i32 || pos < 0 {
+    if pos < 0.0 || pos >= primitive_val.len() as f64 {
         return Ok(Value::from(String::new()));
     }
 
-    Ok(Value::from(
-        primitive_val
-            .chars()
-            .nth(pos as usize)
-            .expect("failed to get value"),
-    ))
+    let pos = pos as usize;
+    Ok(Value::from(JsString::from(&primitive_val[pos..=pos])))
 }
 
 /// `String.prototype.charCodeAt( index )`
@@ -130,29 +155,15 @@ pub fn char_at(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> Resul
 /// [spec]: https://tc39.es/ecma262/#sec-string.prototype.charcodeat
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/String/charCodeAt
 pub fn char_code_at(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
-    // First we get it the actual string a private field stored on the object only the engine has access to.
-    // Then we convert it into a Rust String by wrapping it in from_value
-    let primitive_val: String = ctx.value_to_rust_string(this);
-
-    // Calling .len() on a string would give the wrong result, as they are bytes not the number of unicode code points
-    // Note that this is an O(N) operation (because UTF-8 is complex) while getting the number of bytes is an O(1) operation.
-    let length = primitive_val.chars().count();
-    let pos = i32::from(
-        args.get(0)
-            .expect("failed to get argument for String method"),
-    );
-
-    if pos >= length as i32 || pos < 0 {
+    let primitive_val = to_js_string(this, ctx)?;
+    let pos = integer_arg(args, 0, 0.0);
+
+    // If there is no element at that index, the result is NaN
+    if pos < 0.0 || pos >= primitive_val.len() as f64 {
         return Ok(Value::from(NAN));
     }
 
-    let utf16_val = primitive_val
-        .encode_utf16()
-        .nth(pos as usize)
-        .expect("failed to get utf16 value");
-    // If there is no element at that index, the result is NaN
-    // TODO: We currently don't have NaN
-    Ok(Value::from(f64::from(utf16_val)))
+    Ok(Value::from(f64::from(primitive_val[pos as usize])))
 }
 
 /// `String.prototype.concat( str1[, ...strN] )`
@@ -170,16 +181,14 @@ pub fn char_code_at(this: &mut Value, args: &[Value], ctx: &mut Interpreter) ->
 /// [spec]: https://tc39.es/ecma262/#sec-string.prototype.concat
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/String/concat
 pub fn concat(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
-    // First we get it the actual string a private field stored on the object only the engine has access to.
-    // Then we convert it into a Rust String by wrapping it in from_value
-    let mut new_str = ctx.value_to_rust_string(this);
+    let mut new_str = to_js_string(this, ctx)?.to_vec();
 
     for arg in args {
-        let concat_str = String::from(arg);
-        new_str.push_str(&concat_str);
+        new_str.extend_from_slice(&to_js_string(arg, ctx)?);
     }
 
-    Ok(Value::from(new_str))
+    ctx.reserve_string(new_str.len())?;
+    Ok(Value::from(JsString::from(new_str)))
 }
 
 /// `String.prototype.repeat( count )`
@@ -194,16 +203,18 @@ pub fn concat(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> Result
 /// [spec]: https://tc39.es/ecma262/#sec-string.prototype.repeat
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/String/repeat
 pub fn repeat(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
-    // First we get it the actual string a private field stored on the object only the engine has access to.
-    // Then we convert it into a Rust String by wrapping it in from_value
-    let primitive_val: String = ctx.value_to_rust_string(this);
-
-    let repeat_times = usize::from(
-        args.get(0)
-            .expect("failed to get argument for String method"),
-    );
+    let primitive_val = to_js_string(this, ctx)?;
+    let repeat_times = integer_arg(args, 0, 0.0);
+    if repeat_times < 0.0 || repeat_times.is_infinite() {
+        return ctx.throw_range_error(format!("Invalid count value: {}", repeat_times));
+    }
+    ctx.reserve_heap(
+        (primitive_val.len() * mem::size_of::<u16>()).saturating_mul(repeat_times as usize),
+    )?;
 
-    Ok(Value::from(primitive_val.repeat(repeat_times)))
+    Ok(Value::from(JsString::from(
+        primitive_val.repeat(repeat_times as usize),
+    )))
 }
 
 /// `String.prototype.slice( beginIndex [, endIndex] )`
@@ -217,44 +228,29 @@ pub fn repeat(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> Result
 /// [spec]: https://tc39.es/ecma262/#sec-string.prototype.slice
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/String/slice
 pub fn slice(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
-    // First we get it the actual string a private field stored on the object only the engine has access to.
-    // Then we convert it into a Rust String by wrapping it in from_value
-    let primitive_val: String = ctx.value_to_rust_string(this);
-
-    let start = i32::from(
-        args.get(0)
-            .expect("failed to get argument for String method"),
-    );
+    let primitive_val = to_js_string(this, ctx)?;
+    let length = primitive_val.len();
 
-    let end = i32::from(args.get(1).expect("failed to get argument in slice"));
+    let start = integer_arg(args, 0, 0.0);
+    let end = integer_arg(args, 1, length as f64);
 
-    // Calling .len() on a string would give the wrong result, as they are bytes not the number of unicode code points
-    // Note that this is an O(N) operation (because UTF-8 is complex) while getting the number of bytes is an O(1) operation.
-    let length: i32 = primitive_val.chars().count() as i32;
-
-    let from: i32 = if start < 0 {
-        max(length.wrapping_add(start), 0)
+    // Negative positions count back from the end of the string
+    let from = if start < 0.0 {
+        clamp_index(length as f64 + start, length)
     } else {
-        min(start, length)
+        clamp_index(start, length)
     };
-    let to: i32 = if end < 0 {
-        max(length.wrapping_add(end), 0)
+    let to = if end < 0.0 {
+        clamp_index(length as f64 + end, length)
     } else {
-        min(end, length)
+        clamp_index(end, length)
     };
 
-    let span = max(to.wrapping_sub(from), 0);
-
-    let mut new_str = String::new();
-    for i in from..from.wrapping_add(span) {
-        new_str.push(
-            primitive_val
-                .chars()
-                .nth(i as usize)
-                .expect("Could not get nth char"),
-        );
+    if from >= to {
+        return Ok(Value::from(String::new()));
     }
-    Ok(Value::from(new_str))
+    ctx.reserve_string(to - from)?;
+    Ok(Value::from(JsString::from(&primitive_val[from..to])))
 }
 
 /// `String.prototype.startWith( searchString[, position] )`
@@ -268,36 +264,20 @@ pub fn slice(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultV
 /// [spec]: https://tc39.es/ecma262/#sec-string.prototype.startswith
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/String/startsWith
 pub fn starts_with(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
-    // First we get it the actual string a private field stored on the object only the engine has access to.
-    // Then we convert it into a Rust String by wrapping it in from_value
-    let primitive_val: String = ctx.value_to_rust_string(this);
+    let primitive_val = to_js_string(this, ctx)?;
 
     // TODO: Should throw TypeError if pattern is regular expression
-    let search_string = String::from(
-        args.get(0)
-            .expect("failed to get argument for String method"),
-    );
+    let search_string = to_js_string(args.get(0).unwrap_or(&Value::undefined()), ctx)?;
 
-    let length: i32 = primitive_val.chars().count() as i32;
-    let search_length: i32 = search_string.chars().count() as i32;
+    let length = primitive_val.len();
 
     // If less than 2 args specified, position is 'undefined', defaults to 0
-    let position: i32 = if args.len() < 2 {
-        0
-    } else {
-        i32::from(args.get(1).expect("failed to get arg"))
-    };
-
-    let start = min(max(position, 0), length);
-    let end = start.wrapping_add(search_length);
+    let start = clamp_index(integer_arg(args, 1, 0.0), length);
 
-    if end > length {
-        Ok(Value::from(false))
-    } else {
-        // Only use the part of the string from "start"
-        let this_string: String = primitive_val.chars().skip(start as usize).collect();
-        Ok(Value::from(this_string.starts_with(&search_string)))
-    }
+    // Only use the part of the string from "start"
+    Ok(Value::from(
+        primitive_val[start..].starts_with(&search_string),
+    ))
 }
 
 /// `String.prototype.endsWith( searchString[, length] )`
@@ -311,37 +291,19 @@ pub fn starts_with(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> R
 /// [spec]: https://tc39.es/ecma262/#sec-string.prototype.endswith
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/String/endsWith
 pub fn ends_with(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
-    // First we get it the actual string a private field stored on the object only the engine has access to.
-    // Then we convert it into a Rust String by wrapping it in from_value
-    let primitive_val: String = ctx.value_to_rust_string(this);
+    let primitive_val = to_js_string(this, ctx)?;
 
     // TODO: Should throw TypeError if search_string is regular expression
-    let search_string = String::from(
-        args.get(0)
-            .expect("failed to get argument for String method"),
-    );
+    let search_string = to_js_string(args.get(0).unwrap_or(&Value::undefined()), ctx)?;
 
-    let length: i32 = primitive_val.chars().count() as i32;
-    let search_length: i32 = search_string.chars().count() as i32;
+    let length = primitive_val.len();
 
     // If less than 2 args specified, end_position is 'undefined', defaults to
     // length of this
-    let end_position: i32 = if args.len() < 2 {
-        length
-    } else {
-        i32::from(args.get(1).expect("Could not get argumetn"))
-    };
-
-    let end = min(max(end_position, 0), length);
-    let start = end.wrapping_sub(search_length);
+    let end = clamp_index(integer_arg(args, 1, length as f64), length);
 
-    if start < 0 {
-        Ok(Value::from(false))
-    } else {
-        // Only use the part of the string up to "end"
-        let this_string: String = primitive_val.chars().take(end as usize).collect();
-        Ok(Value::from(this_string.ends_with(&search_string)))
-    }
+    // Only use the part of the string up to "end"
+    Ok(Value::from(primitive_val[..end].ends_with(&search_string)))
 }
 
 /// `String.prototype.includes( searchString[, position] )`
@@ -355,49 +317,217 @@ pub fn ends_with(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> Res
 /// [spec]: https://tc39.es/ecma262/#sec-string.prototype.includes
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/String/includes
 pub fn includes(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
-    // First we get it the actual string a private field stored on the object only the engine has access to.
-    // Then we convert it into a Rust String by wrapping it in from_value
-    let primitive_val: String = ctx.value_to_rust_string(this);
+    let primitive_val = to_js_string(this, ctx)?;
 
     // TODO: Should throw TypeError if search_string is regular expression
-    let search_string = String::from(
-        args.get(0)
-            .expect("failed to get argument for String method"),
-    );
-
-    let length: i32 = primitive_val.chars().count() as i32;
+    let search_string = to_js_string(args.get(0).unwrap_or(&Value::undefined()), ctx)?;
 
     // If less than 2 args specified, position is 'undefined', defaults to 0
-    let position: i32 = if args.len() < 2 {
-        0
-    } else {
-        i32::from(args.get(1).expect("Could not get argument"))
+    let start = clamp_index(integer_arg(args, 1, 0.0), primitive_val.len());
+
+    Ok(Value::from(
+        primitive_val.index_of(&search_string, start).is_some(),
+    ))
+}
+
+/// Finds the occurrences of `search` in `string`, as matches of a pattern without capture groups.
+///
+/// Only the first occurrence is returned unless `all` is set.
+fn find_string_matches(string: &JsString, search: &JsString, all: bool) -> Vec<RegExpMatch> {
+    let mut matches = Vec::new();
+    let mut from = 0;
+    while let Some(start) = string.index_of(search, from) {
+        matches.push(RegExpMatch {
+            start,
+            end: start + search.len(),
+            captures: vec![Some(search.clone())],
+            named_groups: Vec::new(),
+        });
+        if !all {
+            break;
+        }
+        // An empty search string matches between every code unit
+        from = start + search.len().max(1);
+    }
+    matches
+}
+
+/// The abstract operation `GetSubstitution`, which expands the `$` patterns of a replacement
+/// string: `$$`, `$&`, `` $` ``, `$'`, `$n`, `$nn` and `$<name>`.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-getsubstitution
+pub(crate) fn get_substitution(
+    string: &JsString,
+    m: &RegExpMatch,
+    replacement: &[u16],
+) -> Vec<u16> {
+    let capture_count = m.captures.len() - 1;
+    let capture = |index: usize| m.captures[index].as_ref().map_or(&[][..], |c| &c[..]);
+    let digit = |unit: Option<&u16>| match unit {
+        Some(&unit) if unit >= u16::from(b'0') && unit <= u16::from(b'9') => {
+            Some(usize::from(unit - u16::from(b'0')))
+        }
+        _ => None,
     };
 
-    let start = min(max(position, 0), length);
+    let mut result = Vec::with_capacity(replacement.len());
+    let mut i = 0;
+    while i < replacement.len() {
+        let unit = replacement[i];
+        if unit != u16::from(b'$') || i + 1 == replacement.len() {
+            result.push(unit);
+            i += 1;
+            continue;
+        }
 
-    // Take the string from "this" and use only the part of it after "start"
-    let this_string: String = primitive_val.chars().skip(start as usize).collect();
+        let next = replacement[i + 1];
+        if next == u16::from(b'$') {
+            result.push(unit);
+            i += 2;
+        } else if next == u16::from(b'&') {
+            result.extend_from_slice(capture(0));
+            i += 2;
+        } else if next == u16::from(b'`') {
+            result.extend_from_slice(&string[..m.start]);
+            i += 2;
+        } else if next == u16::from(b'\'') {
+            result.extend_from_slice(&string[m.end.min(string.len())..]);
+            i += 2;
+        } else if let Some(first) = digit(replacement.get(i + 1)) {
+            // Two digits are used when they name an existing group
+            let two_digits = digit(replacement.get(i + 2))
+                .map(|second| first * 10 + second)
+                .filter(|&index| index >= 1 && index <= capture_count);
+            if let Some(index) = two_digits {
+                result.extend_from_slice(capture(index));
+                i += 3;
+            } else if first >= 1 && first <= capture_count {
+                result.extend_from_slice(capture(first));
+                i += 2;
+            } else {
+                result.push(unit);
+                i += 1;
+            }
+        } else if next == u16::from(b'<') && !m.named_groups.is_empty() {
+            let close = replacement[i + 2..]
+                .iter()
+                .position(|&unit| unit == u16::from(b'>'));
+            match close {
+                Some(close) => {
+                    let name = String::from_utf16_lossy(&replacement[i + 2..i + 2 + close]);
+                    let group = m
+                        .named_groups
+                        .iter()
+                        .find(|(group_name, _)| *group_name == name)
+                        .and_then(|(_, group)| group.as_ref());
+                    if let Some(group) = group {
+                        result.extend_from_slice(group);
+                    }
+                    i += close + 3;
+                }
+                None => {
+                    result.push(unit);
+                    i += 1;
+                }
+            }
+        } else {
+            result.push(unit);
+            i += 1;
+        }
+    }
+    result
+}
+
+/// Calls the method of an object keyed by a well-known symbol, which lets regular expressions
+/// and other objects implement `match`, `replace`, `search` and `split`.
+///
+/// Returns `None` when the value isn't an object or has no such method.
+fn call_symbol_method(
+    value: &Value,
+    symbol: WellKnownSymbol,
+    args: &[Value],
+    ctx: &mut Interpreter,
+) -> Result<Option<Value>, Value> {
+    if !value.is_object() {
+        return Ok(None);
+    }
+    let method = value.get_field(Value::symbol(symbol.into()));
+    if method.is_null_or_undefined() {
+        return Ok(None);
+    }
+    if !method.is_function() {
+        return Err(ctx.construct_type_error(format!("{} is not a function", method)));
+    }
+    ctx.call(&method, &mut value.clone(), args).map(Some)
+}
 
-    Ok(Value::from(this_string.contains(&search_string)))
+/// Calls the method of a `RegExp` object keyed by a well-known symbol.
+fn invoke_symbol_method(
+    regexp: &Value,
+    symbol: WellKnownSymbol,
+    args: &[Value],
+    ctx: &mut Interpreter,
+) -> ResultValue {
+    match call_symbol_method(regexp, symbol, args, ctx)? {
+        Some(result) => Ok(result),
+        None => ctx.throw_type_error("RegExp method is not a function"),
+    }
 }
 
-/// Return either the string itself or the string of the regex equivalent
-fn get_regex_string(value: &Value) -> String {
-    match value.deref() {
-        ValueData::String(ref body) => body.into(),
-        ValueData::Object(ref obj) => {
-            let slots = &obj.borrow().internal_slots;
-            if slots.get("RegExpMatcher").is_some() {
-                // first argument is another `RegExp` object, so copy its pattern and flags
-                if let Some(body) = slots.get("OriginalSource") {
-                    return String::from(r#body);
+/// Shared implementation of `replace` and `replaceAll`.
+fn string_replace(this: &Value, args: &[Value], all: bool, ctx: &mut Interpreter) -> ResultValue {
+    let string = to_js_string(this, ctx)?;
+    let search_value = args.get(0).cloned().unwrap_or_else(Value::undefined);
+    let replace_value = args.get(1).cloned().unwrap_or_else(Value::undefined);
+
+    let search = to_js_string(&search_value, ctx)?;
+    let matches = find_string_matches(&string, &search, all);
+
+    let replacement = if replace_value.is_function() {
+        None
+    } else {
+        Some(to_js_string(&replace_value, ctx)?)
+    };
+
+    let mut result = Vec::with_capacity(string.len());
+    let mut last_end = 0;
+    for m in matches {
+        result.extend_from_slice(&string[last_end..m.start]);
+        match replacement {
+            Some(ref replacement) => {
+                result.extend(get_substitution(&string, &m, replacement));
+            }
+            None => {
+                // The function receives the match, the captures, the position, the whole string
+                // and the named groups if there are some
+                let mut arguments: Vec<Value> = m
+                    .captures
+                    .iter()
+                    .map(|capture| capture.clone().map_or_else(Value::undefined, Value::from))
+                    .collect();
+                arguments.push(Value::from(m.start));
+                arguments.push(Value::from(string.clone()));
+                if !m.named_groups.is_empty() {
+                    let groups = Value::new_object(Some(&ctx.realm.intrinsics));
+                    for (name, group) in &m.named_groups {
+                        let group = group.clone().map_or_else(Value::undefined, Value::from);
+                        groups.set_field_slice(name, group);
+                    }
+                    arguments.push(groups);
                 }
+                let replaced = ctx.call(&replace_value, &mut Value::undefined(), &arguments)?;
+                result.extend_from_slice(&to_js_string(&replaced, ctx)?);
             }
-            "undefined".to_string()
         }
-        _ => "undefined".to_string(),
+        last_end = m.end;
     }
+    result.extend_from_slice(&string[last_end..]);
+
+    ctx.reserve_string(result.len())?;
+    Ok(Value::from(JsString::from(result)))
 }
 
 /// `String.prototype.replace( regexp|substr, newSubstr|function )`
@@ -416,93 +546,47 @@ fn get_regex_string(value: &Value) -> String {
 /// [spec]: https://tc39.es/ecma262/#sec-string.prototype.replace
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/String/replace
 pub fn replace(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
-    // TODO: Support Symbol replacer
-    let primitive_val: String = ctx.value_to_rust_string(this);
-    if args.is_empty() {
-        return Ok(Value::from(primitive_val));
+    let search_value = args.get(0).cloned().unwrap_or_else(Value::undefined);
+    let replace_value = args.get(1).cloned().unwrap_or_else(Value::undefined);
+    let replacer = call_symbol_method(
+        &search_value,
+        WellKnownSymbol::Replace,
+        &[this.clone(), replace_value],
+        ctx,
+    )?;
+    match replacer {
+        Some(result) => Ok(result),
+        None => string_replace(this, args, false, ctx),
     }
+}
 
-    let regex_body = get_regex_string(args.get(0).expect("Value needed"));
-    let re = Regex::new(&regex_body).expect("unable to convert regex to regex object");
-    let mat = re.find(&primitive_val).expect("unable to find value");
-    let caps = re
-        .captures(&primitive_val)
-        .expect("unable to get capture groups from text");
-
-    let replace_value = if args.len() > 1 {
-        // replace_object could be a string or function or not exist at all
-        let replace_object: &Value = args.get(1).expect("second argument expected");
-        match replace_object.deref() {
-            ValueData::String(val) => {
-                // https://tc39.es/ecma262/#table-45
-                let mut result: String = val.to_string();
-                let re = Regex::new(r"\$(\d)").unwrap();
-
-                if val.find("$$").is_some() {
-                    result = val.replace("$$", "$")
-                }
-
-                if val.find("$`").is_some() {
-                    let start_of_match = mat.start();
-                    let slice = &primitive_val[..start_of_match];
-                    result = val.replace("$`", slice);
-                }
-
-                if val.find("$'").is_some() {
-                    let end_of_match = mat.end();
-                    let slice = &primitive_val[end_of_match..];
-                    result = val.replace("$'", slice);
-                }
-
-                if val.find("$&").is_some() {
-                    // get matched value
-                    let matched = caps.get(0).expect("cannot get matched value");
-                    result = val.replace("$&", matched.as_str());
-                }
-
-                // Capture $1, $2, $3 etc
-                if re.is_match(&result) {
-                    let mat_caps = re.captures(&result).unwrap();
-                    let group_str = mat_caps.get(1).unwrap().as_str();
-                    let group_int = group_str.parse::<usize>().unwrap();
-                    result = re
-                        .replace(result.as_str(), caps.get(group_int).unwrap().as_str())
-                        .to_string()
-                }
-
-                result
-            }
-            ValueData::Object(_) => {
-                // This will return the matched substring first, then captured parenthesized groups later
-                let mut results: Vec<Value> = caps
-                    .iter()
-                    .map(|capture| Value::from(capture.unwrap().as_str()))
-                    .collect();
-
-                // Returns the starting byte offset of the match
-                let start = caps
-                    .get(0)
-                    .expect("Unable to get Byte offset from string for match")
-                    .start();
-                results.push(Value::from(start));
-                // Push the whole string being examined
-                results.push(Value::from(primitive_val.to_string()));
-
-                let result = ctx.call(&replace_object, this, &results).unwrap();
-
-                ctx.value_to_rust_string(&result)
-            }
-            _ => "undefined".to_string(),
-        }
-    } else {
-        "undefined".to_string()
-    };
-
-    Ok(Value::from(primitive_val.replacen(
-        &mat.as_str(),
-        &replace_value,
-        1,
-    )))
+/// `String.prototype.replaceAll( pattern, replacement )`
+///
+/// The `replaceAll()` method returns a new string with all matches of a `pattern` replaced by a
+/// `replacement`. A `RegExp` pattern must have the global flag.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-string.prototype.replaceall
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/String/replaceAll
+pub fn replace_all(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let search_value = args.get(0).cloned().unwrap_or_else(Value::undefined);
+    let replace_value = args.get(1).cloned().unwrap_or_else(Value::undefined);
+    if is_regexp(&search_value) && !regexp_flags(&search_value, ctx)?.contains('g') {
+        return ctx.throw_type_error("replaceAll must be called with a global RegExp");
+    }
+    let replacer = call_symbol_method(
+        &search_value,
+        WellKnownSymbol::Replace,
+        &[this.clone(), replace_value],
+        ctx,
+    )?;
+    match replacer {
+        Some(result) => Ok(result),
+        None => string_replace(this, args, true, ctx),
+    }
 }
 
 /// `String.prototype.indexOf( searchValue[, fromIndex] )`
@@ -518,40 +602,18 @@ pub fn replace(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> Resul
 /// [spec]: https://tc39.es/ecma262/#sec-string.prototype.indexof
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/String/indexOf
 pub fn index_of(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
-    // First we get it the actual string a private field stored on the object only the engine has access to.
-    // Then we convert it into a Rust String by wrapping it in from_value
-    let primitive_val: String = ctx.value_to_rust_string(this);
+    let primitive_val = to_js_string(this, ctx)?;
 
     // TODO: Should throw TypeError if search_string is regular expression
-    let search_string = String::from(
-        args.get(0)
-            .expect("failed to get argument for String method"),
-    );
-
-    let length: i32 = primitive_val.chars().count() as i32;
+    let search_string = to_js_string(args.get(0).unwrap_or(&Value::undefined()), ctx)?;
 
     // If less than 2 args specified, position is 'undefined', defaults to 0
-    let position: i32 = if args.len() < 2 {
-        0
-    } else {
-        i32::from(args.get(1).expect("Could not get argument"))
-    };
+    let start = clamp_index(integer_arg(args, 1, 0.0), primitive_val.len());
 
-    let start = min(max(position, 0), length);
-
-    // Here cannot use the &str method "find", because this returns the byte
-    // index: we need to return the char index in the JS String
-    // Instead, iterate over the part we're checking until the slice we're
-    // checking "starts with" the search string
-    for index in start..length {
-        let this_string: String = primitive_val.chars().skip(index as usize).collect();
-        if this_string.starts_with(&search_string) {
-            // Explicitly return early with the index value
-            return Ok(Value::from(index));
-        }
-    }
-    // Didn't find a match, so return -1
-    Ok(Value::from(-1))
+    // Indices are counted in UTF-16 code units, like the string's length
+    Ok(primitive_val
+        .index_of(&search_string, start)
+        .map_or(Value::from(-1), Value::from))
 }
 
 /// `String.prototype.lastIndexOf( searchValue[, fromIndex] )`
@@ -567,41 +629,21 @@ pub fn index_of(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> Resu
 /// [spec]: https://tc39.es/ecma262/#sec-string.prototype.lastindexof
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/String/lastIndexOf
 pub fn last_index_of(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
-    // First we get it the actual string a private field stored on the object only the engine has access to.
-    // Then we convert it into a Rust String by wrapping it in from_value
-    let primitive_val: String = ctx.value_to_rust_string(this);
+    let primitive_val = to_js_string(this, ctx)?;
 
     // TODO: Should throw TypeError if search_string is regular expression
-    let search_string = String::from(
-        args.get(0)
-            .expect("failed to get argument for String method"),
-    );
+    let search_string = to_js_string(args.get(0).unwrap_or(&Value::undefined()), ctx)?;
 
-    let length: i32 = primitive_val.chars().count() as i32;
-
-    // If less than 2 args specified, position is 'undefined', defaults to 0
-    let position: i32 = if args.len() < 2 {
-        0
-    } else {
-        i32::from(args.get(1).expect("Could not get argument"))
+    // A missing or `NaN` position searches the whole string
+    let position = match args.get(1) {
+        Some(arg) if !arg.to_number().is_nan() => arg.to_integer_or_infinity(),
+        _ => std::f64::INFINITY,
     };
+    let start = clamp_index(position, primitive_val.len());
 
-    let start = min(max(position, 0), length);
-
-    // Here cannot use the &str method "rfind", because this returns the last
-    // byte index: we need to return the last char index in the JS String
-    // Instead, iterate over the part we're checking keeping track of the higher
-    // index we found that "starts with" the search string
-    let mut highest_index: i32 = -1;
-    for index in start..length {
-        let this_string: String = primitive_val.chars().skip(index as usize).collect();
-        if this_string.starts_with(&search_string) {
-            highest_index = index;
-        }
-    }
-
-    // This will still be -1 if no matches were found, else with be >= 0
-    Ok(Value::from(highest_index))
+    Ok(primitive_val
+        .last_index_of(&search_string, start)
+        .map_or(Value::from(-1), Value::from))
 }
 
 /// `String.prototype.match( regexp )`
@@ -616,8 +658,14 @@ pub fn last_index_of(this: &mut Value, args: &[Value], ctx: &mut Interpreter) ->
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/String/match
 /// [regex]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Guide/Regular_Expressions
 pub fn r#match(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
-    let mut re = make_regexp(&mut Value::from(Object::default()), &[args[0].clone()], ctx)?;
-    regexp_match(&mut re, ctx.value_to_rust_string(this), ctx)
+    let regexp = args.get(0).cloned().unwrap_or_else(Value::undefined);
+    if let Some(result) = call_symbol_method(&regexp, WellKnownSymbol::Match, &[this.clone()], ctx)?
+    {
+        return Ok(result);
+    }
+    let string = to_js_string(this, ctx)?;
+    let regexp = regexp_create(regexp, "", ctx)?;
+    invoke_symbol_method(&regexp, WellKnownSymbol::Match, &[Value::from(string)], ctx)
 }
 
 /// Abstract method `StringPad`.
@@ -625,40 +673,35 @@ pub fn r#match(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> Resul
 /// Performs the actual string padding for padStart/End.
 /// <https://tc39.es/ecma262/#sec-stringpad/>
 fn string_pad(
-    primitive: String,
-    max_length: i32,
-    fill_string: Option<String>,
+    primitive: JsString,
+    max_length: f64,
+    fill_string: Option<JsString>,
     at_start: bool,
+    ctx: &mut Interpreter,
 ) -> ResultValue {
-    let primitive_length = primitive.len() as i32;
+    let primitive_length = primitive.len();
 
-    if max_length <= primitive_length {
+    if max_length <= primitive_length as f64 {
         return Ok(Value::from(primitive));
     }
+    ctx.reserve_heap((max_length as usize).saturating_mul(mem::size_of::<u16>()))?;
 
-    let filler = match fill_string {
-        Some(filler) => filler,
-        None => String::from(" "),
-    };
+    let filler = fill_string.unwrap_or_else(|| JsString::from(" "));
 
-    if filler == "" {
+    if filler.is_empty() {
         return Ok(Value::from(primitive));
     }
 
-    let fill_len = max_length.wrapping_sub(primitive_length);
-    let mut fill_str = String::new();
+    // Cut the repeated filler to size, counting code units
+    let fill_len = max_length as usize - primitive_length;
+    let fill_str = filler.iter().copied().cycle().take(fill_len);
 
-    while fill_str.len() < fill_len as usize {
-        fill_str.push_str(&filler);
-    }
-    // Cut to size max_length
-    let concat_fill_str: String = fill_str.chars().take(fill_len as usize).collect();
-
-    if at_start {
-        Ok(Value::from(format!("{}{}", concat_fill_str, &primitive)))
+    let padded: Vec<u16> = if at_start {
+        fill_str.chain(primitive.iter().copied()).collect()
     } else {
-        Ok(Value::from(format!("{}{}", primitive, &concat_fill_str)))
-    }
+        primitive.iter().copied().chain(fill_str).collect()
+    };
+    Ok(Value::from(JsString::from(padded)))
 }
 
 /// `String.prototype.padEnd( targetLength[, padString] )`
@@ -674,21 +717,15 @@ fn string_pad(
 /// [spec]: https://tc39.es/ecma262/#sec-string.prototype.padend
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/String/padEnd
 pub fn pad_end(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
-    let primitive_val: String = ctx.value_to_rust_string(this);
-    if args.is_empty() {
-        return Err(Value::from("padEnd requires maxLength argument"));
-    }
-    let max_length = i32::from(
-        args.get(0)
-            .expect("failed to get argument for String method"),
-    );
-
-    let fill_string: Option<String> = match args.len() {
-        1 => None,
-        _ => Some(String::from(args.get(1).expect("Could not get argument"))),
+    let primitive_val = to_js_string(this, ctx)?;
+    let max_length = integer_arg(args, 0, 0.0);
+
+    let fill_string = match args.get(1) {
+        Some(fill) if !fill.is_undefined() => Some(to_js_string(fill, ctx)?),
+        _ => None,
     };
 
-    string_pad(primitive_val, max_length, fill_string, false)
+    string_pad(primitive_val, max_length, fill_string, false, ctx)
 }
 
 /// `String.prototype.padStart( targetLength [, padString] )`
@@ -704,21 +741,15 @@ pub fn pad_end(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> Resul
 /// [spec]: https://tc39.es/ecma262/#sec-string.prototype.padstart
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/String/padStart
 pub fn pad_start(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
-    let primitive_val: String = ctx.value_to_rust_string(this);
-    if args.is_empty() {
-        return Err(Value::from("padStart requires maxLength argument"));
-    }
-    let max_length = i32::from(
-        args.get(0)
-            .expect("failed to get argument for String method"),
-    );
-
-    let fill_string: Option<String> = match args.len() {
-        1 => None,
-        _ => Some(String::from(args.get(1).expect("Could not get argument"))),
+    let primitive_val = to_js_string(this, ctx)?;
+    let max_length = integer_arg(args, 0, 0.0);
+
+    let fill_string = match args.get(1) {
+        Some(fill) if !fill.is_undefined() => Some(to_js_string(fill, ctx)?),
+        _ => None,
     };
 
-    string_pad(primitive_val, max_length, fill_string, true)
+    string_pad(primitive_val, max_length, fill_string, true, ctx)
 }
 
 /// Helper function to check if a `char` is trimmable.
@@ -740,6 +771,56 @@ fn is_trimmable_whitespace(c: char) -> bool {
     }
 }
 
+/// Helper function to check if a UTF-16 code unit is trimmable.
+///
+/// Every whitespace and line terminator is in the Basic Multilingual Plane, so checking code
+/// units one by one is enough.
+fn is_trimmable_unit(unit: u16) -> bool {
+    from_u32(u32::from(unit)).map_or(false, is_trimmable_whitespace)
+}
+
+/// Removes the trimmable whitespace from the start and/or end of a string.
+pub(crate) fn trim_string(string: &JsString, start: bool, end: bool) -> JsString {
+    let mut units: &[u16] = string;
+    if start {
+        let first = units
+            .iter()
+            .position(|&u| !is_trimmable_unit(u))
+            .unwrap_or_else(|| units.len());
+        units = &units[first..];
+    }
+    if end {
+        let last = units
+            .iter()
+            .rposition(|&u| !is_trimmable_unit(u))
+            .map_or(0, |i| i + 1);
+        units = &units[..last];
+    }
+    JsString::from(units)
+}
+
+/// Applies a case mapping to the well formed parts of a string, keeping lone surrogates as they
+/// are.
+fn map_case<F>(string: &JsString, f: F) -> JsString
+where
+    F: Fn(&str) -> String,
+{
+    let mut result = Vec::with_capacity(string.len());
+    let mut run = String::new();
+    for code_point in string.code_points() {
+        match code_point.as_char() {
+            Some(c) => run.push(c),
+            None => {
+                result.extend(f(&run).encode_utf16());
+                run.clear();
+                result.push(code_point.code_point as u16);
+            }
+        }
+    }
+    result.extend(f(&run).encode_utf16());
+    JsString::from(result)
+}
+
 /// String.prototype.trim()
 ///
 /// The `trim()` method removes whitespace from both ends of a string.
@@ -753,8 +834,9 @@ fn is_trimmable_whitespace(c: char) -> bool {
 /// [spec]: https://tc39.es/ecma262/#sec-string.prototype.trim
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/String/trim
 pub fn trim(this: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue {
-    let this_str: String = ctx.value_to_rust_string(this);
-    Ok(Value::from(this_str.trim_matches(is_trimmable_whitespace)))
+    let this_str = to_js_string(this, ctx)?;
+    ctx.reserve_string(this_str.len())?;
+    Ok(Value::from(trim_string(&this_str, true, true)))
 }
 
 /// `String.prototype.trimStart()`
@@ -770,10 +852,9 @@ pub fn trim(this: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue
 /// [spec]: https://tc39.es/ecma262/#sec-string.prototype.trimstart
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/String/trimStart
 pub fn trim_start(this: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue {
-    let this_str: String = ctx.value_to_rust_string(this);
-    Ok(Value::from(
-        this_str.trim_start_matches(is_trimmable_whitespace),
-    ))
+    let this_str = to_js_string(this, ctx)?;
+    ctx.reserve_string(this_str.len())?;
+    Ok(Value::from(trim_string(&this_str, true, false)))
 }
 
 /// String.prototype.trimEnd()
@@ -789,10 +870,9 @@ pub fn trim_start(this: &mut Value, _: &[Value], ctx: &mut Interpreter) -> Resul
 /// [spec]: https://tc39.es/ecma262/#sec-string.prototype.trimend
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/String/trimEnd
 pub fn trim_end(this: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue {
-    let this_str: String = ctx.value_to_rust_string(this);
-    Ok(Value::from(
-        this_str.trim_end_matches(is_trimmable_whitespace),
-    ))
+    let this_str = to_js_string(this, ctx)?;
+    ctx.reserve_string(this_str.len())?;
+    Ok(Value::from(trim_string(&this_str, false, true)))
 }
 
 /// `String.prototype.toLowerCase()`
@@ -806,12 +886,11 @@ pub fn trim_end(this: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultV
 /// [spec]: https://tc39.es/ecma262/#sec-string.prototype.tolowercase
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/String/toLowerCase
 pub fn to_lowercase(this: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue {
-    // First we get it the actual string a private field stored on the object only the engine has access to.
-    // Then we convert it into a Rust String by wrapping it in from_value
-    let this_str: String = ctx.value_to_rust_string(this);
-    // The Rust String is mapped to uppercase using the builtin .to_lowercase().
+    let this_str = to_js_string(this, ctx)?;
+    ctx.reserve_string(this_str.len())?;
+    // The Rust String is mapped to lowercase using the builtin .to_lowercase().
     // There might be corner cases where it does not behave exactly like Javascript expects
-    Ok(Value::from(this_str.to_lowercase()))
+    Ok(Value::from(map_case(&this_str, str::to_lowercase)))
 }
 
 /// `String.prototype.toUpperCase()`
@@ -827,12 +906,11 @@ pub fn to_lowercase(this: &mut Value, _: &[Value], ctx: &mut Interpreter) -> Res
 /// [spec]: https://tc39.es/ecma262/#sec-string.prototype.toUppercase
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/String/toUpperCase
 pub fn to_uppercase(this: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue {
-    // First we get it the actual string a private field stored on the object only the engine has access to.
-    // Then we convert it into a Rust String by wrapping it in from_value
-    let this_str: String = ctx.value_to_rust_string(this);
+    let this_str = to_js_string(this, ctx)?;
+    ctx.reserve_string(this_str.len())?;
     // The Rust String is mapped to uppercase using the builtin .to_uppercase().
     // There might be corner cases where it does not behave exactly like Javascript expects
-    Ok(Value::from(this_str.to_uppercase()))
+    Ok(Value::from(map_case(&this_str, str::to_uppercase)))
 }
 
 /// `String.prototype.substring( indexStart[, indexEnd] )`
@@ -846,40 +924,19 @@ pub fn to_uppercase(this: &mut Value, _: &[Value], ctx: &mut Interpreter) -> Res
 /// [spec]: https://tc39.es/ecma262/#sec-string.prototype.substring
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/String/substring
 pub fn substring(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
-    // First we get it the actual string a private field stored on the object only the engine has access to.
-    // Then we convert it into a Rust String by wrapping it in from_value
-    let primitive_val: String = ctx.value_to_rust_string(this);
-    // If no args are specified, start is 'undefined', defaults to 0
-    let start = if args.is_empty() {
-        0
-    } else {
-        i32::from(
-            args.get(0)
-                .expect("failed to get argument for String method"),
-        )
-    };
-    let length: i32 = primitive_val.chars().count() as i32;
-    // If less than 2 args specified, end is the length of the this object converted to a String
-    let end = if args.len() < 2 {
-        length
-    } else {
-        i32::from(args.get(1).expect("Could not get argument"))
-    };
+    let primitive_val = to_js_string(this, ctx)?;
+    let length = primitive_val.len();
     // Both start and end args replaced by 0 if they were negative
     // or by the length of the String if they were greater
-    let final_start = min(max(start, 0), length);
-    let final_end = min(max(end, 0), length);
+    let final_start = clamp_index(integer_arg(args, 0, 0.0), length);
+    let final_end = clamp_index(integer_arg(args, 1, length as f64), length);
     // Start and end are swapped if start is greater than end
-    let from = min(final_start, final_end) as usize;
-    let to = max(final_start, final_end) as usize;
+    let from = final_start.min(final_end);
+    let to = final_start.max(final_end);
     // Extract the part of the string contained between the start index and the end index
     // where start is guaranteed to be smaller or equals to end
-    let extracted_string: String = primitive_val
-        .chars()
-        .skip(from)
-        .take(to.wrapping_sub(from))
-        .collect();
-    Ok(Value::from(extracted_string))
+    ctx.reserve_string(to - from)?;
+    Ok(Value::from(JsString::from(&primitive_val[from..to])))
 }
 
 /// `String.prototype.substr( start[, length] )`
@@ -894,48 +951,22 @@ pub fn substring(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> Res
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/String/substr
 /// <https://tc39.es/ecma262/#sec-string.prototype.substr>
 pub fn substr(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
-    // First we get it the actual string a private field stored on the object only the engine has access to.
-    // Then we convert it into a Rust String by wrapping it in from_value
-    let primitive_val: String = ctx.value_to_rust_string(this);
-    // If no args are specified, start is 'undefined', defaults to 0
-    let mut start = if args.is_empty() {
-        0
-    } else {
-        i32::from(
-            args.get(0)
-                .expect("failed to get argument for String method"),
-        )
-    };
-    let length: i32 = primitive_val.chars().count() as i32;
-    // If less than 2 args specified, end is +infinity, the maximum number value.
-    // Using i32::max_value() should be safe because the final length used is at most
-    // the number of code units from start to the end of the string,
-    // which should always be smaller or equals to both +infinity and i32::max_value
-    let end = if args.len() < 2 {
-        i32::max_value()
-    } else {
-        i32::from(args.get(1).expect("Could not get argument"))
-    };
+    let primitive_val = to_js_string(this, ctx)?;
+    let length = primitive_val.len();
     // If start is negative it become the number of code units from the end of the string
-    if start < 0 {
-        start = max(length.wrapping_add(start), 0);
-    }
-    // length replaced by 0 if it was negative
-    // or by the number of code units from start to the end of the string if it was greater
-    let result_length = min(max(end, 0), length.wrapping_sub(start));
-    // If length is negative we return an empty string
-    // otherwise we extract the part of the string from start and is length code units long
-    if result_length <= 0 {
-        Ok(Value::from("".to_string()))
+    let start = integer_arg(args, 0, 0.0);
+    let start = if start < 0.0 {
+        clamp_index(length as f64 + start, length)
     } else {
-        let extracted_string: String = primitive_val
-            .chars()
-            .skip(start as usize)
-            .take(result_length as usize)
-            .collect();
-
-        Ok(Value::from(extracted_string))
-    }
+        clamp_index(start, length)
+    };
+    // If less than 2 args specified, the length is +infinity, which is clamped
+    // to the number of code units from start to the end of the string
+    let result_length = clamp_index(integer_arg(args, 1, std::f64::INFINITY), length - start);
+    ctx.reserve_string(result_length)?;
+    Ok(Value::from(JsString::from(
+        &primitive_val[start..start + result_length],
+    )))
 }
 
 /// String.prototype.valueOf()
@@ -965,37 +996,325 @@ pub fn value_of(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> Resu
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/String/matchAll
 /// [regex]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Guide/Regular_Expressions
 /// [cg]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Guide/Regular_Expressions/Groups_and_Ranges
-// TODO: update this method to return iterator
 pub fn match_all(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
-    let mut re: Value = match args.get(0) {
-        Some(arg) => {
-            if arg.is_null() {
-                make_regexp(
-                    &mut Value::from(Object::default()),
-                    &[
-                        Value::from(ctx.value_to_rust_string(arg)),
-                        Value::from(String::from("g")),
-                    ],
-                    ctx,
-                )
-            } else if arg.is_undefined() {
-                make_regexp(
-                    &mut Value::from(Object::default()),
-                    &[Value::undefined(), Value::from(String::from("g"))],
-                    ctx,
-                )
-            } else {
-                Ok(arg.clone())
+    let regexp = args.get(0).cloned().unwrap_or_else(Value::undefined);
+    if is_regexp(&regexp) && !regexp_flags(&regexp, ctx)?.contains('g') {
+        return ctx.throw_type_error("matchAll must be called with a global RegExp");
+    }
+    if let Some(result) =
+        call_symbol_method(&regexp, WellKnownSymbol::MatchAll, &[this.clone()], ctx)?
+    {
+        return Ok(result);
+    }
+    let string = to_js_string(this, ctx)?;
+    let regexp = regexp_create(regexp, "g", ctx)?;
+    invoke_symbol_method(
+        &regexp,
+        WellKnownSymbol::MatchAll,
+        &[Value::from(string)],
+        ctx,
+    )
+}
+
+/// `String.prototype.codePointAt( pos )`
+///
+/// The `codePointAt()` method returns a non-negative integer that is the Unicode code point value
+/// at the given position. A surrogate pair is combined into a single code point.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-string.prototype.codepointat
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/String/codePointAt
+pub fn code_point_at(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let primitive_val = to_js_string(this, ctx)?;
+    let pos = integer_arg(args, 0, 0.0);
+
+    if pos < 0.0 || pos >= primitive_val.len() as f64 {
+        return Ok(Value::undefined());
+    }
+    Ok(primitive_val
+        .code_point_at(pos as usize)
+        .map_or_else(Value::undefined, |code_point| {
+            Value::from(f64::from(code_point.code_point))
+        }))
+}
+
+/// `String.prototype.at( index )`
+///
+/// The `at()` method returns the UTF-16 code unit at the given index, negative integers count
+/// back from the last code unit.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-string.prototype.at
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/String/at
+pub fn at(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let primitive_val = to_js_string(this, ctx)?;
+    let length = primitive_val.len() as f64;
+    let relative = integer_arg(args, 0, 0.0);
+    let index = if relative >= 0.0 {
+        relative
+    } else {
+        length + relative
+    };
+
+    if index < 0.0 || index >= length {
+        return Ok(Value::undefined());
+    }
+    let index = index as usize;
+    Ok(Value::from(JsString::from(&primitive_val[index..=index])))
+}
+
+/// `String.prototype.split( separator [, limit] )`
+///
+/// The `split()` method divides a string into an ordered list of substrings, puts these
+/// substrings into an array, and returns the array. The separator can be a string or a `RegExp`,
+/// the captures of a `RegExp` separator are included in the array.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-string.prototype.split
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/String/split
+pub fn split(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let separator = args.get(0).cloned().unwrap_or_else(Value::undefined);
+    let limit = args.get(1).cloned().unwrap_or_else(Value::undefined);
+    if let Some(result) = call_symbol_method(
+        &separator,
+        WellKnownSymbol::Split,
+        &[this.clone(), limit],
+        ctx,
+    )? {
+        return Ok(result);
+    }
+
+    let primitive_val = to_js_string(this, ctx)?;
+    // The parts hold at most the code units of the string
+    ctx.reserve_string(primitive_val.len())?;
+    let limit = match args.get(1) {
+        Some(limit) if !limit.is_undefined() => modulo(ctx.to_number(limit)?, 32) as usize,
+        _ => u32::MAX as usize,
+    };
+
+    let mut parts: Vec<Value> = Vec::new();
+    if limit == 0 {
+        return create_array_from_list(&parts, ctx);
+    }
+
+    if separator.is_undefined() {
+        parts.push(Value::from(primitive_val));
+        return create_array_from_list(&parts, ctx);
+    }
+    let separator = to_js_string(&separator, ctx)?;
+    if separator.is_empty() {
+        // Split between every code unit
+        for unit in primitive_val.iter().take(limit) {
+            parts.push(Value::from(JsString::from(std::slice::from_ref(unit))));
+        }
+        return create_array_from_list(&parts, ctx);
+    }
+
+    let mut last_end = 0;
+    while let Some(start) = primitive_val.index_of(&separator, last_end) {
+        parts.push(Value::from(JsString::from(&primitive_val[last_end..start])));
+        if parts.len() == limit {
+            return create_array_from_list(&parts, ctx);
+        }
+        last_end = start + separator.len();
+    }
+    parts.push(Value::from(JsString::from(&primitive_val[last_end..])));
+    create_array_from_list(&parts, ctx)
+}
+
+/// `String.prototype.search( regexp )`
+///
+/// The `search()` method returns the index of the first match of a regular expression in the
+/// string, or `-1` if there is none. A value which isn't a `RegExp` is used as a pattern.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-string.prototype.search
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/String/search
+pub fn search(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let regexp = args.get(0).cloned().unwrap_or_else(Value::undefined);
+    if let Some(result) =
+        call_symbol_method(&regexp, WellKnownSymbol::Search, &[this.clone()], ctx)?
+    {
+        return Ok(result);
+    }
+    let string = to_js_string(this, ctx)?;
+    let regexp = regexp_create(regexp, "", ctx)?;
+    invoke_symbol_method(
+        &regexp,
+        WellKnownSymbol::Search,
+        &[Value::from(string)],
+        ctx,
+    )
+}
+
+/// `String.prototype.normalize( [form] )`
+///
+/// The `normalize()` method returns the Unicode Normalization Form of the string, one of `"NFC"`
+/// (the default), `"NFD"`, `"NFKC"` or `"NFKD"`.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-string.prototype.normalize
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/String/normalize
+pub fn normalize(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let primitive_val = to_js_string(this, ctx)?;
+    let form = match args.get(0) {
+        Some(form) if !form.is_undefined() => {
+            let name = to_js_string(form, ctx)?.to_std_string_lossy();
+            match NormalizationForm::from_name(&name) {
+                Some(form) => form,
+                None => {
+                    return ctx.throw_range_error(
+                        "The normalization form should be one of NFC, NFD, NFKC, NFKD.",
+                    )
+                }
             }
         }
-        None => make_regexp(
-            &mut Value::from(Object::default()),
-            &[Value::from(String::new()), Value::from("g")],
-            ctx,
-        ),
-    }?;
+        _ => NormalizationForm::Nfc,
+    };
+
+    ctx.reserve_string(primitive_val.len())?;
+    Ok(Value::from(normalization::normalize(&primitive_val, form)))
+}
+
+/// `String.prototype.localeCompare( compareString )`
+///
+/// The `localeCompare()` method returns a negative number, a positive number or `0` depending on
+/// whether the string sorts before, after or together with the given string.
+///
+/// There is no locale support, so the strings are compared by code points once normalized, which
+/// makes canonically equivalent strings compare equal.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-string.prototype.localecompare
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/String/localeCompare
+pub fn locale_compare(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let primitive_val = to_js_string(this, ctx)?;
+    let that = to_js_string(args.get(0).unwrap_or(&Value::undefined()), ctx)?;
+
+    let left = normalization::normalize(&primitive_val, NormalizationForm::Nfc);
+    let right = normalization::normalize(&that, NormalizationForm::Nfc);
+    let ordering = left
+        .code_points()
+        .map(|c| c.code_point)
+        .cmp(right.code_points().map(|c| c.code_point));
+    Ok(Value::from(ordering as i32))
+}
+
+/// `String.prototype[ @@iterator ]( )`
+///
+/// This method returns a new iterator over the code points of the string.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-string.prototype-@@iterator
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/String/@@iterator
+pub fn iterator(this: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    if this.is_null_or_undefined() {
+        return ctx
+            .throw_type_error("String.prototype[Symbol.iterator] called on null or undefined");
+    }
+    let primitive_val = to_js_string(this, ctx)?;
+    Ok(create_string_iterator(primitive_val, ctx))
+}
 
-    regexp_match_all(&mut re, ctx.value_to_rust_string(this))
+/// `String.fromCharCode( ...codeUnits )`
+///
+/// The `String.fromCharCode()` static method returns a string created from the specified
+/// sequence of UTF-16 code units.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-string.fromcharcode
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/String/fromCharCode
+pub fn from_char_code(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let units = args
+        .iter()
+        .map(|arg| Ok(modulo(ctx.to_number(arg)?, 16) as u16))
+        .collect::<Result<Vec<u16>, Value>>()?;
+    Ok(Value::from(JsString::from(units)))
+}
+
+/// `String.fromCodePoint( ...codePoints )`
+///
+/// The `String.fromCodePoint()` static method returns a string created from the specified
+/// sequence of code points. A code point outside of `0..=0x10FFFF` is a `RangeError`.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-string.fromcodepoint
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/String/fromCodePoint
+pub fn from_code_point(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let mut units = Vec::with_capacity(args.len());
+    for arg in args {
+        let code_point = ctx.to_number(arg)?;
+        if code_point.trunc() != code_point || code_point < 0.0 || code_point > 1_114_111.0 {
+            let code_point = ctx.to_string(arg)?;
+            return ctx.throw_range_error(format!("Invalid code point {}", code_point));
+        }
+        match from_u32(code_point as u32) {
+            Some(c) => units.extend_from_slice(c.encode_utf16(&mut [0; 2])),
+            // Lone surrogates
+            None => units.push(code_point as u16),
+        }
+    }
+    Ok(Value::from(JsString::from(units)))
+}
+
+/// `String.raw( template, ...substitutions )`
+///
+/// The `String.raw()` static method is the tag function of template literals, it returns the raw
+/// strings of the template interleaved with the substitutions.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-string.raw
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/String/raw
+pub fn raw(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let cooked = ctx.to_object(args.get(0).unwrap_or(&Value::undefined()))?;
+    let raw = ctx.to_object(&cooked.get_field_slice("raw"))?;
+    let literal_count = length_of_array_like(&raw, ctx)?;
+
+    let mut result = Vec::new();
+    for index in 0..literal_count {
+        result.extend_from_slice(&to_js_string(
+            &raw.get_field_slice(&index.to_string()),
+            ctx,
+        )?);
+        if index + 1 == literal_count {
+            break;
+        }
+        if let Some(substitution) = args.get(index + 1) {
+            result.extend_from_slice(&to_js_string(substitution, ctx)?);
+        }
+    }
+    ctx.reserve_string(result.len())?;
+    Ok(Value::from(JsString::from(result)))
 }
 
 /// Create a new `String` object.
@@ -1028,8 +1347,24 @@ pub fn create(global: &Value) -> Value {
     make_builtin_fn!(value_of, named "valueOf", of prototype);
     make_builtin_fn!(match_all, named "matchAll", with length 1, of prototype);
     make_builtin_fn!(replace, named "replace", with length 2, of prototype);
-
-    make_constructor_fn!(make_string, call_string, global, prototype)
+    make_builtin_fn!(replace_all, named "replaceAll", with length 2, of prototype);
+    make_builtin_fn!(code_point_at, named "codePointAt", with length 1, of prototype);
+    make_builtin_fn!(at, named "at", with length 1, of prototype);
+    make_builtin_fn!(split, named "split", with length 2, of prototype);
+    make_builtin_fn!(search, named "search", with length 1, of prototype);
+    make_builtin_fn!(normalize, named "normalize", of prototype);
+    make_builtin_fn!(locale_compare, named "localeCompare", with length 1, of prototype);
+    let iterator = make_builtin_fn!(iterator, named "[Symbol.iterator]", with length 0);
+    prototype.set_field(Value::symbol(WellKnownSymbol::Iterator.into()), iterator);
+
+    let string = make_constructor_fn!(make_string, call_string, global, prototype);
+
+    // Static Methods
+    make_builtin_fn!(from_char_code, named "fromCharCode", with length 1, of string);
+    make_builtin_fn!(from_code_point, named "fromCodePoint", with length 1, of string);
+    make_builtin_fn!(raw, named "raw", with length 1, of string);
+
+    string
 }
 
 /// Initialise the `String` object on the global object.
@@ -0,0 +1,204 @@
+//! Unicode normalization of strings, as used by `String.prototype.normalize`.
+//!
+//! More information:
+//!  - [Unicode Standard Annex #15][uax15]
+//!
+//! [uax15]: https://www.unicode.org/reports/tr15/
+
+use super::normalization_tables::{
+    CANONICAL_DECOMPOSITIONS, COMBINING_CLASSES, COMPATIBILITY_DECOMPOSITIONS, COMPOSITIONS,
+};
+use crate::builtins::value::JsString;
+use std::cmp::Ordering;
+
+// Constants of the algorithmic Hangul syllable (de)composition.
+const S_BASE: u32 = 0xAC00;
+const L_BASE: u32 = 0x1100;
+const V_BASE: u32 = 0x1161;
+const T_BASE: u32 = 0x11A7;
+const L_COUNT: u32 = 19;
+const V_COUNT: u32 = 21;
+const T_COUNT: u32 = 28;
+const N_COUNT: u32 = V_COUNT * T_COUNT;
+const S_COUNT: u32 = L_COUNT * N_COUNT;
+
+/// The normalization forms accepted by `String.prototype.normalize`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum NormalizationForm {
+    Nfc,
+    Nfd,
+    Nfkc,
+    Nfkd,
+}
+
+impl NormalizationForm {
+    /// Parses the name of a normalization form, like `"NFC"`.
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "NFC" => Some(Self::Nfc),
+            "NFD" => Some(Self::Nfd),
+            "NFKC" => Some(Self::Nfkc),
+            "NFKD" => Some(Self::Nfkd),
+            _ => None,
+        }
+    }
+
+    fn is_compatibility(self) -> bool {
+        self == Self::Nfkc || self == Self::Nfkd
+    }
+
+    fn is_composed(self) -> bool {
+        self == Self::Nfc || self == Self::Nfkc
+    }
+}
+
+/// Returns the canonical combining class of a code point.
+fn combining_class(code_point: u32) -> u8 {
+    COMBINING_CLASSES
+        .binary_search_by(|&(first, last, _)| {
+            if last < code_point {
+                Ordering::Less
+            } else if first > code_point {
+                Ordering::Greater
+            } else {
+                Ordering::Equal
+            }
+        })
+        .map_or(0, |index| COMBINING_CLASSES[index].2)
+}
+
+/// Looks up the decomposition mapping of a code point in one of the tables.
+fn lookup(table: &'static [(u32, &'static [u32])], code_point: u32) -> Option<&'static [u32]> {
+    table
+        .binary_search_by_key(&code_point, |&(c, _)| c)
+        .ok()
+        .map(|index| table[index].1)
+}
+
+/// Appends the full decomposition of `code_point` to `out`.
+fn decompose(code_point: u32, compatibility: bool, out: &mut Vec<u32>) {
+    if code_point >= S_BASE && code_point < S_BASE + S_COUNT {
+        let index = code_point - S_BASE;
+        out.push(L_BASE + index / N_COUNT);
+        out.push(V_BASE + (index % N_COUNT) / T_COUNT);
+        if index % T_COUNT != 0 {
+            out.push(T_BASE + index % T_COUNT);
+        }
+        return;
+    }
+
+    let mapping = if compatibility {
+        lookup(COMPATIBILITY_DECOMPOSITIONS, code_point)
+            .or_else(|| lookup(CANONICAL_DECOMPOSITIONS, code_point))
+    } else {
+        lookup(CANONICAL_DECOMPOSITIONS, code_point)
+    };
+    match mapping {
+        Some(mapping) => {
+            for &c in mapping {
+                decompose(c, compatibility, out);
+            }
+        }
+        None => out.push(code_point),
+    }
+}
+
+/// Sorts every run of non-starters by their combining class, keeping the order of equal ones.
+fn canonical_order(code_points: &mut [u32]) {
+    let mut start = 0;
+    while start < code_points.len() {
+        if combining_class(code_points[start]) == 0 {
+            start += 1;
+            continue;
+        }
+        let end = code_points[start..]
+            .iter()
+            .position(|&c| combining_class(c) == 0)
+            .map_or(code_points.len(), |offset| start + offset);
+        code_points[start..end].sort_by_key(|&c| combining_class(c));
+        start = end;
+    }
+}
+
+/// Returns the primary composite of a pair of code points, if there is one.
+fn compose_pair(first: u32, second: u32) -> Option<u32> {
+    // Hangul LV and LVT syllables
+    if first >= L_BASE && first < L_BASE + L_COUNT && second >= V_BASE && second < V_BASE + V_COUNT
+    {
+        return Some(S_BASE + ((first - L_BASE) * V_COUNT + (second - V_BASE)) * T_COUNT);
+    }
+    if first >= S_BASE
+        && first < S_BASE + S_COUNT
+        && (first - S_BASE) % T_COUNT == 0
+        && second > T_BASE
+        && second < T_BASE + T_COUNT
+    {
+        return Some(first + (second - T_BASE));
+    }
+
+    COMPOSITIONS
+        .binary_search_by(|&(a, b, _)| (a, b).cmp(&(first, second)))
+        .ok()
+        .map(|index| COMPOSITIONS[index].2)
+}
+
+/// The canonical composition algorithm, applied to a decomposed and ordered sequence.
+fn compose(code_points: &[u32]) -> Vec<u32> {
+    let mut result: Vec<u32> = Vec::with_capacity(code_points.len());
+    // Index in `result` of the last starter, which following code points may compose with
+    let mut starter: Option<usize> = None;
+    // Combining class of the last code point appended after the starter
+    let mut last_class: Option<u8> = None;
+
+    for &c in code_points {
+        let class = combining_class(c);
+        if let Some(index) = starter {
+            // A code point is blocked from the starter by one of the same or a higher class
+            let blocked = match last_class {
+                Some(last_class) => last_class == 0 || last_class >= class,
+                None => false,
+            };
+            if !blocked {
+                if let Some(composite) = compose_pair(result[index], c) {
+                    result[index] = composite;
+                    continue;
+                }
+            }
+        }
+        if class == 0 {
+            starter = Some(result.len());
+            last_class = None;
+        } else {
+            last_class = Some(class);
+        }
+        result.push(c);
+    }
+    result
+}
+
+/// Normalizes the string to the given form.
+///
+/// Lone surrogates are kept as they are, they never combine with anything.
+pub(crate) fn normalize(string: &JsString, form: NormalizationForm) -> JsString {
+    let mut code_points = Vec::with_capacity(string.len());
+    for code_point in string.code_points() {
+        decompose(
+            code_point.code_point,
+            form.is_compatibility(),
+            &mut code_points,
+        );
+    }
+    canonical_order(&mut code_points);
+    if form.is_composed() {
+        code_points = compose(&code_points);
+    }
+
+    let mut units = Vec::with_capacity(code_points.len());
+    for code_point in code_points {
+        match std::char::from_u32(code_point) {
+            Some(c) => units.extend_from_slice(c.encode_utf16(&mut [0; 2])),
+            None => units.push(code_point as u16),
+        }
+    }
+    JsString::from(units)
+}
@@ -0,0 +1,104 @@
+//! This module implements the `StringIterator` objects, returned by
+//! `String.prototype[Symbol.iterator]()`.
+//!
+//! More information:
+//!  - [ECMAScript reference][spec]
+//!
+//! [spec]: https://tc39.es/ecma262/#sec-string-iterator-objects
+
+use crate::{
+    builtins::{
+        iterator::{create_iter_result_object, create_iterator_prototype},
+        object::{InternalState, Object},
+        value::{JsString, ResultValue, Value},
+    },
+    exec::Interpreter,
+};
+
+/// The internal slot of the global object holding `%StringIteratorPrototype%`.
+const STRING_ITERATOR_PROTOTYPE: &str = "StringIteratorPrototype";
+
+/// The internal state of a string iterator.
+#[derive(Debug, Clone)]
+struct StringIterator {
+    /// `[[IteratedString]]`, `None` once the iteration is done.
+    string: Option<JsString>,
+    /// The index of the next code unit to visit.
+    next_index: usize,
+}
+
+impl InternalState for StringIterator {}
+
+/// Creates a new iterator over the code points of `string`.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-createstringiterator
+pub(crate) fn create_string_iterator(string: JsString, ctx: &Interpreter) -> Value {
+    let prototype = ctx
+        .realm
+        .intrinsics
+        .get_internal_slot(STRING_ITERATOR_PROTOTYPE);
+    let iterator = Value::object(Object::create(prototype));
+    iterator.set_internal_state(StringIterator {
+        string: Some(string),
+        next_index: 0,
+    });
+    iterator
+}
+
+/// `%StringIteratorPrototype%.next( )`
+///
+/// Produces the next code point of the string, surrogate pairs are kept together.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-%stringiteratorprototype%.next
+pub fn next(this: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let state = this
+        .get_internal_state()
+        .and_then(|state| state.downcast_ref::<StringIterator>().cloned());
+    let state = match state {
+        Some(state) => state,
+        None => return ctx.throw_type_error("next method called on an incompatible receiver"),
+    };
+
+    let string = match state.string {
+        Some(string) => string,
+        None => return Ok(create_iter_result_object(Value::undefined(), true, ctx)),
+    };
+    let index = state.next_index;
+    let code_point = match string.code_point_at(index) {
+        Some(code_point) => code_point,
+        None => {
+            this.set_internal_state(StringIterator {
+                string: None,
+                next_index: index,
+            });
+            return Ok(create_iter_result_object(Value::undefined(), true, ctx));
+        }
+    };
+
+    let end = index + code_point.code_unit_count;
+    let result = Value::from(JsString::from(&string[index..end]));
+    this.set_internal_state(StringIterator {
+        string: Some(string),
+        next_index: end,
+    });
+    Ok(create_iter_result_object(result, false, ctx))
+}
+
+/// Create `%StringIteratorPrototype%`.
+pub fn create(global: &Value) -> Value {
+    let prototype = create_iterator_prototype(global, "String Iterator");
+    make_builtin_fn!(next, named "next", of prototype);
+    prototype
+}
+
+/// Initialise `%StringIteratorPrototype%` in an internal slot of the global object.
+#[inline]
+pub fn init(global: &Value) {
+    global.set_internal_slot(STRING_ITERATOR_PROTOTYPE, create(global));
+}
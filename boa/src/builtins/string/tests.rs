@@ -10,31 +10,58 @@ fn check_string_constructor_is_function() {
     assert_eq!(string_constructor.is_function(), true);
 }
 
-// #[test]
-// TODO: re-enable when getProperty() is finished;
-// fn length() {
-//     //TEST262: https://github.com/tc39/test262/blob/master/test/built-ins/String/length.js
-//     let mut engine = Executor::new();
-//     let init = r#"
-//     const a = new String(' ');
-//     const b = new String('\ud834\udf06');
-//     const c = new String(' \b ');
-//     cosnt d = new String('中文长度')
-//     "#;
-//     eprintln!("{}", forward(&mut engine, init));
-//     let a = forward(&mut engine, "a.length");
-//     assert_eq!(a, String::from("1"));
-//     let b = forward(&mut engine, "b.length");
-//     // TODO: fix this
-//     // unicode surrogate pair length should be 1
-//     // utf16/usc2 length should be 2
-//     // utf8 length should be 4
-//     //assert_eq!(b, String::from("2"));
-//     let c = forward(&mut engine, "c.length");
-//     assert_eq!(c, String::from("3"));
-//     let d = forward(&mut engine, "d.length");
-//     assert_eq!(d, String::from("4"));
-// }
+#[test]
+fn length() {
+    //TEST262: https://github.com/tc39/test262/blob/master/test/built-ins/String/length.js
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+    var a = new String(' ');
+    var b = new String('\ud834\udf06');
+    var c = new String(' \b ');
+    var d = new String('中文长度');
+    "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "a.length"), "1");
+    // A surrogate pair is two UTF-16 code units
+    assert_eq!(forward(&mut engine, "b.length"), "2");
+    assert_eq!(forward(&mut engine, "c.length"), "3");
+    assert_eq!(forward(&mut engine, "d.length"), "4");
+    assert_eq!(forward(&mut engine, "'😀'.length"), "2");
+    assert_eq!(forward(&mut engine, r"'\uD800'.length"), "1");
+}
+
+#[test]
+fn index_access() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    assert_eq!(forward(&mut engine, "'abc'[1]"), "b");
+    assert_eq!(forward(&mut engine, "new String('abc')[2]"), "c");
+    assert_eq!(forward(&mut engine, "'abc'[3]"), "undefined");
+    assert_eq!(forward(&mut engine, "'😀'[0].charCodeAt(0)"), "55357");
+}
+
+#[test]
+fn code_units() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    assert_eq!(forward(&mut engine, "'😀'.charCodeAt(0)"), "55357");
+    assert_eq!(forward(&mut engine, "'😀'.charCodeAt(1)"), "56832");
+    assert_eq!(forward(&mut engine, r"'\uD800'.charCodeAt(0)"), "55296");
+    assert_eq!(forward(&mut engine, "'a😀b'.indexOf('b')"), "3");
+    assert_eq!(forward(&mut engine, "'a😀b😀'.lastIndexOf('😀')"), "4");
+    assert_eq!(forward(&mut engine, "'中文长度'.slice(1, 3)"), "文长");
+    assert_eq!(forward(&mut engine, "'a😀b'.substring(3)"), "b");
+    assert_eq!(forward(&mut engine, "'a😀b'.substr(-1)"), "b");
+    assert_eq!(
+        forward(&mut engine, "'a😀b'.slice(1, 2).charCodeAt(0)"),
+        "55357"
+    );
+    assert_eq!(
+        forward(&mut engine, r"'ß\uD800x'.toUpperCase().length"),
+        "4"
+    );
+}
 
 #[test]
 fn concat() {
@@ -197,25 +224,29 @@ fn match_all() {
     let mut engine = Executor::new(realm);
 
     assert_eq!(
-        forward(&mut engine, "'aa'.matchAll(null).length"),
+        forward(&mut engine, "[...'aa'.matchAll(null)].length"),
         String::from("0")
     );
     assert_eq!(
-        forward(&mut engine, "'aa'.matchAll(/b/).length"),
+        forward(&mut engine, "[...'aa'.matchAll(/b/g)].length"),
         String::from("0")
     );
     assert_eq!(
-        forward(&mut engine, "'aa'.matchAll(/a/).length"),
-        String::from("1")
+        forward(&mut engine, "[...'aa'.matchAll(/a/g)].length"),
+        String::from("2")
     );
     assert_eq!(
-        forward(&mut engine, "'aa'.matchAll(/a/g).length"),
+        forward(&mut engine, "[...'aa'.matchAll('a')].length"),
         String::from("2")
     );
+    assert_eq!(
+        forward(&mut engine, "'aa'.matchAll(/a/)"),
+        String::from("Error: TypeError: matchAll must be called with a global RegExp")
+    );
 
     forward(
         &mut engine,
-        "var groupMatches = 'test1test2'.matchAll(/t(e)(st(\\d?))/g)",
+        "var groupMatches = [...'test1test2'.matchAll(/t(e)(st(\\d?))/g)]",
     );
     assert_eq!(
         forward(&mut engine, "groupMatches.length"),
@@ -238,18 +269,10 @@ fn match_all() {
         String::from("2")
     );
 
-    assert_eq!(
-        forward(
-            &mut engine,
-            "'test1test2'.matchAll(/t(e)(st(\\d?))/).length"
-        ),
-        String::from("1")
-    );
-
     let init = r#"
         var regexp = RegExp('foo[a-z]*','g');
         var str = 'table football, foosball';
-        var matches = str.matchAll(regexp);
+        var matches = [...str.matchAll(regexp)];
         "#;
     eprintln!("{}", forward(&mut engine, init));
     assert_eq!(
@@ -262,6 +285,7 @@ fn match_all() {
         String::from("foosball")
     );
     assert_eq!(forward(&mut engine, "matches[1].index"), String::from("16"));
+    assert_eq!(forward(&mut engine, "regexp.lastIndex"), String::from("0"));
 }
 
 #[test]
@@ -304,3 +328,195 @@ fn test_match() {
     );
     assert_eq!(forward(&mut engine, "result4[0]"), "B");
 }
+
+#[test]
+fn replace_substitutions() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    assert_eq!(forward(&mut engine, "'a.b.c'.replace('.', '-')"), "a-b.c");
+    assert_eq!(
+        forward(
+            &mut engine,
+            "'John Smith'.replace(/(\\w+)\\s(\\w+)/, '$2, $1')"
+        ),
+        "Smith, John"
+    );
+    assert_eq!(
+        forward(
+            &mut engine,
+            "'2020-05-17'.replace(/(?<year>\\d+)-(?<month>\\d+)-(?<day>\\d+)/, '$<day>/$<month>/$<year>')"
+        ),
+        "17/05/2020"
+    );
+    assert_eq!(forward(&mut engine, "'abc'.replace('b', '[$&]')"), "a[b]c");
+    assert_eq!(forward(&mut engine, "'abc'.replace('b', '[$`]')"), "a[a]c");
+    assert_eq!(
+        forward(&mut engine, "'abc'.replace('b', \"[$']\")"),
+        "a[c]c"
+    );
+    assert_eq!(forward(&mut engine, "'abc'.replace('b', '$$')"), "a$c");
+    // References to groups that don't exist are kept as is
+    assert_eq!(forward(&mut engine, "'abc'.replace('b', '$1')"), "a$1c");
+    assert_eq!(
+        forward(
+            &mut engine,
+            "'a1b2'.replace(/\\d/g, function(m, offset) { return '<' + offset + '>'; })"
+        ),
+        "a<1>b<3>"
+    );
+    assert_eq!(
+        forward(
+            &mut engine,
+            "'x-y'.replace(/(?<a>\\w)-(?<b>\\w)/, function() { var g = arguments[5]; return g.b + g.a; })"
+        ),
+        "yx"
+    );
+}
+
+#[test]
+fn replace_all() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    assert_eq!(
+        forward(&mut engine, "'a.b.c'.replaceAll('.', '-')"),
+        "a-b-c"
+    );
+    assert_eq!(forward(&mut engine, "'abc'.replaceAll('', '_')"), "_a_b_c_");
+    assert_eq!(forward(&mut engine, "'aaa'.replaceAll(/a/g, 'b')"), "bbb");
+    assert_eq!(
+        forward(&mut engine, "'aaa'.replaceAll(/a/, 'b')"),
+        "Error: TypeError: replaceAll must be called with a global RegExp"
+    );
+}
+
+#[test]
+fn split() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    assert_eq!(forward(&mut engine, "'a,b,c'.split(',').length"), "3");
+    assert_eq!(forward(&mut engine, "'a,b,c'.split(',')[2]"), "c");
+    assert_eq!(forward(&mut engine, "'a,b,c'.split(',', 2).length"), "2");
+    assert_eq!(forward(&mut engine, "'abc'.split('').join('-')"), "a-b-c");
+    assert_eq!(forward(&mut engine, "'abc'.split()[0]"), "abc");
+    assert_eq!(forward(&mut engine, "''.split(',').length"), "1");
+    assert_eq!(forward(&mut engine, "''.split('').length"), "0");
+    assert_eq!(
+        forward(&mut engine, "'a1b22c'.split(/\\d+/).join()"),
+        "a,b,c"
+    );
+    assert_eq!(
+        forward(&mut engine, "'a1b2c'.split(/(\\d)/).join()"),
+        "a,1,b,2,c"
+    );
+    assert_eq!(forward(&mut engine, "'abc'.split(/(?:)/).join()"), "a,b,c");
+    assert_eq!(forward(&mut engine, "''.split(/a/).length"), "1");
+}
+
+#[test]
+fn code_point_at_and_at() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    assert_eq!(forward(&mut engine, "'😀'.codePointAt(0)"), "128512");
+    assert_eq!(forward(&mut engine, "'😀'.codePointAt(1)"), "56832");
+    assert_eq!(forward(&mut engine, "'abc'.codePointAt(3)"), "undefined");
+    assert_eq!(forward(&mut engine, "'abc'.at(-1)"), "c");
+    assert_eq!(forward(&mut engine, "'abc'.at(0)"), "a");
+    assert_eq!(forward(&mut engine, "'abc'.at(3)"), "undefined");
+}
+
+#[test]
+fn search() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    assert_eq!(forward(&mut engine, "'hello world'.search(/o/)"), "4");
+    assert_eq!(forward(&mut engine, "'hello world'.search('w')"), "6");
+    assert_eq!(forward(&mut engine, "'hello'.search(/z/)"), "-1");
+    assert_eq!(forward(&mut engine, "'😀a'.search(/a/)"), "2");
+}
+
+#[test]
+fn normalize() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    assert_eq!(forward(&mut engine, r"'Å'.normalize().length"), "1");
+    assert_eq!(forward(&mut engine, r"'Å'.normalize('NFD').length"), "2");
+    assert_eq!(
+        forward(&mut engine, r"'Å'.normalize('NFD') === 'Å'"),
+        "true"
+    );
+    // Compatibility forms replace ligatures
+    assert_eq!(forward(&mut engine, r"'ﬁ'.normalize('NFKC')"), "fi");
+    assert_eq!(forward(&mut engine, r"'ﬁ'.normalize('NFC').length"), "1");
+    // Hangul syllables are composed algorithmically
+    assert_eq!(forward(&mut engine, r"'각'.normalize() === '각'"), "true");
+    // Combining marks are reordered by class
+    assert_eq!(
+        forward(&mut engine, r"'ạ́'.normalize('NFD') === 'ạ́'"),
+        "true"
+    );
+    assert_eq!(
+        forward(&mut engine, "'a'.normalize('nfc')"),
+        "Error: RangeError: The normalization form should be one of NFC, NFD, NFKC, NFKD."
+    );
+}
+
+#[test]
+fn locale_compare() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    assert_eq!(forward(&mut engine, "'a'.localeCompare('b')"), "-1");
+    assert_eq!(forward(&mut engine, "'b'.localeCompare('a')"), "1");
+    assert_eq!(forward(&mut engine, "'a'.localeCompare('a')"), "0");
+    assert_eq!(forward(&mut engine, r"'Å'.localeCompare('Å')"), "0");
+}
+
+#[test]
+fn string_iterator() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var it = 'a😀'[Symbol.iterator]();
+        var first = it.next();
+        var second = it.next();
+        var third = it.next();
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "first.value"), "a");
+    assert_eq!(forward(&mut engine, "second.value.length"), "2");
+    assert_eq!(forward(&mut engine, "third.done"), "true");
+    assert_eq!(forward(&mut engine, "[...'a😀b'].length"), "3");
+    assert_eq!(forward(&mut engine, "Array.from('a😀').length"), "2");
+}
+
+#[test]
+fn static_methods() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    assert_eq!(forward(&mut engine, "String.fromCharCode(72, 105)"), "Hi");
+    assert_eq!(forward(&mut engine, "String.fromCharCode(65601)"), "A");
+    assert_eq!(
+        forward(&mut engine, "String.fromCharCode(0xD83D, 0xDE00) === '😀'"),
+        "true"
+    );
+    assert_eq!(
+        forward(&mut engine, "String.fromCodePoint(128512) === '😀'"),
+        "true"
+    );
+    assert_eq!(forward(&mut engine, "String.fromCodePoint(97, 98)"), "ab");
+    assert_eq!(
+        forward(&mut engine, "String.fromCodePoint(1.5)"),
+        "Error: RangeError: Invalid code point 1.5"
+    );
+    assert_eq!(
+        forward(&mut engine, "String.fromCodePoint(1114112)"),
+        "Error: RangeError: Invalid code point 1114112"
+    );
+    assert_eq!(
+        forward(&mut engine, "String.raw({ raw: ['a', 'b', 'c'] }, 1, 2, 3)"),
+        "a1b2c"
+    );
+    assert_eq!(
+        forward(&mut engine, "String.raw({ raw: 'xyz' }, '-')"),
+        "x-yz"
+    );
+}
@@ -20,55 +20,318 @@ mod tests;
 
 use crate::{
     builtins::{
-        object::{
-            internal_methods_trait::ObjectInternalMethods, Object, ObjectKind, INSTANCE_PROTOTYPE,
-            PROTOTYPE,
-        },
-        value::{ResultValue, Value, ValueData},
+        object::{internal_methods_trait::ObjectInternalMethods, Object, ObjectKind, PROTOTYPE},
+        string::to_js_string,
+        value::{JsString, ResultValue, Value, ValueData},
     },
     exec::Interpreter,
 };
-use gc::{Gc, GcCell};
-use rand::random;
+use gc::{unsafe_empty_trace, Finalize, Trace};
+use rustc_hash::FxHashMap;
+use std::{
+    cell::RefCell,
+    fmt::{self, Display},
+    hash::{Hash, Hasher},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// The identifier given to the next symbol created by `Symbol()`.
+///
+/// The well-known symbols use the identifiers below `WellKnownSymbol::ALL.len()`, so they are
+/// identical in every realm.
+static NEXT_SYMBOL_ID: AtomicU64 = AtomicU64::new(WellKnownSymbol::ALL.len() as u64);
+
+thread_local! {
+    /// The `GlobalSymbolRegistry`, which maps the keys given to `Symbol.for()` to their symbols.
+    ///
+    /// The registry is shared by all the realms of an agent, which in this implementation is a
+    /// thread.
+    static GLOBAL_SYMBOL_REGISTRY: RefCell<FxHashMap<JsString, Symbol>> =
+        RefCell::new(FxHashMap::default());
+}
+
+/// A symbol value.
+///
+/// Two symbols are the same symbol if and only if they have the same identifier, the
+/// description plays no part in the identity.
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    /// The unique identifier of the symbol.
+    id: u64,
+
+    /// The `[[Description]]` of the symbol.
+    description: Option<JsString>,
+}
+
+impl Symbol {
+    /// Creates a new symbol, different from every other symbol.
+    pub fn new(description: Option<JsString>) -> Self {
+        Self {
+            id: NEXT_SYMBOL_ID.fetch_add(1, Ordering::Relaxed),
+            description,
+        }
+    }
+
+    /// Returns the `[[Description]]` of the symbol.
+    pub fn description(&self) -> Option<&JsString> {
+        self.description.as_ref()
+    }
+
+    /// The abstract operation `SymbolDescriptiveString`, `Symbol(description)`.
+    ///
+    /// More information:
+    ///  - [ECMAScript reference][spec]
+    ///
+    /// [spec]: https://tc39.es/ecma262/#sec-symboldescriptivestring
+    pub fn descriptive_string(&self) -> String {
+        format!(
+            "Symbol({})",
+            self.description
+                .as_ref()
+                .map(JsString::to_string)
+                .unwrap_or_default()
+        )
+    }
+}
+
+impl PartialEq for Symbol {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Symbol {}
+
+impl Hash for Symbol {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.descriptive_string())
+    }
+}
+
+impl Finalize for Symbol {}
+
+/// `Symbol` holds no garbage collected values, so there is nothing to trace.
+unsafe impl Trace for Symbol {
+    unsafe_empty_trace!();
+}
+
+impl From<WellKnownSymbol> for Symbol {
+    fn from(symbol: WellKnownSymbol) -> Self {
+        symbol.symbol()
+    }
+}
+
+/// The well-known symbols, which are used by the specification algorithms to look up extension
+/// points of objects.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-well-known-symbols
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WellKnownSymbol {
+    /// `Symbol.asyncIterator`
+    AsyncIterator,
+    /// `Symbol.hasInstance`
+    HasInstance,
+    /// `Symbol.isConcatSpreadable`
+    IsConcatSpreadable,
+    /// `Symbol.iterator`
+    Iterator,
+    /// `Symbol.match`
+    Match,
+    /// `Symbol.matchAll`
+    MatchAll,
+    /// `Symbol.replace`
+    Replace,
+    /// `Symbol.search`
+    Search,
+    /// `Symbol.species`
+    Species,
+    /// `Symbol.split`
+    Split,
+    /// `Symbol.toPrimitive`
+    ToPrimitive,
+    /// `Symbol.toStringTag`
+    ToStringTag,
+    /// `Symbol.unscopables`
+    Unscopables,
+}
+
+impl WellKnownSymbol {
+    /// Every well-known symbol.
+    pub const ALL: [Self; 13] = [
+        Self::AsyncIterator,
+        Self::HasInstance,
+        Self::IsConcatSpreadable,
+        Self::Iterator,
+        Self::Match,
+        Self::MatchAll,
+        Self::Replace,
+        Self::Search,
+        Self::Species,
+        Self::Split,
+        Self::ToPrimitive,
+        Self::ToStringTag,
+        Self::Unscopables,
+    ];
+
+    /// The name of the property of the `Symbol` constructor holding the symbol.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::AsyncIterator => "asyncIterator",
+            Self::HasInstance => "hasInstance",
+            Self::IsConcatSpreadable => "isConcatSpreadable",
+            Self::Iterator => "iterator",
+            Self::Match => "match",
+            Self::MatchAll => "matchAll",
+            Self::Replace => "replace",
+            Self::Search => "search",
+            Self::Species => "species",
+            Self::Split => "split",
+            Self::ToPrimitive => "toPrimitive",
+            Self::ToStringTag => "toStringTag",
+            Self::Unscopables => "unscopables",
+        }
+    }
+
+    /// Returns the symbol value, e.g. `Symbol(Symbol.iterator)`.
+    pub fn symbol(self) -> Symbol {
+        Symbol {
+            id: self as u64,
+            description: Some(JsString::from(format!("Symbol.{}", self.name()))),
+        }
+    }
+}
+
+/// The abstract operation `thisSymbolValue`, the symbol of a symbol value or a `Symbol` object.
+fn this_symbol_value(value: &Value, ctx: &mut Interpreter) -> Result<Symbol, Value> {
+    match value.data() {
+        ValueData::Symbol(ref symbol) => Ok(symbol.clone()),
+        ValueData::Object(_) => match value.get_internal_slot("SymbolData").data() {
+            ValueData::Symbol(ref symbol) => Ok(symbol.clone()),
+            _ => Err(ctx.construct_type_error("'this' is not a Symbol")),
+        },
+        _ => Err(ctx.construct_type_error("'this' is not a Symbol")),
+    }
+}
+
+/// Returns the value of the accessor properties of a `Symbol` object, which are computed from
+/// its `[[SymbolData]]`.
+pub(crate) fn get_own_property(object: &Object, field: &str) -> Option<Value> {
+    match (
+        field,
+        object.internal_slots.get("SymbolData").map(Value::data),
+    ) {
+        ("description", Some(ValueData::Symbol(ref symbol))) => Some(
+            symbol
+                .description()
+                .cloned()
+                .map_or_else(Value::undefined, Value::from),
+        ),
+        _ => None,
+    }
+}
+
+/// Creates a `Symbol` object wrapping `symbol`, as done by `ToObject`.
+pub(crate) fn to_object(symbol: &Value, ctx: &Interpreter) -> Value {
+    let proto = ctx
+        .realm
+        .intrinsics
+        .get_field_slice("Symbol")
+        .get_field_slice(PROTOTYPE);
+    let symbol_obj = Value::new_object_from_prototype(proto, ObjectKind::Symbol);
+    symbol_obj.set_internal_slot("SymbolData", symbol.clone());
+    symbol_obj
+}
 
 /// Creates Symbol instances.
 ///
-/// Symbol instances are ordinary objects that inherit properties from the Symbol prototype object.
-/// Symbol instances have a `[[SymbolData]]` internal slot.
-/// The `[[SymbolData]]` internal slot is the Symbol value represented by this Symbol object.
+/// Symbol values are primitives, so unlike objects they are compared by identity and they have
+/// no properties of their own. Property accesses go through a `Symbol` object, whose
+/// `[[SymbolData]]` internal slot is the Symbol value represented by this Symbol object.
 ///
 /// More information:
 /// - [ECMAScript reference][spec]
 ///
 /// [spec]: https://tc39.es/ecma262/#sec-symbol-description
 pub fn call_symbol(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
-    // From an implementation and specificaition perspective Symbols are similar to Objects.
-    // They have internal slots to hold the SymbolData and Description, they also have methods and a prototype.
-    // So we start by creating an Object
-    // TODO: Set prototype to Symbol.prototype (by changing to Object::create(), use interpreter to get Symbol.prototype)
-    let mut sym_instance = Object::default();
-    sym_instance.kind = ObjectKind::Symbol;
-
     // Set description which should either be undefined or a string
-    let desc_string = match args.get(0) {
-        Some(value) => Value::from(value.to_string()),
-        None => Value::undefined(),
+    let description = match args.get(0) {
+        Some(value) if !value.is_undefined() => Some(to_js_string(value, ctx)?),
+        _ => None,
     };
 
-    sym_instance.set_internal_slot("Description", desc_string);
-    sym_instance.set_internal_slot("SymbolData", Value::from(random::<i32>()));
+    Ok(Value::symbol(Symbol::new(description)))
+}
 
-    // Set __proto__ internal slot
-    let proto = ctx
-        .realm
-        .global_obj
-        .get_field_slice("Symbol")
-        .get_field_slice(PROTOTYPE);
-    sym_instance.set_internal_slot(INSTANCE_PROTOTYPE, proto);
+/// `new Symbol()` throws, symbols can't be constructed.
+pub fn construct_symbol(_: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    ctx.throw_type_error("Symbol is not a constructor")
+}
+
+/// Returns the symbol of the global symbol registry with the given key, a new symbol is registered
+/// if there is none.
+pub(crate) fn registered_symbol(key: JsString) -> Symbol {
+    GLOBAL_SYMBOL_REGISTRY.with(|registry| {
+        registry
+            .borrow_mut()
+            .entry(key.clone())
+            .or_insert_with(|| Symbol::new(Some(key)))
+            .clone()
+    })
+}
 
-    Ok(Value(Gc::new(ValueData::Symbol(Box::new(GcCell::new(
-        sym_instance,
-    ))))))
+/// Returns the key of the symbol in the global symbol registry, if it is registered.
+pub(crate) fn registry_key(symbol: &Symbol) -> Option<JsString> {
+    GLOBAL_SYMBOL_REGISTRY.with(|registry| {
+        registry
+            .borrow()
+            .iter()
+            .find(|(_, registered)| *registered == symbol)
+            .map(|(key, _)| key.clone())
+    })
+}
+
+/// `Symbol.for( key )`
+///
+/// This method searches for existing symbols in the global symbol registry with the given key
+/// and returns it if found. Otherwise a new symbol gets created in the registry with this key.
+///
+/// More information:
+/// - [MDN documentation][mdn]
+/// - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-symbol.for
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Symbol/for
+pub fn for_(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let key = to_js_string(args.get(0).unwrap_or(&Value::undefined()), ctx)?;
+    Ok(Value::symbol(registered_symbol(key)))
+}
+
+/// `Symbol.keyFor( sym )`
+///
+/// This method retrieves a shared symbol key from the global symbol registry for the given
+/// symbol, or returns `undefined` if the symbol isn't in the registry.
+///
+/// More information:
+/// - [MDN documentation][mdn]
+/// - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-symbol.keyfor
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Symbol/keyFor
+pub fn key_for(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let symbol = match args.get(0).map(Value::data) {
+        Some(ValueData::Symbol(ref symbol)) => symbol.clone(),
+        _ => return ctx.throw_type_error("Symbol.keyFor: argument is not a symbol"),
+    };
+    Ok(registry_key(&symbol).map_or_else(Value::undefined, Value::from))
 }
 
 /// `Symbol.prototype.toString()`
@@ -81,10 +344,23 @@ pub fn call_symbol(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> Resu
 ///
 /// [spec]: https://tc39.es/ecma262/#sec-symbol.prototype.tostring
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Symbol/toString
-pub fn to_string(this: &mut Value, _: &[Value], _: &mut Interpreter) -> ResultValue {
-    let s: Value = this.get_internal_slot("Description");
-    let full_string = format!(r#"Symbol({})"#, s.to_string());
-    Ok(Value::from(full_string))
+pub fn to_string(this: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let symbol = this_symbol_value(this, ctx)?;
+    Ok(Value::from(symbol.descriptive_string()))
+}
+
+/// `Symbol.prototype.valueOf()`
+///
+/// This method returns the primitive value of a `Symbol` object.
+///
+/// More information:
+/// - [MDN documentation][mdn]
+/// - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-symbol.prototype.valueof
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Symbol/valueOf
+pub fn value_of(this: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    Ok(Value::symbol(this_symbol_value(this, ctx)?))
 }
 
 /// Create a new `Symbol` object.
@@ -92,7 +368,26 @@ pub fn create(global: &Value) -> Value {
     // Create prototype object
     let prototype = Value::new_object(Some(global));
     make_builtin_fn!(to_string, named "toString", of prototype);
-    make_constructor_fn!(call_symbol, call_symbol, global, prototype)
+    make_builtin_fn!(value_of, named "valueOf", of prototype);
+
+    let to_primitive = make_builtin_fn!(value_of, named "[Symbol.toPrimitive]", with length 1);
+    prototype.set_field(
+        Value::symbol(WellKnownSymbol::ToPrimitive.into()),
+        to_primitive,
+    );
+    prototype.set_field(
+        Value::symbol(WellKnownSymbol::ToStringTag.into()),
+        Value::from("Symbol"),
+    );
+
+    let symbol = make_constructor_fn!(construct_symbol, call_symbol, global, prototype);
+    symbol.set_field_slice("length", Value::from(0));
+    make_builtin_fn!(for_, named "for", with length 1, of symbol);
+    make_builtin_fn!(key_for, named "keyFor", with length 1, of symbol);
+    for well_known in WellKnownSymbol::ALL.iter() {
+        symbol.set_field_slice(well_known.name(), Value::symbol(well_known.symbol()));
+    }
+    symbol
 }
 
 /// Initialise the `Symbol` object on the global object.
@@ -33,3 +33,128 @@ fn print_symbol_expect_description() {
     let sym = forward_val(&mut engine, "sym.toString()").unwrap();
     assert_eq!(sym.to_string(), "Symbol(Hello)");
 }
+
+#[test]
+fn symbol_identity() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var a = Symbol("same");
+        var b = Symbol("same");
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "a === a"), "true");
+    assert_eq!(forward(&mut engine, "a === b"), "false");
+    assert_eq!(forward(&mut engine, "a.description"), "same");
+    assert_eq!(forward(&mut engine, "Symbol().description"), "undefined");
+}
+
+#[test]
+fn symbol_keyed_properties() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var a = Symbol("key");
+        var b = Symbol("key");
+        var o = {};
+        o[a] = 1;
+        o[b] = 2;
+        o["Symbol(key)"] = 3;
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "o[a]"), "1");
+    assert_eq!(forward(&mut engine, "o[b]"), "2");
+    assert_eq!(forward(&mut engine, "o['Symbol(key)']"), "3");
+    assert_eq!(forward(&mut engine, "Symbol('x') in o"), "false");
+}
+
+#[test]
+fn global_symbol_registry() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    assert_eq!(
+        forward(&mut engine, "Symbol.for('app') === Symbol.for('app')"),
+        "true"
+    );
+    assert_eq!(
+        forward(&mut engine, "Symbol.for('app') === Symbol('app')"),
+        "false"
+    );
+    assert_eq!(
+        forward(&mut engine, "Symbol.keyFor(Symbol.for('app'))"),
+        "app"
+    );
+    assert_eq!(
+        forward(&mut engine, "Symbol.keyFor(Symbol('app'))"),
+        "undefined"
+    );
+    assert_eq!(
+        forward(&mut engine, "Symbol.keyFor(Symbol.iterator)"),
+        "undefined"
+    );
+}
+
+#[test]
+fn well_known_symbols() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    assert_eq!(
+        forward(&mut engine, "Symbol.iterator.toString()"),
+        "Symbol(Symbol.iterator)"
+    );
+    assert_eq!(
+        forward(&mut engine, "Symbol.iterator === Symbol.iterator"),
+        "true"
+    );
+    assert_eq!(
+        forward(&mut engine, "Symbol.species === Symbol.iterator"),
+        "false"
+    );
+    assert_eq!(
+        forward(&mut engine, "Symbol.asyncIterator.description"),
+        "Symbol.asyncIterator"
+    );
+}
+
+#[test]
+fn well_known_symbols_are_consulted() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var tagged = {};
+        tagged[Symbol.toStringTag] = "Tagged";
+        var money = {};
+        money[Symbol.toPrimitive] = function(hint) {
+            if (hint === "string") { return "forty-two"; }
+            return 42;
+        };
+        var range = {};
+        var state = { i: 0 };
+        range[Symbol.iterator] = function() {
+            state.i = 0;
+            return {
+                next: function() {
+                    state.i = state.i + 1;
+                    return { value: state.i, done: state.i > 3 };
+                }
+            };
+        };
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "tagged.toString()"), "[object Tagged]");
+    assert_eq!(forward(&mut engine, "Math.toString()"), "[object Math]");
+    assert_eq!(forward(&mut engine, "String(money)"), "forty-two");
+    assert_eq!(forward(&mut engine, "money == 42"), "true");
+    assert_eq!(forward(&mut engine, "[...range].length"), "3");
+    assert_eq!(forward(&mut engine, "[0, ...range][3]"), "3");
+}
+
+#[test]
+fn symbol_is_not_a_constructor() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    assert_eq!(
+        forward(&mut engine, "new Symbol()"),
+        "Error: TypeError: Symbol is not a constructor"
+    );
+}
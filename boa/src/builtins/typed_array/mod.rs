@@ -0,0 +1,1723 @@
+//! This module implements the typed array objects, such as `Int8Array` or `Float64Array`, and
+//! their shared `%TypedArray%` intrinsic.
+//!
+//! A typed array describes an array-like view of an underlying `ArrayBuffer`. Its integer
+//! indexed properties don't live in the object's property map, they are read from and written to
+//! the buffer's bytes directly, which makes typed arrays integer-indexed exotic objects.
+//!
+//! Boa doesn't have a `BigInt` type yet, so the elements of `BigInt64Array` and `BigUint64Array`
+//! are exposed as Numbers.
+//!
+//! More information:
+//!  - [ECMAScript reference][spec]
+//!  - [MDN documentation][mdn]
+//!
+//! [spec]: https://tc39.es/ecma262/#sec-typedarray-objects
+//! [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/TypedArray
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    builtins::{
+        array::merge_sort,
+        array_buffer::{self, relative_index, to_index, DataBlock},
+        function::NativeFunctionData,
+        object::{
+            InternalState, Object, ObjectInternalMethods, ObjectKind, INSTANCE_PROTOTYPE, PROTOTYPE,
+        },
+        property::Property,
+        symbol::WellKnownSymbol,
+        value::{same_value_zero, ResultValue, Value, ValueData},
+    },
+    exec::Interpreter,
+};
+use std::{cmp::Ordering, convert::TryInto};
+
+/// The element types a typed array can hold.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#table-the-typedarray-constructors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypedArrayKind {
+    Int8,
+    Uint8,
+    Uint8Clamped,
+    Int16,
+    Uint16,
+    Int32,
+    Uint32,
+    Float32,
+    Float64,
+    BigInt64,
+    BigUint64,
+}
+
+impl InternalState for TypedArrayKind {}
+
+impl TypedArrayKind {
+    /// All the typed array kinds, in the order their constructors are installed.
+    pub const ALL: [Self; 11] = [
+        Self::Int8,
+        Self::Uint8,
+        Self::Uint8Clamped,
+        Self::Int16,
+        Self::Uint16,
+        Self::Int32,
+        Self::Uint32,
+        Self::Float32,
+        Self::Float64,
+        Self::BigInt64,
+        Self::BigUint64,
+    ];
+
+    /// The name of the constructor for this kind of typed array.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Int8 => "Int8Array",
+            Self::Uint8 => "Uint8Array",
+            Self::Uint8Clamped => "Uint8ClampedArray",
+            Self::Int16 => "Int16Array",
+            Self::Uint16 => "Uint16Array",
+            Self::Int32 => "Int32Array",
+            Self::Uint32 => "Uint32Array",
+            Self::Float32 => "Float32Array",
+            Self::Float64 => "Float64Array",
+            Self::BigInt64 => "BigInt64Array",
+            Self::BigUint64 => "BigUint64Array",
+        }
+    }
+
+    /// The size in bytes of a single element.
+    pub fn element_size(self) -> usize {
+        match self {
+            Self::Int8 | Self::Uint8 | Self::Uint8Clamped => 1,
+            Self::Int16 | Self::Uint16 => 2,
+            Self::Int32 | Self::Uint32 | Self::Float32 => 4,
+            Self::Float64 | Self::BigInt64 | Self::BigUint64 => 8,
+        }
+    }
+
+    /// Decodes an element from the first `element_size()` bytes of `bytes`.
+    ///
+    /// More information:
+    ///  - [ECMAScript reference][spec]
+    ///
+    /// [spec]: https://tc39.es/ecma262/#sec-rawbytestonumeric
+    pub(crate) fn read(self, bytes: &[u8], little_endian: bool) -> f64 {
+        macro_rules! read {
+            ($t:ty) => {{
+                let raw = bytes[..std::mem::size_of::<$t>()]
+                    .try_into()
+                    .expect("not enough bytes for element");
+                if little_endian {
+                    <$t>::from_le_bytes(raw)
+                } else {
+                    <$t>::from_be_bytes(raw)
+                }
+            }};
+        }
+
+        match self {
+            Self::Int8 => f64::from(read!(i8)),
+            Self::Uint8 | Self::Uint8Clamped => f64::from(read!(u8)),
+            Self::Int16 => f64::from(read!(i16)),
+            Self::Uint16 => f64::from(read!(u16)),
+            Self::Int32 => f64::from(read!(i32)),
+            Self::Uint32 => f64::from(read!(u32)),
+            Self::Float32 => f64::from(read!(f32)),
+            Self::Float64 => read!(f64),
+            Self::BigInt64 => read!(i64) as f64,
+            Self::BigUint64 => read!(u64) as f64,
+        }
+    }
+
+    /// Encodes `value` into the first `element_size()` bytes of `bytes`, applying the conversion
+    /// for this element type.
+    ///
+    /// More information:
+    ///  - [ECMAScript reference][spec]
+    ///
+    /// [spec]: https://tc39.es/ecma262/#sec-numerictorawbytes
+    pub(crate) fn write(self, bytes: &mut [u8], value: f64, little_endian: bool) {
+        macro_rules! write {
+            ($v:expr) => {{
+                let v = $v;
+                let raw = if little_endian {
+                    v.to_le_bytes()
+                } else {
+                    v.to_be_bytes()
+                };
+                bytes[..raw.len()].copy_from_slice(&raw);
+            }};
+        }
+
+        match self {
+            Self::Int8 => write!(modulo(value, 8) as u8 as i8),
+            Self::Uint8 => write!(modulo(value, 8) as u8),
+            Self::Uint8Clamped => write!(to_uint8_clamp(value)),
+            Self::Int16 => write!(modulo(value, 16) as u16 as i16),
+            Self::Uint16 => write!(modulo(value, 16) as u16),
+            Self::Int32 => write!(modulo(value, 32) as u32 as i32),
+            Self::Uint32 => write!(modulo(value, 32) as u32),
+            Self::Float32 => write!(value as f32),
+            Self::Float64 => write!(value),
+            Self::BigInt64 => write!(modulo(value, 64) as i64),
+            Self::BigUint64 => write!(modulo(value, 64)),
+        }
+    }
+}
+
+/// Truncates `value` and wraps it modulo `2^bits`, as done by `ToInt8`, `ToUint16` and friends.
+///
+/// Non finite values become `0`.
+pub(crate) fn modulo(value: f64, bits: i32) -> u64 {
+    if !value.is_finite() {
+        return 0;
+    }
+    let modulus = 2f64.powi(bits);
+    let wrapped = value.trunc() % modulus;
+    let wrapped = if wrapped < 0.0 {
+        wrapped + modulus
+    } else {
+        wrapped
+    };
+    // `wrapped` may round up to `2^64` for the widest types.
+    if wrapped >= 18_446_744_073_709_551_616.0 {
+        0
+    } else {
+        wrapped as u64
+    }
+}
+
+/// The abstract operation `ToUint8Clamp`.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-touint8clamp
+#[allow(clippy::float_cmp)]
+fn to_uint8_clamp(value: f64) -> u8 {
+    if value.is_nan() || value <= 0.0 {
+        return 0;
+    }
+    if value >= 255.0 {
+        return 255;
+    }
+    let f = value.floor();
+    if f + 0.5 < value {
+        return f as u8 + 1;
+    }
+    if value < f + 0.5 {
+        return f as u8;
+    }
+    // Ties round to even.
+    if f as u8 % 2 == 0 {
+        f as u8
+    } else {
+        f as u8 + 1
+    }
+}
+
+/// Whether typed arrays use little endian byte order, which is the platform's byte order.
+const LITTLE_ENDIAN: bool = cfg!(target_endian = "little");
+
+/// The internal representation of a typed array.
+#[derive(Debug)]
+pub(crate) struct TypedArray {
+    /// The `[[TypedArrayName]]` internal slot.
+    pub(crate) kind: TypedArrayKind,
+
+    /// The data block of the `[[ViewedArrayBuffer]]`.
+    pub(crate) data: DataBlock,
+
+    /// The `[[ByteOffset]]` internal slot.
+    pub(crate) byte_offset: usize,
+
+    /// The `[[ArrayLength]]` internal slot, `None` for arrays that track the length of a
+    /// resizable buffer.
+    pub(crate) array_length: Option<usize>,
+}
+
+impl InternalState for TypedArray {}
+
+impl TypedArray {
+    /// Returns the current length of the typed array, which is `0` if the array is out of bounds
+    /// of its (resized) buffer.
+    ///
+    /// More information:
+    ///  - [ECMAScript reference][spec]
+    ///
+    /// [spec]: https://tc39.es/ecma262/#sec-typedarraylength
+    fn length(&self) -> usize {
+        let buffer_len = self.data.len();
+        let size = self.kind.element_size();
+        match self.array_length {
+            Some(len) if self.byte_offset + len * size <= buffer_len => len,
+            Some(_) => 0,
+            None if self.byte_offset <= buffer_len => (buffer_len - self.byte_offset) / size,
+            None => 0,
+        }
+    }
+
+    /// Reads the element at `index`, if it is in bounds.
+    fn get(&self, index: usize) -> Option<f64> {
+        if index >= self.length() {
+            return None;
+        }
+        let start = self.byte_offset + index * self.kind.element_size();
+        Some(self.kind.read(&self.data.borrow()[start..], LITTLE_ENDIAN))
+    }
+
+    /// Writes the element at `index`, if it is in bounds.
+    fn set(&self, index: usize, value: f64) {
+        if index >= self.length() {
+            return;
+        }
+        let start = self.byte_offset + index * self.kind.element_size();
+        self.kind
+            .write(&mut self.data.borrow_mut()[start..], value, LITTLE_ENDIAN);
+    }
+
+    /// Reads all the elements of the typed array.
+    fn values(&self) -> Vec<f64> {
+        (0..self.length())
+            .map(|i| self.get(i).expect("index in bounds"))
+            .collect()
+    }
+}
+
+/// Runs `f` on the typed array internal state of `value`, if it has one.
+fn with_typed_array<R, F>(value: &Value, f: F) -> Option<R>
+where
+    F: FnOnce(&TypedArray) -> R,
+{
+    match value.data() {
+        ValueData::Object(ref obj) => obj
+            .borrow()
+            .state
+            .as_ref()
+            .and_then(|state| state.downcast_ref::<TypedArray>())
+            .map(f),
+        _ => None,
+    }
+}
+
+/// Snapshot of the internal slots of a typed array, so no borrow is held while calling back into
+/// JavaScript.
+#[derive(Debug, Clone)]
+struct Snapshot {
+    kind: TypedArrayKind,
+    data: DataBlock,
+    byte_offset: usize,
+    length: usize,
+}
+
+/// The abstract operation `ValidateTypedArray`, returning a snapshot of the typed array.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-validatetypedarray
+fn validate(this: &Value, ctx: &mut Interpreter) -> Result<Snapshot, Value> {
+    with_typed_array(this, |array| Snapshot {
+        kind: array.kind,
+        data: array.data.clone(),
+        byte_offset: array.byte_offset,
+        length: array.length(),
+    })
+    .ok_or_else(|| ctx.construct_type_error("this is not a typed array"))
+}
+
+/// Returns the data block, byte offset and byte length visible through a typed array.
+pub(crate) fn viewed_bytes(value: &Value) -> Option<(DataBlock, usize, usize)> {
+    with_typed_array(value, |array| {
+        (
+            array.data.clone(),
+            array.byte_offset,
+            array.length() * array.kind.element_size(),
+        )
+    })
+}
+
+/// Returns the element kind of a typed array value.
+///
+/// Returns `None` if the value is not a typed array.
+pub fn kind_of(value: &Value) -> Option<TypedArrayKind> {
+    with_typed_array(value, |array| array.kind)
+}
+
+/// Converts a property key to a numeric index if it is a canonical numeric string.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-canonicalnumericindexstring
+pub(crate) fn canonical_numeric_index(field: &str) -> Option<f64> {
+    if field == "-0" {
+        return Some(-0.0);
+    }
+    // Fast path for the common case of a non-numeric property name.
+    match field.as_bytes().first() {
+        Some(b'0'..=b'9') | Some(b'-') | Some(b'I') | Some(b'N') => {}
+        _ => return None,
+    }
+    let number = field.parse::<f64>().ok()?;
+    if Value::from(number).to_string() == field {
+        Some(number)
+    } else {
+        None
+    }
+}
+
+/// Converts a numeric index to a valid integer index for an array of the given length.
+#[allow(clippy::float_cmp)]
+fn valid_integer_index(index: f64, length: usize) -> Option<usize> {
+    if index.is_nan() || index.trunc() != index || index.is_sign_negative() {
+        return None;
+    }
+    if index >= length as f64 {
+        return None;
+    }
+    Some(index as usize)
+}
+
+/// Returns the typed array internal state of `object`, if it has one.
+fn typed_array_of(object: &Object) -> Option<&TypedArray> {
+    object.state.as_ref()?.downcast_ref::<TypedArray>()
+}
+
+/// Returns the typed array internal state of `object` and the numeric index `key` denotes, if
+/// `object` is a typed array and `key` is a canonical numeric string.
+fn numeric_key<'a>(object: &'a Object, key: &Value) -> Option<(&'a TypedArray, f64)> {
+    let array = typed_array_of(object)?;
+    match key.data() {
+        ValueData::String(ref field) => Some((
+            array,
+            canonical_numeric_index(&field.to_std_string_lossy())?,
+        )),
+        _ => None,
+    }
+}
+
+/// Reads the integer indexed element of a typed array named by `field`.
+///
+/// Returns `None` if `field` is not a canonical numeric string, in which case the property is
+/// an ordinary one. An integer index that is out of bounds yields `undefined` without
+/// consulting the prototype chain.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-integer-indexed-exotic-objects-get-p-receiver
+pub(crate) fn get_own_property(object: &Object, field: &str) -> Option<Value> {
+    let array = typed_array_of(object)?;
+    let index = canonical_numeric_index(field)?;
+    Some(
+        valid_integer_index(index, array.length())
+            .and_then(|index| array.get(index))
+            .map_or_else(Value::undefined, Value::from),
+    )
+}
+
+/// The `[[GetOwnProperty]]` of a typed array for the integer indexed element named by `key`.
+///
+/// Returns `None` if `key` is not a canonical numeric string, and an empty descriptor if the
+/// index is out of bounds.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-integer-indexed-exotic-objects-getownproperty-p
+pub(crate) fn get_own_element(object: &Object, key: &Value) -> Option<Property> {
+    let (array, index) = numeric_key(object, key)?;
+    Some(
+        valid_integer_index(index, array.length())
+            .and_then(|index| array.get(index))
+            .map(|value| {
+                Property::new()
+                    .value(Value::from(value))
+                    .writable(true)
+                    .enumerable(true)
+                    .configurable(true)
+            })
+            .unwrap_or_default(),
+    )
+}
+
+/// The `[[HasProperty]]` of a typed array for the integer indexed element named by `key`.
+///
+/// Returns `None` if `key` is not a canonical numeric string.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-integer-indexed-exotic-objects-hasproperty-p
+pub(crate) fn has_element(object: &Object, key: &Value) -> Option<bool> {
+    let (array, index) = numeric_key(object, key)?;
+    Some(valid_integer_index(index, array.length()).is_some())
+}
+
+/// The `[[DefineOwnProperty]]` of a typed array for the integer indexed element named by `key`.
+///
+/// Elements are always writable, enumerable and configurable data properties, so any other
+/// descriptor is rejected. Returns `None` if `key` is not a canonical numeric string.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-integer-indexed-exotic-objects-defineownproperty-p-desc
+pub(crate) fn define_element(object: &Object, key: &Value, desc: &Property) -> Option<bool> {
+    let (array, index) = numeric_key(object, key)?;
+    let index = match valid_integer_index(index, array.length()) {
+        Some(index) => index,
+        None => return Some(false),
+    };
+    if desc.configurable == Some(false)
+        || desc.enumerable == Some(false)
+        || desc.is_accessor_descriptor()
+        || desc.writable == Some(false)
+    {
+        return Some(false);
+    }
+    if let Some(ref value) = desc.value {
+        array.set(index, value.to_number());
+    }
+    Some(true)
+}
+
+/// The `[[Delete]]` of a typed array for the integer indexed element named by `key`: elements
+/// in bounds can't be deleted.
+///
+/// Returns `None` if `key` is not a canonical numeric string.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-integer-indexed-exotic-objects-delete-p
+pub(crate) fn delete_element(object: &Object, key: &Value) -> Option<bool> {
+    has_element(object, key).map(|has| !has)
+}
+
+/// The number of integer indexed elements of `object`, if it is a typed array, which precede
+/// its other keys in `[[OwnPropertyKeys]]`.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-integer-indexed-exotic-objects-ownpropertykeys
+pub(crate) fn element_count(object: &Object) -> Option<usize> {
+    typed_array_of(object).map(TypedArray::length)
+}
+
+/// Sets an integer indexed element of a typed array.
+///
+/// Returns `false` if `field` is not a canonical numeric string, in which case the property is
+/// an ordinary one. Writes to out of bounds indices are ignored.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-integer-indexed-exotic-objects-set-p-v-receiver
+pub(crate) fn set_element(object: &Object, field: &str, value: &Value) -> bool {
+    let array = match typed_array_of(object) {
+        Some(array) => array,
+        None => return false,
+    };
+    let index = match canonical_numeric_index(field) {
+        Some(index) => index,
+        None => return false,
+    };
+    let number = value.to_number();
+    if let Some(index) = valid_integer_index(index, array.length()) {
+        array.set(index, number);
+    }
+    true
+}
+
+/// Initializes `obj` as a typed array viewing `buffer`.
+fn initialize(
+    obj: &Value,
+    kind: TypedArrayKind,
+    buffer: Value,
+    byte_offset: usize,
+    array_length: Option<usize>,
+) {
+    let data = array_buffer::data_block(&buffer).expect("buffer must be an ArrayBuffer");
+    obj.set_kind(ObjectKind::TypedArray);
+    obj.set_internal_slot("ViewedArrayBuffer", buffer);
+    obj.set_internal_state(TypedArray {
+        kind,
+        data,
+        byte_offset,
+        array_length,
+    });
+}
+
+/// Allocates a new typed array of `kind` with a fresh buffer of `length` elements.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-typedarraycreate
+fn allocate(kind: TypedArrayKind, length: usize, ctx: &mut Interpreter) -> ResultValue {
+    let proto = ctx
+        .realm
+        .intrinsics
+        .get_field_slice(kind.name())
+        .get_field_slice(PROTOTYPE);
+    let obj = Value::new_object_from_prototype(proto, ObjectKind::TypedArray);
+    allocate_buffer(&obj, kind, length, ctx)?;
+    Ok(obj)
+}
+
+/// Initializes `obj` as a typed array of `kind` viewing a fresh buffer of `length` elements.
+fn allocate_buffer(
+    obj: &Value,
+    kind: TypedArrayKind,
+    length: usize,
+    ctx: &mut Interpreter,
+) -> Result<(), Value> {
+    let bytes =
+        array_buffer::create_byte_data_block(length.saturating_mul(kind.element_size()), ctx)?;
+    let buffer = array_buffer::new_array_buffer(&ctx.realm.intrinsics, bytes);
+    initialize(obj, kind, buffer, 0, Some(length));
+    Ok(())
+}
+
+/// Creates a new typed array of `kind` holding `values`.
+fn create_from_values(kind: TypedArrayKind, values: &[f64], ctx: &mut Interpreter) -> ResultValue {
+    let obj = allocate(kind, values.len(), ctx)?;
+    write_values(&obj, 0, values, ctx)?;
+    Ok(obj)
+}
+
+/// Writes `values` to the typed array `obj` from the element `offset`.
+fn write_values(
+    obj: &Value,
+    offset: usize,
+    values: &[f64],
+    ctx: &mut Interpreter,
+) -> Result<(), Value> {
+    with_typed_array(obj, |array| {
+        for (i, value) in values.iter().enumerate() {
+            ctx.check_limits(i)?;
+            array.set(offset + i, *value);
+        }
+        Ok(())
+    })
+    .unwrap_or(Ok(()))
+}
+
+/// Collects the numeric values of an array-like object.
+fn array_like_values(source: &Value, ctx: &mut Interpreter) -> Result<Vec<f64>, Value> {
+    if let Some(values) = with_typed_array(source, TypedArray::values) {
+        return Ok(values);
+    }
+    let len = source
+        .get_field_slice("length")
+        .to_integer_or_infinity()
+        .max(0.0) as usize;
+    let mut values = Vec::new();
+    for i in 0..len {
+        ctx.check_limits(i)?;
+        values.push(source.get_field_slice(&i.to_string()).to_number());
+    }
+    Ok(values)
+}
+
+/// The shared `[[Construct]]` of the typed array constructors.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-typedarray
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/TypedArray/TypedArray
+fn construct(
+    kind: TypedArrayKind,
+    this: &mut Value,
+    args: &[Value],
+    ctx: &mut Interpreter,
+) -> ResultValue {
+    let first = args.get(0).cloned().unwrap_or_else(Value::undefined);
+    let size = kind.element_size();
+
+    if array_buffer::data_block(&first).is_some() {
+        // new TypedArray(buffer [, byteOffset [, length]])
+        let offset = to_index(args.get(1).unwrap_or(&Value::undefined()), ctx)?;
+        if offset % size != 0 {
+            return ctx.throw_range_error(format!(
+                "start offset of {} should be a multiple of {}",
+                kind.name(),
+                size
+            ));
+        }
+        let buffer_len = first.get_field_slice("byteLength").to_integer() as usize;
+        let resizable = first.get_field_slice("resizable").is_true();
+        let array_length = match args.get(2) {
+            Some(length) if !length.is_undefined() => {
+                let length = to_index(length, ctx)?;
+                if offset + length * size > buffer_len {
+                    return ctx.throw_range_error(format!("Invalid {} length", kind.name()));
+                }
+                Some(length)
+            }
+            _ if resizable => {
+                if offset > buffer_len {
+                    return ctx.throw_range_error(format!("Invalid {} offset", kind.name()));
+                }
+                None
+            }
+            _ => {
+                if buffer_len % size != 0 {
+                    return ctx.throw_range_error(format!(
+                        "byte length of {} should be a multiple of {}",
+                        kind.name(),
+                        size
+                    ));
+                }
+                if offset > buffer_len {
+                    return ctx.throw_range_error(format!("Invalid {} offset", kind.name()));
+                }
+                Some((buffer_len - offset) / size)
+            }
+        };
+        initialize(this, kind, first, offset, array_length);
+        return Ok(this.clone());
+    }
+
+    if first.is_object() {
+        // new TypedArray(typedArray) and new TypedArray(arrayLike)
+        let values = array_like_values(&first, ctx)?;
+        allocate_buffer(this, kind, values.len(), ctx)?;
+        write_values(this, 0, &values, ctx)?;
+    } else {
+        // new TypedArray([length])
+        let length = to_index(&first, ctx)?;
+        allocate_buffer(this, kind, length, ctx)?;
+    }
+    Ok(this.clone())
+}
+
+/// Generates the `[[Construct]]` and `[[Call]]` functions of a typed array constructor.
+macro_rules! typed_array_constructor {
+    ($construct:ident, $call:ident, $kind:expr) => {
+        fn $construct(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+            construct($kind, this, args, ctx)
+        }
+
+        fn $call(_: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue {
+            ctx.throw_type_error(format!("Constructor {} requires 'new'", $kind.name()))
+        }
+    };
+}
+
+typed_array_constructor!(make_int8_array, call_int8_array, TypedArrayKind::Int8);
+typed_array_constructor!(make_uint8_array, call_uint8_array, TypedArrayKind::Uint8);
+typed_array_constructor!(
+    make_uint8_clamped_array,
+    call_uint8_clamped_array,
+    TypedArrayKind::Uint8Clamped
+);
+typed_array_constructor!(make_int16_array, call_int16_array, TypedArrayKind::Int16);
+typed_array_constructor!(make_uint16_array, call_uint16_array, TypedArrayKind::Uint16);
+typed_array_constructor!(make_int32_array, call_int32_array, TypedArrayKind::Int32);
+typed_array_constructor!(make_uint32_array, call_uint32_array, TypedArrayKind::Uint32);
+typed_array_constructor!(
+    make_float32_array,
+    call_float32_array,
+    TypedArrayKind::Float32
+);
+typed_array_constructor!(
+    make_float64_array,
+    call_float64_array,
+    TypedArrayKind::Float64
+);
+typed_array_constructor!(
+    make_big_int64_array,
+    call_big_int64_array,
+    TypedArrayKind::BigInt64
+);
+typed_array_constructor!(
+    make_big_uint64_array,
+    call_big_uint64_array,
+    TypedArrayKind::BigUint64
+);
+
+/// The `%TypedArray%` intrinsic, which is abstract and can't be constructed directly.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-%typedarray%
+fn make_typed_array(_: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    ctx.throw_type_error("Abstract class TypedArray not directly constructable")
+}
+
+/// Returns the kind of typed array constructed by the constructor `this`.
+fn constructor_kind(this: &Value, ctx: &mut Interpreter) -> Result<TypedArrayKind, Value> {
+    this.get_internal_state()
+        .and_then(|state| state.downcast_ref::<TypedArrayKind>().copied())
+        .ok_or_else(|| ctx.construct_type_error("this is not a typed array constructor"))
+}
+
+/// Returns the callback argument of an iteration method, or throws a `TypeError`.
+fn callback_arg(args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    match args.get(0) {
+        Some(callback) if callback.is_function() => Ok(callback.clone()),
+        _ => ctx.throw_type_error("callback is not a function"),
+    }
+}
+
+/// Calls `callback` with `(element, index, array)` for an iteration method.
+fn call_callback(
+    callback: &Value,
+    this_arg: &Value,
+    element: f64,
+    index: usize,
+    array: &Value,
+    ctx: &mut Interpreter,
+) -> ResultValue {
+    ctx.call(
+        callback,
+        &mut this_arg.clone(),
+        &[Value::from(element), Value::from(index), array.clone()],
+    )
+}
+
+/// `%TypedArray%.from( source[, mapFn[, thisArg]] )`
+///
+/// The `from()` method creates a new typed array from an array-like or typed array object.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-%typedarray%.from
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/TypedArray/from
+pub fn from(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let kind = constructor_kind(this, ctx)?;
+    let source = args.get(0).cloned().unwrap_or_else(Value::undefined);
+    if source.is_null_or_undefined() {
+        return ctx.throw_type_error("source is null or undefined");
+    }
+    let map_fn = match args.get(1) {
+        Some(f) if !f.is_undefined() => {
+            if !f.is_function() {
+                return ctx.throw_type_error("mapFn is not a function");
+            }
+            Some(f.clone())
+        }
+        _ => None,
+    };
+    let this_arg = args.get(2).cloned().unwrap_or_else(Value::undefined);
+
+    let source = ctx.to_object(&source)?;
+    let len = source
+        .get_field_slice("length")
+        .to_integer_or_infinity()
+        .max(0.0) as usize;
+    let mut values = Vec::with_capacity(len);
+    for i in 0..len {
+        ctx.check_limits(i)?;
+        let element = source.get_field_slice(&i.to_string());
+        let element = match map_fn {
+            Some(ref f) => ctx.call(f, &mut this_arg.clone(), &[element, Value::from(i)])?,
+            None => element,
+        };
+        values.push(element.to_number());
+    }
+    create_from_values(kind, &values, ctx)
+}
+
+/// `%TypedArray%.of( ...items )`
+///
+/// The `of()` method creates a new typed array from a variable number of arguments.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-%typedarray%.of
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/TypedArray/of
+pub fn of(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let kind = constructor_kind(this, ctx)?;
+    let values: Vec<f64> = args.iter().map(|arg| arg.to_number()).collect();
+    create_from_values(kind, &values, ctx)
+}
+
+/// `%TypedArray%.prototype.at( index )`
+///
+/// The `at()` method takes an integer value and returns the element at that index, counting
+/// back from the last element for negative integers.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-%typedarray%.prototype.at
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/TypedArray/at
+pub fn at(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let array = validate(this, ctx)?;
+    let relative = args
+        .get(0)
+        .unwrap_or(&Value::undefined())
+        .to_integer_or_infinity();
+    let index = if relative >= 0.0 {
+        relative
+    } else {
+        array.length as f64 + relative
+    };
+    Ok(valid_integer_index(index, array.length)
+        .and_then(|i| with_typed_array(this, |a| a.get(i)).flatten())
+        .map_or_else(Value::undefined, Value::from))
+}
+
+/// `%TypedArray%.prototype.copyWithin( target, start[, end] )`
+///
+/// The `copyWithin()` method copies a sequence of elements within the typed array to the
+/// position starting at `target`.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-%typedarray%.prototype.copywithin
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/TypedArray/copyWithin
+pub fn copy_within(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let array = validate(this, ctx)?;
+    let len = array.length;
+    let to = relative_index(args.get(0), len, 0);
+    let from = relative_index(args.get(1), len, 0);
+    let fin = relative_index(args.get(2), len, len);
+    if from < fin && to < len {
+        let count = (fin - from).min(len - to);
+        let size = array.kind.element_size();
+        let base = array.byte_offset;
+        array.data.borrow_mut().copy_within(
+            base + from * size..base + (from + count) * size,
+            base + to * size,
+        );
+    }
+    Ok(this.clone())
+}
+
+/// `%TypedArray%.prototype.every( callback[, thisArg] )`
+///
+/// The `every()` method tests whether all elements in the typed array pass the test
+/// implemented by the provided function.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-%typedarray%.prototype.every
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/TypedArray/every
+pub fn every(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let array = validate(this, ctx)?;
+    let callback = callback_arg(args, ctx)?;
+    let this_arg = args.get(1).cloned().unwrap_or_else(Value::undefined);
+    for i in 0..array.length {
+        ctx.check_limits(i)?;
+        let element = with_typed_array(this, |a| a.get(i))
+            .flatten()
+            .unwrap_or(std::f64::NAN);
+        if !call_callback(&callback, &this_arg, element, i, this, ctx)?.is_true() {
+            return Ok(Value::from(false));
+        }
+    }
+    Ok(Value::from(true))
+}
+
+/// `%TypedArray%.prototype.fill( value[, start[, end]] )`
+///
+/// The `fill()` method fills all the elements of the typed array from a start index to an end
+/// index with a static value.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-%typedarray%.prototype.fill
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/TypedArray/fill
+pub fn fill(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let array = validate(this, ctx)?;
+    let value = args.get(0).unwrap_or(&Value::undefined()).to_number();
+    let start = relative_index(args.get(1), array.length, 0);
+    let fin = relative_index(args.get(2), array.length, array.length);
+    with_typed_array(this, |a| -> Result<(), Value> {
+        for i in start..fin {
+            ctx.check_limits(i)?;
+            a.set(i, value);
+        }
+        Ok(())
+    })
+    .unwrap_or(Ok(()))?;
+    Ok(this.clone())
+}
+
+/// `%TypedArray%.prototype.filter( callback[, thisArg] )`
+///
+/// The `filter()` method creates a new typed array with all elements that pass the test
+/// implemented by the provided function.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-%typedarray%.prototype.filter
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/TypedArray/filter
+pub fn filter(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let array = validate(this, ctx)?;
+    let callback = callback_arg(args, ctx)?;
+    let this_arg = args.get(1).cloned().unwrap_or_else(Value::undefined);
+    let mut kept = Vec::new();
+    for i in 0..array.length {
+        ctx.check_limits(i)?;
+        let element = with_typed_array(this, |a| a.get(i))
+            .flatten()
+            .unwrap_or(std::f64::NAN);
+        if call_callback(&callback, &this_arg, element, i, this, ctx)?.is_true() {
+            kept.push(element);
+        }
+    }
+    create_from_values(array.kind, &kept, ctx)
+}
+
+/// Shared implementation of `find`, `findIndex`, `findLast` and `findLastIndex`.
+///
+/// Returns the index and value of the first element, in the given direction, for which the
+/// predicate returns a truthy value.
+fn find_from(
+    this: &Value,
+    args: &[Value],
+    reverse: bool,
+    ctx: &mut Interpreter,
+) -> Result<Option<(usize, f64)>, Value> {
+    let array = validate(this, ctx)?;
+    let predicate = callback_arg(args, ctx)?;
+    let this_arg = args.get(1).cloned().unwrap_or_else(Value::undefined);
+    let indices: Box<dyn Iterator<Item = usize>> = if reverse {
+        Box::new((0..array.length).rev())
+    } else {
+        Box::new(0..array.length)
+    };
+    for i in indices {
+        ctx.check_limits(i)?;
+        let element = with_typed_array(this, |a| a.get(i))
+            .flatten()
+            .unwrap_or(std::f64::NAN);
+        if call_callback(&predicate, &this_arg, element, i, this, ctx)?.is_true() {
+            return Ok(Some((i, element)));
+        }
+    }
+    Ok(None)
+}
+
+/// `%TypedArray%.prototype.find( predicate[, thisArg] )`
+///
+/// The `find()` method returns the value of the first element in the typed array that satisfies
+/// the provided testing function, or `undefined`.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-%typedarray%.prototype.find
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/TypedArray/find
+pub fn find(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    Ok(find_from(this, args, false, ctx)?.map_or_else(Value::undefined, |(_, v)| Value::from(v)))
+}
+
+/// `%TypedArray%.prototype.findIndex( predicate[, thisArg] )`
+///
+/// The `findIndex()` method returns the index of the first element in the typed array that
+/// satisfies the provided testing function, or `-1`.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-%typedarray%.prototype.findindex
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/TypedArray/findIndex
+pub fn find_index(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    Ok(find_from(this, args, false, ctx)?.map_or(Value::from(-1), |(i, _)| Value::from(i)))
+}
+
+/// `%TypedArray%.prototype.findLast( predicate[, thisArg] )`
+///
+/// The `findLast()` method iterates the typed array in reverse order and returns the value of
+/// the first element that satisfies the provided testing function, or `undefined`.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-%typedarray%.prototype.findlast
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/TypedArray/findLast
+pub fn find_last(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    Ok(find_from(this, args, true, ctx)?.map_or_else(Value::undefined, |(_, v)| Value::from(v)))
+}
+
+/// `%TypedArray%.prototype.findLastIndex( predicate[, thisArg] )`
+///
+/// The `findLastIndex()` method iterates the typed array in reverse order and returns the index
+/// of the first element that satisfies the provided testing function, or `-1`.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-%typedarray%.prototype.findlastindex
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/TypedArray/findLastIndex
+pub fn find_last_index(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    Ok(find_from(this, args, true, ctx)?.map_or(Value::from(-1), |(i, _)| Value::from(i)))
+}
+
+/// `%TypedArray%.prototype.forEach( callback[, thisArg] )`
+///
+/// The `forEach()` method executes a provided function once per typed array element.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-%typedarray%.prototype.foreach
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/TypedArray/forEach
+pub fn for_each(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let array = validate(this, ctx)?;
+    let callback = callback_arg(args, ctx)?;
+    let this_arg = args.get(1).cloned().unwrap_or_else(Value::undefined);
+    for i in 0..array.length {
+        ctx.check_limits(i)?;
+        let element = with_typed_array(this, |a| a.get(i))
+            .flatten()
+            .unwrap_or(std::f64::NAN);
+        call_callback(&callback, &this_arg, element, i, this, ctx)?;
+    }
+    Ok(Value::undefined())
+}
+
+/// `%TypedArray%.prototype.includes( searchElement[, fromIndex] )`
+///
+/// The `includes()` method determines whether a typed array includes a certain element, using
+/// the SameValueZero comparison.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-%typedarray%.prototype.includes
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/TypedArray/includes
+pub fn includes(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let array = validate(this, ctx)?;
+    let search = args.get(0).cloned().unwrap_or_else(Value::undefined);
+    if !search.is_number() {
+        return Ok(Value::from(false));
+    }
+    let start = relative_index(args.get(1), array.length, 0);
+    let values = with_typed_array(this, TypedArray::values).unwrap_or_default();
+    let found = values
+        .iter()
+        .skip(start)
+        .any(|v| same_value_zero(&Value::from(*v), &search));
+    Ok(Value::from(found))
+}
+
+/// `%TypedArray%.prototype.indexOf( searchElement[, fromIndex] )`
+///
+/// The `indexOf()` method returns the first index at which a given element can be found in the
+/// typed array, or `-1` if it is not present.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-%typedarray%.prototype.indexof
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/TypedArray/indexOf
+#[allow(clippy::float_cmp)]
+pub fn index_of(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let array = validate(this, ctx)?;
+    let search = args.get(0).cloned().unwrap_or_else(Value::undefined);
+    if !search.is_number() {
+        return Ok(Value::from(-1));
+    }
+    let search = search.to_number();
+    let start = relative_index(args.get(1), array.length, 0);
+    let values = with_typed_array(this, TypedArray::values).unwrap_or_default();
+    Ok(values
+        .iter()
+        .enumerate()
+        .skip(start)
+        .find(|(_, v)| **v == search)
+        .map_or(Value::from(-1), |(i, _)| Value::from(i)))
+}
+
+/// `%TypedArray%.prototype.join( [separator] )`
+///
+/// The `join()` method joins all elements of a typed array into a string.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-%typedarray%.prototype.join
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/TypedArray/join
+pub fn join(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    validate(this, ctx)?;
+    let separator = match args.get(0) {
+        Some(sep) if !sep.is_undefined() => ctx.to_string(sep)?.to_string(),
+        _ => String::from(","),
+    };
+    let values = with_typed_array(this, TypedArray::values).unwrap_or_default();
+    let joined = values
+        .into_iter()
+        // Adding `+0` turns `-0` into `+0`, which `ToString` formats as "0".
+        .map(|v| Value::from(v + 0.0).to_string())
+        .collect::<Vec<_>>()
+        .join(&separator);
+    ctx.reserve_string(joined.len())?;
+    Ok(Value::from(joined))
+}
+
+/// `%TypedArray%.prototype.lastIndexOf( searchElement[, fromIndex] )`
+///
+/// The `lastIndexOf()` method returns the last index at which a given element can be found in
+/// the typed array, or `-1` if it is not present.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-%typedarray%.prototype.lastindexof
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/TypedArray/lastIndexOf
+#[allow(clippy::float_cmp)]
+pub fn last_index_of(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let array = validate(this, ctx)?;
+    let search = args.get(0).cloned().unwrap_or_else(Value::undefined);
+    if !search.is_number() || array.length == 0 {
+        return Ok(Value::from(-1));
+    }
+    let search = search.to_number();
+    let from = match args.get(1) {
+        Some(from) => {
+            let from = from.to_integer_or_infinity();
+            if from < 0.0 {
+                array.length as f64 + from
+            } else {
+                from.min(array.length as f64 - 1.0)
+            }
+        }
+        None => array.length as f64 - 1.0,
+    };
+    if from < 0.0 {
+        return Ok(Value::from(-1));
+    }
+    let values = with_typed_array(this, TypedArray::values).unwrap_or_default();
+    Ok(values
+        .iter()
+        .enumerate()
+        .take(from as usize + 1)
+        .rev()
+        .find(|(_, v)| **v == search)
+        .map_or(Value::from(-1), |(i, _)| Value::from(i)))
+}
+
+/// `%TypedArray%.prototype.map( callback[, thisArg] )`
+///
+/// The `map()` method creates a new typed array with the results of calling a provided function
+/// on every element in this typed array.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-%typedarray%.prototype.map
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/TypedArray/map
+pub fn map(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let array = validate(this, ctx)?;
+    let callback = callback_arg(args, ctx)?;
+    let this_arg = args.get(1).cloned().unwrap_or_else(Value::undefined);
+    let mut mapped = Vec::with_capacity(array.length);
+    for i in 0..array.length {
+        ctx.check_limits(i)?;
+        let element = with_typed_array(this, |a| a.get(i))
+            .flatten()
+            .unwrap_or(std::f64::NAN);
+        mapped.push(call_callback(&callback, &this_arg, element, i, this, ctx)?.to_number());
+    }
+    create_from_values(array.kind, &mapped, ctx)
+}
+
+/// Shared implementation of `reduce` and `reduceRight`.
+fn reduce_from(this: &Value, args: &[Value], reverse: bool, ctx: &mut Interpreter) -> ResultValue {
+    let array = validate(this, ctx)?;
+    let callback = callback_arg(args, ctx)?;
+    let mut indices: Vec<usize> = (0..array.length).collect();
+    if reverse {
+        indices.reverse();
+    }
+    let mut indices = indices.into_iter();
+    let mut accumulator = match args.get(1) {
+        Some(initial) => initial.clone(),
+        None => match indices.next() {
+            Some(i) => Value::from(
+                with_typed_array(this, |a| a.get(i))
+                    .flatten()
+                    .unwrap_or(std::f64::NAN),
+            ),
+            None => return ctx.throw_type_error("Reduce of empty array with no initial value"),
+        },
+    };
+    for i in indices {
+        ctx.check_limits(i)?;
+        let element = with_typed_array(this, |a| a.get(i))
+            .flatten()
+            .unwrap_or(std::f64::NAN);
+        accumulator = ctx.call(
+            &callback,
+            &mut Value::undefined(),
+            &[
+                accumulator,
+                Value::from(element),
+                Value::from(i),
+                this.clone(),
+            ],
+        )?;
+    }
+    Ok(accumulator)
+}
+
+/// `%TypedArray%.prototype.reduce( callback[, initialValue] )`
+///
+/// The `reduce()` method applies a function against an accumulator and each value of the typed
+/// array (from left-to-right) to reduce it to a single value.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-%typedarray%.prototype.reduce
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/TypedArray/reduce
+pub fn reduce(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    reduce_from(this, args, false, ctx)
+}
+
+/// `%TypedArray%.prototype.reduceRight( callback[, initialValue] )`
+///
+/// The `reduceRight()` method applies a function against an accumulator and each value of the
+/// typed array (from right-to-left) to reduce it to a single value.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-%typedarray%.prototype.reduceright
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/TypedArray/reduceRight
+pub fn reduce_right(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    reduce_from(this, args, true, ctx)
+}
+
+/// `%TypedArray%.prototype.reverse()`
+///
+/// The `reverse()` method reverses a typed array in place.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-%typedarray%.prototype.reverse
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/TypedArray/reverse
+pub fn reverse(this: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    validate(this, ctx)?;
+    let mut values = with_typed_array(this, TypedArray::values).unwrap_or_default();
+    values.reverse();
+    write_values(this, 0, &values, ctx)?;
+    Ok(this.clone())
+}
+
+/// `%TypedArray%.prototype.set( source[, offset] )`
+///
+/// The `set()` method stores multiple values in the typed array, reading input values from an
+/// array-like or typed array object.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-%typedarray%.prototype.set
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/TypedArray/set
+pub fn set(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let array = validate(this, ctx)?;
+    let offset = args
+        .get(1)
+        .unwrap_or(&Value::undefined())
+        .to_integer_or_infinity();
+    if offset < 0.0 {
+        return ctx.throw_range_error("offset is out of bounds");
+    }
+    let source = ctx.to_object(args.get(0).unwrap_or(&Value::undefined()))?;
+    // Read every source value before writing, the source may share our buffer.
+    let values = array_like_values(&source, ctx)?;
+    if offset + values.len() as f64 > array.length as f64 {
+        return ctx.throw_range_error("offset is out of bounds");
+    }
+    write_values(this, offset as usize, &values, ctx)?;
+    Ok(Value::undefined())
+}
+
+/// `%TypedArray%.prototype.slice( [start[, end]] )`
+///
+/// The `slice()` method returns a new typed array, with a copy of a portion of this typed
+/// array.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-%typedarray%.prototype.slice
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/TypedArray/slice
+pub fn slice(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let array = validate(this, ctx)?;
+    let start = relative_index(args.get(0), array.length, 0);
+    let fin = relative_index(args.get(1), array.length, array.length);
+    let values = with_typed_array(this, TypedArray::values).unwrap_or_default();
+    let values = if start < fin {
+        &values[start..fin]
+    } else {
+        &[]
+    };
+    create_from_values(array.kind, values, ctx)
+}
+
+/// `%TypedArray%.prototype.some( callback[, thisArg] )`
+///
+/// The `some()` method tests whether some element in the typed array passes the test
+/// implemented by the provided function.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-%typedarray%.prototype.some
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/TypedArray/some
+pub fn some(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let array = validate(this, ctx)?;
+    let callback = callback_arg(args, ctx)?;
+    let this_arg = args.get(1).cloned().unwrap_or_else(Value::undefined);
+    for i in 0..array.length {
+        ctx.check_limits(i)?;
+        let element = with_typed_array(this, |a| a.get(i))
+            .flatten()
+            .unwrap_or(std::f64::NAN);
+        if call_callback(&callback, &this_arg, element, i, this, ctx)?.is_true() {
+            return Ok(Value::from(true));
+        }
+    }
+    Ok(Value::from(false))
+}
+
+/// Numeric comparison used by `sort` when no comparator is given.
+///
+/// `NaN`s are sorted to the end and `-0` sorts before `+0`.
+fn compare_numbers(a: f64, b: f64) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => a
+            .partial_cmp(&b)
+            .expect("numbers are comparable")
+            .then_with(|| b.is_sign_negative().cmp(&a.is_sign_negative())),
+    }
+}
+
+/// `%TypedArray%.prototype.sort( [compareFn] )`
+///
+/// The `sort()` method sorts the elements of a typed array numerically in place.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-%typedarray%.prototype.sort
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/TypedArray/sort
+pub fn sort(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let compare_fn = match args.get(0) {
+        Some(f) if !f.is_undefined() => {
+            if !f.is_function() {
+                return ctx.throw_type_error("The comparison function must be a function");
+            }
+            Some(f.clone())
+        }
+        _ => None,
+    };
+    validate(this, ctx)?;
+    let values = with_typed_array(this, TypedArray::values).unwrap_or_default();
+    let sorted = merge_sort(values, |a, b| match compare_fn {
+        Some(ref f) => {
+            let v = ctx
+                .call(
+                    f,
+                    &mut Value::undefined(),
+                    &[Value::from(*a), Value::from(*b)],
+                )?
+                .to_number();
+            Ok(if v < 0.0 {
+                Ordering::Less
+            } else if v > 0.0 {
+                Ordering::Greater
+            } else {
+                Ordering::Equal
+            })
+        }
+        None => Ok(compare_numbers(*a, *b)),
+    })?;
+    write_values(this, 0, &sorted, ctx)?;
+    Ok(this.clone())
+}
+
+/// `%TypedArray%.prototype.subarray( [begin[, end]] )`
+///
+/// The `subarray()` method returns a new typed array on the same `ArrayBuffer` store and with
+/// the same element types as this typed array.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-%typedarray%.prototype.subarray
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/TypedArray/subarray
+pub fn subarray(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let array = validate(this, ctx)?;
+    let begin = relative_index(args.get(0), array.length, 0);
+    let fin = relative_index(args.get(1), array.length, array.length);
+    let length = fin.saturating_sub(begin);
+    let buffer = this.get_internal_slot("ViewedArrayBuffer");
+
+    let proto = ctx
+        .realm
+        .intrinsics
+        .get_field_slice(array.kind.name())
+        .get_field_slice(PROTOTYPE);
+    let obj = Value::new_object_from_prototype(proto, ObjectKind::TypedArray);
+    initialize(
+        &obj,
+        array.kind,
+        buffer,
+        array.byte_offset + begin * array.kind.element_size(),
+        Some(length),
+    );
+    Ok(obj)
+}
+
+/// `%TypedArray%.prototype.toString()`
+///
+/// The `toString()` method returns a string representing the typed array and its elements.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-%typedarray%.prototype.tostring
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/TypedArray/toString
+pub fn to_string(this: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    join(this, &[], ctx)
+}
+
+/// `get %TypedArray%.prototype.buffer`
+///
+/// This accessor returns the `ArrayBuffer` viewed by the typed array.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-get-%typedarray%.prototype.buffer
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/TypedArray/buffer
+pub fn buffer(this: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    validate(this, ctx)?;
+    Ok(this.get_internal_slot("ViewedArrayBuffer"))
+}
+
+/// `get %TypedArray%.prototype.byteLength`
+///
+/// This accessor returns the length in bytes of the typed array.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-get-%typedarray%.prototype.bytelength
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/TypedArray/byteLength
+pub fn byte_length(this: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let array = validate(this, ctx)?;
+    Ok(Value::from(array.length * array.kind.element_size()))
+}
+
+/// `get %TypedArray%.prototype.byteOffset`
+///
+/// This accessor returns the offset in bytes of the typed array from the start of its buffer.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-get-%typedarray%.prototype.byteoffset
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/TypedArray/byteOffset
+pub fn byte_offset(this: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let array = validate(this, ctx)?;
+    Ok(Value::from(if array.length == 0 {
+        0
+    } else {
+        array.byte_offset
+    }))
+}
+
+/// `get %TypedArray%.prototype.length`
+///
+/// This accessor returns the number of elements of the typed array.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-get-%typedarray%.prototype.length
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/TypedArray/length
+pub fn length(this: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let array = validate(this, ctx)?;
+    Ok(Value::from(array.length))
+}
+
+/// Installs the accessor property `name` with the native `getter` and no setter on `prototype`.
+fn make_getter(prototype: &Value, name: &str, getter: NativeFunctionData) {
+    let getter = make_builtin_fn!(getter, named format!("get {}", name), with length 0);
+    prototype.set_property_slice(
+        name,
+        Property::new()
+            .get(getter)
+            .set(Value::undefined())
+            .enumerable(false)
+            .configurable(true),
+    );
+}
+
+/// Create the `%TypedArray%` intrinsic object.
+pub fn create(global: &Value) -> Value {
+    let prototype = Value::new_object(Some(global));
+
+    make_getter(&prototype, "buffer", buffer);
+    make_getter(&prototype, "byteLength", byte_length);
+    make_getter(&prototype, "byteOffset", byte_offset);
+    make_getter(&prototype, "length", length);
+
+    make_builtin_fn!(at, named "at", with length 1, of prototype);
+    make_builtin_fn!(copy_within, named "copyWithin", with length 2, of prototype);
+    make_builtin_fn!(every, named "every", with length 1, of prototype);
+    make_builtin_fn!(fill, named "fill", with length 1, of prototype);
+    make_builtin_fn!(filter, named "filter", with length 1, of prototype);
+    make_builtin_fn!(find, named "find", with length 1, of prototype);
+    make_builtin_fn!(find_index, named "findIndex", with length 1, of prototype);
+    make_builtin_fn!(find_last, named "findLast", with length 1, of prototype);
+    make_builtin_fn!(find_last_index, named "findLastIndex", with length 1, of prototype);
+    make_builtin_fn!(for_each, named "forEach", with length 1, of prototype);
+    make_builtin_fn!(includes, named "includes", with length 1, of prototype);
+    make_builtin_fn!(index_of, named "indexOf", with length 1, of prototype);
+    make_builtin_fn!(join, named "join", with length 1, of prototype);
+    make_builtin_fn!(last_index_of, named "lastIndexOf", with length 1, of prototype);
+    make_builtin_fn!(map, named "map", with length 1, of prototype);
+    make_builtin_fn!(reduce, named "reduce", with length 1, of prototype);
+    make_builtin_fn!(reduce_right, named "reduceRight", with length 1, of prototype);
+    make_builtin_fn!(reverse, named "reverse", of prototype);
+    make_builtin_fn!(set, named "set", with length 1, of prototype);
+    make_builtin_fn!(slice, named "slice", with length 2, of prototype);
+    make_builtin_fn!(some, named "some", with length 1, of prototype);
+    make_builtin_fn!(sort, named "sort", with length 1, of prototype);
+    make_builtin_fn!(subarray, named "subarray", with length 2, of prototype);
+    make_builtin_fn!(to_string, named "toString", of prototype);
+
+    let typed_array = make_constructor_fn!(make_typed_array, make_typed_array, global, prototype);
+
+    make_builtin_fn!(from, named "from", with length 1, of typed_array);
+    make_builtin_fn!(of, named "of", of typed_array);
+
+    typed_array
+}
+
+/// Creates the constructor of one kind of typed array, inheriting from `%TypedArray%`.
+fn create_constructor(
+    global: &Value,
+    typed_array: &Value,
+    kind: TypedArrayKind,
+    construct_fn: fn(&mut Value, &[Value], &mut Interpreter) -> ResultValue,
+    call_fn: fn(&mut Value, &[Value], &mut Interpreter) -> ResultValue,
+) -> Value {
+    let prototype = Value::new_object(Some(global));
+    prototype.set_internal_slot(INSTANCE_PROTOTYPE, typed_array.get_field_slice(PROTOTYPE));
+    prototype.set_field_slice("BYTES_PER_ELEMENT", Value::from(kind.element_size()));
+    prototype.set_field(
+        Value::symbol(WellKnownSymbol::ToStringTag.into()),
+        Value::from(kind.name()),
+    );
+
+    let constructor = make_constructor_fn!(construct_fn, call_fn, global, prototype);
+    constructor.set_internal_slot(INSTANCE_PROTOTYPE, typed_array.clone());
+    constructor.set_internal_state(kind);
+    constructor.set_field_slice("name", Value::from(kind.name()));
+    constructor.set_field_slice("length", Value::from(3));
+    constructor.set_field_slice("BYTES_PER_ELEMENT", Value::from(kind.element_size()));
+    constructor
+}
+
+/// Initialise the typed array constructors on the global object.
+#[inline]
+pub fn init(global: &Value) {
+    let typed_array = create(global);
+
+    let constructors: [(TypedArrayKind, NativeConstructor, NativeConstructor); 11] = [
+        (TypedArrayKind::Int8, make_int8_array, call_int8_array),
+        (TypedArrayKind::Uint8, make_uint8_array, call_uint8_array),
+        (
+            TypedArrayKind::Uint8Clamped,
+            make_uint8_clamped_array,
+            call_uint8_clamped_array,
+        ),
+        (TypedArrayKind::Int16, make_int16_array, call_int16_array),
+        (TypedArrayKind::Uint16, make_uint16_array, call_uint16_array),
+        (TypedArrayKind::Int32, make_int32_array, call_int32_array),
+        (TypedArrayKind::Uint32, make_uint32_array, call_uint32_array),
+        (
+            TypedArrayKind::Float32,
+            make_float32_array,
+            call_float32_array,
+        ),
+        (
+            TypedArrayKind::Float64,
+            make_float64_array,
+            call_float64_array,
+        ),
+        (
+            TypedArrayKind::BigInt64,
+            make_big_int64_array,
+            call_big_int64_array,
+        ),
+        (
+            TypedArrayKind::BigUint64,
+            make_big_uint64_array,
+            call_big_uint64_array,
+        ),
+    ];
+
+    for (kind, construct_fn, call_fn) in constructors.iter() {
+        global.set_field_slice(
+            kind.name(),
+            create_constructor(global, &typed_array, *kind, *construct_fn, *call_fn),
+        );
+    }
+}
+
+/// The signature of the native constructor functions.
+type NativeConstructor = fn(&mut Value, &[Value], &mut Interpreter) -> ResultValue;
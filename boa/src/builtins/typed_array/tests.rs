@@ -0,0 +1,291 @@
+use crate::{exec::Executor, forward, realm::Realm};
+
+#[test]
+fn construct() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var empty = new Int8Array();
+        var sized = new Float64Array(3);
+        var fromArray = new Int16Array([1, 2, 3]);
+        var copy = new Uint8Array(fromArray);
+        var buffer = new ArrayBuffer(16);
+        var view = new Int32Array(buffer, 4, 2);
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "empty.length"), "0");
+    assert_eq!(forward(&mut engine, "sized.length"), "3");
+    assert_eq!(forward(&mut engine, "sized.byteLength"), "24");
+    assert_eq!(forward(&mut engine, "fromArray.join()"), "1,2,3");
+    assert_eq!(forward(&mut engine, "copy.join()"), "1,2,3");
+    assert_eq!(forward(&mut engine, "view.byteOffset"), "4");
+    assert_eq!(forward(&mut engine, "view.buffer === buffer"), "true");
+    assert_eq!(forward(&mut engine, "Int32Array.BYTES_PER_ELEMENT"), "4");
+    assert_eq!(forward(&mut engine, "view.BYTES_PER_ELEMENT"), "4");
+    assert_eq!(
+        forward(&mut engine, "new Int32Array(buffer, 1)"),
+        "Error: RangeError: start offset of Int32Array should be a multiple of 4"
+    );
+    assert_eq!(
+        forward(&mut engine, "new Int32Array(buffer, 8, 3)"),
+        "Error: RangeError: Invalid Int32Array length"
+    );
+    assert_eq!(
+        forward(&mut engine, "Int8Array(2)"),
+        "Error: TypeError: Constructor Int8Array requires 'new'"
+    );
+}
+
+#[test]
+fn element_conversions() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var i8 = new Int8Array(1);
+        var u8 = new Uint8Array(1);
+        var clamped = new Uint8ClampedArray(4);
+        var u32 = new Uint32Array(1);
+        var f32 = new Float32Array(1);
+        "#;
+    forward(&mut engine, init);
+    forward(&mut engine, "i8[0] = 200;");
+    assert_eq!(forward(&mut engine, "i8[0]"), "-56");
+    forward(&mut engine, "u8[0] = -1;");
+    assert_eq!(forward(&mut engine, "u8[0]"), "255");
+    forward(
+        &mut engine,
+        "clamped[0] = 300; clamped[1] = -5; clamped[2] = 2.5; clamped[3] = 3.5;",
+    );
+    assert_eq!(forward(&mut engine, "clamped.join()"), "255,0,2,4");
+    forward(&mut engine, "u32[0] = -1;");
+    assert_eq!(forward(&mut engine, "u32[0]"), "4294967295");
+    forward(&mut engine, "f32[0] = 0.5;");
+    assert_eq!(forward(&mut engine, "f32[0]"), "0.5");
+}
+
+#[test]
+fn integer_indexed_exotic() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    forward(&mut engine, "var a = new Int8Array(2);");
+    forward(&mut engine, "a[5] = 1; a['-0'] = 1; a.foo = 'bar';");
+    assert_eq!(forward(&mut engine, "a[5]"), "undefined");
+    assert_eq!(forward(&mut engine, "a.length"), "2");
+    assert_eq!(forward(&mut engine, "a.foo"), "bar");
+    assert_eq!(forward(&mut engine, "a.join()"), "0,0");
+}
+
+#[test]
+fn shared_buffer() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var bytes = new Uint8Array(4);
+        var words = new Uint16Array(bytes.buffer);
+        var sub = bytes.subarray(1, 3);
+        "#;
+    forward(&mut engine, init);
+    forward(&mut engine, "sub[0] = 1;");
+    assert_eq!(forward(&mut engine, "bytes.join()"), "0,1,0,0");
+    forward(&mut engine, "words[1] = 0x0102;");
+    assert_eq!(forward(&mut engine, "bytes[2] + bytes[3]"), "3");
+    assert_eq!(forward(&mut engine, "sub.length"), "2");
+}
+
+#[test]
+fn from_and_of() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    assert_eq!(
+        forward(&mut engine, "Int16Array.of(1, 2, 3).join()"),
+        "1,2,3"
+    );
+    assert_eq!(
+        forward(
+            &mut engine,
+            "Float32Array.from([1, 2], function (x) { return x * 2; }).join()"
+        ),
+        "2,4"
+    );
+    assert_eq!(
+        forward(
+            &mut engine,
+            "Object.getPrototypeOf(Int8Array) === Object.getPrototypeOf(Uint8Array)"
+        ),
+        "true"
+    );
+}
+
+#[test]
+fn prototype_methods() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    forward(&mut engine, "var a = new Int32Array([5, 1, 4, 2, 3]);");
+    assert_eq!(forward(&mut engine, "a.at(-1)"), "3");
+    assert_eq!(forward(&mut engine, "a.indexOf(4)"), "2");
+    assert_eq!(forward(&mut engine, "a.lastIndexOf(1)"), "1");
+    assert_eq!(forward(&mut engine, "a.includes(2)"), "true");
+    assert_eq!(
+        forward(&mut engine, "a.map(function (x) { return x * 10; }).join()"),
+        "50,10,40,20,30"
+    );
+    assert_eq!(
+        forward(
+            &mut engine,
+            "a.filter(function (x) { return x > 2; }).join()"
+        ),
+        "5,4,3"
+    );
+    assert_eq!(
+        forward(
+            &mut engine,
+            "a.reduce(function (acc, x) { return acc + x; })"
+        ),
+        "15"
+    );
+    assert_eq!(
+        forward(&mut engine, "a.findLast(function (x) { return x > 3; })"),
+        "4"
+    );
+    assert_eq!(forward(&mut engine, "a.slice(1, 3).join()"), "1,4");
+    assert_eq!(forward(&mut engine, "a.sort().join()"), "1,2,3,4,5");
+    assert_eq!(
+        forward(
+            &mut engine,
+            "a.sort(function (x, y) { return y - x; }).join()"
+        ),
+        "5,4,3,2,1"
+    );
+    assert_eq!(forward(&mut engine, "a.reverse().join()"), "1,2,3,4,5");
+    assert_eq!(
+        forward(&mut engine, "a.copyWithin(0, 3).join()"),
+        "4,5,3,4,5"
+    );
+    assert_eq!(forward(&mut engine, "a.fill(0, 1, 3).join()"), "4,0,0,4,5");
+    assert_eq!(forward(&mut engine, "a.toString()"), "4,0,0,4,5");
+}
+
+#[test]
+fn sort_numbers() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    assert_eq!(
+        forward(
+            &mut engine,
+            "new Float64Array([NaN, 1, -0, 0, -1]).sort().join()"
+        ),
+        "-1,0,0,1,NaN"
+    );
+}
+
+#[test]
+fn element_internal_methods() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    forward(&mut engine, "var a = new Uint8Array([7, 8]);");
+    assert_eq!(forward(&mut engine, "'1' in a"), "true");
+    assert_eq!(forward(&mut engine, "'2' in a"), "false");
+    assert_eq!(forward(&mut engine, "'-0' in a"), "false");
+    assert_eq!(forward(&mut engine, "Object.keys(a).join()"), "0,1");
+    assert_eq!(
+        forward(&mut engine, "JSON.stringify(a)"),
+        r#"{"0":7,"1":8}"#
+    );
+    forward(
+        &mut engine,
+        "var d = Object.getOwnPropertyDescriptor(a, '0');",
+    );
+    assert_eq!(
+        forward(
+            &mut engine,
+            "[d.value, d.writable, d.enumerable, d.configurable].join()"
+        ),
+        "7,true,true,true"
+    );
+    assert_eq!(
+        forward(&mut engine, "Object.getOwnPropertyDescriptor(a, '2')"),
+        "undefined"
+    );
+    assert_eq!(
+        forward(
+            &mut engine,
+            "Object.defineProperty(a, '1', { value: 300 })[1]"
+        ),
+        "44"
+    );
+    assert_eq!(
+        forward(
+            &mut engine,
+            "Object.defineProperty(a, '0', { value: 1, writable: false })"
+        ),
+        "Error: TypeError: Cannot define property 0"
+    );
+    assert_eq!(forward(&mut engine, "delete a[0]"), "false");
+    assert_eq!(forward(&mut engine, "a[0]"), "7");
+}
+
+#[test]
+fn integrity_levels() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    forward(&mut engine, "var a = new Int8Array(1);");
+    assert_eq!(
+        forward(&mut engine, "Object.freeze(a)"),
+        "Error: TypeError: Cannot change the integrity level of the object"
+    );
+    forward(&mut engine, "a[0] = 5;");
+    assert_eq!(forward(&mut engine, "a[0]"), "5");
+    assert_eq!(forward(&mut engine, "Object.isFrozen(a)"), "false");
+    assert_eq!(
+        forward(
+            &mut engine,
+            "Object.isFrozen(Object.freeze(new Int8Array(0)))"
+        ),
+        "true"
+    );
+}
+
+#[test]
+fn prototype_accessors() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var buffer = new ArrayBuffer(8);
+        var a = new Int16Array(buffer, 2, 3);
+        var proto = Object.getPrototypeOf(Int8Array.prototype);
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "a.hasOwnProperty('length')"), "false");
+    assert_eq!(
+        forward(&mut engine, "Object.getOwnPropertyNames(a).join()"),
+        "0,1,2"
+    );
+    assert_eq!(
+        forward(
+            &mut engine,
+            "Object.getOwnPropertyDescriptor(proto, 'byteLength').get.name"
+        ),
+        "get byteLength"
+    );
+    assert_eq!(
+        forward(
+            &mut engine,
+            "Object.getOwnPropertyDescriptor(proto, 'length').get.call(a)"
+        ),
+        "3"
+    );
+    assert_eq!(forward(&mut engine, "a.byteLength"), "6");
+    assert_eq!(forward(&mut engine, "a.byteOffset"), "2");
+    assert_eq!(forward(&mut engine, "a.buffer === buffer"), "true");
+    assert_eq!(
+        forward(
+            &mut engine,
+            "Object.getOwnPropertyDescriptor(proto, 'buffer').get.call({})"
+        ),
+        "Error: TypeError: this is not a typed array"
+    );
+    assert_eq!(
+        forward(&mut engine, "Array.prototype.join.call(a, '-')"),
+        "0-0-0"
+    );
+}
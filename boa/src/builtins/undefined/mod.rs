@@ -0,0 +1,28 @@
+//! This module implements the global `undefined` property.
+//!
+//! The global `undefined` property is a read-only property holding the primitive value `undefined`.
+//!
+//! More information:
+//!  - [ECMAScript reference][spec]
+//!  - [MDN documentation][mdn]
+//!
+//! [spec]: https://tc39.es/ecma262/#sec-undefined
+//! [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/undefined
+
+use crate::builtins::{
+    object::internal_methods_trait::ObjectInternalMethods, property::Property, value::Value,
+};
+
+/// Initialise the `undefined` property on the global object.
+#[inline]
+pub fn init(global: &Value) {
+    let property = Property::new()
+        .value(Value::undefined())
+        .writable(false)
+        .enumerable(false)
+        .configurable(false);
+    global
+        .as_object_mut()
+        .expect("the global object is an object")
+        .define_own_property(Value::from("undefined"), property);
+}
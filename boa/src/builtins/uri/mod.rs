@@ -0,0 +1,212 @@
+//! This module implements the global URI handling functions, `encodeURI`, `encodeURIComponent`,
+//! `decodeURI` and `decodeURIComponent`.
+//!
+//! They escape the characters of a string as the `%XX` sequences of their UTF-8 encoding, and the
+//! other way around. The `Component` versions also escape the characters used as delimiters in
+//! URIs.
+//!
+//! More information:
+//!  - [ECMAScript reference][spec]
+//!  - [MDN documentation][mdn]
+//!
+//! [spec]: https://tc39.es/ecma262/#sec-uri-handling-functions
+//! [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/encodeURI
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    builtins::{
+        string::to_js_string,
+        value::{JsString, ResultValue, Value},
+    },
+    exec::Interpreter,
+};
+
+/// The characters which delimit the components of a URI, `uriReserved` and `#`.
+const RESERVED: &str = ";/?:@&=+$,#";
+
+/// The characters which never need escaping, `uriAlpha`, `DecimalDigit` and `uriMark`.
+fn is_unreserved(unit: u16) -> bool {
+    unit < 0x80 && {
+        let c = unit as u8 as char;
+        c.is_ascii_alphanumeric() || "-_.!~*'()".contains(c)
+    }
+}
+
+/// Checks if a code unit is one of the characters of `set`, which are ASCII.
+fn is_in(set: &str, unit: u16) -> bool {
+    unit < 0x80 && set.contains(unit as u8 as char)
+}
+
+/// The abstract operation `Encode`, which escapes all the code points of a string except the
+/// unreserved ones and the ones of `unescaped`.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-encode
+fn encode(string: &JsString, unescaped: &str, ctx: &mut Interpreter) -> ResultValue {
+    let mut result = String::with_capacity(string.len());
+    let mut index = 0;
+    while index < string.len() {
+        let unit = string[index];
+        if is_unreserved(unit) || is_in(unescaped, unit) {
+            result.push(unit as u8 as char);
+            index += 1;
+            continue;
+        }
+
+        let code_point = string
+            .code_point_at(index)
+            .expect("the index is in the string");
+        let c = match std::char::from_u32(code_point.code_point) {
+            Some(c) => c,
+            None => return ctx.throw_uri_error("URI malformed"),
+        };
+        let mut bytes = [0; 4];
+        for byte in c.encode_utf8(&mut bytes).bytes() {
+            result.push_str(&format!("%{:02X}", byte));
+        }
+        index += code_point.code_unit_count;
+    }
+    ctx.reserve_string(result.len())?;
+    Ok(Value::from(result))
+}
+
+/// Reads the byte of the escape sequence `%XX` at `index`.
+fn escaped_byte(string: &JsString, index: usize) -> Option<u8> {
+    if string.get(index) != Some(&u16::from(b'%')) {
+        return None;
+    }
+    let digit = |index: usize| {
+        let unit = *string.get(index)?;
+        std::char::from_u32(u32::from(unit))?.to_digit(16)
+    };
+    Some((digit(index + 1)? * 16 + digit(index + 2)?) as u8)
+}
+
+/// The abstract operation `Decode`, which unescapes the `%XX` sequences of a string, except the
+/// ones of the characters in `preserved`.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-decode
+fn decode(string: &JsString, preserved: &str, ctx: &mut Interpreter) -> ResultValue {
+    let mut result = Vec::with_capacity(string.len());
+    let mut index = 0;
+    while index < string.len() {
+        if string[index] != u16::from(b'%') {
+            result.push(string[index]);
+            index += 1;
+            continue;
+        }
+
+        let first = match escaped_byte(string, index) {
+            Some(byte) => byte,
+            None => return ctx.throw_uri_error("URI malformed"),
+        };
+        if first < 0x80 {
+            if is_in(preserved, u16::from(first)) {
+                result.extend_from_slice(&string[index..index + 3]);
+            } else {
+                result.push(u16::from(first));
+            }
+            index += 3;
+            continue;
+        }
+
+        // The leading byte tells how many bytes the UTF-8 sequence has
+        let length = first.leading_ones() as usize;
+        if length < 2 || length > 4 {
+            return ctx.throw_uri_error("URI malformed");
+        }
+        let mut bytes = vec![first];
+        for continuation in 1..length {
+            match escaped_byte(string, index + continuation * 3) {
+                Some(byte) if byte & 0xC0 == 0x80 => bytes.push(byte),
+                _ => return ctx.throw_uri_error("URI malformed"),
+            }
+        }
+        // Overlong encodings and surrogates are rejected as invalid UTF-8
+        match std::str::from_utf8(&bytes) {
+            Ok(decoded) => result.extend(decoded.encode_utf16()),
+            Err(_) => return ctx.throw_uri_error("URI malformed"),
+        }
+        index += length * 3;
+    }
+    ctx.reserve_string(result.len())?;
+    Ok(Value::from(JsString::from(result)))
+}
+
+/// `encodeURI( uri )`
+///
+/// The `encodeURI()` function encodes a URI, the characters delimiting its components are kept.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-encodeuri-uri
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/encodeURI
+pub fn encode_uri(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let uri = to_js_string(args.get(0).unwrap_or(&Value::undefined()), ctx)?;
+    encode(&uri, RESERVED, ctx)
+}
+
+/// `encodeURIComponent( uriComponent )`
+///
+/// The `encodeURIComponent()` function encodes a component of a URI, the characters delimiting
+/// components are escaped as well.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-encodeuricomponent-uricomponent
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/encodeURIComponent
+pub fn encode_uri_component(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let component = to_js_string(args.get(0).unwrap_or(&Value::undefined()), ctx)?;
+    encode(&component, "", ctx)
+}
+
+/// `decodeURI( encodedURI )`
+///
+/// The `decodeURI()` function decodes a URI encoded by `encodeURI`, the escape sequences of the
+/// characters delimiting components are kept.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-decodeuri-encodeduri
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/decodeURI
+pub fn decode_uri(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let uri = to_js_string(args.get(0).unwrap_or(&Value::undefined()), ctx)?;
+    decode(&uri, RESERVED, ctx)
+}
+
+/// `decodeURIComponent( encodedURIComponent )`
+///
+/// The `decodeURIComponent()` function decodes all the escape sequences of a URI component.
+///
+/// More information:
+///  - [ECMAScript reference][spec]
+///  - [MDN documentation][mdn]
+///
+/// [spec]: https://tc39.es/ecma262/#sec-decodeuricomponent-encodeduricomponent
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/decodeURIComponent
+pub fn decode_uri_component(_: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let component = to_js_string(args.get(0).unwrap_or(&Value::undefined()), ctx)?;
+    decode(&component, "", ctx)
+}
+
+/// Initialise the URI handling functions on the global object.
+#[inline]
+pub fn init(global: &Value) {
+    make_builtin_fn!(decode_uri, named "decodeURI", with length 1, of global);
+    make_builtin_fn!(decode_uri_component, named "decodeURIComponent", with length 1, of global);
+    make_builtin_fn!(encode_uri, named "encodeURI", with length 1, of global);
+    make_builtin_fn!(encode_uri_component, named "encodeURIComponent", with length 1, of global);
+}
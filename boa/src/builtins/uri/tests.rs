@@ -0,0 +1,59 @@
+use crate::{exec::Executor, forward, realm::Realm};
+
+#[test]
+fn encode() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+
+    assert_eq!(
+        forward(&mut engine, "encodeURI('http://a.b/c d?e=ü&f=#g')"),
+        "http://a.b/c%20d?e=%C3%BC&f=#g"
+    );
+    assert_eq!(
+        forward(&mut engine, "encodeURIComponent('a b&c=d/é')"),
+        "a%20b%26c%3Dd%2F%C3%A9"
+    );
+    assert_eq!(
+        forward(&mut engine, "encodeURIComponent('😀')"),
+        "%F0%9F%98%80"
+    );
+    assert_eq!(
+        forward(&mut engine, "encodeURIComponent(\"-_.!~*'()\")"),
+        "-_.!~*'()"
+    );
+    assert_eq!(
+        forward(&mut engine, "encodeURIComponent('\\uD800')"),
+        "Error: URIError: URI malformed"
+    );
+}
+
+#[test]
+fn decode() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+
+    assert_eq!(
+        forward(&mut engine, "decodeURI('%41%20%3Fb%C3%BC')"),
+        "A %3Fbü"
+    );
+    assert_eq!(
+        forward(&mut engine, "decodeURIComponent('%41%20%3Fb%C3%BC')"),
+        "A ?bü"
+    );
+    assert_eq!(
+        forward(&mut engine, "decodeURIComponent('%F0%9F%98%80')"),
+        "😀"
+    );
+    assert_eq!(
+        forward(&mut engine, "decodeURIComponent('%')"),
+        "Error: URIError: URI malformed"
+    );
+    assert_eq!(
+        forward(&mut engine, "decodeURIComponent('%C0%80')"),
+        "Error: URIError: URI malformed"
+    );
+    assert_eq!(
+        forward(&mut engine, "decodeURIComponent('%ED%A0%80')"),
+        "Error: URIError: URI malformed"
+    );
+}
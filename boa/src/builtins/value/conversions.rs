@@ -13,6 +13,12 @@ impl From<String> for Value {
     }
 }
 
+impl From<JsString> for Value {
+    fn from(value: JsString) -> Self {
+        Self::string(value)
+    }
+}
+
 impl From<&Value> for String {
     fn from(value: &Value) -> Self {
         value.to_string()
@@ -78,7 +84,10 @@ impl From<&Value> for i32 {
 
 impl From<usize> for Value {
     fn from(value: usize) -> Value {
-        Value::integer(value as i32)
+        match i32::try_from(value) {
+            Ok(value) => Value::integer(value),
+            Err(_) => Value::rational(value as f64),
+        }
     }
 }
 impl From<&Value> for usize {
@@ -107,7 +116,7 @@ where
         let mut array = Object::default();
         for (i, item) in value.iter().enumerate() {
             array.properties.insert(
-                i.to_string(),
+                i.to_string().into(),
                 Property::default().value(item.clone().into()),
             );
         }
@@ -124,7 +133,7 @@ where
         for (i, item) in value.into_iter().enumerate() {
             array
                 .properties
-                .insert(i.to_string(), Property::default().value(item.into()));
+                .insert(i.to_string().into(), Property::default().value(item.into()));
         }
         Value::from(array)
     }
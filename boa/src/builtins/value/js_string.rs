@@ -0,0 +1,323 @@
+//! This module implements `JsString`, the representation of JavaScript string values.
+//!
+//! ECMAScript strings are sequences of UTF-16 code units, which don't have to be well formed:
+//! a string can contain lone surrogates, such as the one created by `"\uD800"`. Rust strings
+//! can't represent those, so the code units are stored directly, and every index based string
+//! operation works on code units just like other engines do.
+//!
+//! More information:
+//!  - [ECMAScript reference][spec]
+//!
+//! [spec]: https://tc39.es/ecma262/#sec-ecmascript-language-types-string-type
+
+use crate::heap::Charge;
+use gc::{unsafe_empty_trace, Finalize, Trace};
+use std::{
+    char::decode_utf16,
+    cmp::Ordering,
+    fmt::{self, Display},
+    hash::{Hash, Hasher},
+    mem,
+    ops::Deref,
+    rc::Rc,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// An immutable JavaScript string, stored as UTF-16 code units.
+///
+/// Cloning a `JsString` is cheap, the code units are shared.
+#[derive(Clone, Debug)]
+pub struct JsString(Rc<[u16]>, Option<Rc<Charge>>);
+
+/// The length from which the code units of a string are charged to the running interpreter,
+/// shorter strings are accounted for by the objects holding them.
+const CHARGED_LENGTH: usize = 32;
+
+impl JsString {
+    /// Creates a string from its code units, charging them to the running interpreter.
+    fn from_units(units: Rc<[u16]>) -> Self {
+        let charge = if units.len() >= CHARGED_LENGTH {
+            Some(Rc::new(Charge::new(units.len() * mem::size_of::<u16>())))
+        } else {
+            None
+        };
+        Self(units, charge)
+    }
+
+    /// Creates a new empty string.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the code units of the string.
+    pub fn as_utf16(&self) -> &[u16] {
+        &self.0
+    }
+
+    /// Converts the string to a Rust `String`, replacing lone surrogates with
+    /// `U+FFFD REPLACEMENT CHARACTER`.
+    pub fn to_std_string_lossy(&self) -> String {
+        String::from_utf16_lossy(&self.0)
+    }
+
+    /// Converts the string to a Rust `String`.
+    ///
+    /// Returns `None` if the string contains lone surrogates.
+    pub fn to_std_string(&self) -> Option<String> {
+        String::from_utf16(&self.0).ok()
+    }
+
+    /// Returns the code point starting at code unit `index`, as described by the
+    /// `CodePointAt` abstract operation.
+    ///
+    /// A lone surrogate is returned as is, with `is_unpaired_surrogate` set.
+    ///
+    /// More information:
+    ///  - [ECMAScript reference][spec]
+    ///
+    /// [spec]: https://tc39.es/ecma262/#sec-codepointat
+    pub fn code_point_at(&self, index: usize) -> Option<CodePoint> {
+        let first = *self.0.get(index)?;
+        let lone = CodePoint {
+            code_point: u32::from(first),
+            code_unit_count: 1,
+            is_unpaired_surrogate: is_surrogate(first),
+        };
+        if !is_leading_surrogate(first) {
+            return Some(lone);
+        }
+        match self.0.get(index + 1) {
+            Some(&second) if is_trailing_surrogate(second) => Some(CodePoint {
+                code_point: (u32::from(first) - 0xD800) * 0x400
+                    + (u32::from(second) - 0xDC00)
+                    + 0x10000,
+                code_unit_count: 2,
+                is_unpaired_surrogate: false,
+            }),
+            _ => Some(lone),
+        }
+    }
+
+    /// Returns an iterator over the code points of the string.
+    pub fn code_points(&self) -> CodePoints<'_> {
+        CodePoints {
+            string: self,
+            index: 0,
+        }
+    }
+
+    /// Returns the index of the first occurrence of `search` at or after code unit `from`.
+    pub fn index_of(&self, search: &[u16], from: usize) -> Option<usize> {
+        if from > self.len() {
+            return None;
+        }
+        if search.is_empty() {
+            return Some(from);
+        }
+        self.0[from..]
+            .windows(search.len())
+            .position(|window| window == search)
+            .map(|position| position + from)
+    }
+
+    /// Returns the index of the last occurrence of `search` starting at or before code unit
+    /// `from`.
+    pub fn last_index_of(&self, search: &[u16], from: usize) -> Option<usize> {
+        if search.len() > self.len() {
+            return None;
+        }
+        let last_start = (self.len() - search.len()).min(from);
+        (0..=last_start)
+            .rev()
+            .find(|&start| &self.0[start..start + search.len()] == search)
+    }
+
+    /// Returns the concatenation of `self` and `other`.
+    pub fn concat(&self, other: &[u16]) -> Self {
+        let mut units = Vec::with_capacity(self.len() + other.len());
+        units.extend_from_slice(&self.0);
+        units.extend_from_slice(other);
+        Self::from(units)
+    }
+}
+
+impl Default for JsString {
+    fn default() -> Self {
+        Self::from_units(Rc::from(Vec::new()))
+    }
+}
+
+impl PartialEq for JsString {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Eq for JsString {}
+
+impl PartialOrd for JsString {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for JsString {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl Hash for JsString {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+impl Finalize for JsString {}
+
+/// `JsString` holds no garbage collected values, so there is nothing to trace.
+unsafe impl Trace for JsString {
+    unsafe_empty_trace!();
+}
+
+impl Deref for JsString {
+    type Target = [u16];
+
+    fn deref(&self) -> &[u16] {
+        &self.0
+    }
+}
+
+impl Display for JsString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in decode_utf16(self.0.iter().copied()) {
+            write!(f, "{}", c.unwrap_or(std::char::REPLACEMENT_CHARACTER))?;
+        }
+        Ok(())
+    }
+}
+
+impl From<Vec<u16>> for JsString {
+    fn from(units: Vec<u16>) -> Self {
+        Self::from_units(Rc::from(units))
+    }
+}
+
+impl From<&[u16]> for JsString {
+    fn from(units: &[u16]) -> Self {
+        Self::from_units(Rc::from(units))
+    }
+}
+
+impl From<&str> for JsString {
+    fn from(s: &str) -> Self {
+        Self::from(s.encode_utf16().collect::<Vec<_>>())
+    }
+}
+
+impl From<String> for JsString {
+    fn from(s: String) -> Self {
+        Self::from(s.as_str())
+    }
+}
+
+impl From<&String> for JsString {
+    fn from(s: &String) -> Self {
+        Self::from(s.as_str())
+    }
+}
+
+impl From<char> for JsString {
+    fn from(c: char) -> Self {
+        let mut buf = [0; 2];
+        Self::from(&*c.encode_utf16(&mut buf))
+    }
+}
+
+impl From<&JsString> for JsString {
+    fn from(s: &JsString) -> Self {
+        s.clone()
+    }
+}
+
+impl PartialEq<str> for JsString {
+    fn eq(&self, other: &str) -> bool {
+        self.0.iter().copied().eq(other.encode_utf16())
+    }
+}
+
+impl PartialEq<&str> for JsString {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for JsString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_std_string_lossy())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for JsString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self::from)
+    }
+}
+
+/// A code point of a `JsString`, as returned by [`JsString::code_point_at`][cpa].
+///
+/// [cpa]: struct.JsString.html#method.code_point_at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CodePoint {
+    /// The numeric value of the code point.
+    pub code_point: u32,
+
+    /// The number of code units the code point is made of, `1` or `2`.
+    pub code_unit_count: usize,
+
+    /// Whether the code point is a lone surrogate.
+    pub is_unpaired_surrogate: bool,
+}
+
+impl CodePoint {
+    /// Returns the code point as a `char`, or `None` for lone surrogates.
+    pub fn as_char(self) -> Option<char> {
+        std::char::from_u32(self.code_point)
+    }
+}
+
+/// Iterator over the code points of a `JsString`.
+#[derive(Debug, Clone)]
+pub struct CodePoints<'a> {
+    string: &'a JsString,
+    index: usize,
+}
+
+impl Iterator for CodePoints<'_> {
+    type Item = CodePoint;
+
+    fn next(&mut self) -> Option<CodePoint> {
+        let code_point = self.string.code_point_at(self.index)?;
+        self.index += code_point.code_unit_count;
+        Some(code_point)
+    }
+}
+
+/// Returns `true` if `unit` is a leading (high) surrogate.
+pub fn is_leading_surrogate(unit: u16) -> bool {
+    (0xD800..=0xDBFF).contains(&unit)
+}
+
+/// Returns `true` if `unit` is a trailing (low) surrogate.
+pub fn is_trailing_surrogate(unit: u16) -> bool {
+    (0xDC00..=0xDFFF).contains(&unit)
+}
+
+/// Returns `true` if `unit` is a surrogate.
+pub fn is_surrogate(unit: u16) -> bool {
+    (0xD800..=0xDFFF).contains(&unit)
+}
@@ -6,14 +6,19 @@
 mod tests;
 
 use crate::builtins::{
-    function::Function,
+    array_buffer, data_view,
+    function::{Function, REALM},
+    number,
     object::{
         internal_methods_trait::ObjectInternalMethods, InternalState, InternalStateCell, Object,
         ObjectKind, INSTANCE_PROTOTYPE, PROTOTYPE,
     },
     property::Property,
+    string,
+    symbol::{self, Symbol},
+    typed_array,
 };
-use gc::{Finalize, Gc, GcCell, GcCellRef, Trace};
+use gc::{Finalize, Gc, GcCell, GcCellRef, GcCellRefMut, Trace};
 use serde_json::{map::Map, Number as JSONNumber, Value as JSONValue};
 use std::{
     any::Any,
@@ -21,12 +26,13 @@ use std::{
     f64::NAN,
     fmt::{self, Display},
     ops::{Add, BitAnd, BitOr, BitXor, Deref, DerefMut, Div, Mul, Not, Rem, Shl, Shr, Sub},
-    str::FromStr,
 };
 
 pub mod conversions;
+pub mod js_string;
 pub mod operations;
 pub use conversions::*;
+pub use js_string::JsString;
 pub use operations::*;
 
 /// The result of a Javascript expression is represented like this so it can succeed (`Ok`) or fail (`Err`)
@@ -54,7 +60,7 @@ impl Value {
     #[inline]
     pub fn string<S>(value: S) -> Self
     where
-        S: Into<String>,
+        S: Into<JsString>,
     {
         Self(Gc::new(ValueData::String(value.into())))
     }
@@ -92,6 +98,12 @@ impl Value {
         Self(Gc::new(ValueData::Boolean(value)))
     }
 
+    /// Creates a new symbol value.
+    #[inline]
+    pub fn symbol(symbol: Symbol) -> Self {
+        Self(Gc::new(ValueData::Symbol(symbol)))
+    }
+
     /// Creates a new object value.
     #[inline]
     pub fn object(object: Object) -> Self {
@@ -157,16 +169,16 @@ pub enum ValueData {
     Undefined,
     /// `boolean` - A `true` / `false` value, for if a certain criteria is met
     Boolean(bool),
-    /// `String` - A UTF-8 string, such as `"Hello, world"`
-    String(String),
+    /// `String` - A UTF-16 string, such as `"Hello, world"`
+    String(JsString),
     /// `Number` - A 64-bit floating point number, such as `3.1415`
     Rational(f64),
     /// `Number` - A 32-bit integer, such as `42`
     Integer(i32),
     /// `Object` - An object, such as `Math`, represented by a binary tree of string keys to Javascript values
     Object(Box<GcCell<Object>>),
-    /// `Symbol` - A Symbol Type - Internally Symbols are similar to objects, except there are no properties, only internal slots
-    Symbol(Box<GcCell<Object>>),
+    /// `Symbol` - A unique value which can be used as the key of a property, such as `Symbol.iterator`
+    Symbol(Symbol),
 }
 
 impl ValueData {
@@ -298,16 +310,7 @@ impl ValueData {
     pub fn to_number(&self) -> f64 {
         match *self {
             Self::Object(_) | Self::Symbol(_) | Self::Undefined => NAN,
-            Self::String(ref str) => {
-                if str.is_empty() {
-                    return 0.0;
-                }
-
-                match FromStr::from_str(str) {
-                    Ok(num) => num,
-                    Err(_) => NAN,
-                }
-            }
+            Self::String(ref str) => number::string_to_number(str),
             Self::Boolean(true) => 1.0,
             Self::Boolean(false) | Self::Null => 0.0,
             Self::Rational(num) => num,
@@ -315,6 +318,23 @@ impl ValueData {
         }
     }
 
+    /// Converts the value into an integral number, or an infinity, as done by the abstract
+    /// operation `ToIntegerOrInfinity`.
+    ///
+    /// More information:
+    ///  - [ECMAScript reference][spec]
+    ///
+    /// [spec]: https://tc39.es/ecma262/#sec-tointegerorinfinity
+    pub fn to_integer_or_infinity(&self) -> f64 {
+        let number = self.to_number();
+        if number.is_nan() {
+            0.0
+        } else {
+            // Adding `+0` turns `-0` into `+0`.
+            number.trunc() + 0.0
+        }
+    }
+
     /// Converts the value into a 32-bit integer
     pub fn to_integer(&self) -> i32 {
         match *self {
@@ -323,10 +343,7 @@ impl ValueData {
             | Self::Symbol(_)
             | Self::Null
             | Self::Boolean(false) => 0,
-            Self::String(ref str) => match FromStr::from_str(str) {
-                Ok(num) => num,
-                Err(_) => 0,
-            },
+            Self::String(ref str) => number::string_to_number(str) as i32,
             Self::Rational(num) => num as i32,
             Self::Boolean(true) => 1,
             Self::Integer(num) => num,
@@ -340,12 +357,23 @@ impl ValueData {
         }
     }
 
+    pub fn as_object_mut(&self) -> Option<GcCellRefMut<'_, Object>> {
+        match *self {
+            ValueData::Object(ref o) => Some(o.borrow_mut()),
+            _ => None,
+        }
+    }
+
     /// Removes a property from a Value object.
     ///
     /// It will return a boolean based on if the value was removed, if there was no value to remove false is returned
     pub fn remove_property(&self, field: &str) -> bool {
         let removed = match *self {
-            Self::Object(ref obj) => obj.borrow_mut().deref_mut().properties.remove(field),
+            Self::Object(ref obj) => obj
+                .borrow_mut()
+                .deref_mut()
+                .properties
+                .remove(&JsString::from(field)),
             _ => None,
         };
 
@@ -356,41 +384,75 @@ impl ValueData {
     ///
     /// A copy of the Property is returned.
     pub fn get_property(&self, field: &str) -> Option<Property> {
+        self.get_property_key(&JsString::from(field))
+    }
+
+    /// Resolve the property keyed by a string in the object, which can hold any code units.
+    ///
+    /// A copy of the Property is returned.
+    pub(crate) fn get_property_key(&self, key: &JsString) -> Option<Property> {
         // Spidermonkey has its own GetLengthProperty: https://searchfox.org/mozilla-central/source/js/src/vm/Interpreter-inl.h#154
-        // This is only for primitive strings, String() objects have their lengths calculated in string.rs
-        if self.is_string() && field == "length" {
-            if let Self::String(ref s) = *self {
-                return Some(Property::default().value(Value::from(s.len())));
-            }
+        // Primitive strings expose their length and code units as own properties
+        if let Self::String(ref s) = *self {
+            return string::get_own_property(s, &key.to_std_string_lossy())
+                .map(|v| Property::default().value(v));
         }
 
         if self.is_undefined() {
             return None;
         }
 
-        let obj: Object = match *self {
-            Self::Object(ref obj) => {
-                let hash = obj.clone();
-                // TODO: This will break, we should return a GcCellRefMut instead
-                // into_inner will consume the wrapped value and remove it from the hashmap
-                hash.into_inner()
-            }
-            Self::Symbol(ref obj) => {
-                let hash = obj.clone();
-                hash.into_inner()
-            }
+        let obj = match *self {
+            Self::Object(ref obj) => obj.borrow(),
             _ => return None,
         };
 
-        match obj.properties.get(field) {
+        // String objects, buffers and their views compute some of their own properties from
+        // internal state. None of those has a lone surrogate in its name.
+        let field = &key.to_std_string_lossy();
+        let exotic = match obj.kind {
+            ObjectKind::String => match obj.internal_slots.get("StringData").map(Value::data) {
+                Some(ValueData::String(ref s)) => string::get_own_property(s, field),
+                _ => None,
+            },
+            ObjectKind::ArrayBuffer => array_buffer::get_own_property(&obj, field),
+            ObjectKind::TypedArray => typed_array::get_own_property(&obj, field),
+            ObjectKind::DataView => data_view::get_own_property(&obj, field),
+            ObjectKind::Symbol => symbol::get_own_property(&obj, field),
+            _ => None,
+        };
+        if let Some(value) = exotic {
+            return Some(Property::default().value(value));
+        }
+
+        match obj.properties.get(key) {
             Some(val) => Some(val.clone()),
             None => match obj.internal_slots.get(&INSTANCE_PROTOTYPE.to_string()) {
-                Some(value) => value.get_property(field),
+                Some(value) => value.get_property_key(key),
                 None => None,
             },
         }
     }
 
+    /// Resolve the property keyed by a symbol in the object, walking up the prototype chain.
+    ///
+    /// A copy of the Property is returned.
+    pub fn get_symbol_property(&self, symbol: &Symbol) -> Option<Property> {
+        match *self {
+            Self::Object(ref obj) => {
+                let obj = obj.borrow();
+                match obj.sym_properties.get(symbol) {
+                    Some(prop) => Some(prop.clone()),
+                    None => obj
+                        .internal_slots
+                        .get(INSTANCE_PROTOTYPE)
+                        .and_then(|proto| proto.get_symbol_property(symbol)),
+                }
+            }
+            _ => None,
+        }
+    }
+
     /// update_prop will overwrite individual [Property] fields, unlike
     /// Set_prop, which will overwrite prop with a new Property
     /// Mostly used internally for now
@@ -409,7 +471,7 @@ impl ValueData {
 
         if let Some(mut obj_data) = obj {
             // Use value, or walk up the prototype chain
-            if let Some(ref mut prop) = obj_data.properties.get_mut(field) {
+            if let Some(ref mut prop) = obj_data.properties.get_mut(&JsString::from(field)) {
                 prop.value = value;
                 prop.enumerable = enumerable;
                 prop.writable = writable;
@@ -422,15 +484,8 @@ impl ValueData {
     ///
     /// Returns a copy of the Property.
     pub fn get_internal_slot(&self, field: &str) -> Value {
-        let obj: Object = match *self {
-            Self::Object(ref obj) => {
-                let hash = obj.clone();
-                hash.into_inner()
-            }
-            Self::Symbol(ref obj) => {
-                let hash = obj.clone();
-                hash.into_inner()
-            }
+        let obj = match *self {
+            Self::Object(ref obj) => obj.borrow(),
             _ => return Value::undefined(),
         };
 
@@ -441,13 +496,13 @@ impl ValueData {
     }
 
     /// Resolve the property in the object and get its value, or undefined if this is not an object or the field doesn't exist
-    /// get_field recieves a Property from get_prop(). It should then return the [[Get]] result value if that's set, otherwise fall back to [[Value]]
-    /// TODO: this function should use the get Value if its set
+    ///
+    /// Accessors read as undefined here, `Interpreter::get_field` calls their getter.
     pub fn get_field(&self, field: Value) -> Value {
         match *field {
             // Our field will either be a String or a Symbol
             Self::String(ref s) => {
-                match self.get_property(s) {
+                match self.get_property_key(s) {
                     Some(prop) => {
                         // If the Property has [[Get]] set to a function, we should run that and return the Value
                         let prop_getter = match prop.get {
@@ -459,17 +514,16 @@ impl ValueData {
                         if let Some(val) = prop_getter {
                             val
                         } else {
-                            let val = prop
-                                .value
-                                .as_ref()
-                                .expect("Could not get property as reference");
-                            val.clone()
+                            prop.value.clone().unwrap_or_else(Value::undefined)
                         }
                     }
                     None => Value::undefined(),
                 }
             }
-            Self::Symbol(_) => unimplemented!(),
+            Self::Symbol(ref sym) => self
+                .get_symbol_property(sym)
+                .and_then(|prop| prop.value.clone())
+                .unwrap_or_else(Value::undefined),
             _ => Value::undefined(),
         }
     }
@@ -556,28 +610,45 @@ impl ValueData {
     /// Set the field in the value
     /// Field could be a Symbol, so we need to accept a Value (not a string)
     pub fn set_field(&self, field: Value, val: Value) -> Value {
+        self.try_set_field(field, val.clone());
+        val
+    }
+
+    /// Set the field in the value, returning `false` if the assignment was rejected, e.g.
+    /// because the property is read-only or the object is not extensible.
+    pub fn try_set_field(&self, field: Value, val: Value) -> bool {
         if let Self::Object(ref obj) = *self {
-            if obj.borrow().kind == ObjectKind::Array {
-                if let Ok(num) = field.to_string().parse::<usize>() {
-                    if num > 0 {
-                        let len = i32::from(&self.get_field_slice("length"));
-                        if len < (num + 1) as i32 {
-                            self.set_field_slice("length", Value::from(num + 1));
-                        }
-                    }
-                }
+            // Integer indexed elements of typed arrays are stored in their buffer
+            if obj.borrow().kind == ObjectKind::TypedArray
+                && !field.is_symbol()
+                && typed_array::set_element(&obj.borrow(), &field.to_string(), &val)
+            {
+                return true;
             }
 
             // Symbols get saved into a different bucket to general properties
-            if field.is_symbol() {
-                obj.borrow_mut().set(field, val.clone());
+            let field = if field.is_symbol() || field.is_string() {
+                field
             } else {
-                obj.borrow_mut()
-                    .set(Value::from(field.to_string()), val.clone());
+                Value::from(field.to_string())
+            };
+            let is_array = obj.borrow().kind == ObjectKind::Array;
+            if !obj.borrow_mut().set(field.clone(), val) {
+                return false;
+            }
+
+            if is_array {
+                if let Ok(num) = field.to_string().parse::<usize>() {
+                    let len = i32::from(&self.get_field_slice("length"));
+                    if len < (num + 1) as i32 {
+                        self.set_field_slice("length", Value::from(num + 1));
+                    }
+                }
             }
+            return true;
         }
 
-        val
+        false
     }
 
     /// Set the field in the value
@@ -608,7 +679,9 @@ impl ValueData {
     /// Set the property in the value
     pub fn set_property(&self, field: String, prop: Property) -> Property {
         if let Self::Object(ref obj) = *self {
-            obj.borrow_mut().properties.insert(field, prop.clone());
+            obj.borrow_mut()
+                .properties
+                .insert(JsString::from(field), prop.clone());
         }
         prop
     }
@@ -648,18 +721,18 @@ impl ValueData {
             JSONValue::Number(v) => {
                 Self::Rational(v.as_f64().expect("Could not convert value to f64"))
             }
-            JSONValue::String(v) => Self::String(v),
+            JSONValue::String(v) => Self::String(v.into()),
             JSONValue::Bool(v) => Self::Boolean(v),
             JSONValue::Array(vs) => {
                 let mut new_obj = Object::default();
                 for (idx, json) in vs.iter().enumerate() {
                     new_obj.properties.insert(
-                        idx.to_string(),
+                        idx.to_string().into(),
                         Property::default().value(Value::from(json.clone())),
                     );
                 }
                 new_obj.properties.insert(
-                    "length".to_string(),
+                    "length".into(),
                     Property::default().value(Value::from(vs.len())),
                 );
                 Self::Object(Box::new(GcCell::new(new_obj)))
@@ -668,7 +741,7 @@ impl ValueData {
                 let mut new_obj = Object::default();
                 for (key, json) in obj.iter() {
                     new_obj.properties.insert(
-                        key.clone(),
+                        key.into(),
                         Property::default().value(Value::from(json.clone())),
                     );
                 }
@@ -689,11 +762,15 @@ impl ValueData {
                     .borrow()
                     .properties
                     .iter()
-                    .map(|(k, _)| (k.clone(), self.get_field_slice(k).to_json()))
+                    .map(|(k, _)| {
+                        let key = k.to_std_string_lossy();
+                        let value = self.get_field(Value::from(k.clone())).to_json();
+                        (key, value)
+                    })
                     .collect::<Map<String, JSONValue>>();
                 JSONValue::Object(new_obj)
             }
-            Self::String(ref str) => JSONValue::String(str.clone()),
+            Self::String(ref str) => JSONValue::String(str.to_string()),
             Self::Rational(num) => JSONValue::Number(
                 JSONNumber::from_f64(num).expect("Could not convert to JSONNumber"),
             ),
@@ -721,6 +798,12 @@ impl ValueData {
             }
         }
     }
+
+    /// Formats the value like `Display` does, except that objects only show their own
+    /// properties, without their internal slots and prototype.
+    pub fn display_own_properties(&self) -> String {
+        log_string_from(self, false)
+    }
 }
 
 impl Default for ValueData {
@@ -751,15 +834,22 @@ macro_rules! print_obj_value {
         }
     };
     (internals of $obj:expr, $display_fn:ident, $indent:expr, $encounters:expr) => {
-        print_obj_value!(impl internal_slots, $obj, |(key, val)| {
-            format!(
-                "{}{}: {}",
-                String::from_utf8(vec![b' '; $indent])
-                                .expect("Could not create indentation string"),
-                key,
-                $display_fn(&val, $encounters, $indent.wrapping_add(4), true)
-            )
-        })
+        $obj
+            .borrow()
+            .internal_slots
+            .iter()
+            // The realm of a function would print all the intrinsics
+            .filter(|(key, _)| key.as_str() != REALM)
+            .map(|(key, val)| {
+                format!(
+                    "{}{}: {}",
+                    String::from_utf8(vec![b' '; $indent])
+                                    .expect("Could not create indentation string"),
+                    key,
+                    $display_fn(&val, $encounters, $indent.wrapping_add(4), true)
+                )
+            })
+            .collect::<Vec<String>>()
     };
     (props of $obj:expr, $display_fn:ident, $indent:expr, $encounters:expr, $print_internals:expr) => {
         print_obj_value!(impl properties, $obj, |(key, val)| {
@@ -803,6 +893,15 @@ pub(crate) fn log_string_from(x: &ValueData, print_internals: bool) -> String {
                         .get("StringData")
                         .expect("Cannot get primitive value from String"),
                 ),
+                ObjectKind::Error => {
+                    let name = x.get_field_slice("name");
+                    let message = x.get_field_slice("message");
+                    if message.is_undefined() || message.to_string().is_empty() {
+                        name.to_string()
+                    } else {
+                        format!("{}: {}", name, message)
+                    }
+                }
                 ObjectKind::Boolean => {
                     let bool_data = v.borrow().get_internal_slot("BooleanData").to_string();
 
@@ -812,7 +911,7 @@ pub(crate) fn log_string_from(x: &ValueData, print_internals: bool) -> String {
                     let len = i32::from(
                         &v.borrow()
                             .properties
-                            .get("length")
+                            .get(&JsString::from("length"))
                             .unwrap()
                             .value
                             .clone()
@@ -830,7 +929,7 @@ pub(crate) fn log_string_from(x: &ValueData, print_internals: bool) -> String {
                             log_string_from(
                                 &v.borrow()
                                     .properties
-                                    .get(&i.to_string())
+                                    .get(&JsString::from(i.to_string()))
                                     .unwrap()
                                     .value
                                     .clone()
@@ -843,16 +942,26 @@ pub(crate) fn log_string_from(x: &ValueData, print_internals: bool) -> String {
 
                     format!("[ {} ]", arr)
                 }
+                ObjectKind::TypedArray => {
+                    let name = x.get_field_slice("constructor").get_field_slice("name");
+                    let len = i32::from(&x.get_field_slice("length"));
+                    if len == 0 {
+                        return format!("{} []", name);
+                    }
+                    let arr = (0..len)
+                        .map(|i| x.get_field_slice(&i.to_string()).to_string())
+                        .collect::<Vec<String>>()
+                        .join(", ");
+
+                    format!("{} [ {} ]", name, arr)
+                }
                 _ => display_obj(&x, print_internals),
             }
         }
-        ValueData::Symbol(ref sym) => {
-            let desc: Value = sym.borrow().get_internal_slot("Description");
-            match *desc {
-                ValueData::String(ref st) => format!("Symbol(\"{}\")", st.to_string()),
-                _ => String::from("Symbol()"),
-            }
-        }
+        ValueData::Symbol(ref sym) => match sym.description() {
+            Some(desc) => format!("Symbol(\"{}\")", desc),
+            None => String::from("Symbol()"),
+        },
 
         _ => format!("{}", x),
     }
@@ -920,22 +1029,9 @@ impl Display for ValueData {
             Self::Null => write!(f, "null"),
             Self::Undefined => write!(f, "undefined"),
             Self::Boolean(v) => write!(f, "{}", v),
-            Self::Symbol(ref v) => match *v.borrow().get_internal_slot("Description") {
-                // If a description exists use it
-                Self::String(ref v) => write!(f, "{}", format!("Symbol({})", v)),
-                _ => write!(f, "Symbol()"),
-            },
+            Self::Symbol(ref v) => write!(f, "{}", v),
             Self::String(ref v) => write!(f, "{}", v),
-            Self::Rational(v) => write!(
-                f,
-                "{}",
-                match v {
-                    _ if v.is_nan() => "NaN".to_string(),
-                    _ if v.is_infinite() && v.is_sign_negative() => "-Infinity".to_string(),
-                    _ if v.is_infinite() => "Infinity".to_string(),
-                    _ => v.to_string(),
-                }
-            ),
+            Self::Rational(v) => write!(f, "{}", number::number_to_string(*v)),
             Self::Object(_) => write!(f, "{}", log_string_from(self, true)),
             Self::Integer(v) => write!(f, "{}", v),
         }
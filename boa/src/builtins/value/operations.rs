@@ -25,12 +25,16 @@ impl Value {
     ///
     /// This method is executed when doing abstract equality comparisons with the `==` operator.
     ///  For more information, check <https://tc39.es/ecma262/#sec-abstract-equality-comparison>
-    pub fn equals(&mut self, other: &mut Self, interpreter: &mut Interpreter) -> bool {
+    pub fn equals(
+        &mut self,
+        other: &mut Self,
+        interpreter: &mut Interpreter,
+    ) -> Result<bool, Value> {
         if self.get_type() == other.get_type() {
-            return self.strict_equals(other);
+            return Ok(self.strict_equals(other));
         }
 
-        match (self.data(), other.data()) {
+        Ok(match (self.data(), other.data()) {
             _ if self.is_null_or_undefined() && other.is_null_or_undefined() => true,
 
             // https://github.com/rust-lang/rust/issues/54883
@@ -45,21 +49,21 @@ impl Value {
                 number::equals(f64::from(a), f64::from(b))
             }
             (ValueData::Boolean(_), _) => {
-                other.equals(&mut Value::from(self.to_integer()), interpreter)
+                other.equals(&mut Value::from(self.to_integer()), interpreter)?
             }
             (_, ValueData::Boolean(_)) => {
-                self.equals(&mut Value::from(other.to_integer()), interpreter)
+                self.equals(&mut Value::from(other.to_integer()), interpreter)?
             }
             (ValueData::Object(_), _) => {
-                let mut primitive = interpreter.to_primitive(self, None);
-                primitive.equals(other, interpreter)
+                let mut primitive = interpreter.to_primitive(self, None)?;
+                primitive.equals(other, interpreter)?
             }
             (_, ValueData::Object(_)) => {
-                let mut primitive = interpreter.to_primitive(other, None);
-                primitive.equals(self, interpreter)
+                let mut primitive = interpreter.to_primitive(other, None)?;
+                primitive.equals(self, interpreter)?
             }
             _ => false,
-        }
+        })
     }
 }
 
@@ -194,7 +198,14 @@ pub fn same_value_non_number(x: &Value, y: &Value) -> bool {
             false
         }
         "boolean" => bool::from(x) == bool::from(y),
-        "object" => std::ptr::eq(x, y),
+        "symbol" => match (x.data(), y.data()) {
+            (ValueData::Symbol(ref a), ValueData::Symbol(ref b)) => a == b,
+            _ => false,
+        },
+        "object" | "function" => match (x.data(), y.data()) {
+            (ValueData::Object(ref a), ValueData::Object(ref b)) => std::ptr::eq(&**a, &**b),
+            _ => false,
+        },
         _ => false,
     }
 }
@@ -1,5 +1,5 @@
 use super::*;
-use crate::{forward, Executor, Realm};
+use crate::{forward, forward_val, Executor, Realm};
 
 #[test]
 fn check_is_object() {
@@ -94,3 +94,17 @@ fn abstract_equality_comparison() {
     // assert_eq!(forward(&mut engine, "'foo' == NaN"), "false");
     // assert_eq!(forward(&mut engine, "NaN == NaN"), "false");
 }
+
+#[test]
+fn display_own_properties() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let object = forward_val(&mut engine, "({ a: 1, b: { c: 'd' } })").unwrap();
+    assert_eq!(
+        object.display_own_properties(),
+        "{\n    a: 1,\n    b: {\n        c: d\n    }\n}"
+    );
+    let error = forward_val(&mut engine, "new TypeError('bad')").unwrap();
+    assert_eq!(error.display_own_properties(), "TypeError: bad");
+    assert_eq!(Value::from(1).display_own_properties(), "1");
+}
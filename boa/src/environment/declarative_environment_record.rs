@@ -14,6 +14,7 @@ use crate::{
 };
 use gc::{Finalize, Trace};
 use rustc_hash::FxHashMap;
+use std::any::Any;
 
 /// Declarative Bindings have a few properties for book keeping purposes, such as mutability (const vs let).
 /// Can it be deleted? and strict mode.
@@ -68,7 +69,7 @@ impl EnvironmentRecordTrait for DeclarativeEnvironmentRecord {
             name,
             DeclarativeEnvironmentRecordBinding {
                 value: None,
-                can_delete: true,
+                can_delete: false,
                 mutable: false,
                 strict,
             },
@@ -88,6 +89,18 @@ impl EnvironmentRecordTrait for DeclarativeEnvironmentRecord {
         }
     }
 
+    fn is_initialized_binding(&self, name: &str) -> bool {
+        self.env_rec
+            .get(name)
+            .map_or(false, |binding| binding.value.is_some())
+    }
+
+    fn is_mutable_binding(&self, name: &str) -> bool {
+        self.env_rec
+            .get(name)
+            .map_or(true, |binding| binding.mutable)
+    }
+
     #[allow(clippy::else_if_without_else)]
     fn set_mutable_binding(&mut self, name: &str, value: Value, mut strict: bool) {
         if self.env_rec.get(name).is_none() {
@@ -149,6 +162,10 @@ impl EnvironmentRecordTrait for DeclarativeEnvironmentRecord {
         false
     }
 
+    fn get_this_binding(&self) -> Value {
+        Value::undefined()
+    }
+
     fn has_super_binding(&self) -> bool {
         false
     }
@@ -175,4 +192,12 @@ impl EnvironmentRecordTrait for DeclarativeEnvironmentRecord {
             None => None,
         }
     }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
@@ -13,7 +13,7 @@ use crate::{
     environment::lexical_environment::{Environment, EnvironmentType},
 };
 use gc::{Finalize, Trace};
-use std::fmt::Debug;
+use std::{any::Any, fmt::Debug};
 
 /// <https://tc39.es/ecma262/#sec-environment-records>
 ///
@@ -38,6 +38,15 @@ pub trait EnvironmentRecordTrait: Debug + Trace + Finalize {
     /// V is the value for the binding and is a value of any ECMAScript language type.
     fn initialize_binding(&mut self, name: &str, value: Value);
 
+    /// Determine if the binding for the String value N in an Environment Record has been initialized.
+    /// The bindings of `let` and `const` declarations stay uninitialized until the declaration is evaluated,
+    /// using them before is a ReferenceError.
+    fn is_initialized_binding(&self, name: &str) -> bool;
+
+    /// Determine if the binding for the String value N in an Environment Record can be set.
+    /// The bindings of `const` declarations are immutable, assigning them is a TypeError.
+    fn is_mutable_binding(&self, name: &str) -> bool;
+
     /// Set the value of an already existing mutable binding in an Environment Record.
     /// The String value `name` is the text of the bound name.
     /// value is the `value` for the binding and may be a value of any ECMAScript language type. S is a Boolean flag.
@@ -60,6 +69,10 @@ pub trait EnvironmentRecordTrait: Debug + Trace + Finalize {
     /// Return true if it does and false if it does not.
     fn has_this_binding(&self) -> bool;
 
+    /// Return the `this` value of an Environment Record which establishes a this binding.
+    /// Other Environment Records return undefined.
+    fn get_this_binding(&self) -> Value;
+
     /// Determine if an Environment Record establishes a super method binding.
     /// Return true if it does and false if it does not.
     fn has_super_binding(&self) -> bool;
@@ -79,4 +92,10 @@ pub trait EnvironmentRecordTrait: Debug + Trace + Finalize {
 
     /// Fetch global variable
     fn get_global_object(&self) -> Option<Value>;
+
+    /// Returns the record as `Any`, to get at the concrete record behind the trait object.
+    fn as_any(&self) -> &dyn Any;
+
+    /// Returns the record as mutable `Any`.
+    fn as_any_mut(&mut self) -> &mut dyn Any;
 }
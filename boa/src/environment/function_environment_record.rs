@@ -18,6 +18,7 @@ use crate::{
 };
 use gc::{Finalize, Trace};
 use rustc_hash::FxHashMap;
+use std::any::Any;
 
 /// Different binding status for `this`.
 /// Usually set on a function environment record
@@ -74,21 +75,6 @@ impl FunctionEnvironmentRecord {
             }
         }
     }
-
-    pub fn get_this_binding(&self) -> Value {
-        match self.this_binding_status {
-            BindingStatus::Lexical => {
-                // TODO: change this when error handling comes into play
-                panic!("There is no this for a lexical function record");
-            }
-            BindingStatus::Uninitialized => {
-                // TODO: change this when error handling comes into play
-                panic!("Reference Error: Unitialised binding for this function");
-            }
-
-            BindingStatus::Initialized => self.this_value.clone(),
-        }
-    }
 }
 
 impl EnvironmentRecordTrait for FunctionEnvironmentRecord {
@@ -125,7 +111,7 @@ impl EnvironmentRecordTrait for FunctionEnvironmentRecord {
             name,
             DeclarativeEnvironmentRecordBinding {
                 value: None,
-                can_delete: true,
+                can_delete: false,
                 mutable: false,
                 strict,
             },
@@ -146,6 +132,18 @@ impl EnvironmentRecordTrait for FunctionEnvironmentRecord {
         }
     }
 
+    fn is_initialized_binding(&self, name: &str) -> bool {
+        self.env_rec
+            .get(name)
+            .map_or(false, |binding| binding.value.is_some())
+    }
+
+    fn is_mutable_binding(&self, name: &str) -> bool {
+        self.env_rec
+            .get(name)
+            .map_or(true, |binding| binding.mutable)
+    }
+
     #[allow(clippy::else_if_without_else)]
     fn set_mutable_binding(&mut self, name: &str, value: Value, mut strict: bool) {
         if self.env_rec.get(name).is_none() {
@@ -219,6 +217,21 @@ impl EnvironmentRecordTrait for FunctionEnvironmentRecord {
         }
     }
 
+    fn get_this_binding(&self) -> Value {
+        match self.this_binding_status {
+            BindingStatus::Lexical => {
+                // TODO: change this when error handling comes into play
+                panic!("There is no this for a lexical function record");
+            }
+            BindingStatus::Uninitialized => {
+                // TODO: change this when error handling comes into play
+                panic!("Reference Error: Unitialised binding for this function");
+            }
+
+            BindingStatus::Initialized => self.this_value.clone(),
+        }
+    }
+
     fn with_base_object(&self) -> Value {
         Value::undefined()
     }
@@ -244,4 +257,12 @@ impl EnvironmentRecordTrait for FunctionEnvironmentRecord {
             None => None,
         }
     }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
@@ -18,6 +18,7 @@ use crate::{
 };
 use gc::{Finalize, Trace};
 use rustc_hash::FxHashSet;
+use std::any::Any;
 
 #[derive(Debug, Trace, Finalize, Clone)]
 pub struct GlobalEnvironmentRecord {
@@ -28,10 +29,6 @@ pub struct GlobalEnvironmentRecord {
 }
 
 impl GlobalEnvironmentRecord {
-    pub fn get_this_binding(&self) -> Value {
-        self.global_this_binding.clone()
-    }
-
     pub fn has_var_declaration(&self, name: &str) -> bool {
         self.var_names.contains(name)
     }
@@ -132,6 +129,20 @@ impl EnvironmentRecordTrait for GlobalEnvironmentRecord {
         panic!("Should not initialized binding without creating first.");
     }
 
+    fn is_initialized_binding(&self, name: &str) -> bool {
+        if self.declarative_record.has_binding(&name) {
+            return self.declarative_record.is_initialized_binding(name);
+        }
+        self.object_record.is_initialized_binding(name)
+    }
+
+    fn is_mutable_binding(&self, name: &str) -> bool {
+        if self.declarative_record.has_binding(&name) {
+            return self.declarative_record.is_mutable_binding(name);
+        }
+        self.object_record.is_mutable_binding(name)
+    }
+
     fn set_mutable_binding(&mut self, name: &str, value: Value, strict: bool) {
         if self.declarative_record.has_binding(&name) {
             return self
@@ -157,12 +168,9 @@ impl EnvironmentRecordTrait for GlobalEnvironmentRecord {
         if global.has_field(name) {
             let status = self.object_record.delete_binding(name);
             if status {
-                let var_names = &mut self.var_names;
-                if var_names.contains(name) {
-                    var_names.remove(name);
-                    return status;
-                }
+                self.var_names.remove(name);
             }
+            return status;
         }
         true
     }
@@ -171,6 +179,10 @@ impl EnvironmentRecordTrait for GlobalEnvironmentRecord {
         true
     }
 
+    fn get_this_binding(&self) -> Value {
+        self.global_this_binding.clone()
+    }
+
     fn has_super_binding(&self) -> bool {
         false
     }
@@ -195,4 +207,12 @@ impl EnvironmentRecordTrait for GlobalEnvironmentRecord {
     fn get_global_object(&self) -> Option<Value> {
         Some(self.global_this_binding.clone())
     }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
@@ -15,7 +15,7 @@ use crate::{
         object_environment_record::ObjectEnvironmentRecord,
     },
 };
-use gc::{Gc, GcCell};
+use gc::{Finalize, Gc, GcCell, Trace};
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::{collections::VecDeque, error, fmt};
 
@@ -24,7 +24,7 @@ pub type Environment = Gc<GcCell<Box<dyn EnvironmentRecordTrait>>>;
 
 /// Give each environment an easy way to declare its own type
 /// This helps with comparisons
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EnvironmentType {
     Declarative,
     Function,
@@ -41,7 +41,7 @@ pub enum VariableScope {
     Function,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Trace, Finalize)]
 pub struct LexicalEnvironment {
     environment_stack: VecDeque<Environment>,
 }
@@ -79,7 +79,12 @@ impl error::Error for EnvironmentError {
 
 impl LexicalEnvironment {
     pub fn new(global: Value) -> Self {
-        let global_env = new_global_environment(global.clone(), global);
+        Self::with_this(global.clone(), global)
+    }
+
+    /// Creates a lexical environment whose global `this` value isn't the global object.
+    pub fn with_this(global: Value, this_value: Value) -> Self {
+        let global_env = new_global_environment(global, this_value);
         let mut lexical_env = Self {
             environment_stack: VecDeque::new(),
         };
@@ -89,9 +94,34 @@ impl LexicalEnvironment {
         lexical_env
     }
 
+    /// Creates a lexical environment from an existing global environment, such as one restored
+    /// from a snapshot.
+    pub(crate) fn from_global_environment(global_env: Environment) -> Self {
+        let mut environment_stack = VecDeque::new();
+        environment_stack.push_back(global_env);
+        Self { environment_stack }
+    }
+
+    /// Creates a lexical environment which only holds the global environment of this one, code run
+    /// in it doesn't see the bindings of the enclosing functions and blocks.
+    pub fn global_scope(&self) -> Self {
+        let mut environment_stack = VecDeque::new();
+        environment_stack.push_back(
+            self.environment_stack
+                .get(0)
+                .expect("No global environment")
+                .clone(),
+        );
+        Self { environment_stack }
+    }
+
     pub fn push(&mut self, env: Environment) {
-        let current_env: Environment = self.get_current_environment().clone();
-        env.borrow_mut().set_outer_environment(current_env);
+        // An environment created with its outer environment keeps it, function environments are
+        // linked to the scope the function was defined in rather than to the one of the caller
+        if env.borrow().get_outer_environment().is_none() {
+            let current_env: Environment = self.get_current_environment().clone();
+            env.borrow_mut().set_outer_environment(current_env);
+        }
         self.environment_stack.push_back(env);
     }
 
@@ -99,6 +129,16 @@ impl LexicalEnvironment {
         self.environment_stack.pop_back()
     }
 
+    /// The number of environments on the stack, the global one included.
+    pub fn depth(&self) -> usize {
+        self.environment_stack.len()
+    }
+
+    /// The environment at the given index of the stack, the global one is at 0.
+    pub fn environment_at(&self, index: usize) -> Option<&Environment> {
+        self.environment_stack.get(index)
+    }
+
     pub fn environments(&self) -> impl Iterator<Item = Environment> {
         std::iter::successors(Some(self.get_current_environment_ref().clone()), |env| {
             env.borrow().get_outer_environment()
@@ -113,6 +153,16 @@ impl LexicalEnvironment {
             .get_global_object()
     }
 
+    /// Returns the `this` value of the global environment, which non-strict functions get when
+    /// they are called without one.
+    pub fn get_global_this(&self) -> Value {
+        self.environment_stack
+            .get(0)
+            .expect("No global environment")
+            .borrow()
+            .get_this_binding()
+    }
+
     pub fn create_mutable_binding(&mut self, name: String, deletion: bool, scope: VariableScope) {
         match scope {
             VariableScope::Block => self
@@ -174,6 +224,21 @@ impl LexicalEnvironment {
         env.borrow_mut().set_mutable_binding(name, value, strict);
     }
 
+    /// Sets the binding of the nearest function or global environment, if it has one for the name.
+    pub fn set_var_binding(&mut self, name: &str, value: Value) {
+        let env = self
+            .environments()
+            .find(|env| match env.borrow().get_environment_type() {
+                EnvironmentType::Function | EnvironmentType::Global => true,
+                _ => false,
+            })
+            .expect("No function or global environment");
+
+        if env.borrow().has_binding(name) {
+            env.borrow_mut().set_mutable_binding(name, value, false);
+        }
+    }
+
     pub fn initialize_binding(&mut self, name: &str, value: Value) {
         // Find the first environment which has the given binding
         let env = self
@@ -207,6 +272,40 @@ impl LexicalEnvironment {
             .any(|env| env.borrow().has_binding(name))
     }
 
+    /// Checks if the nearest binding of the name is initialized, a `let` or `const` binding can't
+    /// be used before its declaration. Names which can't be resolved have no such restriction.
+    pub fn is_initialized_binding(&self, name: &str) -> bool {
+        self.environments()
+            .find(|env| env.borrow().has_binding(name))
+            .map_or(true, |env| env.borrow().is_initialized_binding(name))
+    }
+
+    /// Checks if the nearest binding of the name can be set, `const` bindings can't. Names which
+    /// can't be resolved can be set.
+    pub fn is_mutable_binding(&self, name: &str) -> bool {
+        self.environments()
+            .find(|env| env.borrow().has_binding(name))
+            .map_or(true, |env| env.borrow().is_mutable_binding(name))
+    }
+
+    /// Deletes the binding of the nearest environment which has it, a name which can't be resolved
+    /// counts as deleted.
+    pub fn delete_binding(&mut self, name: &str) -> bool {
+        self.environments()
+            .find(|env| env.borrow().has_binding(name))
+            .map_or(true, |env| env.borrow_mut().delete_binding(name))
+    }
+
+    /// Resolves the `this` value from the nearest environment which establishes a this binding.
+    ///
+    /// <https://tc39.es/ecma262/#sec-resolvethisbinding>
+    pub fn get_this_binding(&self) -> Value {
+        self.environments()
+            .find(|env| env.borrow().has_this_binding())
+            .map(|env| env.borrow().get_this_binding())
+            .unwrap_or_else(Value::undefined)
+    }
+
     pub fn get_binding_value(&self, name: &str) -> Value {
         self.environments()
             .find(|env| env.borrow().has_binding(name))
@@ -224,20 +323,27 @@ pub fn new_declarative_environment(env: Option<Environment>) -> Environment {
     Gc::new(GcCell::new(boxed_env))
 }
 
+/// Creates the environment of a function call, `this` is `None` for arrow functions which take
+/// the `this` value of their enclosing scope.
 pub fn new_function_environment(
     f: Value,
+    this: Option<Value>,
     new_target: Value,
     outer: Option<Environment>,
 ) -> Environment {
     debug_assert!(new_target.is_object() || new_target.is_undefined());
+    let (this_binding_status, this_value) = match this {
+        Some(this) => (BindingStatus::Initialized, this),
+        None => (BindingStatus::Lexical, Value::undefined()),
+    };
     Gc::new(GcCell::new(Box::new(FunctionEnvironmentRecord {
         env_rec: FxHashMap::default(),
         function: f,
-        this_binding_status: BindingStatus::Uninitialized, // hardcoding to unitialized for now until short functions are properly supported
+        this_binding_status,
         home_object: Value::undefined(),
         new_target,
         outer_env: outer, // this will come from Environment set as a private property of F - https://tc39.es/ecma262/#sec-ecmascript-function-objects
-        this_value: Value::undefined(), // TODO: this_value should start as an Option as its not always there to begin with
+        this_value,
     })))
 }
 
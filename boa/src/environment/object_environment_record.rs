@@ -7,13 +7,16 @@
 //! More info:  [Object Records](https://tc39.es/ecma262/#sec-object-environment-records)
 
 use crate::{
-    builtins::{property::Property, value::Value},
+    builtins::{
+        object::internal_methods_trait::ObjectInternalMethods, property::Property, value::Value,
+    },
     environment::{
         environment_record_trait::EnvironmentRecordTrait,
         lexical_environment::{Environment, EnvironmentType},
     },
 };
 use gc::{Finalize, Trace};
+use std::any::Any;
 
 #[derive(Debug, Trace, Finalize, Clone)]
 pub struct ObjectEnvironmentRecord {
@@ -59,11 +62,19 @@ impl EnvironmentRecordTrait for ObjectEnvironmentRecord {
         self.set_mutable_binding(name, value, false)
     }
 
-    fn set_mutable_binding(&mut self, name: &str, value: Value, strict: bool) {
-        debug_assert!(value.is_object() || value.is_function());
+    fn is_initialized_binding(&self, name: &str) -> bool {
+        // The bindings are the properties of the object, which always have a value
+        self.has_binding(name)
+    }
 
-        let bindings = &mut self.bindings;
-        bindings.update_property(name, Some(value), None, None, Some(strict));
+    fn is_mutable_binding(&self, _name: &str) -> bool {
+        // Read-only properties are left to `[[Set]]`
+        true
+    }
+
+    fn set_mutable_binding(&mut self, name: &str, value: Value, _strict: bool) {
+        // The assignment goes through `[[Set]]`, so read-only properties like `NaN` are kept
+        self.bindings.set_field_slice(name, value);
     }
 
     fn get_binding_value(&self, name: &str, strict: bool) -> Value {
@@ -79,14 +90,20 @@ impl EnvironmentRecordTrait for ObjectEnvironmentRecord {
     }
 
     fn delete_binding(&mut self, name: &str) -> bool {
-        self.bindings.remove_property(name);
-        true
+        match self.bindings.as_object_mut() {
+            Some(mut bindings) => bindings.delete(&Value::from(name)),
+            None => true,
+        }
     }
 
     fn has_this_binding(&self) -> bool {
         false
     }
 
+    fn get_this_binding(&self) -> Value {
+        Value::undefined()
+    }
+
     fn has_super_binding(&self) -> bool {
         false
     }
@@ -123,4 +140,12 @@ impl EnvironmentRecordTrait for ObjectEnvironmentRecord {
             None
         }
     }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
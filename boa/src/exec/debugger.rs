@@ -0,0 +1,336 @@
+//! The debugger of the interpreter.
+//!
+//! Scripts parsed [with locations][locations] mark the line of each of their statements, the
+//! interpreter can then stop before a statement: on a line with a breakpoint, at a `debugger`
+//! statement, when a step ends, or when a pause was asked for through a [`PauseHandle`]. Nothing
+//! stops unless a [`DebugHandler`] is set. The handler gets the paused interpreter, to look at its
+//! stack frames and their scopes, and tells it how to go on.
+//!
+//! [locations]: ../../syntax/parser/struct.Parser.html#method.with_locations
+
+use super::{frame_name, Interpreter, SCRIPT_FRAME_NAME};
+use crate::{
+    builtins::{
+        eval,
+        value::{JsString, ResultValue, Value},
+    },
+    environment::{
+        declarative_environment_record::DeclarativeEnvironmentRecord,
+        function_environment_record::FunctionEnvironmentRecord,
+        global_environment_record::GlobalEnvironmentRecord,
+        lexical_environment::{Environment, EnvironmentType},
+        object_environment_record::ObjectEnvironmentRecord,
+    },
+    syntax::ast::pos::Position,
+};
+use rustc_hash::FxHashSet;
+use std::{
+    fmt, iter, mem,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+/// Why the interpreter stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PauseReason {
+    /// The statement is on a line with a breakpoint.
+    Breakpoint,
+    /// A `debugger` statement was run.
+    DebuggerStatement,
+    /// A step ended at the statement.
+    Step,
+    /// A pause was asked for through a [`PauseHandle`].
+    Requested,
+}
+
+/// How the interpreter goes on after a pause.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resume {
+    /// Runs until the next breakpoint or `debugger` statement.
+    Continue,
+    /// Stops at the next statement, in a function it calls if there is one.
+    StepIn,
+    /// Stops at the next statement of the current function, or of its caller once it returns.
+    StepOver,
+    /// Stops at the next statement of the caller of the current function.
+    StepOut,
+}
+
+/// The host side of the debugger, called whenever the interpreter stops.
+pub trait DebugHandler: fmt::Debug {
+    /// Called when the interpreter stops before a statement, it resumes once this returns.
+    ///
+    /// The handler can look at the stack frames and scopes of the interpreter and run code in it
+    /// with `Interpreter::debug_eval`, it isn't called again for that code.
+    fn paused(&mut self, engine: &mut Interpreter, reason: PauseReason) -> Resume;
+}
+
+/// A handle which makes the interpreter stop at its next statement, it can be sent to and triggered
+/// from another thread.
+#[derive(Debug, Clone)]
+pub struct PauseHandle(Arc<AtomicBool>);
+
+impl PauseHandle {
+    /// Asks the interpreter to stop before its next statement, with `PauseReason::Requested`.
+    pub fn pause(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+/// A function being run, or the script at the bottom of the stack.
+#[derive(Debug, Clone)]
+pub struct StackFrame {
+    /// The name of the function.
+    pub name: String,
+    /// The position of the statement being run, `None` until the frame reaches a statement of
+    /// a script parsed with locations.
+    pub position: Option<Position>,
+    /// The number of environments on the stack when the frame was entered
+    environment_depth: usize,
+}
+
+/// An environment of the scope chain of a stack frame, with its bindings.
+#[derive(Debug, Clone)]
+pub struct Scope {
+    environment: Environment,
+}
+
+impl Scope {
+    /// The kind of environment: a block, a function, a `with` statement, or the global one.
+    pub fn kind(&self) -> EnvironmentType {
+        self.environment.borrow().get_environment_type()
+    }
+
+    /// The bindings of the environment sorted by name, with their value if they are initialised.
+    ///
+    /// The global scope lists the declarations of the scripts rather than every property of the
+    /// global object.
+    pub fn variables(&self) -> Vec<(String, Option<Value>)> {
+        let record = self.environment.borrow();
+        let any = record.as_any();
+        let mut names: Vec<String> =
+            if let Some(record) = any.downcast_ref::<DeclarativeEnvironmentRecord>() {
+                record.env_rec.keys().cloned().collect()
+            } else if let Some(record) = any.downcast_ref::<FunctionEnvironmentRecord>() {
+                record.env_rec.keys().cloned().collect()
+            } else if let Some(record) = any.downcast_ref::<GlobalEnvironmentRecord>() {
+                record
+                    .declarative_record
+                    .env_rec
+                    .keys()
+                    .chain(record.var_names.iter())
+                    .cloned()
+                    .collect()
+            } else if let Some(record) = any.downcast_ref::<ObjectEnvironmentRecord>() {
+                record
+                    .bindings
+                    .as_object()
+                    .map(|object| {
+                        object
+                            .properties
+                            .keys()
+                            .map(JsString::to_std_string_lossy)
+                            .collect()
+                    })
+                    .unwrap_or_default()
+            } else {
+                Vec::new()
+            };
+        names.sort();
+        names.dedup();
+        names
+            .into_iter()
+            .map(|name| {
+                let value = if record.is_initialized_binding(&name) {
+                    Some(record.get_binding_value(&name, false))
+                } else {
+                    None
+                };
+                (name, value)
+            })
+            .collect()
+    }
+}
+
+/// The breakpoints and the stepping state of an interpreter.
+#[derive(Debug)]
+pub(crate) struct Debugger {
+    handler: Option<Box<dyn DebugHandler>>,
+    /// The lines with a breakpoint
+    breakpoints: FxHashSet<u64>,
+    /// How the last pause was resumed, with the number of frames at that time
+    step: Option<(Resume, usize)>,
+    /// The frames of the functions being run, the one of the script first
+    frames: Vec<StackFrame>,
+    pause: Arc<AtomicBool>,
+    /// Whether the interpreter stopped before the statement being run, a `debugger` statement
+    /// reached by a step doesn't stop a second time
+    paused_at_statement: bool,
+}
+
+impl Debugger {
+    pub(crate) fn new() -> Self {
+        Self {
+            handler: None,
+            breakpoints: FxHashSet::default(),
+            step: None,
+            frames: vec![StackFrame {
+                name: SCRIPT_FRAME_NAME.to_string(),
+                position: None,
+                environment_depth: 0,
+            }],
+            pause: Arc::new(AtomicBool::new(false)),
+            paused_at_statement: false,
+        }
+    }
+
+    /// Checks if a handler is set, frames are only tracked then.
+    pub(crate) fn is_attached(&self) -> bool {
+        self.handler.is_some()
+    }
+
+    /// Decides if the interpreter stops before a statement of the current frame at the given
+    /// position.
+    fn reach(&mut self, position: Position) -> Option<PauseReason> {
+        let depth = self.frames.len();
+        if let Some(frame) = self.frames.last_mut() {
+            frame.position = Some(position);
+        }
+        let stepped = match self.step {
+            Some((Resume::StepIn, _)) => true,
+            Some((Resume::StepOver, from)) => depth <= from,
+            Some((Resume::StepOut, from)) => depth < from,
+            _ => false,
+        };
+        let reason = if self.pause.swap(false, Ordering::SeqCst) {
+            Some(PauseReason::Requested)
+        } else if self.breakpoints.contains(&position.line_number) {
+            Some(PauseReason::Breakpoint)
+        } else if stepped {
+            Some(PauseReason::Step)
+        } else {
+            None
+        };
+        self.paused_at_statement = reason.is_some();
+        reason
+    }
+}
+
+impl Interpreter {
+    /// Sets the handler called when the interpreter stops, `None` detaches the debugger and
+    /// nothing stops anymore.
+    pub fn set_debug_handler(&mut self, handler: Option<Box<dyn DebugHandler>>) {
+        self.debugger.handler = handler;
+        self.debugger.step = None;
+    }
+
+    /// Replaces the breakpoints with the given lines, the interpreter stops before the statements
+    /// starting on them.
+    pub fn set_breakpoints<I>(&mut self, lines: I)
+    where
+        I: IntoIterator<Item = u64>,
+    {
+        self.debugger.breakpoints = lines.into_iter().collect();
+    }
+
+    /// Returns a handle which makes the interpreter stop, from the host or another thread.
+    pub fn pause_handle(&self) -> PauseHandle {
+        PauseHandle(self.debugger.pause.clone())
+    }
+
+    /// The frames of the running functions, from the innermost to the script. Frames are only
+    /// tracked while a debug handler is set.
+    pub fn stack_frames(&self) -> Vec<StackFrame> {
+        self.debugger.frames.iter().rev().cloned().collect()
+    }
+
+    /// The scope chain of a frame, from its innermost environment to the global one. Frames are
+    /// counted like in `stack_frames`, from the innermost one.
+    pub fn scopes(&self, frame: usize) -> Vec<Scope> {
+        let frames = &self.debugger.frames;
+        let index = match frames.len().checked_sub(frame + 1) {
+            Some(index) => index,
+            None => return Vec::new(),
+        };
+        let depth = frames
+            .get(index + 1)
+            .map_or_else(|| self.realm.environment.depth(), |f| f.environment_depth);
+        let innermost = match depth
+            .checked_sub(1)
+            .and_then(|i| self.realm.environment.environment_at(i))
+        {
+            Some(environment) => environment.clone(),
+            None => return Vec::new(),
+        };
+        iter::successors(Some(innermost), |env| env.borrow().get_outer_environment())
+            .map(|environment| Scope { environment })
+            .collect()
+    }
+
+    /// Evaluates code in the scope of the innermost frame, like a direct `eval`. The debugger
+    /// doesn't stop in it.
+    pub fn debug_eval(&mut self, source: &str) -> ResultValue {
+        let handler = self.debugger.handler.take();
+        let step = self.debugger.step.take();
+        let is_return = self.is_return;
+        let result = eval::perform_eval(&Value::from(source), true, self);
+        self.is_return = is_return;
+        self.debugger.handler = handler;
+        self.debugger.step = step;
+        result
+    }
+
+    /// Called before a statement at the given position, stops if there is a reason to.
+    pub(crate) fn reach_statement(&mut self, position: Position) {
+        if !self.debugger.is_attached() {
+            return;
+        }
+        if let Some(reason) = self.debugger.reach(position) {
+            self.pause(reason);
+        }
+    }
+
+    /// Stops at a `debugger` statement.
+    pub(crate) fn reach_debugger_statement(&mut self) {
+        let paused = mem::replace(&mut self.debugger.paused_at_statement, false);
+        if self.debugger.is_attached() && !paused {
+            self.pause(PauseReason::DebuggerStatement);
+        }
+    }
+
+    /// Hands the interpreter to the debug handler until it resumes.
+    fn pause(&mut self, reason: PauseReason) {
+        let mut handler = match self.debugger.handler.take() {
+            Some(handler) => handler,
+            None => return,
+        };
+        self.debugger.step = None;
+        let resume = handler.paused(self, reason);
+        self.debugger.handler.get_or_insert(handler);
+        self.debugger.step = match resume {
+            Resume::Continue => None,
+            step => Some((step, self.debugger.frames.len())),
+        };
+    }
+
+    /// Runs `f` in a new stack frame for the given function, when the debugger is attached.
+    pub(crate) fn run_frame<F>(&mut self, function: &Value, f: F) -> ResultValue
+    where
+        F: FnOnce(&mut Self) -> ResultValue,
+    {
+        if !self.debugger.is_attached() {
+            return f(self);
+        }
+        self.debugger.frames.push(StackFrame {
+            name: frame_name(function),
+            position: None,
+            environment_depth: self.realm.environment.depth(),
+        });
+        let depth = self.debugger.frames.len();
+        let result = f(self);
+        self.debugger.frames.truncate(depth - 1);
+        result
+    }
+}
@@ -0,0 +1,627 @@
+//! Declaration instantiation, the bindings declared by a script, a function body, a block or
+//! eval code are created before any of its statements run.
+//!
+//! Function declarations are initialised right away and `var` bindings start out as `undefined`,
+//! so both can be used before their declaration. The bindings of `let` and `const` are created
+//! uninitialised, using them before their declaration is evaluated throws a `ReferenceError`.
+
+use super::{has_use_strict_directive, Interpreter};
+use crate::{
+    builtins::{
+        function::ThisMode,
+        object::internal_methods_trait::ObjectInternalMethods,
+        property::Property,
+        value::{ResultValue, Value},
+    },
+    environment::{
+        global_environment_record::GlobalEnvironmentRecord,
+        lexical_environment::{new_declarative_environment, Environment, EnvironmentType},
+    },
+    syntax::ast::node::{FormalParameter, Node},
+};
+use std::iter;
+
+/// A declaration which creates its binding in the lexical environment of a script, function
+/// body or block, instead of the variable environment.
+enum LexicalDeclaration<'a> {
+    Let(&'a str),
+    Const(&'a str),
+    /// Function declarations are lexical in blocks, and var scoped everywhere else.
+    Function(&'a Node),
+}
+
+impl LexicalDeclaration<'_> {
+    fn name(&self) -> &str {
+        match *self {
+            LexicalDeclaration::Let(name) | LexicalDeclaration::Const(name) => name,
+            LexicalDeclaration::Function(&Node::FunctionDecl(ref name, ..)) => name,
+            LexicalDeclaration::Function(_) => unreachable!("not a function declaration"),
+        }
+    }
+}
+
+/// The statements nested in a statement, not looking into functions.
+fn nested_statements(node: &Node) -> Vec<&Node> {
+    match *node {
+        Node::Block(ref list) => list.iter().collect(),
+        Node::If(_, ref then, ref otherwise) => {
+            iter::once(&**then).chain(otherwise.as_deref()).collect()
+        }
+        Node::WhileLoop(_, ref body) | Node::DoWhileLoop(ref body, _) => vec![body],
+        Node::ForLoop(ref init, _, _, ref body) => init
+            .as_deref()
+            .into_iter()
+            .chain(iter::once(&**body))
+            .collect(),
+        Node::Switch(_, ref cases, ref default) => cases
+            .iter()
+            .flat_map(|(_, list)| list.iter())
+            .chain(default.as_deref())
+            .collect(),
+        Node::Try(ref block, ref catch, _, ref finally) => iter::once(&**block)
+            .chain(catch.as_deref())
+            .chain(finally.as_deref())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Collects the names declared with `var` in a statement and in the statements nested in it.
+///
+/// <https://tc39.es/ecma262/#sec-static-semantics-vardeclarednames>
+fn var_declared_names(node: &Node, names: &mut Vec<String>) {
+    // Statements are nested as deep as the parser allows, they are walked without recursing
+    let mut pending = vec![node];
+    while let Some(node) = pending.pop() {
+        if let Node::VarDecl(ref vars) = *node {
+            names.extend(vars.iter().map(|(name, _)| name.clone()));
+        }
+        pending.extend(nested_statements(node).into_iter().rev());
+    }
+}
+
+/// The names declared with `var` in the code of a script, a function or eval, function
+/// declarations at its top level are var scoped too.
+///
+/// <https://tc39.es/ecma262/#sec-static-semantics-toplevelvardeclarednames>
+fn top_level_var_declared_names(list: &[Node]) -> Vec<String> {
+    let mut names = Vec::new();
+    for statement in list {
+        match *statement {
+            Node::FunctionDecl(ref name, ..) => names.push(name.clone()),
+            _ => var_declared_names(statement, &mut names),
+        }
+    }
+    names
+}
+
+/// Collects the names of the functions declared in blocks nested in a statement. In non-strict
+/// code they also get a `var` binding, which is assigned when the declaration is evaluated.
+///
+/// <https://tc39.es/ecma262/#sec-block-level-function-declarations-web-legacy-compatibility-semantics>
+fn block_function_names(node: &Node, names: &mut Vec<String>) {
+    // Walked without recursing like `var_declared_names`, with whether each statement is
+    // directly in a block
+    let mut pending = vec![(node, false)];
+    while let Some((node, in_block)) = pending.pop() {
+        match *node {
+            Node::FunctionDecl(ref name, ..) if in_block => names.push(name.clone()),
+            _ => {
+                let block = match *node {
+                    Node::Block(_) | Node::Switch(..) => true,
+                    _ => false,
+                };
+                pending.extend(
+                    nested_statements(node)
+                        .into_iter()
+                        .rev()
+                        .map(|statement| (statement, block)),
+                );
+            }
+        }
+    }
+}
+
+/// The `let`, `const` and, only for blocks, function declarations of a statement list.
+///
+/// <https://tc39.es/ecma262/#sec-static-semantics-lexicallyscopeddeclarations>
+fn lexically_scoped_declarations(list: &[Node], block: bool) -> Vec<LexicalDeclaration<'_>> {
+    let mut declarations = Vec::new();
+    for statement in list {
+        match *statement {
+            Node::LetDecl(ref vars) => declarations.extend(
+                vars.iter()
+                    .map(|(name, _)| LexicalDeclaration::Let(name.as_str())),
+            ),
+            Node::ConstDecl(ref vars) => declarations.extend(
+                vars.iter()
+                    .map(|(name, _)| LexicalDeclaration::Const(name.as_str())),
+            ),
+            Node::FunctionDecl(..) if block => {
+                declarations.push(LexicalDeclaration::Function(statement))
+            }
+            _ => {}
+        }
+    }
+    declarations
+}
+
+/// The function declarations at the top level of a script, function or eval code, the last
+/// declaration of a name wins.
+fn functions_to_initialize(list: &[Node]) -> Vec<&Node> {
+    let mut names: Vec<&str> = Vec::new();
+    let mut functions = Vec::new();
+    for statement in list.iter().rev() {
+        if let Node::FunctionDecl(ref name, ..) = *statement {
+            if !names.contains(&name.as_str()) {
+                names.push(name);
+                functions.push(statement);
+            }
+        }
+    }
+    functions.reverse();
+    functions
+}
+
+impl Interpreter {
+    /// Runs a script, its declarations are instantiated in the global environment.
+    pub(super) fn run_script(&mut self, script: &[Node]) -> ResultValue {
+        let strict = self.strict || has_use_strict_directive(script);
+        self.run_in_mode(strict, |interpreter| {
+            interpreter.global_declaration_instantiation(script)?;
+            interpreter.run_statements(script)
+        })
+    }
+
+    /// Runs the body of a function once the environment of the call holds its parameters, its
+    /// declarations are instantiated in that environment.
+    pub(crate) fn run_function_body(
+        &mut self,
+        params: &[FormalParameter],
+        body: &Node,
+    ) -> ResultValue {
+        let list = match *body {
+            Node::StatementList(ref list) => list,
+            // The concise body of an arrow function is an expression
+            _ => return self.run_node(body),
+        };
+
+        // Top level lexical declarations get their own environment, so that eval code in the
+        // function can tell them apart from the var scoped ones
+        let var_env = self.realm.environment.get_current_environment().clone();
+        self.realm
+            .environment
+            .push(new_declarative_environment(Some(var_env.clone())));
+        let result = match self.function_declaration_instantiation(params, list, &var_env) {
+            Ok(()) => self.run_statements(list),
+            Err(error) => Err(error),
+        };
+        let _ = self.realm.environment.pop();
+        result
+    }
+
+    /// Runs a block in a new declarative environment holding its declarations.
+    pub(super) fn run_block(&mut self, block: &[Node]) -> ResultValue {
+        {
+            let env = &mut self.realm.environment;
+            env.push(new_declarative_environment(Some(
+                env.get_current_environment_ref().clone(),
+            )));
+        }
+
+        let result = match self.block_declaration_instantiation(block) {
+            Ok(()) => self.run_statements(block),
+            Err(error) => Err(error),
+        };
+
+        // pop the block env
+        let _ = self.realm.environment.pop();
+        result
+    }
+
+    /// Runs the code of a direct or indirect eval in the current environment. Its lexical
+    /// declarations stay in the eval code, the others are added to the variable environment
+    /// unless the code is strict.
+    pub(crate) fn run_eval_code(&mut self, body: &[Node]) -> ResultValue {
+        let strict = self.strict || has_use_strict_directive(body);
+        self.run_in_mode(strict, |interpreter| {
+            {
+                let env = &mut interpreter.realm.environment;
+                env.push(new_declarative_environment(Some(
+                    env.get_current_environment_ref().clone(),
+                )));
+            }
+
+            let result = match interpreter.eval_declaration_instantiation(body) {
+                Ok(()) => interpreter.run_statements(body),
+                Err(error) => Err(error),
+            };
+            let _ = interpreter.realm.environment.pop();
+            result
+        })
+    }
+
+    /// Creates the function object of a function declaration in the current environment.
+    ///
+    /// <https://tc39.es/ecma262/#sec-runtime-semantics-instantiatefunctionobject>
+    fn instantiate_function_object(&mut self, declaration: &Node) -> Value {
+        match *declaration {
+            Node::FunctionDecl(ref name, ref args, ref body, ref source_text) => {
+                let function = self.create_function(args, body, ThisMode::NonLexical, source_text);
+                function.set_field_slice("name", Value::from(name.clone()));
+                function
+            }
+            _ => unreachable!("not a function declaration"),
+        }
+    }
+
+    /// The early errors of redeclarations, lexically declared names must be unique and can't be
+    /// declared with `var` too. Non-strict blocks can declare a function more than once.
+    fn check_redeclarations(
+        &self,
+        declarations: &[LexicalDeclaration<'_>],
+        var_names: &[String],
+    ) -> Result<(), Value> {
+        for (index, declaration) in declarations.iter().enumerate() {
+            let name = declaration.name();
+            let redeclared = declarations[..index].iter().any(|previous| {
+                previous.name() == name
+                    && (self.strict
+                        || match (previous, declaration) {
+                            (LexicalDeclaration::Function(_), LexicalDeclaration::Function(_)) => {
+                                false
+                            }
+                            _ => true,
+                        })
+            });
+            if redeclared || var_names.iter().any(|var_name| var_name == name) {
+                return Err(self.redeclaration_error(name));
+            }
+        }
+        Ok(())
+    }
+
+    fn redeclaration_error(&self, name: &str) -> Value {
+        self.construct_syntax_error(format!("Identifier '{}' has already been declared", name))
+    }
+
+    /// Creates the uninitialised bindings of `let` and `const` declarations, and the initialised
+    /// bindings of the functions declared in a block.
+    fn instantiate_lexical_declarations(
+        &mut self,
+        declarations: &[LexicalDeclaration<'_>],
+        env: &Environment,
+    ) {
+        for declaration in declarations {
+            match *declaration {
+                LexicalDeclaration::Let(name) => env
+                    .borrow_mut()
+                    .create_mutable_binding(name.to_string(), false),
+                LexicalDeclaration::Const(name) => {
+                    env.borrow_mut()
+                        .create_immutable_binding(name.to_string(), true);
+                }
+                LexicalDeclaration::Function(function) => {
+                    let name = declaration.name();
+                    let function = self.instantiate_function_object(function);
+                    let mut env = env.borrow_mut();
+                    if !env.has_binding(name) {
+                        env.create_mutable_binding(name.to_string(), false);
+                    }
+                    if env.is_initialized_binding(name) {
+                        env.set_mutable_binding(name, function, false);
+                    } else {
+                        env.initialize_binding(name, function);
+                    }
+                }
+            }
+        }
+    }
+
+    /// The global environment of the realm.
+    fn global_environment(&self) -> Environment {
+        self.realm
+            .environment
+            .environments()
+            .last()
+            .expect("No global environment")
+    }
+
+    /// Checks if a `let` or `const` declaration of a script created a binding with the name.
+    fn has_lexical_declaration(&self, name: &str) -> bool {
+        self.global_environment().borrow().has_binding(name)
+            && self.global_own_property(name).is_none()
+    }
+
+    fn global_own_property(&self, name: &str) -> Property {
+        self.realm
+            .global_obj
+            .as_object()
+            .expect("the global object is an object")
+            .get_own_property(&Value::from(name))
+    }
+
+    /// <https://tc39.es/ecma262/#sec-hasrestrictedglobalproperty>
+    fn has_restricted_global_property(&self, name: &str) -> bool {
+        let property = self.global_own_property(name);
+        !property.is_none() && !property.configurable.unwrap_or(false)
+    }
+
+    /// <https://tc39.es/ecma262/#sec-candeclareglobalfunction>
+    fn can_declare_global_function(&self, name: &str) -> bool {
+        let property = self.global_own_property(name);
+        if property.is_none() {
+            return self.realm.global_obj.is_extensible();
+        }
+        property.configurable.unwrap_or(false)
+            || (property.is_data_descriptor()
+                && property.writable.unwrap_or(false)
+                && property.enumerable.unwrap_or(false))
+    }
+
+    /// <https://tc39.es/ecma262/#sec-candeclareglobalvar>
+    fn can_declare_global_var(&self, name: &str) -> bool {
+        !self.global_own_property(name).is_none() || self.realm.global_obj.is_extensible()
+    }
+
+    /// <https://tc39.es/ecma262/#sec-createglobalvarbinding>
+    fn create_global_var_binding(&mut self, name: &str, deletion: bool) {
+        if self.global_own_property(name).is_none() {
+            let property = Property::new()
+                .value(Value::undefined())
+                .writable(true)
+                .enumerable(true)
+                .configurable(deletion);
+            self.realm
+                .global_obj
+                .as_object_mut()
+                .expect("the global object is an object")
+                .define_own_property(Value::from(name), property);
+        }
+        self.add_global_var_name(name);
+    }
+
+    /// <https://tc39.es/ecma262/#sec-createglobalfunctionbinding>
+    fn create_global_function_binding(&mut self, name: &str, function: Value, deletion: bool) {
+        let existing = self.global_own_property(name);
+        if existing.is_none() || existing.configurable.unwrap_or(false) {
+            let property = Property::new()
+                .value(function)
+                .writable(true)
+                .enumerable(true)
+                .configurable(deletion);
+            self.realm
+                .global_obj
+                .as_object_mut()
+                .expect("the global object is an object")
+                .define_own_property(Value::from(name), property);
+        } else {
+            self.realm.global_obj.set_field_slice(name, function);
+        }
+        self.add_global_var_name(name);
+    }
+
+    /// Records a `var` or function declaration of a script in the `[[VarNames]]` of the global
+    /// environment, which tell them from the other properties of the global object.
+    fn add_global_var_name(&self, name: &str) {
+        let global_env = self.global_environment();
+        let mut global_env = global_env.borrow_mut();
+        if let Some(record) = global_env
+            .as_any_mut()
+            .downcast_mut::<GlobalEnvironmentRecord>()
+        {
+            record.var_names.insert(name.to_string());
+        }
+    }
+
+    /// <https://tc39.es/ecma262/#sec-globaldeclarationinstantiation>
+    fn global_declaration_instantiation(&mut self, script: &[Node]) -> Result<(), Value> {
+        let declarations = lexically_scoped_declarations(script, false);
+        let mut var_names = top_level_var_declared_names(script);
+        self.check_redeclarations(&declarations, &var_names)?;
+
+        for declaration in &declarations {
+            let name = declaration.name();
+            if self.has_lexical_declaration(name) || self.has_restricted_global_property(name) {
+                return Err(self.redeclaration_error(name));
+            }
+        }
+        for name in &var_names {
+            if self.has_lexical_declaration(name) {
+                return Err(self.redeclaration_error(name));
+            }
+        }
+
+        let functions = functions_to_initialize(script);
+        for function in &functions {
+            if let Node::FunctionDecl(ref name, ..) = **function {
+                if !self.can_declare_global_function(name) {
+                    return Err(self.construct_type_error(format!(
+                        "Cannot declare global function '{}'",
+                        name
+                    )));
+                }
+            }
+        }
+        if !self.strict {
+            for statement in script {
+                block_function_names(statement, &mut var_names);
+            }
+            var_names.retain(|name| {
+                declarations
+                    .iter()
+                    .all(|declaration| declaration.name() != name)
+            });
+        }
+        for name in &var_names {
+            if !self.can_declare_global_var(name) {
+                return Err(
+                    self.construct_type_error(format!("Cannot declare global variable '{}'", name))
+                );
+            }
+        }
+
+        let global_env = self.global_environment();
+        self.instantiate_lexical_declarations(&declarations, &global_env);
+        for function in functions {
+            if let Node::FunctionDecl(ref name, ..) = *function {
+                let function = self.instantiate_function_object(function);
+                self.create_global_function_binding(name, function, false);
+            }
+        }
+        for name in &var_names {
+            self.create_global_var_binding(name, false);
+        }
+        Ok(())
+    }
+
+    /// The declarations of a function body, the parameters and the `arguments` object are
+    /// already bound in the variable environment of the call.
+    ///
+    /// <https://tc39.es/ecma262/#sec-functiondeclarationinstantiation>
+    fn function_declaration_instantiation(
+        &mut self,
+        params: &[FormalParameter],
+        body: &[Node],
+        var_env: &Environment,
+    ) -> Result<(), Value> {
+        let declarations = lexically_scoped_declarations(body, false);
+        let mut var_names = top_level_var_declared_names(body);
+        let mut declared_names = var_names.clone();
+        declared_names.extend(params.iter().map(|param| param.name.clone()));
+        self.check_redeclarations(&declarations, &declared_names)?;
+
+        if !self.strict {
+            let mut function_names = Vec::new();
+            for statement in body {
+                block_function_names(statement, &mut function_names);
+            }
+            var_names.extend(function_names.into_iter().filter(|name| {
+                declarations
+                    .iter()
+                    .all(|declaration| declaration.name() != name)
+            }));
+        }
+        for name in var_names {
+            let mut var_env = var_env.borrow_mut();
+            if !var_env.has_binding(&name) {
+                var_env.create_mutable_binding(name.clone(), false);
+                var_env.initialize_binding(&name, Value::undefined());
+            }
+        }
+
+        let lex_env = self.realm.environment.get_current_environment().clone();
+        self.instantiate_lexical_declarations(&declarations, &lex_env);
+        for function in functions_to_initialize(body) {
+            if let Node::FunctionDecl(ref name, ..) = *function {
+                let function = self.instantiate_function_object(function);
+                var_env
+                    .borrow_mut()
+                    .set_mutable_binding(name, function, false);
+            }
+        }
+        Ok(())
+    }
+
+    /// <https://tc39.es/ecma262/#sec-blockdeclarationinstantiation>
+    pub(super) fn block_declaration_instantiation(&mut self, block: &[Node]) -> Result<(), Value> {
+        let declarations = lexically_scoped_declarations(block, true);
+        let mut var_names = Vec::new();
+        for statement in block {
+            var_declared_names(statement, &mut var_names);
+        }
+        self.check_redeclarations(&declarations, &var_names)?;
+
+        let env = self.realm.environment.get_current_environment().clone();
+        self.instantiate_lexical_declarations(&declarations, &env);
+        Ok(())
+    }
+
+    /// Instantiates the declarations of eval code, the current environment is the lexical
+    /// environment of the code. Strict code also keeps its `var` and function declarations.
+    ///
+    /// <https://tc39.es/ecma262/#sec-evaldeclarationinstantiation>
+    fn eval_declaration_instantiation(&mut self, body: &[Node]) -> Result<(), Value> {
+        let declarations = lexically_scoped_declarations(body, false);
+        let mut var_names = top_level_var_declared_names(body);
+        self.check_redeclarations(&declarations, &var_names)?;
+
+        let lex_env = self.realm.environment.get_current_environment().clone();
+        let var_env = if self.strict {
+            lex_env.clone()
+        } else {
+            let mut var_env = None;
+            for env in self.realm.environment.environments().skip(1) {
+                let env_type = env.borrow().get_environment_type();
+                match env_type {
+                    EnvironmentType::Function | EnvironmentType::Global => {
+                        var_env = Some(env);
+                        break;
+                    }
+                    // A var declaration can't hoist over a lexical declaration of the same name
+                    _ => {
+                        if let Some(name) =
+                            var_names.iter().find(|name| env.borrow().has_binding(name))
+                        {
+                            return Err(self.redeclaration_error(name));
+                        }
+                    }
+                }
+            }
+            let var_env = var_env.expect("No function or global environment");
+
+            let mut function_names = Vec::new();
+            for statement in body {
+                block_function_names(statement, &mut function_names);
+            }
+            var_names.extend(function_names.into_iter().filter(|name| {
+                declarations
+                    .iter()
+                    .all(|declaration| declaration.name() != name)
+            }));
+            var_env
+        };
+        let global = match var_env.borrow().get_environment_type() {
+            EnvironmentType::Global => true,
+            _ => false,
+        };
+        if global {
+            for name in &var_names {
+                if self.has_lexical_declaration(name) {
+                    return Err(self.redeclaration_error(name));
+                }
+            }
+        }
+
+        self.instantiate_lexical_declarations(&declarations, &lex_env);
+        let functions = functions_to_initialize(body);
+        for function in &functions {
+            if let Node::FunctionDecl(ref name, ..) = **function {
+                let function = self.instantiate_function_object(function);
+                if global {
+                    self.create_global_function_binding(name, function, true);
+                    continue;
+                }
+                let mut var_env = var_env.borrow_mut();
+                if var_env.has_binding(name) {
+                    var_env.set_mutable_binding(name, function, false);
+                } else {
+                    var_env.create_mutable_binding(name.clone(), true);
+                    var_env.initialize_binding(name, function);
+                }
+            }
+        }
+        for name in &var_names {
+            if global {
+                self.create_global_var_binding(name, true);
+                continue;
+            }
+            let mut var_env = var_env.borrow_mut();
+            if !var_env.has_binding(name) {
+                var_env.create_mutable_binding(name.clone(), true);
+                var_env.initialize_binding(name, Value::undefined());
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,161 @@
+//! Limits on the execution of untrusted scripts.
+//!
+//! An interpreter can be given a budget of evaluation steps, a wall-clock deadline, and an
+//! [`InterruptHandle`] which another thread can trigger. Once one of them fires the running code is
+//! terminated: execution unwinds up to the host, and the termination can't be caught by the
+//! script, every further step fails until the host clears it.
+
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+
+/// The number of steps between two checks of the deadline, reading the clock at every step would
+/// slow down the interpreter.
+const DEADLINE_CHECK_INTERVAL: u64 = 1024;
+
+/// The number of iterations of a loop in a builtin between two checks of the limits. Builtins
+/// don't evaluate steps, so a loop over a length set by the script checks on its own.
+pub(crate) const NATIVE_CHECK_INTERVAL: usize = 256;
+
+/// The reason why the execution of a script was terminated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Termination {
+    /// The script used up its budget of evaluation steps.
+    StepLimit,
+    /// The script was still running at its deadline.
+    Deadline,
+    /// The script was stopped through an [`InterruptHandle`].
+    Interrupted,
+    /// The script kept the heap over its limit after it was told it ran out of memory.
+    OutOfMemory,
+}
+
+impl fmt::Display for Termination {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Termination::StepLimit => write!(f, "execution step limit exceeded"),
+            Termination::Deadline => write!(f, "execution deadline exceeded"),
+            Termination::Interrupted => write!(f, "execution interrupted"),
+            Termination::OutOfMemory => write!(f, "out of memory"),
+        }
+    }
+}
+
+/// A handle which stops the script running in an interpreter, it can be sent to and triggered
+/// from another thread.
+#[derive(Debug, Clone)]
+pub struct InterruptHandle(Arc<AtomicBool>);
+
+impl InterruptHandle {
+    /// Asks the interpreter to terminate the running script at its next step. When no script is
+    /// running, the next one is terminated as soon as it starts.
+    pub fn interrupt(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Checks if an interrupt is pending.
+    pub fn is_interrupted(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// The execution limits of an interpreter and their state.
+#[derive(Debug)]
+pub(crate) struct Limits {
+    /// The number of steps left, `None` for no limit
+    remaining_steps: Option<u64>,
+    /// The number of steps evaluated since the interpreter was created
+    steps: u64,
+    deadline: Option<Instant>,
+    interrupt: Arc<AtomicBool>,
+    termination: Option<Termination>,
+}
+
+impl Limits {
+    pub(crate) fn new() -> Self {
+        Self {
+            remaining_steps: None,
+            steps: 0,
+            deadline: None,
+            interrupt: Arc::new(AtomicBool::new(false)),
+            termination: None,
+        }
+    }
+
+    pub(crate) fn set_step_limit(&mut self, limit: Option<u64>) {
+        self.remaining_steps = limit;
+    }
+
+    pub(crate) fn set_deadline(&mut self, deadline: Option<Instant>) {
+        self.deadline = deadline;
+    }
+
+    pub(crate) fn interrupt_handle(&self) -> InterruptHandle {
+        InterruptHandle(self.interrupt.clone())
+    }
+
+    pub(crate) fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    pub(crate) fn termination(&self) -> Option<Termination> {
+        self.termination
+    }
+
+    /// Terminates the running code for a reason found outside of `step`.
+    pub(crate) fn terminate(&mut self, termination: Termination) {
+        self.termination = Some(termination);
+    }
+
+    pub(crate) fn clear_termination(&mut self) {
+        self.termination = None;
+        self.interrupt.store(false, Ordering::SeqCst);
+    }
+
+    /// Accounts for one evaluation step, the termination is returned if a limit was hit now or
+    /// earlier.
+    pub(crate) fn step(&mut self) -> Result<(), Termination> {
+        if let Some(termination) = self.termination {
+            return Err(termination);
+        }
+        let termination = if self.interrupt.load(Ordering::Relaxed) {
+            Some(Termination::Interrupted)
+        } else if self.remaining_steps == Some(0) {
+            Some(Termination::StepLimit)
+        } else if self.steps % DEADLINE_CHECK_INTERVAL == 0 && self.is_past_deadline() {
+            Some(Termination::Deadline)
+        } else {
+            None
+        };
+        if let Some(termination) = termination {
+            self.termination = Some(termination);
+            return Err(termination);
+        }
+        self.steps += 1;
+        if let Some(ref mut remaining) = self.remaining_steps {
+            *remaining -= 1;
+        }
+        Ok(())
+    }
+
+    /// Accounts for a batch of iterations of a builtin as one step. The deadline is checked every
+    /// time, since a batch can take much longer than a step.
+    pub(crate) fn native_step(&mut self) -> Result<(), Termination> {
+        self.step()?;
+        if self.is_past_deadline() {
+            self.termination = Some(Termination::Deadline);
+            return Err(Termination::Deadline);
+        }
+        Ok(())
+    }
+
+    fn is_past_deadline(&self) -> bool {
+        self.deadline
+            .map_or(false, |deadline| Instant::now() >= deadline)
+    }
+}
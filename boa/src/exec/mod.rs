@@ -1,32 +1,71 @@
 //! Execution of the AST, this is where the interpreter actually runs
 
+mod debugger;
+mod declaration;
+mod limits;
+mod profiler;
 #[cfg(test)]
 mod tests;
 
+pub use self::{
+    debugger::{DebugHandler, PauseHandle, PauseReason, Resume, Scope, StackFrame},
+    limits::{InterruptHandle, Termination},
+    profiler::{FunctionProfile, Profile},
+};
+
+use self::{
+    debugger::Debugger,
+    limits::{Limits, NATIVE_CHECK_INTERVAL},
+    profiler::Profiler,
+};
 use crate::{
     builtins::{
-        array,
-        function::{Function as FunctionObject, FunctionBody, ThisMode},
+        array, eval,
+        function::{self, Function as FunctionObject, FunctionBody, ThisMode, REALM},
+        number,
         object::{
-            internal_methods_trait::ObjectInternalMethods, Object, ObjectKind, INSTANCE_PROTOTYPE,
-            PROTOTYPE,
+            internal_methods_trait::ObjectInternalMethods, Object, ObjectKind, PropertyMap,
+            INSTANCE_PROTOTYPE, PROTOTYPE,
         },
         property::Property,
-        value::{ResultValue, Value, ValueData},
+        symbol::{self, WellKnownSymbol},
+        value::{JsString, ResultValue, Value, ValueData},
     },
-    environment::lexical_environment::{new_declarative_environment, VariableScope},
-    realm::Realm,
+    heap::{self, Account, HeapStats},
+    realm::{Realm, RealmHandle},
     syntax::ast::{
         constant::Const,
-        node::{MethodDefinitionKind, Node, PropertyDefinition},
+        node::{FormalParameter, MethodDefinitionKind, Node, PropertyDefinition, SourceText},
         op::{AssignOp, BinOp, BitOp, CompOp, LogOp, NumOp, UnaryOp},
+        pos::Position,
     },
 };
+use gc::Gc;
 use std::{
     borrow::{Borrow, BorrowMut},
+    mem,
     ops::Deref,
+    rc::Rc,
+    slice,
+    time::Instant,
 };
 
+/// The default maximum number of nested function calls.
+const DEFAULT_MAX_CALL_DEPTH: usize = 10_000;
+
+/// The space left on the stack below which evaluating a node allocates a new stack segment.
+const STACK_RED_ZONE: usize = 128 * 1024;
+
+/// The size of the stack segments allocated to evaluate nodes.
+const STACK_GROWTH: usize = 2 * 1024 * 1024;
+
+/// The memory a step can allocate without reserving it, such as a property or a short string.
+/// Operators and builtins creating larger values reserve their size themselves.
+const STEP_HEAP_RESERVE: usize = 4 * 1024;
+
+/// The name given to the script in stack traces and profiles, below the functions it calls.
+const SCRIPT_FRAME_NAME: &str = "(script)";
+
 /// An execution engine
 pub trait Executor {
     /// Make a new execution engine
@@ -39,12 +78,67 @@ pub trait Executor {
 #[derive(Debug)]
 pub struct Interpreter {
     is_return: bool,
+    /// Whether the code being run is strict mode code
+    strict: bool,
+    /// The step budget, deadline and interrupt flag of the running code
+    limits: Limits,
+    /// The number of function calls being run
+    call_depth: usize,
+    /// The number of nested function calls after which a `RangeError` is thrown
+    max_call_depth: usize,
+    /// The memory charged to the code run by this interpreter
+    heap: Rc<Account>,
+    /// The number of bytes the code can use before it runs out of memory
+    heap_limit: Option<usize>,
+    /// Whether a `RangeError` was thrown for the heap usage over the limit
+    out_of_memory: bool,
     /// realm holds both the global object and the environment
     pub realm: Realm,
+    /// The breakpoints and the stepping state of the debugger
+    debugger: Debugger,
+    /// The profile being recorded, if the profiler is started
+    profiler: Option<Profiler>,
+    /// The value thrown last and the position of the statement which threw it
+    thrown: Option<(Value, Position)>,
+}
+
+/// Checks if a list of statements starts with a directive prologue containing `"use strict"`.
+///
+/// <https://tc39.es/ecma262/#sec-directive-prologues-and-the-use-strict-directive>
+fn has_use_strict_directive(list: &[Node]) -> bool {
+    list.iter()
+        .filter(|statement| !statement.is_location())
+        .map_while(|statement| match statement {
+            Node::Const(Const::String(ref directive)) => Some(directive),
+            _ => None,
+        })
+        .any(|directive| directive == "use strict")
 }
 
-fn exec_assign_op(op: &AssignOp, v_a: Value, v_b: Value) -> Value {
-    match *op {
+/// The name of a function in stack traces and profiles.
+fn frame_name(function: &Value) -> String {
+    match function.get_field_slice("name").to_string() {
+        ref name if name.is_empty() || name == "undefined" => "(anonymous)".to_string(),
+        name => name,
+    }
+}
+
+fn exec_assign_op(
+    op: &AssignOp,
+    v_a: Value,
+    v_b: Value,
+    interpreter: &mut Interpreter,
+) -> ResultValue {
+    let hint = match *op {
+        AssignOp::Add => None,
+        _ => Some("number"),
+    };
+    let v_a = interpreter.to_primitive(&mut v_a.clone(), hint)?;
+    let v_b = interpreter.to_primitive(&mut v_b.clone(), hint)?;
+    if *op == AssignOp::Add {
+        interpreter.reserve_concatenation(&v_a, &v_b)?;
+    }
+    Ok(match *op {
         AssignOp::Add => v_a + v_b,
         AssignOp::Sub => v_a - v_b,
         AssignOp::Mul => v_a * v_b,
@@ -56,7 +150,7 @@ fn exec_assign_op(op: &AssignOp, v_a: Value, v_b: Value) -> Value {
         AssignOp::Xor => v_a ^ v_b,
         AssignOp::Shl => v_a << v_b,
         AssignOp::Shr => v_a << v_b,
-    }
+    })
 }
 
 impl Executor for Interpreter {
@@ -64,11 +158,41 @@ impl Executor for Interpreter {
         Self {
             realm,
             is_return: false,
+            strict: false,
+            limits: Limits::new(),
+            call_depth: 0,
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+            heap: Rc::new(Account::default()),
+            heap_limit: None,
+            out_of_memory: false,
+            debugger: Debugger::new(),
+            profiler: None,
+            thrown: None,
         }
     }
 
-    #[allow(clippy::match_same_arms)]
     fn run(&mut self, node: &Node) -> ResultValue {
+        let _heap_scope = heap::enter(&self.heap);
+        self.run_node(node)
+    }
+}
+
+impl Interpreter {
+    /// Evaluates a node of the syntax tree, which counts as one step.
+    pub(crate) fn run_node(&mut self, node: &Node) -> ResultValue {
+        if let Err(termination) = self.limits.step() {
+            return Err(Value::from(termination.to_string()));
+        }
+        self.check_heap()?;
+        // Nested expressions recurse in the interpreter, as deep as the parser lets them nest, so
+        // they can outgrow the stack of the thread within a single call, which is then extended
+        // on the heap
+        stacker::maybe_grow(STACK_RED_ZONE, STACK_GROWTH, || self.run_node_here(node))
+    }
+
+    /// Implements `run_node` once enough stack is available.
+    #[allow(clippy::match_same_arms)]
+    fn run_node_here(&mut self, node: &Node) -> ResultValue {
         match *node {
             Node::Const(Const::Null) => Ok(Value::null()),
             Node::Const(Const::Undefined) => Ok(Value::undefined()),
@@ -77,143 +201,136 @@ impl Executor for Interpreter {
             // we can't move String from Const into value, because const is a garbage collected value
             // Which means Drop() get's called on Const, but str will be gone at that point.
             // Do Const values need to be garbage collected? We no longer need them once we've generated Values
-            Node::Const(Const::String(ref value)) => Ok(Value::string(value.to_string())),
+            Node::Const(Const::String(ref value)) => Ok(Value::string(value.clone())),
             Node::Const(Const::Bool(value)) => Ok(Value::boolean(value)),
-            Node::Block(ref es) => {
-                {
-                    let env = &mut self.realm.environment;
-                    env.push(new_declarative_environment(Some(
-                        env.get_current_environment_ref().clone(),
-                    )));
-                }
-
-                let mut obj = Value::null();
-                for e in es.iter() {
-                    let val = self.run(e)?;
-                    // early return
-                    if self.is_return {
-                        obj = val;
-                        break;
-                    }
-                    if e == es.last().expect("unable to get last value") {
-                        obj = val;
-                    }
-                }
-
-                // pop the block env
-                let _ = self.realm.environment.pop();
-
-                Ok(obj)
-            }
+            Node::Block(ref es) => self.run_block(es),
             Node::Local(ref name) => {
+                self.check_initialized(name)?;
                 let val = self.realm.environment.get_binding_value(name);
                 Ok(val)
             }
+            Node::This => Ok(self.realm.environment.get_this_binding()),
             Node::GetConstField(ref obj, ref field) => {
-                let val_obj = self.run(obj)?;
-                Ok(val_obj.borrow().get_field_slice(field))
+                let val_obj = self.run_node(obj)?;
+                let val_obj = self.property_base(val_obj);
+                self.get_field(&val_obj, Value::from(field.as_str()))
             }
             Node::GetField(ref obj, ref field) => {
-                let val_obj = self.run(obj)?;
-                let val_field = self.run(field)?;
-                Ok(val_obj
-                    .borrow()
-                    .get_field_slice(&val_field.borrow().to_string()))
+                let val_obj = self.run_node(obj)?;
+                let val_obj = self.property_base(val_obj);
+                let mut val_field = self.run_node(field)?;
+                let key = self.to_property_key(&mut val_field)?;
+                self.get_field(&val_obj, key)
             }
             Node::Call(ref callee, ref args) => {
                 let (mut this, func) = match callee.deref() {
                     Node::GetConstField(ref obj, ref field) => {
-                        let mut obj = self.run(obj)?;
+                        let mut obj = self.run_node(obj)?;
                         if obj.get_type() != "object" || obj.get_type() != "symbol" {
                             obj = self.to_object(&obj).expect("failed to convert to object");
                         }
-                        (obj.clone(), obj.borrow().get_field_slice(field))
+                        let func = self.get_field(&obj, Value::from(field.as_str()))?;
+                        (obj, func)
                     }
                     Node::GetField(ref obj, ref field) => {
-                        let obj = self.run(obj)?;
-                        let field = self.run(field)?;
-                        (
-                            obj.clone(),
-                            obj.borrow().get_field_slice(&field.borrow().to_string()),
-                        )
+                        let obj = self.run_node(obj)?;
+                        let obj = self.property_base(obj);
+                        let mut field = self.run_node(field)?;
+                        let key = self.to_property_key(&mut field)?;
+                        let func = self.get_field(&obj, key)?;
+                        (obj, func)
                     }
-                    _ => (self.realm.global_obj.clone(), self.run(&callee.clone())?), // 'this' binding should come from the function's self-contained environment
+                    // Non-strict functions replace the undefined `this` with the global object
+                    _ => (Value::undefined(), self.run_node(&callee.clone())?),
                 };
                 let mut v_args = Vec::with_capacity(args.len());
                 for arg in args.iter() {
                     if let Node::Spread(ref x) = arg.deref() {
-                        let val = self.run(x)?;
-                        let mut vals = self.extract_array_properties(&val).unwrap();
+                        let val = self.run_node(x)?;
+                        let mut vals = self.extract_array_properties(&val)?;
                         v_args.append(&mut vals);
                         break; // after spread we don't accept any new arguments
                     }
-                    v_args.push(self.run(arg)?);
+                    v_args.push(self.run_node(arg)?);
                 }
 
-                // execute the function call itself
-                let fnct_result = self.call(&func, &mut this, &v_args);
+                // A call to the `%eval%` intrinsic through the name `eval` is a direct eval
+                if let Node::Local(ref name) = callee.deref() {
+                    if name == "eval" && func.strict_equals(&self.realm.intrinsic_eval) {
+                        let x = v_args.get(0).cloned().unwrap_or_else(Value::undefined);
+                        return eval::perform_eval(&x, true, self);
+                    }
+                }
 
-                // unset the early return flag
-                self.is_return = false;
+                let callable = match func.data() {
+                    ValueData::Object(ref obj) => (*obj).deref().borrow().is_callable(),
+                    _ => false,
+                };
+                if !callable {
+                    return self.throw_type_error(format!("{} is not a function", callee));
+                }
 
-                fnct_result
+                // execute the function call itself
+                self.call(&func, &mut this, &v_args)
             }
             Node::WhileLoop(ref cond, ref expr) => {
                 let mut result = Value::undefined();
-                while self.run(cond)?.borrow().is_true() {
-                    result = self.run(expr)?;
+                while self.run_node(cond)?.borrow().is_true() {
+                    result = self.run_node(expr)?;
                 }
                 Ok(result)
             }
             Node::DoWhileLoop(ref body, ref cond) => {
-                let mut result = self.run(body)?;
-                while self.run(cond)?.borrow().is_true() {
-                    result = self.run(body)?;
+                let mut result = self.run_node(body)?;
+                while self.run_node(cond)?.borrow().is_true() {
+                    result = self.run_node(body)?;
                 }
                 Ok(result)
             }
             Node::ForLoop(ref init, ref cond, ref step, ref body) => {
                 if let Some(init) = init {
-                    self.run(init)?;
+                    // A `let` or `const` declaration is bound in the block wrapping the loop
+                    self.block_declaration_instantiation(slice::from_ref(init))?;
+                    self.run_node(init)?;
                 }
 
                 while match cond {
-                    Some(cond) => self.run(cond)?.borrow().is_true(),
+                    Some(cond) => self.run_node(cond)?.borrow().is_true(),
                     None => true,
                 } {
-                    self.run(body)?;
+                    self.run_node(body)?;
 
                     if let Some(step) = step {
-                        self.run(step)?;
+                        self.run_node(step)?;
                     }
                 }
 
                 Ok(Value::undefined())
             }
-            Node::If(ref cond, ref expr, None) => Ok(if self.run(cond)?.borrow().is_true() {
-                self.run(expr)?
+            Node::If(ref cond, ref expr, None) => Ok(if self.run_node(cond)?.borrow().is_true() {
+                self.run_node(expr)?
             } else {
                 Value::undefined()
             }),
             Node::If(ref cond, ref expr, Some(ref else_e)) => {
-                Ok(if self.run(cond)?.borrow().is_true() {
-                    self.run(expr)?
+                Ok(if self.run_node(cond)?.borrow().is_true() {
+                    self.run_node(expr)?
                 } else {
-                    self.run(else_e)?
+                    self.run_node(else_e)?
                 })
             }
             Node::Switch(ref val_e, ref vals, ref default) => {
-                let val = self.run(val_e)?;
+                let val = self.run_node(val_e)?;
                 let mut result = Value::null();
                 let mut matched = false;
                 for tup in vals.iter() {
                     let cond = &tup.0;
                     let block = &tup.1;
-                    if val.strict_equals(&self.run(cond)?) {
+                    if val.strict_equals(&self.run_node(cond)?) {
                         matched = true;
                         let last_expr = block.last().expect("Block has no expressions");
                         for expr in block.iter() {
-                            let e_result = self.run(expr)?;
+                            let e_result = self.run_node(expr)?;
                             if expr == last_expr {
                                 result = e_result;
                             }
@@ -221,7 +338,7 @@ impl Executor for Interpreter {
                     }
                 }
                 if !matched && default.is_some() {
-                    result = self.run(
+                    result = self.run_node(
                         default
                             .as_ref()
                             .expect("Could not get default as reference"),
@@ -230,22 +347,19 @@ impl Executor for Interpreter {
                 Ok(result)
             }
             Node::Object(ref properties) => {
-                let global_val = &self
-                    .realm
-                    .environment
-                    .get_global_object()
-                    .expect("Could not get the global object");
-                let obj = Value::new_object(Some(global_val));
+                let obj = Value::new_object(Some(&self.realm.intrinsics));
 
                 // TODO: Implement the rest of the property types.
                 for property in properties.iter() {
                     match property {
                         PropertyDefinition::Property(key, value) => {
-                            obj.borrow().set_field_slice(&key.clone(), self.run(value)?);
+                            obj.borrow()
+                                .set_field(Value::from(key.clone()), self.run_node(value)?);
                         }
                         PropertyDefinition::MethodDefinition(kind, name, func) => {
                             if let MethodDefinitionKind::Ordinary = kind {
-                                obj.borrow().set_field_slice(&name.clone(), self.run(func)?);
+                                obj.borrow()
+                                    .set_field_slice(&name.clone(), self.run_node(func)?);
                             } else {
                                 // TODO: Implement other types of MethodDefinitionKinds.
                                 unimplemented!("other types of property method definitions.");
@@ -262,73 +376,29 @@ impl Executor for Interpreter {
                 let mut elements = Vec::new();
                 for elem in arr.iter() {
                     if let Node::Spread(ref x) = elem.deref() {
-                        let val = self.run(x)?;
-                        let mut vals = self.extract_array_properties(&val).unwrap();
+                        let val = self.run_node(x)?;
+                        let mut vals = self.extract_array_properties(&val)?;
                         elements.append(&mut vals);
                         continue; // Don't push array after spread
                     }
-                    elements.push(self.run(elem)?);
+                    elements.push(self.run_node(elem)?);
                 }
                 array::add_to_array_object(&array, &elements)?;
                 Ok(array)
             }
             // <https://tc39.es/ecma262/#sec-createdynamicfunction>
-            Node::FunctionDecl(ref name, ref args, ref expr) => {
-                // Todo: Function.prototype doesn't exist yet, so the prototype right now is the Object.prototype
-                // let proto = &self
-                //     .realm
-                //     .environment
-                //     .get_global_object()
-                //     .expect("Could not get the global object")
-                //     .get_field_slice("Object")
-                //     .get_field_slice("Prototype");
-
-                let func = FunctionObject::create_ordinary(
-                    args.clone(), // TODO: args shouldn't need to be a reference it should be passed by value
-                    self.realm.environment.get_current_environment().clone(),
-                    FunctionBody::Ordinary(*expr.clone()),
-                    ThisMode::NonLexical,
-                );
-
-                let mut new_func = Object::function();
-                new_func.set_call(func);
-                let val = Value::from(new_func);
-                val.set_field_slice("length", Value::from(args.len()));
-
-                // Set the name and assign it in the current environment
-                val.set_field_slice("name", Value::from(name.clone()));
-                self.realm.environment.create_mutable_binding(
-                    name.clone(),
-                    false,
-                    VariableScope::Function,
-                );
-
-                self.realm.environment.initialize_binding(name, val.clone());
-
-                Ok(val)
+            // The function was created when the declarations of its scope were instantiated
+            Node::FunctionDecl(ref name, ..) => {
+                // <https://tc39.es/ecma262/#sec-block-level-function-declarations-web-legacy-compatibility-semantics>
+                if !self.strict {
+                    let function = self.realm.environment.get_binding_value(name);
+                    self.realm.environment.set_var_binding(name, function);
+                }
+                Ok(Value::undefined())
             }
             // <https://tc39.es/ecma262/#sec-createdynamicfunction>
-            Node::FunctionExpr(ref name, ref args, ref expr) => {
-                // Todo: Function.prototype doesn't exist yet, so the prototype right now is the Object.prototype
-                // let proto = &self
-                //     .realm
-                //     .environment
-                //     .get_global_object()
-                //     .expect("Could not get the global object")
-                //     .get_field_slice("Object")
-                //     .get_field_slice("Prototype");
-
-                let func = FunctionObject::create_ordinary(
-                    args.clone(), // TODO: args shouldn't need to be a reference it should be passed by value
-                    self.realm.environment.get_current_environment().clone(),
-                    FunctionBody::Ordinary(*expr.clone()),
-                    ThisMode::NonLexical,
-                );
-
-                let mut new_func = Object::function();
-                new_func.set_call(func);
-                let val = Value::from(new_func);
-                val.set_field_slice("length", Value::from(args.len()));
+            Node::FunctionExpr(ref name, ref args, ref expr, ref source_text) => {
+                let val = self.create_function(args, expr, ThisMode::NonLexical, source_text);
 
                 if let Some(name) = name {
                     val.set_field_slice("name", Value::from(name.clone()));
@@ -336,33 +406,22 @@ impl Executor for Interpreter {
 
                 Ok(val)
             }
-            Node::ArrowFunctionDecl(ref args, ref expr) => {
-                // Todo: Function.prototype doesn't exist yet, so the prototype right now is the Object.prototype
-                // let proto = &self
-                //     .realm
-                //     .environment
-                //     .get_global_object()
-                //     .expect("Could not get the global object")
-                //     .get_field_slice("Object")
-                //     .get_field_slice("Prototype");
-
-                let func = FunctionObject::create_ordinary(
-                    args.clone(), // TODO: args shouldn't need to be a reference it should be passed by value
-                    self.realm.environment.get_current_environment().clone(),
-                    FunctionBody::Ordinary(*expr.clone()),
-                    ThisMode::Lexical,
-                );
-
-                let mut new_func = Object::function();
-                new_func.set_call(func);
-                let val = Value::from(new_func);
-                val.set_field_slice("length", Value::from(args.len()));
-
-                Ok(val)
+            Node::ArrowFunctionDecl(ref args, ref expr, ref source_text) => {
+                Ok(self.create_function(args, expr, ThisMode::Lexical, source_text))
             }
             Node::BinOp(BinOp::Num(ref op), ref a, ref b) => {
-                let v_a = self.run(a)?;
-                let v_b = self.run(b)?;
+                let mut v_a = self.run_node(a)?;
+                let mut v_b = self.run_node(b)?;
+                // Only `+` lets objects choose their conversion, it concatenates strings
+                let hint = match *op {
+                    NumOp::Add => None,
+                    _ => Some("number"),
+                };
+                let v_a = self.to_primitive(&mut v_a, hint)?;
+                let v_b = self.to_primitive(&mut v_b, hint)?;
+                if *op == NumOp::Add {
+                    self.reserve_concatenation(&v_a, &v_b)?;
+                }
                 Ok(match *op {
                     NumOp::Add => v_a + v_b,
                     NumOp::Sub => v_a - v_b,
@@ -372,30 +431,33 @@ impl Executor for Interpreter {
                     NumOp::Mod => v_a % v_b,
                 })
             }
+            Node::UnaryOp(UnaryOp::Delete, ref a) => self.delete(a),
             Node::UnaryOp(ref op, ref a) => {
-                let v_a = self.run(a)?;
+                let v_a = self.run_node(a)?;
                 Ok(match *op {
-                    UnaryOp::Minus => Value::from(-v_a.to_number()),
-                    UnaryOp::Plus => Value::from(v_a.to_number()),
+                    UnaryOp::Minus => Value::from(-self.to_number(&v_a)?),
+                    UnaryOp::Plus => Value::from(self.to_number(&v_a)?),
                     UnaryOp::IncrementPost => {
-                        let ret = v_a.clone();
-                        self.set_value(a, Value::from(v_a.to_number() + 1.0))?;
-                        ret
+                        let old = self.to_number(&v_a)?;
+                        self.set_value(a, Value::from(old + 1.0))?;
+                        Value::from(old)
                     }
                     UnaryOp::IncrementPre => {
-                        self.set_value(a, Value::from(v_a.to_number() + 1.0))?
+                        let old = self.to_number(&v_a)?;
+                        self.set_value(a, Value::from(old + 1.0))?
                     }
                     UnaryOp::DecrementPost => {
-                        let ret = v_a.clone();
-                        self.set_value(a, Value::from(v_a.to_number() - 1.0))?;
-                        ret
+                        let old = self.to_number(&v_a)?;
+                        self.set_value(a, Value::from(old - 1.0))?;
+                        Value::from(old)
                     }
                     UnaryOp::DecrementPre => {
-                        self.set_value(a, Value::from(v_a.to_number() - 1.0))?
+                        let old = self.to_number(&v_a)?;
+                        self.set_value(a, Value::from(old - 1.0))?
                     }
                     UnaryOp::Not => !v_a,
                     UnaryOp::Tilde => {
-                        let num_v_a = v_a.to_number();
+                        let num_v_a = self.to_number(&v_a)?;
                         // NOTE: possible UB: https://github.com/rust-lang/rust/issues/10184
                         Value::from(if num_v_a.is_nan() {
                             -1
@@ -404,32 +466,14 @@ impl Executor for Interpreter {
                         })
                     }
                     UnaryOp::Void => Value::undefined(),
-                    UnaryOp::Delete => match a.deref() {
-                        Node::GetConstField(ref obj, ref field) => {
-                            Value::boolean(self.run(obj)?.remove_property(field))
-                        }
-                        Node::GetField(ref obj, ref field) => Value::boolean(
-                            self.run(obj)?
-                                .remove_property(&self.run(field)?.to_string()),
-                        ),
-                        Node::Local(_) => Value::boolean(false),
-                        Node::ArrayDecl(_)
-                        | Node::Block(_)
-                        | Node::Const(_)
-                        | Node::FunctionDecl(_, _, _)
-                        | Node::FunctionExpr(_, _, _)
-                        | Node::New(_)
-                        | Node::Object(_)
-                        | Node::TypeOf(_)
-                        | Node::UnaryOp(_, _) => Value::boolean(true),
-                        _ => panic!("SyntaxError: wrong delete argument {}", node),
-                    },
                     _ => unimplemented!(),
                 })
             }
             Node::BinOp(BinOp::Bit(ref op), ref a, ref b) => {
-                let v_a = self.run(a)?;
-                let v_b = self.run(b)?;
+                let mut v_a = self.run_node(a)?;
+                let mut v_b = self.run_node(b)?;
+                let v_a = self.to_primitive(&mut v_a, Some("number"))?;
+                let v_b = self.to_primitive(&mut v_b, Some("number"))?;
                 Ok(match *op {
                     BitOp::And => v_a & v_b,
                     BitOp::Or => v_a | v_b,
@@ -441,54 +485,63 @@ impl Executor for Interpreter {
                 })
             }
             Node::BinOp(BinOp::Comp(ref op), ref a, ref b) => {
-                let mut v_r_a = self.run(a)?;
-                let mut v_r_b = self.run(b)?;
+                let mut v_r_a = self.run_node(a)?;
+                let mut v_r_b = self.run_node(b)?;
                 let mut v_a = v_r_a.borrow_mut();
                 let mut v_b = v_r_b.borrow_mut();
                 Ok(Value::from(match *op {
-                    CompOp::Equal => v_r_a.equals(v_b, self),
-                    CompOp::NotEqual => !v_r_a.equals(v_b, self),
+                    CompOp::Equal => v_r_a.equals(v_b, self)?,
+                    CompOp::NotEqual => !v_r_a.equals(v_b, self)?,
                     CompOp::StrictEqual => v_r_a.strict_equals(v_b),
                     CompOp::StrictNotEqual => !v_r_a.strict_equals(v_b),
-                    CompOp::GreaterThan => v_a.to_number() > v_b.to_number(),
-                    CompOp::GreaterThanOrEqual => v_a.to_number() >= v_b.to_number(),
-                    CompOp::LessThan => v_a.to_number() < v_b.to_number(),
-                    CompOp::LessThanOrEqual => v_a.to_number() <= v_b.to_number(),
+                    CompOp::GreaterThan => self.to_number(v_a)? > self.to_number(v_b)?,
+                    CompOp::GreaterThanOrEqual => self.to_number(v_a)? >= self.to_number(v_b)?,
+                    CompOp::LessThan => self.to_number(v_a)? < self.to_number(v_b)?,
+                    CompOp::LessThanOrEqual => self.to_number(v_a)? <= self.to_number(v_b)?,
                     CompOp::In => {
                         if !v_b.is_object() {
-                            panic!("TypeError: {} is not an Object.", v_b);
+                            return self.throw_type_error(format!(
+                                "right-hand side of 'in' should be an object, got {}",
+                                v_b
+                            ));
                         }
-                        let key = self.to_property_key(&mut v_a);
+                        let key = self.to_property_key(&mut v_a)?;
                         self.has_property(&mut v_b, &key)
                     }
+                    CompOp::InstanceOf => self.instance_of(v_a, v_b)?,
                 }))
             }
             Node::BinOp(BinOp::Log(ref op), ref a, ref b) => {
                 // turn a `Value` into a `bool`
                 let to_bool = |value| bool::from(&value);
                 Ok(match *op {
-                    LogOp::And => Value::from(to_bool(self.run(a)?) && to_bool(self.run(b)?)),
-                    LogOp::Or => Value::from(to_bool(self.run(a)?) || to_bool(self.run(b)?)),
+                    LogOp::And => {
+                        Value::from(to_bool(self.run_node(a)?) && to_bool(self.run_node(b)?))
+                    }
+                    LogOp::Or => {
+                        Value::from(to_bool(self.run_node(a)?) || to_bool(self.run_node(b)?))
+                    }
                 })
             }
             Node::BinOp(BinOp::Assign(ref op), ref a, ref b) => match a.deref() {
                 Node::Local(ref name) => {
+                    self.check_initialized(name)?;
                     let v_a = self.realm.environment.get_binding_value(&name);
-                    let v_b = self.run(b)?;
-                    let value = exec_assign_op(op, v_a, v_b);
+                    let v_b = self.run_node(b)?;
+                    let value = exec_assign_op(op, v_a, v_b, self)?;
+                    self.check_mutable(name)?;
                     self.realm
                         .environment
                         .set_mutable_binding(&name, value.clone(), true);
                     Ok(value)
                 }
                 Node::GetConstField(ref obj, ref field) => {
-                    let v_r_a = self.run(obj)?;
-                    let v_a = v_r_a.get_field_slice(field);
-                    let v_b = self.run(b)?;
-                    let value = exec_assign_op(op, v_a, v_b);
-                    v_r_a
-                        .borrow()
-                        .set_field_slice(&field.clone(), value.clone());
+                    let v_r_a = self.run_node(obj)?;
+                    let key = Value::from(field.as_str());
+                    let v_a = self.get_field(&v_r_a, key.clone())?;
+                    let v_b = self.run_node(b)?;
+                    let value = exec_assign_op(op, v_a, v_b, self)?;
+                    self.set_field(&v_r_a, key, value.clone(), self.strict)?;
                     Ok(value)
                 }
                 _ => Ok(Value::undefined()),
@@ -499,116 +552,80 @@ impl Executor for Interpreter {
                     _ => unreachable!("Node::New(ref call): 'call' must only be Node::Call type."),
                 };
 
-                let func_object = self.run(callee)?;
+                let func_object = self.run_node(callee)?;
                 let mut v_args = Vec::with_capacity(args.len());
                 for arg in args.iter() {
-                    v_args.push(self.run(arg)?);
-                }
-                let mut this = Value::new_object(None);
-                // Create a blank object, then set its __proto__ property to the [Constructor].prototype
-                this.borrow().set_internal_slot(
-                    INSTANCE_PROTOTYPE,
-                    func_object.borrow().get_field_slice(PROTOTYPE),
-                );
-
-                match *(func_object.borrow()).deref() {
-                    ValueData::Object(ref o) => (*o.deref().clone().borrow_mut())
-                        .construct
-                        .as_ref()
-                        .unwrap()
-                        .construct(&mut func_object.clone(), &v_args, self, &mut this),
-                    _ => Ok(Value::undefined()),
+                    v_args.push(self.run_node(arg)?);
                 }
+                self.construct(&func_object, &v_args)
             }
             Node::Return(ref ret) => {
                 let result = match *ret {
-                    Some(ref v) => self.run(v),
+                    Some(ref v) => self.run_node(v),
                     None => Ok(Value::undefined()),
                 };
                 // Set flag for return
                 self.is_return = true;
                 result
             }
-            Node::Throw(ref ex) => Err(self.run(ex)?),
+            Node::Throw(ref ex) => {
+                let value = self.run_node(ex)?;
+                // A value thrown again is recorded where it's thrown this time
+                self.thrown = None;
+                Err(value)
+            }
             Node::Assign(ref ref_e, ref val_e) => {
-                let val = self.run(val_e)?;
+                let val = self.run_node(val_e)?;
                 match ref_e.deref() {
-                    Node::Local(ref name) => {
-                        if self.realm.environment.has_binding(name) {
-                            // Binding already exists
-                            self.realm
-                                .environment
-                                .set_mutable_binding(&name, val.clone(), true);
-                        } else {
-                            self.realm.environment.create_mutable_binding(
-                                name.clone(),
-                                true,
-                                VariableScope::Function,
-                            );
-                            self.realm.environment.initialize_binding(name, val.clone());
-                        }
+                    Node::Local(_) => {
+                        self.set_value(ref_e, val.clone())?;
                     }
                     Node::GetConstField(ref obj, ref field) => {
-                        let val_obj = self.run(obj)?;
-                        val_obj
-                            .borrow()
-                            .set_field_slice(&field.clone(), val.clone());
+                        let val_obj = self.run_node(obj)?;
+                        let key = Value::from(field.as_str());
+                        self.set_field(&val_obj, key, val.clone(), self.strict)?;
                     }
                     Node::GetField(ref obj, ref field) => {
-                        let val_obj = self.run(obj)?;
-                        let val_field = self.run(field)?;
-                        val_obj.borrow().set_field(val_field, val.clone());
+                        let val_obj = self.run_node(obj)?;
+                        let mut val_field = self.run_node(field)?;
+                        let key = self.to_property_key(&mut val_field)?;
+                        self.set_field(&val_obj, key, val.clone(), self.strict)?;
                     }
                     _ => (),
                 }
                 Ok(val)
             }
+            // The bindings were created when the declarations of the scope were instantiated
             Node::VarDecl(ref vars) => {
-                for var in vars.iter() {
-                    let (name, value) = var.clone();
-                    let val = match value {
-                        Some(v) => self.run(&v)?,
-                        None => Value::undefined(),
-                    };
-                    self.realm.environment.create_mutable_binding(
-                        name.clone(),
-                        false,
-                        VariableScope::Function,
-                    );
-                    self.realm.environment.initialize_binding(&name, val);
+                for (name, value) in vars.iter() {
+                    if let Some(value) = value {
+                        let val = self.run_node(value)?;
+                        self.realm
+                            .environment
+                            .set_mutable_binding(name, val, self.strict);
+                    }
                 }
                 Ok(Value::undefined())
             }
             Node::LetDecl(ref vars) => {
-                for var in vars.iter() {
-                    let (name, value) = var.clone();
+                for (name, value) in vars.iter() {
                     let val = match value {
-                        Some(v) => self.run(&v)?,
+                        Some(value) => self.run_node(value)?,
                         None => Value::undefined(),
                     };
-                    self.realm.environment.create_mutable_binding(
-                        name.clone(),
-                        false,
-                        VariableScope::Block,
-                    );
-                    self.realm.environment.initialize_binding(&name, val);
+                    self.realm.environment.initialize_binding(name, val);
                 }
                 Ok(Value::undefined())
             }
             Node::ConstDecl(ref vars) => {
                 for (name, value) in vars.iter() {
-                    self.realm.environment.create_immutable_binding(
-                        name.clone(),
-                        false,
-                        VariableScope::Block,
-                    );
-                    let val = self.run(&value)?;
-                    self.realm.environment.initialize_binding(&name, val);
+                    let val = self.run_node(value)?;
+                    self.realm.environment.initialize_binding(name, val);
                 }
                 Ok(Value::undefined())
             }
             Node::TypeOf(ref val_e) => {
-                let val = self.run(val_e)?;
+                let val = self.run_node(val_e)?;
                 Ok(Value::from(match *val {
                     ValueData::Undefined => "undefined",
                     ValueData::Symbol(_) => "symbol",
@@ -625,35 +642,18 @@ impl Executor for Interpreter {
                     }
                 }))
             }
-            Node::StatementList(ref list) => {
-                {
-                    let env = &mut self.realm.environment;
-                    env.push(new_declarative_environment(Some(
-                        env.get_current_environment_ref().clone(),
-                    )));
-                }
-
-                let mut obj = Value::null();
-                for (i, item) in list.iter().enumerate() {
-                    let val = self.run(item)?;
-                    // early return
-                    if self.is_return {
-                        obj = val;
-                        break;
-                    }
-                    if i + 1 == list.len() {
-                        obj = val;
-                    }
-                }
-
-                // pop the block env
-                let _ = self.realm.environment.pop();
-
-                Ok(obj)
+            Node::StatementList(ref list) => self.run_script(list),
+            Node::Location(position) => {
+                self.reach_statement(position);
+                Ok(Value::undefined())
+            }
+            Node::Debugger => {
+                self.reach_debugger_statement();
+                Ok(Value::undefined())
             }
             Node::Spread(ref node) => {
                 // TODO: for now we can do nothing but return the value as-is
-                self.run(node)
+                self.run_node(node)
             }
             ref i => unimplemented!("{}", i),
         }
@@ -661,11 +661,497 @@ impl Executor for Interpreter {
 }
 
 impl Interpreter {
+    /// Creates an ordinary function object in the current environment, its prototype is
+    /// `Function.prototype`. Arrow functions can't be used as constructors.
+    fn create_function(
+        &mut self,
+        args: &[FormalParameter],
+        body: &Node,
+        this_mode: ThisMode,
+        source_text: &Option<SourceText>,
+    ) -> Value {
+        let constructable = match this_mode {
+            ThisMode::Lexical => false,
+            ThisMode::NonLexical => true,
+        };
+        let mut func = FunctionObject::create_ordinary(
+            args.to_vec(),
+            self.realm.environment.get_current_environment().clone(),
+            FunctionBody::Ordinary(body.clone()),
+            this_mode,
+        );
+        func.strict = self.strict
+            || match body {
+                Node::StatementList(ref list) => has_use_strict_directive(list),
+                _ => false,
+            };
+
+        let mut new_func = Object::function();
+        if constructable {
+            new_func.set_construct(func.clone());
+        }
+        new_func.set_call(func);
+        new_func.set_internal_slot(REALM, self.realm.intrinsics.clone());
+        new_func.set_internal_slot(
+            INSTANCE_PROTOTYPE,
+            self.realm
+                .intrinsics
+                .get_field_slice("Function")
+                .get_field_slice(PROTOTYPE),
+        );
+        if let Some(source_text) = source_text {
+            let position = source_text.position;
+            new_func.set_internal_slot("SourceText", Value::from(source_text.text.clone()));
+            new_func.set_internal_slot("SourceLine", Value::from(position.line_number as usize));
+            new_func
+                .set_internal_slot("SourceColumn", Value::from(position.column_number as usize));
+        }
+        let val = Value::from(new_func);
+        val.set_field_slice("length", Value::from(args.len()));
+
+        // <https://tc39.es/ecma262/#sec-makeconstructor>
+        if constructable {
+            let prototype = Value::new_object(Some(&self.realm.intrinsics));
+            prototype
+                .as_object_mut()
+                .expect("prototype is an object")
+                .define_own_property(
+                    Value::from("constructor"),
+                    Property::new()
+                        .value(val.clone())
+                        .writable(true)
+                        .enumerable(false)
+                        .configurable(true),
+                );
+            val.as_object_mut()
+                .expect("functions are objects")
+                .define_own_property(
+                    Value::from(PROTOTYPE),
+                    Property::new()
+                        .value(prototype)
+                        .writable(true)
+                        .enumerable(false)
+                        .configurable(false),
+                );
+        }
+        val
+    }
+
+    /// Runs a list of statements, a `return` ends it early. The value of the last statement is
+    /// the result.
+    fn run_statements(&mut self, list: &[Node]) -> ResultValue {
+        let mut obj = Value::null();
+        let mut position = None;
+        for (i, item) in list.iter().enumerate() {
+            if let Node::Location(pos) = *item {
+                position = Some(pos);
+            }
+            let val = match self.run_node(item) {
+                Ok(val) => val,
+                Err(error) => {
+                    if let Some(position) = position {
+                        self.record_throw(&error, position);
+                    }
+                    return Err(error);
+                }
+            };
+            // early return
+            if self.is_return {
+                obj = val;
+                break;
+            }
+            if i + 1 == list.len() {
+                obj = val;
+            }
+        }
+        Ok(obj)
+    }
+
+    /// Records the position of the statement a value is thrown from, the innermost statement list
+    /// with locations records it and the ones it propagates through keep it.
+    fn record_throw(&mut self, error: &Value, position: Position) {
+        match self.thrown {
+            Some((ref thrown, _)) if Gc::ptr_eq(&thrown.0, &error.0) => {}
+            _ => self.thrown = Some((error.clone(), position)),
+        }
+    }
+
+    /// Returns the position of the statement which threw the given value, if it's the last value
+    /// thrown and the script was [parsed with locations][locations].
+    ///
+    /// [locations]: ../syntax/parser/struct.Parser.html#method.with_locations
+    pub fn throw_position(&self, error: &Value) -> Option<Position> {
+        match self.thrown {
+            Some((ref thrown, position)) if Gc::ptr_eq(&thrown.0, &error.0) => Some(position),
+            _ => None,
+        }
+    }
+
+    /// Throws a `ReferenceError` when a `let` or `const` binding is used in its temporal dead
+    /// zone, before its declaration initialised it.
+    fn check_initialized(&self, name: &str) -> Result<(), Value> {
+        if self.realm.environment.is_initialized_binding(name) {
+            Ok(())
+        } else {
+            Err(self.construct_reference_error(format!(
+                "Cannot access '{}' before initialization",
+                name
+            )))
+        }
+    }
+
+    /// Throws a `TypeError` when a `const` binding is assigned.
+    fn check_mutable(&self, name: &str) -> Result<(), Value> {
+        if self.realm.environment.is_mutable_binding(name) {
+            Ok(())
+        } else {
+            Err(self.construct_type_error("Assignment to constant variable."))
+        }
+    }
+
+    /// Limits the number of evaluation steps the interpreter can run from now on, `None` removes
+    /// the limit. Every node of the syntax tree which is evaluated counts as a step.
+    pub fn set_step_limit(&mut self, limit: Option<u64>) {
+        self.limits.set_step_limit(limit);
+    }
+
+    /// Sets the instant after which the running code is terminated, `None` removes the deadline.
+    pub fn set_deadline(&mut self, deadline: Option<Instant>) {
+        self.limits.set_deadline(deadline);
+    }
+
+    /// Returns a handle which can terminate the running code from another thread.
+    pub fn interrupt_handle(&self) -> InterruptHandle {
+        self.limits.interrupt_handle()
+    }
+
+    /// The number of evaluation steps run by the interpreter so far.
+    pub fn steps(&self) -> u64 {
+        self.limits.steps()
+    }
+
+    /// The reason why the code was terminated, if it was. The termination can't be caught by
+    /// scripts: the interpreter refuses to run anything until it is cleared.
+    pub fn termination(&self) -> Option<Termination> {
+        self.limits.termination()
+    }
+
+    /// Clears the termination and a pending interrupt so that the interpreter can run code again.
+    /// An exhausted step budget or a past deadline has to be replaced as well.
+    pub fn clear_termination(&mut self) {
+        self.limits.clear_termination();
+    }
+
+    /// Checks the limits from a loop of a builtin at the given iteration: the heap every time,
+    /// the others once every `NATIVE_CHECK_INTERVAL` iterations.
+    pub(crate) fn check_limits(&mut self, iteration: usize) -> Result<(), Value> {
+        self.check_heap()?;
+        if iteration % NATIVE_CHECK_INTERVAL == 0 {
+            self.check_limits_now()
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Checks the limits from a builtin which counts its work on its own.
+    pub(crate) fn check_limits_now(&mut self) -> Result<(), Value> {
+        self.limits
+            .native_step()
+            .map_err(|termination| Value::from(termination.to_string()))
+    }
+
+    /// Limits the memory the code can allocate to about `limit` bytes, `None` removes the limit.
+    ///
+    /// Going over the limit triggers a garbage collection. If the code still uses too much memory a
+    /// `RangeError` is thrown, and the code is terminated if it goes over the limit again before
+    /// releasing a good part of its memory.
+    pub fn set_heap_limit(&mut self, limit: Option<usize>) {
+        self.heap_limit = limit;
+    }
+
+    /// The memory usage of the code run by the interpreter.
+    pub fn heap_stats(&self) -> HeapStats {
+        self.heap.stats(self.heap_limit)
+    }
+
+    /// Checks that the code can run another step without going over its memory limit.
+    pub(crate) fn check_heap(&mut self) -> Result<(), Value> {
+        self.reserve_heap(STEP_HEAP_RESERVE)
+    }
+
+    /// Reserves the memory of a string of `len` code units before creating it.
+    pub(crate) fn reserve_string(&mut self, len: usize) -> Result<(), Value> {
+        self.reserve_heap(len.saturating_mul(mem::size_of::<u16>()))
+    }
+
+    /// Reserves the memory of `count` properties before adding them to an object.
+    pub(crate) fn reserve_properties(&mut self, count: usize) -> Result<(), Value> {
+        self.reserve_heap(count.saturating_mul(PropertyMap::<JsString, Property>::entry_size()))
+    }
+
+    /// Reserves the memory of the string `a + b` concatenates, if one of the primitives is a
+    /// string.
+    fn reserve_concatenation(&mut self, a: &Value, b: &Value) -> Result<(), Value> {
+        if !a.is_string() && !b.is_string() {
+            return Ok(());
+        }
+        let len = |value: &Value| match value.data() {
+            ValueData::String(ref string) => string.len(),
+            _ => value.to_string().len(),
+        };
+        self.reserve_string(len(a) + len(b))
+    }
+
+    /// Checks that the code can allocate `bytes` more without going over its memory limit,
+    /// builtins creating large values reserve the memory before allocating it.
+    pub(crate) fn reserve_heap(&mut self, bytes: usize) -> Result<(), Value> {
+        let limit = match self.heap_limit {
+            Some(limit) => limit,
+            None => return Ok(()),
+        };
+        let required = self.heap.used().saturating_add(bytes);
+        if required <= limit {
+            // The code is forgiven once it released a good part of the memory
+            if required <= limit / 4 * 3 {
+                self.out_of_memory = false;
+            }
+            return Ok(());
+        }
+        gc::force_collect();
+        self.heap.count_collection();
+        if self.heap.used().saturating_add(bytes) <= limit {
+            return Ok(());
+        }
+        if self.out_of_memory {
+            self.limits.terminate(Termination::OutOfMemory);
+            return Err(Value::from(Termination::OutOfMemory.to_string()));
+        }
+        self.out_of_memory = true;
+        // The account has no room left for the error
+        let _uncharged = heap::suspend();
+        Err(self.construct_range_error("Out of memory"))
+    }
+
+    /// Sets the maximum number of nested function calls, a deeper call throws a `RangeError`.
+    pub fn set_max_call_depth(&mut self, depth: usize) {
+        self.max_call_depth = depth;
+    }
+
+    /// Runs `f` as a nested function call.
+    ///
+    /// Every call of a script function recurses in the interpreter, the depth is limited so that
+    /// a runaway recursion throws a catchable error instead of overflowing the stack of the host.
+    fn run_call<F>(&mut self, function: &Value, f: F) -> ResultValue
+    where
+        F: FnOnce(&mut Self) -> ResultValue,
+    {
+        if self.call_depth >= self.max_call_depth {
+            return self.throw_range_error("Maximum call stack size exceeded");
+        }
+        // Builtins can be called by the host without going through `run`
+        let _heap_scope = heap::enter(&self.heap);
+        self.call_depth += 1;
+        let result = stacker::maybe_grow(STACK_RED_ZONE, STACK_GROWTH, || {
+            self.run_profiled(function, |interpreter| interpreter.run_frame(function, f))
+        });
+        self.call_depth -= 1;
+        result
+    }
+
+    /// Runs `f` as strict or non-strict code, the mode of the caller is restored afterwards.
+    pub(crate) fn run_in_mode<F>(&mut self, strict: bool, f: F) -> ResultValue
+    where
+        F: FnOnce(&mut Self) -> ResultValue,
+    {
+        let caller_strict = mem::replace(&mut self.strict, strict);
+        let result = f(self);
+        self.strict = caller_strict;
+        result
+    }
+
+    /// Adds a realm to the interpreter and returns its global object.
+    ///
+    /// The realms of an interpreter share its heap and its limits, and objects can be passed from
+    /// one to another. A function runs in the realm it was created in: the objects it creates and
+    /// the errors it throws come from the intrinsics of that realm. The realm lives as long as its
+    /// global object or one of its functions can be reached.
+    pub fn add_realm(&mut self, realm: Realm) -> Value {
+        let global = realm.global_obj.clone();
+        RealmHandle::of(&realm.intrinsics)
+            .expect("every realm has a handle")
+            .put(realm);
+        global
+    }
+
+    /// Runs `f` in the realm of the given global object, e.g. to `forward` a script to it.
+    ///
+    /// # Panics
+    ///
+    /// If the global object isn't the one of a realm of the interpreter.
+    pub fn enter_realm<F>(&mut self, global: &Value, f: F) -> ResultValue
+    where
+        F: FnOnce(&mut Self) -> ResultValue,
+    {
+        let handle = RealmHandle::of(global).expect("not the global object of a realm");
+        assert!(
+            handle.is_suspended()
+                || RealmHandle::of(&self.realm.intrinsics).as_ref() == Some(&handle),
+            "not the global object of a realm of the interpreter"
+        );
+        self.run_in_handle(&handle, f)
+    }
+
+    /// Runs `f` with the realm of the given intrinsics as the current realm, the realm of the
+    /// caller is restored afterwards. Code of an unknown realm runs in the current one.
+    pub(crate) fn run_in_realm<F>(&mut self, intrinsics: &Value, f: F) -> ResultValue
+    where
+        F: FnOnce(&mut Self) -> ResultValue,
+    {
+        match RealmHandle::of(intrinsics) {
+            Some(handle) => self.run_in_handle(&handle, f),
+            None => f(self),
+        }
+    }
+
+    /// Runs `f` with the realm of the handle as the current realm. The current realm goes to its
+    /// handle in the meantime, so that code of the other realm can call back into it. Code of a
+    /// realm which isn't suspended, e.g. one which wasn't added, runs in the current realm.
+    fn run_in_handle<F>(&mut self, handle: &RealmHandle, f: F) -> ResultValue
+    where
+        F: FnOnce(&mut Self) -> ResultValue,
+    {
+        let caller_handle =
+            RealmHandle::of(&self.realm.intrinsics).expect("every realm has a handle");
+        if *handle == caller_handle {
+            return f(self);
+        }
+        let realm = match handle.take() {
+            Some(realm) => realm,
+            None => return f(self),
+        };
+        caller_handle.put(mem::replace(&mut self.realm, realm));
+        let result = f(self);
+        let caller = caller_handle.take().expect("the caller realm is suspended");
+        handle.put(mem::replace(&mut self.realm, caller));
+        result
+    }
+
     /// Get the Interpreter's realm
     pub(crate) fn get_realm(&self) -> &Realm {
         &self.realm
     }
 
+    /// Creates a new error object of the given native error type, e.g. `"TypeError"`.
+    fn construct_native_error<M>(&self, name: &str, message: M) -> Value
+    where
+        M: Into<String>,
+    {
+        let proto = self
+            .realm
+            .intrinsics
+            .get_field_slice(name)
+            .get_field_slice(PROTOTYPE);
+        let error = Value::new_object_from_prototype(proto, ObjectKind::Error);
+        error.set_field_slice("message", Value::from(message.into()));
+        error
+    }
+
+    /// Constructs an `EvalError` with the specified message.
+    pub fn construct_eval_error<M>(&self, message: M) -> Value
+    where
+        M: Into<String>,
+    {
+        self.construct_native_error("EvalError", message)
+    }
+
+    /// Throws an `EvalError` with the specified message.
+    pub fn throw_eval_error<M>(&self, message: M) -> ResultValue
+    where
+        M: Into<String>,
+    {
+        Err(self.construct_eval_error(message))
+    }
+
+    /// Constructs a `RangeError` with the specified message.
+    pub fn construct_range_error<M>(&self, message: M) -> Value
+    where
+        M: Into<String>,
+    {
+        self.construct_native_error("RangeError", message)
+    }
+
+    /// Throws a `RangeError` with the specified message.
+    pub fn throw_range_error<M>(&self, message: M) -> ResultValue
+    where
+        M: Into<String>,
+    {
+        Err(self.construct_range_error(message))
+    }
+
+    /// Constructs a `ReferenceError` with the specified message.
+    pub fn construct_reference_error<M>(&self, message: M) -> Value
+    where
+        M: Into<String>,
+    {
+        self.construct_native_error("ReferenceError", message)
+    }
+
+    /// Throws a `ReferenceError` with the specified message.
+    pub fn throw_reference_error<M>(&self, message: M) -> ResultValue
+    where
+        M: Into<String>,
+    {
+        Err(self.construct_reference_error(message))
+    }
+
+    /// Constructs a `SyntaxError` with the specified message.
+    pub fn construct_syntax_error<M>(&self, message: M) -> Value
+    where
+        M: Into<String>,
+    {
+        self.construct_native_error("SyntaxError", message)
+    }
+
+    /// Throws a `SyntaxError` with the specified message.
+    pub fn throw_syntax_error<M>(&self, message: M) -> ResultValue
+    where
+        M: Into<String>,
+    {
+        Err(self.construct_syntax_error(message))
+    }
+
+    /// Constructs a `TypeError` with the specified message.
+    pub fn construct_type_error<M>(&self, message: M) -> Value
+    where
+        M: Into<String>,
+    {
+        self.construct_native_error("TypeError", message)
+    }
+
+    /// Throws a `TypeError` with the specified message.
+    pub fn throw_type_error<M>(&self, message: M) -> ResultValue
+    where
+        M: Into<String>,
+    {
+        Err(self.construct_type_error(message))
+    }
+
+    /// Constructs a `URIError` with the specified message.
+    pub fn construct_uri_error<M>(&self, message: M) -> Value
+    where
+        M: Into<String>,
+    {
+        self.construct_native_error("URIError", message)
+    }
+
+    /// Throws a `URIError` with the specified message.
+    pub fn throw_uri_error<M>(&self, message: M) -> ResultValue
+    where
+        M: Into<String>,
+    {
+        Err(self.construct_uri_error(message))
+    }
+
     /// https://tc39.es/ecma262/#sec-call
     pub(crate) fn call(
         &mut self,
@@ -675,18 +1161,65 @@ impl Interpreter {
     ) -> ResultValue {
         // All functions should be objects, and eventually will be.
         // During this transition call will support both native functions and function objects
-        match (*f).deref() {
+        let realm = function::own_realm(f);
+        let result = match (*f).deref() {
             ValueData::Object(ref obj) => match (*obj).deref().borrow().call {
-                Some(ref func) => func.call(&mut f.clone(), arguments_list, self, this),
-                None => panic!("Expected function"),
+                Some(ref func) => self.run_call(f, |interpreter| {
+                    interpreter.run_in_realm(&realm, |interpreter| {
+                        func.call(&mut f.clone(), arguments_list, interpreter, this)
+                    })
+                }),
+                None => self.throw_type_error("object is not a function"),
             },
-            _ => Err(Value::undefined()),
-        }
+            _ => self.throw_type_error(format!("{} is not a function", f)),
+        };
+
+        // A `return` in the callee must not end the statement list of the caller
+        self.is_return = false;
+        result
+    }
+
+    /// https://tc39.es/ecma262/#sec-construct
+    pub(crate) fn construct(&mut self, f: &Value, arguments_list: &[Value]) -> ResultValue {
+        let constructor = match f.data() {
+            ValueData::Object(ref obj) => (*obj).deref().borrow().construct.clone(),
+            _ => None,
+        };
+        let constructor = match constructor {
+            Some(constructor) => constructor,
+            None => return self.throw_type_error(format!("{} is not a constructor", f)),
+        };
+
+        // The default prototype of the new object comes from the realm of the constructor
+        let realm = function::own_realm(f);
+        self.run_in_realm(&realm, |interpreter| {
+            // Create a blank object, then set its __proto__ property to the [Constructor].prototype
+            let mut this = Value::new_object(None);
+            let mut prototype = f.get_field_slice(PROTOTYPE);
+            if !prototype.is_object() {
+                prototype = interpreter
+                    .realm
+                    .intrinsics
+                    .get_field_slice("Object")
+                    .get_field_slice(PROTOTYPE);
+            }
+            this.set_internal_slot(INSTANCE_PROTOTYPE, prototype);
+
+            let result = interpreter.run_call(f, |interpreter| {
+                constructor.construct(&mut f.clone(), arguments_list, interpreter, &mut this)
+            });
+            interpreter.is_return = false;
+            // Constructors that don't return an object produce the newly created one
+            match result {
+                Ok(ref value) if !value.is_object() => Ok(this),
+                result => result,
+            }
+        })
     }
 
     /// https://tc39.es/ecma262/#sec-ordinarytoprimitive
-    fn ordinary_to_primitive(&mut self, o: &mut Value, hint: &str) -> Value {
-        debug_assert!(o.get_type() == "object");
+    fn ordinary_to_primitive(&mut self, o: &mut Value, hint: &str) -> ResultValue {
+        debug_assert!(o.is_object());
         debug_assert!(hint == "string" || hint == "number");
         let method_names: Vec<&str> = if hint == "string" {
             vec!["toString", "valueOf"]
@@ -696,28 +1229,20 @@ impl Interpreter {
         for name in method_names.iter() {
             let method: Value = o.get_field_slice(name);
             if method.is_function() {
-                let result = self.call(&method, o, &[]);
-                match result {
-                    Ok(val) => {
-                        if val.is_object() {
-                            // TODO: throw exception
-                            continue;
-                        } else {
-                            return val;
-                        }
-                    }
-                    Err(_) => continue,
+                let result = self.call(&method, o, &[])?;
+                if !result.is_object() {
+                    return Ok(result);
                 }
             }
         }
 
-        Value::undefined()
+        self.throw_type_error("Cannot convert object to primitive value")
     }
 
     /// The abstract operation ToPrimitive takes an input argument and an optional argument PreferredType.
     /// https://tc39.es/ecma262/#sec-toprimitive
     #[allow(clippy::wrong_self_convention)]
-    pub fn to_primitive(&mut self, input: &mut Value, preferred_type: Option<&str>) -> Value {
+    pub fn to_primitive(&mut self, input: &mut Value, preferred_type: Option<&str>) -> ResultValue {
         let mut hint: &str;
         match (*input).deref() {
             ValueData::Object(_) => {
@@ -730,43 +1255,66 @@ impl Interpreter {
                     },
                 };
 
-                // Skip d, e we don't support Symbols yet
-                // TODO: add when symbols are supported
+                let exotic_to_prim =
+                    input.get_field(Value::symbol(WellKnownSymbol::ToPrimitive.into()));
+                if !exotic_to_prim.is_null_or_undefined() {
+                    if !exotic_to_prim.is_function() {
+                        return self.throw_type_error("Symbol.toPrimitive is not a function");
+                    }
+                    let result = self.call(&exotic_to_prim, input, &[Value::from(hint)])?;
+                    if result.is_object() {
+                        return self.throw_type_error("Cannot convert object to primitive value");
+                    }
+                    return Ok(result);
+                }
+
                 if hint == "default" {
                     hint = "number";
                 };
 
                 self.ordinary_to_primitive(input, hint)
             }
-            _ => input.clone(),
+            _ => Ok(input.clone()),
         }
     }
     /// to_string() converts a value into a String
     /// https://tc39.es/ecma262/#sec-tostring
     #[allow(clippy::wrong_self_convention)]
-    pub fn to_string(&mut self, value: &Value) -> Value {
-        match *value.deref().borrow() {
+    pub fn to_string(&mut self, value: &Value) -> ResultValue {
+        Ok(match *value.deref().borrow() {
             ValueData::Undefined => Value::from("undefined"),
             ValueData::Null => Value::from("null"),
             ValueData::Boolean(ref boolean) => Value::from(boolean.to_string()),
-            ValueData::Rational(ref num) => Value::from(num.to_string()),
+            ValueData::Rational(ref num) => Value::from(number::number_to_string(*num)),
             ValueData::Integer(ref num) => Value::from(num.to_string()),
             ValueData::String(ref string) => Value::from(string.clone()),
             ValueData::Object(_) => {
-                let prim_value = self.to_primitive(&mut (value.clone()), Some("string"));
-                self.to_string(&prim_value)
+                let prim_value = self.to_primitive(&mut (value.clone()), Some("string"))?;
+                return self.to_string(&prim_value);
             }
-            _ => Value::from("function(){...}"),
+            // TODO: throw a TypeError, only `String()` may convert symbols
+            ValueData::Symbol(ref symbol) => Value::from(symbol.descriptive_string()),
+        })
+    }
+
+    /// Converts a value to a number, objects are converted to a primitive first.
+    /// https://tc39.es/ecma262/#sec-tonumber
+    pub fn to_number(&mut self, value: &Value) -> Result<f64, Value> {
+        match value.data() {
+            ValueData::Object(_) => Ok(self
+                .to_primitive(&mut value.clone(), Some("number"))?
+                .to_number()),
+            _ => Ok(value.to_number()),
         }
     }
 
     /// The abstract operation ToPropertyKey takes argument argument. It converts argument to a value that can be used as a property key.
     /// https://tc39.es/ecma262/#sec-topropertykey
     #[allow(clippy::wrong_self_convention)]
-    pub fn to_property_key(&mut self, value: &mut Value) -> Value {
-        let key = self.to_primitive(value, Some("string"));
+    pub fn to_property_key(&mut self, value: &mut Value) -> ResultValue {
+        let key = self.to_primitive(value, Some("string"))?;
         if key.is_symbol() {
-            key
+            Ok(key)
         } else {
             self.to_string(&key)
         }
@@ -785,30 +1333,202 @@ impl Interpreter {
         }
     }
 
+    /// Finds the property keyed by a string or a symbol, walking up the prototype chain.
+    fn find_property(object: &Value, key: &Value) -> Option<Property> {
+        match key.data() {
+            ValueData::Symbol(ref symbol) => object.get_symbol_property(symbol),
+            ValueData::String(ref string) => object.get_property_key(string),
+            _ => object.get_property(&key.to_string()),
+        }
+    }
+
+    /// The [[Get]] of a property, the getter of an accessor is called with the object as `this`.
+    ///
+    /// More information:
+    ///  - [ECMAScript reference][spec]
+    ///
+    /// [spec]: https://tc39.es/ecma262/#sec-ordinary-object-internal-methods-and-internal-slots-get-p-receiver
+    pub fn get_field(&mut self, object: &Value, key: Value) -> ResultValue {
+        match Self::find_property(object, &key) {
+            Some(ref property) if property.is_accessor_descriptor() => match property.get {
+                Some(ref getter) if getter.is_function() => {
+                    self.call(getter, &mut object.clone(), &[])
+                }
+                _ => Ok(Value::undefined()),
+            },
+            Some(property) => Ok(property.value.clone().unwrap_or_else(Value::undefined)),
+            None => Ok(Value::undefined()),
+        }
+    }
+
+    /// The abstract operation `Set`, the setter of an accessor is called with the object as
+    /// `this`. A rejected assignment throws a `TypeError` if `throw` is set, as it is in strict
+    /// mode code.
+    ///
+    /// More information:
+    ///  - [ECMAScript reference][spec]
+    ///
+    /// [spec]: https://tc39.es/ecma262/#sec-set-o-p-v-throw
+    pub fn set_field(
+        &mut self,
+        object: &Value,
+        key: Value,
+        value: Value,
+        throw: bool,
+    ) -> Result<(), Value> {
+        let property = Self::find_property(object, &key);
+        let assigned = match property {
+            Some(ref property) if property.is_accessor_descriptor() => match property.set {
+                Some(ref setter) if setter.is_function() => {
+                    self.call(setter, &mut object.clone(), &[value])?;
+                    true
+                }
+                _ => false,
+            },
+            _ => object.try_set_field(key.clone(), value),
+        };
+        if assigned || !throw {
+            Ok(())
+        } else if !object.is_object() {
+            Err(self.construct_type_error(format!("Cannot create property {} on {}", key, object)))
+        } else if property.is_some() {
+            Err(self.construct_type_error(format!("Cannot assign to read only property {}", key)))
+        } else {
+            Err(self.construct_type_error(format!(
+                "Cannot add property {}, object is not extensible",
+                key
+            )))
+        }
+    }
+
+    /// The `instanceof` operator, a `Symbol.hasInstance` method of the target decides if the value
+    /// is an instance of it.
+    ///
+    /// <https://tc39.es/ecma262/#sec-instanceofoperator>
+    pub(crate) fn instance_of(&mut self, value: &Value, target: &Value) -> Result<bool, Value> {
+        if !target.is_object() {
+            return Err(self.construct_type_error(format!(
+                "right-hand side of 'instanceof' is not an object, got {}",
+                target
+            )));
+        }
+
+        let handler = target.get_field(Value::symbol(WellKnownSymbol::HasInstance.into()));
+        if !handler.is_null_or_undefined() {
+            if !handler.is_function() {
+                return Err(self.construct_type_error("Symbol.hasInstance is not a function"));
+            }
+            let result = self.call(&handler, &mut target.clone(), &[value.clone()])?;
+            return Ok(bool::from(&result));
+        }
+
+        if !target.is_function() {
+            return Err(
+                self.construct_type_error("right-hand side of 'instanceof' is not callable")
+            );
+        }
+        self.ordinary_has_instance(target, value)
+    }
+
+    /// Checks if the `prototype` of the constructor is in the prototype chain of the value, bound
+    /// functions check against their target function.
+    ///
+    /// <https://tc39.es/ecma262/#sec-ordinaryhasinstance>
+    pub(crate) fn ordinary_has_instance(
+        &mut self,
+        constructor: &Value,
+        value: &Value,
+    ) -> Result<bool, Value> {
+        if !constructor.is_function() {
+            return Ok(false);
+        }
+        let bound_target = constructor.get_internal_slot("BoundTargetFunction");
+        if bound_target.is_object() {
+            return self.instance_of(value, &bound_target);
+        }
+        if !value.is_object() {
+            return Ok(false);
+        }
+
+        let prototype = constructor.get_field_slice(PROTOTYPE);
+        if !prototype.is_object() {
+            return Err(
+                self.construct_type_error("Function has non-object prototype in instanceof check")
+            );
+        }
+        let mut object = value.get_internal_slot(INSTANCE_PROTOTYPE);
+        while object.is_object() {
+            if object.strict_equals(&prototype) {
+                return Ok(true);
+            }
+            object = object.get_internal_slot(INSTANCE_PROTOTYPE);
+        }
+        Ok(false)
+    }
+
+    /// The `delete` operator, the reference is resolved without reading its value. Deleting a
+    /// variable or a non-configurable property is an error in strict mode code.
+    ///
+    /// <https://tc39.es/ecma262/#sec-delete-operator-runtime-semantics-evaluation>
+    fn delete(&mut self, reference: &Node) -> ResultValue {
+        let (base, key) = match *reference {
+            Node::Local(ref name) => {
+                if self.strict {
+                    return self
+                        .throw_syntax_error("Delete of an unqualified identifier in strict mode");
+                }
+                return Ok(Value::from(self.realm.environment.delete_binding(name)));
+            }
+            Node::GetConstField(ref obj, ref field) => {
+                (self.run_node(obj)?, Value::from(field.clone()))
+            }
+            Node::GetField(ref obj, ref field) => {
+                let base = self.run_node(obj)?;
+                let mut field = self.run_node(field)?;
+                (base, self.to_property_key(&mut field)?)
+            }
+            _ => {
+                self.run_node(reference)?;
+                return Ok(Value::from(true));
+            }
+        };
+
+        let base = self.to_object(&base)?;
+        let deleted = base
+            .as_object_mut()
+            .expect("ToObject returns an object")
+            .delete(&key);
+        if !deleted && self.strict {
+            return self
+                .throw_type_error(format!("Cannot delete non-configurable property '{}'", key));
+        }
+        Ok(Value::from(deleted))
+    }
+
     /// The abstract operation ToObject converts argument to a value of type Object
     /// https://tc39.es/ecma262/#sec-toobject
     #[allow(clippy::wrong_self_convention)]
     pub fn to_object(&mut self, value: &Value) -> ResultValue {
         match *value.deref().borrow() {
-            ValueData::Undefined | ValueData::Integer(_) | ValueData::Null => {
-                Err(Value::undefined())
+            ValueData::Undefined | ValueData::Null => {
+                self.throw_type_error("Cannot convert undefined or null to object")
             }
             ValueData::Boolean(_) => {
                 let proto = self
                     .realm
-                    .environment
-                    .get_binding_value("Boolean")
+                    .intrinsics
+                    .get_field_slice("Boolean")
                     .get_field_slice(PROTOTYPE);
 
                 let bool_obj = Value::new_object_from_prototype(proto, ObjectKind::Boolean);
                 bool_obj.set_internal_slot("BooleanData", value.clone());
                 Ok(bool_obj)
             }
-            ValueData::Rational(_) => {
+            ValueData::Rational(_) | ValueData::Integer(_) => {
                 let proto = self
                     .realm
-                    .environment
-                    .get_binding_value("Number")
+                    .intrinsics
+                    .get_field_slice("Number")
                     .get_field_slice(PROTOTYPE);
                 let number_obj = Value::new_object_from_prototype(proto, ObjectKind::Number);
                 number_obj.set_internal_slot("NumberData", value.clone());
@@ -817,93 +1537,136 @@ impl Interpreter {
             ValueData::String(_) => {
                 let proto = self
                     .realm
-                    .environment
-                    .get_binding_value("String")
+                    .intrinsics
+                    .get_field_slice("String")
                     .get_field_slice(PROTOTYPE);
                 let string_obj = Value::new_object_from_prototype(proto, ObjectKind::String);
                 string_obj.set_internal_slot("StringData", value.clone());
                 Ok(string_obj)
             }
-            ValueData::Object(_) | ValueData::Symbol(_) => Ok(value.clone()),
+            ValueData::Symbol(_) => Ok(symbol::to_object(value, self)),
+            ValueData::Object(_) => Ok(value.clone()),
+        }
+    }
+
+    /// Returns the value whose properties are looked up when accessing a property of `value`.
+    ///
+    /// Primitive values are converted to their wrapper object, so that the properties of their
+    /// prototype can be found. `undefined` and `null` are returned as is.
+    fn property_base(&mut self, value: Value) -> Value {
+        match *value {
+            ValueData::Undefined | ValueData::Null | ValueData::Object(_) => value,
+            _ => self.to_object(&value).unwrap_or(value),
         }
     }
 
     /// value_to_rust_string() converts a value into a rust heap allocated string
-    pub fn value_to_rust_string(&mut self, value: &Value) -> String {
-        match *value.deref().borrow() {
+    pub fn value_to_rust_string(&mut self, value: &Value) -> Result<String, Value> {
+        Ok(match *value.deref().borrow() {
             ValueData::Null => String::from("null"),
             ValueData::Boolean(ref boolean) => boolean.to_string(),
             ValueData::Rational(ref num) => num.to_string(),
             ValueData::Integer(ref num) => num.to_string(),
-            ValueData::String(ref string) => string.clone(),
+            ValueData::String(ref string) => string.to_string(),
             ValueData::Object(_) => {
-                let prim_value = self.to_primitive(&mut (value.clone()), Some("string"));
-                self.to_string(&prim_value).to_string()
+                let prim_value = self.to_primitive(&mut (value.clone()), Some("string"))?;
+                self.to_string(&prim_value)?.to_string()
             }
             _ => String::from("undefined"),
-        }
+        })
     }
 
-    pub fn value_to_rust_number(&mut self, value: &Value) -> f64 {
-        match *value.deref().borrow() {
-            ValueData::Null => f64::from(0),
-            ValueData::Boolean(boolean) => {
-                if boolean {
-                    f64::from(1)
-                } else {
-                    f64::from(0)
-                }
-            }
-            ValueData::Rational(num) => num,
-            ValueData::Integer(num) => f64::from(num),
-            ValueData::String(ref string) => string.parse::<f64>().unwrap(),
-            ValueData::Object(_) => {
-                let prim_value = self.to_primitive(&mut (value.clone()), Some("number"));
-                self.to_string(&prim_value)
-                    .to_string()
-                    .parse::<f64>()
-                    .expect("cannot parse valur to x64")
-            }
-            _ => {
-                // TODO: Make undefined?
-                f64::from(0)
-            }
-        }
+    pub fn value_to_rust_number(&mut self, value: &Value) -> Result<f64, Value> {
+        self.to_number(value)
     }
 
-    /// `extract_array_properties` converts an array object into a rust vector of Values.
-    /// This is useful for the spread operator, for any other object an `Err` is returned
-    fn extract_array_properties(&mut self, value: &Value) -> Result<Vec<Value>, ()> {
+    /// `extract_array_properties` converts an iterable value into a rust vector of Values.
+    /// This is useful for the spread operator, for any other value a `TypeError` is returned
+    pub(crate) fn extract_array_properties(&mut self, value: &Value) -> Result<Vec<Value>, Value> {
+        let iterator_method = self
+            .property_base(value.clone())
+            .get_field(Value::symbol(WellKnownSymbol::Iterator.into()));
+        if iterator_method.is_function() {
+            return self.iterate_to_list(value, &iterator_method);
+        }
+
         if let ValueData::Object(ref x) = *value.deref().borrow() {
             // Check if object is array
             if x.deref().borrow().kind == ObjectKind::Array {
                 let length: i32 =
-                    self.value_to_rust_number(&value.get_field_slice("length")) as i32;
+                    self.value_to_rust_number(&value.get_field_slice("length"))? as i32;
                 let values: Vec<Value> = (0..length)
                     .map(|idx| value.get_field_slice(&idx.to_string()))
                     .collect();
                 return Ok(values);
             }
+        }
+
+        Err(self.construct_type_error(format!("{} is not iterable", value)))
+    }
 
-            return Err(());
+    /// Collects the values produced by the iterator that `method` returns for `iterable`.
+    ///
+    /// More information:
+    ///  - [ECMAScript reference][spec]
+    ///
+    /// [spec]: https://tc39.es/ecma262/#sec-iterabletolist
+    fn iterate_to_list(&mut self, iterable: &Value, method: &Value) -> Result<Vec<Value>, Value> {
+        let mut iterator = self.call(method, &mut iterable.clone(), &[])?;
+        if !iterator.is_object() {
+            return Err(
+                self.construct_type_error("Result of the Symbol.iterator method is not an object")
+            );
         }
+        let next = iterator.get_field_slice("next");
 
-        Err(())
+        let mut values = Vec::new();
+        loop {
+            let result = self.call(&next, &mut iterator, &[])?;
+            if !result.is_object() {
+                return Err(self
+                    .construct_type_error(format!("Iterator result {} is not an object", result)));
+            }
+            if result.get_field_slice("done").is_true() {
+                return Ok(values);
+            }
+            values.push(result.get_field_slice("value"));
+        }
     }
 
     fn set_value(&mut self, node: &Node, value: Value) -> ResultValue {
         match node {
             Node::Local(ref name) => {
-                self.realm
-                    .environment
-                    .set_mutable_binding(name, value.clone(), true);
+                if self.realm.environment.has_binding(name) {
+                    self.check_initialized(name)?;
+                    self.check_mutable(name)?;
+                    self.realm
+                        .environment
+                        .set_mutable_binding(name, value.clone(), true);
+                } else if self.strict {
+                    return self.throw_reference_error(format!("{} is not defined", name));
+                } else {
+                    // Assigning an undeclared name creates a property of the global object
+                    self.realm.global_obj.set_field_slice(name, value.clone());
+                }
                 Ok(value)
             }
             Node::GetConstField(ref obj, ref field) => {
-                Ok(self.run(obj)?.set_field_slice(field, value))
+                let obj = self.run_node(obj)?;
+                self.set_field(
+                    &obj,
+                    Value::from(field.as_str()),
+                    value.clone(),
+                    self.strict,
+                )?;
+                Ok(value)
             }
             Node::GetField(ref obj, ref field) => {
-                Ok(self.run(obj)?.set_field(self.run(field)?, value))
+                let obj = self.run_node(obj)?;
+                let mut field = self.run_node(field)?;
+                let key = self.to_property_key(&mut field)?;
+                self.set_field(&obj, key, value.clone(), self.strict)?;
+                Ok(value)
             }
             _ => panic!("TypeError: invalid assignment to {}", node),
         }
@@ -0,0 +1,358 @@
+//! The profiler of the interpreter.
+//!
+//! While profiling, every call of a function through the interpreter is timed, native functions
+//! included. The calls are recorded in a tree of call stacks, rooted at the script, and each
+//! stack gets the time spent in its innermost function, not in the functions this one called.
+//! A [`Profile`] reads as folded stacks for flame graphs, or as self and total times per function.
+//!
+//! Functions are told apart by their code when the parser kept their source, so the closures
+//! created from the same code are one function, and by their object otherwise.
+
+use super::{frame_name, Interpreter, SCRIPT_FRAME_NAME};
+use crate::{
+    builtins::{
+        function::FunctionBody,
+        value::{ResultValue, Value, ValueData},
+    },
+    syntax::ast::pos::Position,
+};
+use rustc_hash::FxHashMap;
+use std::{
+    fmt::Write,
+    ops::Deref,
+    time::{Duration, Instant},
+};
+
+/// The time spent in one function over a profile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionProfile {
+    /// The name of the function.
+    pub name: String,
+    /// Where the function starts in its script, if the parser kept its source.
+    pub position: Option<Position>,
+    /// Whether the function is a builtin written in Rust.
+    pub native: bool,
+    /// The number of calls of the function.
+    pub calls: u64,
+    /// The time spent in the function itself, without the functions it called.
+    pub self_time: Duration,
+    /// The time spent in the function and the functions it called. A recursive call counts
+    /// once, in the outermost call.
+    pub total_time: Duration,
+}
+
+impl FunctionProfile {
+    /// The name of the function, followed by its line and column when they are known.
+    pub fn label(&self) -> String {
+        match self.position {
+            Some(pos) => format!("{} ({}:{})", self.name, pos.line_number, pos.column_number),
+            None => self.name.clone(),
+        }
+    }
+}
+
+/// What the calls of a function have in common.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum FunctionKey {
+    Script,
+    /// A function by its source text and where it starts
+    Code(Position, String),
+    /// A function by the address of its object, which the profiler keeps alive so that no other
+    /// function gets the same address
+    Object(*const ValueData),
+}
+
+/// A call stack of the tree, the parent of its innermost function.
+#[derive(Debug)]
+struct CallNode {
+    /// The index of the function in the profile
+    function: usize,
+    /// The index of the stack without its innermost function, `None` for the script
+    parent: Option<usize>,
+    /// The stacks with one more function, by function
+    children: FxHashMap<usize, usize>,
+    /// The time spent in the innermost function
+    self_time: Duration,
+}
+
+/// A call being run.
+#[derive(Debug)]
+struct OpenCall {
+    /// The index of the stack of the call in the tree
+    node: usize,
+    start: Instant,
+    /// The time spent in the calls it made
+    callees: Duration,
+}
+
+/// The times recorded by the profiler.
+#[derive(Debug)]
+pub struct Profile {
+    functions: Vec<FunctionProfile>,
+    /// The indices of the functions
+    indices: FxHashMap<FunctionKey, usize>,
+    nodes: Vec<CallNode>,
+}
+
+impl Profile {
+    fn new() -> Self {
+        let mut profile = Self {
+            functions: Vec::new(),
+            indices: FxHashMap::default(),
+            nodes: Vec::new(),
+        };
+        let script = profile.function(
+            FunctionKey::Script,
+            SCRIPT_FRAME_NAME.to_string(),
+            None,
+            false,
+        );
+        profile.nodes.push(CallNode {
+            function: script,
+            parent: None,
+            children: FxHashMap::default(),
+            self_time: Duration::default(),
+        });
+        profile
+    }
+
+    /// Returns the index of a function, adding it if it wasn't called yet.
+    fn function(
+        &mut self,
+        key: FunctionKey,
+        name: String,
+        position: Option<Position>,
+        native: bool,
+    ) -> usize {
+        let functions = &mut self.functions;
+        *self.indices.entry(key).or_insert_with(|| {
+            functions.push(FunctionProfile {
+                name,
+                position,
+                native,
+                calls: 0,
+                self_time: Duration::default(),
+                total_time: Duration::default(),
+            });
+            functions.len() - 1
+        })
+    }
+
+    /// Returns the index of the stack calling a function from another stack.
+    fn child(&mut self, parent: usize, function: usize) -> usize {
+        if let Some(&node) = self.nodes[parent].children.get(&function) {
+            return node;
+        }
+        self.nodes.push(CallNode {
+            function,
+            parent: Some(parent),
+            children: FxHashMap::default(),
+            self_time: Duration::default(),
+        });
+        let node = self.nodes.len() - 1;
+        self.nodes[parent].children.insert(function, node);
+        node
+    }
+
+    /// Checks if a function is already in a stack.
+    fn is_in_stack(&self, function: usize, mut node: Option<usize>) -> bool {
+        while let Some(index) = node {
+            if self.nodes[index].function == function {
+                return true;
+            }
+            node = self.nodes[index].parent;
+        }
+        false
+    }
+
+    /// The functions called while profiling, the script first, then by decreasing self time.
+    pub fn functions(&self) -> Vec<FunctionProfile> {
+        let mut functions = self.functions.clone();
+        let line_and_column =
+            |f: &FunctionProfile| f.position.map(|pos| (pos.line_number, pos.column_number));
+        functions[1..].sort_by(|a, b| {
+            b.self_time
+                .cmp(&a.self_time)
+                .then(a.name.cmp(&b.name))
+                .then(line_and_column(a).cmp(&line_and_column(b)))
+        });
+        functions
+    }
+
+    /// The call stacks in the folded format of flame graph tools: a line per stack, with its
+    /// functions from the script to the innermost one separated by `;`, then the self time of the
+    /// stack in microseconds. Functions are named by their [label](FunctionProfile::label),
+    /// native ones are marked with ` [native]`, stacks which took less than a microsecond are
+    /// left out.
+    pub fn folded(&self) -> String {
+        let mut lines: Vec<(String, u128)> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| node.self_time.as_micros() > 0)
+            .map(|(index, node)| {
+                let mut names = Vec::new();
+                let mut next = Some(index);
+                while let Some(index) = next {
+                    let function = &self.functions[self.nodes[index].function];
+                    if function.native {
+                        names.push(format!("{} [native]", function.name));
+                    } else {
+                        names.push(function.label().replace(';', ":"));
+                    }
+                    next = self.nodes[index].parent;
+                }
+                names.reverse();
+                (names.join(";"), node.self_time.as_micros())
+            })
+            .collect();
+        lines.sort();
+        let mut folded = String::new();
+        for (stack, micros) in lines {
+            writeln!(folded, "{} {}", stack, micros).expect("writing to a string can't fail");
+        }
+        folded
+    }
+}
+
+/// The profile being recorded, with the calls being run.
+#[derive(Debug)]
+pub(crate) struct Profiler {
+    profile: Profile,
+    /// The calls being run, the script first
+    calls: Vec<OpenCall>,
+    /// The functions keyed by the address of their object
+    retained: Vec<Value>,
+}
+
+impl Profiler {
+    fn new() -> Self {
+        Self {
+            profile: Profile::new(),
+            calls: vec![OpenCall {
+                node: 0,
+                start: Instant::now(),
+                callees: Duration::default(),
+            }],
+            retained: Vec::new(),
+        }
+    }
+
+    fn enter(&mut self, function: &Value) {
+        let name = frame_name(function);
+        let native = is_native(function);
+        let index = if let Some((position, text)) = source_of(function) {
+            let key = FunctionKey::Code(position, text);
+            self.profile.function(key, name, Some(position), native)
+        } else {
+            let known = self.profile.functions.len();
+            let address: &ValueData = function;
+            let key = FunctionKey::Object(address as *const ValueData);
+            let index = self.profile.function(key, name, None, native);
+            if index == known {
+                self.retained.push(function.clone());
+            }
+            index
+        };
+        let parent = self.calls.last().map_or(0, |call| call.node);
+        let node = self.profile.child(parent, index);
+        self.calls.push(OpenCall {
+            node,
+            start: Instant::now(),
+            callees: Duration::default(),
+        });
+    }
+
+    fn exit(&mut self) {
+        let call = match self.calls.pop() {
+            Some(call) => call,
+            None => return,
+        };
+        let elapsed = call.start.elapsed();
+        let self_time = elapsed.checked_sub(call.callees).unwrap_or_default();
+        let node = &mut self.profile.nodes[call.node];
+        node.self_time += self_time;
+        let (function, parent) = (node.function, node.parent);
+        let recursive = self.profile.is_in_stack(function, parent);
+        let profile = &mut self.profile.functions[function];
+        profile.calls += 1;
+        profile.self_time += self_time;
+        if !recursive {
+            profile.total_time += elapsed;
+        }
+        if let Some(caller) = self.calls.last_mut() {
+            caller.callees += elapsed;
+        }
+    }
+
+    /// Ends the calls still being run, the script last, and returns the profile.
+    fn finish(mut self) -> Profile {
+        while !self.calls.is_empty() {
+            self.exit();
+        }
+        self.profile
+    }
+}
+
+/// Checks if a function is a builtin written in Rust.
+fn is_native(function: &Value) -> bool {
+    match function.deref() {
+        ValueData::Object(ref object) => match object.borrow().call {
+            Some(ref call) => matches!(call.body, FunctionBody::BuiltIn(_)),
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+/// Gets where a function starts and its source text, if the parser kept them.
+fn source_of(function: &Value) -> Option<(Position, String)> {
+    let text = match *function.get_internal_slot("SourceText") {
+        ValueData::String(ref text) => text.to_string(),
+        _ => return None,
+    };
+    let line = function.get_internal_slot("SourceLine").to_number();
+    let column = function.get_internal_slot("SourceColumn").to_number();
+    if line.is_nan() || column.is_nan() {
+        return None;
+    }
+    Some((Position::new(line as u64, column as u64), text))
+}
+
+impl Interpreter {
+    /// Starts recording the time spent in the functions called, a profile being recorded is
+    /// dropped.
+    ///
+    /// The time until the profiling stops is counted for the script, so it should start right
+    /// before the code to profile is run.
+    pub fn start_profiling(&mut self) {
+        self.profiler = Some(Profiler::new());
+    }
+
+    /// Stops profiling and returns the profile, `None` if the profiler wasn't started.
+    pub fn stop_profiling(&mut self) -> Option<Profile> {
+        self.profiler.take().map(Profiler::finish)
+    }
+
+    /// Runs `f`, timed as a call of the given function when the profiler is started.
+    pub(crate) fn run_profiled<F>(&mut self, function: &Value, f: F) -> ResultValue
+    where
+        F: FnOnce(&mut Self) -> ResultValue,
+    {
+        let depth = match self.profiler {
+            Some(ref mut profiler) => {
+                profiler.enter(function);
+                profiler.calls.len()
+            }
+            None => return f(self),
+        };
+        let result = f(self);
+        // The call isn't timed if the profiler was restarted while it ran
+        if let Some(ref mut profiler) = self.profiler {
+            if profiler.calls.len() == depth {
+                profiler.exit();
+            }
+        }
+        result
+    }
+}
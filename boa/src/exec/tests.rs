@@ -1,7 +1,11 @@
+use crate::builtins::value::Value;
+use crate::environment::lexical_environment::EnvironmentType;
 use crate::exec;
-use crate::exec::Executor;
-use crate::forward;
+use crate::exec::{DebugHandler, Executor, Interpreter, PauseReason, Resume};
 use crate::realm::Realm;
+use crate::syntax::{lexer::Lexer, parser::Parser};
+use crate::{forward, forward_val};
+use std::{cell::RefCell, rc::Rc};
 
 #[test]
 fn empty_let_decl_undefined() {
@@ -466,7 +470,7 @@ fn unary_delete() {
         const c = delete a.c + '';
         a.b + c
     "#;
-    assert_eq!(&exec(delete_not_existing_prop), "5false");
+    assert_eq!(&exec(delete_not_existing_prop), "5true");
 
     let delete_field = r#"
         const a = { b: 5 };
@@ -495,6 +499,274 @@ fn unary_delete() {
         delete delete delete 1;
     "#;
     assert_eq!(&exec(delete_recursive), "true");
+
+    let delete_non_configurable = r#"
+        var a = {};
+        Object.defineProperty(a, 'b', { value: 5 });
+        delete a.b + '' + a.b
+    "#;
+    assert_eq!(&exec(delete_non_configurable), "false5");
+
+    let delete_implicit_global = r#"
+        implicit = 5;
+        var declared = 5;
+        delete implicit + '' + delete declared + implicit
+    "#;
+    assert_eq!(&exec(delete_implicit_global), "truefalseundefined");
+}
+
+#[test]
+fn unary_delete_strict() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var a = {};
+        Object.defineProperty(a, 'b', { value: 5 });
+        var c = { d: 1 };
+        function deleteProperty() {
+            'use strict';
+            return delete a.b;
+        }
+        function deleteConfigurable() {
+            'use strict';
+            return delete c.d;
+        }
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(
+        forward(&mut engine, "deleteProperty()"),
+        "Error: TypeError: Cannot delete non-configurable property 'b'"
+    );
+    assert_eq!(forward(&mut engine, "deleteConfigurable()"), "true");
+    assert_eq!(
+        forward(&mut engine, "eval(\"'use strict'; delete c\")"),
+        "Error: SyntaxError: Delete of an unqualified identifier in strict mode"
+    );
+    // The directive of the eval code doesn't make the caller strict
+    assert_eq!(forward(&mut engine, "delete a.b"), "false");
+}
+
+#[test]
+fn strict_mode_this() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        function strict() {
+            'use strict';
+            return this;
+        }
+        function sloppy() {
+            return this;
+        }
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "strict() === undefined"), "true");
+    assert_eq!(forward(&mut engine, "strict.call(5) === 5"), "true");
+    assert_eq!(forward(&mut engine, "sloppy() === this"), "true");
+}
+
+#[test]
+fn instance_of() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        function Foo() {}
+        var foo = new Foo();
+        var Bound = Foo.bind(null);
+        var even = {};
+        even[Symbol.hasInstance] = function (value) {
+            return value % 2 == 0;
+        };
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "foo instanceof Foo"), "true");
+    assert_eq!(forward(&mut engine, "foo instanceof Object"), "true");
+    assert_eq!(forward(&mut engine, "({}) instanceof Foo"), "false");
+    assert_eq!(forward(&mut engine, "1 instanceof Foo"), "false");
+    assert_eq!(forward(&mut engine, "foo instanceof Bound"), "true");
+    assert_eq!(forward(&mut engine, "[] instanceof Array"), "true");
+    assert_eq!(forward(&mut engine, "Foo instanceof Function"), "true");
+    assert_eq!(forward(&mut engine, "2 instanceof even"), "true");
+    assert_eq!(forward(&mut engine, "3 instanceof even"), "false");
+    assert_eq!(
+        forward(&mut engine, "foo instanceof 1"),
+        "Error: TypeError: right-hand side of 'instanceof' is not an object, got 1"
+    );
+    assert_eq!(
+        forward(&mut engine, "foo instanceof foo"),
+        "Error: TypeError: right-hand side of 'instanceof' is not callable"
+    );
+    assert_eq!(
+        forward(&mut engine, "Foo.prototype = 1; foo instanceof Foo"),
+        "Error: TypeError: Function has non-object prototype in instanceof check"
+    );
+}
+
+#[test]
+fn hoisting() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var before = f();
+        function f() {
+            return inner;
+            function inner() {}
+        }
+        var hoisted = x;
+        var x = 5;
+        function g() {
+            var a = y;
+            var y = 1;
+            return a;
+        }
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "before.name"), "inner");
+    assert_eq!(forward(&mut engine, "hoisted"), "undefined");
+    assert_eq!(forward(&mut engine, "x"), "5");
+    assert_eq!(forward(&mut engine, "g()"), "undefined");
+    assert_eq!(forward(&mut engine, "{ var inBlock = 1; } inBlock"), "1");
+}
+
+#[test]
+fn temporal_dead_zone() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    assert_eq!(
+        forward(&mut engine, "a; let a = 1;"),
+        "Error: ReferenceError: Cannot access 'a' before initialization"
+    );
+    assert_eq!(
+        forward(&mut engine, "{ b = 2; const b = 1; }"),
+        "Error: ReferenceError: Cannot access 'b' before initialization"
+    );
+    assert_eq!(
+        forward(&mut engine, "function f() { return c; } f(); let c = 1;"),
+        "Error: ReferenceError: Cannot access 'c' before initialization"
+    );
+    assert_eq!(
+        forward(&mut engine, "let d = 1; { d; let d = 2; }"),
+        "Error: ReferenceError: Cannot access 'd' before initialization"
+    );
+    assert_eq!(
+        forward(&mut engine, "function h() { return e; } let e = 3; h()"),
+        "3"
+    );
+}
+
+#[test]
+fn calling_non_functions() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    assert_eq!(
+        forward(&mut engine, "var o = { a: 1 }; o.foo()"),
+        "Error: TypeError: o.foo is not a function"
+    );
+    assert_eq!(
+        forward(&mut engine, "o.a()"),
+        "Error: TypeError: o.a is not a function"
+    );
+    assert_eq!(
+        forward(&mut engine, "o['a']()"),
+        "Error: TypeError: o[\"a\"] is not a function"
+    );
+    assert_eq!(
+        forward(&mut engine, "missing()"),
+        "Error: TypeError: missing is not a function"
+    );
+
+    let mut this = Value::undefined();
+    let error = engine.call(&Value::from(1), &mut this, &[]).unwrap_err();
+    assert_eq!(error.to_string(), "TypeError: 1 is not a function");
+    let object = forward_val(&mut engine, "o").unwrap();
+    let error = engine.call(&object, &mut this, &[]).unwrap_err();
+    assert_eq!(error.to_string(), "TypeError: object is not a function");
+}
+
+#[test]
+fn assignment_to_constants() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let error = "Error: TypeError: Assignment to constant variable.";
+    assert_eq!(forward(&mut engine, "const C = 1; C = 2;"), error);
+    assert_eq!(forward(&mut engine, "C += 1"), error);
+    assert_eq!(forward(&mut engine, "C++"), error);
+    assert_eq!(forward(&mut engine, "C"), "1");
+    assert_eq!(
+        forward(&mut engine, "function f() { const D = 1; D = 2; } f()"),
+        error
+    );
+    assert_eq!(forward(&mut engine, "{ const E = 1; E = 2; }"), error);
+    assert_eq!(
+        forward(&mut engine, "let L = 1; L = 2; { const L = 3; } L"),
+        "2"
+    );
+}
+
+#[test]
+fn lexical_declarations_across_scripts() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    forward(&mut engine, "let a = 1; var b = 2;");
+    assert_eq!(forward(&mut engine, "a + b"), "3");
+    assert_eq!(
+        forward(&mut engine, "let a = 2;"),
+        "Error: SyntaxError: Identifier 'a' has already been declared"
+    );
+    assert_eq!(
+        forward(&mut engine, "var a;"),
+        "Error: SyntaxError: Identifier 'a' has already been declared"
+    );
+    assert_eq!(
+        forward(&mut engine, "let b;"),
+        "Error: SyntaxError: Identifier 'b' has already been declared"
+    );
+    assert_eq!(
+        forward(&mut engine, "{ let c; var c; }"),
+        "Error: SyntaxError: Identifier 'c' has already been declared"
+    );
+    assert_eq!(forward(&mut engine, "b"), "2");
+}
+
+#[test]
+fn block_function_declarations() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        var early = f;
+        {
+            var inside = f();
+            function f() { return 1; }
+        }
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "early"), "undefined");
+    assert_eq!(forward(&mut engine, "inside"), "1");
+    assert_eq!(forward(&mut engine, "f()"), "1");
+    assert_eq!(
+        forward(&mut engine, "'use strict'; { function g() {} } g = 1"),
+        "Error: ReferenceError: g is not defined"
+    );
+}
+
+#[test]
+fn eval_declarations() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    let init = r#"
+        function f() {
+            eval("var a = 1; let b = 2;");
+            return a;
+        }
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(forward(&mut engine, "f()"), "1");
+    assert_eq!(forward(&mut engine, "eval('var c = 3'); c"), "3");
+    assert_eq!(forward(&mut engine, "eval('let d = 4'); d"), "undefined");
+    assert_eq!(
+        forward(&mut engine, "eval(\"'use strict'; var e = 5\"); e"),
+        "undefined"
+    );
 }
 
 #[cfg(test)]
@@ -543,10 +815,7 @@ mod in_operator {
     }
 
     #[test]
-    #[ignore]
     fn symbol_in_object() {
-        // FIXME: this scenario works in Firefox's console, this is probably an issue
-        // with Symbol comparison.
         let sym_in_object = r#"
             var sym = Symbol('hi');
             var o = {};
@@ -557,11 +826,677 @@ mod in_operator {
     }
 
     #[test]
-    #[should_panic(expected = "TypeError: undefined is not an Object.")]
     fn should_type_error_when_rhs_not_object() {
         let scenario = r#"
             'fail' in undefined
         "#;
-        exec(scenario);
+        assert_eq!(
+            &exec(scenario),
+            "Error: TypeError: right-hand side of 'in' should be an object, got undefined"
+        );
+    }
+}
+
+#[cfg(test)]
+mod limits {
+    use super::*;
+    use crate::exec::Termination;
+    use std::{
+        thread,
+        time::{Duration, Instant},
+    };
+
+    #[test]
+    fn step_limit() {
+        let realm = Realm::create();
+        let mut engine: Interpreter = Executor::new(realm);
+        engine.set_step_limit(Some(10_000));
+        assert_eq!(forward(&mut engine, "var a = 1 + 2; a"), "3");
+        assert_eq!(
+            forward(&mut engine, "while (true) {}"),
+            "Terminated: execution step limit exceeded"
+        );
+        assert_eq!(engine.termination(), Some(Termination::StepLimit));
+    }
+
+    #[test]
+    fn termination_is_sticky() {
+        let realm = Realm::create();
+        let mut engine: Interpreter = Executor::new(realm);
+        engine.set_step_limit(Some(10_000));
+        let scenario = r#"
+            var swallowed = { toString: function () { while (true) {} } };
+            String(swallowed);
+            "after";
+            "#;
+        assert_eq!(
+            forward(&mut engine, scenario),
+            "Terminated: execution step limit exceeded"
+        );
+        assert_eq!(
+            forward(&mut engine, "1"),
+            "Terminated: execution step limit exceeded"
+        );
+
+        engine.clear_termination();
+        engine.set_step_limit(None);
+        assert_eq!(forward(&mut engine, "1"), "1");
+    }
+
+    #[test]
+    fn termination_in_callback() {
+        let realm = Realm::create();
+        let mut engine: Interpreter = Executor::new(realm);
+        engine.set_step_limit(Some(10_000));
+        assert_eq!(
+            forward(
+                &mut engine,
+                "[1, 2].forEach(function () { while (true) {} })"
+            ),
+            "Terminated: execution step limit exceeded"
+        );
+    }
+
+    #[test]
+    fn deadline() {
+        let realm = Realm::create();
+        let mut engine: Interpreter = Executor::new(realm);
+        engine.set_deadline(Some(Instant::now() + Duration::from_millis(50)));
+        assert_eq!(
+            forward(&mut engine, "while (true) {}"),
+            "Terminated: execution deadline exceeded"
+        );
+        assert_eq!(engine.termination(), Some(Termination::Deadline));
+    }
+
+    #[test]
+    fn heap_stats() {
+        let realm = Realm::create();
+        let mut engine: Interpreter = Executor::new(realm);
+        assert_eq!(engine.heap_stats().used_bytes, 0);
+        forward(&mut engine, "var a = Array(1000);");
+        let stats = engine.heap_stats();
+        assert!(stats.used_bytes > 1000);
+        assert!(stats.peak_bytes >= stats.used_bytes);
+        assert_eq!(stats.limit, None);
+    }
+
+    #[test]
+    fn heap_limit() {
+        let realm = Realm::create();
+        let mut engine: Interpreter = Executor::new(realm);
+        engine.set_heap_limit(Some(256 * 1024));
+        assert_eq!(
+            forward(&mut engine, "Array(1e9).fill()"),
+            "Error: RangeError: Out of memory"
+        );
+        assert_eq!(
+            forward(&mut engine, "'x'.repeat(1e9)"),
+            "Error: RangeError: Out of memory"
+        );
+        assert_eq!(
+            forward(&mut engine, "'x'.padEnd(1e9)"),
+            "Error: RangeError: Out of memory"
+        );
+        // The garbage was collected, so the interpreter can go on
+        assert_eq!(forward(&mut engine, "[1, 2, 3].length"), "3");
+        let stats = engine.heap_stats();
+        assert!(stats.collections > 0);
+        assert!(stats.used_bytes <= 256 * 1024);
+        assert!(stats.peak_bytes <= 256 * 1024);
+    }
+
+    #[test]
+    fn heap_limit_peak() {
+        const LIMIT: usize = 1024 * 1024;
+        let scenarios = [
+            "var s = 'x'.repeat(1000); while (true) { s = s + s; }",
+            "var s = 'x'.repeat(1000); while (true) { s += s; }",
+            "var a = []; while (true) { a.push(a.length); }",
+            "var o = {}; var i = 0; while (true) { o['k' + i] = [i, i]; i++; }",
+            "var a = Array(3000).fill('a string long enough to be charged'); a.join(a.join());",
+            "var a = Array(3000).fill(123456789); JSON.stringify([a, a, a, a, a, a, a, a, a, a, a, a, a, a, a, a]);",
+            "var a = Array(2000).fill(1); var k = Object.keys(a); Object.entries(a);",
+            "var s = 'abcdefghij'.repeat(20000); var t = s.toUpperCase(); s.concat(s, t);",
+        ];
+        for scenario in scenarios.iter() {
+            let realm = Realm::create();
+            let mut engine: Interpreter = Executor::new(realm);
+            engine.set_heap_limit(Some(LIMIT));
+            assert_eq!(
+                forward(&mut engine, scenario),
+                "Error: RangeError: Out of memory",
+                "{}",
+                scenario
+            );
+            let stats = engine.heap_stats();
+            assert!(stats.peak_bytes <= LIMIT, "{}: {:?}", scenario, stats);
+        }
+    }
+
+    #[test]
+    fn heap_limit_termination() {
+        let realm = Realm::create();
+        let mut engine: Interpreter = Executor::new(realm);
+        engine.set_heap_limit(Some(256 * 1024));
+        let scenario = r#"
+            while (true) {
+                kept.push("a string long enough to be charged " + kept.length);
+            }
+            "#;
+        forward(&mut engine, "var kept = [];");
+        assert_eq!(
+            forward(&mut engine, scenario),
+            "Error: RangeError: Out of memory"
+        );
+        // The array still holds the memory, going over the limit again terminates the script
+        assert_eq!(forward(&mut engine, scenario), "Terminated: out of memory");
+        assert_eq!(engine.termination(), Some(Termination::OutOfMemory));
+    }
+
+    #[test]
+    fn interrupt_handle() {
+        let realm = Realm::create();
+        let mut engine: Interpreter = Executor::new(realm);
+        let handle = engine.interrupt_handle();
+        let interrupter = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            handle.interrupt();
+        });
+        assert_eq!(
+            forward(&mut engine, "while (true) {}"),
+            "Terminated: execution interrupted"
+        );
+        interrupter.join().expect("interrupter thread panicked");
+
+        engine.clear_termination();
+        assert!(!engine.interrupt_handle().is_interrupted());
+        assert_eq!(forward(&mut engine, "1 + 1"), "2");
+    }
+
+    /// Scenarios which spend their time in a single call of a builtin, with the code setting
+    /// them up and, for `fill`, the code checking that it was stopped halfway.
+    const BUILTIN_SCENARIOS: [(&str, &str, &str); 4] = [
+        (
+            "",
+            r#"/(a+)+b/.test("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaac")"#,
+            "",
+        ),
+        (
+            "",
+            "Array.prototype.includes.call({ length: 9007199254740991 }, 1)",
+            "",
+        ),
+        (
+            "var a = new Uint8Array(4194304);",
+            "a.fill(1)",
+            "a[0] + ',' + a[a.length - 1]",
+        ),
+        (
+            "var a = new Uint8Array(4194304); var b = new Uint8Array(4194304).fill(1);",
+            "a.set(b)",
+            "",
+        ),
+    ];
+
+    #[test]
+    fn deadline_in_builtins() {
+        for (setup, scenario, partly_written) in BUILTIN_SCENARIOS.iter() {
+            let realm = Realm::create();
+            let mut engine: Interpreter = Executor::new(realm);
+            forward(&mut engine, setup);
+            engine.set_deadline(Some(Instant::now() + Duration::from_millis(10)));
+            assert_eq!(
+                forward(&mut engine, scenario),
+                "Terminated: execution deadline exceeded",
+                "{}",
+                scenario
+            );
+
+            engine.clear_termination();
+            engine.set_deadline(None);
+            if !partly_written.is_empty() {
+                assert_eq!(forward(&mut engine, partly_written), "1,0", "{}", scenario);
+            }
+        }
+    }
+
+    #[test]
+    fn interrupt_in_builtins() {
+        for (setup, scenario, partly_written) in BUILTIN_SCENARIOS.iter() {
+            let realm = Realm::create();
+            let mut engine: Interpreter = Executor::new(realm);
+            forward(&mut engine, setup);
+            let handle = engine.interrupt_handle();
+            let interrupter = thread::spawn(move || {
+                thread::sleep(Duration::from_millis(10));
+                handle.interrupt();
+            });
+            assert_eq!(
+                forward(&mut engine, scenario),
+                "Terminated: execution interrupted",
+                "{}",
+                scenario
+            );
+            interrupter.join().expect("interrupter thread panicked");
+
+            engine.clear_termination();
+            if !partly_written.is_empty() {
+                assert_eq!(forward(&mut engine, partly_written), "1,0", "{}", scenario);
+            }
+        }
+    }
+}
+
+#[test]
+fn call_depth_limit() {
+    let realm = Realm::create();
+    let mut engine: Interpreter = Executor::new(realm);
+    let init = r#"
+        var depth = 0;
+        function f() {
+            depth++;
+            f();
+        }
+        function C() {
+            new C();
+        }
+        "#;
+    forward(&mut engine, init);
+    assert_eq!(
+        forward(&mut engine, "f()"),
+        "Error: RangeError: Maximum call stack size exceeded"
+    );
+    assert_eq!(forward(&mut engine, "depth"), "10000");
+    assert_eq!(
+        forward(&mut engine, "new C()"),
+        "Error: RangeError: Maximum call stack size exceeded"
+    );
+    // The depth is back to zero once the error was thrown
+    assert_eq!(
+        forward(
+            &mut engine,
+            "function g(n) { if (n == 0) { return 0; } return g(n - 1) + 1; } g(100)"
+        ),
+        "100"
+    );
+
+    engine.set_max_call_depth(10);
+    assert_eq!(forward(&mut engine, "g(9)"), "9");
+    assert_eq!(
+        forward(&mut engine, "g(10)"),
+        "Error: RangeError: Maximum call stack size exceeded"
+    );
+}
+
+#[test]
+fn deeply_nested_expression() {
+    let nested = format!("{}1{}", "(1 + ".repeat(900), ")".repeat(900));
+    assert_eq!(exec(&nested), "901");
+}
+
+#[test]
+fn syntax_errors_are_thrown() {
+    let realm = Realm::create();
+    let mut engine: Interpreter = Executor::new(realm);
+    let error = forward_val(&mut engine, "let a = ;").expect_err("parsed invalid code");
+    assert_eq!(error.get_field_slice("name").to_string(), "SyntaxError");
+    assert!(forward(&mut engine, "1 +* 2").starts_with("SyntaxError: "));
+    // Nothing ran, the engine is still usable
+    assert_eq!(forward(&mut engine, "1 + 1"), "2");
+}
+
+/// Why and where the interpreter stopped, with the names of the frames.
+type Pause = (PauseReason, u64, Vec<String>);
+
+/// A debug handler recording where the interpreter stopped, it resumes as it is told to.
+#[derive(Debug)]
+struct Recorder {
+    pauses: Rc<RefCell<Vec<Pause>>>,
+    resumes: Vec<Resume>,
+}
+
+impl DebugHandler for Recorder {
+    fn paused(&mut self, engine: &mut Interpreter, reason: PauseReason) -> Resume {
+        let frames = engine.stack_frames();
+        let line = frames[0].position.map_or(0, |pos| pos.line_number);
+        let names = frames.into_iter().map(|frame| frame.name).collect();
+        self.pauses.borrow_mut().push((reason, line, names));
+        if self.resumes.is_empty() {
+            Resume::Continue
+        } else {
+            self.resumes.remove(0)
+        }
+    }
+}
+
+/// Runs a script parsed with locations under a `Recorder`, and returns where it stopped.
+fn debug(src: &str, breakpoints: &[u64], resumes: &[Resume]) -> Vec<Pause> {
+    let mut lexer = Lexer::new(src);
+    lexer.lex().unwrap();
+    let script = Parser::new(&lexer.tokens)
+        .with_locations()
+        .parse_all()
+        .unwrap();
+    let pauses = Rc::new(RefCell::new(Vec::new()));
+    let mut engine: Interpreter = Executor::new(Realm::create());
+    engine.set_breakpoints(breakpoints.iter().cloned());
+    engine.set_debug_handler(Some(Box::new(Recorder {
+        pauses: pauses.clone(),
+        resumes: resumes.to_vec(),
+    })));
+    engine.run(&script).unwrap();
+    let pauses = pauses.borrow().clone();
+    pauses
+}
+
+const DEBUGGED: &str = r#"function add(a, b) {
+    let sum = a + b;
+    return sum;
+}
+var x = add(1, 2);
+debugger;
+x;
+"#;
+
+#[test]
+fn debugger_breakpoints() {
+    let script = || vec!["(script)".to_string()];
+    let in_add = || vec!["add".to_string(), "(script)".to_string()];
+    assert_eq!(
+        debug(DEBUGGED, &[2], &[]),
+        vec![
+            (PauseReason::Breakpoint, 2, in_add()),
+            (PauseReason::DebuggerStatement, 6, script()),
+        ]
+    );
+    // Without a handler, nothing stops
+    assert_eq!(&exec("debugger; 1"), "1");
+}
+
+#[test]
+fn debugger_stepping() {
+    let steps = |resumes: &[Resume]| -> Vec<(PauseReason, u64)> {
+        debug(DEBUGGED, &[5], resumes)
+            .into_iter()
+            .map(|(reason, line, _)| (reason, line))
+            .collect()
+    };
+    assert_eq!(
+        steps(&[Resume::StepIn, Resume::StepOver, Resume::StepOver]),
+        vec![
+            (PauseReason::Breakpoint, 5),
+            (PauseReason::Step, 2),
+            (PauseReason::Step, 3),
+            (PauseReason::Step, 6),
+        ]
+    );
+    assert_eq!(
+        steps(&[Resume::StepIn, Resume::StepOut]),
+        vec![
+            (PauseReason::Breakpoint, 5),
+            (PauseReason::Step, 2),
+            (PauseReason::Step, 6),
+        ]
+    );
+    assert_eq!(
+        steps(&[Resume::StepOver]),
+        vec![(PauseReason::Breakpoint, 5), (PauseReason::Step, 6)]
+    );
+}
+
+#[test]
+fn debugger_scopes() {
+    #[derive(Debug)]
+    struct Inspector(Rc<RefCell<Vec<String>>>);
+
+    impl DebugHandler for Inspector {
+        fn paused(&mut self, engine: &mut Interpreter, reason: PauseReason) -> Resume {
+            if reason != PauseReason::Breakpoint {
+                return Resume::Continue;
+            }
+            let mut seen = self.0.borrow_mut();
+            for scope in engine.scopes(0) {
+                for (name, value) in scope.variables() {
+                    let value = value.map_or("<uninitialized>".to_string(), |v| v.to_string());
+                    seen.push(format!("{:?} {} = {}", scope.kind(), name, value));
+                }
+            }
+            let script_scopes = engine.scopes(1);
+            assert_eq!(script_scopes.len(), 1);
+            assert_eq!(script_scopes[0].kind(), EnvironmentType::Global);
+            seen.push(engine.debug_eval("a + b * 10").unwrap().to_string());
+            Resume::Continue
+        }
+    }
+
+    let mut lexer = Lexer::new(DEBUGGED);
+    lexer.lex().unwrap();
+    let script = Parser::new(&lexer.tokens)
+        .with_locations()
+        .parse_all()
+        .unwrap();
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let mut engine: Interpreter = Executor::new(Realm::create());
+    engine.set_breakpoints(vec![3]);
+    engine.set_debug_handler(Some(Box::new(Inspector(seen.clone()))));
+    assert_eq!(engine.run(&script).unwrap().to_string(), "3");
+
+    let seen = seen.borrow();
+    assert!(seen.contains(&"Function a = 1".to_string()), "{:?}", seen);
+    assert!(
+        seen.iter().any(|line| line.ends_with("sum = 3")),
+        "{:?}",
+        seen
+    );
+    assert!(
+        seen.contains(&"Global x = undefined".to_string()),
+        "{:?}",
+        seen
+    );
+    assert!(
+        seen.iter().any(|line| line.starts_with("Global add = ")),
+        "{:?}",
+        seen
+    );
+    assert_eq!(seen.last().unwrap(), "21");
+}
+
+#[test]
+fn profiler() {
+    let mut engine: Interpreter = Executor::new(Realm::create());
+    assert!(engine.stop_profiling().is_none());
+    engine.start_profiling();
+    let src = r#"
+        function fib(n) {
+            if (n < 2) {
+                return Math.max(n, 0);
+            }
+            return fib(n - 1) + fib(n - 2);
+        }
+        var anonymous = function() { return fib(5); };
+        anonymous();
+    "#;
+    assert_eq!(forward(&mut engine, src), "5");
+    let profile = engine.stop_profiling().unwrap();
+
+    let functions = profile.functions();
+    let function = |name: &str| functions.iter().find(|f| f.name == name).unwrap();
+    assert_eq!(functions[0].name, "(script)");
+    assert_eq!(function("(script)").calls, 1);
+    assert_eq!(function("(anonymous)").calls, 1);
+    assert_eq!(function("fib").calls, 15);
+    assert_eq!(function("max").calls, 8);
+    assert!(function("max").native);
+    assert!(!function("fib").native);
+    for f in &functions {
+        assert!(f.self_time <= f.total_time, "{:?}", f);
+    }
+    // The recursive calls of `fib` run inside its outermost call
+    assert!(function("fib").total_time <= function("(anonymous)").total_time);
+    assert!(function("(anonymous)").total_time <= function("(script)").total_time);
+
+    let folded = profile.folded();
+    for line in folded.lines() {
+        let (stack, micros) = line.split_at(line.rfind(' ').unwrap());
+        assert!(stack.starts_with("(script)"), "{}", line);
+        assert!(micros.trim().parse::<u64>().is_ok(), "{}", line);
+        assert!(!stack.contains("max;"), "{}", line);
+    }
+}
+
+#[test]
+fn profiler_tells_functions_apart() {
+    let mut engine: Interpreter = Executor::new(Realm::create());
+    engine.start_profiling();
+    let src = r#"
+        var a = { f: function f() { return 1; } };
+        var b = { f: function f() { return 2; } };
+        var first = function() { return 3; }, second = function() { return 4; };
+        function adder(x) { return function(y) { return x + y; }; }
+        a.f(); b.f(); b.f(); first(); second();
+        adder(1)(2) + adder(3)(4);
+    "#;
+    assert_eq!(forward(&mut engine, src), "10");
+    let profile = engine.stop_profiling().unwrap();
+
+    let functions = profile.functions();
+    let calls = |label: &str| {
+        let function = functions.iter().find(|f| f.label() == label);
+        function
+            .unwrap_or_else(|| panic!("{} in {:?}", label, functions))
+            .calls
+    };
+    assert_eq!(calls("f (2:22)"), 1);
+    assert_eq!(calls("f (3:22)"), 2);
+    assert_eq!(calls("(anonymous) (4:21)"), 1);
+    assert_eq!(calls("(anonymous) (4:56)"), 1);
+    // The closures returned by `adder` are the same function
+    assert_eq!(calls("adder (5:9)"), 2);
+    assert_eq!(calls("(anonymous) (5:36)"), 2);
+    assert!(profile.folded().contains("(script);f (3:22) "));
+}
+
+/// Runs a script parsed with locations, and returns the line and column of the statement which
+/// threw the uncaught error.
+fn throw_position(engine: &mut Interpreter, src: &str) -> Option<(u64, u64)> {
+    let mut lexer = Lexer::new(src);
+    lexer.lex().unwrap();
+    let script = Parser::new(&lexer.tokens)
+        .with_locations()
+        .parse_all()
+        .unwrap();
+    let error = engine.run(&script).unwrap_err();
+    engine
+        .throw_position(&error)
+        .map(|pos| (pos.line_number, pos.column_number))
+}
+
+#[test]
+fn throw_positions() {
+    let mut engine: Interpreter = Executor::new(Realm::create());
+    let mut position = |src| throw_position(&mut engine, src);
+    assert_eq!(position("var a = 1;\n  throw 'x';"), Some((2, 3)));
+    // The innermost statement is recorded, not the call which the error goes through
+    let src = "function f() {\n    new Array(-1);\n}\nf();";
+    assert_eq!(position(src), Some((2, 5)));
+    // A call which returned doesn't move the position of a later error
+    let src = "function g() {\n    return 1;\n}\ng(); new Array(-1);";
+    assert_eq!(position(src), Some((4, 6)));
+    // A value thrown again is recorded where it's thrown last
+    assert_eq!(position("var e = new Error('x');\nthrow e;"), Some((2, 1)));
+    assert_eq!(position("\n\n  throw e;"), Some((3, 3)));
+    // Code parsed without locations, like an `eval`, throws from the statement running it
+    assert_eq!(position("\neval('throw 1');"), Some((2, 1)));
+
+    let error = forward_val(&mut engine, "throw 1;").unwrap_err();
+    assert_eq!(engine.throw_position(&error), None);
+}
+
+#[test]
+fn to_primitive_conversions() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    forward(
+        &mut engine,
+        "var o = {}; o[Symbol.toPrimitive] = function(hint) { if (hint === 'number') { return 3; } return 'o'; };",
+    );
+    assert_eq!(forward(&mut engine, "+o"), "3");
+    assert_eq!(forward(&mut engine, "o * 2"), "6");
+    assert_eq!(forward(&mut engine, "-o"), "-3");
+    assert_eq!(forward(&mut engine, "o | 4"), "7");
+    assert_eq!(forward(&mut engine, "o > 2"), "true");
+    assert_eq!(forward(&mut engine, "o + 1"), "o1");
+    assert_eq!(forward(&mut engine, "var n = o; n++; n"), "4");
+    assert_eq!(
+        forward(
+            &mut engine,
+            "var m = { valueOf() { return 5; } }; m -= 1; m"
+        ),
+        "4"
+    );
+
+    let error = "Error: TypeError: Cannot convert object to primitive value";
+    assert_eq!(
+        forward(
+            &mut engine,
+            "var p = {}; p[Symbol.toPrimitive] = function() { return {}; }; +p"
+        ),
+        error
+    );
+    assert_eq!(
+        forward(
+            &mut engine,
+            "+{ valueOf() { return {}; }, toString() { return {}; } }"
+        ),
+        error
+    );
+    // Functions are converted like other objects
+    assert_eq!(
+        forward(&mut engine, "function g() { return 1; } g + '!'"),
+        "function g() { return 1; }!"
+    );
+    assert_eq!(forward(&mut engine, "+g"), "NaN");
+    assert_eq!(
+        forward(&mut engine, "({ valueOf() { throw 1; } }) + 1"),
+        "Error: 1"
+    );
+    assert_eq!(
+        forward(
+            &mut engine,
+            "({ toString() { throw 2; } })[{ toString() { throw 3; } }]"
+        ),
+        "Error: 3"
+    );
+}
+
+#[test]
+fn deeply_nested_operations() {
+    let realm = Realm::create();
+    let mut engine = Executor::new(realm);
+    forward(
+        &mut engine,
+        "var a = { b: null }; a.b = a; var arr = [0]; arr[0] = arr; function f(x) { return x; }",
+    );
+
+    // Chains the parser accepts are evaluated without overflowing the stack
+    let sum = format!("1{}", " + 1".repeat(900));
+    assert_eq!(forward(&mut engine, &sum), "901");
+    let access = format!("a{} === a", ".b".repeat(900));
+    assert_eq!(forward(&mut engine, &access), "true");
+    let calls = format!("{}1{}", "f(".repeat(600), ")".repeat(600));
+    assert_eq!(forward(&mut engine, &calls), "1");
+
+    // Longer ones are a syntax error
+    let too_deep = [
+        format!("1{}", " + 1".repeat(10_000)),
+        format!("a{}", ".b".repeat(10_000)),
+        format!("arr{}", "[0]".repeat(10_000)),
+        format!("f(1){}", "(1)".repeat(10_000)),
+        format!("{}f", "new ".repeat(10_000)),
+        format!("{}1{}", "f(".repeat(10_000), ")".repeat(10_000)),
+    ];
+    for js in too_deep.iter() {
+        assert!(forward(&mut engine, js).starts_with("SyntaxError: Maximum nesting depth exceeded"));
     }
 }
@@ -0,0 +1,178 @@
+//! Accounting of the memory allocated by scripts.
+//!
+//! Values are allocated by the `gc` crate, which doesn't tell how much memory it holds. Instead the
+//! data which grows with what a script does, the properties of objects, the code units of strings
+//! and the bytes of array buffers, carries a [`Charge`] on the account of the interpreter which was
+//! running when it was created. The charge is refunded when the data is dropped, after the garbage
+//! collector freed it.
+//!
+//! The accounting is an estimate: the sizes of the allocations are computed from the sizes of the
+//! types, and data created outside of any interpreter, such as the builtins of a realm, isn't
+//! charged.
+
+use std::{
+    cell::{Cell, RefCell},
+    fmt,
+    rc::Rc,
+};
+
+thread_local! {
+    /// The account of the interpreter running on this thread.
+    static CURRENT: RefCell<Option<Rc<Account>>> = RefCell::new(None);
+}
+
+/// The memory usage of an interpreter, as reported to the embedder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeapStats {
+    /// The number of bytes currently charged to the interpreter.
+    pub used_bytes: usize,
+    /// The highest number of bytes charged to the interpreter at once.
+    pub peak_bytes: usize,
+    /// The heap limit of the interpreter, if it has one.
+    pub limit: Option<usize>,
+    /// The number of garbage collections triggered by the heap limit.
+    pub collections: usize,
+}
+
+/// The memory charged to an interpreter.
+#[derive(Debug, Default)]
+pub(crate) struct Account {
+    used: Cell<usize>,
+    peak: Cell<usize>,
+    collections: Cell<usize>,
+}
+
+impl Account {
+    pub(crate) fn used(&self) -> usize {
+        self.used.get()
+    }
+
+    pub(crate) fn count_collection(&self) {
+        self.collections.set(self.collections.get() + 1);
+    }
+
+    pub(crate) fn stats(&self, limit: Option<usize>) -> HeapStats {
+        HeapStats {
+            used_bytes: self.used.get(),
+            peak_bytes: self.peak.get(),
+            limit,
+            collections: self.collections.get(),
+        }
+    }
+
+    fn add(&self, bytes: usize) {
+        let used = self.used.get().saturating_add(bytes);
+        self.used.set(used);
+        if used > self.peak.get() {
+            self.peak.set(used);
+        }
+    }
+
+    fn sub(&self, bytes: usize) {
+        self.used.set(self.used.get().saturating_sub(bytes));
+    }
+}
+
+/// Makes an account the current one, which new charges go to, until the returned scope is dropped.
+///
+/// Nothing is returned if the account already is the current one.
+pub(crate) fn enter(account: &Rc<Account>) -> Option<Scope> {
+    CURRENT
+        .try_with(|current| {
+            let mut current = current.borrow_mut();
+            match *current {
+                Some(ref running) if Rc::ptr_eq(running, account) => None,
+                _ => Some(Scope(current.replace(account.clone()))),
+            }
+        })
+        .ok()
+        .flatten()
+}
+
+/// Stops charging the current account until the returned scope is dropped, for the few values
+/// which have to be created when the account has no room left, like the error reporting it.
+pub(crate) fn suspend() -> Option<Scope> {
+    CURRENT
+        .try_with(|current| Scope(current.borrow_mut().take()))
+        .ok()
+}
+
+/// Restores the account which was current before `enter` or `suspend` when it is dropped.
+#[derive(Debug)]
+pub(crate) struct Scope(Option<Rc<Account>>);
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        let _ = CURRENT.try_with(|current| *current.borrow_mut() = previous);
+    }
+}
+
+fn current() -> Option<Rc<Account>> {
+    CURRENT
+        .try_with(|current| current.borrow().clone())
+        .ok()
+        .flatten()
+}
+
+/// A number of bytes charged to the current account when it is created, and refunded when it is
+/// dropped. A clone is charged again, as the data it belongs to is copied as well.
+pub(crate) struct Charge {
+    bytes: usize,
+    account: Option<Rc<Account>>,
+}
+
+impl Charge {
+    pub(crate) fn new(bytes: usize) -> Self {
+        let account = current();
+        if let Some(ref account) = account {
+            account.add(bytes);
+        }
+        Self { bytes, account }
+    }
+
+    /// Changes the number of bytes charged. A charge created outside of any interpreter moves to
+    /// the current account as soon as it grows in one.
+    pub(crate) fn resize(&mut self, bytes: usize) {
+        if self.account.is_none() {
+            self.account = current();
+            if let Some(ref account) = self.account {
+                account.add(self.bytes);
+            }
+        }
+        if let Some(ref account) = self.account {
+            if bytes > self.bytes {
+                account.add(bytes - self.bytes);
+            } else {
+                account.sub(self.bytes - bytes);
+            }
+        }
+        self.bytes = bytes;
+    }
+}
+
+impl Default for Charge {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl Clone for Charge {
+    fn clone(&self) -> Self {
+        Self::new(self.bytes)
+    }
+}
+
+impl Drop for Charge {
+    fn drop(&mut self) {
+        if let Some(ref account) = self.account {
+            account.sub(self.bytes);
+        }
+    }
+}
+
+impl fmt::Debug for Charge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Charge({})", self.bytes)
+    }
+}
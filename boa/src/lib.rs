@@ -36,7 +36,9 @@
 pub mod builtins;
 pub mod environment;
 pub mod exec;
+pub mod heap;
 pub mod realm;
+pub mod snapshot;
 pub mod syntax;
 use crate::{
     builtins::value::ResultValue,
@@ -45,44 +47,48 @@ use crate::{
     syntax::{ast::node::Node, lexer::Lexer, parser::Parser},
 };
 
+/// Lexes and parses the source, the error is the message of the `SyntaxError` it has.
 fn parser_expr(src: &str) -> Result<Node, String> {
     let mut lexer = Lexer::new(src);
-    lexer.lex().map_err(|e| format!("SyntaxError: {}", e))?;
+    lexer.lex().map_err(|e| e.to_string())?;
     let tokens = lexer.tokens;
-    Parser::new(&tokens)
+    Parser::with_source(&tokens, src)
         .parse_all()
-        .map_err(|e| format!("ParsingError: {}", e))
+        .map_err(|e| e.to_string())
 }
 
 /// Execute the code using an existing Interpreter
 /// The str is consumed and the state of the Interpreter is changed
+/// Code stopped by the execution limits of the Interpreter gives `Terminated: <reason>`
 pub fn forward(engine: &mut Interpreter, src: &str) -> String {
     // Setup executor
     let expr = match parser_expr(src) {
         Ok(v) => v,
-        Err(error_string) => {
-            return error_string;
+        Err(message) => {
+            return format!("SyntaxError: {}", message);
         }
     };
     let result = engine.run(&expr);
     match result {
         Ok(v) => v.to_string(),
-        Err(v) => format!("{}: {}", "Error", v.to_string()),
+        Err(v) => match engine.termination() {
+            Some(termination) => format!("Terminated: {}", termination),
+            None => format!("{}: {}", "Error", v.to_string()),
+        },
     }
 }
 
 /// Execute the code using an existing Interpreter.
 /// The str is consumed and the state of the Interpreter is changed
 /// Similar to `forward`, except the current value is returned instad of the string
-/// If the interpreter fails parsing an error value is returned instead (error object)
+/// If the code can't be parsed a `SyntaxError` is returned as the error, and nothing is run
+/// When the code is stopped by the execution limits the error is the reason of the termination,
+/// `Interpreter::termination` tells it apart from a value thrown by the script
 pub fn forward_val(engine: &mut Interpreter, src: &str) -> ResultValue {
     // Setup executor
     match parser_expr(src) {
         Ok(expr) => engine.run(&expr),
-        Err(e) => {
-            eprintln!("{}", e);
-            std::process::exit(1);
-        }
+        Err(message) => engine.throw_syntax_error(message),
     }
 }
 
@@ -0,0 +1,469 @@
+//! Conceptually, a realm consists of a set of intrinsic objects, an ECMAScript global environment,
+//! all of the ECMAScript code that is loaded within the scope of that global environment,
+//! and other associated state and resources.
+//!
+//! A realm is represented in this implementation as a Realm struct with the fields specified from the spec.
+
+use crate::{
+    builtins::{
+        self,
+        function::NativeFunctionData,
+        harden,
+        object::internal_methods_trait::ObjectInternalMethods,
+        property::Property,
+        value::{JsString, Value, ValueData},
+    },
+    environment::{
+        declarative_environment_record::DeclarativeEnvironmentRecord,
+        global_environment_record::GlobalEnvironmentRecord,
+        lexical_environment::{Environment, LexicalEnvironment},
+        object_environment_record::ObjectEnvironmentRecord,
+    },
+    snapshot::{SnapshotError, Snapshotter},
+};
+use gc::{unsafe_empty_trace, Finalize, Gc, GcCell, Trace};
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::{
+    fmt::{self, Debug},
+    iter, ptr,
+    rc::Rc,
+};
+
+/// The host hook deciding whether source text can be compiled at runtime, by `eval` or the
+/// `Function` constructor. It receives the source text and returns `false` to refuse it.
+///
+/// <https://tc39.es/ecma262/#sec-hostensurecancompilestrings>
+#[derive(Clone)]
+pub struct CompileStringsHook(Rc<dyn Fn(&str) -> bool>);
+
+impl Debug for CompileStringsHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CompileStringsHook")
+    }
+}
+
+impl Finalize for CompileStringsHook {}
+
+/// The hook is a closure of the host, which can't capture values of the heap.
+unsafe impl Trace for CompileStringsHook {
+    unsafe_empty_trace!();
+}
+
+/// Representation of a Realm.
+///
+/// In the specification these are called Realm Records.
+#[derive(Debug, Trace, Finalize)]
+pub struct Realm {
+    pub global_obj: Value,
+    pub global_env: Gc<GcCell<Box<GlobalEnvironmentRecord>>>,
+    pub environment: LexicalEnvironment,
+    /// The intrinsic objects, such as `%Array%` or `%Object.prototype%`, which builtins use
+    /// whether or not the global object exposes them
+    pub(crate) intrinsics: Value,
+    /// The `%eval%` intrinsic, a call to it through the name `eval` is a direct eval
+    pub(crate) intrinsic_eval: Value,
+    compile_strings_hook: Option<CompileStringsHook>,
+    /// The standard builtins installed on the global object, the realms of `ShadowRealm`s
+    /// created in this realm get the same ones
+    #[unsafe_ignore_trace]
+    globals: Globals,
+}
+
+/// The handle through which the heap owns a realm while it isn't the running realm.
+///
+/// The intrinsics and the global object of a realm hold its handle: the realm lives as long as
+/// one of them can be reached, e.g. from a `ShadowRealm` object or from a function of the realm.
+/// The handle of the running realm is empty, the interpreter owns it.
+#[derive(Debug, Clone, Trace, Finalize)]
+pub(crate) struct RealmHandle(Gc<GcCell<Option<Realm>>>);
+
+impl RealmHandle {
+    /// Returns the handle of the realm of the given intrinsics or global object.
+    pub(crate) fn of(object: &Value) -> Option<Self> {
+        object.as_object().and_then(|object| object.realm.clone())
+    }
+
+    /// Takes the realm out of its handle, `None` if it is running.
+    pub(crate) fn take(&self) -> Option<Realm> {
+        self.0.borrow_mut().take()
+    }
+
+    /// Hands a realm which stops running over to its handle.
+    pub(crate) fn put(&self, realm: Realm) {
+        *self.0.borrow_mut() = Some(realm);
+    }
+
+    /// Checks if the realm is in its handle, waiting to run.
+    pub(crate) fn is_suspended(&self) -> bool {
+        self.0.borrow().is_some()
+    }
+}
+
+impl PartialEq for RealmHandle {
+    fn eq(&self, other: &Self) -> bool {
+        Gc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Realm {
+    /// Creates a realm with all the standard builtins installed on its global object.
+    pub fn create() -> Self {
+        RealmBuilder::new().build()
+    }
+
+    /// Returns a builder which chooses the global object and the globals of a new realm.
+    pub fn builder() -> RealmBuilder {
+        RealmBuilder::new()
+    }
+
+    /// Creates the realm of a `ShadowRealm` of this realm.
+    ///
+    /// It gets the standard builtins this realm was built with, none of the host objects, and it
+    /// is locked down if this realm is. The host hook of this realm applies to it as well.
+    pub(crate) fn create_child(&self) -> Self {
+        let mut builder = RealmBuilder::new();
+        builder.globals = self.globals.clone();
+        let mut realm = builder.build();
+        realm.compile_strings_hook = self.compile_strings_hook.clone();
+        if self.is_locked_down() {
+            realm.lockdown();
+        }
+        realm
+    }
+
+    /// Restores a realm from an image written by `Realm::snapshot`.
+    ///
+    /// The image must come from the same build of Boa, realms holding host functions need a
+    /// `Snapshotter` knowing them.
+    pub fn from_snapshot(image: &[u8]) -> Result<Self, SnapshotError> {
+        Snapshotter::new().read(image)
+    }
+
+    /// Creates a realm from the objects and the global environment restored from an image.
+    pub(crate) fn from_parts(
+        global: Value,
+        global_env: Environment,
+        intrinsics: Value,
+        globals: Globals,
+    ) -> Self {
+        let this = global_env.borrow().get_this_binding();
+        let intrinsic_eval = intrinsics.get_field_slice("eval");
+        attach_handle(&intrinsics, &global);
+        Realm {
+            global_obj: global.clone(),
+            global_env: new_global_environment(global, this),
+            environment: LexicalEnvironment::from_global_environment(global_env),
+            intrinsics,
+            intrinsic_eval,
+            compile_strings_hook: None,
+            globals,
+        }
+    }
+
+    /// The standard builtins installed on the global object.
+    pub(crate) fn globals(&self) -> &Globals {
+        &self.globals
+    }
+
+    /// Checks if the realm was locked down, its intrinsics are frozen then.
+    fn is_locked_down(&self) -> bool {
+        !self
+            .intrinsics
+            .as_object()
+            .expect("intrinsics are an object")
+            .is_extensible()
+    }
+
+    /// Writes the realm into a byte image, from which `Realm::from_snapshot` restores it with its
+    /// builtins and everything scripts added to it.
+    pub fn snapshot(&self) -> Result<Vec<u8>, SnapshotError> {
+        Snapshotter::new().write(self)
+    }
+
+    /// Utility to add a function to the global object
+    pub fn register_global_func(self, func_name: &str, func: NativeFunctionData) -> Self {
+        let func = crate::builtins::function::Function::create_builtin(
+            vec![],
+            crate::builtins::function::FunctionBody::BuiltIn(func),
+        );
+        let func = ValueData::from_func(func);
+        // Named like the builtins, for stack traces and profiles
+        func.set_field_slice("name", Value::from(func_name));
+        self.global_obj.set_field(Value::from(func_name), func);
+
+        self
+    }
+
+    /// Tamper-proofs the realm: every intrinsic and everything reachable from it is frozen, and
+    /// the global properties holding intrinsics can neither be reassigned nor deleted.
+    ///
+    /// The global object itself stays extensible, scripts can still add their own globals. Host
+    /// objects are left alone, `harden` them before handing them to untrusted code.
+    pub fn lockdown(&self) {
+        // The intrinsics which aren't globals, such as `%ArrayIteratorPrototype%`, are internal
+        // slots
+        let hidden: Vec<Value> = self
+            .intrinsics
+            .as_object()
+            .expect("intrinsics are an object")
+            .internal_slots
+            .values()
+            .cloned()
+            .collect();
+        harden::deep_freeze(iter::once(self.intrinsics.clone()).chain(hidden))
+            .expect("the intrinsics can be frozen");
+
+        let intrinsics = self
+            .intrinsics
+            .as_object()
+            .expect("intrinsics are an object");
+        let is_intrinsic = |value: &Value| {
+            value.is_object()
+                && intrinsics.properties.values().any(|property| {
+                    property
+                        .value
+                        .as_ref()
+                        .map_or(false, |intrinsic| ptr::eq(value.data(), intrinsic.data()))
+                })
+        };
+        let mut global = self
+            .global_obj
+            .as_object_mut()
+            .expect("the global object is an object");
+        let locked: Vec<JsString> = global
+            .properties
+            .iter()
+            .filter(|(_, property)| property.value.as_ref().map_or(false, is_intrinsic))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in locked {
+            let property = Property::new().writable(false).configurable(false);
+            global.define_own_property(Value::from(key), property);
+        }
+    }
+
+    /// Sets the host hook deciding whether `eval` and the `Function` constructor can compile the
+    /// given source text. When it returns `false` they throw an `EvalError` instead.
+    pub fn set_compile_strings_hook<F>(&mut self, hook: F)
+    where
+        F: Fn(&str) -> bool + 'static,
+    {
+        self.compile_strings_hook = Some(CompileStringsHook(Rc::new(hook)));
+    }
+
+    /// Disables the compilation of source text at runtime, for sandboxed realms.
+    pub fn disable_dynamic_code(&mut self) {
+        self.set_compile_strings_hook(|_| false);
+    }
+
+    /// Asks the host hook whether the source text can be compiled, it can without a hook.
+    pub(crate) fn can_compile_strings(&self, source: &str) -> bool {
+        self.compile_strings_hook
+            .as_ref()
+            .map_or(true, |hook| (hook.0)(source))
+    }
+}
+
+/// Which of the standard builtins a `RealmBuilder` installs on the global object.
+#[derive(Debug, Clone)]
+pub(crate) enum Globals {
+    /// Every standard builtin except the named ones.
+    AllExcept(FxHashSet<String>),
+    /// Only the named standard builtins.
+    Only(FxHashSet<String>),
+}
+
+impl Globals {
+    fn includes(&self, name: &str) -> bool {
+        match self {
+            Self::AllExcept(excluded) => !excluded.contains(name),
+            Self::Only(included) => included.contains(name),
+        }
+    }
+}
+
+/// Builds a realm with the globals chosen by the embedder.
+///
+/// The intrinsic objects of the realm are always created, as builtins depend on each other, but
+/// only the selected ones are reachable through the global object. Host objects are added after
+/// the builtins, before any script runs.
+///
+/// The realms of the `ShadowRealm`s created in the realm get the same standard builtins, but not
+/// the host objects. Leave `"ShadowRealm"` out to keep scripts from creating realms at all.
+///
+/// ```
+/// # use boa::{builtins::value::Value, realm::Realm};
+/// let realm = Realm::builder()
+///     .without_global("console")
+///     .host_global("tenant", Value::from("acme"))
+///     .build();
+/// assert!(realm.global_obj.get_field_slice("console").is_undefined());
+/// ```
+#[derive(Debug)]
+pub struct RealmBuilder {
+    globals: Globals,
+    global_object: Option<Value>,
+    global_this: Option<Value>,
+    host_globals: Vec<(String, Value)>,
+}
+
+impl RealmBuilder {
+    /// Creates a builder installing all the standard builtins.
+    pub fn new() -> Self {
+        Self {
+            globals: Globals::AllExcept(FxHashSet::default()),
+            global_object: None,
+            global_this: None,
+            host_globals: Vec::new(),
+        }
+    }
+
+    /// Leaves the standard builtin of the given name, such as `"console"`, out of the global
+    /// object.
+    pub fn without_global(mut self, name: &str) -> Self {
+        match self.globals {
+            Globals::AllExcept(ref mut excluded) => {
+                excluded.insert(name.to_string());
+            }
+            Globals::Only(ref mut included) => {
+                included.remove(name);
+            }
+        }
+        self
+    }
+
+    /// Installs only the standard builtins of the given names on the global object.
+    pub fn only_globals<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.globals = Globals::Only(names.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Uses the given object as the global object, the builtins are installed on it.
+    pub fn global_object(mut self, global: Value) -> Self {
+        debug_assert!(global.is_object(), "the global object must be an object");
+        self.global_object = Some(global);
+        self
+    }
+
+    /// Uses the given object as the `this` value of scripts and `globalThis`, instead of the
+    /// global object.
+    pub fn global_this(mut self, this: Value) -> Self {
+        debug_assert!(this.is_object(), "the global this value must be an object");
+        self.global_this = Some(this);
+        self
+    }
+
+    /// Adds a host object to the global object, it replaces a builtin of the same name.
+    pub fn host_global(mut self, name: &str, value: Value) -> Self {
+        self.host_globals.push((name.to_string(), value));
+        self
+    }
+
+    /// Adds a host function to the global object.
+    pub fn host_function(self, name: &str, func: NativeFunctionData) -> Self {
+        let function = builtins::function::Function::create_builtin(
+            vec![],
+            builtins::function::FunctionBody::BuiltIn(func),
+        );
+        self.host_global(name, ValueData::from_func(function))
+    }
+
+    /// Creates the realm.
+    pub fn build(self) -> Realm {
+        // The intrinsics are created on an object of their own, the global object gets the
+        // selected ones afterwards
+        let intrinsics = Value::new_object(None);
+        builtins::init(&intrinsics);
+
+        // Global has no prototype to pass None to new_obj
+        let global = self
+            .global_object
+            .unwrap_or_else(|| Value::new_object(None));
+        let this = self.global_this.unwrap_or_else(|| global.clone());
+        {
+            let intrinsics = intrinsics.as_object().expect("intrinsics are an object");
+            let mut global = global
+                .as_object_mut()
+                .expect("the global object is an object");
+            for (key, property) in intrinsics.properties.iter() {
+                if self.globals.includes(&key.to_std_string_lossy()) {
+                    global.define_own_property(Value::from(key.clone()), property.clone());
+                }
+            }
+        }
+        if self.globals.includes("globalThis") {
+            global.set_field_slice("globalThis", this.clone());
+        }
+        for (name, value) in self.host_globals {
+            global.set_field_slice(&name, value);
+        }
+
+        // We need to clone the global here because its referenced from separate places (only pointer is cloned)
+        let global_env = new_global_environment(global.clone(), this.clone());
+        let intrinsic_eval = intrinsics.get_field_slice("eval");
+        attach_handle(&intrinsics, &global);
+
+        Realm {
+            global_obj: global.clone(),
+            global_env,
+            environment: LexicalEnvironment::with_this(global, this),
+            intrinsics,
+            intrinsic_eval,
+            compile_strings_hook: None,
+            globals: self.globals,
+        }
+    }
+}
+
+impl Default for RealmBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Gives the intrinsics and the global object of a new realm its empty handle.
+fn attach_handle(intrinsics: &Value, global: &Value) {
+    let handle = RealmHandle(Gc::new(GcCell::new(None)));
+    for object in &[intrinsics, global] {
+        object
+            .as_object_mut()
+            .expect("the intrinsics and the global object are objects")
+            .realm = Some(handle.clone());
+    }
+}
+
+// Similar to new_global_environment in lexical_environment, except we need to return a GlobalEnvirionment
+fn new_global_environment(
+    global: Value,
+    this_value: Value,
+) -> Gc<GcCell<Box<GlobalEnvironmentRecord>>> {
+    let obj_rec = Box::new(ObjectEnvironmentRecord {
+        bindings: global,
+        outer_env: None,
+        // Object Environment Records created for with statements (13.11)
+        // can provide their binding object as an implicit this value for use in function calls.
+        // The capability is controlled by a withEnvironment Boolean value that is associated
+        // with each object Environment Record. By default, the value of withEnvironment is false
+        // for any object Environment Record.
+        with_environment: false,
+    });
+
+    let dcl_rec = Box::new(DeclarativeEnvironmentRecord {
+        env_rec: FxHashMap::default(),
+        outer_env: None,
+    });
+
+    Gc::new(GcCell::new(Box::new(GlobalEnvironmentRecord {
+        object_record: obj_rec,
+        global_this_binding: this_value,
+        declarative_record: dcl_rec,
+        var_names: FxHashSet::default(),
+    })))
+}
+
+#[cfg(test)]
+mod tests;
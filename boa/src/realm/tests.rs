@@ -0,0 +1,82 @@
+use super::*;
+use crate::{
+    builtins::value::ResultValue,
+    exec::{Executor, Interpreter},
+    forward,
+};
+
+fn engine(realm: Realm) -> Interpreter {
+    Executor::new(realm)
+}
+
+#[test]
+fn without_global() {
+    let realm = Realm::builder().without_global("console").build();
+    assert!(realm.global_obj.get_field_slice("console").is_undefined());
+    assert!(realm.global_obj.get_field_slice("Math").is_object());
+}
+
+#[test]
+fn only_globals() {
+    let mut engine = engine(Realm::builder().only_globals(vec!["Math"]).build());
+    assert_eq!(forward(&mut engine, "Math.max(1, 2)"), "2");
+    assert_eq!(forward(&mut engine, "Array"), "undefined");
+    // The builtins still work without their global
+    assert_eq!(forward(&mut engine, "[1, 2, 3].map(Math.abs).length"), "3");
+    assert_eq!(
+        forward(&mut engine, "var o = { a: 1 }; o.hasOwnProperty('a')"),
+        "true"
+    );
+    assert_eq!(forward(&mut engine, "'abc'.toUpperCase()"), "ABC");
+}
+
+#[test]
+fn shadowed_builtins() {
+    let mut engine = engine(Realm::create());
+    let scenario = r#"
+        var Array = undefined;
+        var String = undefined;
+        [1, 2].concat([3]).length + 'x'.repeat(2)
+        "#;
+    assert_eq!(forward(&mut engine, scenario), "3xx");
+}
+
+fn answer(_: &mut Value, _: &[Value], _: &mut Interpreter) -> ResultValue {
+    Ok(Value::from(42))
+}
+
+#[test]
+fn host_globals() {
+    let realm = Realm::builder()
+        .host_global("tenant", Value::from("acme"))
+        .host_function("answer", answer)
+        .host_global("Math", Value::from(1))
+        .build();
+    let mut engine = engine(realm);
+    assert_eq!(forward(&mut engine, "tenant"), "acme");
+    assert_eq!(forward(&mut engine, "answer()"), "42");
+    assert_eq!(forward(&mut engine, "Math"), "1");
+}
+
+#[test]
+fn global_object_and_this() {
+    let global = Value::new_object(None);
+    global.set_field_slice("host", Value::from("global"));
+    let this = Value::new_object(None);
+    this.set_field_slice("host", Value::from("this"));
+    let realm = Realm::builder()
+        .global_object(global.clone())
+        .global_this(this)
+        .build();
+    let mut engine = engine(realm);
+    assert_eq!(forward(&mut engine, "host"), "global");
+    assert_eq!(forward(&mut engine, "this.host"), "this");
+    assert_eq!(forward(&mut engine, "globalThis.host"), "this");
+    assert_eq!(
+        forward(&mut engine, "(function () { return this.host; })()"),
+        "this"
+    );
+    forward(&mut engine, "var declared = 1;");
+    assert_eq!(global.get_field_slice("declared").to_string(), "1");
+    assert!(global.get_field_slice("Object").is_object());
+}
@@ -0,0 +1,1233 @@
+//! Snapshots of realms, to start interpreters from an initialised realm.
+//!
+//! Creating a realm builds every builtin object, and an embedder which runs setup scripts in each
+//! new realm runs them again as well. A snapshot is a byte image of a realm: its intrinsics, its
+//! global object, its global bindings and everything reachable from them, such as the objects and
+//! closures created by setup scripts. Reading the image builds the same object graph again without
+//! initialising any builtin or running any script.
+//!
+//! ```
+//! # use boa::{exec::{Executor, Interpreter}, forward, realm::Realm};
+//! let mut engine: Interpreter = Executor::new(Realm::create());
+//! forward(&mut engine, "var greet = function (name) { return 'hello ' + name; };");
+//! let image = engine.realm.snapshot().unwrap();
+//!
+//! let mut restored: Interpreter = Executor::new(Realm::from_snapshot(&image).unwrap());
+//! assert_eq!(forward(&mut restored, "greet('boa')"), "hello boa");
+//! ```
+//!
+//! An image can only be read by the build of Boa which wrote it:
+//!
+//! - Native functions are saved as their position in the table of the builtins of a fresh realm,
+//!   followed by the host functions given to the [`Snapshotter`].
+//! - The code of a function is saved as its source text, which is parsed again when the image is
+//!   read. Functions parsed without their source text can't be saved.
+//!
+//! Iterators, objects with the internal state of a host, objects of another realm and a realm
+//! which is running code can't be saved. The compile strings hook of a realm is a host closure, a
+//! restored realm has none.
+
+use crate::{
+    builtins::{
+        array_buffer::{ArrayBuffer, DataBlock},
+        console::ConsoleState,
+        data_view::DataView,
+        function::{Function, FunctionBody, NativeFunctionData, ThisMode, REALM},
+        object::{InternalStateCell, Object, ObjectKind, PropertyMap},
+        property::Property,
+        regexp::RegExp,
+        symbol::{self, Symbol, WellKnownSymbol},
+        typed_array::{TypedArray, TypedArrayKind},
+        value::{JsString, Value, ValueData},
+    },
+    environment::{
+        declarative_environment_record::{
+            DeclarativeEnvironmentRecord, DeclarativeEnvironmentRecordBinding,
+        },
+        environment_record_trait::EnvironmentRecordTrait,
+        function_environment_record::{BindingStatus, FunctionEnvironmentRecord},
+        global_environment_record::GlobalEnvironmentRecord,
+        lexical_environment::{Environment, EnvironmentType},
+        object_environment_record::ObjectEnvironmentRecord,
+    },
+    realm::{Globals, Realm},
+    syntax::{
+        ast::node::{FormalParameter, Node, PropertyDefinition},
+        lexer::Lexer,
+        parser::Parser,
+    },
+};
+use gc::{Gc, GcCell};
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::{
+    collections::hash_map::Entry,
+    error,
+    fmt::{self, Display},
+    ptr,
+    rc::Rc,
+};
+
+/// The first bytes of every image.
+const MAGIC: &[u8; 8] = b"BOAIMAGE";
+
+/// The version of the layout of images, bumped whenever it changes.
+const FORMAT_VERSION: usize = 3;
+
+/// The internal slot holding the source text of a function.
+const SOURCE_TEXT: &str = "SourceText";
+
+thread_local! {
+    /// The native functions of the builtins, in the order a fresh realm reaches them.
+    static BUILTINS: Rc<Natives> = Rc::new(Natives::builtins());
+}
+
+/// The reason a realm couldn't be saved or an image couldn't be read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnapshotError {
+    /// The realm holds something which images can't hold.
+    Unsupported(String),
+    /// The image is corrupted, or wasn't written by this build of Boa.
+    Invalid(String),
+}
+
+impl Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsupported(what) => write!(f, "the realm holds {}, which can't be saved", what),
+            Self::Invalid(reason) => write!(f, "invalid realm image: {}", reason),
+        }
+    }
+}
+
+impl error::Error for SnapshotError {}
+
+fn unsupported<T>(what: &str) -> Result<T, SnapshotError> {
+    Err(SnapshotError::Unsupported(what.to_string()))
+}
+
+fn invalid<T>(reason: &str) -> Result<T, SnapshotError> {
+    Err(SnapshotError::Invalid(reason.to_string()))
+}
+
+/// Writes realms into images and reads them back.
+///
+/// The host functions of the realms, such as the ones given to `RealmBuilder::host_function`,
+/// must be given to the snapshotter in the same order when writing and reading an image.
+///
+/// ```
+/// # use boa::{builtins::value::{ResultValue, Value}, exec::Interpreter, realm::Realm, snapshot::Snapshotter};
+/// fn answer(_: &mut Value, _: &[Value], _: &mut Interpreter) -> ResultValue {
+///     Ok(Value::from(42))
+/// }
+///
+/// let realm = Realm::builder().host_function("answer", answer).build();
+/// let snapshotter = Snapshotter::new().host_function(answer);
+/// let image = snapshotter.write(&realm).unwrap();
+/// let restored = snapshotter.read(&image).unwrap();
+/// assert!(restored.global_obj.get_field_slice("answer").is_function());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Snapshotter {
+    host_functions: Vec<NativeFunctionData>,
+}
+
+impl Snapshotter {
+    /// Creates a snapshotter which knows about the builtins only.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a host function which the realms can hold.
+    pub fn host_function(mut self, func: NativeFunctionData) -> Self {
+        self.host_functions.push(func);
+        self
+    }
+
+    /// Writes the realm into an image.
+    pub fn write(&self, realm: &Realm) -> Result<Vec<u8>, SnapshotError> {
+        let natives = self.natives();
+        Writer::new(&natives, realm).write(realm)
+    }
+
+    /// Reads a realm from an image written by a snapshotter with the same host functions.
+    pub fn read(&self, image: &[u8]) -> Result<Realm, SnapshotError> {
+        let natives = self.natives();
+        Reader::new(&natives.functions, image).read()
+    }
+
+    /// The native functions images refer to, the builtins followed by the host functions.
+    fn natives(&self) -> Rc<Natives> {
+        let builtins = BUILTINS.with(Rc::clone);
+        if self.host_functions.is_empty() {
+            return builtins;
+        }
+        let mut natives = Natives::clone(&builtins);
+        for &func in &self.host_functions {
+            natives.add(func);
+        }
+        Rc::new(natives)
+    }
+}
+
+/// A table of native functions, which images refer to by their position.
+#[derive(Debug, Clone, Default)]
+struct Natives {
+    functions: Vec<NativeFunctionData>,
+    /// The position of each function, by its address.
+    indices: FxHashMap<usize, usize>,
+}
+
+impl Natives {
+    /// Collects the native functions reachable from a fresh realm.
+    ///
+    /// The walk follows the properties in their insertion order, so every run of the same build
+    /// finds the functions in the same order.
+    fn builtins() -> Self {
+        let realm = Realm::create();
+        let mut natives = Self::default();
+        let mut visited = FxHashSet::default();
+        let mut pending = vec![realm.global_obj.clone(), realm.intrinsics.clone()];
+        while let Some(value) = pending.pop() {
+            let object = match value.data() {
+                ValueData::Object(ref object) => object,
+                _ => continue,
+            };
+            if !visited.insert(&**object as *const GcCell<Object>) {
+                continue;
+            }
+
+            let object = object.borrow();
+            for function in object.call.iter().chain(object.construct.iter()) {
+                if let FunctionBody::BuiltIn(func) = function.body {
+                    natives.add(func);
+                }
+            }
+            let mut slots: Vec<_> = object.internal_slots.iter().collect();
+            slots.sort_by_key(|&(name, _)| name);
+            pending.extend(slots.into_iter().map(|(_, slot)| slot.clone()));
+            for property in object
+                .properties
+                .values()
+                .chain(object.sym_properties.values())
+            {
+                pending.extend(property.value.iter().cloned());
+                pending.extend(property.get.iter().cloned());
+                pending.extend(property.set.iter().cloned());
+            }
+        }
+        natives
+    }
+
+    fn add(&mut self, func: NativeFunctionData) {
+        if let Entry::Vacant(entry) = self.indices.entry(func as usize) {
+            entry.insert(self.functions.len());
+            self.functions.push(func);
+        }
+    }
+}
+
+/// The bytes of an image being written.
+#[derive(Debug, Default)]
+struct Encoder(Vec<u8>);
+
+impl Encoder {
+    fn u8(&mut self, byte: u8) {
+        self.0.push(byte);
+    }
+
+    fn bool(&mut self, value: bool) {
+        self.u8(value as u8);
+    }
+
+    /// Writes an unsigned integer in LEB128, small integers take a single byte.
+    fn usize(&mut self, mut value: usize) {
+        while value >= 0x80 {
+            self.u8(value as u8 | 0x80);
+            value >>= 7;
+        }
+        self.u8(value as u8);
+    }
+
+    fn optional_usize(&mut self, value: Option<usize>) {
+        match value {
+            Some(value) => {
+                self.u8(1);
+                self.usize(value);
+            }
+            None => self.u8(0),
+        }
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.0.extend_from_slice(bytes);
+    }
+
+    fn str(&mut self, string: &str) {
+        self.usize(string.len());
+        self.bytes(string.as_bytes());
+    }
+
+    fn utf16(&mut self, units: &[u16]) {
+        self.usize(units.len());
+        for unit in units {
+            self.bytes(&unit.to_le_bytes());
+        }
+    }
+}
+
+/// The bytes of an image being read.
+#[derive(Debug)]
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], SnapshotError> {
+        let end = self.pos.saturating_add(len);
+        match self.bytes.get(self.pos..end) {
+            Some(bytes) => {
+                self.pos = end;
+                Ok(bytes)
+            }
+            None => invalid("the image is truncated"),
+        }
+    }
+
+    fn u8(&mut self) -> Result<u8, SnapshotError> {
+        self.bytes(1).map(|bytes| bytes[0])
+    }
+
+    fn bool(&mut self) -> Result<bool, SnapshotError> {
+        Ok(self.u8()? != 0)
+    }
+
+    fn usize(&mut self) -> Result<usize, SnapshotError> {
+        let mut value = 0usize;
+        let mut shift = 0;
+        loop {
+            let byte = self.u8()?;
+            if shift >= usize::MAX.count_ones() {
+                return invalid("an integer is too large");
+            }
+            value |= usize::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+            shift += 7;
+        }
+    }
+
+    fn optional_usize(&mut self) -> Result<Option<usize>, SnapshotError> {
+        if self.bool()? {
+            self.usize().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn str(&mut self) -> Result<&'a str, SnapshotError> {
+        let len = self.usize()?;
+        std::str::from_utf8(self.bytes(len)?).or_else(|_| invalid("a string isn't valid UTF-8"))
+    }
+
+    fn utf16(&mut self) -> Result<JsString, SnapshotError> {
+        let len = self.usize()?;
+        let bytes = self.bytes(len.saturating_mul(2))?;
+        let units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+            .collect();
+        Ok(JsString::from(units))
+    }
+
+    fn array<T: Default + AsMut<[u8]>>(&mut self) -> Result<T, SnapshotError> {
+        let mut array = T::default();
+        let len = array.as_mut().len();
+        array.as_mut().copy_from_slice(self.bytes(len)?);
+        Ok(array)
+    }
+}
+
+type EnvironmentCell = GcCell<Box<dyn EnvironmentRecordTrait>>;
+
+/// Writes the object graph of a realm.
+///
+/// Objects, environments and symbols are numbered in the order they are reached, the records of
+/// environments and objects are written in that order. The outer environment of an environment is
+/// numbered before it, so that a reader always has it when it creates the environment.
+#[derive(Debug)]
+struct Writer<'a> {
+    natives: &'a Natives,
+    intrinsics: *const GcCell<Object>,
+    objects: Vec<Value>,
+    object_ids: FxHashMap<*const GcCell<Object>, usize>,
+    environments: Vec<Environment>,
+    environment_ids: FxHashMap<*const EnvironmentCell, usize>,
+    symbols: Vec<Symbol>,
+    symbol_ids: FxHashMap<Symbol, usize>,
+    blocks: FxHashMap<*const (), usize>,
+}
+
+impl<'a> Writer<'a> {
+    fn new(natives: &'a Natives, realm: &Realm) -> Self {
+        let intrinsics = match realm.intrinsics.data() {
+            ValueData::Object(ref object) => &**object as *const GcCell<Object>,
+            _ => ptr::null(),
+        };
+        Self {
+            natives,
+            intrinsics,
+            objects: Vec::new(),
+            object_ids: FxHashMap::default(),
+            environments: Vec::new(),
+            environment_ids: FxHashMap::default(),
+            symbols: Vec::new(),
+            symbol_ids: FxHashMap::default(),
+            blocks: FxHashMap::default(),
+        }
+    }
+
+    fn write(mut self, realm: &Realm) -> Result<Vec<u8>, SnapshotError> {
+        let global_env = realm.environment.get_current_environment_ref().clone();
+        match global_env.borrow().get_environment_type() {
+            EnvironmentType::Global => {}
+            _ => return unsupported("running code"),
+        }
+
+        let mut roots = Encoder::default();
+        self.value(&mut roots, &realm.global_obj);
+        self.value(&mut roots, &realm.intrinsics);
+        let global_env = self.environment_id(&global_env);
+        roots.usize(global_env);
+        let (kind, names) = match realm.globals() {
+            Globals::AllExcept(names) => (0, names),
+            Globals::Only(names) => (1, names),
+        };
+        let mut names: Vec<&String> = names.iter().collect();
+        names.sort();
+        roots.u8(kind);
+        roots.usize(names.len());
+        for name in names {
+            roots.str(name);
+        }
+
+        let mut environments = Encoder::default();
+        let mut objects = Encoder::default();
+        let (mut next_environment, mut next_object) = (0, 0);
+        loop {
+            if let Some(env) = self.environments.get(next_environment).cloned() {
+                self.environment(&mut environments, &env);
+                next_environment += 1;
+            } else if let Some(object) = self.objects.get(next_object).cloned() {
+                self.object(&mut objects, &object)?;
+                next_object += 1;
+            } else {
+                break;
+            }
+        }
+
+        let mut image = Encoder::default();
+        image.bytes(MAGIC);
+        image.usize(FORMAT_VERSION);
+        image.str(env!("CARGO_PKG_VERSION"));
+        image.usize(self.natives.functions.len());
+        image.usize(self.symbols.len());
+        for symbol in &self.symbols {
+            if let Some(index) = WellKnownSymbol::ALL
+                .iter()
+                .position(|well_known| well_known.symbol() == *symbol)
+            {
+                image.u8(0);
+                image.usize(index);
+            } else if let Some(key) = symbol::registry_key(symbol) {
+                image.u8(1);
+                image.utf16(&key);
+            } else if let Some(description) = symbol.description() {
+                image.u8(2);
+                image.utf16(description);
+            } else {
+                image.u8(3);
+            }
+        }
+        image.usize(self.environments.len());
+        image.usize(self.objects.len());
+        image.bytes(&environments.0);
+        image.bytes(&objects.0);
+        image.bytes(&roots.0);
+        Ok(image.0)
+    }
+
+    fn object_id(&mut self, value: &Value, object: &GcCell<Object>) -> usize {
+        let objects = &mut self.objects;
+        *self
+            .object_ids
+            .entry(object as *const GcCell<Object>)
+            .or_insert_with(|| {
+                objects.push(value.clone());
+                objects.len() - 1
+            })
+    }
+
+    fn environment_id(&mut self, env: &Environment) -> usize {
+        let key = &**env as *const EnvironmentCell;
+        if let Some(&id) = self.environment_ids.get(&key) {
+            return id;
+        }
+        if let Some(outer) = env.borrow().get_outer_environment() {
+            self.environment_id(&outer);
+        }
+        self.environments.push(env.clone());
+        self.environment_ids
+            .insert(key, self.environments.len() - 1);
+        self.environments.len() - 1
+    }
+
+    fn symbol_id(&mut self, symbol: &Symbol) -> usize {
+        let symbols = &mut self.symbols;
+        *self.symbol_ids.entry(symbol.clone()).or_insert_with(|| {
+            symbols.push(symbol.clone());
+            symbols.len() - 1
+        })
+    }
+
+    fn value(&mut self, out: &mut Encoder, value: &Value) {
+        match value.data() {
+            ValueData::Null => out.u8(0),
+            ValueData::Undefined => out.u8(1),
+            ValueData::Boolean(boolean) => {
+                out.u8(2);
+                out.bool(*boolean);
+            }
+            ValueData::String(ref string) => {
+                out.u8(3);
+                out.utf16(string);
+            }
+            ValueData::Rational(number) => {
+                out.u8(4);
+                out.bytes(&number.to_le_bytes());
+            }
+            ValueData::Integer(integer) => {
+                out.u8(5);
+                out.bytes(&integer.to_le_bytes());
+            }
+            ValueData::Object(ref object) => {
+                out.u8(6);
+                let id = self.object_id(value, object);
+                out.usize(id);
+            }
+            ValueData::Symbol(ref symbol) => {
+                out.u8(7);
+                let id = self.symbol_id(symbol);
+                out.usize(id);
+            }
+        }
+    }
+
+    fn optional_value(&mut self, out: &mut Encoder, value: Option<&Value>) {
+        match value {
+            Some(value) => {
+                out.u8(1);
+                self.value(out, value);
+            }
+            None => out.u8(0),
+        }
+    }
+
+    fn bindings(
+        &mut self,
+        out: &mut Encoder,
+        bindings: &FxHashMap<String, DeclarativeEnvironmentRecordBinding>,
+    ) {
+        out.usize(bindings.len());
+        for (name, binding) in bindings {
+            out.str(name);
+            self.optional_value(out, binding.value.as_ref());
+            out.u8(binding.can_delete as u8
+                | (binding.mutable as u8) << 1
+                | (binding.strict as u8) << 2);
+        }
+    }
+
+    fn outer_environment(&mut self, out: &mut Encoder, outer: Option<&Environment>) {
+        let outer = outer.map(|outer| self.environment_id(outer));
+        out.optional_usize(outer);
+    }
+
+    fn environment(&mut self, out: &mut Encoder, env: &Environment) {
+        let env = env.borrow();
+        let record = env.as_any();
+        match env.get_environment_type() {
+            EnvironmentType::Declarative => {
+                let record = record
+                    .downcast_ref::<DeclarativeEnvironmentRecord>()
+                    .expect("declarative environment");
+                out.u8(0);
+                self.bindings(out, &record.env_rec);
+                self.outer_environment(out, record.outer_env.as_ref());
+            }
+            EnvironmentType::Function => {
+                let record = record
+                    .downcast_ref::<FunctionEnvironmentRecord>()
+                    .expect("function environment");
+                out.u8(1);
+                self.bindings(out, &record.env_rec);
+                self.value(out, &record.this_value);
+                out.u8(match record.this_binding_status {
+                    BindingStatus::Lexical => 0,
+                    BindingStatus::Initialized => 1,
+                    BindingStatus::Uninitialized => 2,
+                });
+                self.value(out, &record.function);
+                self.value(out, &record.home_object);
+                self.value(out, &record.new_target);
+                self.outer_environment(out, record.outer_env.as_ref());
+            }
+            EnvironmentType::Object => {
+                let record = record
+                    .downcast_ref::<ObjectEnvironmentRecord>()
+                    .expect("object environment");
+                out.u8(2);
+                self.value(out, &record.bindings);
+                out.bool(record.with_environment);
+                self.outer_environment(out, record.outer_env.as_ref());
+            }
+            EnvironmentType::Global => {
+                let record = record
+                    .downcast_ref::<GlobalEnvironmentRecord>()
+                    .expect("global environment");
+                out.u8(3);
+                self.value(out, &record.object_record.bindings);
+                self.value(out, &record.global_this_binding);
+                self.bindings(out, &record.declarative_record.env_rec);
+                out.usize(record.var_names.len());
+                for name in &record.var_names {
+                    out.str(name);
+                }
+            }
+        }
+    }
+
+    fn property(&mut self, out: &mut Encoder, property: &Property) {
+        let flag = |attribute: Option<bool>, shift: u8| match attribute {
+            Some(value) => (1 | (value as u8) << 1) << shift,
+            None => 0,
+        };
+        out.u8(flag(property.configurable, 0)
+            | flag(property.enumerable, 2)
+            | flag(property.writable, 4));
+        self.optional_value(out, property.value.as_ref());
+        self.optional_value(out, property.get.as_ref());
+        self.optional_value(out, property.set.as_ref());
+    }
+
+    fn object(&mut self, out: &mut Encoder, value: &Value) -> Result<(), SnapshotError> {
+        let object = value.as_object().expect("objects of the image are objects");
+        if let Some(realm) = object.internal_slots.get(REALM) {
+            match realm.data() {
+                ValueData::Object(ref realm) if ptr::eq(&**realm, self.intrinsics) => {}
+                _ => return unsupported("an object of another realm"),
+            }
+        }
+
+        out.u8(match object.kind {
+            ObjectKind::Function => 0,
+            ObjectKind::Array => 1,
+            ObjectKind::String => 2,
+            ObjectKind::Symbol => 3,
+            ObjectKind::Error => 4,
+            ObjectKind::Ordinary => 5,
+            ObjectKind::Boolean => 6,
+            ObjectKind::Number => 7,
+            ObjectKind::ArrayBuffer => 8,
+            ObjectKind::TypedArray => 9,
+            ObjectKind::DataView => 10,
+        });
+        out.usize(object.internal_slots.len());
+        for (name, slot) in &object.internal_slots {
+            out.str(name);
+            self.value(out, slot);
+        }
+        out.usize(object.properties.len());
+        for (key, property) in object.properties.iter() {
+            out.utf16(key);
+            self.property(out, property);
+        }
+        out.usize(object.sym_properties.len());
+        for (symbol, property) in object.sym_properties.iter() {
+            let id = self.symbol_id(symbol);
+            out.usize(id);
+            self.property(out, property);
+        }
+        self.state(out, &object)?;
+        self.function(out, &object, object.call.as_ref())?;
+        self.function(out, &object, object.construct.as_ref())
+    }
+
+    fn block(&mut self, out: &mut Encoder, block: &DataBlock) {
+        let id = self.blocks.len();
+        match self.blocks.entry(block.as_ptr()) {
+            Entry::Occupied(entry) => out.usize(*entry.get()),
+            Entry::Vacant(entry) => {
+                // The first reference to a block holds its bytes
+                entry.insert(id);
+                out.usize(id);
+                out.usize(block.len());
+                out.bytes(&block.borrow());
+            }
+        }
+    }
+
+    fn typed_array_kind(out: &mut Encoder, kind: TypedArrayKind) {
+        let index = TypedArrayKind::ALL
+            .iter()
+            .position(|&other| other == kind)
+            .expect("typed array kind");
+        out.usize(index);
+    }
+
+    fn state(&mut self, out: &mut Encoder, object: &Object) -> Result<(), SnapshotError> {
+        let state = if let Some(ref state) = object.state {
+            state
+        } else {
+            out.u8(0);
+            return Ok(());
+        };
+        if let Some(console) = state.downcast_ref::<ConsoleState>() {
+            out.u8(1);
+            out.usize(console.count_map.len());
+            for (label, count) in &console.count_map {
+                out.str(label);
+                out.usize(*count as usize);
+            }
+            out.usize(console.timer_map.len());
+            for (label, time) in &console.timer_map {
+                out.str(label);
+                out.bytes(&time.to_le_bytes());
+            }
+            out.usize(console.groups.len());
+            for group in &console.groups {
+                out.str(group);
+            }
+        } else if let Some(&kind) = state.downcast_ref::<TypedArrayKind>() {
+            out.u8(2);
+            Self::typed_array_kind(out, kind);
+        } else if let Some(regexp) = state.downcast_ref::<RegExp>() {
+            let source = match object.internal_slots.get("OriginalSource").map(Value::data) {
+                Some(ValueData::String(ref source)) => source,
+                _ => return unsupported("a regular expression without its source"),
+            };
+            out.u8(3);
+            out.utf16(source);
+            out.str(&regexp.flags);
+        } else if let Some(buffer) = state.downcast_ref::<ArrayBuffer>() {
+            out.u8(4);
+            self.block(out, &buffer.data);
+            out.optional_usize(buffer.max_byte_length);
+        } else if let Some(view) = state.downcast_ref::<DataView>() {
+            out.u8(5);
+            self.block(out, &view.data);
+            out.usize(view.byte_offset);
+            out.optional_usize(view.byte_length);
+        } else if let Some(array) = state.downcast_ref::<TypedArray>() {
+            out.u8(6);
+            Self::typed_array_kind(out, array.kind);
+            self.block(out, &array.data);
+            out.usize(array.byte_offset);
+            out.optional_usize(array.array_length);
+        } else {
+            return unsupported("an iterator or an object with host state");
+        }
+        Ok(())
+    }
+
+    fn function(
+        &mut self,
+        out: &mut Encoder,
+        object: &Object,
+        function: Option<&Function>,
+    ) -> Result<(), SnapshotError> {
+        let function = if let Some(function) = function {
+            function
+        } else {
+            out.u8(0);
+            return Ok(());
+        };
+        match function.body {
+            FunctionBody::BuiltIn(func) => match self.natives.indices.get(&(func as usize)) {
+                Some(&index) => {
+                    out.u8(1);
+                    out.usize(index);
+                }
+                None => return unsupported("a host function unknown to the snapshotter"),
+            },
+            FunctionBody::Ordinary(_) => {
+                match object.internal_slots.get(SOURCE_TEXT).map(Value::data) {
+                    Some(ValueData::String(_)) => {}
+                    _ => return unsupported("a function without its source text"),
+                }
+                let env = function
+                    .environment
+                    .as_ref()
+                    .expect("ordinary functions have an environment");
+                out.u8(2);
+                out.u8(match function.this_mode {
+                    ThisMode::Lexical => 0,
+                    ThisMode::NonLexical => 1,
+                });
+                out.bool(function.strict);
+                let env = self.environment_id(env);
+                out.usize(env);
+            }
+            FunctionBody::Bound => out.u8(3),
+            FunctionBody::Wrapped => return unsupported("an object of another realm"),
+        }
+        Ok(())
+    }
+}
+
+/// Reads the object graph of a realm.
+///
+/// The objects are created empty before the environments, which refer to them, and filled in
+/// afterwards.
+#[derive(Debug)]
+struct Reader<'a> {
+    input: Decoder<'a>,
+    natives: &'a [NativeFunctionData],
+    symbols: Vec<Symbol>,
+    objects: Vec<Value>,
+    environments: Vec<Environment>,
+    blocks: Vec<DataBlock>,
+    /// The parameters and body of the functions read so far, by source text.
+    code: FxHashMap<String, (Box<[FormalParameter]>, Node)>,
+}
+
+impl<'a> Reader<'a> {
+    fn new(natives: &'a [NativeFunctionData], image: &'a [u8]) -> Self {
+        Self {
+            input: Decoder {
+                bytes: image,
+                pos: 0,
+            },
+            natives,
+            symbols: Vec::new(),
+            objects: Vec::new(),
+            environments: Vec::new(),
+            blocks: Vec::new(),
+            code: FxHashMap::default(),
+        }
+    }
+
+    fn read(mut self) -> Result<Realm, SnapshotError> {
+        if self.input.bytes(MAGIC.len()).ok() != Some(&MAGIC[..]) {
+            return invalid("not a realm image");
+        }
+        if self.input.usize()? != FORMAT_VERSION
+            || self.input.str()? != env!("CARGO_PKG_VERSION")
+            || self.input.usize()? != self.natives.len()
+        {
+            return invalid("the image was written by another build of Boa");
+        }
+
+        let symbols = self.input.usize()?;
+        for _ in 0..symbols {
+            let symbol = match self.input.u8()? {
+                0 => match WellKnownSymbol::ALL.get(self.input.usize()?) {
+                    Some(symbol) => symbol.symbol(),
+                    None => return invalid("unknown well-known symbol"),
+                },
+                1 => symbol::registered_symbol(self.input.utf16()?),
+                2 => Symbol::new(Some(self.input.utf16()?)),
+                3 => Symbol::new(None),
+                _ => return invalid("unknown kind of symbol"),
+            };
+            self.symbols.push(symbol);
+        }
+
+        let environments = self.input.usize()?;
+        let objects = self.input.usize()?;
+        self.objects = (0..objects)
+            .map(|_| Value::object(empty_object()))
+            .collect();
+        for _ in 0..environments {
+            let env = self.environment()?;
+            self.environments.push(env);
+        }
+        for id in 0..objects {
+            let object = self.object()?;
+            *self.objects[id]
+                .as_object_mut()
+                .expect("objects of the image are objects") = object;
+        }
+
+        let global = self.value()?;
+        let intrinsics = self.value()?;
+        let global_env = self.environment_ref()?;
+        let kind = self.input.u8()?;
+        let names = (0..self.input.usize()?)
+            .map(|_| self.input.str().map(str::to_string))
+            .collect::<Result<_, _>>()?;
+        let globals = match kind {
+            0 => Globals::AllExcept(names),
+            1 => Globals::Only(names),
+            _ => return invalid("unknown selection of globals"),
+        };
+        if self.input.pos != self.input.bytes.len() {
+            return invalid("unexpected bytes after the realm");
+        }
+        match global_env.borrow().get_environment_type() {
+            EnvironmentType::Global if global.is_object() && intrinsics.is_object() => {}
+            _ => return invalid("the roots of the realm are corrupted"),
+        }
+        Ok(Realm::from_parts(global, global_env, intrinsics, globals))
+    }
+
+    fn value(&mut self) -> Result<Value, SnapshotError> {
+        Ok(match self.input.u8()? {
+            0 => Value::null(),
+            1 => Value::undefined(),
+            2 => Value::boolean(self.input.bool()?),
+            3 => Value::from(self.input.utf16()?),
+            4 => Value::rational(f64::from_le_bytes(self.input.array()?)),
+            5 => Value::integer(i32::from_le_bytes(self.input.array()?)),
+            6 => match self.objects.get(self.input.usize()?) {
+                Some(object) => object.clone(),
+                None => return invalid("reference to an unknown object"),
+            },
+            7 => Value::symbol(self.symbol()?),
+            _ => return invalid("unknown kind of value"),
+        })
+    }
+
+    fn optional_value(&mut self) -> Result<Option<Value>, SnapshotError> {
+        if self.input.bool()? {
+            self.value().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn symbol(&mut self) -> Result<Symbol, SnapshotError> {
+        match self.symbols.get(self.input.usize()?) {
+            Some(symbol) => Ok(symbol.clone()),
+            None => invalid("reference to an unknown symbol"),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, SnapshotError> {
+        self.input.str().map(String::from)
+    }
+
+    fn environment_ref(&mut self) -> Result<Environment, SnapshotError> {
+        match self.environments.get(self.input.usize()?) {
+            Some(env) => Ok(env.clone()),
+            None => invalid("reference to an unknown environment"),
+        }
+    }
+
+    fn outer_environment(&mut self) -> Result<Option<Environment>, SnapshotError> {
+        if self.input.bool()? {
+            self.environment_ref().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn bindings(
+        &mut self,
+    ) -> Result<FxHashMap<String, DeclarativeEnvironmentRecordBinding>, SnapshotError> {
+        let len = self.input.usize()?;
+        let mut bindings = FxHashMap::default();
+        for _ in 0..len {
+            let name = self.string()?;
+            let value = self.optional_value()?;
+            let flags = self.input.u8()?;
+            let binding = DeclarativeEnvironmentRecordBinding {
+                value,
+                can_delete: flags & 1 != 0,
+                mutable: flags & 2 != 0,
+                strict: flags & 4 != 0,
+            };
+            bindings.insert(name, binding);
+        }
+        Ok(bindings)
+    }
+
+    fn environment(&mut self) -> Result<Environment, SnapshotError> {
+        let record: Box<dyn EnvironmentRecordTrait> = match self.input.u8()? {
+            0 => Box::new(DeclarativeEnvironmentRecord {
+                env_rec: self.bindings()?,
+                outer_env: self.outer_environment()?,
+            }),
+            1 => Box::new(FunctionEnvironmentRecord {
+                env_rec: self.bindings()?,
+                this_value: self.value()?,
+                this_binding_status: match self.input.u8()? {
+                    0 => BindingStatus::Lexical,
+                    1 => BindingStatus::Initialized,
+                    2 => BindingStatus::Uninitialized,
+                    _ => return invalid("unknown binding status"),
+                },
+                function: self.value()?,
+                home_object: self.value()?,
+                new_target: self.value()?,
+                outer_env: self.outer_environment()?,
+            }),
+            2 => Box::new(ObjectEnvironmentRecord {
+                bindings: self.value()?,
+                with_environment: self.input.bool()?,
+                outer_env: self.outer_environment()?,
+            }),
+            3 => {
+                let global = self.value()?;
+                let this = self.value()?;
+                let bindings = self.bindings()?;
+                let len = self.input.usize()?;
+                let mut var_names = FxHashSet::default();
+                for _ in 0..len {
+                    var_names.insert(self.string()?);
+                }
+                Box::new(GlobalEnvironmentRecord {
+                    object_record: Box::new(ObjectEnvironmentRecord {
+                        bindings: global,
+                        with_environment: false,
+                        outer_env: None,
+                    }),
+                    global_this_binding: this,
+                    declarative_record: Box::new(DeclarativeEnvironmentRecord {
+                        env_rec: bindings,
+                        outer_env: None,
+                    }),
+                    var_names,
+                })
+            }
+            _ => return invalid("unknown kind of environment"),
+        };
+        Ok(Gc::new(GcCell::new(record)))
+    }
+
+    fn property(&mut self) -> Result<Property, SnapshotError> {
+        let flags = self.input.u8()?;
+        let flag = |shift: u8| {
+            if flags >> shift & 1 != 0 {
+                Some(flags >> shift & 2 != 0)
+            } else {
+                None
+            }
+        };
+        Ok(Property {
+            configurable: flag(0),
+            enumerable: flag(2),
+            writable: flag(4),
+            value: self.optional_value()?,
+            get: self.optional_value()?,
+            set: self.optional_value()?,
+        })
+    }
+
+    fn object(&mut self) -> Result<Object, SnapshotError> {
+        let mut object = empty_object();
+        object.kind = match self.input.u8()? {
+            0 => ObjectKind::Function,
+            1 => ObjectKind::Array,
+            2 => ObjectKind::String,
+            3 => ObjectKind::Symbol,
+            4 => ObjectKind::Error,
+            5 => ObjectKind::Ordinary,
+            6 => ObjectKind::Boolean,
+            7 => ObjectKind::Number,
+            8 => ObjectKind::ArrayBuffer,
+            9 => ObjectKind::TypedArray,
+            10 => ObjectKind::DataView,
+            _ => return invalid("unknown kind of object"),
+        };
+        let slots = self.input.usize()?;
+        for _ in 0..slots {
+            let name = self.string()?;
+            let slot = self.value()?;
+            object.internal_slots.insert(name, slot);
+        }
+        let properties = self.input.usize()?;
+        for _ in 0..properties {
+            let key = self.input.utf16()?;
+            let property = self.property()?;
+            object.properties.insert(key, property);
+        }
+        let sym_properties = self.input.usize()?;
+        for _ in 0..sym_properties {
+            let symbol = self.symbol()?;
+            let property = self.property()?;
+            object.sym_properties.insert(symbol, property);
+        }
+        object.state = self.state()?;
+        object.call = self.function(&object)?;
+        object.construct = self.function(&object)?;
+        Ok(object)
+    }
+
+    fn block(&mut self) -> Result<DataBlock, SnapshotError> {
+        let id = self.input.usize()?;
+        if let Some(block) = self.blocks.get(id) {
+            return Ok(block.clone());
+        }
+        if id != self.blocks.len() {
+            return invalid("reference to an unknown data block");
+        }
+        let len = self.input.usize()?;
+        let block = DataBlock::from(self.input.bytes(len)?.to_vec());
+        self.blocks.push(block.clone());
+        Ok(block)
+    }
+
+    fn typed_array_kind(&mut self) -> Result<TypedArrayKind, SnapshotError> {
+        match TypedArrayKind::ALL.get(self.input.usize()?) {
+            Some(&kind) => Ok(kind),
+            None => invalid("unknown typed array kind"),
+        }
+    }
+
+    fn state(&mut self) -> Result<Option<InternalStateCell>, SnapshotError> {
+        let state = match self.input.u8()? {
+            0 => return Ok(None),
+            1 => {
+                let mut console = ConsoleState::default();
+                let counts = self.input.usize()?;
+                for _ in 0..counts {
+                    let label = self.string()?;
+                    let count = self.input.usize()?;
+                    console.count_map.insert(label, count as u32);
+                }
+                let timers = self.input.usize()?;
+                for _ in 0..timers {
+                    let label = self.string()?;
+                    let time = u128::from_le_bytes(self.input.array()?);
+                    console.timer_map.insert(label, time);
+                }
+                let groups = self.input.usize()?;
+                for _ in 0..groups {
+                    console.groups.push(self.string()?);
+                }
+                InternalStateCell::new(console)
+            }
+            2 => InternalStateCell::new(self.typed_array_kind()?),
+            3 => {
+                let source = self.input.utf16()?;
+                let flags = self.input.str()?;
+                match RegExp::new(&source, flags) {
+                    Ok(regexp) => InternalStateCell::new(regexp),
+                    Err(_) => return invalid("a regular expression doesn't compile"),
+                }
+            }
+            4 => InternalStateCell::new(ArrayBuffer {
+                data: self.block()?,
+                max_byte_length: self.input.optional_usize()?,
+            }),
+            5 => InternalStateCell::new(DataView {
+                data: self.block()?,
+                byte_offset: self.input.usize()?,
+                byte_length: self.input.optional_usize()?,
+            }),
+            6 => InternalStateCell::new(TypedArray {
+                kind: self.typed_array_kind()?,
+                data: self.block()?,
+                byte_offset: self.input.usize()?,
+                array_length: self.input.optional_usize()?,
+            }),
+            _ => return invalid("unknown kind of internal state"),
+        };
+        Ok(Some(state))
+    }
+
+    fn function(&mut self, object: &Object) -> Result<Option<Function>, SnapshotError> {
+        let function = match self.input.u8()? {
+            0 => return Ok(None),
+            1 => match self.natives.get(self.input.usize()?) {
+                Some(&func) => Function::create_builtin(vec![], FunctionBody::BuiltIn(func)),
+                None => return invalid("reference to an unknown native function"),
+            },
+            2 => {
+                let this_mode = match self.input.u8()? {
+                    0 => ThisMode::Lexical,
+                    _ => ThisMode::NonLexical,
+                };
+                let strict = self.input.bool()?;
+                let env = self.environment_ref()?;
+                let (params, body) = match object.internal_slots.get(SOURCE_TEXT).map(Value::data) {
+                    Some(ValueData::String(ref source)) => {
+                        self.code(&source.to_std_string_lossy())?
+                    }
+                    _ => return invalid("a function lost its source text"),
+                };
+                let mut function =
+                    Function::create_ordinary(params, env, FunctionBody::Ordinary(body), this_mode);
+                function.strict = strict;
+                function
+            }
+            3 => Function::create_bound(),
+            _ => return invalid("unknown kind of function"),
+        };
+        Ok(Some(function))
+    }
+
+    /// Parses the source text of a function again, the closures of a function share it.
+    fn code(&mut self, source: &str) -> Result<(Box<[FormalParameter]>, Node), SnapshotError> {
+        if let Some(code) = self.code.get(source) {
+            return Ok(code.clone());
+        }
+        let code = match parse_function(source) {
+            Some(code) => code,
+            None => return invalid("the source text of a function doesn't parse"),
+        };
+        self.code.insert(source.to_string(), code.clone());
+        Ok(code)
+    }
+}
+
+/// An object without any slot, property or behaviour, to be filled in.
+fn empty_object() -> Object {
+    Object {
+        kind: ObjectKind::Ordinary,
+        internal_slots: FxHashMap::default(),
+        properties: PropertyMap::default(),
+        sym_properties: PropertyMap::default(),
+        state: None,
+        call: None,
+        construct: None,
+        realm: None,
+    }
+}
+
+/// Parses the source text of a function into its parameters and body.
+///
+/// Function declarations, function expressions and arrow functions parse as expressions in
+/// parentheses, methods as the method of an object literal.
+fn parse_function(source: &str) -> Option<(Box<[FormalParameter]>, Node)> {
+    let parse = |wrapped: String| {
+        let mut lexer = Lexer::new(&wrapped);
+        lexer.lex().ok()?;
+        match Parser::with_source(&lexer.tokens, &wrapped).parse_all() {
+            Ok(Node::StatementList(ref list)) if list.len() == 1 => Some(list[0].clone()),
+            _ => None,
+        }
+    };
+    let function = match parse(format!("({})", source)) {
+        Some(function) => function,
+        None => match parse(format!("({{{}}})", source))? {
+            Node::Object(ref properties) if properties.len() == 1 => match properties[0] {
+                PropertyDefinition::MethodDefinition(_, _, ref function) => function.clone(),
+                _ => return None,
+            },
+            _ => return None,
+        },
+    };
+    match function {
+        Node::FunctionExpr(_, ref params, ref body, _)
+        | Node::ArrowFunctionDecl(ref params, ref body, _) => {
+            Some((params.clone(), (**body).clone()))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests;
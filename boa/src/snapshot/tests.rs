@@ -0,0 +1,216 @@
+use super::*;
+use crate::{
+    builtins::value::ResultValue,
+    exec::{Executor, Interpreter},
+    forward,
+};
+
+fn restore(engine: &Interpreter) -> Interpreter {
+    let image = engine.realm.snapshot().expect("the realm can be saved");
+    Executor::new(Realm::from_snapshot(&image).expect("the image can be read"))
+}
+
+#[test]
+fn restores_builtins() {
+    let mut engine = restore(&Executor::new(Realm::create()));
+    assert_eq!(forward(&mut engine, "[3, 1, 2].sort().join()"), "1,2,3");
+    assert_eq!(
+        forward(&mut engine, "[1, 2].map(function (x) { return x * 2; })[1]"),
+        "4"
+    );
+    assert_eq!(forward(&mut engine, "'abc'.toUpperCase()"), "ABC");
+    assert_eq!(forward(&mut engine, "/b(c)/.exec('abc')[1]"), "c");
+    assert_eq!(
+        forward(&mut engine, "JSON.stringify({ a: [1] })"),
+        r#"{"a":[1]}"#
+    );
+    assert_eq!(
+        forward(
+            &mut engine,
+            "var it = [1, 2][Symbol.iterator](); it.next(); it.next().value"
+        ),
+        "2"
+    );
+    assert_eq!(forward(&mut engine, "new Uint8Array([1, 2])[1]"), "2");
+    assert_eq!(forward(&mut engine, "eval('1 + 1')"), "2");
+    assert_eq!(forward(&mut engine, "globalThis.Math === Math"), "true");
+}
+
+#[test]
+fn restores_globals_of_scripts() {
+    let mut engine: Interpreter = Executor::new(Realm::create());
+    let init = r#"
+        var count = 0;
+        let label = 'counter';
+        const limit = 3;
+        function next() { return ++count; }
+        var makeCounter = function () {
+            var value = 10;
+            return () => ++value;
+        };
+        var counter = makeCounter();
+        counter();
+        var tree = { name: 'root', method(x) { return this.name + x; } };
+        tree.self = tree;
+        var key = Symbol('key');
+        tree[key] = 'symbol';
+        var shared = Symbol.for('shared');
+        var pattern = /a+/g;
+        pattern.test('baa');
+        var buffer = new ArrayBuffer(4);
+        var bytes = new Uint8Array(buffer);
+        bytes[1] = 7;
+        var frozen = Object.freeze({ a: 1 });
+        "#;
+    forward(&mut engine, init);
+    let mut restored = restore(&engine);
+
+    assert_eq!(forward(&mut restored, "next() + next()"), "3");
+    assert_eq!(forward(&mut restored, "label + limit"), "counter3");
+    assert_eq!(forward(&mut restored, "counter()"), "12");
+    assert_eq!(forward(&mut restored, "tree.self.method('!')"), "root!");
+    assert_eq!(forward(&mut restored, "tree[key]"), "symbol");
+    assert_eq!(
+        forward(&mut restored, "shared === Symbol.for('shared')"),
+        "true"
+    );
+    assert_eq!(forward(&mut restored, "pattern.lastIndex"), "3");
+    assert_eq!(forward(&mut restored, "pattern.exec('aaa')"), "null");
+    assert_eq!(
+        forward(&mut restored, "new Uint8Array(buffer)[1] = 9; bytes[1]"),
+        "9"
+    );
+    assert_eq!(forward(&mut restored, "frozen.a = 2; frozen.a"), "1");
+    assert_eq!(
+        forward(&mut restored, "tree.method.toString()"),
+        "method(x) { return this.name + x; }"
+    );
+
+    // The original realm is left alone
+    assert_eq!(forward(&mut engine, "count"), "0");
+    assert_eq!(forward(&mut engine, "bytes[1]"), "7");
+}
+
+#[test]
+fn restores_lone_surrogate_keys() {
+    let mut engine: Interpreter = Executor::new(Realm::create());
+    forward(
+        &mut engine,
+        "var obj = {}; obj['\\uD800'] = 1; obj['\\uDC00'] = 2;",
+    );
+    let mut restored = restore(&engine);
+    assert_eq!(forward(&mut restored, "Object.keys(obj).length"), "2");
+    assert_eq!(forward(&mut restored, "obj['\\uD800']"), "1");
+    assert_eq!(forward(&mut restored, "obj['\\uFFFD']"), "undefined");
+}
+
+#[test]
+fn restored_realms_are_independent() {
+    let mut engine: Interpreter = Executor::new(Realm::create());
+    forward(&mut engine, "var list = [];");
+    let image = engine.realm.snapshot().unwrap();
+    let mut first: Interpreter = Executor::new(Realm::from_snapshot(&image).unwrap());
+    let mut second: Interpreter = Executor::new(Realm::from_snapshot(&image).unwrap());
+    forward(&mut first, "list.push(1); Array.prototype.extra = 1;");
+    assert_eq!(forward(&mut second, "list.length"), "0");
+    assert_eq!(forward(&mut second, "[].extra"), "undefined");
+}
+
+#[test]
+fn restores_locked_down_realms() {
+    let realm = Realm::create();
+    realm.lockdown();
+    let mut engine: Interpreter = Executor::new(realm);
+    let mut restored = restore(&engine);
+    forward(&mut restored, "Array.prototype.push = 1; Array = 2;");
+    assert_eq!(forward(&mut restored, "[].push(1)"), "1");
+    assert_eq!(forward(&mut restored, "Array.isArray([])"), "true");
+    assert_eq!(forward(&mut engine, "Object.isFrozen(Math)"), "true");
+}
+
+#[test]
+fn restores_the_globals_of_shadow_realms() {
+    let realm = Realm::builder().without_global("console").build();
+    let mut restored = restore(&Executor::new(realm));
+    assert_eq!(
+        forward(
+            &mut restored,
+            "new ShadowRealm().evaluate('this.console === undefined')"
+        ),
+        "true"
+    );
+}
+
+fn answer(_: &mut Value, _: &[Value], _: &mut Interpreter) -> ResultValue {
+    Ok(Value::from(42))
+}
+
+#[test]
+fn host_functions() {
+    let realm = Realm::builder().host_function("answer", answer).build();
+    assert_eq!(
+        realm.snapshot(),
+        Err(SnapshotError::Unsupported(
+            "a host function unknown to the snapshotter".to_string()
+        ))
+    );
+
+    let snapshotter = Snapshotter::new().host_function(answer);
+    let image = snapshotter.write(&realm).unwrap();
+    assert!(Realm::from_snapshot(&image).is_err());
+    let mut engine: Interpreter = Executor::new(snapshotter.read(&image).unwrap());
+    assert_eq!(forward(&mut engine, "answer()"), "42");
+}
+
+#[test]
+fn unsupported_values() {
+    let mut engine: Interpreter = Executor::new(Realm::create());
+    forward(&mut engine, "var it = [1][Symbol.iterator]();");
+    assert_eq!(
+        engine.realm.snapshot(),
+        Err(SnapshotError::Unsupported(
+            "an iterator or an object with host state".to_string()
+        ))
+    );
+
+    let mut engine: Interpreter = Executor::new(Realm::create());
+    forward(&mut engine, "var inner = new ShadowRealm();");
+    assert_eq!(
+        engine.realm.snapshot(),
+        Err(SnapshotError::Unsupported(
+            "an object of another realm".to_string()
+        ))
+    );
+}
+
+#[test]
+fn invalid_images() {
+    let image = Realm::create().snapshot().unwrap();
+    assert_eq!(
+        Realm::from_snapshot(b"not an image").err(),
+        Some(SnapshotError::Invalid("not a realm image".to_string()))
+    );
+    assert_eq!(
+        Realm::from_snapshot(&image[..image.len() / 2]).err(),
+        Some(SnapshotError::Invalid("the image is truncated".to_string()))
+    );
+    let mut other_version = image.clone();
+    other_version[MAGIC.len()] += 1;
+    assert_eq!(
+        Realm::from_snapshot(&other_version).err(),
+        Some(SnapshotError::Invalid(
+            "the image was written by another build of Boa".to_string()
+        ))
+    );
+}
+
+#[test]
+fn parse_functions() {
+    let (params, _) = parse_function("function f(a, b) { return a + b; }").unwrap();
+    assert_eq!(params.len(), 2);
+    let (params, _) = parse_function("(a) => a * 2").unwrap();
+    assert_eq!(params.len(), 1);
+    let (params, _) = parse_function("method(a, b, c) {}").unwrap();
+    assert_eq!(params.len(), 3);
+    assert!(parse_function("1 + 1").is_none());
+}
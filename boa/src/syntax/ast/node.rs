@@ -1,8 +1,12 @@
 //! This module implements the `Node` structure, which composes the AST.
 
-use crate::syntax::ast::{
-    constant::Const,
-    op::{BinOp, Operator, UnaryOp},
+use crate::{
+    builtins::value::JsString,
+    syntax::ast::{
+        constant::Const,
+        op::{BinOp, Operator, UnaryOp},
+        pos::Position,
+    },
 };
 use gc::{Finalize, Trace};
 use std::fmt;
@@ -45,7 +49,7 @@ pub enum Node {
     ///
     /// [spec]: https://tc39.es/ecma262/#prod-ArrowFunction
     /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Functions/Arrow_functions
-    ArrowFunctionDecl(Box<[FormalParameter]>, Box<Node>),
+    ArrowFunctionDecl(Box<[FormalParameter]>, Box<Node>, Option<SourceText>),
 
     /// An assignment operator assigns a value to its left operand based on the value of its right
     /// operand.
@@ -180,6 +184,19 @@ pub enum Node {
     /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Statements/continue
     Continue(Option<String>),
 
+    /// The `debugger` statement invokes any available debugging functionality, such as setting a
+    /// breakpoint.
+    ///
+    /// If no debugging functionality is available, this statement has no effect.
+    ///
+    /// More information:
+    ///  - [ECMAScript reference][spec]
+    ///  - [MDN documentation][mdn]
+    ///
+    /// [spec]: https://tc39.es/ecma262/#prod-DebuggerStatement
+    /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Statements/debugger
+    Debugger,
+
     /// The `do...while` statement creates a loop that executes a specified statement until the
     /// test condition evaluates to false.
     ///
@@ -205,13 +222,20 @@ pub enum Node {
     /// By default, functions return `undefined`. To return any other value, the function must have
     /// a return statement that specifies the value to return.
     ///
+    /// The source text of the function is kept when the parser was given the source code.
+    ///
     /// More information:
     ///  - [ECMAScript reference][spec]
     ///  - [MDN documentation][mdn]
     ///
     /// [spec]: https://tc39.es/ecma262/#sec-terms-and-definitions-function
     /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Statements/function
-    FunctionDecl(String, Box<[FormalParameter]>, Box<Node>),
+    FunctionDecl(
+        String,
+        Box<[FormalParameter]>,
+        Box<Node>,
+        Option<SourceText>,
+    ),
 
     /// The `function` expression defines a function with the specified parameters.
     ///
@@ -223,13 +247,20 @@ pub enum Node {
     /// By default, functions return `undefined`. To return any other value, the function must have
     /// a return statement that specifies the value to return.
     ///
+    /// Like declarations, function expressions may hold their source text.
+    ///
     /// More information:
     ///  - [ECMAScript reference][spec]
     ///  - [MDN documentation][mdn]
     ///
     /// [spec]: https://tc39.es/ecma262/#sec-terms-and-definitions-function
     /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Operators/function
-    FunctionExpr(Option<String>, Box<[FormalParameter]>, Box<Node>),
+    FunctionExpr(
+        Option<String>,
+        Box<[FormalParameter]>,
+        Box<Node>,
+        Option<SourceText>,
+    ),
 
     /// This property accessor provides access to an object's properties by using the
     /// [dot notation][mdn].
@@ -351,6 +382,12 @@ pub enum Node {
     /// [mdn]: https://developer.mozilla.org/en-US/docs/Glossary/Identifier
     Local(String),
 
+    /// The position in the source code of the statement which follows in a statement list.
+    ///
+    /// It isn't part of the language: parsers only record locations when asked to, so that the
+    /// debugger can tell which line is running. Evaluating it does nothing else.
+    Location(Position),
+
     /// The `new` operator lets developers create an instance of a user-defined object type or of
     /// one of the built-in object types that has a constructor function.
     ///
@@ -611,7 +648,7 @@ impl Node {
         P: Into<Box<[FormalParameter]>>,
         B: Into<Box<Self>>,
     {
-        Self::ArrowFunctionDecl(params.into(), body.into())
+        Self::ArrowFunctionDecl(params.into(), body.into(), None)
     }
 
     /// Creates an `Assign` AST node.
@@ -694,6 +731,11 @@ impl Node {
         Self::Continue(label.into().map(L::into))
     }
 
+    /// Creates a `Debugger` AST node.
+    pub fn debugger() -> Self {
+        Self::Debugger
+    }
+
     /// Creates a `DoWhileLoop` AST node.
     pub fn do_while_loop<B, C>(body: B, condition: C) -> Self
     where
@@ -710,7 +752,7 @@ impl Node {
         P: Into<Box<[FormalParameter]>>,
         B: Into<Box<Self>>,
     {
-        Self::FunctionDecl(name.into(), params.into(), body.into())
+        Self::FunctionDecl(name.into(), params.into(), body.into(), None)
     }
 
     /// Creates a `FunctionDecl` AST node.
@@ -721,7 +763,18 @@ impl Node {
         P: Into<Box<[FormalParameter]>>,
         B: Into<Box<Self>>,
     {
-        Self::FunctionExpr(name.into().map(N::into), params.into(), body.into())
+        Self::FunctionExpr(name.into().map(N::into), params.into(), body.into(), None)
+    }
+
+    /// Keeps the source text of a function node, other nodes are returned unchanged.
+    pub fn with_source_text(mut self, source_text: Option<SourceText>) -> Self {
+        match self {
+            Self::ArrowFunctionDecl(_, _, ref mut text)
+            | Self::FunctionDecl(_, _, _, ref mut text)
+            | Self::FunctionExpr(_, _, _, ref mut text) => *text = source_text,
+            _ => {}
+        }
+        self
     }
 
     /// Creates a `GetConstField` AST node.
@@ -788,6 +841,11 @@ impl Node {
         Self::Local(name.into())
     }
 
+    /// Creates a `Location` AST node.
+    pub fn location(position: Position) -> Self {
+        Self::Location(position)
+    }
+
     /// Creates a `New` AST node.
     pub fn new<N>(node: N) -> Self
     where
@@ -908,6 +966,11 @@ impl Node {
         Self::WhileLoop(condition.into(), body.into())
     }
 
+    /// Checks if the node is a `Location` marker rather than a statement.
+    pub(crate) fn is_location(&self) -> bool {
+        matches!(*self, Self::Location(_))
+    }
+
     /// Implements the display formatting with indentation.
     fn display(&self, f: &mut fmt::Formatter<'_>, indentation: usize) -> fmt::Result {
         let indent = "    ".repeat(indentation);
@@ -943,16 +1006,18 @@ impl Node {
                 }
             ),
             Self::Spread(ref node) => write!(f, "...{}", node),
+            Self::Debugger => write!(f, "debugger"),
+            Self::Location(ref pos) => write!(f, "/* line {} */", pos.line_number),
             Self::Block(ref block) => {
                 writeln!(f, "{{")?;
-                for node in block.iter() {
+                for node in block.iter().filter(|node| !node.is_location()) {
                     node.display(f, indentation + 1)?;
 
                     match node {
                         Self::Block(_)
                         | Self::If(_, _, _)
                         | Self::Switch(_, _, _)
-                        | Self::FunctionDecl(_, _, _)
+                        | Self::FunctionDecl(..)
                         | Self::WhileLoop(_, _)
                         | Self::StatementList(_) => {}
                         _ => write!(f, ";")?,
@@ -962,14 +1027,14 @@ impl Node {
                 write!(f, "{}}}", indent)
             }
             Self::StatementList(ref list) => {
-                for node in list.iter() {
+                for node in list.iter().filter(|node| !node.is_location()) {
                     node.display(f, indentation + 1)?;
 
                     match node {
                         Self::Block(_)
                         | Self::If(_, _, _)
                         | Self::Switch(_, _, _)
-                        | Self::FunctionDecl(_, _, _)
+                        | Self::FunctionDecl(..)
                         | Self::WhileLoop(_, _)
                         | Self::StatementList(_) => {}
                         _ => write!(f, ";")?,
@@ -1067,13 +1132,13 @@ impl Node {
                 join_nodes(f, arr)?;
                 f.write_str("]")
             }
-            Self::FunctionDecl(ref name, ref _args, ref node) => {
+            Self::FunctionDecl(ref name, ref _args, ref node, _) => {
                 write!(f, "function {} {{", name)?;
                 //join_nodes(f, args)?; TODO: port
                 f.write_str("} ")?;
                 node.display(f, indentation + 1)
             }
-            Self::FunctionExpr(ref name, ref args, ref node) => {
+            Self::FunctionExpr(ref name, ref args, ref node, _) => {
                 write!(f, "function ")?;
                 if let Some(func_name) = name {
                     write!(f, "{}", func_name)?;
@@ -1083,7 +1148,7 @@ impl Node {
                 f.write_str("} ")?;
                 node.display(f, indentation + 1)
             }
-            Self::ArrowFunctionDecl(ref args, ref node) => {
+            Self::ArrowFunctionDecl(ref args, ref node, _) => {
                 write!(f, "(")?;
                 join_nodes(f, args)?;
                 f.write_str(") => ")?;
@@ -1137,6 +1202,14 @@ where
     Ok(())
 }
 
+/// The source text of a function, with the position where it starts in the script.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, PartialEq, Trace, Finalize)]
+pub struct SourceText {
+    pub text: String,
+    pub position: Position,
+}
+
 /// "Formal parameter" is a fancy way of saying "function parameter".
 ///
 /// In the declaration of a function, the parameters must be identifiers,
@@ -1220,7 +1293,7 @@ pub enum PropertyDefinition {
     ///
     /// [spec]: https://tc39.es/ecma262/#prod-PropertyDefinition
     /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Operators/Object_initializer#Property_definitions
-    Property(String, Node),
+    Property(JsString, Node),
 
     /// A property of an object can also refer to a function or a getter or setter method.
     ///
@@ -1258,7 +1331,7 @@ impl PropertyDefinition {
     /// Creates a `Property` definition.
     pub fn property<N, V>(name: N, value: V) -> Self
     where
-        N: Into<String>,
+        N: Into<JsString>,
         V: Into<Node>,
     {
         Self::Property(name.into(), value.into())
@@ -592,6 +592,21 @@ pub enum CompOp {
     /// [spec]: https://tc39.es/ecma262/#prod-RelationalExpression
     /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Operators/in
     In,
+    /// The `instanceof` operator returns true if the specified object is an instance of the
+    /// right hand side object.
+    ///
+    /// Syntax: `obj instanceof Object`
+    ///
+    /// Returns `true` if the `prototype` property of the right hand side constructor appears anywhere in the prototype chain of the object,
+    /// unless the constructor customises the check with a `Symbol.hasInstance` method.
+    ///
+    /// More information:
+    ///  - [ECMAScript reference][spec]
+    ///  - [MDN documentation][mdn]
+    ///
+    /// [spec]: https://tc39.es/ecma262/#prod-RelationalExpression
+    /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Operators/instanceof
+    InstanceOf,
 }
 
 impl Display for CompOp {
@@ -609,6 +624,7 @@ impl Display for CompOp {
                 Self::LessThan => "<",
                 Self::LessThanOrEqual => "<=",
                 Self::In => "in",
+                Self::InstanceOf => "instanceof",
             }
         )
     }
@@ -742,7 +758,8 @@ impl Operator for BinOp {
             | Self::Comp(CompOp::LessThanOrEqual)
             | Self::Comp(CompOp::GreaterThan)
             | Self::Comp(CompOp::GreaterThanOrEqual)
-            | Self::Comp(CompOp::In) => 8,
+            | Self::Comp(CompOp::In)
+            | Self::Comp(CompOp::InstanceOf) => 8,
             Self::Comp(CompOp::Equal)
             | Self::Comp(CompOp::NotEqual)
             | Self::Comp(CompOp::StrictEqual)
@@ -1,5 +1,6 @@
 //! This module implements the `Pos` structure, which represents a position in the source code.
 
+use gc::{unsafe_empty_trace, Finalize, Trace};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
@@ -10,7 +11,7 @@ use serde::{Deserialize, Serialize};
 /// ## Similar Implementations
 /// [V8: Location](https://cs.chromium.org/chromium/src/v8/src/parsing/scanner.h?type=cs&q=isValid+Location&g=0&l=216)
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Clone, Copy, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub struct Position {
     // Column number
     pub column_number: u64,
@@ -18,6 +19,13 @@ pub struct Position {
     pub line_number: u64,
 }
 
+impl Finalize for Position {}
+
+/// `Position` holds only numbers, so there is nothing to trace.
+unsafe impl Trace for Position {
+    unsafe_empty_trace!();
+}
+
 impl Position {
     /// Creates a new `Position`.
     ///
@@ -34,3 +42,22 @@ impl Position {
         }
     }
 }
+
+/// A range of the Javascript source code, given by the byte offsets of its start and its end.
+///
+/// Spans keep the original source text of the code, e.g. for `Function.prototype.toString`.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct Span {
+    // Offset of the first byte
+    pub start: usize,
+    // Offset after the last byte
+    pub end: usize,
+}
+
+impl Span {
+    /// Creates a new `Span`.
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+}
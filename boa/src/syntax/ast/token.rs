@@ -5,7 +5,14 @@
 //!
 //! [spec]: https://tc39.es/ecma262/#sec-tokens
 
-use crate::syntax::ast::{keyword::Keyword, pos::Position, punc::Punctuator};
+use crate::{
+    builtins::value::JsString,
+    syntax::ast::{
+        keyword::Keyword,
+        pos::{Position, Span},
+        punc::Punctuator,
+    },
+};
 use std::fmt::{Debug, Display, Formatter, Result};
 
 #[cfg(feature = "serde")]
@@ -25,6 +32,9 @@ pub struct Token {
 
     /// The token position from origina source code.
     pub pos: Position,
+
+    /// The range of the source code the token was read from.
+    pub span: Span,
 }
 
 impl Token {
@@ -33,6 +43,7 @@ impl Token {
         Self {
             kind,
             pos: Position::new(line_number, column_number),
+            span: Span::default(),
         }
     }
 }
@@ -110,7 +121,7 @@ pub enum TokenKind {
     Punctuator(Punctuator),
 
     /// A string literal.
-    StringLiteral(String),
+    StringLiteral(JsString),
 
     /// A regular expression, consisting of body and flags.
     RegularExpressionLiteral(String, String),
@@ -177,7 +188,7 @@ impl TokenKind {
     /// Creates a `StringLiteral` token type.
     pub fn string_literal<S>(lit: S) -> Self
     where
-        S: Into<String>,
+        S: Into<JsString>,
     {
         Self::StringLiteral(lit.into())
     }
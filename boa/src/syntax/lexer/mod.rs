@@ -6,12 +6,16 @@
 #[cfg(test)]
 mod tests;
 
-use crate::syntax::ast::{
-    punc::Punctuator,
-    token::{NumericLiteral, Token, TokenKind},
+use crate::{
+    builtins::value::JsString,
+    syntax::ast::{
+        pos::{Position, Span},
+        punc::Punctuator,
+        token::{NumericLiteral, Token, TokenKind},
+    },
 };
 use std::{
-    char::{decode_utf16, from_u32},
+    char::from_u32,
     error, fmt,
     iter::Peekable,
     str::{Chars, FromStr},
@@ -22,9 +26,9 @@ use std::{
 /// If the next value is not an assignment operation it will pattern match  the provided values and return the corresponding token.
 macro_rules! vop {
     ($this:ident, $assign_op:expr, $op:expr) => ({
-        let preview = $this.preview_next().ok_or_else(|| LexerError::new("Could not preview next value"))?;
-        match preview {
-            '=' => {
+        // The operator can be the last character of the source, the parser reports it
+        match $this.preview_next() {
+            Some('=') => {
                 $this.next();
                 $this.column_number += 1;
                 $assign_op
@@ -33,14 +37,13 @@ macro_rules! vop {
         }
     });
     ($this:ident, $assign_op:expr, $op:expr, {$($case:pat => $block:expr), +}) => ({
-        let preview = $this.preview_next().ok_or_else(|| LexerError::new("Could not preview next value"))?;
-        match preview {
-            '=' => {
+        match $this.preview_next() {
+            Some('=') => {
                 $this.next();
                 $this.column_number += 1;
                 $assign_op
             },
-            $($case => {
+            $(Some($case) => {
                 $this.next();
                 $this.column_number += 1;
                 $block
@@ -49,9 +52,8 @@ macro_rules! vop {
         }
     });
     ($this:ident, $op:expr, {$($case:pat => $block:expr),+}) => {
-        let preview = $this.preview_next().ok_or_else(|| LexerError::new("Could not preview next value"))?;
-        match preview {
-            $($case => {
+        match $this.preview_next() {
+            $(Some($case) => {
                 $this.next()?;
                 $this.column_number += 1;
                 $block
@@ -84,6 +86,8 @@ macro_rules! op {
 pub struct LexerError {
     /// details will be displayed when a LexerError occurs.
     details: String,
+    /// Where the lexer stopped in the source.
+    position: Option<Position>,
 }
 
 impl LexerError {
@@ -93,13 +97,27 @@ impl LexerError {
     fn new(msg: &str) -> Self {
         Self {
             details: msg.to_string(),
+            position: None,
         }
     }
+
+    /// Returns the position in the source where the error occurred.
+    pub fn position(&self) -> Option<Position> {
+        self.position
+    }
 }
 
 impl fmt::Display for LexerError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.details)
+        write!(f, "{}", self.details)?;
+        if let Some(position) = self.position {
+            write!(
+                f,
+                " at line {}, col {}",
+                position.line_number, position.column_number
+            )?;
+        }
+        Ok(())
     }
 }
 
@@ -125,6 +143,10 @@ pub struct Lexer<'a> {
     line_number: u64,
     /// the current column number in the script
     column_number: u64,
+    /// The byte offset of the next character in the script
+    offset: usize,
+    /// The byte offset of the token being read
+    token_start: usize,
     /// The full Peekable buffer, an array of [Char]s
     buffer: Peekable<Chars<'a>>,
 }
@@ -138,14 +160,17 @@ impl<'a> Lexer<'a> {
             tokens: Vec::new(),
             line_number: 1,
             column_number: 0,
+            offset: 0,
+            token_start: 0,
             buffer: buffer.chars().peekable(),
         }
     }
 
     /// Push a token onto the token queue.
     fn push_token(&mut self, tk: TokenKind) {
-        self.tokens
-            .push(Token::new(tk, self.line_number, self.column_number))
+        let mut token = Token::new(tk, self.line_number, self.column_number);
+        token.span = Span::new(self.token_start, self.offset);
+        self.tokens.push(token)
     }
 
     /// Push a punctuation token
@@ -155,10 +180,12 @@ impl<'a> Lexer<'a> {
 
     /// next fetches the next token and return it, or a LexerError if there are no more.
     fn next(&mut self) -> char {
-        self.buffer.next().expect(
+        let ch = self.buffer.next().expect(
             "No more more characters to consume from input stream, \
              use preview_next() first to check before calling next()",
-        )
+        );
+        self.offset += ch.len_utf8();
+        ch
     }
 
     /// Preview the next character but don't actually increment
@@ -197,11 +224,25 @@ impl<'a> Lexer<'a> {
         Ok(s)
     }
 
+    /// Reads exactly `count` hexadecimal digits and returns their value.
+    fn read_hex_digits(&mut self, count: usize) -> Result<u32, LexerError> {
+        let mut value = 0;
+        for _ in 0..count {
+            let digit = self
+                .preview_next()
+                .and_then(|c| c.to_digit(16))
+                .ok_or_else(|| LexerError::new("Invalid hexadecimal escape sequence"))?;
+            self.next();
+            value = value * 16 + digit;
+        }
+        Ok(value)
+    }
+
     /// Compares the character passed in to the next character, if they match true is returned and the buffer is incremented
     fn next_is(&mut self, peek: char) -> bool {
         let result = self.preview_next() == Some(peek);
         if result {
-            self.buffer.next();
+            self.next();
         }
         result
     }
@@ -444,16 +485,28 @@ impl<'a> Lexer<'a> {
     /// }
     /// ```
     pub fn lex(&mut self) -> Result<(), LexerError> {
+        self.lex_tokens().map_err(|mut error| {
+            error.position = Some(Position::new(self.line_number, self.column_number));
+            error
+        })
+    }
+
+    /// Lexes the tokens of the whole source.
+    fn lex_tokens(&mut self) -> Result<(), LexerError> {
         loop {
             // Check if we've reached the end
             if self.preview_next().is_none() {
                 return Ok(());
             }
             self.column_number += 1;
+            self.token_start = self.offset;
             let ch = self.next();
             match ch {
                 '"' | '\'' => {
-                    let mut buf = String::new();
+                    // Strings are sequences of UTF-16 code units, which can include lone
+                    // surrogates written as `\uXXXX` escapes.
+                    let mut buf: Vec<u16> = Vec::new();
+                    let mut utf16 = [0; 2];
                     loop {
                         if self.preview_next().is_none() {
                             return Err(LexerError::new("Unterminated String"));
@@ -479,99 +532,52 @@ impl<'a> Lexer<'a> {
                                         'f' => '\x0c',
                                         '0' => '\0',
                                         'x' => {
-                                            let mut nums = String::with_capacity(2);
-                                            for _ in 0_u8..2 {
-                                                if self.preview_next().is_none() {
-                                                    return Err(LexerError::new("Unterminated String"));
-                                                }
-                                                nums.push(self.next());
-                                            }
+                                            let as_num = self.read_hex_digits(2)?;
                                             self.column_number += 2;
-                                            let as_num = match u64::from_str_radix(&nums, 16) {
-                                                Ok(v) => v,
-                                                Err(_) => 0,
-                                            };
-                                            match from_u32(as_num as u32) {
-                                                Some(v) => v,
-                                                None => panic!(
-                                                    "{}:{}: {} is not a valid unicode scalar value",
-                                                    self.line_number, self.column_number, as_num
-                                                ),
-                                            }
+                                            from_u32(as_num).expect("two hex digits are a valid char")
                                         }
                                         'u' => {
-                                            // There are 2 types of codepoints. Surragate codepoints and unicode codepoints.
-                                            // UTF-16 could be surrogate codepoints, "\uXXXX\uXXXX" which make up a single unicode codepoint.
-                                            // We will need to loop to make sure we catch all UTF-16 codepoints
-                                            // Example Test: https://github.com/tc39/test262/blob/ee3715ee56744ccc8aeb22a921f442e98090b3c1/implementation-contributed/v8/mjsunit/es6/unicode-escapes.js#L39-L44
-
                                             // Support \u{X..X} (Unicode Codepoint)
                                             if self.next_is('{') {
                                                 let s = self
-                                                    .take_char_while(char::is_alphanumeric)
+                                                    .take_char_while(|c| c.is_digit(16))
                                                     .expect("Could not read chars");
 
                                                 // We know this is a single unicode codepoint, convert to u32
-                                                let as_num = match u32::from_str_radix(&s, 16) {
-                                                    Ok(v) => v,
-                                                    Err(_) => 0,
-                                                };
-                                                let c = from_u32(as_num).ok_or_else(|| LexerError::new("Invalid Unicode escape sequence"))?;
-
-                                                if self.preview_next().is_none() {
-                                                    return Err(LexerError::new("Unterminated String"));
+                                                let c = u32::from_str_radix(&s, 16)
+                                                    .ok()
+                                                    .and_then(from_u32)
+                                                    .ok_or_else(|| LexerError::new("Invalid Unicode escape sequence"))?;
+
+                                                if !self.next_is('}') {
+                                                    return Err(LexerError::new("Invalid Unicode escape sequence"));
                                                 }
-                                                self.next(); // '}'
                                                 self.column_number +=
                                                     (s.len() as u64).wrapping_add(3);
                                                 c
                                             } else {
-                                                let mut codepoints: Vec<u16> = vec![];
-                                                loop {
-                                                    // Collect each character after \u e.g \uD83D will give "D83D"
-                                                    let s = self
-                                                        .take_char_while(char::is_alphanumeric)
-                                                        .expect("Could not read chars");
-
-                                                    // Convert to u16
-                                                    let as_num = match u16::from_str_radix(&s, 16) {
-                                                        Ok(v) => v,
-                                                        Err(_) => 0,
-                                                    };
-
-                                                    codepoints.push(as_num);
-                                                    self.column_number +=
-                                                        (s.len() as u64).wrapping_add(2);
-
-                                                    // Check for another UTF-16 codepoint
-                                                    if self.next_is('\\') && self.next_is('u') {
-                                                        continue;
-                                                    }
-                                                    break;
-                                                }
-
-                                                // codepoints length should either be 1 (unicode codepoint) or 2 (surrogate codepoint).
-                                                // Rust's decode_utf16 will deal with it regardless
-                                                decode_utf16(codepoints.iter().cloned())
-                                                    .next()
-                                                    .expect("Could not get next codepoint")
-                                                    .expect("Could not get next codepoint")
+                                                // A \uXXXX escape is a single UTF-16 code unit. Surrogate pairs are
+                                                // written as two consecutive escapes, and lone surrogates are kept
+                                                // as is.
+                                                let unit = self.read_hex_digits(4)?;
+                                                self.column_number += 5;
+                                                buf.push(unit as u16);
+                                                continue;
                                             }
                                         }
                                         '\'' | '"' | '\\' => escape,
                                         ch => {
-                                            let details = format!("{}:{}: Invalid escape `{}`", self.line_number, self.column_number, ch);
-                                            return Err(LexerError { details });
+                                            return Err(LexerError::new(&format!("Invalid escape `{}`", ch)));
                                         }
                                     };
-                                    buf.push(escaped_ch);
+                                    buf.extend_from_slice(escaped_ch.encode_utf16(&mut utf16));
                                 }
                             }
-                            next_ch => buf.push(next_ch),
+                            next_ch => buf.extend_from_slice(next_ch.encode_utf16(&mut utf16)),
                         }
                     }
                     let str_length = buf.len() as u64;
-                    self.push_token(TokenKind::StringLiteral(buf));
+                    self.push_token(TokenKind::StringLiteral(JsString::from(buf)));
                     // Why +1? Quotation marks are not included,
                     // So technically it would be +2, (for both " ") but we want to be 1 less
                     // to compensate for the incrementing at the top
@@ -592,7 +598,6 @@ impl<'a> Lexer<'a> {
                         "true" => TokenKind::BooleanLiteral(true),
                         "false" => TokenKind::BooleanLiteral(false),
                         "null" => TokenKind::NullLiteral,
-                        "NaN" => TokenKind::NumericLiteral(NumericLiteral::Rational(f64::NAN)),
                         slice => {
                             if let Ok(keyword) = FromStr::from_str(slice) {
                                 TokenKind::Keyword(keyword)
@@ -669,12 +674,15 @@ impl<'a> Lexer<'a> {
                                 // if we fail to parse a regex literal, store a copy of the current
                                 // buffer to restore later on
                                 let original_buffer = self.buffer.clone();
+                                let original_offset = self.offset;
                                 // first, try to parse a regex literal
                                 let mut body = String::new();
                                 let mut regex = false;
                                 loop {
                                     self.column_number +=1;
-                                    match self.buffer.next() {
+                                    let next = self.buffer.next();
+                                    self.offset += next.map_or(0, char::len_utf8);
+                                    match next {
                                         // end of body
                                         Some('/') => {
                                             regex = true;
@@ -718,6 +726,7 @@ impl<'a> Lexer<'a> {
                                     // failed to parse regex, restore original buffer position and
                                     // parse either div or assigndiv
                                     self.buffer = original_buffer;
+                                    self.offset = original_offset;
                                     if self.next_is('=') {
                                         self.push_token(TokenKind::Punctuator(
                                             Punctuator::AssignDiv,
@@ -792,8 +801,7 @@ impl<'a> Lexer<'a> {
                 // Unicode Space_Seperator category (minus \u{0020} and \u{00A0} which are allready stated above)
                 '\u{1680}' | '\u{2000}'..='\u{200A}' | '\u{202F}' | '\u{205F}' | '\u{3000}' => (),
                 _ => {
-                    let details = format!("{}:{}: Unexpected '{}'", self.line_number, self.column_number, ch);
-                    return Err(LexerError { details });
+                    return Err(LexerError::new(&format!("Unexpected '{}'", ch)));
                 },
             }
         }
@@ -33,6 +33,23 @@ fn check_string() {
     assert_eq!(lexer.tokens[1].kind, TokenKind::string_literal("bbb"));
 }
 
+#[test]
+fn check_string_unicode_escapes() {
+    let s = r#"'\u{1F600}' '\uD800' '\x41'"#;
+    let mut lexer = Lexer::new(s);
+    lexer.lex().expect("failed to lex");
+    assert_eq!(
+        lexer.tokens[0].kind,
+        TokenKind::string_literal(vec![0xD83D, 0xDE00])
+    );
+    // Lone surrogates are kept as they are
+    assert_eq!(
+        lexer.tokens[1].kind,
+        TokenKind::string_literal(vec![0xD800])
+    );
+    assert_eq!(lexer.tokens[2].kind, TokenKind::string_literal("A"));
+}
+
 #[test]
 fn check_punctuators() {
     // https://tc39.es/ecma262/#sec-punctuators
@@ -374,12 +391,8 @@ fn check_nan() {
     let mut lexer = Lexer::new("let a = NaN;");
     lexer.lex().expect("failed to lex");
 
-    match lexer.tokens[3].kind {
-        TokenKind::NumericLiteral(NumericLiteral::Rational(a)) => {
-            assert_eq!(a.is_nan(), true);
-        }
-        _ => assert!(false),
-    }
+    // `NaN` is a property of the global object, not a literal
+    assert_eq!(lexer.tokens[3].kind, TokenKind::identifier("NaN"));
 }
 
 #[test]
@@ -529,3 +542,33 @@ fn addition_no_spaces_e_number() {
         TokenKind::numeric_literal(100_000_000_000.0)
     );
 }
+
+#[test]
+fn check_token_spans() {
+    let source = "let é = 'ü' /* c */ / 2;";
+    let mut lexer = Lexer::new(source);
+    lexer.lex().expect("failed to lex");
+    let texts: Vec<&str> = lexer
+        .tokens
+        .iter()
+        .map(|token| &source[token.span.start..token.span.end])
+        .collect();
+    assert_eq!(texts, ["let", "é", "=", "'ü'", "/", "2", ";"]);
+}
+
+#[test]
+fn errors_have_positions() {
+    let mut lexer = Lexer::new("let a = 1;\nlet b = 'open");
+    let error = lexer.lex().expect_err("lexed an unterminated string");
+    assert_eq!(error.position().map(|p| p.line_number), Some(2));
+    assert_eq!(error.to_string(), "Unterminated String at line 2, col 9");
+}
+
+#[test]
+fn operators_at_end_of_input() {
+    for source in &["1 +", "x =", "a <", "b &"] {
+        let mut lexer = Lexer::new(source);
+        lexer.lex().expect("failed to lex");
+        assert_eq!(lexer.tokens.len(), 2);
+    }
+}
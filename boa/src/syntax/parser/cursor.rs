@@ -2,10 +2,21 @@
 
 use super::ParseError;
 use crate::syntax::ast::{
+    keyword::Keyword,
+    node::{Node, SourceText},
     punc::Punctuator,
     token::{Token, TokenKind},
 };
 
+/// The maximum number of nested statements and expressions in a script.
+const MAX_NESTING_DEPTH: usize = 1000;
+
+/// The space left on the stack below which `Cursor::nested` allocates a new stack segment.
+const STACK_RED_ZONE: usize = 256 * 1024;
+
+/// The size of the stack segments allocated by `Cursor::nested`.
+const STACK_GROWTH: usize = 2 * 1024 * 1024;
+
 /// Token cursor.
 ///
 /// This internal structure gives basic testable operations to the parser.
@@ -15,6 +26,12 @@ pub(super) struct Cursor<'a> {
     tokens: &'a [Token],
     /// The current position within the tokens.
     pos: usize,
+    /// The source code the tokens were read from, if known.
+    source: Option<&'a str>,
+    /// The number of nested statements and expressions being parsed.
+    depth: usize,
+    /// Whether the position of every statement of a statement list is recorded.
+    locations: bool,
 }
 
 impl<'a> Cursor<'a> {
@@ -26,6 +43,92 @@ impl<'a> Cursor<'a> {
         }
     }
 
+    /// Creates a new cursor which knows the source code of the tokens.
+    pub(super) fn with_source(tokens: &'a [Token], source: &'a str) -> Self {
+        Self {
+            tokens,
+            source: Some(source),
+            ..Self::default()
+        }
+    }
+
+    /// Makes the parser record the position of the statements of statement lists.
+    pub(super) fn record_locations(&mut self) {
+        self.locations = true;
+    }
+
+    /// The location of the statement starting at the next token, if the parser records them.
+    ///
+    /// Function declarations get none, they are hoisted and there is nothing to stop at where
+    /// they are.
+    pub(super) fn statement_location(&self) -> Option<Node> {
+        if !self.locations {
+            return None;
+        }
+        self.peek(0)
+            .filter(|token| token.kind != TokenKind::Keyword(Keyword::Function))
+            .map(|token| Node::location(token.pos))
+    }
+
+    /// Retrieves the source text from the token at position `start` to the last token read, with
+    /// the position of its first token, if the source code is known.
+    pub(super) fn source_text(&self, start: usize) -> Option<SourceText> {
+        let source = self.source?;
+        let first = self
+            .tokens
+            .get(start..self.pos)?
+            .iter()
+            .find(|tk| tk.kind != TokenKind::LineTerminator)?;
+        let last = self.peek_prev()?;
+        source
+            .get(first.span.start..last.span.end)
+            .map(|text| SourceText {
+                text: String::from(text),
+                position: first.pos,
+            })
+    }
+
+    /// Runs a parser of a statement or expression which can be nested in itself.
+    ///
+    /// Parsers call each other recursively, the nesting depth is limited so that a deeply nested
+    /// script gives an error instead of overflowing the stack.
+    pub(super) fn nested<T, F>(&mut self, parse: F) -> Result<T, ParseError>
+    where
+        F: FnOnce(&mut Self) -> Result<T, ParseError>,
+    {
+        self.chained(|cursor| {
+            cursor.deepen()?;
+            stacker::maybe_grow(STACK_RED_ZONE, STACK_GROWTH, || parse(cursor))
+        })
+    }
+
+    /// Runs a parser which builds its node in a loop, such as the parser of the left associative
+    /// binary operators or of property accesses.
+    ///
+    /// Such a parser doesn't recurse, but every step of its loop nests the node built so far one
+    /// level deeper, which `Cursor::deepen` counts. The depth is restored afterwards.
+    pub(super) fn chained<T, F>(&mut self, parse: F) -> Result<T, ParseError>
+    where
+        F: FnOnce(&mut Self) -> Result<T, ParseError>,
+    {
+        let depth = self.depth;
+        let result = parse(self);
+        self.depth = depth;
+        result
+    }
+
+    /// Counts one more level of nesting, within a `Cursor::nested` or `Cursor::chained` parser.
+    pub(super) fn deepen(&mut self) -> Result<(), ParseError> {
+        if self.depth >= MAX_NESTING_DEPTH {
+            return Err(ParseError::General(
+                "Maximum nesting depth exceeded",
+                self.peek(0).map(|tok| tok.pos),
+            ));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
     /// Retrieves the current position of the cursor in the token stream.
     pub(super) fn pos(&self) -> usize {
         self.pos
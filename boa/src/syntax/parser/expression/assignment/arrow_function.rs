@@ -60,6 +60,7 @@ impl TokenParser for ArrowFunction {
     type Output = Node;
 
     fn parse(self, cursor: &mut Cursor<'_>) -> ParseResult {
+        let start = cursor.pos();
         let next_token = cursor.peek(0).ok_or(ParseError::AbruptEnd)?;
         let params = if let TokenKind::Punctuator(Punctuator::OpenParen) = &next_token.kind {
             // CoverParenthesizedExpressionAndArrowParameterList
@@ -90,7 +91,7 @@ impl TokenParser for ArrowFunction {
 
         let body = ConciseBody::new(self.allow_in).parse(cursor)?;
 
-        Ok(Node::arrow_function_decl(params, body))
+        Ok(Node::arrow_function_decl(params, body).with_source_text(cursor.source_text(start)))
     }
 }
 
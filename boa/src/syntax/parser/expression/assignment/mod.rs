@@ -67,60 +67,63 @@ impl TokenParser for AssignmentExpression {
     type Output = Node;
 
     fn parse(self, cursor: &mut Cursor<'_>) -> ParseResult {
-        // Arrow function
-        let next_token = cursor.peek(0).ok_or(ParseError::AbruptEnd)?;
-        match next_token.kind {
-            // a=>{}
-            TokenKind::Identifier(_)
-            | TokenKind::Keyword(Keyword::Yield)
-            | TokenKind::Keyword(Keyword::Await)
-                if cursor
-                    .peek_expect_no_lineterminator(1, "arrow function")
-                    .is_ok() =>
-            {
-                if let Some(tok) = cursor.peek(1) {
-                    if tok.kind == TokenKind::Punctuator(Punctuator::Arrow) {
-                        return ArrowFunction::new(
-                            self.allow_in,
-                            self.allow_yield,
-                            self.allow_await,
-                        )
-                        .parse(cursor);
+        cursor.nested(|cursor| {
+            // Arrow function
+            let next_token = cursor.peek(0).ok_or(ParseError::AbruptEnd)?;
+            match next_token.kind {
+                // a=>{}
+                TokenKind::Identifier(_)
+                | TokenKind::Keyword(Keyword::Yield)
+                | TokenKind::Keyword(Keyword::Await)
+                    if cursor
+                        .peek_expect_no_lineterminator(1, "arrow function")
+                        .is_ok() =>
+                {
+                    if let Some(tok) = cursor.peek(1) {
+                        if tok.kind == TokenKind::Punctuator(Punctuator::Arrow) {
+                            return ArrowFunction::new(
+                                self.allow_in,
+                                self.allow_yield,
+                                self.allow_await,
+                            )
+                            .parse(cursor);
+                        }
                     }
                 }
-            }
-            // (a,b)=>{}
-            TokenKind::Punctuator(Punctuator::OpenParen) => {
-                if let Some(node) =
-                    ArrowFunction::new(self.allow_in, self.allow_yield, self.allow_await)
-                        .try_parse(cursor)
-                {
-                    return Ok(node);
+                // (a,b)=>{}
+                TokenKind::Punctuator(Punctuator::OpenParen) => {
+                    if let Some(node) =
+                        ArrowFunction::new(self.allow_in, self.allow_yield, self.allow_await)
+                            .try_parse(cursor)
+                    {
+                        return Ok(node);
+                    }
                 }
+                _ => {}
             }
-            _ => {}
-        }
 
-        let mut lhs = ConditionalExpression::new(self.allow_in, self.allow_yield, self.allow_await)
-            .parse(cursor)?;
-        // let mut lhs = self.read_block()?;
+            let mut lhs =
+                ConditionalExpression::new(self.allow_in, self.allow_yield, self.allow_await)
+                    .parse(cursor)?;
+            // let mut lhs = self.read_block()?;
 
-        if let Some(tok) = cursor.next() {
-            match tok.kind {
-                TokenKind::Punctuator(Punctuator::Assign) => {
-                    lhs = Node::assign(lhs, self.parse(cursor)?)
-                }
-                TokenKind::Punctuator(p) if p.as_binop().is_some() => {
-                    let expr = self.parse(cursor)?;
-                    let binop = p.as_binop().expect("binop disappeared");
-                    lhs = Node::bin_op(binop, lhs, expr);
-                }
-                _ => {
-                    cursor.back();
+            if let Some(tok) = cursor.next() {
+                match tok.kind {
+                    TokenKind::Punctuator(Punctuator::Assign) => {
+                        lhs = Node::assign(lhs, self.parse(cursor)?)
+                    }
+                    TokenKind::Punctuator(p) if p.as_binop().is_some() => {
+                        let expr = self.parse(cursor)?;
+                        let binop = p.as_binop().expect("binop disappeared");
+                        lhs = Node::bin_op(binop, lhs, expr);
+                    }
+                    _ => {
+                        cursor.back();
+                    }
                 }
             }
-        }
 
-        Ok(lhs)
+            Ok(lhs)
+        })
     }
 }
@@ -66,10 +66,12 @@ impl TokenParser for CallExpression {
         while let Some(tok) = cursor.peek(0) {
             match tok.kind {
                 TokenKind::Punctuator(Punctuator::OpenParen) => {
+                    cursor.deepen()?;
                     let args = Arguments::new(self.allow_yield, self.allow_await).parse(cursor)?;
                     lhs = Node::call(lhs, args);
                 }
                 TokenKind::Punctuator(Punctuator::Dot) => {
+                    cursor.deepen()?;
                     let _ = cursor.next().ok_or(ParseError::AbruptEnd)?; // We move the cursor.
                     match &cursor.next().ok_or(ParseError::AbruptEnd)?.kind {
                         TokenKind::Identifier(name) => {
@@ -88,6 +90,7 @@ impl TokenParser for CallExpression {
                     }
                 }
                 TokenKind::Punctuator(Punctuator::OpenBracket) => {
+                    cursor.deepen()?;
                     let _ = cursor.next().ok_or(ParseError::AbruptEnd)?; // We move the cursor.
                     let idx =
                         Expression::new(true, self.allow_yield, self.allow_await).parse(cursor)?;
@@ -48,7 +48,7 @@ impl TokenParser for MemberExpression {
             == TokenKind::Keyword(Keyword::New)
         {
             let _ = cursor.next().expect("keyword disappeared");
-            let lhs = self.parse(cursor)?;
+            let lhs = cursor.nested(|cursor| self.parse(cursor))?;
             let args = Arguments::new(self.allow_yield, self.allow_await).parse(cursor)?;
             let call_node = Node::call(lhs, args);
 
@@ -59,6 +59,7 @@ impl TokenParser for MemberExpression {
         while let Some(tok) = cursor.peek(0) {
             match &tok.kind {
                 TokenKind::Punctuator(Punctuator::Dot) => {
+                    cursor.deepen()?;
                     let _ = cursor.next().ok_or(ParseError::AbruptEnd)?; // We move the cursor forward.
                     match &cursor.next().ok_or(ParseError::AbruptEnd)?.kind {
                         TokenKind::Identifier(name) => lhs = Node::get_const_field(lhs, name),
@@ -73,6 +74,7 @@ impl TokenParser for MemberExpression {
                     }
                 }
                 TokenKind::Punctuator(Punctuator::OpenBracket) => {
+                    cursor.deepen()?;
                     let _ = cursor.next().ok_or(ParseError::AbruptEnd)?; // We move the cursor forward.
                     let idx =
                         Expression::new(true, self.allow_yield, self.allow_await).parse(cursor)?;
@@ -49,13 +49,16 @@ impl TokenParser for LeftHandSideExpression {
     type Output = Node;
 
     fn parse(self, cursor: &mut Cursor<'_>) -> ParseResult {
-        // TODO: Implement NewExpression: new MemberExpression
-        let lhs = MemberExpression::new(self.allow_yield, self.allow_await).parse(cursor)?;
-        match cursor.peek(0) {
-            Some(ref tok) if tok.kind == TokenKind::Punctuator(Punctuator::OpenParen) => {
-                CallExpression::new(self.allow_yield, self.allow_await, lhs).parse(cursor)
+        // The member and call expressions count every property access, call and `new` they nest
+        cursor.chained(|cursor| {
+            // TODO: Implement NewExpression: new MemberExpression
+            let lhs = MemberExpression::new(self.allow_yield, self.allow_await).parse(cursor)?;
+            match cursor.peek(0) {
+                Some(ref tok) if tok.kind == TokenKind::Punctuator(Punctuator::OpenParen) => {
+                    CallExpression::new(self.allow_yield, self.allow_await, lhs).parse(cursor)
+                }
+                _ => Ok(lhs), // TODO: is this correct?
             }
-            _ => Ok(lhs), // TODO: is this correct?
-        }
+        })
     }
 }
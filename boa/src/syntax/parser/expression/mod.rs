@@ -48,29 +48,42 @@ macro_rules! expression { ($name:ident, $lower:ident, [$( $op:path ),*], [$( $lo
         type Output = Node;
 
         fn parse(self, cursor: &mut Cursor<'_>) -> ParseResult {
-            let mut lhs = $lower::new($( self.$low_param ),*).parse(cursor)?;
-            while let Some(tok) = cursor.peek(0) {
-                match tok.kind {
-                    TokenKind::Punctuator(op) if $( op == $op )||* => {
-                        let _ = cursor.next().expect("token disappeared");
-                        lhs = Node::bin_op(
-                            op.as_binop().expect("Could not get binary operation."),
-                            lhs,
-                            $lower::new($( self.$low_param ),*).parse(cursor)?
-                        )
+            cursor.chained(|cursor| {
+                let mut lhs = $lower::new($( self.$low_param ),*).parse(cursor)?;
+                // The first operator builds the node of the expression itself, every other one
+                // nests the operations before it one level deeper
+                let mut operators = 0;
+                while let Some(tok) = cursor.peek(0) {
+                    match tok.kind {
+                        TokenKind::Punctuator(op) if $( op == $op )||* => {
+                            let _ = cursor.next().expect("token disappeared");
+                            if operators > 0 {
+                                cursor.deepen()?;
+                            }
+                            operators += 1;
+                            lhs = Node::bin_op(
+                                op.as_binop().expect("Could not get binary operation."),
+                                lhs,
+                                $lower::new($( self.$low_param ),*).parse(cursor)?
+                            )
+                        }
+                        TokenKind::Keyword(op) if $( op == $op )||* => {
+                            let _ = cursor.next().expect("token disappeared");
+                            if operators > 0 {
+                                cursor.deepen()?;
+                            }
+                            operators += 1;
+                            lhs = Node::bin_op(
+                                op.as_binop().expect("Could not get binary operation."),
+                                lhs,
+                                $lower::new($( self.$low_param ),*).parse(cursor)?
+                            )
+                        }
+                        _ => break
                     }
-                    TokenKind::Keyword(op) if $( op == $op )||* => {
-                        let _ = cursor.next().expect("token disappeared");
-                        lhs = Node::bin_op(
-                            op.as_binop().expect("Could not get binary operation."),
-                            lhs,
-                            $lower::new($( self.$low_param ),*).parse(cursor)?
-                        )
-                    }
-                    _ => break
                 }
-            }
-            Ok(lhs)
+                Ok(lhs)
+            })
         }
     }
 } }
@@ -385,7 +398,8 @@ expression!(
         Punctuator::GreaterThan,
         Punctuator::LessThanOrEq,
         Punctuator::GreaterThanOrEq,
-        Keyword::In
+        Keyword::In,
+        Keyword::InstanceOf
     ],
     [allow_yield, allow_await]
 );
@@ -31,6 +31,8 @@ impl TokenParser for FunctionExpression {
     type Output = Node;
 
     fn parse(self, cursor: &mut Cursor<'_>) -> ParseResult {
+        // The `function` keyword was read by the primary expression parser
+        let start = cursor.pos().saturating_sub(1);
         let name = BindingIdentifier::new(false, false).try_parse(cursor);
 
         cursor.expect(Punctuator::OpenParen, "function expression")?;
@@ -46,6 +48,7 @@ impl TokenParser for FunctionExpression {
 
         cursor.expect(Punctuator::CloseBlock, "function expression")?;
 
-        Ok(Node::function_expr::<_, String, _, _>(name, params, body))
+        Ok(Node::function_expr::<_, String, _, _>(name, params, body)
+            .with_source_text(cursor.source_text(start)))
     }
 }
@@ -10,16 +10,19 @@
 #[cfg(test)]
 mod tests;
 
-use crate::syntax::{
-    ast::{
-        node::{self, MethodDefinitionKind, Node},
-        punc::Punctuator,
-        token::{Token, TokenKind},
-    },
-    parser::{
-        expression::AssignmentExpression,
-        function::{FormalParameters, FunctionBody},
-        AllowAwait, AllowIn, AllowYield, Cursor, ParseError, ParseResult, TokenParser,
+use crate::{
+    builtins::value::JsString,
+    syntax::{
+        ast::{
+            node::{self, MethodDefinitionKind, Node},
+            punc::Punctuator,
+            token::{Token, TokenKind},
+        },
+        parser::{
+            expression::AssignmentExpression,
+            function::{FormalParameters, FunctionBody},
+            AllowAwait, AllowIn, AllowYield, Cursor, ParseError, ParseResult, TokenParser,
+        },
     },
 };
 
@@ -122,14 +125,18 @@ impl TokenParser for PropertyDefinition {
             return Ok(node::PropertyDefinition::SpreadObject(node));
         }
 
-        let prop_name = cursor
-            .next()
-            .map(Token::to_string)
-            .ok_or(ParseError::AbruptEnd)?;
+        let start = cursor.pos();
+        let token = cursor.next().ok_or(ParseError::AbruptEnd)?;
+        let prop_name = token.to_string();
         if cursor.next_if(Punctuator::Colon).is_some() {
             let val = AssignmentExpression::new(true, self.allow_yield, self.allow_await)
                 .parse(cursor)?;
-            return Ok(node::PropertyDefinition::Property(prop_name, val));
+            // String literals can hold lone surrogates, which the name as a `String` would lose
+            let key = match token.kind {
+                TokenKind::StringLiteral(ref key) => key.clone(),
+                _ => JsString::from(prop_name),
+            };
+            return Ok(node::PropertyDefinition::Property(key, val));
         }
 
         if cursor
@@ -137,7 +144,7 @@ impl TokenParser for PropertyDefinition {
             .is_some()
             || ["get", "set"].contains(&prop_name.as_str())
         {
-            return MethodDefinition::new(self.allow_yield, self.allow_await, prop_name)
+            return MethodDefinition::new(self.allow_yield, self.allow_await, prop_name, start)
                 .parse(cursor);
         }
 
@@ -163,11 +170,13 @@ struct MethodDefinition {
     allow_yield: AllowYield,
     allow_await: AllowAwait,
     identifier: String,
+    /// The position of the first token of the method, where its source text starts.
+    start: usize,
 }
 
 impl MethodDefinition {
     /// Creates a new `MethodDefinition` parser.
-    fn new<Y, A, I>(allow_yield: Y, allow_await: A, identifier: I) -> Self
+    fn new<Y, A, I>(allow_yield: Y, allow_await: A, identifier: I, start: usize) -> Self
     where
         Y: Into<AllowYield>,
         A: Into<AllowAwait>,
@@ -177,6 +186,7 @@ impl MethodDefinition {
             allow_yield: allow_yield.into(),
             allow_await: allow_await.into(),
             identifier: identifier.into(),
+            start,
         }
     }
 }
@@ -242,7 +252,8 @@ impl TokenParser for MethodDefinition {
         Ok(node::PropertyDefinition::MethodDefinition(
             methodkind,
             prop_name,
-            Node::function_expr::<_, String, _, _>(None, params, body),
+            Node::function_expr::<_, String, _, _>(None, params, body)
+                .with_source_text(cursor.source_text(self.start)),
         ))
     }
 }
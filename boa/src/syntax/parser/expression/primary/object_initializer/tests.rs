@@ -61,6 +61,7 @@ fn check_object_short_function_arguments() {
                 None,
                 Box::new([FormalParameter::new("test", None, false)]),
                 Box::new(Node::StatementList(Box::new([]))),
+                None,
             ),
         ),
     ];
@@ -89,6 +90,7 @@ fn check_object_getter() {
                 None,
                 Box::new([]),
                 Box::new(Node::statement_list(Vec::new())),
+                None,
             ),
         ),
     ];
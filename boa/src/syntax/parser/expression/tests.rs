@@ -334,4 +334,12 @@ fn check_relational_operations() {
             Node::Local(String::from("o")),
         )],
     );
+    check_parser(
+        "o instanceof C",
+        vec![Node::bin_op(
+            BinOp::Comp(CompOp::InstanceOf),
+            Node::Local(String::from("o")),
+            Node::Local(String::from("C")),
+        )],
+    );
 }
@@ -47,33 +47,35 @@ impl TokenParser for UnaryExpression {
     type Output = Node;
 
     fn parse(self, cursor: &mut Cursor<'_>) -> ParseResult {
-        let tok = cursor.next().ok_or(ParseError::AbruptEnd)?;
-        match tok.kind {
-            TokenKind::Keyword(Keyword::Delete) => {
-                Ok(Node::unary_op(UnaryOp::Delete, self.parse(cursor)?))
+        cursor.nested(|cursor| {
+            let tok = cursor.next().ok_or(ParseError::AbruptEnd)?;
+            match tok.kind {
+                TokenKind::Keyword(Keyword::Delete) => {
+                    Ok(Node::unary_op(UnaryOp::Delete, self.parse(cursor)?))
+                }
+                TokenKind::Keyword(Keyword::Void) => {
+                    Ok(Node::unary_op(UnaryOp::Void, self.parse(cursor)?))
+                }
+                TokenKind::Keyword(Keyword::TypeOf) => {
+                    Ok(Node::unary_op(UnaryOp::TypeOf, self.parse(cursor)?))
+                }
+                TokenKind::Punctuator(Punctuator::Add) => {
+                    Ok(Node::unary_op(UnaryOp::Plus, self.parse(cursor)?))
+                }
+                TokenKind::Punctuator(Punctuator::Sub) => {
+                    Ok(Node::unary_op(UnaryOp::Minus, self.parse(cursor)?))
+                }
+                TokenKind::Punctuator(Punctuator::Neg) => {
+                    Ok(Node::unary_op(UnaryOp::Tilde, self.parse(cursor)?))
+                }
+                TokenKind::Punctuator(Punctuator::Not) => {
+                    Ok(Node::unary_op(UnaryOp::Not, self.parse(cursor)?))
+                }
+                _ => {
+                    cursor.back();
+                    UpdateExpression::new(self.allow_yield, self.allow_await).parse(cursor)
+                }
             }
-            TokenKind::Keyword(Keyword::Void) => {
-                Ok(Node::unary_op(UnaryOp::Void, self.parse(cursor)?))
-            }
-            TokenKind::Keyword(Keyword::TypeOf) => {
-                Ok(Node::unary_op(UnaryOp::TypeOf, self.parse(cursor)?))
-            }
-            TokenKind::Punctuator(Punctuator::Add) => {
-                Ok(Node::unary_op(UnaryOp::Plus, self.parse(cursor)?))
-            }
-            TokenKind::Punctuator(Punctuator::Sub) => {
-                Ok(Node::unary_op(UnaryOp::Minus, self.parse(cursor)?))
-            }
-            TokenKind::Punctuator(Punctuator::Neg) => {
-                Ok(Node::unary_op(UnaryOp::Tilde, self.parse(cursor)?))
-            }
-            TokenKind::Punctuator(Punctuator::Not) => {
-                Ok(Node::unary_op(UnaryOp::Not, self.parse(cursor)?))
-            }
-            _ => {
-                cursor.back();
-                UpdateExpression::new(self.allow_yield, self.allow_await).parse(cursor)
-            }
-        }
+        })
     }
 }
@@ -102,6 +102,26 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Create a new parser, using `tokens` read from `source` as input
+    ///
+    /// The functions it parses keep their source text, which `Function.prototype.toString`
+    /// returns.
+    pub fn with_source(tokens: &'a [Token], source: &'a str) -> Self {
+        Self {
+            cursor: Cursor::with_source(tokens, source),
+        }
+    }
+
+    /// Makes the parser put a `Node::Location` before every statement of a statement list, which
+    /// the debugger uses to stop at lines of the script.
+    ///
+    /// Function declarations get no location, the statement lists of the tree are otherwise
+    /// unchanged.
+    pub fn with_locations(mut self) -> Self {
+        self.cursor.record_locations();
+        self
+    }
+
     /// Parse all expressions in the token array
     pub fn parse_all(&mut self) -> ParseResult {
         Script.parse(&mut self.cursor).map(Node::statement_list)
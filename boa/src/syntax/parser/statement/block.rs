@@ -137,6 +137,7 @@ impl TokenParser for StatementList {
                 _ => {}
             }
 
+            items.extend(cursor.statement_location());
             let item =
                 StatementListItem::new(self.allow_yield, self.allow_await, self.allow_return)
                     .parse(cursor)?;
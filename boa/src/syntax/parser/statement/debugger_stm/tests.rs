@@ -0,0 +1,30 @@
+use crate::syntax::{
+    ast::node::Node,
+    parser::tests::{check_invalid, check_parser},
+};
+
+#[test]
+fn check_debugger() {
+    check_parser("debugger;", vec![Node::debugger()]);
+}
+
+#[test]
+fn check_debugger_semicolon_insertion() {
+    check_parser(
+        "if (true) { debugger }
+        debugger",
+        vec![
+            Node::if_node::<_, _, Node, _>(
+                Node::const_node(true),
+                Node::block(vec![Node::debugger()]),
+                None,
+            ),
+            Node::debugger(),
+        ],
+    );
+}
+
+#[test]
+fn check_invalid_debugger() {
+    check_invalid("debugger 1;");
+}
@@ -87,6 +87,7 @@ impl TokenParser for FunctionDeclaration {
     type Output = Node;
 
     fn parse(self, cursor: &mut Cursor<'_>) -> ParseResult {
+        let start = cursor.pos();
         cursor.expect(Keyword::Function, "function declaration")?;
 
         let name = BindingIdentifier::new(self.allow_yield, self.allow_await).parse(cursor)?;
@@ -104,6 +105,6 @@ impl TokenParser for FunctionDeclaration {
 
         cursor.expect(Punctuator::CloseBlock, "function declaration")?;
 
-        Ok(Node::function_decl(name, params, body))
+        Ok(Node::function_decl(name, params, body).with_source_text(cursor.source_text(start)))
     }
 }
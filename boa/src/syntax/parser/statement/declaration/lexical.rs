@@ -185,8 +185,14 @@ impl TokenParser for LexicalBinding {
 
     fn parse(self, cursor: &mut Cursor<'_>) -> Result<(String, Option<Node>), ParseError> {
         let ident = BindingIdentifier::new(self.allow_yield, self.allow_await).parse(cursor)?;
-        let initializer =
-            Initializer::new(self.allow_in, self.allow_yield, self.allow_await).try_parse(cursor);
+        // The errors of an initializer are reported, such as the end of the input after the `=`
+        let initializer = match cursor.peek(0) {
+            Some(tk) if tk.kind == TokenKind::Punctuator(Punctuator::Assign) => Some(
+                Initializer::new(self.allow_in, self.allow_yield, self.allow_await)
+                    .parse(cursor)?,
+            ),
+            _ => None,
+        };
 
         Ok((ident, initializer))
     }
@@ -10,6 +10,7 @@
 mod block;
 mod break_stm;
 mod continue_stm;
+mod debugger_stm;
 mod declaration;
 mod if_stm;
 mod iteration;
@@ -23,6 +24,7 @@ use self::{
     block::BlockStatement,
     break_stm::BreakStatement,
     continue_stm::ContinueStatement,
+    debugger_stm::DebuggerStatement,
     declaration::Declaration,
     if_stm::IfStatement,
     iteration::{DoWhileStatement, ForStatement, WhileStatement},
@@ -90,63 +92,66 @@ impl TokenParser for Statement {
     type Output = Node;
 
     fn parse(self, cursor: &mut Cursor<'_>) -> ParseResult {
-        // TODO: add BreakableStatement and divide Whiles, fors and so on to another place.
-        let tok = cursor.peek(0).ok_or(ParseError::AbruptEnd)?;
-
-        match tok.kind {
-            TokenKind::Keyword(Keyword::If) => {
-                IfStatement::new(self.allow_yield, self.allow_await, self.allow_return)
-                    .parse(cursor)
-            }
-            TokenKind::Keyword(Keyword::Var) => {
-                VariableStatement::new(self.allow_yield, self.allow_await).parse(cursor)
-            }
-            TokenKind::Keyword(Keyword::While) => {
-                WhileStatement::new(self.allow_yield, self.allow_await, self.allow_return)
-                    .parse(cursor)
-            }
-            TokenKind::Keyword(Keyword::Do) => {
-                DoWhileStatement::new(self.allow_yield, self.allow_await, self.allow_return)
-                    .parse(cursor)
-            }
-            TokenKind::Keyword(Keyword::For) => {
-                ForStatement::new(self.allow_yield, self.allow_await, self.allow_return)
-                    .parse(cursor)
-            }
-            TokenKind::Keyword(Keyword::Return) => {
-                if self.allow_return.0 {
-                    ReturnStatement::new(self.allow_yield, self.allow_await).parse(cursor)
-                } else {
-                    Err(ParseError::Unexpected(tok.clone(), Some("statement")))
+        cursor.nested(|cursor| {
+            // TODO: add BreakableStatement and divide Whiles, fors and so on to another place.
+            let tok = cursor.peek(0).ok_or(ParseError::AbruptEnd)?;
+
+            match tok.kind {
+                TokenKind::Keyword(Keyword::If) => {
+                    IfStatement::new(self.allow_yield, self.allow_await, self.allow_return)
+                        .parse(cursor)
                 }
+                TokenKind::Keyword(Keyword::Var) => {
+                    VariableStatement::new(self.allow_yield, self.allow_await).parse(cursor)
+                }
+                TokenKind::Keyword(Keyword::While) => {
+                    WhileStatement::new(self.allow_yield, self.allow_await, self.allow_return)
+                        .parse(cursor)
+                }
+                TokenKind::Keyword(Keyword::Do) => {
+                    DoWhileStatement::new(self.allow_yield, self.allow_await, self.allow_return)
+                        .parse(cursor)
+                }
+                TokenKind::Keyword(Keyword::For) => {
+                    ForStatement::new(self.allow_yield, self.allow_await, self.allow_return)
+                        .parse(cursor)
+                }
+                TokenKind::Keyword(Keyword::Return) => {
+                    if self.allow_return.0 {
+                        ReturnStatement::new(self.allow_yield, self.allow_await).parse(cursor)
+                    } else {
+                        Err(ParseError::Unexpected(tok.clone(), Some("statement")))
+                    }
+                }
+                TokenKind::Keyword(Keyword::Break) => {
+                    BreakStatement::new(self.allow_yield, self.allow_await).parse(cursor)
+                }
+                TokenKind::Keyword(Keyword::Continue) => {
+                    ContinueStatement::new(self.allow_yield, self.allow_await).parse(cursor)
+                }
+                TokenKind::Keyword(Keyword::Try) => {
+                    TryStatement::new(self.allow_yield, self.allow_await, self.allow_return)
+                        .parse(cursor)
+                }
+                TokenKind::Keyword(Keyword::Throw) => {
+                    ThrowStatement::new(self.allow_yield, self.allow_await).parse(cursor)
+                }
+                TokenKind::Keyword(Keyword::Debugger) => DebuggerStatement.parse(cursor),
+                TokenKind::Keyword(Keyword::Switch) => {
+                    SwitchStatement::new(self.allow_yield, self.allow_await, self.allow_return)
+                        .parse(cursor)
+                }
+                TokenKind::Punctuator(Punctuator::OpenBlock) => {
+                    BlockStatement::new(self.allow_yield, self.allow_await, self.allow_return)
+                        .parse(cursor)
+                }
+                // TODO: https://tc39.es/ecma262/#prod-LabelledStatement
+                // TokenKind::Punctuator(Punctuator::Semicolon) => {
+                //     return Ok(Node::new(NodeBase::Nope, tok.pos))
+                // }
+                _ => ExpressionStatement::new(self.allow_yield, self.allow_await).parse(cursor),
             }
-            TokenKind::Keyword(Keyword::Break) => {
-                BreakStatement::new(self.allow_yield, self.allow_await).parse(cursor)
-            }
-            TokenKind::Keyword(Keyword::Continue) => {
-                ContinueStatement::new(self.allow_yield, self.allow_await).parse(cursor)
-            }
-            TokenKind::Keyword(Keyword::Try) => {
-                TryStatement::new(self.allow_yield, self.allow_await, self.allow_return)
-                    .parse(cursor)
-            }
-            TokenKind::Keyword(Keyword::Throw) => {
-                ThrowStatement::new(self.allow_yield, self.allow_await).parse(cursor)
-            }
-            TokenKind::Keyword(Keyword::Switch) => {
-                SwitchStatement::new(self.allow_yield, self.allow_await, self.allow_return)
-                    .parse(cursor)
-            }
-            TokenKind::Punctuator(Punctuator::OpenBlock) => {
-                BlockStatement::new(self.allow_yield, self.allow_await, self.allow_return)
-                    .parse(cursor)
-            }
-            // TODO: https://tc39.es/ecma262/#prod-LabelledStatement
-            // TokenKind::Punctuator(Punctuator::Semicolon) => {
-            //     return Ok(Node::new(NodeBase::Nope, tok.pos))
-            // }
-            _ => ExpressionStatement::new(self.allow_yield, self.allow_await).parse(cursor),
-        }
+        })
     }
 }
 
@@ -213,6 +218,7 @@ impl TokenParser for StatementList {
                 _ => {}
             }
 
+            items.extend(cursor.statement_location());
             let item =
                 StatementListItem::new(self.allow_yield, self.allow_await, self.allow_return)
                     .parse(cursor)?;
@@ -268,9 +274,9 @@ impl TokenParser for StatementListItem {
         match tok.kind {
             TokenKind::Keyword(Keyword::Function)
             | TokenKind::Keyword(Keyword::Const)
-            | TokenKind::Keyword(Keyword::Let) => {
+            | TokenKind::Keyword(Keyword::Let) => cursor.nested(|cursor| {
                 Declaration::new(self.allow_yield, self.allow_await).parse(cursor)
-            }
+            }),
             _ => {
                 Statement::new(self.allow_yield, self.allow_await, self.allow_return).parse(cursor)
             }
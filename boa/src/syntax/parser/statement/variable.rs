@@ -158,8 +158,14 @@ impl TokenParser for VariableDeclaration {
 
         let name = BindingIdentifier::new(self.allow_yield, self.allow_await).parse(cursor)?;
 
-        let ident =
-            Initializer::new(self.allow_in, self.allow_yield, self.allow_await).try_parse(cursor);
+        // The errors of an initializer are reported, such as the end of the input after the `=`
+        let ident = match cursor.peek(0) {
+            Some(tk) if tk.kind == TokenKind::Punctuator(Punctuator::Assign) => Some(
+                Initializer::new(self.allow_in, self.allow_yield, self.allow_await)
+                    .parse(cursor)?,
+            ),
+            _ => None,
+        };
 
         Ok((name, ident))
     }
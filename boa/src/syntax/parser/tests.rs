@@ -51,3 +51,59 @@ fn assign_operator_precedence() {
         )],
     );
 }
+
+/// Deeply nested code gives an error instead of overflowing the stack.
+#[test]
+fn check_nesting_depth_limit() {
+    let nested =
+        |open: &str, close: &str, depth| format!("{}1{}", open.repeat(depth), close.repeat(depth));
+    let parse = |js: &str| {
+        let mut lexer = Lexer::new(js);
+        lexer.lex().expect("failed to lex");
+        Parser::new(&lexer.tokens).parse_all()
+    };
+
+    for js in &[
+        nested("(", ")", 100_000),
+        nested("[", "]", 100_000),
+        nested("!", "", 100_000),
+        nested("{", "}", 100_000),
+        nested("if (1) ", "", 100_000),
+        nested("function f() {", "}", 100_000),
+    ] {
+        let error = parse(js).expect_err("parsed too deeply nested code");
+        assert!(error
+            .to_string()
+            .starts_with("Maximum nesting depth exceeded"));
+    }
+    assert!(parse(&nested("(", ")", 500)).is_ok());
+}
+
+/// Statements of statement lists get their location when asked for, function declarations don't.
+#[test]
+fn check_locations() {
+    use crate::syntax::ast::pos::Position;
+
+    let mut lexer = Lexer::new("function f() {\n  return 1;\n}\nf();  { a }");
+    lexer.lex().expect("failed to lex");
+    assert_eq!(
+        Parser::new(&lexer.tokens)
+            .with_locations()
+            .parse_all()
+            .expect("failed to parse"),
+        Node::statement_list(vec![
+            Node::function_decl(
+                "f",
+                Vec::new(),
+                Node::statement_list(vec![
+                    Node::location(Position::new(2, 3)),
+                    Node::return_node::<_, Node>(Node::const_node(1)),
+                ]),
+            ),
+            Node::location(Position::new(4, 1)),
+            Node::call(Node::local("f"), Vec::new()),
+            Node::location(Position::new(4, 7)),
+            Node::block(vec![Node::location(Position::new(4, 9)), Node::local("a"),]),
+        ])
+    );
+}
@@ -0,0 +1,701 @@
+//! A line editor for the REPL, for terminals which understand ANSI escape sequences.
+//!
+//! While a line is read the terminal is in raw mode, the editor handles the keys itself: the
+//! arrows move in the line and through the history, `Tab` completes the word before the cursor and
+//! the usual Emacs shortcuts (`Ctrl-A`, `Ctrl-E`, `Ctrl-K`, `Ctrl-U`, `Ctrl-W`, `Ctrl-L`) work.
+//! The line is redrawn with the colours of a `Helper` after every key.
+//!
+//! When the standard input isn't a terminal, lines are read as they come, without any editing.
+//!
+//! The editor is written here rather than taken from a line editing crate such as `rustyline`:
+//! the REPL only needs the keys above, and handling them takes a few hundred lines with `libc` as
+//! the only dependency, where those crates bring their own terminal and Unicode width handling.
+
+use std::{
+    fmt::{self, Display, Write as _},
+    fs::{self, OpenOptions},
+    io::{self, BufRead, Read, Write},
+    path::PathBuf,
+};
+
+/// The maximum number of lines kept in the history.
+const HISTORY_SIZE: usize = 1000;
+
+/// The language specific parts of the editor.
+pub(crate) trait Helper {
+    /// Returns the line with ANSI colour codes added, the visible characters must be the same.
+    fn highlight(&self, line: &str) -> String;
+
+    /// Returns the byte position where the word before the cursor at byte `pos` starts, and the
+    /// candidates which can replace it.
+    fn complete(&self, line: &str, pos: usize) -> (usize, Vec<String>);
+}
+
+/// The outcome of reading a line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ReadLine {
+    /// The user entered a line.
+    Line(String),
+    /// The user discarded the input with `Ctrl-C`.
+    Interrupted,
+    /// The input is over, `Ctrl-D` was pressed on an empty line.
+    Eof,
+}
+
+/// Reads lines from the standard input, with line editing when it is a terminal.
+#[derive(Debug)]
+pub(crate) struct Editor {
+    history: History,
+    terminal: bool,
+}
+
+impl Editor {
+    /// Creates an editor with the history kept in the given file.
+    pub(crate) fn new(history_file: Option<PathBuf>) -> Self {
+        Self {
+            history: History::load(history_file),
+            terminal: raw::is_terminal(),
+        }
+    }
+
+    /// Reads a line, showing the prompt on terminals.
+    pub(crate) fn read_line(&mut self, prompt: &str, helper: &dyn Helper) -> io::Result<ReadLine> {
+        let result = if self.terminal {
+            let _raw = raw::RawMode::enable()?;
+            self.edit(prompt, helper)?
+        } else {
+            let mut line = String::new();
+            if io::stdin().lock().read_line(&mut line)? == 0 {
+                ReadLine::Eof
+            } else {
+                let len = line.trim_end_matches(&['\n', '\r'][..]).len();
+                line.truncate(len);
+                ReadLine::Line(line)
+            }
+        };
+        if let ReadLine::Line(ref line) = result {
+            self.history.add(line);
+        }
+        Ok(result)
+    }
+
+    /// Runs the editing keys of the terminal until the line is entered.
+    fn edit(&mut self, prompt: &str, helper: &dyn Helper) -> io::Result<ReadLine> {
+        let stdin = io::stdin();
+        let mut keys = Keys(stdin.lock().bytes());
+        self.edit_keys(&mut keys, &mut io::stdout(), prompt, helper)
+    }
+
+    /// Runs the keys until the line is entered, the line is drawn on `out`.
+    fn edit_keys<R: Read>(
+        &mut self,
+        keys: &mut Keys<R>,
+        out: &mut impl Write,
+        prompt: &str,
+        helper: &dyn Helper,
+    ) -> io::Result<ReadLine> {
+        let mut line = LineBuffer::default();
+        // The position in the history while browsing it, with the line being written
+        let mut browsing: Option<(usize, String)> = None;
+
+        refresh(out, prompt, &line, helper)?;
+        loop {
+            let key = match keys.next_key()? {
+                Some(key) => key,
+                None => return Ok(ReadLine::Eof),
+            };
+            match key {
+                Key::Char(c) => line.insert(c),
+                Key::Enter => {
+                    write!(out, "\r\n")?;
+                    out.flush()?;
+                    return Ok(ReadLine::Line(line.to_string()));
+                }
+                Key::Interrupt => {
+                    write!(out, "^C\r\n")?;
+                    out.flush()?;
+                    return Ok(ReadLine::Interrupted);
+                }
+                Key::EndOfInput if line.is_empty() => {
+                    write!(out, "\r\n")?;
+                    out.flush()?;
+                    return Ok(ReadLine::Eof);
+                }
+                Key::EndOfInput | Key::Delete => line.delete(),
+                Key::Backspace => line.backspace(),
+                Key::Left => line.move_left(),
+                Key::Right => line.move_right(),
+                Key::Home => line.move_home(),
+                Key::End => line.move_end(),
+                Key::KillEnd => line.kill_end(),
+                Key::KillStart => line.kill_start(),
+                Key::DeleteWord => line.delete_word(),
+                Key::Clear => write!(out, "\x1b[H\x1b[2J")?,
+                Key::Up | Key::Down => {
+                    let entries = &self.history.entries;
+                    let current = browsing.as_ref().map_or(entries.len(), |(index, _)| *index);
+                    let next = match key {
+                        Key::Up => current.checked_sub(1),
+                        _ if current < entries.len() => Some(current + 1),
+                        _ => None,
+                    };
+                    if let Some(next) = next {
+                        let draft = match browsing.take() {
+                            Some((_, draft)) => draft,
+                            None => line.to_string(),
+                        };
+                        match entries.get(next) {
+                            Some(entry) => {
+                                line.set(entry);
+                                browsing = Some((next, draft));
+                            }
+                            None => line.set(&draft),
+                        }
+                    }
+                }
+                Key::Tab => complete(out, &mut line, helper)?,
+                Key::Ignored => {}
+            }
+            refresh(out, prompt, &line, helper)?;
+        }
+    }
+}
+
+/// Redraws the prompt and the line, and puts the cursor back in place.
+fn refresh(
+    out: &mut impl Write,
+    prompt: &str,
+    line: &LineBuffer,
+    helper: &dyn Helper,
+) -> io::Result<()> {
+    let text = line.to_string();
+    write!(out, "\r{}{}\x1b[K\r", prompt, helper.highlight(&text))?;
+    let column = prompt.chars().count() + line.cursor;
+    if column > 0 {
+        write!(out, "\x1b[{}C", column)?;
+    }
+    out.flush()
+}
+
+/// Completes the word before the cursor, with the longest prefix the candidates share. When it
+/// can't be extended the candidates are listed below the line.
+fn complete(out: &mut impl Write, line: &mut LineBuffer, helper: &dyn Helper) -> io::Result<()> {
+    let text = line.to_string();
+    let pos = line.byte_cursor();
+    let (start, candidates) = helper.complete(&text, pos);
+    let word = &text[start..pos];
+    let prefix = match candidates.split_first() {
+        Some((first, rest)) => rest.iter().fold(first.as_str(), |prefix, candidate| {
+            let len = prefix
+                .char_indices()
+                .zip(candidate.chars())
+                .find(|((_, a), b)| a != b)
+                .map_or_else(|| prefix.len().min(candidate.len()), |((i, _), _)| i);
+            &prefix[..len]
+        }),
+        None => return write!(out, "\x07"),
+    };
+    if prefix.len() > word.len() || candidates.len() == 1 {
+        line.replace_before_cursor(word.chars().count(), prefix);
+    } else {
+        write!(out, "\r\n{}\r\n", candidates.join("  "))?;
+    }
+    Ok(())
+}
+
+/// The keys the editor understands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Key {
+    Char(char),
+    Enter,
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Home,
+    End,
+    Up,
+    Down,
+    Tab,
+    Interrupt,
+    EndOfInput,
+    KillEnd,
+    KillStart,
+    DeleteWord,
+    Clear,
+    Ignored,
+}
+
+/// Decodes the bytes a terminal in raw mode sends into keys.
+struct Keys<R>(io::Bytes<R>);
+
+impl<R: Read> Keys<R> {
+    fn byte(&mut self) -> io::Result<Option<u8>> {
+        self.0.next().transpose()
+    }
+
+    fn next_key(&mut self) -> io::Result<Option<Key>> {
+        let byte = match self.byte()? {
+            Some(byte) => byte,
+            None => return Ok(None),
+        };
+        let key = match byte {
+            0x01 => Key::Home,
+            0x02 => Key::Left,
+            0x03 => Key::Interrupt,
+            0x04 => Key::EndOfInput,
+            0x05 => Key::End,
+            0x06 => Key::Right,
+            0x08 | 0x7f => Key::Backspace,
+            b'\t' => Key::Tab,
+            0x0b => Key::KillEnd,
+            0x0c => Key::Clear,
+            b'\r' | b'\n' => Key::Enter,
+            0x0e => Key::Down,
+            0x10 => Key::Up,
+            0x15 => Key::KillStart,
+            0x17 => Key::DeleteWord,
+            0x1b => self.escape()?,
+            byte if byte < 0x20 => Key::Ignored,
+            byte => self.utf8(byte)?,
+        };
+        Ok(Some(key))
+    }
+
+    /// Decodes the escape sequences of the arrows, `Home`, `End` and `Delete`.
+    fn escape(&mut self) -> io::Result<Key> {
+        match self.byte()? {
+            Some(b'[') | Some(b'O') => {}
+            _ => return Ok(Key::Ignored),
+        }
+        let mut parameter = 0;
+        loop {
+            let byte = match self.byte()? {
+                Some(byte) => byte,
+                None => return Ok(Key::Ignored),
+            };
+            match byte {
+                b'0'..=b'9' => parameter = parameter * 10 + u32::from(byte - b'0'),
+                b'A' => return Ok(Key::Up),
+                b'B' => return Ok(Key::Down),
+                b'C' => return Ok(Key::Right),
+                b'D' => return Ok(Key::Left),
+                b'H' => return Ok(Key::Home),
+                b'F' => return Ok(Key::End),
+                b'~' => {
+                    return Ok(match parameter {
+                        1 | 7 => Key::Home,
+                        3 => Key::Delete,
+                        4 | 8 => Key::End,
+                        _ => Key::Ignored,
+                    })
+                }
+                0x40..=0x7e => return Ok(Key::Ignored),
+                _ => {}
+            }
+        }
+    }
+
+    /// Decodes a character from its first byte and the continuation bytes following it.
+    fn utf8(&mut self, first: u8) -> io::Result<Key> {
+        let len = match first {
+            0xc0..=0xdf => 2,
+            0xe0..=0xef => 3,
+            0xf0..=0xf7 => 4,
+            _ => 1,
+        };
+        let mut bytes = vec![first];
+        for _ in 1..len {
+            match self.byte()? {
+                Some(byte) => bytes.push(byte),
+                None => break,
+            }
+        }
+        Ok(std::str::from_utf8(&bytes)
+            .ok()
+            .and_then(|s| s.chars().next())
+            .map_or(Key::Ignored, Key::Char))
+    }
+}
+
+/// The line being edited and the position of the cursor in it, in characters.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct LineBuffer {
+    chars: Vec<char>,
+    cursor: usize,
+}
+
+impl LineBuffer {
+    fn is_empty(&self) -> bool {
+        self.chars.is_empty()
+    }
+
+    fn byte_cursor(&self) -> usize {
+        self.chars[..self.cursor].iter().map(|c| c.len_utf8()).sum()
+    }
+
+    fn set(&mut self, line: &str) {
+        self.chars = line.chars().collect();
+        self.cursor = self.chars.len();
+    }
+
+    fn insert(&mut self, c: char) {
+        self.chars.insert(self.cursor, c);
+        self.cursor += 1;
+    }
+
+    fn backspace(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            self.chars.remove(self.cursor);
+        }
+    }
+
+    fn delete(&mut self) {
+        if self.cursor < self.chars.len() {
+            self.chars.remove(self.cursor);
+        }
+    }
+
+    fn move_left(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    fn move_right(&mut self) {
+        self.cursor = (self.cursor + 1).min(self.chars.len());
+    }
+
+    fn move_home(&mut self) {
+        self.cursor = 0;
+    }
+
+    fn move_end(&mut self) {
+        self.cursor = self.chars.len();
+    }
+
+    fn kill_end(&mut self) {
+        self.chars.truncate(self.cursor);
+    }
+
+    fn kill_start(&mut self) {
+        self.chars.drain(..self.cursor);
+        self.cursor = 0;
+    }
+
+    /// Deletes the word before the cursor, with the spaces following it.
+    fn delete_word(&mut self) {
+        let mut start = self.cursor;
+        while start > 0 && self.chars[start - 1].is_whitespace() {
+            start -= 1;
+        }
+        while start > 0 && !self.chars[start - 1].is_whitespace() {
+            start -= 1;
+        }
+        self.chars.drain(start..self.cursor);
+        self.cursor = start;
+    }
+
+    /// Replaces the `len` characters before the cursor with the given text.
+    fn replace_before_cursor(&mut self, len: usize, text: &str) {
+        let start = self.cursor - len;
+        let _ = self.chars.splice(start..self.cursor, text.chars());
+        self.cursor = start + text.chars().count();
+    }
+}
+
+impl Display for LineBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.chars.iter().try_for_each(|c| f.write_char(*c))
+    }
+}
+
+/// The lines entered so far, kept in a file across sessions.
+#[derive(Debug, Default)]
+struct History {
+    entries: Vec<String>,
+    file: Option<PathBuf>,
+}
+
+impl History {
+    /// Loads the history from the file, keeping its last `HISTORY_SIZE` lines.
+    fn load(file: Option<PathBuf>) -> Self {
+        let mut entries: Vec<String> = file
+            .as_ref()
+            .and_then(|file| fs::read_to_string(file).ok())
+            .map(|text| text.lines().map(String::from).collect())
+            .unwrap_or_default();
+        if entries.len() > HISTORY_SIZE {
+            entries.drain(..entries.len() - HISTORY_SIZE);
+            if let Some(ref file) = file {
+                let mut text = entries.join("\n");
+                text.push('\n');
+                // The history is a convenience, failing to save it isn't an error
+                let _ = fs::write(file, text);
+            }
+        }
+        Self { entries, file }
+    }
+
+    /// Adds a line to the history, unless it is empty or repeats the last one.
+    fn add(&mut self, line: &str) {
+        if line.trim().is_empty() || self.entries.last().map(String::as_str) == Some(line) {
+            return;
+        }
+        self.entries.push(line.to_string());
+        if let Some(ref file) = self.file {
+            let _ = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(file)
+                .and_then(|mut file| writeln!(file, "{}", line));
+        }
+    }
+}
+
+#[cfg(unix)]
+mod raw {
+    use std::{io, mem};
+
+    /// Returns whether the standard input and output are a terminal.
+    pub(crate) fn is_terminal() -> bool {
+        unsafe { libc::isatty(libc::STDIN_FILENO) == 1 && libc::isatty(libc::STDOUT_FILENO) == 1 }
+    }
+
+    /// Keeps the terminal in raw mode, the previous mode is restored when it is dropped.
+    pub(crate) struct RawMode {
+        original: libc::termios,
+    }
+
+    impl RawMode {
+        pub(crate) fn enable() -> io::Result<Self> {
+            unsafe {
+                let mut original: libc::termios = mem::zeroed();
+                if libc::tcgetattr(libc::STDIN_FILENO, &mut original) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                let mut raw = original;
+                // The keys are read one by one without echo or signals, output is left alone so
+                // that `\n` still starts a new line
+                raw.c_iflag &=
+                    !(libc::BRKINT | libc::ICRNL | libc::INPCK | libc::ISTRIP | libc::IXON);
+                raw.c_lflag &= !(libc::ECHO | libc::ICANON | libc::IEXTEN | libc::ISIG);
+                raw.c_cflag |= libc::CS8;
+                raw.c_cc[libc::VMIN] = 1;
+                raw.c_cc[libc::VTIME] = 0;
+                if libc::tcsetattr(libc::STDIN_FILENO, libc::TCSAFLUSH, &raw) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                Ok(Self { original })
+            }
+        }
+    }
+
+    impl Drop for RawMode {
+        fn drop(&mut self) {
+            unsafe {
+                libc::tcsetattr(libc::STDIN_FILENO, libc::TCSAFLUSH, &self.original);
+            }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod raw {
+    use std::io;
+
+    /// Line editing needs a Unix terminal, lines are read as they come elsewhere.
+    pub(crate) fn is_terminal() -> bool {
+        false
+    }
+
+    pub(crate) struct RawMode;
+
+    impl RawMode {
+        pub(crate) fn enable() -> io::Result<Self> {
+            Ok(Self)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(input: &[u8]) -> Vec<Key> {
+        let mut keys = Keys(input.bytes());
+        let mut decoded = Vec::new();
+        while let Some(key) = keys.next_key().unwrap() {
+            decoded.push(key);
+        }
+        decoded
+    }
+
+    #[test]
+    fn decodes_keys() {
+        assert_eq!(
+            keys(b"a\x1b[A\x1b[D\x1bOH\x1b[3~\x7f\t\r\x03"),
+            vec![
+                Key::Char('a'),
+                Key::Up,
+                Key::Left,
+                Key::Home,
+                Key::Delete,
+                Key::Backspace,
+                Key::Tab,
+                Key::Enter,
+                Key::Interrupt
+            ]
+        );
+        assert_eq!(keys("é€".as_bytes()), vec![Key::Char('é'), Key::Char('€')]);
+    }
+
+    #[test]
+    fn edits_lines() {
+        let mut line = LineBuffer::default();
+        line.set("let x = 1;");
+        line.move_left();
+        line.backspace();
+        line.insert('2');
+        assert_eq!(line.to_string(), "let x = 2;");
+        line.delete_word();
+        assert_eq!(line.to_string(), "let x = ;");
+        line.move_home();
+        line.delete();
+        line.kill_end();
+        assert!(line.is_empty());
+
+        line.set("Math.ma");
+        line.replace_before_cursor(2, "max");
+        assert_eq!(line.to_string(), "Math.max");
+        assert_eq!(line.byte_cursor(), 8);
+    }
+
+    /// Completes the names which start with the word before the cursor.
+    struct Names(&'static [&'static str]);
+
+    impl Helper for Names {
+        fn highlight(&self, line: &str) -> String {
+            line.to_string()
+        }
+
+        fn complete(&self, line: &str, pos: usize) -> (usize, Vec<String>) {
+            let start = line[..pos]
+                .rfind(|c: char| !c.is_alphanumeric())
+                .map_or(0, |i| i + 1);
+            let word = &line[start..pos];
+            let candidates = self
+                .0
+                .iter()
+                .filter(|name| name.starts_with(word))
+                .map(|name| name.to_string())
+                .collect();
+            (start, candidates)
+        }
+    }
+
+    /// Reads a line from the keys, returning what was drawn with it.
+    fn edit(editor: &mut Editor, input: &[u8]) -> (ReadLine, String) {
+        let mut out = Vec::new();
+        let result = editor
+            .edit_keys(
+                &mut Keys(input.bytes()),
+                &mut out,
+                "> ",
+                &Names(&["Math", "max", "map"]),
+            )
+            .unwrap();
+        (result, String::from_utf8(out).unwrap())
+    }
+
+    fn editor() -> Editor {
+        Editor {
+            history: History::default(),
+            terminal: true,
+        }
+    }
+
+    fn line(input: &[u8]) -> ReadLine {
+        edit(&mut editor(), input).0
+    }
+
+    #[test]
+    fn handles_editing_keys() {
+        assert_eq!(line(b"1+2\r"), ReadLine::Line("1+2".to_string()));
+        // Left, Backspace and a character in the middle of the line
+        assert_eq!(line(b"1+2\x1b[D\x7f*\r"), ReadLine::Line("1*2".to_string()));
+        // Ctrl-A and Ctrl-E move to the ends of the line
+        assert_eq!(line(b"bc\x01a\x05d\r"), ReadLine::Line("abcd".to_string()));
+        // Ctrl-K and Ctrl-U kill the end and the start of the line
+        assert_eq!(
+            line(b"abcd\x1b[D\x1b[D\x0b\r"),
+            ReadLine::Line("ab".to_string())
+        );
+        assert_eq!(line(b"abcd\x1b[D\x15\r"), ReadLine::Line("d".to_string()));
+        // Ctrl-W deletes the word before the cursor
+        assert_eq!(
+            line(b"let x = 1\x17\r"),
+            ReadLine::Line("let x = ".to_string())
+        );
+        // Delete and Ctrl-D remove the character under the cursor
+        assert_eq!(
+            line(b"abc\x01\x1b[3~\x04\r"),
+            ReadLine::Line("c".to_string())
+        );
+    }
+
+    #[test]
+    fn ends_lines() {
+        assert_eq!(line(b"abc\x03"), ReadLine::Interrupted);
+        assert_eq!(line(b"\x04"), ReadLine::Eof);
+        assert_eq!(line(b"abc"), ReadLine::Eof);
+    }
+
+    #[test]
+    fn browses_the_history() {
+        let mut editor = editor();
+        editor.history.add("first");
+        editor.history.add("second");
+        assert_eq!(
+            edit(&mut editor, b"\x1b[A\r").0,
+            ReadLine::Line("second".to_string())
+        );
+        assert_eq!(
+            edit(&mut editor, b"\x1b[A\x1b[A\x1b[A\r").0,
+            ReadLine::Line("first".to_string())
+        );
+        // Going down past the newest entry brings back the line being written
+        assert_eq!(
+            edit(&mut editor, b"draft\x1b[A\x1b[A\x1b[B\x1b[B\r").0,
+            ReadLine::Line("draft".to_string())
+        );
+        // Ctrl-P and Ctrl-N browse too, and the entry can be edited
+        assert_eq!(
+            edit(&mut editor, b"\x10\x10\x0e!\r").0,
+            ReadLine::Line("second!".to_string())
+        );
+    }
+
+    #[test]
+    fn completes_words() {
+        assert_eq!(line(b"Ma\t.\r"), ReadLine::Line("Math.".to_string()));
+        assert_eq!(line(b"x = max\t\r"), ReadLine::Line("x = max".to_string()));
+        // The candidates are listed when they share no longer prefix
+        let (result, drawn) = edit(&mut editor(), b"ma\t\r");
+        assert_eq!(result, ReadLine::Line("ma".to_string()));
+        assert!(drawn.contains("\r\nmax  map\r\n"));
+        // Nothing to complete rings the bell
+        let (result, drawn) = edit(&mut editor(), b"z\t\r");
+        assert_eq!(result, ReadLine::Line("z".to_string()));
+        assert!(drawn.contains('\x07'));
+    }
+
+    #[test]
+    fn history_skips_repeats() {
+        let mut history = History::default();
+        history.add("1 + 1");
+        history.add("1 + 1");
+        history.add("  ");
+        history.add("2 + 2");
+        assert_eq!(history.entries, vec!["1 + 1", "2 + 2"]);
+    }
+}
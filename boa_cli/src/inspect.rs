@@ -0,0 +1,715 @@
+//! The `--inspect` mode of the CLI, a [Debug Adapter Protocol][dap] server over stdio.
+//!
+//! An editor starts `boa --inspect`, sends it the script to run with a `launch` request and the
+//! breakpoints, and the script runs once the configuration is done. Whenever it stops, the server
+//! answers the requests for the stack, the scopes and the variables until the editor resumes it.
+//! The output of the script is sent to the editor as `output` events, the protocol takes stdout.
+//!
+//! [dap]: https://microsoft.github.io/debug-adapter-protocol/specification
+
+use crate::{EXIT_SYNTAX_ERROR, EXIT_UNCAUGHT_EXCEPTION};
+use boa::{
+    builtins::{
+        object::{ObjectKind, INSTANCE_PROTOTYPE},
+        value::{Value, ValueData},
+    },
+    environment::lexical_environment::EnvironmentType,
+    exec::{DebugHandler, Executor, Interpreter, PauseHandle, PauseReason, Resume, Scope},
+    syntax::{ast::node::Node, lexer::Lexer, parser::Parser},
+};
+use serde_json::{json, Value as Json};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt, fs,
+    io::{self, BufRead, BufReader, Read, Write},
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+};
+
+/// The only thread of the debuggee, the protocol needs an id for it.
+const THREAD_ID: i64 = 1;
+
+/// Runs the debug server until the client disconnects, and returns the exit code of the script.
+///
+/// The script is the `program` of the `launch` request, or the given file.
+pub(crate) fn run(engine: &mut Interpreter, program: Option<PathBuf>) -> io::Result<i32> {
+    let (protocol, output) = redirect_stdout()?;
+    let writer = Arc::new(Mutex::new(Writer {
+        out: protocol,
+        seq: 0,
+    }));
+    let forwarder = output.map(|output| forward_output(output, writer.clone()));
+
+    let running = Arc::new(AtomicBool::new(false));
+    let requests = read_requests(engine.pause_handle(), running.clone());
+    let session = Rc::new(RefCell::new(Session {
+        writer,
+        requests,
+        running,
+        program,
+        breakpoints: HashMap::new(),
+        stop_on_entry: false,
+        entering: false,
+        last_resume: Resume::Continue,
+        handles: Vec::new(),
+        disconnected: false,
+    }));
+
+    // Nothing runs before the client is done with the configuration
+    loop {
+        let request = match session.borrow().requests.recv() {
+            Ok(request) => request,
+            Err(_) => return Ok(0),
+        };
+        match session.borrow_mut().handle(engine, &request)? {
+            Control::Start => break,
+            Control::Disconnect => return Ok(0),
+            _ => {}
+        }
+    }
+
+    let script = session.borrow_mut().start(engine);
+    engine.set_debug_handler(Some(Box::new(Handler(session.clone()))));
+    let error = match script {
+        Ok(script) => match engine.run(&script) {
+            Ok(_) => None,
+            // Only a disconnection terminates the script
+            Err(_) if engine.termination().is_some() => None,
+            Err(e) => Some((EXIT_UNCAUGHT_EXCEPTION, format!("Uncaught {}\n", e))),
+        },
+        Err(e) => Some((EXIT_SYNTAX_ERROR, format!("{}\n", e))),
+    };
+    engine.set_debug_handler(None);
+
+    // The output of the script comes before its error and the end of the session
+    io::stdout().flush()?;
+    if let Some(forwarder) = forwarder {
+        restore_stdout();
+        let _ = forwarder.join();
+    }
+    let mut session = session.borrow_mut();
+    if session.disconnected {
+        return Ok(0);
+    }
+    let code = match error {
+        Some((code, message)) => {
+            session.output("stderr", &message)?;
+            code
+        }
+        None => 0,
+    };
+    session.event("exited", json!({ "exitCode": code }))?;
+    session.event("terminated", json!({}))?;
+    while !session.disconnected {
+        let request = match session.requests.recv() {
+            Ok(request) => request,
+            Err(_) => break,
+        };
+        session.handle(engine, &request)?;
+    }
+    Ok(code)
+}
+
+/// What the session does after a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Control {
+    /// Waits for the next request.
+    Wait,
+    /// Runs the script, the configuration is done.
+    Start,
+    /// Resumes the paused script.
+    Resume(Resume),
+    /// Ends the session.
+    Disconnect,
+}
+
+/// Writes the messages of the server to the client.
+struct Writer {
+    out: Box<dyn Write + Send>,
+    /// The sequence number of the last message
+    seq: i64,
+}
+
+impl fmt::Debug for Writer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Writer").field("seq", &self.seq).finish()
+    }
+}
+
+impl Writer {
+    /// Sends a message, its sequence number is added.
+    fn send(&mut self, mut message: Json) -> io::Result<()> {
+        self.seq += 1;
+        message["seq"] = json!(self.seq);
+        let message = message.to_string();
+        write!(
+            self.out,
+            "Content-Length: {}\r\n\r\n{}",
+            message.len(),
+            message
+        )?;
+        self.out.flush()
+    }
+}
+
+/// A value whose bindings or properties the client can ask for.
+#[derive(Debug, Clone)]
+enum Handle {
+    Scope(Scope),
+    Object(Value),
+}
+
+/// The state of the debug session.
+#[derive(Debug)]
+struct Session {
+    writer: Arc<Mutex<Writer>>,
+    requests: Receiver<Json>,
+    /// Whether the script runs, requests arriving then make it stop to be answered
+    running: Arc<AtomicBool>,
+    program: Option<PathBuf>,
+    /// The lines of the breakpoints, by the path of their source
+    breakpoints: HashMap<PathBuf, Vec<u64>>,
+    stop_on_entry: bool,
+    /// Whether the first pause is the one on entry
+    entering: bool,
+    /// How the script was last resumed, it goes on that way after answering requests
+    last_resume: Resume,
+    /// The scopes and objects listed since the script stopped, the client refers to them by their
+    /// index plus one
+    handles: Vec<Handle>,
+    disconnected: bool,
+}
+
+impl Session {
+    /// Answers a request.
+    fn handle(&mut self, engine: &mut Interpreter, request: &Json) -> io::Result<Control> {
+        let arguments = &request["arguments"];
+        let command = request["command"].as_str().unwrap_or_default();
+        let result = match command {
+            "initialize" => {
+                self.respond(
+                    request,
+                    json!({
+                        "supportsConfigurationDoneRequest": true,
+                        "supportsEvaluateForHovers": true,
+                        "supportsTerminateRequest": true,
+                    }),
+                )?;
+                self.event("initialized", json!({}))?;
+                return Ok(Control::Wait);
+            }
+            "launch" => {
+                if let Some(program) = arguments["program"].as_str() {
+                    self.program = Some(PathBuf::from(program));
+                }
+                self.stop_on_entry = arguments["stopOnEntry"].as_bool().unwrap_or(false);
+                Ok(json!({}))
+            }
+            "setBreakpoints" => Ok(self.set_breakpoints(engine, arguments)),
+            "configurationDone" => {
+                self.respond(request, json!({}))?;
+                return Ok(Control::Start);
+            }
+            "threads" => Ok(json!({ "threads": [{ "id": THREAD_ID, "name": "main" }] })),
+            "stackTrace" => Ok(self.stack_trace(engine)),
+            "scopes" => Ok(self.scopes(engine, arguments["frameId"].as_u64().unwrap_or(0))),
+            "variables" => self.variables(arguments["variablesReference"].as_u64().unwrap_or(0)),
+            "evaluate" => self.evaluate(engine, arguments),
+            "continue" | "next" | "stepIn" | "stepOut" => {
+                let resume = match command {
+                    "next" => Resume::StepOver,
+                    "stepIn" => Resume::StepIn,
+                    "stepOut" => Resume::StepOut,
+                    _ => Resume::Continue,
+                };
+                self.respond(request, json!({ "allThreadsContinued": true }))?;
+                return Ok(Control::Resume(resume));
+            }
+            "pause" => Ok(json!({})),
+            "disconnect" | "terminate" => {
+                engine.interrupt_handle().interrupt();
+                self.respond(request, json!({}))?;
+                self.disconnected = true;
+                return Ok(Control::Disconnect);
+            }
+            _ => Err(format!("unsupported request '{}'", command)),
+        };
+        match result {
+            Ok(body) => self.respond(request, body)?,
+            Err(message) => self.fail(request, &message)?,
+        }
+        Ok(Control::Wait)
+    }
+
+    /// Parses the program with the locations of its statements, and sets its breakpoints.
+    fn start(&mut self, engine: &mut Interpreter) -> Result<Node, String> {
+        let program = self
+            .program
+            .clone()
+            .ok_or_else(|| "no program to debug".to_string())?;
+        let source = fs::read_to_string(&program)
+            .map_err(|e| format!("cannot read {}: {}", program.display(), e))?;
+        if let Some(lines) = self.breakpoints_of(&program) {
+            engine.set_breakpoints(lines.iter().cloned());
+        }
+        if self.stop_on_entry {
+            self.entering = true;
+            engine.pause_handle().pause();
+        }
+        self.running.store(true, Ordering::SeqCst);
+
+        let mut lexer = Lexer::new(&source);
+        lexer.lex().map_err(|e| format!("SyntaxError: {}", e))?;
+        Parser::with_source(&lexer.tokens, &source)
+            .with_locations()
+            .parse_all()
+            .map_err(|e| format!("SyntaxError: {}", e))
+    }
+
+    fn set_breakpoints(&mut self, engine: &mut Interpreter, arguments: &Json) -> Json {
+        let path = PathBuf::from(arguments["source"]["path"].as_str().unwrap_or_default());
+        let lines: Vec<u64> = arguments["breakpoints"]
+            .as_array()
+            .map(|breakpoints| {
+                breakpoints
+                    .iter()
+                    .filter_map(|breakpoint| breakpoint["line"].as_u64())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let verified = self.program.is_none() || self.is_program(&path);
+        if self.is_program(&path) {
+            engine.set_breakpoints(lines.iter().cloned());
+        }
+        let breakpoints: Vec<Json> = lines
+            .iter()
+            .map(|line| json!({ "verified": verified, "line": line }))
+            .collect();
+        self.breakpoints.insert(path, lines);
+        json!({ "breakpoints": breakpoints })
+    }
+
+    /// The breakpoints set in the source of the program, the client may name it differently.
+    fn breakpoints_of(&self, program: &Path) -> Option<&Vec<u64>> {
+        self.breakpoints
+            .iter()
+            .find(|(path, _)| same_file(path, program))
+            .map(|(_, lines)| lines)
+    }
+
+    fn is_program(&self, path: &Path) -> bool {
+        self.program
+            .as_ref()
+            .map_or(false, |program| same_file(path, program))
+    }
+
+    fn stack_trace(&self, engine: &Interpreter) -> Json {
+        let source = self.program.as_ref().map(|program| {
+            json!({
+                "name": program.file_name().map(|name| name.to_string_lossy()),
+                "path": fs::canonicalize(program).unwrap_or_else(|_| program.clone()),
+            })
+        });
+        let frames: Vec<Json> = engine
+            .stack_frames()
+            .into_iter()
+            .enumerate()
+            .map(|(id, frame)| {
+                let mut json = json!({
+                    "id": id,
+                    "name": frame.name,
+                    "line": frame.position.map_or(0, |pos| pos.line_number),
+                    "column": frame.position.map_or(0, |pos| pos.column_number),
+                });
+                // Frames of natives and of code parsed without locations have no source
+                if let (Some(source), Some(_)) = (&source, frame.position) {
+                    json["source"] = source.clone();
+                }
+                json
+            })
+            .collect();
+        json!({ "stackFrames": frames, "totalFrames": frames.len() })
+    }
+
+    fn scopes(&mut self, engine: &Interpreter, frame: u64) -> Json {
+        let mut scopes = Vec::new();
+        for scope in engine.scopes(frame as usize) {
+            let name = match scope.kind() {
+                EnvironmentType::Declarative => "Block",
+                EnvironmentType::Function => "Local",
+                EnvironmentType::Object => "With",
+                EnvironmentType::Global => "Global",
+            };
+            // Every block has an environment, most of them declare nothing
+            if scope.kind() == EnvironmentType::Declarative && scope.variables().is_empty() {
+                continue;
+            }
+            scopes.push(json!({
+                "name": name,
+                "variablesReference": self.add_handle(Handle::Scope(scope)),
+                "expensive": false,
+            }));
+        }
+        json!({ "scopes": scopes })
+    }
+
+    fn variables(&mut self, reference: u64) -> Result<Json, String> {
+        let handle = (reference as usize)
+            .checked_sub(1)
+            .and_then(|index| self.handles.get(index))
+            .cloned()
+            .ok_or_else(|| format!("unknown variables reference {}", reference))?;
+        let members: Vec<(String, Option<Value>)> = match handle {
+            Handle::Scope(scope) => scope.variables(),
+            Handle::Object(object) => match object.as_object() {
+                Some(object) => object
+                    .properties
+                    .iter()
+                    .map(|(name, property)| (name.to_std_string_lossy(), property.value.clone()))
+                    .chain(
+                        object
+                            .internal_slots
+                            .get(INSTANCE_PROTOTYPE)
+                            .filter(|prototype| prototype.is_object())
+                            .map(|prototype| {
+                                ("[[Prototype]]".to_string(), Some(prototype.clone()))
+                            }),
+                    )
+                    .collect(),
+                None => Vec::new(),
+            },
+        };
+        let variables: Vec<Json> = members
+            .into_iter()
+            .map(|(name, value)| match value {
+                Some(value) => json!({
+                    "name": name,
+                    "value": describe(&value),
+                    "variablesReference": self.reference_of(&value),
+                }),
+                None => {
+                    json!({ "name": name, "value": "<uninitialized>", "variablesReference": 0 })
+                }
+            })
+            .collect();
+        Ok(json!({ "variables": variables }))
+    }
+
+    /// Evaluates an expression in the innermost frame, the only one whose scope is live.
+    fn evaluate(&mut self, engine: &mut Interpreter, arguments: &Json) -> Result<Json, String> {
+        if arguments["frameId"]
+            .as_u64()
+            .map_or(false, |frame| frame != 0)
+        {
+            return Err("expressions can only be evaluated in the innermost frame".to_string());
+        }
+        let expression = arguments["expression"].as_str().unwrap_or_default();
+        match engine.debug_eval(expression) {
+            Ok(value) => Ok(json!({
+                "result": describe(&value),
+                "variablesReference": self.reference_of(&value),
+            })),
+            Err(e) => Err(format!("Uncaught {}", e)),
+        }
+    }
+
+    /// The reference the client uses to list the properties of an object, 0 for other values.
+    fn reference_of(&mut self, value: &Value) -> u64 {
+        if value.is_object() {
+            self.add_handle(Handle::Object(value.clone()))
+        } else {
+            0
+        }
+    }
+
+    fn add_handle(&mut self, handle: Handle) -> u64 {
+        self.handles.push(handle);
+        self.handles.len() as u64
+    }
+
+    /// Tells the client that the script stopped, and answers its requests until it resumes.
+    fn pause(&mut self, engine: &mut Interpreter, reason: PauseReason) -> Resume {
+        self.running.store(false, Ordering::SeqCst);
+        let resume = self.serve(engine, reason).unwrap_or_else(|_| {
+            // The client is gone, there is nothing left to debug for
+            engine.interrupt_handle().interrupt();
+            Resume::Continue
+        });
+        self.handles.clear();
+        self.last_resume = resume;
+        self.running.store(true, Ordering::SeqCst);
+        resume
+    }
+
+    fn serve(&mut self, engine: &mut Interpreter, reason: PauseReason) -> io::Result<Resume> {
+        let reason = match reason {
+            PauseReason::Breakpoint => "breakpoint",
+            PauseReason::DebuggerStatement => "debugger statement",
+            PauseReason::Step => "step",
+            PauseReason::Requested if self.entering => "entry",
+            PauseReason::Requested => {
+                // Requests arriving while the script runs stop it, a `pause` request among them
+                // keeps it stopped
+                let mut paused = false;
+                while let Ok(request) = self.requests.try_recv() {
+                    paused |= request["command"] == "pause";
+                    match self.handle(engine, &request)? {
+                        Control::Disconnect => return Ok(Resume::Continue),
+                        Control::Resume(resume) => return Ok(resume),
+                        _ => {}
+                    }
+                }
+                if !paused {
+                    return Ok(self.last_resume);
+                }
+                "pause"
+            }
+        };
+        self.entering = false;
+        self.event(
+            "stopped",
+            json!({ "reason": reason, "threadId": THREAD_ID, "allThreadsStopped": true }),
+        )?;
+        loop {
+            let request = self
+                .requests
+                .recv()
+                .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "client disconnected"))?;
+            match self.handle(engine, &request)? {
+                Control::Resume(resume) => return Ok(resume),
+                Control::Disconnect => return Ok(Resume::Continue),
+                _ => {}
+            }
+        }
+    }
+
+    fn respond(&self, request: &Json, body: Json) -> io::Result<()> {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "command": request["command"],
+            "success": true,
+            "body": body,
+        }))
+    }
+
+    fn fail(&self, request: &Json, message: &str) -> io::Result<()> {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "command": request["command"],
+            "success": false,
+            "message": message,
+        }))
+    }
+
+    fn event(&self, event: &str, body: Json) -> io::Result<()> {
+        self.send(json!({ "type": "event", "event": event, "body": body }))
+    }
+
+    fn output(&self, category: &str, text: &str) -> io::Result<()> {
+        self.event("output", json!({ "category": category, "output": text }))
+    }
+
+    fn send(&self, message: Json) -> io::Result<()> {
+        self.writer
+            .lock()
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "poisoned writer"))?
+            .send(message)
+    }
+}
+
+/// The debug handler given to the interpreter, the session stays with the server.
+#[derive(Debug)]
+struct Handler(Rc<RefCell<Session>>);
+
+impl DebugHandler for Handler {
+    fn paused(&mut self, engine: &mut Interpreter, reason: PauseReason) -> Resume {
+        self.0.borrow_mut().pause(engine, reason)
+    }
+}
+
+/// A short description of a value, objects are listed by the client when it asks for them.
+fn describe(value: &Value) -> String {
+    match value.data() {
+        ValueData::String(_) => format!("{:?}", value.to_string()),
+        ValueData::Object(_) => {
+            let object = value.as_object().expect("the value is an object");
+            match object.kind {
+                ObjectKind::Function => match value.get_field_slice("name").data() {
+                    ValueData::String(ref name) if !name.is_empty() => {
+                        format!("function {}()", name)
+                    }
+                    _ => "function ()".to_string(),
+                },
+                ObjectKind::Array => {
+                    format!("Array({})", value.get_field_slice("length"))
+                }
+                ObjectKind::Error => value.to_string(),
+                ObjectKind::Ordinary => "Object".to_string(),
+                ref kind => kind.to_string(),
+            }
+        }
+        _ => value.to_string(),
+    }
+}
+
+/// Checks if two paths name the same file.
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Reads the requests of the client from stdin on a thread of its own.
+///
+/// A request arriving while the script runs asks it to pause so that it is answered. The end of
+/// the input stops the script.
+fn read_requests(pause: PauseHandle, running: Arc<AtomicBool>) -> Receiver<Json> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let stdin = io::stdin();
+        let mut input = stdin.lock();
+        while let Ok(Some(message)) = read_message(&mut input) {
+            if sender.send(message).is_err() {
+                return;
+            }
+            if running.load(Ordering::SeqCst) {
+                pause.pause();
+            }
+        }
+        // The channel closes, a running script stops to notice it
+        drop(sender);
+        pause.pause();
+    });
+    receiver
+}
+
+/// Reads a message framed by a `Content-Length` header, `None` at the end of the input.
+fn read_message<R: BufRead>(input: &mut R) -> io::Result<Option<Json>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim();
+        if header.is_empty() {
+            if length.is_some() {
+                break;
+            }
+            continue;
+        }
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            length = value.trim().parse::<usize>().ok();
+        }
+    }
+    let mut content = vec![0; length.unwrap_or_default()];
+    input.read_exact(&mut content)?;
+    serde_json::from_slice(&content)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Sends what the script writes to stdout to the client, until the pipe closes.
+fn forward_output(
+    output: impl Read + Send + 'static,
+    writer: Arc<Mutex<Writer>>,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        let mut output = BufReader::new(output);
+        let mut line = Vec::new();
+        while let Ok(read) = output.read_until(b'\n', &mut line) {
+            if read == 0 {
+                break;
+            }
+            let event = json!({
+                "type": "event",
+                "event": "output",
+                "body": { "category": "stdout", "output": String::from_utf8_lossy(&line) },
+            });
+            if let Ok(mut writer) = writer.lock() {
+                let _ = writer.send(event);
+            }
+            line.clear();
+        }
+    })
+}
+
+/// Moves stdout to a pipe, so that the output of the script doesn't get mixed with the protocol.
+///
+/// Returns the writer of the protocol, the original stdout, and the end of the pipe to read the
+/// output from.
+#[cfg(unix)]
+fn redirect_stdout() -> io::Result<(Box<dyn Write + Send>, Option<fs::File>)> {
+    use std::os::unix::io::FromRawFd;
+
+    // SAFETY: the descriptors are fresh ones returned by `dup` and `pipe`, each owned by one file
+    unsafe {
+        let protocol = libc::dup(libc::STDOUT_FILENO);
+        if protocol < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let mut pipe = [0; 2];
+        if libc::pipe(pipe.as_mut_ptr()) < 0 || libc::dup2(pipe[1], libc::STDOUT_FILENO) < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        libc::close(pipe[1]);
+        Ok((
+            Box::new(fs::File::from_raw_fd(protocol)),
+            Some(fs::File::from_raw_fd(pipe[0])),
+        ))
+    }
+}
+
+/// Without pipes the output of the script goes to stdout with the protocol.
+#[cfg(not(unix))]
+fn redirect_stdout() -> io::Result<(Box<dyn Write + Send>, Option<fs::File>)> {
+    Ok((Box::new(io::stdout()), None))
+}
+
+/// Closes the pipe stdout was moved to, so that the output forwarder ends.
+#[cfg(unix)]
+fn restore_stdout() {
+    // SAFETY: stderr stays open for the whole process
+    unsafe {
+        libc::dup2(libc::STDERR_FILENO, libc::STDOUT_FILENO);
+    }
+}
+
+#[cfg(not(unix))]
+fn restore_stdout() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use boa::{forward_val, realm::Realm};
+
+    #[test]
+    fn reads_messages() {
+        let mut input = io::Cursor::new(
+            "Content-Length: 13\r\n\r\n{\"seq\":1}    Content-Length: 2\r\n\r\n{}".as_bytes(),
+        );
+        // The length counts bytes, the padding is part of the first message
+        assert_eq!(read_message(&mut input).unwrap(), Some(json!({ "seq": 1 })));
+        assert_eq!(read_message(&mut input).unwrap(), Some(json!({})));
+        assert_eq!(read_message(&mut input).unwrap(), None);
+    }
+
+    #[test]
+    fn describes_values() {
+        let mut engine: Interpreter = Executor::new(Realm::create());
+        let array = forward_val(&mut engine, "[1, 2, 3]").unwrap();
+        assert_eq!(describe(&array), "Array(3)");
+        assert_eq!(describe(&Value::from("a\"b")), "\"a\\\"b\"");
+        assert_eq!(describe(&Value::from(1.5)), "1.5");
+    }
+}
@@ -25,10 +25,17 @@
     clippy::as_conversions
 )]
 
+mod editor;
+mod inspect;
+mod profile;
+mod repl;
+
 use boa::{
-    builtins::console::log,
-    exec::Executor,
-    forward_val,
+    builtins::{
+        console::formatter,
+        value::{ResultValue, Value},
+    },
+    exec::{Executor, Interpreter},
     realm::Realm,
     syntax::ast::{node::Node, token::Token},
 };
@@ -36,9 +43,19 @@ use std::{
     fs::read_to_string,
     io::{self, Write},
     path::PathBuf,
+    process,
 };
 use structopt::{clap::arg_enum, StructOpt};
 
+/// The exit code when a script throws an exception it doesn't catch.
+const EXIT_UNCAUGHT_EXCEPTION: i32 = 1;
+
+/// The exit code when a script has a syntax error, none of it is run then.
+const EXIT_SYNTAX_ERROR: i32 = 2;
+
+/// The exit code when a script can't be read, `EX_NOINPUT` of `sysexits.h`.
+const EXIT_UNREADABLE_FILE: i32 = 66;
+
 #[cfg(all(target_arch = "x86_64", target_os = "linux", target_env = "gnu"))]
 #[cfg_attr(
     all(target_arch = "x86_64", target_os = "linux", target_env = "gnu"),
@@ -79,6 +96,32 @@ struct Opt {
         case_insensitive = true
     )]
     dump_ast: Option<Option<DumpFormat>>,
+
+    /// Evaluate the given code, after the files.
+    #[structopt(long, short = "e", value_name = "CODE")]
+    eval: Option<String>,
+
+    /// Evaluate the given code, after the files, and print its result.
+    #[structopt(long, short = "p", value_name = "CODE", conflicts_with = "eval")]
+    print: Option<String>,
+
+    /// Debug the first file from an editor, with the Debug Adapter Protocol over stdio.
+    #[structopt(long, conflicts_with_all = &["dump-tokens", "dump-ast", "eval", "print"])]
+    inspect: bool,
+
+    /// Profile the code run, and write its call stacks for flame graphs and a summary of the time
+    /// spent per function.
+    #[structopt(long, conflicts_with_all = &["dump-tokens", "dump-ast", "inspect"])]
+    profile: bool,
+
+    /// The path of the profile, without the `.folded` and `.json` extensions of its files.
+    #[structopt(
+        long,
+        value_name = "PATH",
+        parse(from_os_str),
+        default_value = "boa-profile"
+    )]
+    profile_out: PathBuf,
 }
 
 impl Opt {
@@ -126,12 +169,19 @@ fn lex_source(src: &str) -> Result<Vec<Token>, String> {
 ///
 /// Returns a error of type String with a message,
 /// if the token stream has a parsing error.
-fn parse_tokens(tokens: Vec<Token>) -> Result<Node, String> {
+///
+/// With `locations` the statements are marked with their positions, which the reports of
+/// uncaught errors show.
+fn parse_tokens(tokens: Vec<Token>, src: &str, locations: bool) -> Result<Node, String> {
     use boa::syntax::parser::Parser;
 
-    Parser::new(&tokens)
+    let mut parser = Parser::with_source(&tokens, src);
+    if locations {
+        parser = parser.with_locations();
+    }
+    parser
         .parse_all()
-        .map_err(|e| format!("ParsingError: {}", e))
+        .map_err(|e| format!("SyntaxError: {}", e))
 }
 
 /// Dumps the token stream or ast to stdout depending on the given arguments.
@@ -154,7 +204,7 @@ fn dump(src: &str, args: &Opt) -> Result<(), String> {
             None => println!("{:#?}", tokens),
         }
     } else if let Some(ref arg) = args.dump_ast {
-        let ast = parse_tokens(tokens)?;
+        let ast = parse_tokens(tokens, src, false)?;
 
         match arg {
             Some(format) => match format {
@@ -172,51 +222,127 @@ fn dump(src: &str, args: &Opt) -> Result<(), String> {
     Ok(())
 }
 
+/// Runs a script, or dumps it with the dump flags, and prints its result if asked to.
+///
+/// Errors are reported with the name of the script, the error is the code to exit with.
+fn run_script(
+    engine: &mut Interpreter,
+    args: &Opt,
+    name: &str,
+    src: &str,
+    print: bool,
+) -> Result<(), i32> {
+    if args.has_dump_flag() {
+        return dump(src, args).map_err(|e| {
+            eprintln!("{}\n    at {}", e, name);
+            EXIT_SYNTAX_ERROR
+        });
+    }
+
+    let ast = lex_source(src)
+        .and_then(|tokens| parse_tokens(tokens, src, true))
+        .map_err(|e| {
+            eprintln!("{}\n    at {}", e, name);
+            EXIT_SYNTAX_ERROR
+        })?;
+    match engine.run(&ast) {
+        Ok(v) => {
+            if print {
+                println!("{}", v);
+            }
+            Ok(())
+        }
+        Err(e) => {
+            report_uncaught(engine, &e, name);
+            Err(EXIT_UNCAUGHT_EXCEPTION)
+        }
+    }
+}
+
+/// The `print` global of the CLI, writes its arguments to stdout like `console.log`.
+fn print(_: &mut Value, args: &[Value], _: &mut Interpreter) -> ResultValue {
+    println!("{}", formatter(args));
+    Ok(Value::undefined())
+}
+
+/// Prints an exception nothing caught, errors show their name and their message. It's reported
+/// at the line and column of the statement which threw it in the named script, when it's known.
+fn report_uncaught(engine: &Interpreter, error: &Value, name: &str) {
+    let shown = error.display_own_properties();
+    match engine.throw_position(error) {
+        Some(pos) => eprintln!(
+            "Uncaught {}\n    at {}:{}:{}",
+            shown, name, pos.line_number, pos.column_number
+        ),
+        None => eprintln!("Uncaught {}\n    at {}", shown, name),
+    }
+}
+
+/// Stops the profiler and writes the profile, if the code was profiled.
+fn write_profile(engine: &mut Interpreter, args: &Opt) -> io::Result<()> {
+    match engine.stop_profiling() {
+        Some(profile) => profile::write(&profile, &args.profile_out),
+        None => Ok(()),
+    }
+}
+
 pub fn main() -> Result<(), std::io::Error> {
     let args = Opt::from_args();
 
-    let realm = Realm::create().register_global_func("print", log);
+    let realm = Realm::create().register_global_func("print", print);
 
     let mut engine = Executor::new(realm);
 
-    for file in &args.files {
-        let buffer = read_to_string(file)?;
-
-        if args.has_dump_flag() {
-            match dump(&buffer, &args) {
-                Ok(_) => {}
-                Err(e) => eprintln!("{}", e),
-            }
-        } else {
-            match forward_val(&mut engine, &buffer) {
-                Ok(v) => print!("{}", v.to_string()),
-                Err(v) => eprint!("{}", v.to_string()),
-            }
+    if args.inspect {
+        let code = inspect::run(&mut engine, args.files.first().cloned())?;
+        if code != 0 {
+            process::exit(code);
         }
+        return Ok(());
     }
 
-    if args.files.is_empty() {
-        loop {
-            let mut buffer = String::new();
-
-            io::stdin().read_line(&mut buffer)?;
+    if args.profile {
+        engine.start_profiling();
+    }
 
-            if args.has_dump_flag() {
-                match dump(&buffer, &args) {
-                    Ok(_) => {}
-                    Err(e) => eprintln!("{}", e),
-                }
-            } else {
-                match forward_val(&mut engine, buffer.trim_end()) {
-                    Ok(v) => println!("{}", v.to_string()),
-                    Err(v) => eprintln!("{}", v.to_string()),
-                }
+    let mut result = Ok(());
+    for file in &args.files {
+        let buffer = match read_to_string(file) {
+            Ok(buffer) => buffer,
+            Err(e) => {
+                eprintln!("boa: {}: {}", file.display(), e);
+                result = Err(EXIT_UNREADABLE_FILE);
+                break;
             }
-
-            // The flush is needed because where in a REPL and we do not want buffering.
-            std::io::stdout().flush().unwrap();
+        };
+        result = run_script(
+            &mut engine,
+            &args,
+            &file.display().to_string(),
+            &buffer,
+            false,
+        );
+        if result.is_err() {
+            break;
+        }
+    }
+    if result.is_ok() {
+        if let Some(ref code) = args.eval {
+            result = run_script(&mut engine, &args, "[eval]", code, false);
+        } else if let Some(ref code) = args.print {
+            result = run_script(&mut engine, &args, "[eval]", code, true);
         }
     }
 
-    Ok(())
+    if let Err(code) = result {
+        write_profile(&mut engine, &args)?;
+        io::stdout().flush()?;
+        process::exit(code);
+    }
+
+    if args.files.is_empty() && args.eval.is_none() && args.print.is_none() {
+        repl::run(&mut engine, &args)?;
+    }
+
+    write_profile(&mut engine, &args)
 }
@@ -0,0 +1,93 @@
+//! The `--profile` mode of the CLI.
+//!
+//! The scripts are run with the profiler of the interpreter started, then the profile is written
+//! next to each other in two files: the call stacks in the folded format which flame graph tools
+//! such as [inferno] or [FlameGraph] read, and a JSON summary of the time spent per function.
+//!
+//! [inferno]: https://github.com/jonhoo/inferno
+//! [FlameGraph]: https://github.com/brendangregg/FlameGraph
+
+use boa::exec::Profile;
+use serde_json::{json, Value as Json};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// Writes the profile to `<prefix>.folded` and `<prefix>.json`.
+pub(crate) fn write(profile: &Profile, prefix: &Path) -> io::Result<()> {
+    let with_suffix = |suffix| {
+        let mut path = prefix.as_os_str().to_owned();
+        path.push(suffix);
+        PathBuf::from(path)
+    };
+    let folded = with_suffix(".folded");
+    let summary = with_suffix(".json");
+    fs::write(&folded, profile.folded())?;
+    fs::write(&summary, format!("{:#}\n", summarize(profile)))?;
+    eprintln!(
+        "Profile written to {} and {}",
+        folded.display(),
+        summary.display()
+    );
+    Ok(())
+}
+
+/// The JSON summary of a profile, the functions by decreasing self time after the script.
+fn summarize(profile: &Profile) -> Json {
+    let micros = |time: Duration| time.as_micros() as u64;
+    let functions: Vec<Json> = profile
+        .functions()
+        .into_iter()
+        .map(|function| {
+            let position = function
+                .position
+                .map(|pos| json!({ "line": pos.line_number, "column": pos.column_number }));
+            json!({
+                "name": function.name,
+                "position": position,
+                "native": function.native,
+                "calls": function.calls,
+                "self_micros": micros(function.self_time),
+                "total_micros": micros(function.total_time),
+            })
+        })
+        .collect();
+    json!({ "functions": functions })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use boa::{
+        exec::{Executor, Interpreter},
+        forward,
+        realm::Realm,
+    };
+
+    #[test]
+    fn summarizes_profiles() {
+        let mut engine: Interpreter = Executor::new(Realm::create());
+        engine.start_profiling();
+        forward(
+            &mut engine,
+            "function f() { return [1, 2].join(); } f(); f();",
+        );
+        let summary = summarize(&engine.stop_profiling().unwrap());
+
+        let functions = summary["functions"].as_array().unwrap();
+        assert_eq!(functions[0]["name"], "(script)");
+        let call = |name: &str| {
+            let function = functions.iter().find(|f| f["name"] == name).unwrap();
+            (function["native"].clone(), function["calls"].clone())
+        };
+        assert_eq!(call("f"), (json!(false), json!(2)));
+        assert_eq!(call("join"), (json!(true), json!(2)));
+        let position =
+            |name: &str| &functions.iter().find(|f| f["name"] == name).unwrap()["position"];
+        assert_eq!(position("f"), &json!({ "line": 1, "column": 1 }));
+        assert_eq!(position("join"), &Json::Null);
+        assert!(functions[0]["total_micros"].is_u64());
+    }
+}
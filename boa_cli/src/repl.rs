@@ -0,0 +1,393 @@
+//! The interactive mode of the CLI.
+//!
+//! Statements are read with the line editor until they parse, a parse which ends abruptly means
+//! that the input goes on in the next line. The names of the realm complete with `Tab`, and the
+//! input is coloured by the lexer.
+
+use crate::{
+    dump,
+    editor::{Editor, Helper, ReadLine},
+    Opt,
+};
+use boa::{
+    builtins::{
+        object::INSTANCE_PROTOTYPE,
+        value::{JsString, Value, ValueData},
+    },
+    environment::{
+        declarative_environment_record::DeclarativeEnvironmentRecord,
+        global_environment_record::GlobalEnvironmentRecord,
+    },
+    exec::Interpreter,
+    forward_val,
+    realm::Realm,
+    syntax::{
+        ast::token::TokenKind,
+        lexer::Lexer,
+        parser::{error::ParseError, Parser},
+    },
+};
+use std::{
+    env,
+    io::{self, Write},
+    path::PathBuf,
+};
+
+/// The prompt of a new statement.
+const PROMPT: &str = "> ";
+/// The prompt of the lines continuing a statement.
+const CONTINUATION_PROMPT: &str = "... ";
+
+/// How deep a prototype chain is followed for completions, in case it is cyclic.
+const MAX_PROTOTYPE_DEPTH: usize = 64;
+
+const RESET: &str = "\x1b[0m";
+const KEYWORD: &str = "\x1b[36m";
+const LITERAL: &str = "\x1b[33m";
+const STRING: &str = "\x1b[32m";
+const REGEXP: &str = "\x1b[31m";
+const COMMENT: &str = "\x1b[90m";
+
+/// Reads and evaluates statements until the end of the input.
+pub(crate) fn run(engine: &mut Interpreter, args: &Opt) -> io::Result<()> {
+    let history = env::var_os("HOME").map(|home| PathBuf::from(home).join(".boa_history"));
+    let mut editor = Editor::new(history);
+    let mut source = String::new();
+
+    loop {
+        let prompt = if source.is_empty() {
+            PROMPT
+        } else {
+            CONTINUATION_PROMPT
+        };
+        let helper = ReplHelper {
+            realm: &engine.realm,
+        };
+        match editor.read_line(prompt, &helper)? {
+            ReadLine::Line(line) => {
+                if !source.is_empty() {
+                    source.push('\n');
+                }
+                source.push_str(&line);
+            }
+            ReadLine::Interrupted => {
+                source.clear();
+                continue;
+            }
+            ReadLine::Eof => break,
+        }
+        if is_incomplete(&source) {
+            continue;
+        }
+
+        if args.has_dump_flag() {
+            if let Err(e) = dump(&source, args) {
+                eprintln!("{}", e);
+            }
+        } else {
+            match forward_val(engine, &source) {
+                Ok(v) => println!("{}", v),
+                Err(v) => eprintln!("Uncaught {}", v.display_own_properties()),
+            }
+        }
+        source.clear();
+
+        // The flush is needed because where in a REPL and we do not want buffering.
+        io::stdout().flush()?;
+    }
+
+    Ok(())
+}
+
+/// Returns whether the source ends before its statements do, so more lines are needed.
+///
+/// Other errors are left for the evaluation to report.
+fn is_incomplete(source: &str) -> bool {
+    let mut lexer = Lexer::new(source);
+    if lexer.lex().is_err() {
+        return false;
+    }
+    matches!(
+        Parser::new(&lexer.tokens).parse_all(),
+        Err(ParseError::AbruptEnd)
+    )
+}
+
+/// Completes and colours the input with the names and the lexer of a realm.
+#[derive(Debug)]
+struct ReplHelper<'a> {
+    realm: &'a Realm,
+}
+
+impl ReplHelper<'_> {
+    /// The names of the global object and of the global lexical declarations.
+    fn global_names(&self) -> Vec<String> {
+        let mut names = member_names(&self.realm.global_obj);
+        self.with_global_declarations(|declarations| {
+            names.extend(declarations.env_rec.keys().cloned())
+        });
+        names
+    }
+
+    /// Calls `f` with the record of the `let`, `const` and `class` declarations of the realm.
+    fn with_global_declarations<T>(
+        &self,
+        f: impl FnOnce(&DeclarativeEnvironmentRecord) -> T,
+    ) -> Option<T> {
+        let environment = self
+            .realm
+            .environment
+            .get_current_environment_ref()
+            .borrow();
+        let global = environment
+            .as_any()
+            .downcast_ref::<GlobalEnvironmentRecord>()?;
+        Some(f(&global.declarative_record))
+    }
+
+    /// Finds the object a dotted path such as `Math` or `a.b` names, without calling getters.
+    ///
+    /// Primitive values stand for the prototype of their wrapper, whose methods they have.
+    fn resolve(&self, path: &str) -> Option<Value> {
+        let mut names = path.split('.');
+        let first = names.next()?;
+        let declared = self
+            .with_global_declarations(|declarations| {
+                declarations
+                    .env_rec
+                    .get(first)
+                    .map(|binding| binding.value.clone())
+            })
+            .flatten();
+        let mut value = match declared {
+            Some(value) => value?,
+            None => own_or_inherited(&self.realm.global_obj, first)?,
+        };
+        for name in names {
+            value = own_or_inherited(&self.members_of(value)?, name)?;
+        }
+        self.members_of(value)
+    }
+
+    /// The object holding the members of a value, the `prototype` of a global constructor for
+    /// primitive values.
+    fn members_of(&self, value: Value) -> Option<Value> {
+        let constructor = match value.data() {
+            ValueData::String(_) => "String",
+            ValueData::Rational(_) | ValueData::Integer(_) => "Number",
+            ValueData::Boolean(_) => "Boolean",
+            _ => return Some(value),
+        };
+        let constructor = own_or_inherited(&self.realm.global_obj, constructor)?;
+        own_or_inherited(&constructor, "prototype")
+    }
+}
+
+impl Helper for ReplHelper<'_> {
+    fn highlight(&self, line: &str) -> String {
+        let mut lexer = Lexer::new(line);
+        // The tokens lexed before an error are coloured, the rest is left as it is
+        let _ = lexer.lex();
+        let mut highlighted = String::with_capacity(line.len() * 2);
+        let mut last = 0;
+        for token in &lexer.tokens {
+            let (start, end) = (token.span.start, token.span.end);
+            if start < last || end > line.len() {
+                break;
+            }
+            paint_gap(&mut highlighted, &line[last..start]);
+            let colour = match token.kind {
+                TokenKind::Keyword(_) => Some(KEYWORD),
+                TokenKind::BooleanLiteral(_)
+                | TokenKind::NullLiteral
+                | TokenKind::NumericLiteral(_) => Some(LITERAL),
+                TokenKind::StringLiteral(_) => Some(STRING),
+                TokenKind::RegularExpressionLiteral(_, _) => Some(REGEXP),
+                _ => None,
+            };
+            match colour {
+                Some(colour) => paint(&mut highlighted, colour, &line[start..end]),
+                None => highlighted.push_str(&line[start..end]),
+            }
+            last = end;
+        }
+        // What follows the last token is a comment, or a string or code the lexer rejected
+        let rest = &line[last..];
+        let code = rest.trim_start();
+        if code.starts_with('"') || code.starts_with('\'') {
+            highlighted.push_str(&rest[..rest.len() - code.len()]);
+            paint(&mut highlighted, STRING, code);
+        } else if code.starts_with("//") || code.starts_with("/*") {
+            paint_gap(&mut highlighted, rest);
+        } else {
+            highlighted.push_str(rest);
+        }
+        highlighted
+    }
+
+    fn complete(&self, line: &str, pos: usize) -> (usize, Vec<String>) {
+        let before = &line[..pos];
+        let start = before
+            .rfind(|c: char| !(is_identifier_part(c) || c == '.'))
+            .map_or(0, |i| {
+                i + before[i..].chars().next().map_or(1, char::len_utf8)
+            });
+        let chain = &before[start..];
+        let (path, word) = match chain.rfind('.') {
+            Some(dot) => (&chain[..dot], &chain[dot + 1..]),
+            None => ("", chain),
+        };
+        let word_start = pos - word.len();
+        if path.starts_with(|c: char| c.is_ascii_digit())
+            || word.starts_with(|c: char| c.is_ascii_digit())
+        {
+            return (word_start, Vec::new());
+        }
+
+        let mut names = if path.is_empty() {
+            self.global_names()
+        } else {
+            self.resolve(path)
+                .map(|value| member_names(&value))
+                .unwrap_or_default()
+        };
+        names.retain(|name| name.starts_with(word) && name.chars().all(is_identifier_part));
+        names.sort();
+        names.dedup();
+        (word_start, names)
+    }
+}
+
+/// Whether the character can be part of an identifier.
+fn is_identifier_part(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+/// Adds the text in the given colour.
+fn paint(out: &mut String, colour: &str, text: &str) {
+    if !text.is_empty() {
+        out.push_str(colour);
+        out.push_str(text);
+        out.push_str(RESET);
+    }
+}
+
+/// Adds the text between two tokens, the comments in it are dimmed.
+fn paint_gap(out: &mut String, text: &str) {
+    let comment = text.trim_start();
+    out.push_str(&text[..text.len() - comment.len()]);
+    paint(out, COMMENT, comment);
+}
+
+/// Looks a data property up through the prototype chain, accessors aren't called.
+fn own_or_inherited(value: &Value, name: &str) -> Option<Value> {
+    let mut current = value.clone();
+    for _ in 0..MAX_PROTOTYPE_DEPTH {
+        let next = {
+            let object = current.as_object()?;
+            if let Some(property) = object.properties.get(&JsString::from(name)) {
+                return property.value.clone();
+            }
+            object.internal_slots.get(INSTANCE_PROTOTYPE)?.clone()
+        };
+        current = next;
+    }
+    None
+}
+
+/// The names of the properties of an object and of its prototypes.
+fn member_names(value: &Value) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut current = value.clone();
+    for _ in 0..MAX_PROTOTYPE_DEPTH {
+        let next = match current.as_object() {
+            Some(object) => {
+                names.extend(object.properties.keys().map(JsString::to_std_string_lossy));
+                object.internal_slots.get(INSTANCE_PROTOTYPE).cloned()
+            }
+            None => None,
+        };
+        match next {
+            Some(next) => current = next,
+            None => break,
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use boa::exec::Executor;
+
+    fn completions(engine: &Interpreter, line: &str) -> (usize, Vec<String>) {
+        ReplHelper {
+            realm: &engine.realm,
+        }
+        .complete(line, line.len())
+    }
+
+    #[test]
+    fn detects_incomplete_input() {
+        assert!(is_incomplete("function f() {"));
+        assert!(is_incomplete("print(1,\n2,"));
+        // Operators and initializers which end the input
+        assert!(is_incomplete("1 +"));
+        assert!(is_incomplete("x ="));
+        assert!(is_incomplete("let x ="));
+        assert!(is_incomplete("var list = [1,"));
+        assert!(is_incomplete("a &&"));
+        assert!(!is_incomplete("function f() {\nreturn 1;\n}"));
+        assert!(!is_incomplete("1 +* 2"));
+        assert!(!is_incomplete("'unterminated"));
+        assert!(!is_incomplete(""));
+    }
+
+    #[test]
+    fn completes_names() {
+        let mut engine: Interpreter = Executor::new(Realm::create());
+        forward_val(
+            &mut engine,
+            "let answer = 42; var obj = { alpha: 1, beta: { gamma: 2 } };",
+        )
+        .unwrap();
+
+        assert_eq!(completions(&engine, "1 + ans"), (4, vec!["answer".into()]));
+        assert_eq!(completions(&engine, "Math.ma"), (5, vec!["max".into()]));
+        assert_eq!(
+            completions(&engine, "obj.beta.g"),
+            (9, vec!["gamma".into()])
+        );
+        let (start, members) = completions(&engine, "obj.");
+        assert_eq!(start, 4);
+        assert!(members.starts_with(&["alpha".into(), "beta".into()]));
+        assert!(members.contains(&"hasOwnProperty".into()));
+        assert!(completions(&engine, "answer.toF")
+            .1
+            .contains(&"toFixed".into()));
+        assert!(completions(&engine, "missing.a").1.is_empty());
+        assert!(completions(&engine, "1.5").1.is_empty());
+    }
+
+    #[test]
+    fn highlights_tokens() {
+        let engine: Interpreter = Executor::new(Realm::create());
+        let helper = ReplHelper {
+            realm: &engine.realm,
+        };
+        assert_eq!(
+            helper.highlight("let s = 'a'; // c"),
+            format!(
+                "{k}let{r} s = {s}'a'{r}; {c}// c{r}",
+                k = KEYWORD,
+                s = STRING,
+                c = COMMENT,
+                r = RESET
+            )
+        );
+        assert_eq!(
+            helper.highlight("x = \"open"),
+            format!("x = {}\"open{}", STRING, RESET)
+        );
+    }
+}
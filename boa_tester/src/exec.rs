@@ -0,0 +1,588 @@
+//! Execution of the tests in fresh realms, with the `$262` host object of the harness.
+//!
+//! More information:
+//!  - [test262 documentation][doc]
+//!
+//! [doc]: https://github.com/tc39/test262/blob/master/INTERPRETING.md
+
+use crate::read::{read_metadata, Harness, MetaData, Phase, DEFAULT_INCLUDES};
+use boa::{
+    builtins::{
+        function::{Function, FunctionBody, NativeFunctionData},
+        value::{ResultValue, Value, ValueData},
+    },
+    exec::{Executor, Interpreter, InterruptHandle, Termination},
+    forward_val,
+    realm::Realm,
+    syntax::{ast::node::Node, lexer::Lexer, parser::Parser},
+};
+use serde::Serialize;
+use std::{
+    any::Any,
+    cell::RefCell,
+    collections::HashMap,
+    fs,
+    panic::{self, AssertUnwindSafe},
+    path::Path,
+    rc::Rc,
+    sync::{
+        mpsc::{self, Receiver, RecvTimeoutError, Sender},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// How a test ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Outcome {
+    Passed,
+    Failed,
+    Panicked,
+    /// The test was still running at its timeout, it was interrupted.
+    TimedOut,
+    /// The test needs something the runner doesn't support, such as modules.
+    Ignored,
+}
+
+/// The outcome of a test, with the reason when it didn't pass.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct TestResult {
+    pub(crate) outcome: Outcome,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) reason: Option<String>,
+}
+
+impl TestResult {
+    fn new(outcome: Outcome, reason: Option<String>) -> Self {
+        Self { outcome, reason }
+    }
+
+    fn failed<R: Into<String>>(reason: R) -> Self {
+        Self::new(Outcome::Failed, Some(reason.into()))
+    }
+}
+
+/// Why a run of a test didn't pass.
+#[derive(Debug)]
+enum Failure {
+    Failed(String),
+    /// The watchdog interrupted the run at its timeout.
+    TimedOut,
+}
+
+impl From<String> for Failure {
+    fn from(reason: String) -> Self {
+        Failure::Failed(reason)
+    }
+}
+
+/// The flags of tests which the runner can't run.
+const UNSUPPORTED_FLAGS: [&str; 2] = ["module", "async"];
+
+/// The stack of the threads running tests, deeply nested code needs a big one.
+const STACK_SIZE: usize = 32 * 1024 * 1024;
+
+thread_local! {
+    /// The parsed files of the harness, they are the same for every test run by a thread.
+    static HARNESS_CACHE: RefCell<HashMap<String, Rc<Node>>> = RefCell::new(HashMap::new());
+
+    /// The thread running the tests of this thread, it's replaced when a test doesn't stop.
+    static WORKER: RefCell<Option<Worker>> = RefCell::new(None);
+}
+
+/// A job of a worker, it's given a function to call with the interrupt handle of each
+/// interpreter it creates.
+type Job = Box<dyn FnOnce(&dyn Fn(InterruptHandle)) -> TestResult + Send>;
+
+/// What a worker tells the thread watching it.
+enum Message {
+    /// An interpreter was created, its timeout starts.
+    Started(InterruptHandle),
+    Finished(TestResult),
+}
+
+/// A thread running tests, so that the thread which watches it can give up on a test which
+/// doesn't stop.
+///
+/// A test is interrupted once its timeout is over, and if it's still running after a second
+/// timeout, for example in a native function which doesn't check for interrupts, its worker is
+/// left behind. Both are recorded as timed out.
+#[derive(Debug)]
+struct Worker {
+    jobs: Sender<Job>,
+    messages: Receiver<Message>,
+}
+
+impl Worker {
+    fn spawn() -> Result<Self, String> {
+        let (jobs, job_receiver) = mpsc::channel::<Job>();
+        let (sender, messages) = mpsc::channel();
+        thread::Builder::new()
+            .name("test worker".to_string())
+            .stack_size(STACK_SIZE)
+            .spawn(move || {
+                let started = |handle| {
+                    let _ = sender.send(Message::Started(handle));
+                };
+                for job in job_receiver {
+                    let result = match panic::catch_unwind(AssertUnwindSafe(|| job(&started))) {
+                        Ok(result) => result,
+                        Err(payload) => {
+                            TestResult::new(Outcome::Panicked, Some(panic_message(&*payload)))
+                        }
+                    };
+                    if sender.send(Message::Finished(result)).is_err() {
+                        break;
+                    }
+                }
+            })
+            .map_err(|e| format!("could not start a worker: {}", e))?;
+        Ok(Self { jobs, messages })
+    }
+
+    /// Waits for the result of the running job, the error is returned when the worker can't be
+    /// used anymore.
+    fn watch(&self, timeout: Duration) -> Result<TestResult, TestResult> {
+        let mut deadline = Instant::now() + timeout;
+        let mut handle: Option<InterruptHandle> = None;
+        let mut interrupted = false;
+        loop {
+            let wait = deadline.saturating_duration_since(Instant::now());
+            match self.messages.recv_timeout(wait) {
+                Ok(Message::Started(started)) => {
+                    handle = Some(started);
+                    deadline = Instant::now() + timeout;
+                    interrupted = false;
+                }
+                Ok(Message::Finished(result)) => return Ok(result),
+                Err(RecvTimeoutError::Timeout) if !interrupted => {
+                    if let Some(ref handle) = handle {
+                        handle.interrupt();
+                    }
+                    deadline = Instant::now() + timeout;
+                    interrupted = true;
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    return Err(TestResult::new(
+                        Outcome::TimedOut,
+                        Some("the test didn't stop when it was interrupted".to_string()),
+                    ))
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(TestResult::new(
+                        Outcome::Panicked,
+                        Some("the worker stopped".to_string()),
+                    ))
+                }
+            }
+        }
+    }
+}
+
+/// Runs a job on the worker of the current thread, watching it with the given timeout.
+fn run_watched(job: Job, timeout: Duration) -> TestResult {
+    WORKER.with(|worker| {
+        let mut worker = worker.borrow_mut();
+        let current = match worker.take() {
+            Some(current) => current,
+            None => match Worker::spawn() {
+                Ok(spawned) => spawned,
+                Err(e) => return TestResult::failed(e),
+            },
+        };
+        if current.jobs.send(job).is_err() {
+            return TestResult::new(Outcome::Panicked, Some("the worker stopped".to_string()));
+        }
+        match current.watch(timeout) {
+            Ok(result) => {
+                *worker = Some(current);
+                result
+            }
+            Err(result) => result,
+        }
+    })
+}
+
+/// Runs the test file on a worker, catching the panics of the engine.
+pub(crate) fn run_test(path: &Path, harness: &Arc<Harness>, timeout: Duration) -> TestResult {
+    let source = match fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(e) => return TestResult::failed(format!("could not read the test: {}", e)),
+    };
+    let metadata = match read_metadata(&source) {
+        Ok(metadata) => metadata,
+        Err(e) => return TestResult::failed(e),
+    };
+    if let Some(flag) = UNSUPPORTED_FLAGS
+        .iter()
+        .find(|flag| metadata.has_flag(flag))
+    {
+        return TestResult::new(
+            Outcome::Ignored,
+            Some(format!("{} tests aren't supported", flag)),
+        );
+    }
+
+    let harness = harness.clone();
+    run_watched(
+        Box::new(move |started| run_source(&source, &metadata, &harness, started)),
+        timeout,
+    )
+}
+
+/// Runs a test in the modes its flags ask for, it passes if it passes in all of them.
+///
+/// Tests run in both sloppy and strict mode unless they have the `noStrict`, `onlyStrict` or `raw`
+/// flags, the raw ones run as they are without the harness.
+pub(crate) fn run_source(
+    source: &str,
+    metadata: &MetaData,
+    harness: &Harness,
+    started: &dyn Fn(InterruptHandle),
+) -> TestResult {
+    let raw = metadata.has_flag("raw");
+    let strict_modes: &[bool] = if metadata.has_flag("onlyStrict") {
+        &[true]
+    } else if metadata.has_flag("noStrict") || raw {
+        &[false]
+    } else {
+        &[false, true]
+    };
+
+    for &strict in strict_modes {
+        let result = if strict {
+            run_once(
+                &format!("\"use strict\";\n{}", source),
+                metadata,
+                harness,
+                started,
+            )
+        } else {
+            run_once(source, metadata, harness, started)
+        };
+        let mode = if strict { "strict" } else { "sloppy" };
+        match result {
+            Ok(()) => {}
+            Err(Failure::Failed(reason)) => {
+                return TestResult::failed(format!("{} mode: {}", mode, reason))
+            }
+            Err(Failure::TimedOut) => {
+                return TestResult::new(
+                    Outcome::TimedOut,
+                    Some(format!("{} mode: interrupted at the timeout", mode)),
+                )
+            }
+        }
+    }
+    TestResult::new(Outcome::Passed, None)
+}
+
+/// Runs a test once in a fresh realm, the error is why it didn't pass.
+fn run_once(
+    source: &str,
+    metadata: &MetaData,
+    harness: &Harness,
+    started: &dyn Fn(InterruptHandle),
+) -> Result<(), Failure> {
+    let negative = metadata.negative.as_ref();
+    let parsed = parse(source);
+    if let Some(negative) = negative.filter(|negative| negative.phase != Phase::Runtime) {
+        return match parsed {
+            Err(_) if negative.error_type == "SyntaxError" => Ok(()),
+            Err(e) => Err(format!("expected a {}, got {}", negative.error_type, e)),
+            Ok(_) => Err(format!("expected a {} when parsing", negative.error_type)),
+        }
+        .map_err(Failure::from);
+    }
+    let test = parsed?;
+
+    let mut engine = create_engine();
+    started(engine.interrupt_handle());
+    if !metadata.has_flag("raw") {
+        let includes = DEFAULT_INCLUDES
+            .iter()
+            .copied()
+            .chain(metadata.includes.iter().map(String::as_str));
+        for include in includes {
+            let include = harness_file(harness, include)?;
+            let result = engine.run(&include);
+            check_termination(&engine)?;
+            result.map_err(|e| format!("the harness threw {}", describe(&e)))?;
+        }
+    }
+
+    let result = engine.run(&test);
+    check_termination(&engine)?;
+    let result = match (result, negative) {
+        (Ok(_), None) => Ok(()),
+        (Ok(_), Some(negative)) => Err(format!("expected a {} to be thrown", negative.error_type)),
+        (Err(e), None) => Err(format!("uncaught {}", describe(&e))),
+        (Err(e), Some(negative)) => {
+            if error_name(&e) == negative.error_type {
+                Ok(())
+            } else {
+                Err(format!(
+                    "expected a {}, got {}",
+                    negative.error_type,
+                    describe(&e)
+                ))
+            }
+        }
+    };
+    result.map_err(Failure::from)
+}
+
+/// Checks if the engine was terminated, the only interrupts are the watchdog's.
+fn check_termination(engine: &Interpreter) -> Result<(), Failure> {
+    match engine.termination() {
+        None => Ok(()),
+        Some(Termination::Interrupted) => Err(Failure::TimedOut),
+        Some(termination) => Err(Failure::Failed(format!("terminated: {}", termination))),
+    }
+}
+
+/// Lexes and parses the source of a script.
+fn parse(source: &str) -> Result<Node, String> {
+    let mut lexer = Lexer::new(source);
+    lexer.lex().map_err(|e| format!("SyntaxError: {}", e))?;
+    Parser::with_source(&lexer.tokens, source)
+        .parse_all()
+        .map_err(|e| format!("SyntaxError: {}", e))
+}
+
+/// Returns a file of the harness, parsed once per thread.
+fn harness_file(harness: &Harness, name: &str) -> Result<Rc<Node>, String> {
+    HARNESS_CACHE.with(|cache| {
+        if let Some(node) = cache.borrow().get(name) {
+            return Ok(node.clone());
+        }
+        let source = harness
+            .file(name)
+            .ok_or_else(|| format!("the harness has no {}", name))?;
+        let node = Rc::new(parse(source).map_err(|e| format!("in {}: {}", name, e))?);
+        cache.borrow_mut().insert(name.to_string(), node.clone());
+        Ok(node)
+    })
+}
+
+/// The name of the constructor of a thrown error, or its `name` property.
+fn error_name(error: &Value) -> String {
+    let name = error.get_field_slice("constructor").get_field_slice("name");
+    if name.is_string() {
+        name.to_string()
+    } else {
+        error.get_field_slice("name").to_string()
+    }
+}
+
+/// Describes a thrown value by the name of its constructor and its message, like errors print.
+fn describe(error: &Value) -> String {
+    if !error.is_object() {
+        return error.to_string();
+    }
+    let message = error.get_field_slice("message");
+    if message.is_undefined() {
+        error_name(error)
+    } else {
+        format!("{}: {}", error_name(error), message)
+    }
+}
+
+/// Returns the message of a panic.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        (*message).to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Creates an interpreter with a fresh realm which has a `$262` object.
+fn create_engine() -> Interpreter {
+    let realm = Realm::create();
+    let global = realm.global_obj.clone();
+    let engine = Executor::new(realm);
+    create_262(&global);
+    engine
+}
+
+/// Creates the `$262` object of the realm with the given global object.
+///
+/// More information:
+///  - [test262 documentation][doc]
+///
+/// [doc]: https://github.com/tc39/test262/blob/master/INTERPRETING.md#host-defined-functions
+fn create_262(global: &Value) -> Value {
+    let host = Value::new_object(Some(global));
+    host.set_field_slice("global", global.clone());
+    host.set_field_slice("createRealm", host_function(create_realm));
+    host.set_field_slice("evalScript", host_function(eval_script));
+    host.set_field_slice("gc", host_function(collect_garbage));
+    global.set_field_slice("$262", host.clone());
+    host
+}
+
+fn host_function(func: NativeFunctionData) -> Value {
+    ValueData::from_func(Function::create_builtin(
+        vec![],
+        FunctionBody::BuiltIn(func),
+    ))
+}
+
+/// `$262.createRealm()`, creates a realm and returns its `$262` object.
+fn create_realm(_: &mut Value, _: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let global = ctx.add_realm(Realm::create());
+    Ok(create_262(&global))
+}
+
+/// `$262.evalScript(source)`, runs a script in the realm of the `$262` object.
+fn eval_script(this: &mut Value, args: &[Value], ctx: &mut Interpreter) -> ResultValue {
+    let global = this.get_field_slice("global");
+    if !global.is_object() {
+        return ctx.throw_type_error("evalScript must be called on a $262 object");
+    }
+    let source = match args.get(0) {
+        Some(source) => ctx.value_to_rust_string(source)?,
+        None => String::new(),
+    };
+    ctx.enter_realm(&global, |ctx| forward_val(ctx, &source))
+}
+
+/// `$262.gc()`, runs a garbage collection.
+fn collect_garbage(_: &mut Value, _: &[Value], _: &mut Interpreter) -> ResultValue {
+    gc::force_collect();
+    Ok(Value::undefined())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn harness() -> Harness {
+        Harness::default()
+            .with_file(
+                "assert.js",
+                "function assert(value) { if (!value) { throw new Test262Error('assertion'); } }",
+            )
+            .with_file(
+                "sta.js",
+                "function Test262Error(message) { this.message = message; }",
+            )
+            .with_file("answer.js", "var answer = 42;")
+    }
+
+    fn run(source: &str) -> TestResult {
+        let metadata = read_metadata(source).unwrap();
+        run_source(source, &metadata, &harness(), &|_| {})
+    }
+
+    #[test]
+    fn passes_and_fails() {
+        assert_eq!(
+            run("/*---\n---*/\nassert(1 + 1 == 2);").outcome,
+            Outcome::Passed
+        );
+        assert_eq!(
+            run("/*---\nincludes: [answer.js]\n---*/\nassert(answer == 42);").outcome,
+            Outcome::Passed
+        );
+
+        let failed = run("/*---\n---*/\nassert(1 + 1 == 3);");
+        assert_eq!(failed.outcome, Outcome::Failed);
+        assert!(failed.reason.unwrap().starts_with("sloppy mode: uncaught"));
+    }
+
+    #[test]
+    fn negative_tests() {
+        let parse = "/*---\nnegative:\n  phase: parse\n  type: SyntaxError\n---*/\n";
+        assert_eq!(run(&format!("{}let = ;", parse)).outcome, Outcome::Passed);
+        assert_eq!(run(&format!("{}1 + 1;", parse)).outcome, Outcome::Failed);
+
+        let runtime = "/*---\nnegative:\n  phase: runtime\n  type: Test262Error\n---*/\n";
+        assert_eq!(
+            run(&format!("{}throw new Test262Error('x');", runtime)).outcome,
+            Outcome::Passed
+        );
+        assert_eq!(
+            run(&format!("{}throw new TypeError('x');", runtime)).outcome,
+            Outcome::Failed
+        );
+    }
+
+    #[test]
+    fn raw_tests_run_without_the_harness() {
+        assert_eq!(
+            run("/*---\nflags: [raw]\n---*/\nvar assert = 1;").outcome,
+            Outcome::Passed
+        );
+        let failed = run("/*---\nflags: [raw]\n---*/\nassert(true);");
+        assert_eq!(failed.outcome, Outcome::Failed);
+    }
+
+    #[test]
+    fn host_object() {
+        let source = r#"/*---
+---*/
+var other = $262.createRealm();
+other.evalScript("var x = 1;");
+assert(other.global.x == 1);
+assert($262.global.x == undefined);
+$262.evalScript("var y = 2;");
+assert(y == 2);
+$262.gc();
+"#;
+        assert_eq!(run(source), TestResult::new(Outcome::Passed, None));
+    }
+
+    fn run_with_timeout(source: &'static str, timeout: Duration) -> TestResult {
+        let harness = harness();
+        run_watched(
+            Box::new(move |started| run_source(source, &MetaData::default(), &harness, started)),
+            timeout,
+        )
+    }
+
+    #[test]
+    fn timeouts() {
+        let result = run_with_timeout("while (true) {}", Duration::from_millis(50));
+        assert_eq!(
+            result,
+            TestResult::new(
+                Outcome::TimedOut,
+                Some("sloppy mode: interrupted at the timeout".to_string())
+            )
+        );
+
+        // The worker is kept after an interrupted test
+        let passed = run_with_timeout("1 + 1;", Duration::from_millis(50));
+        assert_eq!(passed.outcome, Outcome::Passed);
+    }
+
+    #[test]
+    fn tests_which_ignore_interrupts_time_out() {
+        let result = run_watched(
+            Box::new(|_| loop {
+                thread::sleep(Duration::from_secs(60));
+            }),
+            Duration::from_millis(50),
+        );
+        assert_eq!(result.outcome, Outcome::TimedOut);
+
+        // A new worker runs the next test
+        let passed = run_with_timeout("1 + 1;", Duration::from_millis(50));
+        assert_eq!(passed.outcome, Outcome::Passed);
+    }
+
+    #[test]
+    fn panics() {
+        let result = run_watched(Box::new(|_| panic!("engine bug")), Duration::from_secs(5));
+        assert_eq!(
+            result,
+            TestResult::new(Outcome::Panicked, Some("engine bug".to_string()))
+        );
+    }
+}
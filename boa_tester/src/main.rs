@@ -0,0 +1,145 @@
+//! Runs the tests of a [test262][test262] checkout with Boa, and writes the results of every
+//! directory of the suite as JSON.
+//!
+//! [test262]: https://github.com/tc39/test262
+
+#![deny(
+    unused_qualifications,
+    clippy::all,
+    unused_import_braces,
+    unused_lifetimes,
+    unreachable_pub,
+    trivial_numeric_casts,
+    missing_debug_implementations,
+    missing_copy_implementations,
+    deprecated_in_future,
+    non_ascii_idents,
+    rust_2018_compatibility,
+    rust_2018_idioms,
+    future_incompatible,
+    nonstandard_style
+)]
+#![warn(clippy::perf, clippy::single_match_else, clippy::dbg_macro)]
+#![allow(
+    clippy::missing_inline_in_public_items,
+    clippy::cognitive_complexity,
+    clippy::must_use_candidate,
+    clippy::missing_errors_doc,
+    clippy::as_conversions
+)]
+
+mod exec;
+mod read;
+mod results;
+
+use crate::{
+    exec::run_test,
+    read::{read_tests, Harness},
+    results::{Report, SuiteResult},
+};
+use rayon::prelude::*;
+use std::{
+    fs,
+    io::{self, Write},
+    panic,
+    path::PathBuf,
+    process,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use structopt::StructOpt;
+
+/// CLI configuration for the test262 runner.
+#[derive(Debug, StructOpt)]
+#[structopt(
+    name = "boa_tester",
+    about = "Runs the test262 conformance suite with Boa"
+)]
+struct Opt {
+    /// The path of the test262 checkout.
+    #[structopt(long, short = "t", parse(from_os_str), default_value = "test262")]
+    test262_path: PathBuf,
+
+    /// The directory or file of tests to run, relative to the `test` directory of the checkout.
+    #[structopt(name = "SUITE", parse(from_os_str))]
+    suite: Option<PathBuf>,
+
+    /// The time after which a test is interrupted and recorded as timed out, in milliseconds. A
+    /// test which is still running after twice this time is left behind on its thread.
+    #[structopt(long, value_name = "MS", default_value = "10000")]
+    timeout: u64,
+
+    /// Write the JSON results to the given file instead of stdout.
+    #[structopt(long, short = "o", parse(from_os_str))]
+    output: Option<PathBuf>,
+
+    /// List the tests which didn't pass, with the reason, in the results.
+    #[structopt(long, short = "d")]
+    details: bool,
+
+    /// The number of threads running tests, one per core by default.
+    #[structopt(long, short = "j")]
+    threads: Option<usize>,
+}
+
+fn main() {
+    let opt = Opt::from_args();
+    if let Err(e) = run(&opt) {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
+
+/// Runs the suite and writes its results.
+fn run(opt: &Opt) -> io::Result<()> {
+    let harness = Arc::new(Harness::read(&opt.test262_path)?);
+    let test_dir = opt.test262_path.join("test");
+    let suite = test_dir.join(opt.suite.clone().unwrap_or_default());
+    let tests = read_tests(&suite)?;
+    let timeout = Duration::from_millis(opt.timeout);
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(opt.threads.unwrap_or(0))
+        .build()
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+
+    // Panics are part of the results, their messages would only clutter the progress
+    panic::set_hook(Box::new(|_| {}));
+    let done = AtomicUsize::new(0);
+    let results: Vec<_> = pool.install(|| {
+        tests
+            .par_iter()
+            .map(|path| {
+                let result = run_test(path, &harness, timeout);
+                let done = done.fetch_add(1, Ordering::Relaxed) + 1;
+                if done % 100 == 0 || done == tests.len() {
+                    eprint!("\r{}/{} tests run", done, tests.len());
+                }
+                result
+            })
+            .collect()
+    });
+    let _ = panic::take_hook();
+    eprintln!();
+
+    let mut root = SuiteResult::default();
+    for (path, result) in tests.iter().zip(results) {
+        let path = path.strip_prefix(&test_dir).unwrap_or(path);
+        root.add(path, result, opt.details);
+    }
+
+    eprintln!("{}", root.summary);
+    for (name, suite) in &root.suites {
+        eprintln!("  {}: {}", name, suite.summary);
+    }
+
+    let report = Report::new(&opt.test262_path, root);
+    let json = serde_json::to_string_pretty(&report)?;
+    match opt.output {
+        Some(ref output) => fs::write(output, json),
+        None => writeln!(io::stdout(), "{}", json),
+    }
+}
@@ -0,0 +1,166 @@
+//! Reading of the tests and of the harness of a test262 checkout.
+
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The files of the harness which every test, except raw ones, includes.
+pub(crate) const DEFAULT_INCLUDES: [&str; 2] = ["assert.js", "sta.js"];
+
+/// The metadata of a test, from the YAML frontmatter between `/*---` and `---*/`.
+///
+/// More information:
+///  - [test262 documentation][doc]
+///
+/// [doc]: https://github.com/tc39/test262/blob/master/INTERPRETING.md#metadata
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct MetaData {
+    /// The flags changing how the test is run, such as `onlyStrict` or `raw`.
+    pub(crate) flags: Vec<String>,
+    /// The files of the harness the test needs, besides the default ones.
+    pub(crate) includes: Vec<String>,
+    /// The error the test has to end with, for tests of invalid code.
+    pub(crate) negative: Option<Negative>,
+}
+
+impl MetaData {
+    /// Returns whether the test has the given flag.
+    pub(crate) fn has_flag(&self, flag: &str) -> bool {
+        self.flags.iter().any(|f| f == flag)
+    }
+}
+
+/// The expectation of a negative test.
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct Negative {
+    /// When the error has to be thrown.
+    pub(crate) phase: Phase,
+    /// The name of the constructor of the error, e.g. `SyntaxError`.
+    #[serde(rename = "type")]
+    pub(crate) error_type: String,
+}
+
+/// The phase of a negative test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Phase {
+    /// The code mustn't parse.
+    Parse,
+    /// The code mustn't pass early errors, the older name of the parse phase.
+    Early,
+    /// The imports of a module mustn't resolve.
+    Resolution,
+    /// The code has to throw when it runs.
+    Runtime,
+}
+
+/// Reads the metadata of a test from its source.
+pub(crate) fn read_metadata(source: &str) -> Result<MetaData, String> {
+    let start = source
+        .find("/*---")
+        .ok_or_else(|| "no frontmatter".to_string())?;
+    let yaml = &source[start + 5..];
+    let end = yaml
+        .find("---*/")
+        .ok_or_else(|| "unterminated frontmatter".to_string())?;
+    let yaml = &yaml[..end];
+    if yaml.trim().is_empty() {
+        return Ok(MetaData::default());
+    }
+    serde_yaml::from_str(yaml).map_err(|e| format!("invalid frontmatter: {}", e))
+}
+
+/// The files of the `harness` directory, by name.
+#[derive(Debug, Default)]
+pub(crate) struct Harness {
+    files: HashMap<String, String>,
+}
+
+impl Harness {
+    /// Reads the harness of a test262 checkout.
+    pub(crate) fn read(test262: &Path) -> io::Result<Self> {
+        let mut files = HashMap::new();
+        for entry in fs::read_dir(test262.join("harness"))? {
+            let path = entry?.path();
+            if path.extension().map_or(false, |ext| ext == "js") {
+                let name = path.file_name().unwrap().to_string_lossy().into_owned();
+                files.insert(name, fs::read_to_string(&path)?);
+            }
+        }
+        Ok(Self { files })
+    }
+
+    /// Returns the source of a file of the harness.
+    pub(crate) fn file(&self, name: &str) -> Option<&str> {
+        self.files.get(name).map(String::as_str)
+    }
+
+    #[cfg(test)]
+    pub(crate) fn with_file(mut self, name: &str, source: &str) -> Self {
+        self.files.insert(name.to_string(), source.to_string());
+        self
+    }
+}
+
+/// Lists the tests of a directory, or the file itself if it is a test, in a stable order.
+///
+/// The `_FIXTURE.js` files are modules the tests import, and aren't tests themselves.
+pub(crate) fn read_tests(path: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut tests = Vec::new();
+    if path.is_dir() {
+        let mut entries = fs::read_dir(path)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<io::Result<Vec<_>>>()?;
+        entries.sort();
+        for entry in entries {
+            tests.extend(read_tests(&entry)?);
+        }
+    } else if path.extension().map_or(false, |ext| ext == "js")
+        && !path.to_string_lossy().ends_with("_FIXTURE.js")
+    {
+        tests.push(path.to_path_buf());
+    }
+    Ok(tests)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_metadata() {
+        let source = r#"// Copyright
+/*---
+esid: sec-array.prototype.map
+description: >
+  Maps the values.
+features: [Symbol, Reflect]
+flags: [onlyStrict]
+includes: [compareArray.js]
+negative:
+  phase: runtime
+  type: TypeError
+---*/
+[].map();
+"#;
+        let metadata = read_metadata(source).unwrap();
+        assert!(metadata.has_flag("onlyStrict"));
+        assert!(!metadata.has_flag("raw"));
+        assert_eq!(metadata.includes, vec!["compareArray.js"]);
+        let negative = metadata.negative.unwrap();
+        assert_eq!(negative.phase, Phase::Runtime);
+        assert_eq!(negative.error_type, "TypeError");
+    }
+
+    #[test]
+    fn reads_minimal_metadata() {
+        let metadata = read_metadata("/*---\ndescription: nothing else\n---*/").unwrap();
+        assert!(metadata.flags.is_empty() && metadata.negative.is_none());
+        assert_eq!(read_metadata("1 + 1").unwrap_err(), "no frontmatter");
+        assert!(read_metadata("/*---\nflags: [\n---*/").is_err());
+    }
+}
@@ -0,0 +1,186 @@
+//! The conformance results, summarised for every directory of the test suite.
+
+use crate::exec::{Outcome, TestResult};
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The number of tests with each outcome.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub(crate) struct Summary {
+    pub(crate) total: usize,
+    pub(crate) passed: usize,
+    pub(crate) failed: usize,
+    pub(crate) panicked: usize,
+    pub(crate) timed_out: usize,
+    pub(crate) ignored: usize,
+}
+
+impl Summary {
+    fn add(&mut self, outcome: Outcome) {
+        self.total += 1;
+        match outcome {
+            Outcome::Passed => self.passed += 1,
+            Outcome::Failed => self.failed += 1,
+            Outcome::Panicked => self.panicked += 1,
+            Outcome::TimedOut => self.timed_out += 1,
+            Outcome::Ignored => self.ignored += 1,
+        }
+    }
+
+    /// The percentage of the tests which were run that passed.
+    pub(crate) fn conformance(&self) -> f64 {
+        let run = self.total - self.ignored;
+        if run == 0 {
+            0.0
+        } else {
+            self.passed as f64 * 100.0 / run as f64
+        }
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{} passed ({:.2}%), {} failed, {} panicked, {} timed out, {} ignored",
+            self.passed,
+            self.total - self.ignored,
+            self.conformance(),
+            self.failed,
+            self.panicked,
+            self.timed_out,
+            self.ignored
+        )
+    }
+}
+
+/// The results of a directory of tests, and of the directories in it.
+#[derive(Debug, Default, Serialize)]
+pub(crate) struct SuiteResult {
+    #[serde(flatten)]
+    pub(crate) summary: Summary,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) suites: BTreeMap<String, SuiteResult>,
+    /// The tests of the directory which didn't pass, when the details are asked for.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) tests: BTreeMap<String, TestResult>,
+}
+
+impl SuiteResult {
+    /// Adds the result of the test at the given path, relative to the directory of the suite.
+    pub(crate) fn add(&mut self, path: &Path, result: TestResult, details: bool) {
+        let mut names: Vec<String> = path
+            .iter()
+            .map(|name| name.to_string_lossy().into_owned())
+            .collect();
+        let file = names.pop().unwrap_or_default();
+
+        self.summary.add(result.outcome);
+        let mut suite = self;
+        for name in names {
+            suite = suite.suites.entry(name).or_default();
+            suite.summary.add(result.outcome);
+        }
+        if details && result.outcome != Outcome::Passed {
+            suite.tests.insert(file, result);
+        }
+    }
+}
+
+/// The results of a run, with what is needed to compare them with the results of other runs.
+#[derive(Debug, Serialize)]
+pub(crate) struct Report {
+    pub(crate) boa_version: &'static str,
+    /// The commit of the test262 checkout, if it is a git repository.
+    pub(crate) test262_commit: Option<String>,
+    /// When the tests were run, in seconds since the Unix epoch.
+    pub(crate) timestamp: u64,
+    pub(crate) results: SuiteResult,
+}
+
+impl Report {
+    pub(crate) fn new(test262: &Path, results: SuiteResult) -> Self {
+        Self {
+            boa_version: env!("CARGO_PKG_VERSION"),
+            test262_commit: test262_commit(test262),
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |time| time.as_secs()),
+            results,
+        }
+    }
+}
+
+/// Reads the commit checked out in a git repository, following the branch `HEAD` points to.
+fn test262_commit(test262: &Path) -> Option<String> {
+    let git = test262.join(".git");
+    let head = fs::read_to_string(git.join("HEAD")).ok()?;
+    let head = head.trim();
+    let reference = match head.strip_prefix("ref: ") {
+        Some(reference) => reference,
+        None => return Some(head.to_string()),
+    };
+    if let Ok(commit) = fs::read_to_string(git.join(reference)) {
+        return Some(commit.trim().to_string());
+    }
+    fs::read_to_string(git.join("packed-refs"))
+        .ok()?
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split(' ');
+            Some((parts.next()?, parts.next()?))
+        })
+        .find(|&(_, name)| name == reference)
+        .map(|(commit, _)| commit.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(outcome: Outcome) -> TestResult {
+        TestResult {
+            outcome,
+            reason: None,
+        }
+    }
+
+    #[test]
+    fn summarises_directories() {
+        let mut root = SuiteResult::default();
+        root.add(
+            Path::new("built-ins/Array/a.js"),
+            result(Outcome::Passed),
+            true,
+        );
+        root.add(
+            Path::new("built-ins/Array/b.js"),
+            result(Outcome::Failed),
+            true,
+        );
+        root.add(
+            Path::new("built-ins/Math/c.js"),
+            result(Outcome::Panicked),
+            true,
+        );
+        root.add(Path::new("language/d.js"), result(Outcome::Ignored), true);
+
+        assert_eq!(root.summary.total, 4);
+        let built_ins = &root.suites["built-ins"];
+        assert_eq!(built_ins.summary.total, 3);
+        let array = &built_ins.suites["Array"];
+        assert_eq!((array.summary.passed, array.summary.failed), (1, 1));
+        assert_eq!(array.tests.keys().collect::<Vec<_>>(), vec!["b.js"]);
+        assert_eq!(built_ins.suites["Math"].summary.panicked, 1);
+        assert_eq!(root.suites["language"].summary.conformance(), 0.0);
+        assert_eq!(
+            format!("{}", array.summary),
+            "1/2 passed (50.00%), 1 failed, 0 panicked, 0 timed out, 0 ignored"
+        );
+    }
+}